@@ -0,0 +1,68 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+/**
+ * @title Achievements
+ * @dev Non-transferable badges. Issuers approved by the admin award an
+ * achievement to a user; the admin or the awarding issuer can revoke it.
+ * There is no transfer function, so badges stay with the account that earned them.
+ */
+contract Achievements {
+    struct Badge {
+        address issuer;
+        uint64 issuedAt;
+    }
+
+    address public immutable admin;
+    mapping(address => bool) public isIssuer;
+    /// user => achievementId => badge
+    mapping(address => mapping(uint64 => Badge)) public badges;
+
+    event IssuerAdded(address indexed issuer);
+    event IssuerRemoved(address indexed issuer);
+    event Issued(address indexed user, uint64 indexed achievementId, address indexed issuer);
+    event Revoked(address indexed user, uint64 indexed achievementId);
+
+    modifier onlyAdmin() {
+        require(msg.sender == admin, "Only admin");
+        _;
+    }
+
+    constructor() {
+        admin = msg.sender;
+    }
+
+    function addIssuer(address issuer) external onlyAdmin {
+        require(!isIssuer[issuer], "Already an issuer");
+        isIssuer[issuer] = true;
+        emit IssuerAdded(issuer);
+    }
+
+    function removeIssuer(address issuer) external onlyAdmin {
+        require(isIssuer[issuer], "Not an issuer");
+        isIssuer[issuer] = false;
+        emit IssuerRemoved(issuer);
+    }
+
+    function issue(address user, uint64 achievementId) external {
+        require(isIssuer[msg.sender], "Only issuer");
+        require(badges[user][achievementId].issuer == address(0), "Already issued");
+        badges[user][achievementId] = Badge(msg.sender, uint64(block.timestamp));
+        emit Issued(user, achievementId, msg.sender);
+    }
+
+    function revoke(address user, uint64 achievementId) external {
+        Badge memory badge = badges[user][achievementId];
+        require(badge.issuer != address(0), "No badge");
+        require(
+            msg.sender == admin || (msg.sender == badge.issuer && isIssuer[msg.sender]),
+            "Not authorized"
+        );
+        delete badges[user][achievementId];
+        emit Revoked(user, achievementId);
+    }
+
+    function hasAchievement(address user, uint64 achievementId) external view returns (bool) {
+        return badges[user][achievementId].issuer != address(0);
+    }
+}
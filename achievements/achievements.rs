@@ -0,0 +1,480 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint,
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// Seed prefix for registry PDAs: ["registry", admin]
+pub const REGISTRY_SEED: &[u8] = b"registry";
+
+/// Seed prefix for badge PDAs: ["badge", registry, user, achievement_id (u64 LE)]
+pub const BADGE_SEED: &[u8] = b"badge";
+
+/// Most issuers a registry can hold
+pub const MAX_ISSUERS: usize = 8;
+
+/// Size of a `RegistryAccount` with `MAX_ISSUERS` issuers
+pub const REGISTRY_ACCOUNT_SIZE: usize = 1 // is_initialized
+    + 32 // admin
+    + 4 + 32 * MAX_ISSUERS // issuers
+    + 1; // bump
+
+/// Size of a `BadgeAccount`
+pub const BADGE_ACCOUNT_SIZE: usize = 1 // is_initialized
+    + 32 // registry
+    + 32 // user
+    + 8 // achievement_id
+    + 32 // issuer
+    + 8 // issued_at
+    + 1; // bump
+
+/// Errors returned by the program as `ProgramError::Custom` codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AchievementError {
+    /// The signer is not allowed to perform this action
+    Unauthorized = 0,
+    /// The registry already has `MAX_ISSUERS` issuers
+    TooManyIssuers = 1,
+    /// The key is already an issuer
+    AlreadyIssuer = 2,
+    /// The key is not an issuer
+    NotAnIssuer = 3,
+    /// The registry or badge has not been initialized
+    NotInitialized = 4,
+}
+
+impl From<AchievementError> for ProgramError {
+    fn from(e: AchievementError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+/// A set of achievements and the keys allowed to award them
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct RegistryAccount {
+    pub is_initialized: bool,
+    /// Manages the issuer list and can revoke any badge
+    pub admin: Pubkey,
+    pub issuers: Vec<Pubkey>,
+    pub bump: u8,
+}
+
+impl RegistryAccount {
+    pub fn is_issuer(&self, key: &Pubkey) -> bool {
+        self.issuers.contains(key)
+    }
+
+    pub fn add_issuer(&mut self, issuer: Pubkey) -> Result<(), AchievementError> {
+        if self.is_issuer(&issuer) {
+            return Err(AchievementError::AlreadyIssuer);
+        }
+        if self.issuers.len() >= MAX_ISSUERS {
+            return Err(AchievementError::TooManyIssuers);
+        }
+        self.issuers.push(issuer);
+        Ok(())
+    }
+
+    pub fn remove_issuer(&mut self, issuer: &Pubkey) -> Result<(), AchievementError> {
+        let index = self
+            .issuers
+            .iter()
+            .position(|k| k == issuer)
+            .ok_or(AchievementError::NotAnIssuer)?;
+        self.issuers.swap_remove(index);
+        Ok(())
+    }
+
+    /// The admin can revoke any badge; an issuer only the badges it awarded,
+    /// and only while it is still an issuer
+    pub fn can_revoke(&self, badge: &BadgeAccount, signer: &Pubkey) -> bool {
+        *signer == self.admin || (*signer == badge.issuer && self.is_issuer(signer))
+    }
+}
+
+/// Proof that `user` earned `achievement_id`. The address is derived from the
+/// user and there is no transfer instruction, so the badge is soulbound.
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct BadgeAccount {
+    pub is_initialized: bool,
+    pub registry: Pubkey,
+    pub user: Pubkey,
+    pub achievement_id: u64,
+    pub issuer: Pubkey,
+    pub issued_at: i64,
+    pub bump: u8,
+}
+
+/// Address of the badge for `user` and `achievement_id`; verifiers check a
+/// credential by deriving this and reading the account
+pub fn badge_address(program_id: &Pubkey, registry: &Pubkey, user: &Pubkey, achievement_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[BADGE_SEED, registry.as_ref(), user.as_ref(), &achievement_id.to_le_bytes()],
+        program_id,
+    )
+}
+
+entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = AchievementInstruction::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        AchievementInstruction::InitializeRegistry => initialize_registry(program_id, accounts),
+        AchievementInstruction::AddIssuer { issuer } => add_issuer(program_id, accounts, issuer),
+        AchievementInstruction::RemoveIssuer { issuer } => remove_issuer(program_id, accounts, issuer),
+        AchievementInstruction::Issue { achievement_id } => issue(program_id, accounts, achievement_id),
+        AchievementInstruction::Revoke => revoke(program_id, accounts),
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub enum AchievementInstruction {
+    /// Create a registry with the signer as admin and no issuers
+    /// Accounts: [writable, signer] admin, [writable] registry PDA, [] system program
+    InitializeRegistry,
+    /// Allow `issuer` to award badges
+    /// Accounts: [signer] admin, [writable] registry PDA
+    AddIssuer { issuer: Pubkey },
+    /// Stop `issuer` from awarding or revoking badges; its badges remain
+    /// Accounts: [signer] admin, [writable] registry PDA
+    RemoveIssuer { issuer: Pubkey },
+    /// Award `achievement_id` to a user; the issuer pays rent for the badge
+    /// Accounts: [writable, signer] issuer, [] registry PDA, [] user, [writable] badge PDA,
+    /// [] system program
+    Issue { achievement_id: u64 },
+    /// Close a badge, returning its rent to the signer
+    /// Accounts: [writable, signer] admin or issuing issuer, [] registry PDA, [writable] badge PDA
+    Revoke,
+}
+
+fn initialize_registry(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let admin = next_account_info(accounts_iter)?;
+    let registry_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !admin.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let (expected_registry, bump) =
+        Pubkey::find_program_address(&[REGISTRY_SEED, admin.key.as_ref()], program_id);
+    if expected_registry != *registry_account.key {
+        msg!("Registry account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    invoke_signed(
+        &system_instruction::create_account(
+            admin.key,
+            registry_account.key,
+            Rent::get()?.minimum_balance(REGISTRY_ACCOUNT_SIZE),
+            REGISTRY_ACCOUNT_SIZE as u64,
+            program_id,
+        ),
+        &[admin.clone(), registry_account.clone(), system_program.clone()],
+        &[&[REGISTRY_SEED, admin.key.as_ref(), &[bump]]],
+    )?;
+
+    let registry = RegistryAccount {
+        is_initialized: true,
+        admin: *admin.key,
+        issuers: Vec::new(),
+        bump,
+    };
+    registry.serialize(&mut &mut registry_account.data.borrow_mut()[..])?;
+
+    msg!("Registry {} created", registry_account.key);
+
+    Ok(())
+}
+
+fn add_issuer(program_id: &Pubkey, accounts: &[AccountInfo], issuer: Pubkey) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let admin = next_account_info(accounts_iter)?;
+    let registry_account = next_account_info(accounts_iter)?;
+
+    let mut registry = load_registry_as_admin(program_id, registry_account, admin)?;
+
+    registry.add_issuer(issuer)?;
+    registry.serialize(&mut &mut registry_account.data.borrow_mut()[..])?;
+
+    msg!("Issuer {} added", issuer);
+
+    Ok(())
+}
+
+fn remove_issuer(program_id: &Pubkey, accounts: &[AccountInfo], issuer: Pubkey) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let admin = next_account_info(accounts_iter)?;
+    let registry_account = next_account_info(accounts_iter)?;
+
+    let mut registry = load_registry_as_admin(program_id, registry_account, admin)?;
+
+    registry.remove_issuer(&issuer)?;
+    // The shorter list leaves stale bytes at the end of the account, which
+    // `deserialize` ignores
+    registry.serialize(&mut &mut registry_account.data.borrow_mut()[..])?;
+
+    msg!("Issuer {} removed", issuer);
+
+    Ok(())
+}
+
+fn issue(program_id: &Pubkey, accounts: &[AccountInfo], achievement_id: u64) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let issuer = next_account_info(accounts_iter)?;
+    let registry_account = next_account_info(accounts_iter)?;
+    let user = next_account_info(accounts_iter)?;
+    let badge_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !issuer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let registry = load_registry(program_id, registry_account)?;
+    if !registry.is_issuer(issuer.key) {
+        msg!("{} is not an issuer of this registry", issuer.key);
+        return Err(AchievementError::Unauthorized.into());
+    }
+
+    let (expected_badge, bump) = badge_address(program_id, registry_account.key, user.key, achievement_id);
+    if expected_badge != *badge_account.key {
+        msg!("Badge account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    // Fails if the user already holds this badge
+    invoke_signed(
+        &system_instruction::create_account(
+            issuer.key,
+            badge_account.key,
+            Rent::get()?.minimum_balance(BADGE_ACCOUNT_SIZE),
+            BADGE_ACCOUNT_SIZE as u64,
+            program_id,
+        ),
+        &[issuer.clone(), badge_account.clone(), system_program.clone()],
+        &[&[
+            BADGE_SEED,
+            registry_account.key.as_ref(),
+            user.key.as_ref(),
+            &achievement_id.to_le_bytes(),
+            &[bump],
+        ]],
+    )?;
+
+    let badge = BadgeAccount {
+        is_initialized: true,
+        registry: *registry_account.key,
+        user: *user.key,
+        achievement_id,
+        issuer: *issuer.key,
+        issued_at: Clock::get()?.unix_timestamp,
+        bump,
+    };
+    badge.serialize(&mut &mut badge_account.data.borrow_mut()[..])?;
+
+    msg!("Achievement {} awarded to {}", achievement_id, user.key);
+
+    Ok(())
+}
+
+fn revoke(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let signer = next_account_info(accounts_iter)?;
+    let registry_account = next_account_info(accounts_iter)?;
+    let badge_account = next_account_info(accounts_iter)?;
+
+    if !signer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let registry = load_registry(program_id, registry_account)?;
+
+    if badge_account.owner != program_id {
+        msg!("Badge account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let badge = BadgeAccount::try_from_slice(&badge_account.data.borrow())?;
+    if !badge.is_initialized {
+        return Err(AchievementError::NotInitialized.into());
+    }
+
+    if badge.registry != *registry_account.key {
+        msg!("Badge belongs to a different registry");
+        return Err(AchievementError::Unauthorized.into());
+    }
+
+    if !registry.can_revoke(&badge, signer.key) {
+        msg!("Only the admin or the badge's issuer can revoke it");
+        return Err(AchievementError::Unauthorized.into());
+    }
+
+    // Close the badge: move its rent to the signer and clear its data
+    let badge_lamports = badge_account.lamports();
+    **badge_account.try_borrow_mut_lamports()? = 0;
+    **signer.try_borrow_mut_lamports()? = signer
+        .lamports()
+        .checked_add(badge_lamports)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    badge_account.data.borrow_mut().fill(0);
+
+    msg!("Achievement {} revoked from {}", badge.achievement_id, badge.user);
+
+    Ok(())
+}
+
+fn load_registry(program_id: &Pubkey, registry_account: &AccountInfo) -> Result<RegistryAccount, ProgramError> {
+    if registry_account.owner != program_id {
+        msg!("Registry account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    // The issuer list makes the encoding shorter than the account
+    let registry = RegistryAccount::deserialize(&mut &registry_account.data.borrow()[..])?;
+
+    if !registry.is_initialized {
+        return Err(AchievementError::NotInitialized.into());
+    }
+
+    Ok(registry)
+}
+
+fn load_registry_as_admin(
+    program_id: &Pubkey,
+    registry_account: &AccountInfo,
+    admin: &AccountInfo,
+) -> Result<RegistryAccount, ProgramError> {
+    if !admin.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let registry = load_registry(program_id, registry_account)?;
+
+    if registry.admin != *admin.key {
+        msg!("Only the admin can manage issuers");
+        return Err(AchievementError::Unauthorized.into());
+    }
+
+    Ok(registry)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_program::clock::Epoch;
+
+    fn new_registry(admin: Pubkey) -> RegistryAccount {
+        RegistryAccount {
+            is_initialized: true,
+            admin,
+            issuers: Vec::new(),
+            bump: 255,
+        }
+    }
+
+    fn new_badge(registry: Pubkey, issuer: Pubkey) -> BadgeAccount {
+        BadgeAccount {
+            is_initialized: true,
+            registry,
+            user: Pubkey::new_unique(),
+            achievement_id: 7,
+            issuer,
+            issued_at: 1_700_000_000,
+            bump: 255,
+        }
+    }
+
+    #[test]
+    fn test_issuer_management_and_revoke_rights() {
+        let admin = Pubkey::new_unique();
+        let issuer = Pubkey::new_unique();
+        let other_issuer = Pubkey::new_unique();
+        let mut registry = new_registry(admin);
+
+        registry.add_issuer(issuer).unwrap();
+        registry.add_issuer(other_issuer).unwrap();
+        assert_eq!(registry.add_issuer(issuer), Err(AchievementError::AlreadyIssuer));
+
+        let badge = new_badge(Pubkey::new_unique(), issuer);
+        assert!(registry.can_revoke(&badge, &admin));
+        assert!(registry.can_revoke(&badge, &issuer));
+        assert!(!registry.can_revoke(&badge, &other_issuer));
+
+        // A removed issuer loses the right to revoke its own badges
+        registry.remove_issuer(&issuer).unwrap();
+        assert!(!registry.can_revoke(&badge, &issuer));
+        assert!(registry.can_revoke(&badge, &admin));
+        assert_eq!(registry.remove_issuer(&issuer), Err(AchievementError::NotAnIssuer));
+
+        while registry.issuers.len() < MAX_ISSUERS {
+            registry.add_issuer(Pubkey::new_unique()).unwrap();
+        }
+        assert_eq!(
+            registry.add_issuer(Pubkey::new_unique()),
+            Err(AchievementError::TooManyIssuers)
+        );
+
+        // A full issuer list still fits the allocated size
+        assert_eq!(registry.try_to_vec().unwrap().len(), REGISTRY_ACCOUNT_SIZE);
+    }
+
+    #[test]
+    fn test_revoke_by_admin_only() {
+        let program_id = Pubkey::new_unique();
+        let admin = Pubkey::new_unique();
+        let stranger = Pubkey::new_unique();
+        let registry_key = Pubkey::new_unique();
+        let badge_key = Pubkey::new_unique();
+
+        let mut registry_data = new_registry(admin).try_to_vec().unwrap();
+        registry_data.resize(REGISTRY_ACCOUNT_SIZE, 0);
+        let badge_data = new_badge(registry_key, Pubkey::new_unique()).try_to_vec().unwrap();
+
+        for (signer, expected) in [
+            (stranger, Err(AchievementError::Unauthorized.into())),
+            (admin, Ok(())),
+        ] {
+            let keys = [signer, registry_key, badge_key];
+            let mut lamports: Vec<u64> = vec![10_000; keys.len()];
+            let mut data: Vec<Vec<u8>> = vec![vec![], registry_data.clone(), badge_data.clone()];
+
+            let accounts: Vec<AccountInfo> = keys
+                .iter()
+                .zip(lamports.iter_mut())
+                .zip(data.iter_mut())
+                .enumerate()
+                .map(|(i, ((key, lamports), data))| {
+                    AccountInfo::new(key, i == 0, true, lamports, data, &program_id, false, Epoch::default())
+                })
+                .collect();
+
+            let instruction_data = AchievementInstruction::Revoke.try_to_vec().unwrap();
+            let result = process_instruction(&program_id, &accounts, &instruction_data);
+            assert_eq!(result, expected);
+
+            if result.is_ok() {
+                assert_eq!(accounts[0].lamports(), 20_000);
+                assert_eq!(accounts[2].lamports(), 0);
+                assert!(accounts[2].data.borrow().iter().all(|&b| b == 0));
+            }
+        }
+    }
+}
@@ -0,0 +1,63 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+/**
+ * @title Allowlist
+ * @dev Admin-managed sets of approved addresses that other contracts query
+ */
+contract Allowlist {
+    struct List {
+        address admin;
+        uint32 memberCount;
+    }
+
+    uint256 public nextListId = 1;
+
+    mapping(uint256 => List) public lists;
+    mapping(uint256 => mapping(address => bool)) private members;
+
+    event ListCreated(uint256 indexed listId, address indexed admin);
+    event MemberAdded(uint256 indexed listId, address indexed member);
+    event MemberRemoved(uint256 indexed listId, address indexed member);
+
+    modifier onlyAdmin(uint256 listId) {
+        require(lists[listId].admin == msg.sender, "Only admin");
+        _;
+    }
+
+    /**
+     * @dev Create an empty list administered by the caller
+     */
+    function createList() external returns (uint256 listId) {
+        listId = nextListId++;
+        lists[listId].admin = msg.sender;
+        emit ListCreated(listId, msg.sender);
+    }
+
+    /**
+     * @dev Approve an address
+     */
+    function addMember(uint256 listId, address member) external onlyAdmin(listId) {
+        require(!members[listId][member], "Already a member");
+        members[listId][member] = true;
+        lists[listId].memberCount += 1;
+        emit MemberAdded(listId, member);
+    }
+
+    /**
+     * @dev Revoke an address
+     */
+    function removeMember(uint256 listId, address member) external onlyAdmin(listId) {
+        require(members[listId][member], "Not a member");
+        members[listId][member] = false;
+        lists[listId].memberCount -= 1;
+        emit MemberRemoved(listId, member);
+    }
+
+    /**
+     * @dev Membership check for other contracts
+     */
+    function isMember(uint256 listId, address account) external view returns (bool) {
+        return members[listId][account];
+    }
+}
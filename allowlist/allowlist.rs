@@ -0,0 +1,405 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint,
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// Seed prefix for list PDAs: ["allowlist", admin, list_id]
+pub const ALLOWLIST_SEED: &[u8] = b"allowlist";
+
+/// Seed prefix for membership PDAs: ["member", list, member]
+pub const MEMBER_SEED: &[u8] = b"member";
+
+/// Errors returned by the program as `ProgramError::Custom` codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AllowlistError {
+    /// Only the list's admin can change it
+    Unauthorized = 0,
+    /// The pubkey is not on the list
+    NotMember = 1,
+    /// The pubkey is already on the list
+    AlreadyMember = 2,
+    /// Account does not match the expected PDA
+    WrongAccount = 3,
+    /// The list has not been initialized
+    NotInitialized = 4,
+}
+
+impl From<AllowlistError> for ProgramError {
+    fn from(e: AllowlistError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+/// A named set of approved pubkeys managed by one admin
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct AllowlistAccount {
+    pub is_initialized: bool,
+    pub admin: Pubkey,
+    pub list_id: u64,
+    pub member_count: u32,
+    pub bump: u8,
+}
+
+impl AllowlistAccount {
+    pub const SIZE: usize = 1 // is_initialized
+        + 32 // admin
+        + 8 // list_id
+        + 4 // member_count
+        + 1; // bump
+}
+
+/// Membership marker; the account existing at its PDA is the membership
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct MemberAccount {
+    pub list: Pubkey,
+    pub member: Pubkey,
+    pub bump: u8,
+}
+
+impl MemberAccount {
+    pub const SIZE: usize = 32 // list
+        + 32 // member
+        + 1; // bump
+}
+
+/// Address of `admin`'s list `list_id`
+pub fn allowlist_address(program_id: &Pubkey, admin: &Pubkey, list_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[ALLOWLIST_SEED, admin.as_ref(), &list_id.to_le_bytes()], program_id)
+}
+
+/// Address of `member`'s membership in `list`
+pub fn member_address(program_id: &Pubkey, list: &Pubkey, member: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[MEMBER_SEED, list.as_ref(), member.as_ref()], program_id)
+}
+
+/// The check other programs copy to verify membership without a CPI: the
+/// account must be this program's live membership PDA for (`list`, `member`).
+/// Ownership matters: anyone can create an account at an address, but only
+/// this program can assign one to itself.
+pub fn is_member(program_id: &Pubkey, list: &Pubkey, member: &Pubkey, member_account: &AccountInfo) -> bool {
+    member_account.owner == program_id
+        && member_account.lamports() > 0
+        && *member_account.key == member_address(program_id, list, member).0
+}
+
+entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = AllowlistInstruction::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        AllowlistInstruction::CreateList { list_id } => create_list(program_id, accounts, list_id),
+        AllowlistInstruction::AddMember { member } => add_member(program_id, accounts, member),
+        AllowlistInstruction::RemoveMember { member } => remove_member(program_id, accounts, member),
+        AllowlistInstruction::VerifyMember => verify_member(program_id, accounts),
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub enum AllowlistInstruction {
+    /// Create an empty list owned by the signer
+    /// Accounts: [writable, signer] admin, [writable] list PDA, [] system program
+    CreateList { list_id: u64 },
+    /// Approve `member` (admin only)
+    /// Accounts: [writable, signer] admin, [writable] list PDA, [writable] member PDA, [] system program
+    AddMember { member: Pubkey },
+    /// Revoke `member` and refund the membership rent to the admin
+    /// Accounts: [writable, signer] admin, [writable] list PDA, [writable] member PDA
+    RemoveMember { member: Pubkey },
+    /// Succeed only if the wallet is on the list (for CPI callers)
+    /// Accounts: [] list PDA, [] member PDA, [] wallet
+    VerifyMember,
+}
+
+fn create_list(program_id: &Pubkey, accounts: &[AccountInfo], list_id: u64) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let admin = next_account_info(accounts_iter)?;
+    let list_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !admin.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let (expected_list, bump) = allowlist_address(program_id, admin.key, list_id);
+    if expected_list != *list_account.key {
+        msg!("List account does not match the expected PDA");
+        return Err(AllowlistError::WrongAccount.into());
+    }
+
+    invoke_signed(
+        &system_instruction::create_account(
+            admin.key,
+            list_account.key,
+            Rent::get()?.minimum_balance(AllowlistAccount::SIZE),
+            AllowlistAccount::SIZE as u64,
+            program_id,
+        ),
+        &[admin.clone(), list_account.clone(), system_program.clone()],
+        &[&[ALLOWLIST_SEED, admin.key.as_ref(), &list_id.to_le_bytes(), &[bump]]],
+    )?;
+
+    let list = AllowlistAccount {
+        is_initialized: true,
+        admin: *admin.key,
+        list_id,
+        member_count: 0,
+        bump,
+    };
+    list.serialize(&mut &mut list_account.data.borrow_mut()[..])?;
+
+    msg!("Created allowlist {} for {}", list_id, admin.key);
+
+    Ok(())
+}
+
+fn add_member(program_id: &Pubkey, accounts: &[AccountInfo], member: Pubkey) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let admin = next_account_info(accounts_iter)?;
+    let list_account = next_account_info(accounts_iter)?;
+    let member_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    let mut list = load_list_as_admin(program_id, list_account, admin)?;
+
+    let (expected_member, bump) = member_address(program_id, list_account.key, &member);
+    if expected_member != *member_account.key {
+        msg!("Member account does not match the expected PDA");
+        return Err(AllowlistError::WrongAccount.into());
+    }
+
+    if member_account.lamports() > 0 {
+        msg!("{} is already on the list", member);
+        return Err(AllowlistError::AlreadyMember.into());
+    }
+
+    invoke_signed(
+        &system_instruction::create_account(
+            admin.key,
+            member_account.key,
+            Rent::get()?.minimum_balance(MemberAccount::SIZE),
+            MemberAccount::SIZE as u64,
+            program_id,
+        ),
+        &[admin.clone(), member_account.clone(), system_program.clone()],
+        &[&[MEMBER_SEED, list_account.key.as_ref(), member.as_ref(), &[bump]]],
+    )?;
+
+    let membership = MemberAccount {
+        list: *list_account.key,
+        member,
+        bump,
+    };
+    membership.serialize(&mut &mut member_account.data.borrow_mut()[..])?;
+
+    list.member_count = list.member_count.checked_add(1).ok_or(ProgramError::ArithmeticOverflow)?;
+    list.serialize(&mut &mut list_account.data.borrow_mut()[..])?;
+
+    msg!("Added {} ({} members)", member, list.member_count);
+
+    Ok(())
+}
+
+fn remove_member(program_id: &Pubkey, accounts: &[AccountInfo], member: Pubkey) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let admin = next_account_info(accounts_iter)?;
+    let list_account = next_account_info(accounts_iter)?;
+    let member_account = next_account_info(accounts_iter)?;
+
+    let mut list = load_list_as_admin(program_id, list_account, admin)?;
+
+    if !is_member(program_id, list_account.key, &member, member_account) {
+        msg!("{} is not on the list", member);
+        return Err(AllowlistError::NotMember.into());
+    }
+
+    let lamports = member_account.lamports();
+    **member_account.try_borrow_mut_lamports()? = 0;
+    **admin.try_borrow_mut_lamports()? = admin
+        .lamports()
+        .checked_add(lamports)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    member_account.data.borrow_mut().fill(0);
+
+    list.member_count = list.member_count.saturating_sub(1);
+    list.serialize(&mut &mut list_account.data.borrow_mut()[..])?;
+
+    msg!("Removed {} ({} members)", member, list.member_count);
+
+    Ok(())
+}
+
+fn verify_member(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let list_account = next_account_info(accounts_iter)?;
+    let member_account = next_account_info(accounts_iter)?;
+    let wallet = next_account_info(accounts_iter)?;
+
+    load_list(program_id, list_account)?;
+
+    if !is_member(program_id, list_account.key, wallet.key, member_account) {
+        msg!("{} is not on the list", wallet.key);
+        return Err(AllowlistError::NotMember.into());
+    }
+
+    Ok(())
+}
+
+fn load_list(program_id: &Pubkey, list_account: &AccountInfo) -> Result<AllowlistAccount, ProgramError> {
+    if list_account.owner != program_id {
+        msg!("List account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let list = AllowlistAccount::try_from_slice(&list_account.data.borrow())?;
+
+    if !list.is_initialized {
+        return Err(AllowlistError::NotInitialized.into());
+    }
+
+    Ok(list)
+}
+
+fn load_list_as_admin(
+    program_id: &Pubkey,
+    list_account: &AccountInfo,
+    admin: &AccountInfo,
+) -> Result<AllowlistAccount, ProgramError> {
+    if !admin.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let list = load_list(program_id, list_account)?;
+
+    if list.admin != *admin.key {
+        msg!("Only the admin can change this list");
+        return Err(AllowlistError::Unauthorized.into());
+    }
+
+    Ok(list)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_program::clock::Epoch;
+
+    #[test]
+    fn test_membership_requires_owned_pda() {
+        let program_id = Pubkey::new_unique();
+        let list = Pubkey::new_unique();
+        let member = Pubkey::new_unique();
+        let (member_key, _) = member_address(&program_id, &list, &member);
+        let other_program = Pubkey::new_unique();
+
+        let mut lamports = 1_000;
+        let mut data = vec![0; MemberAccount::SIZE];
+        let membership = AccountInfo::new(
+            &member_key,
+            false,
+            false,
+            &mut lamports,
+            &mut data,
+            &program_id,
+            false,
+            Epoch::default(),
+        );
+        assert!(is_member(&program_id, &list, &member, &membership));
+        // Same PDA, but for a different wallet or list
+        assert!(!is_member(&program_id, &list, &Pubkey::new_unique(), &membership));
+        assert!(!is_member(&program_id, &Pubkey::new_unique(), &member, &membership));
+
+        let mut lamports = 1_000;
+        let mut data = vec![0; MemberAccount::SIZE];
+        let spoofed = AccountInfo::new(
+            &member_key,
+            false,
+            false,
+            &mut lamports,
+            &mut data,
+            &other_program,
+            false,
+            Epoch::default(),
+        );
+        assert!(!is_member(&program_id, &list, &member, &spoofed));
+
+        let list_state = AllowlistAccount {
+            is_initialized: true,
+            admin: Pubkey::new_unique(),
+            list_id: 7,
+            member_count: 0,
+            bump: 255,
+        };
+        assert_eq!(list_state.try_to_vec().unwrap().len(), AllowlistAccount::SIZE);
+        let membership_state = MemberAccount { list, member, bump: 255 };
+        assert_eq!(membership_state.try_to_vec().unwrap().len(), MemberAccount::SIZE);
+    }
+
+    #[test]
+    fn test_remove_member() {
+        let program_id = Pubkey::new_unique();
+        let admin = Pubkey::new_unique();
+        let member = Pubkey::new_unique();
+        let (list_key, bump) = allowlist_address(&program_id, &admin, 1);
+        let (member_key, member_bump) = member_address(&program_id, &list_key, &member);
+
+        let list = AllowlistAccount {
+            is_initialized: true,
+            admin,
+            list_id: 1,
+            member_count: 1,
+            bump,
+        };
+        let membership = MemberAccount {
+            list: list_key,
+            member,
+            bump: member_bump,
+        };
+
+        let keys = [admin, list_key, member_key, member];
+        let mut lamports: Vec<u64> = vec![0, 1_000, 500, 0];
+        let mut data: Vec<Vec<u8>> = vec![
+            vec![],
+            list.try_to_vec().unwrap(),
+            membership.try_to_vec().unwrap(),
+            vec![],
+        ];
+
+        let accounts: Vec<AccountInfo> = keys
+            .iter()
+            .zip(lamports.iter_mut())
+            .zip(data.iter_mut())
+            .enumerate()
+            .map(|(i, ((key, lamports), data))| {
+                AccountInfo::new(key, i == 0, true, lamports, data, &program_id, false, Epoch::default())
+            })
+            .collect();
+
+        let verify = AllowlistInstruction::VerifyMember.try_to_vec().unwrap();
+        process_instruction(&program_id, &accounts[1..], &verify).unwrap();
+
+        let remove = AllowlistInstruction::RemoveMember { member }.try_to_vec().unwrap();
+        process_instruction(&program_id, &accounts[..3], &remove).unwrap();
+        assert_eq!(accounts[0].lamports(), 500);
+
+        let stored = AllowlistAccount::try_from_slice(&accounts[1].data.borrow()).unwrap();
+        assert_eq!(stored.member_count, 0);
+
+        let result = process_instruction(&program_id, &accounts[1..], &verify);
+        assert_eq!(result, Err(AllowlistError::NotMember.into()));
+    }
+}
@@ -0,0 +1,164 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+interface IERC20 {
+    function transfer(address to, uint256 amount) external returns (bool);
+    function transferFrom(address from, address to, uint256 amount) external returns (bool);
+}
+
+/**
+ * @title Amm
+ * @dev Two-token constant-product (x * y = k) pool; the contract itself is the LP token
+ */
+contract Amm {
+    uint256 public constant BPS_DENOMINATOR = 10_000;
+    uint256 public constant MAX_FEE_BPS = 1_000;
+
+    IERC20 public immutable tokenA;
+    IERC20 public immutable tokenB;
+    uint256 public immutable feeBps;
+
+    uint256 public reserveA;
+    uint256 public reserveB;
+
+    // LP token balances
+    uint256 public totalSupply;
+    mapping(address => uint256) public balanceOf;
+
+    event LiquidityAdded(address indexed provider, uint256 amountA, uint256 amountB, uint256 lpMinted);
+    event LiquidityRemoved(address indexed provider, uint256 amountA, uint256 amountB, uint256 lpBurned);
+    event Swapped(address indexed user, bool aToB, uint256 amountIn, uint256 amountOut);
+    event Transfer(address indexed from, address indexed to, uint256 value);
+
+    constructor(IERC20 _tokenA, IERC20 _tokenB, uint256 _feeBps) {
+        require(address(_tokenA) != address(_tokenB), "Identical tokens");
+        require(_feeBps <= MAX_FEE_BPS, "Fee too high");
+        tokenA = _tokenA;
+        tokenB = _tokenB;
+        feeBps = _feeBps;
+    }
+
+    /**
+     * @dev Output of a swap against the given reserves, fee taken from the input
+     */
+    function getAmountOut(uint256 amountIn, uint256 reserveIn, uint256 reserveOut) public view returns (uint256) {
+        uint256 amountInAfterFee = (amountIn * (BPS_DENOMINATOR - feeBps)) / BPS_DENOMINATOR;
+        return (amountInAfterFee * reserveOut) / (reserveIn + amountInAfterFee);
+    }
+
+    /**
+     * @dev Deposit up to the given amounts at the pool ratio (requires prior `approve`)
+     */
+    function addLiquidity(uint256 maxAmountA, uint256 maxAmountB, uint256 minLpOut) external returns (uint256 lp) {
+        require(maxAmountA > 0 && maxAmountB > 0, "Amounts must be greater than zero");
+
+        uint256 amountA;
+        uint256 amountB;
+        if (totalSupply == 0) {
+            (amountA, amountB) = (maxAmountA, maxAmountB);
+            lp = sqrt(amountA * amountB);
+        } else {
+            uint256 lpFromA = (maxAmountA * totalSupply) / reserveA;
+            uint256 lpFromB = (maxAmountB * totalSupply) / reserveB;
+            lp = lpFromA < lpFromB ? lpFromA : lpFromB;
+            amountA = ceilDiv(lp * reserveA, totalSupply);
+            amountB = ceilDiv(lp * reserveB, totalSupply);
+        }
+
+        require(lp > 0, "Insufficient liquidity minted");
+        require(lp >= minLpOut, "Slippage exceeded");
+
+        reserveA += amountA;
+        reserveB += amountB;
+        totalSupply += lp;
+        balanceOf[msg.sender] += lp;
+
+        require(tokenA.transferFrom(msg.sender, address(this), amountA), "Transfer failed");
+        require(tokenB.transferFrom(msg.sender, address(this), amountB), "Transfer failed");
+
+        emit Transfer(address(0), msg.sender, lp);
+        emit LiquidityAdded(msg.sender, amountA, amountB, lp);
+    }
+
+    /**
+     * @dev Burn LP tokens for a proportional share of both reserves
+     */
+    function removeLiquidity(uint256 lp, uint256 minAmountA, uint256 minAmountB)
+        external
+        returns (uint256 amountA, uint256 amountB)
+    {
+        require(lp > 0, "Amount must be greater than zero");
+        require(balanceOf[msg.sender] >= lp, "Insufficient LP balance");
+
+        amountA = (lp * reserveA) / totalSupply;
+        amountB = (lp * reserveB) / totalSupply;
+        require(amountA >= minAmountA && amountB >= minAmountB, "Slippage exceeded");
+
+        balanceOf[msg.sender] -= lp;
+        totalSupply -= lp;
+        reserveA -= amountA;
+        reserveB -= amountB;
+
+        require(tokenA.transfer(msg.sender, amountA), "Transfer failed");
+        require(tokenB.transfer(msg.sender, amountB), "Transfer failed");
+
+        emit Transfer(msg.sender, address(0), lp);
+        emit LiquidityRemoved(msg.sender, amountA, amountB, lp);
+    }
+
+    /**
+     * @dev Swap one token for the other (requires prior `approve` of the input token)
+     */
+    function swap(uint256 amountIn, uint256 minAmountOut, bool aToB) external returns (uint256 amountOut) {
+        require(amountIn > 0, "Amount must be greater than zero");
+        require(reserveA > 0 && reserveB > 0, "No liquidity");
+
+        (IERC20 tokenIn, IERC20 tokenOut) = aToB ? (tokenA, tokenB) : (tokenB, tokenA);
+        (uint256 reserveIn, uint256 reserveOut) = aToB ? (reserveA, reserveB) : (reserveB, reserveA);
+
+        amountOut = getAmountOut(amountIn, reserveIn, reserveOut);
+        require(amountOut > 0, "Insufficient output");
+        require(amountOut >= minAmountOut, "Slippage exceeded");
+
+        if (aToB) {
+            reserveA += amountIn;
+            reserveB -= amountOut;
+        } else {
+            reserveB += amountIn;
+            reserveA -= amountOut;
+        }
+
+        require(tokenIn.transferFrom(msg.sender, address(this), amountIn), "Transfer failed");
+        require(tokenOut.transfer(msg.sender, amountOut), "Transfer failed");
+
+        emit Swapped(msg.sender, aToB, amountIn, amountOut);
+    }
+
+    /**
+     * @dev Transfer LP tokens
+     */
+    function transfer(address to, uint256 amount) external returns (bool) {
+        require(balanceOf[msg.sender] >= amount, "Insufficient LP balance");
+        balanceOf[msg.sender] -= amount;
+        balanceOf[to] += amount;
+        emit Transfer(msg.sender, to, amount);
+        return true;
+    }
+
+    function ceilDiv(uint256 a, uint256 b) private pure returns (uint256) {
+        return (a + b - 1) / b;
+    }
+
+    function sqrt(uint256 y) private pure returns (uint256 z) {
+        if (y > 3) {
+            z = y;
+            uint256 x = y / 2 + 1;
+            while (x < z) {
+                z = x;
+                x = (y / x + x) / 2;
+            }
+        } else if (y != 0) {
+            z = 1;
+        }
+    }
+}
@@ -0,0 +1,670 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint,
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use spl_token::state::{Account as TokenAccount, Mint};
+
+/// Seed prefix for pool state PDAs: ["pool", mint_a, mint_b]
+pub const POOL_SEED: &[u8] = b"pool";
+
+/// Seed prefix for reserve vault PDAs: ["vault", pool, mint]
+pub const VAULT_SEED: &[u8] = b"vault";
+
+/// Seed prefix for the LP mint PDA: ["lp_mint", pool]
+pub const LP_MINT_SEED: &[u8] = b"lp_mint";
+
+/// Fees are expressed in basis points of the input amount
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Highest fee a pool may charge (10%)
+pub const MAX_FEE_BPS: u16 = 1_000;
+
+/// Decimals of every LP mint
+pub const LP_DECIMALS: u8 = 6;
+
+/// Size of a `PoolAccount`
+pub const POOL_ACCOUNT_SIZE: usize = 1 // is_initialized
+    + 32 // mint_a
+    + 32 // mint_b
+    + 32 // vault_a
+    + 32 // vault_b
+    + 32 // lp_mint
+    + 8 // reserve_a
+    + 8 // reserve_b
+    + 8 // lp_supply
+    + 2 // fee_bps
+    + 1; // bump
+
+/// Errors returned by the program as `ProgramError::Custom` codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AmmError {
+    /// Fee must not exceed `MAX_FEE_BPS`
+    InvalidFee = 0,
+    /// Amounts must be greater than zero
+    ZeroAmount = 1,
+    /// The result is worse than the caller's minimum
+    SlippageExceeded = 2,
+    /// The pool cannot cover the request, or the deposit is too small to mint LP tokens
+    InsufficientLiquidity = 3,
+    /// The pool has not been initialized
+    NotInitialized = 4,
+    /// A pool needs two different mints
+    IdenticalMints = 5,
+    /// A vault, mint, or token account does not belong to this pool
+    InvalidPoolAccount = 6,
+}
+
+impl From<AmmError> for ProgramError {
+    fn from(e: AmmError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+/// A two-token constant-product pool. Reserves are tracked here rather than
+/// read from the vaults, so tokens sent directly to a vault cannot move the price.
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct PoolAccount {
+    pub is_initialized: bool,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    /// Token account holding reserve A, owned by the pool PDA
+    pub vault_a: Pubkey,
+    /// Token account holding reserve B, owned by the pool PDA
+    pub vault_b: Pubkey,
+    /// LP token mint; the pool PDA is its mint authority
+    pub lp_mint: Pubkey,
+    pub reserve_a: u64,
+    pub reserve_b: u64,
+    /// Outstanding LP tokens
+    pub lp_supply: u64,
+    /// Swap fee in basis points, left in the pool for liquidity providers
+    pub fee_bps: u16,
+    pub bump: u8,
+}
+
+/// Output of a swap of `amount_in` against the given reserves, keeping
+/// `reserve_in * reserve_out` constant after the fee is taken from the input
+pub fn swap_output(amount_in: u64, reserve_in: u64, reserve_out: u64, fee_bps: u16) -> Option<u64> {
+    let amount_in_after_fee = (amount_in as u128)
+        .checked_mul((BPS_DENOMINATOR - fee_bps as u64) as u128)?
+        / BPS_DENOMINATOR as u128;
+    let numerator = amount_in_after_fee.checked_mul(reserve_out as u128)?;
+    let denominator = (reserve_in as u128).checked_add(amount_in_after_fee)?;
+    if denominator == 0 {
+        return None;
+    }
+    u64::try_from(numerator / denominator).ok()
+}
+
+/// Amounts of A and B to take, and LP tokens to mint, for a deposit of at most
+/// `max_a` and `max_b`. The first deposit sets the price and mints
+/// `sqrt(a * b)`; later deposits are trimmed to the current pool ratio.
+pub fn deposit_amounts(
+    reserve_a: u64,
+    reserve_b: u64,
+    lp_supply: u64,
+    max_a: u64,
+    max_b: u64,
+) -> Option<(u64, u64, u64)> {
+    if lp_supply == 0 {
+        let lp = integer_sqrt((max_a as u128).checked_mul(max_b as u128)?);
+        return Some((max_a, max_b, u64::try_from(lp).ok()?));
+    }
+
+    let supply = lp_supply as u128;
+    let lp_from_a = (max_a as u128).checked_mul(supply)? / reserve_a as u128;
+    let lp_from_b = (max_b as u128).checked_mul(supply)? / reserve_b as u128;
+    let lp = lp_from_a.min(lp_from_b);
+
+    // Round deposits up so the pool never gives away value to the depositor
+    let amount_a = (lp.checked_mul(reserve_a as u128)?).div_ceil(supply);
+    let amount_b = (lp.checked_mul(reserve_b as u128)?).div_ceil(supply);
+
+    Some((
+        u64::try_from(amount_a).ok()?,
+        u64::try_from(amount_b).ok()?,
+        u64::try_from(lp).ok()?,
+    ))
+}
+
+/// Amounts of A and B returned for burning `lp_amount`, rounded down
+pub fn withdraw_amounts(reserve_a: u64, reserve_b: u64, lp_supply: u64, lp_amount: u64) -> Option<(u64, u64)> {
+    if lp_supply == 0 || lp_amount > lp_supply {
+        return None;
+    }
+    let amount_a = (lp_amount as u128).checked_mul(reserve_a as u128)? / lp_supply as u128;
+    let amount_b = (lp_amount as u128).checked_mul(reserve_b as u128)? / lp_supply as u128;
+    Some((amount_a as u64, amount_b as u64))
+}
+
+fn integer_sqrt(value: u128) -> u128 {
+    if value < 2 {
+        return value;
+    }
+    let mut x = value;
+    let mut y = x.div_ceil(2);
+    while y < x {
+        x = y;
+        y = (x + value / x) / 2;
+    }
+    x
+}
+
+entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = AmmInstruction::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        AmmInstruction::InitializePool { fee_bps } => initialize_pool(program_id, accounts, fee_bps),
+        AmmInstruction::AddLiquidity {
+            max_amount_a,
+            max_amount_b,
+            min_lp_out,
+        } => add_liquidity(program_id, accounts, max_amount_a, max_amount_b, min_lp_out),
+        AmmInstruction::RemoveLiquidity {
+            lp_amount,
+            min_amount_a,
+            min_amount_b,
+        } => remove_liquidity(program_id, accounts, lp_amount, min_amount_a, min_amount_b),
+        AmmInstruction::Swap {
+            amount_in,
+            min_amount_out,
+            a_to_b,
+        } => swap(program_id, accounts, amount_in, min_amount_out, a_to_b),
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub enum AmmInstruction {
+    /// Create the pool state, both reserve vaults, and the LP mint
+    /// Accounts: [writable, signer] payer, [writable] pool PDA, [] mint_a, [] mint_b,
+    /// [writable] vault A PDA, [writable] vault B PDA, [writable] LP mint PDA,
+    /// [] token program, [] system program
+    InitializePool { fee_bps: u16 },
+    /// Deposit up to the given amounts at the pool ratio and mint LP tokens
+    /// Accounts: [signer] user, [writable] pool PDA, [writable] vault A, [writable] vault B,
+    /// [writable] LP mint, [writable] user token A, [writable] user token B,
+    /// [writable] user LP token account, [] token program
+    AddLiquidity {
+        max_amount_a: u64,
+        max_amount_b: u64,
+        min_lp_out: u64,
+    },
+    /// Burn LP tokens for a proportional share of both reserves
+    /// Accounts: same as `AddLiquidity`
+    RemoveLiquidity {
+        lp_amount: u64,
+        min_amount_a: u64,
+        min_amount_b: u64,
+    },
+    /// Swap one token for the other along x * y = k
+    /// Accounts: [signer] user, [writable] pool PDA, [writable] vault A, [writable] vault B,
+    /// [writable] user source token account, [writable] user destination token account,
+    /// [] token program
+    Swap {
+        amount_in: u64,
+        min_amount_out: u64,
+        a_to_b: bool,
+    },
+}
+
+fn initialize_pool(program_id: &Pubkey, accounts: &[AccountInfo], fee_bps: u16) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let payer = next_account_info(accounts_iter)?;
+    let pool_account = next_account_info(accounts_iter)?;
+    let mint_a = next_account_info(accounts_iter)?;
+    let mint_b = next_account_info(accounts_iter)?;
+    let vault_a = next_account_info(accounts_iter)?;
+    let vault_b = next_account_info(accounts_iter)?;
+    let lp_mint = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !payer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if fee_bps > MAX_FEE_BPS {
+        msg!("Fee must be at most {} bps", MAX_FEE_BPS);
+        return Err(AmmError::InvalidFee.into());
+    }
+
+    if mint_a.key == mint_b.key {
+        return Err(AmmError::IdenticalMints.into());
+    }
+
+    if *token_program.key != spl_token::id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let (expected_pool, bump) = Pubkey::find_program_address(
+        &[POOL_SEED, mint_a.key.as_ref(), mint_b.key.as_ref()],
+        program_id,
+    );
+    if expected_pool != *pool_account.key {
+        msg!("Pool account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let (expected_vault_a, vault_a_bump) = Pubkey::find_program_address(
+        &[VAULT_SEED, pool_account.key.as_ref(), mint_a.key.as_ref()],
+        program_id,
+    );
+    let (expected_vault_b, vault_b_bump) = Pubkey::find_program_address(
+        &[VAULT_SEED, pool_account.key.as_ref(), mint_b.key.as_ref()],
+        program_id,
+    );
+    let (expected_lp_mint, lp_mint_bump) =
+        Pubkey::find_program_address(&[LP_MINT_SEED, pool_account.key.as_ref()], program_id);
+    if expected_vault_a != *vault_a.key
+        || expected_vault_b != *vault_b.key
+        || expected_lp_mint != *lp_mint.key
+    {
+        msg!("Vault or LP mint account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let rent = Rent::get()?;
+
+    // Create the pool state account
+    invoke_signed(
+        &system_instruction::create_account(
+            payer.key,
+            pool_account.key,
+            rent.minimum_balance(POOL_ACCOUNT_SIZE),
+            POOL_ACCOUNT_SIZE as u64,
+            program_id,
+        ),
+        &[payer.clone(), pool_account.clone(), system_program.clone()],
+        &[&[POOL_SEED, mint_a.key.as_ref(), mint_b.key.as_ref(), &[bump]]],
+    )?;
+
+    // Create both reserve vaults, owned by the pool PDA
+    for (vault, mint, vault_bump) in [(vault_a, mint_a, vault_a_bump), (vault_b, mint_b, vault_b_bump)] {
+        invoke_signed(
+            &system_instruction::create_account(
+                payer.key,
+                vault.key,
+                rent.minimum_balance(TokenAccount::LEN),
+                TokenAccount::LEN as u64,
+                &spl_token::id(),
+            ),
+            &[payer.clone(), vault.clone(), system_program.clone()],
+            &[&[VAULT_SEED, pool_account.key.as_ref(), mint.key.as_ref(), &[vault_bump]]],
+        )?;
+        invoke(
+            &spl_token::instruction::initialize_account3(
+                &spl_token::id(),
+                vault.key,
+                mint.key,
+                pool_account.key,
+            )?,
+            &[vault.clone(), mint.clone()],
+        )?;
+    }
+
+    // Create the LP mint, with the pool PDA as mint authority
+    invoke_signed(
+        &system_instruction::create_account(
+            payer.key,
+            lp_mint.key,
+            rent.minimum_balance(Mint::LEN),
+            Mint::LEN as u64,
+            &spl_token::id(),
+        ),
+        &[payer.clone(), lp_mint.clone(), system_program.clone()],
+        &[&[LP_MINT_SEED, pool_account.key.as_ref(), &[lp_mint_bump]]],
+    )?;
+    invoke(
+        &spl_token::instruction::initialize_mint2(
+            &spl_token::id(),
+            lp_mint.key,
+            pool_account.key,
+            None,
+            LP_DECIMALS,
+        )?,
+        std::slice::from_ref(lp_mint),
+    )?;
+
+    let pool = PoolAccount {
+        is_initialized: true,
+        mint_a: *mint_a.key,
+        mint_b: *mint_b.key,
+        vault_a: *vault_a.key,
+        vault_b: *vault_b.key,
+        lp_mint: *lp_mint.key,
+        reserve_a: 0,
+        reserve_b: 0,
+        lp_supply: 0,
+        fee_bps,
+        bump,
+    };
+    pool.serialize(&mut &mut pool_account.data.borrow_mut()[..])?;
+
+    msg!("Pool created for {} / {} with fee {} bps", mint_a.key, mint_b.key, fee_bps);
+
+    Ok(())
+}
+
+fn add_liquidity(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    max_amount_a: u64,
+    max_amount_b: u64,
+    min_lp_out: u64,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let user = next_account_info(accounts_iter)?;
+    let pool_account = next_account_info(accounts_iter)?;
+    let vault_a = next_account_info(accounts_iter)?;
+    let vault_b = next_account_info(accounts_iter)?;
+    let lp_mint = next_account_info(accounts_iter)?;
+    let user_token_a = next_account_info(accounts_iter)?;
+    let user_token_b = next_account_info(accounts_iter)?;
+    let user_lp = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+
+    if !user.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if max_amount_a == 0 || max_amount_b == 0 {
+        return Err(AmmError::ZeroAmount.into());
+    }
+
+    let mut pool = load_pool(program_id, pool_account, vault_a, vault_b, token_program)?;
+    if pool.lp_mint != *lp_mint.key {
+        return Err(AmmError::InvalidPoolAccount.into());
+    }
+
+    let (amount_a, amount_b, lp_out) =
+        deposit_amounts(pool.reserve_a, pool.reserve_b, pool.lp_supply, max_amount_a, max_amount_b)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    if lp_out == 0 {
+        msg!("Deposit too small to mint LP tokens");
+        return Err(AmmError::InsufficientLiquidity.into());
+    }
+
+    if lp_out < min_lp_out {
+        msg!("Would mint {} LP tokens, below minimum {}", lp_out, min_lp_out);
+        return Err(AmmError::SlippageExceeded.into());
+    }
+
+    for (source, vault, amount) in [(user_token_a, vault_a, amount_a), (user_token_b, vault_b, amount_b)] {
+        invoke(
+            &spl_token::instruction::transfer(&spl_token::id(), source.key, vault.key, user.key, &[], amount)?,
+            &[source.clone(), vault.clone(), user.clone()],
+        )?;
+    }
+
+    invoke_signed(
+        &spl_token::instruction::mint_to(
+            &spl_token::id(),
+            lp_mint.key,
+            user_lp.key,
+            pool_account.key,
+            &[],
+            lp_out,
+        )?,
+        &[lp_mint.clone(), user_lp.clone(), pool_account.clone()],
+        &[&[POOL_SEED, pool.mint_a.as_ref(), pool.mint_b.as_ref(), &[pool.bump]]],
+    )?;
+
+    pool.reserve_a = pool.reserve_a.checked_add(amount_a).ok_or(ProgramError::ArithmeticOverflow)?;
+    pool.reserve_b = pool.reserve_b.checked_add(amount_b).ok_or(ProgramError::ArithmeticOverflow)?;
+    pool.lp_supply = pool.lp_supply.checked_add(lp_out).ok_or(ProgramError::ArithmeticOverflow)?;
+    pool.serialize(&mut &mut pool_account.data.borrow_mut()[..])?;
+
+    msg!("Deposited {} A and {} B for {} LP", amount_a, amount_b, lp_out);
+
+    Ok(())
+}
+
+fn remove_liquidity(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    lp_amount: u64,
+    min_amount_a: u64,
+    min_amount_b: u64,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let user = next_account_info(accounts_iter)?;
+    let pool_account = next_account_info(accounts_iter)?;
+    let vault_a = next_account_info(accounts_iter)?;
+    let vault_b = next_account_info(accounts_iter)?;
+    let lp_mint = next_account_info(accounts_iter)?;
+    let user_token_a = next_account_info(accounts_iter)?;
+    let user_token_b = next_account_info(accounts_iter)?;
+    let user_lp = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+
+    if !user.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if lp_amount == 0 {
+        return Err(AmmError::ZeroAmount.into());
+    }
+
+    let mut pool = load_pool(program_id, pool_account, vault_a, vault_b, token_program)?;
+    if pool.lp_mint != *lp_mint.key {
+        return Err(AmmError::InvalidPoolAccount.into());
+    }
+
+    let (amount_a, amount_b) = withdraw_amounts(pool.reserve_a, pool.reserve_b, pool.lp_supply, lp_amount)
+        .ok_or(AmmError::InsufficientLiquidity)?;
+
+    if amount_a < min_amount_a || amount_b < min_amount_b {
+        msg!("Would return {} A and {} B, below the minimums", amount_a, amount_b);
+        return Err(AmmError::SlippageExceeded.into());
+    }
+
+    // Burning requires the user's signature, so only the LP holder can withdraw
+    invoke(
+        &spl_token::instruction::burn(&spl_token::id(), user_lp.key, lp_mint.key, user.key, &[], lp_amount)?,
+        &[user_lp.clone(), lp_mint.clone(), user.clone()],
+    )?;
+
+    let pool_seeds: &[&[u8]] = &[POOL_SEED, pool.mint_a.as_ref(), pool.mint_b.as_ref(), &[pool.bump]];
+    for (vault, destination, amount) in [(vault_a, user_token_a, amount_a), (vault_b, user_token_b, amount_b)] {
+        invoke_signed(
+            &spl_token::instruction::transfer(
+                &spl_token::id(),
+                vault.key,
+                destination.key,
+                pool_account.key,
+                &[],
+                amount,
+            )?,
+            &[vault.clone(), destination.clone(), pool_account.clone()],
+            &[pool_seeds],
+        )?;
+    }
+
+    pool.reserve_a -= amount_a;
+    pool.reserve_b -= amount_b;
+    pool.lp_supply -= lp_amount;
+    pool.serialize(&mut &mut pool_account.data.borrow_mut()[..])?;
+
+    msg!("Burned {} LP for {} A and {} B", lp_amount, amount_a, amount_b);
+
+    Ok(())
+}
+
+fn swap(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount_in: u64,
+    min_amount_out: u64,
+    a_to_b: bool,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let user = next_account_info(accounts_iter)?;
+    let pool_account = next_account_info(accounts_iter)?;
+    let vault_a = next_account_info(accounts_iter)?;
+    let vault_b = next_account_info(accounts_iter)?;
+    let user_source = next_account_info(accounts_iter)?;
+    let user_destination = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+
+    if !user.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if amount_in == 0 {
+        return Err(AmmError::ZeroAmount.into());
+    }
+
+    let mut pool = load_pool(program_id, pool_account, vault_a, vault_b, token_program)?;
+
+    let (vault_in, vault_out, reserve_in, reserve_out) = if a_to_b {
+        (vault_a, vault_b, pool.reserve_a, pool.reserve_b)
+    } else {
+        (vault_b, vault_a, pool.reserve_b, pool.reserve_a)
+    };
+
+    if reserve_in == 0 || reserve_out == 0 {
+        msg!("Pool has no liquidity");
+        return Err(AmmError::InsufficientLiquidity.into());
+    }
+
+    let amount_out = swap_output(amount_in, reserve_in, reserve_out, pool.fee_bps)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    if amount_out == 0 {
+        msg!("Swap too small to produce any output");
+        return Err(AmmError::InsufficientLiquidity.into());
+    }
+
+    if amount_out < min_amount_out {
+        msg!("Output {} below minimum {}", amount_out, min_amount_out);
+        return Err(AmmError::SlippageExceeded.into());
+    }
+
+    invoke(
+        &spl_token::instruction::transfer(
+            &spl_token::id(),
+            user_source.key,
+            vault_in.key,
+            user.key,
+            &[],
+            amount_in,
+        )?,
+        &[user_source.clone(), vault_in.clone(), user.clone()],
+    )?;
+
+    invoke_signed(
+        &spl_token::instruction::transfer(
+            &spl_token::id(),
+            vault_out.key,
+            user_destination.key,
+            pool_account.key,
+            &[],
+            amount_out,
+        )?,
+        &[vault_out.clone(), user_destination.clone(), pool_account.clone()],
+        &[&[POOL_SEED, pool.mint_a.as_ref(), pool.mint_b.as_ref(), &[pool.bump]]],
+    )?;
+
+    // The full input, fee included, joins the reserves, so k grows with every swap
+    let new_reserve_in = reserve_in.checked_add(amount_in).ok_or(ProgramError::ArithmeticOverflow)?;
+    let new_reserve_out = reserve_out - amount_out;
+    if a_to_b {
+        pool.reserve_a = new_reserve_in;
+        pool.reserve_b = new_reserve_out;
+    } else {
+        pool.reserve_b = new_reserve_in;
+        pool.reserve_a = new_reserve_out;
+    }
+    pool.serialize(&mut &mut pool_account.data.borrow_mut()[..])?;
+
+    msg!("Swapped {} in for {} out", amount_in, amount_out);
+
+    Ok(())
+}
+
+/// Validate the pool state account and its vaults, returning the state
+fn load_pool(
+    program_id: &Pubkey,
+    pool_account: &AccountInfo,
+    vault_a: &AccountInfo,
+    vault_b: &AccountInfo,
+    token_program: &AccountInfo,
+) -> Result<PoolAccount, ProgramError> {
+    if pool_account.owner != program_id {
+        msg!("Pool account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    if *token_program.key != spl_token::id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let pool = PoolAccount::try_from_slice(&pool_account.data.borrow())?;
+
+    if !pool.is_initialized {
+        msg!("Pool has not been initialized");
+        return Err(AmmError::NotInitialized.into());
+    }
+
+    if pool.vault_a != *vault_a.key || pool.vault_b != *vault_b.key {
+        msg!("Vault accounts do not match the pool");
+        return Err(AmmError::InvalidPoolAccount.into());
+    }
+
+    Ok(pool)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_swap_output_charges_fee_and_keeps_k() {
+        let (reserve_in, reserve_out) = (1_000_000u64, 2_000_000u64);
+
+        // Without a fee, 1000 in against 1:2 reserves returns just under 2000
+        let no_fee = swap_output(1_000, reserve_in, reserve_out, 0).unwrap();
+        assert_eq!(no_fee, 1_998);
+
+        // A 30 bps fee returns strictly less
+        let with_fee = swap_output(1_000, reserve_in, reserve_out, 30).unwrap();
+        assert_eq!(with_fee, 1_992);
+
+        // k never decreases once the full input joins the reserves
+        let k_before = reserve_in as u128 * reserve_out as u128;
+        let k_after = (reserve_in + 1_000) as u128 * (reserve_out - with_fee) as u128;
+        assert!(k_after >= k_before);
+    }
+
+    #[test]
+    fn test_deposit_and_withdraw_amounts() {
+        // The first deposit mints sqrt(a * b)
+        assert_eq!(deposit_amounts(0, 0, 0, 400, 900), Some((400, 900, 600)));
+
+        // Later deposits are trimmed to the pool ratio (1:2 here), rounding up
+        let (a, b, lp) = deposit_amounts(1_000, 2_000, 1_000, 500, 500).unwrap();
+        assert_eq!((a, b, lp), (250, 500, 250));
+
+        // Burning half the supply returns half of each reserve
+        assert_eq!(withdraw_amounts(1_000, 2_000, 1_000, 500), Some((500, 1_000)));
+        assert_eq!(withdraw_amounts(1_000, 2_000, 1_000, 1_001), None);
+    }
+}
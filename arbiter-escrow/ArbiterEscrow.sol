@@ -0,0 +1,67 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+/**
+ * @title ArbiterEscrow
+ * @dev Holds ETH for a payee until the payer or an arbiter releases it, or the
+ * payee or arbiter refunds it
+ */
+contract ArbiterEscrow {
+    struct Escrow {
+        address payer;
+        address payee;
+        address arbiter;
+        uint256 amount;
+    }
+
+    // payer => seed => escrow
+    mapping(address => mapping(uint64 => Escrow)) public escrows;
+
+    event Initialized(address indexed payer, uint64 seed, address indexed payee, address arbiter, uint256 amount);
+    event Released(address indexed payer, uint64 seed, uint256 amount);
+    event Refunded(address indexed payer, uint64 seed, uint256 amount);
+
+    /**
+     * @dev Deposit msg.value for `payee`, decided by `arbiter`
+     */
+    function initialize(uint64 seed, address payee, address arbiter) external payable {
+        require(msg.value > 0, "Amount must be greater than zero");
+        require(escrows[msg.sender][seed].payer == address(0), "Escrow exists");
+
+        escrows[msg.sender][seed] = Escrow(msg.sender, payee, arbiter, msg.value);
+
+        emit Initialized(msg.sender, seed, payee, arbiter, msg.value);
+    }
+
+    /**
+     * @dev Pay the payee (payer or arbiter)
+     */
+    function release(address payer, uint64 seed) external {
+        Escrow memory escrow = escrows[payer][seed];
+        require(escrow.payer != address(0), "No escrow");
+        require(msg.sender == escrow.payer || msg.sender == escrow.arbiter, "Unauthorized");
+
+        delete escrows[payer][seed];
+
+        (bool success, ) = escrow.payee.call{value: escrow.amount}("");
+        require(success, "Transfer failed");
+
+        emit Released(payer, seed, escrow.amount);
+    }
+
+    /**
+     * @dev Return the deposit to the payer (payee or arbiter)
+     */
+    function refund(address payer, uint64 seed) external {
+        Escrow memory escrow = escrows[payer][seed];
+        require(escrow.payer != address(0), "No escrow");
+        require(msg.sender == escrow.payee || msg.sender == escrow.arbiter, "Unauthorized");
+
+        delete escrows[payer][seed];
+
+        (bool success, ) = escrow.payer.call{value: escrow.amount}("");
+        require(success, "Transfer failed");
+
+        emit Refunded(payer, seed, escrow.amount);
+    }
+}
@@ -0,0 +1,369 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction, system_program,
+    sysvar::Sysvar,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// Seed prefix for escrow PDAs: ["escrow", payer, seed]
+pub const ESCROW_SEED: &[u8] = b"escrow";
+
+/// Anchor's account discriminator for `Escrow`: sha256("account:Escrow")[..8].
+/// Written at the start of every escrow so both programs store identical bytes.
+pub const ESCROW_DISCRIMINATOR: [u8; 8] = [31, 213, 123, 187, 186, 22, 218, 155];
+
+/// Anchor's instruction discriminators: sha256("global:<name>")[..8]
+pub const INITIALIZE_DISCRIMINATOR: [u8; 8] = [175, 175, 109, 31, 13, 152, 155, 237];
+pub const RELEASE_DISCRIMINATOR: [u8; 8] = [253, 249, 15, 206, 28, 127, 193, 241];
+pub const REFUND_DISCRIMINATOR: [u8; 8] = [2, 96, 183, 251, 63, 208, 46, 46];
+
+/// Size of an escrow account, discriminator included
+pub const ESCROW_ACCOUNT_SIZE: usize = 8 // discriminator
+    + 32 // payer
+    + 32 // payee
+    + 32 // arbiter
+    + 8 // amount
+    + 8 // seed
+    + 1; // bump
+
+/// Errors returned by the program as `ProgramError::Custom` codes. Numbered
+/// from 6000 like Anchor's `#[error_code]`, so both programs fail identically.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EscrowError {
+    /// Amount must be greater than zero
+    ZeroAmount = 6000,
+    /// Signer may not release or refund this escrow
+    Unauthorized = 6001,
+    /// Payer or payee account does not match the escrow
+    WrongAccount = 6002,
+}
+
+impl From<EscrowError> for ProgramError {
+    fn from(e: EscrowError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+/// Lamports held for `payee` until `arbiter` (or `payer`) releases them, or
+/// `arbiter` (or `payee`) refunds them. Stored after `ESCROW_DISCRIMINATOR`.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Escrow {
+    pub payer: Pubkey,
+    pub payee: Pubkey,
+    pub arbiter: Pubkey,
+    /// Lamports held on top of rent
+    pub amount: u64,
+    pub seed: u64,
+    pub bump: u8,
+}
+
+impl Escrow {
+    /// The payer can always pay out; the arbiter decides disputes
+    pub fn can_release(&self, signer: &Pubkey) -> bool {
+        *signer == self.payer || *signer == self.arbiter
+    }
+
+    /// The payee can always give the money back; the arbiter decides disputes
+    pub fn can_refund(&self, signer: &Pubkey) -> bool {
+        *signer == self.payee || *signer == self.arbiter
+    }
+}
+
+/// Address of `payer`'s escrow number `seed`
+pub fn escrow_address(program_id: &Pubkey, payer: &Pubkey, seed: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[ESCROW_SEED, payer.as_ref(), &seed.to_le_bytes()], program_id)
+}
+
+/// Instructions in Anchor's wire format: an 8-byte discriminator, then the
+/// borsh-encoded arguments. Either program accepts the other's transactions.
+#[derive(Debug, PartialEq, Eq)]
+pub enum EscrowInstruction {
+    /// Create the escrow PDA and deposit `amount` lamports into it
+    /// Accounts: [writable, signer] payer, [] payee, [] arbiter, [writable] escrow PDA, [] system program
+    Initialize { seed: u64, amount: u64 },
+    /// Pay `amount` to the payee and close the escrow, returning rent to the payer (payer or arbiter)
+    /// Accounts: [signer] signer, [writable] payer, [writable] payee, [writable] escrow PDA
+    Release,
+    /// Close the escrow, returning everything to the payer (payee or arbiter)
+    /// Accounts: [signer] signer, [writable] payer, [writable] escrow PDA
+    Refund,
+}
+
+impl EscrowInstruction {
+    pub fn pack(&self) -> Vec<u8> {
+        match self {
+            Self::Initialize { seed, amount } => {
+                let mut data = INITIALIZE_DISCRIMINATOR.to_vec();
+                data.extend_from_slice(&seed.to_le_bytes());
+                data.extend_from_slice(&amount.to_le_bytes());
+                data
+            }
+            Self::Release => RELEASE_DISCRIMINATOR.to_vec(),
+            Self::Refund => REFUND_DISCRIMINATOR.to_vec(),
+        }
+    }
+
+    pub fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        if data.len() < 8 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        let (discriminator, mut args) = data.split_at(8);
+
+        match <[u8; 8]>::try_from(discriminator).unwrap() {
+            INITIALIZE_DISCRIMINATOR => {
+                let (seed, amount) = <(u64, u64)>::deserialize(&mut args)
+                    .map_err(|_| ProgramError::InvalidInstructionData)?;
+                Ok(Self::Initialize { seed, amount })
+            }
+            RELEASE_DISCRIMINATOR => Ok(Self::Release),
+            REFUND_DISCRIMINATOR => Ok(Self::Refund),
+            _ => Err(ProgramError::InvalidInstructionData),
+        }
+    }
+}
+
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    match EscrowInstruction::unpack(instruction_data)? {
+        EscrowInstruction::Initialize { seed, amount } => initialize(program_id, accounts, seed, amount),
+        EscrowInstruction::Release => release(program_id, accounts),
+        EscrowInstruction::Refund => refund(program_id, accounts),
+    }
+}
+
+fn initialize(program_id: &Pubkey, accounts: &[AccountInfo], seed: u64, amount: u64) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let payer = next_account_info(accounts_iter)?;
+    let payee = next_account_info(accounts_iter)?;
+    let arbiter = next_account_info(accounts_iter)?;
+    let escrow_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !payer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if amount == 0 {
+        msg!("Amount must be greater than zero");
+        return Err(EscrowError::ZeroAmount.into());
+    }
+
+    let (expected_escrow, bump) = escrow_address(program_id, payer.key, seed);
+    if expected_escrow != *escrow_account.key {
+        msg!("Escrow account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    invoke_signed(
+        &system_instruction::create_account(
+            payer.key,
+            escrow_account.key,
+            Rent::get()?.minimum_balance(ESCROW_ACCOUNT_SIZE),
+            ESCROW_ACCOUNT_SIZE as u64,
+            program_id,
+        ),
+        &[payer.clone(), escrow_account.clone(), system_program.clone()],
+        &[&[ESCROW_SEED, payer.key.as_ref(), &seed.to_le_bytes(), &[bump]]],
+    )?;
+
+    let escrow = Escrow {
+        payer: *payer.key,
+        payee: *payee.key,
+        arbiter: *arbiter.key,
+        amount,
+        seed,
+        bump,
+    };
+
+    let mut data = escrow_account.data.borrow_mut();
+    data[..8].copy_from_slice(&ESCROW_DISCRIMINATOR);
+    escrow.serialize(&mut &mut data[8..])?;
+    drop(data);
+
+    invoke(
+        &system_instruction::transfer(payer.key, escrow_account.key, amount),
+        &[payer.clone(), escrow_account.clone(), system_program.clone()],
+    )?;
+
+    msg!("Escrowed {} lamports for {}", amount, payee.key);
+
+    Ok(())
+}
+
+fn release(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let signer = next_account_info(accounts_iter)?;
+    let payer = next_account_info(accounts_iter)?;
+    let payee = next_account_info(accounts_iter)?;
+    let escrow_account = next_account_info(accounts_iter)?;
+
+    if !signer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let escrow = load_escrow(program_id, escrow_account)?;
+
+    if *payer.key != escrow.payer || *payee.key != escrow.payee {
+        msg!("Payer or payee does not match the escrow");
+        return Err(EscrowError::WrongAccount.into());
+    }
+
+    if !escrow.can_release(signer.key) {
+        msg!("Only the payer or arbiter can release");
+        return Err(EscrowError::Unauthorized.into());
+    }
+
+    **escrow_account.try_borrow_mut_lamports()? -= escrow.amount;
+    **payee.try_borrow_mut_lamports()? += escrow.amount;
+    close(escrow_account, payer)?;
+
+    msg!("Released {} lamports to {}", escrow.amount, payee.key);
+
+    Ok(())
+}
+
+fn refund(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let signer = next_account_info(accounts_iter)?;
+    let payer = next_account_info(accounts_iter)?;
+    let escrow_account = next_account_info(accounts_iter)?;
+
+    if !signer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let escrow = load_escrow(program_id, escrow_account)?;
+
+    if *payer.key != escrow.payer {
+        msg!("Payer does not match the escrow");
+        return Err(EscrowError::WrongAccount.into());
+    }
+
+    if !escrow.can_refund(signer.key) {
+        msg!("Only the payee or arbiter can refund");
+        return Err(EscrowError::Unauthorized.into());
+    }
+
+    close(escrow_account, payer)?;
+
+    msg!("Refunded {} lamports to {}", escrow.amount, payer.key);
+
+    Ok(())
+}
+
+fn load_escrow(program_id: &Pubkey, escrow_account: &AccountInfo) -> Result<Escrow, ProgramError> {
+    if escrow_account.owner != program_id {
+        msg!("Escrow account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let data = escrow_account.data.borrow();
+    if data.len() < 8 || data[..8] != ESCROW_DISCRIMINATOR {
+        msg!("Not an escrow account");
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    Ok(Escrow::try_from_slice(&data[8..])?)
+}
+
+/// Close the way Anchor's `close` constraint does: move every lamport to
+/// `destination`, hand the account back to the system program and drop its data
+fn close(account: &AccountInfo, destination: &AccountInfo) -> ProgramResult {
+    let lamports = account.lamports();
+    **account.try_borrow_mut_lamports()? = 0;
+    **destination.try_borrow_mut_lamports()? = destination
+        .lamports()
+        .checked_add(lamports)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    account.assign(&system_program::id());
+    account.realloc(0, false)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_program::{clock::Epoch, hash::hash};
+
+    #[test]
+    fn test_wire_format_matches_anchor() {
+        let discriminator = |preimage: &str| -> [u8; 8] {
+            hash(preimage.as_bytes()).to_bytes()[..8].try_into().unwrap()
+        };
+        assert_eq!(ESCROW_DISCRIMINATOR, discriminator("account:Escrow"));
+        assert_eq!(INITIALIZE_DISCRIMINATOR, discriminator("global:initialize"));
+        assert_eq!(RELEASE_DISCRIMINATOR, discriminator("global:release"));
+        assert_eq!(REFUND_DISCRIMINATOR, discriminator("global:refund"));
+
+        let instruction = EscrowInstruction::Initialize { seed: 7, amount: 1_000 };
+        assert_eq!(EscrowInstruction::unpack(&instruction.pack()), Ok(instruction));
+        assert_eq!(EscrowInstruction::unpack(&[0; 4]), Err(ProgramError::InvalidInstructionData));
+
+        let escrow = Escrow {
+            payer: Pubkey::new_unique(),
+            payee: Pubkey::new_unique(),
+            arbiter: Pubkey::new_unique(),
+            amount: 1_000,
+            seed: 7,
+            bump: 255,
+        };
+        assert_eq!(8 + escrow.try_to_vec().unwrap().len(), ESCROW_ACCOUNT_SIZE);
+    }
+
+    #[test]
+    fn test_only_payer_or_arbiter_releases() {
+        let program_id = Pubkey::new_unique();
+        let payer = Pubkey::new_unique();
+        let payee = Pubkey::new_unique();
+        let (escrow_key, bump) = escrow_address(&program_id, &payer, 0);
+
+        let escrow = Escrow {
+            payer,
+            payee,
+            arbiter: Pubkey::new_unique(),
+            amount: 1_000,
+            seed: 0,
+            bump,
+        };
+        assert!(escrow.can_release(&payer) && !escrow.can_release(&payee));
+        assert!(escrow.can_refund(&payee) && !escrow.can_refund(&payer));
+
+        let mut escrow_data = ESCROW_DISCRIMINATOR.to_vec();
+        escrow_data.extend(escrow.try_to_vec().unwrap());
+
+        // The payee signs, which is not enough to release
+        let keys = [payee, payer, payee, escrow_key];
+        let mut lamports: Vec<u64> = vec![0, 0, 0, 2_000];
+        let mut data: Vec<Vec<u8>> = vec![vec![], vec![], vec![], escrow_data];
+
+        let accounts: Vec<AccountInfo> = keys
+            .iter()
+            .zip(lamports.iter_mut())
+            .zip(data.iter_mut())
+            .enumerate()
+            .map(|(i, ((key, lamports), data))| {
+                AccountInfo::new(key, i == 0, true, lamports, data, &program_id, false, Epoch::default())
+            })
+            .collect();
+
+        let result = process_instruction(&program_id, &accounts, &EscrowInstruction::Release.pack());
+        assert_eq!(result, Err(EscrowError::Unauthorized.into()));
+
+        // Payer and payee swapped
+        let swapped = [accounts[0].clone(), accounts[2].clone(), accounts[1].clone(), accounts[3].clone()];
+        let result = process_instruction(&program_id, &swapped, &EscrowInstruction::Release.pack());
+        assert_eq!(result, Err(EscrowError::WrongAccount.into()));
+    }
+}
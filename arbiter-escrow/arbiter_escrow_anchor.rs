@@ -0,0 +1,159 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+
+declare_id!("HPbndhJ67ZBHeY1u2XnEH2LJTbsgBQmPauV45p24DzA3");
+
+/// Seed prefix for escrow PDAs: ["escrow", payer, seed]
+pub const ESCROW_SEED: &[u8] = b"escrow";
+
+#[program]
+pub mod arbiter_escrow_anchor {
+    use super::*;
+
+    /// Create the escrow PDA and deposit `amount` lamports into it
+    pub fn initialize(ctx: Context<Initialize>, seed: u64, amount: u64) -> Result<()> {
+        require!(amount > 0, EscrowError::ZeroAmount);
+
+        let escrow = &mut ctx.accounts.escrow;
+        escrow.payer = ctx.accounts.payer.key();
+        escrow.payee = ctx.accounts.payee.key();
+        escrow.arbiter = ctx.accounts.arbiter.key();
+        escrow.amount = amount;
+        escrow.seed = seed;
+        escrow.bump = ctx.bumps.escrow;
+
+        transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: ctx.accounts.escrow.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        msg!("Escrowed {} lamports for {}", amount, ctx.accounts.payee.key());
+
+        Ok(())
+    }
+
+    /// Pay `amount` to the payee (payer or arbiter); `close` returns the rent to the payer
+    pub fn release(ctx: Context<Release>) -> Result<()> {
+        let escrow = &ctx.accounts.escrow;
+        let signer = ctx.accounts.signer.key();
+        require!(
+            signer == escrow.payer || signer == escrow.arbiter,
+            EscrowError::Unauthorized
+        );
+
+        let amount = escrow.amount;
+        ctx.accounts.escrow.sub_lamports(amount)?;
+        ctx.accounts.payee.add_lamports(amount)?;
+
+        msg!("Released {} lamports to {}", amount, ctx.accounts.payee.key());
+
+        Ok(())
+    }
+
+    /// Give everything back to the payer (payee or arbiter); `close` does the transfer
+    pub fn refund(ctx: Context<Refund>) -> Result<()> {
+        let escrow = &ctx.accounts.escrow;
+        let signer = ctx.accounts.signer.key();
+        require!(
+            signer == escrow.payee || signer == escrow.arbiter,
+            EscrowError::Unauthorized
+        );
+
+        msg!("Refunded {} lamports to {}", escrow.amount, escrow.payer);
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(seed: u64)]
+pub struct Initialize<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// CHECK: only recorded as the recipient
+    pub payee: UncheckedAccount<'info>,
+    /// CHECK: only recorded as the arbiter
+    pub arbiter: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Escrow::INIT_SPACE,
+        seeds = [ESCROW_SEED, payer.key().as_ref(), &seed.to_le_bytes()],
+        bump
+    )]
+    pub escrow: Account<'info, Escrow>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Release<'info> {
+    pub signer: Signer<'info>,
+    /// CHECK: must match `escrow.payer`
+    #[account(mut)]
+    pub payer: UncheckedAccount<'info>,
+    /// CHECK: must match `escrow.payee`
+    #[account(mut)]
+    pub payee: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        has_one = payer @ EscrowError::WrongAccount,
+        has_one = payee @ EscrowError::WrongAccount,
+        close = payer
+    )]
+    pub escrow: Account<'info, Escrow>,
+}
+
+#[derive(Accounts)]
+pub struct Refund<'info> {
+    pub signer: Signer<'info>,
+    /// CHECK: must match `escrow.payer`
+    #[account(mut)]
+    pub payer: UncheckedAccount<'info>,
+    #[account(mut, has_one = payer @ EscrowError::WrongAccount, close = payer)]
+    pub escrow: Account<'info, Escrow>,
+}
+
+/// Same field order as the native `Escrow`, so the bytes after the 8-byte
+/// discriminator decode with either struct
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct Escrow {
+    pub payer: Pubkey,
+    pub payee: Pubkey,
+    pub arbiter: Pubkey,
+    /// Lamports held on top of rent
+    pub amount: u64,
+    pub seed: u64,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum EscrowError {
+    #[msg("Amount must be greater than zero")]
+    ZeroAmount,
+    #[msg("Signer may not release or refund this escrow")]
+    Unauthorized,
+    #[msg("Payer or payee account does not match the escrow")]
+    WrongAccount,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use anchor_lang::Discriminator;
+
+    #[test]
+    fn test_layout_matches_native() {
+        // Native ESCROW_ACCOUNT_SIZE is 121 bytes and its errors start at 6000
+        assert_eq!(8 + Escrow::INIT_SPACE, 121);
+        assert_eq!(Escrow::DISCRIMINATOR, [31, 213, 123, 187, 186, 22, 218, 155]);
+        assert_eq!(u32::from(EscrowError::ZeroAmount), 6000);
+        assert_eq!(u32::from(EscrowError::WrongAccount), 6002);
+    }
+}
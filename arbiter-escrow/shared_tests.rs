@@ -0,0 +1,216 @@
+//! One solana-program-test suite run against both escrow programs.
+//!
+//! Every test sends the same instructions, in Anchor's wire format, to the
+//! native program (`arbiter_escrow.rs`) and the Anchor program
+//! (`arbiter_escrow_anchor.rs`) loaded side by side in one test validator,
+//! and asserts the same balances, account bytes and error codes from each.
+//! Both crates are linked with their `no-entrypoint` feature and run as
+//! builtins, so no SBF build is needed. Run with `cargo test`.
+
+use arbiter_escrow::{escrow_address, Escrow, EscrowError, EscrowInstruction, ESCROW_ACCOUNT_SIZE, ESCROW_DISCRIMINATOR};
+use borsh::BorshDeserialize;
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    pubkey,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::{Transaction, TransactionError},
+};
+
+/// Test id for the native program; Anchor's id comes from its `declare_id!`
+const NATIVE_PROGRAM_ID: Pubkey = pubkey!("HCqTqaNUgqBS6YCJE7Joqxu85auq7neeCMjYcm2yrWw4");
+
+const PROGRAM_IDS: [Pubkey; 2] = [NATIVE_PROGRAM_ID, arbiter_escrow_anchor::ID];
+
+const AMOUNT: u64 = 1_000_000;
+
+/// Anchor's `entry` ties the slice and account lifetimes together, which the
+/// runtime's plain fn pointer type cannot express, so widen them for the call
+fn process_anchor(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let accounts: &[AccountInfo] = unsafe { std::mem::transmute(accounts) };
+    arbiter_escrow_anchor::entry(program_id, accounts, data)
+}
+
+struct Parties {
+    payee: Keypair,
+    arbiter: Keypair,
+}
+
+async fn start() -> (ProgramTestContext, Parties) {
+    let mut program_test = ProgramTest::default();
+    program_test.add_program(
+        "arbiter_escrow",
+        NATIVE_PROGRAM_ID,
+        processor!(arbiter_escrow::process_instruction),
+    );
+    program_test.add_program("arbiter_escrow_anchor", arbiter_escrow_anchor::ID, processor!(process_anchor));
+
+    let context = program_test.start_with_context().await;
+    let parties = Parties {
+        payee: Keypair::new(),
+        arbiter: Keypair::new(),
+    };
+    (context, parties)
+}
+
+async fn send(
+    context: &mut ProgramTestContext,
+    instruction: Instruction,
+    signer: Option<&Keypair>,
+) -> Result<(), BanksClientError> {
+    let mut signers = vec![&context.payer];
+    signers.extend(signer);
+    let blockhash = context.banks_client.get_latest_blockhash().await?;
+    let transaction =
+        Transaction::new_signed_with_payer(&[instruction], Some(&context.payer.pubkey()), &signers, blockhash);
+    context.banks_client.process_transaction(transaction).await
+}
+
+/// The custom error code a failed transaction returned, if any
+fn custom_error(result: Result<(), BanksClientError>) -> Option<u32> {
+    match result.err()?.unwrap() {
+        TransactionError::InstructionError(0, InstructionError::Custom(code)) => Some(code),
+        _ => None,
+    }
+}
+
+fn initialize(program_id: &Pubkey, payer: &Pubkey, parties: &Parties, seed: u64, amount: u64) -> Instruction {
+    Instruction::new_with_bytes(
+        *program_id,
+        &EscrowInstruction::Initialize { seed, amount }.pack(),
+        vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(parties.payee.pubkey(), false),
+            AccountMeta::new_readonly(parties.arbiter.pubkey(), false),
+            AccountMeta::new(escrow_address(program_id, payer, seed).0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+fn release(program_id: &Pubkey, signer: &Pubkey, payer: &Pubkey, payee: &Pubkey, seed: u64) -> Instruction {
+    Instruction::new_with_bytes(
+        *program_id,
+        &EscrowInstruction::Release.pack(),
+        vec![
+            AccountMeta::new_readonly(*signer, true),
+            AccountMeta::new(*payer, false),
+            AccountMeta::new(*payee, false),
+            AccountMeta::new(escrow_address(program_id, payer, seed).0, false),
+        ],
+    )
+}
+
+fn refund(program_id: &Pubkey, signer: &Pubkey, payer: &Pubkey, seed: u64) -> Instruction {
+    Instruction::new_with_bytes(
+        *program_id,
+        &EscrowInstruction::Refund.pack(),
+        vec![
+            AccountMeta::new_readonly(*signer, true),
+            AccountMeta::new(*payer, false),
+            AccountMeta::new(escrow_address(program_id, payer, seed).0, false),
+        ],
+    )
+}
+
+#[tokio::test]
+async fn test_accounts_are_byte_compatible() {
+    let (mut context, parties) = start().await;
+    let payer = context.payer.pubkey();
+
+    let mut stored = vec![];
+    for program_id in PROGRAM_IDS {
+        send(&mut context, initialize(&program_id, &payer, &parties, 7, AMOUNT), None)
+            .await
+            .unwrap();
+
+        let account = context
+            .banks_client
+            .get_account(escrow_address(&program_id, &payer, 7).0)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(account.owner, program_id);
+        assert_eq!(account.data.len(), ESCROW_ACCOUNT_SIZE);
+        assert_eq!(account.data[..8], ESCROW_DISCRIMINATOR);
+
+        let escrow = Escrow::try_from_slice(&account.data[8..]).unwrap();
+        assert_eq!(escrow.payee, parties.payee.pubkey());
+        assert_eq!(escrow.amount, AMOUNT);
+        stored.push(account);
+    }
+
+    // Only the trailing bump differs, since each program derives its own PDA
+    let (native, anchor) = (&stored[0], &stored[1]);
+    assert_eq!(native.data[..ESCROW_ACCOUNT_SIZE - 1], anchor.data[..ESCROW_ACCOUNT_SIZE - 1]);
+    assert_eq!(native.lamports, anchor.lamports);
+}
+
+#[tokio::test]
+async fn test_release_pays_payee_and_closes() {
+    let (mut context, parties) = start().await;
+    let payer = context.payer.pubkey();
+    let payee = parties.payee.pubkey();
+    let arbiter = parties.arbiter.pubkey();
+
+    for (seed, program_id) in PROGRAM_IDS.iter().enumerate() {
+        let seed = seed as u64;
+        send(&mut context, initialize(program_id, &payer, &parties, seed, AMOUNT), None)
+            .await
+            .unwrap();
+
+        let before = context.banks_client.get_balance(payee).await.unwrap();
+        send(&mut context, release(program_id, &arbiter, &payer, &payee, seed), Some(&parties.arbiter))
+            .await
+            .unwrap();
+
+        assert_eq!(context.banks_client.get_balance(payee).await.unwrap(), before + AMOUNT);
+        let escrow = context
+            .banks_client
+            .get_account(escrow_address(program_id, &payer, seed).0)
+            .await
+            .unwrap();
+        assert!(escrow.is_none());
+    }
+}
+
+#[tokio::test]
+async fn test_refund_and_authorization() {
+    let (mut context, parties) = start().await;
+    let payer = context.payer.pubkey();
+    let payee = parties.payee.pubkey();
+
+    for program_id in PROGRAM_IDS {
+        let result = send(&mut context, initialize(&program_id, &payer, &parties, 0, 0), None).await;
+        assert_eq!(custom_error(result), Some(EscrowError::ZeroAmount as u32));
+
+        send(&mut context, initialize(&program_id, &payer, &parties, 0, AMOUNT), None)
+            .await
+            .unwrap();
+
+        // The payee cannot pay themselves, and the payer cannot take it back
+        let result = send(&mut context, release(&program_id, &payee, &payer, &payee, 0), Some(&parties.payee)).await;
+        assert_eq!(custom_error(result), Some(EscrowError::Unauthorized as u32));
+        let result = send(&mut context, refund(&program_id, &payer, &payer, 0), None).await;
+        assert_eq!(custom_error(result), Some(EscrowError::Unauthorized as u32));
+
+        // Funds can only go to the recorded payee
+        let stranger = Pubkey::new_unique();
+        let result = send(&mut context, release(&program_id, &payer, &payer, &stranger, 0), None).await;
+        assert_eq!(custom_error(result), Some(EscrowError::WrongAccount as u32));
+
+        send(&mut context, refund(&program_id, &payee, &payer, 0), Some(&parties.payee))
+            .await
+            .unwrap();
+        let escrow = context
+            .banks_client
+            .get_account(escrow_address(&program_id, &payer, 0).0)
+            .await
+            .unwrap();
+        assert!(escrow.is_none());
+    }
+}
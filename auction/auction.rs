@@ -0,0 +1,576 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint,
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use spl_token::state::Account as TokenAccount;
+
+/// Seed prefix for auction PDAs: ["auction", seller, mint]
+pub const AUCTION_SEED: &[u8] = b"auction";
+
+/// Seed prefix for the item vault PDA: ["vault", auction]
+pub const VAULT_SEED: &[u8] = b"vault";
+
+/// Size of an `AuctionAccount`
+pub const AUCTION_ACCOUNT_SIZE: usize = 1 // is_initialized
+    + 32 // seller
+    + 32 // mint
+    + 8 // item_amount
+    + 8 // min_bid
+    + 8 // min_increment
+    + 8 // end_slot
+    + 8 // extension_slots
+    + 1 + 32 // highest_bidder
+    + 8 // highest_bid
+    + 1 // settled
+    + 1 // claimed
+    + 1 // bump
+    + 1; // vault_bump
+
+/// Errors returned by the program as `ProgramError::Custom` codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuctionError {
+    /// Item amount, minimum bid, or duration is zero
+    InvalidParameters = 0,
+    /// Bidding has closed
+    AuctionEnded = 1,
+    /// The auction is still running
+    AuctionNotEnded = 2,
+    /// The bid is below the minimum bid or the required increment
+    BidTooLow = 3,
+    /// The refund account is not the current highest bidder
+    WrongPreviousBidder = 4,
+    /// The seller has already been paid
+    AlreadySettled = 5,
+    /// The item has already been delivered
+    AlreadyClaimed = 6,
+    /// Only the highest bidder may claim the item
+    NotWinner = 7,
+    /// The auction has not been initialized
+    NotInitialized = 8,
+    /// The seller may not bid on their own auction
+    SellerCannotBid = 9,
+}
+
+impl From<AuctionError> for ProgramError {
+    fn from(e: AuctionError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+/// An English auction for `item_amount` of `mint`, paid in lamports. The
+/// highest bid is held in this account and replaced on every outbid.
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct AuctionAccount {
+    pub is_initialized: bool,
+    pub seller: Pubkey,
+    /// Mint of the item being sold; the vault holds `item_amount` of it
+    pub mint: Pubkey,
+    pub item_amount: u64,
+    /// Lowest acceptable opening bid in lamports
+    pub min_bid: u64,
+    /// Each new bid must beat the current one by at least this much
+    pub min_increment: u64,
+    /// Bidding is open while `slot < end_slot`
+    pub end_slot: u64,
+    /// A bid within this many slots of the end pushes the end out to
+    /// `slot + extension_slots`
+    pub extension_slots: u64,
+    pub highest_bidder: Option<Pubkey>,
+    pub highest_bid: u64,
+    /// The seller has been paid (or the unsold item returned)
+    pub settled: bool,
+    /// The item has left the vault
+    pub claimed: bool,
+    pub bump: u8,
+    pub vault_bump: u8,
+}
+
+impl AuctionAccount {
+    /// Smallest bid that would currently be accepted
+    pub fn minimum_next_bid(&self) -> Option<u64> {
+        match self.highest_bidder {
+            Some(_) => self.highest_bid.checked_add(self.min_increment),
+            None => Some(self.min_bid),
+        }
+    }
+
+    /// End slot after a bid lands at `slot`: bids near the end extend the
+    /// auction so no one can win by sniping in the final slot
+    pub fn extended_end_slot(&self, slot: u64) -> u64 {
+        let extended = slot.saturating_add(self.extension_slots);
+        self.end_slot.max(extended)
+    }
+}
+
+entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = AuctionInstruction::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        AuctionInstruction::CreateAuction {
+            item_amount,
+            min_bid,
+            min_increment,
+            duration_slots,
+            extension_slots,
+        } => create_auction(
+            program_id,
+            accounts,
+            item_amount,
+            min_bid,
+            min_increment,
+            duration_slots,
+            extension_slots,
+        ),
+        AuctionInstruction::Bid { amount } => bid(program_id, accounts, amount),
+        AuctionInstruction::Settle => settle(program_id, accounts),
+        AuctionInstruction::Claim => claim(program_id, accounts),
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub enum AuctionInstruction {
+    /// Create the auction and vault PDAs and deposit the item
+    /// Accounts: [writable, signer] seller, [writable] auction PDA, [writable] vault PDA, [] mint,
+    /// [writable] seller token account, [] token program, [] system program
+    CreateAuction {
+        item_amount: u64,
+        min_bid: u64,
+        min_increment: u64,
+        duration_slots: u64,
+        extension_slots: u64,
+    },
+    /// Escrow `amount` lamports in the auction PDA and refund the previous
+    /// highest bidder in full. Pass the bidder again as the previous bidder
+    /// when there is no bid yet.
+    /// Accounts: [writable, signer] bidder, [writable] auction PDA, [writable] previous highest bidder,
+    /// [] system program
+    Bid { amount: u64 },
+    /// After the end: pay the highest bid to the seller, or return the item if there were no bids
+    /// Accounts: [writable] seller, [writable] auction PDA, [writable] vault,
+    /// [writable] seller token account, [] token program
+    Settle,
+    /// After the end: deliver the item to the highest bidder
+    /// Accounts: [signer] winner, [writable] auction PDA, [writable] vault,
+    /// [writable] winner token account, [] token program
+    Claim,
+}
+
+fn create_auction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    item_amount: u64,
+    min_bid: u64,
+    min_increment: u64,
+    duration_slots: u64,
+    extension_slots: u64,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let seller = next_account_info(accounts_iter)?;
+    let auction_account = next_account_info(accounts_iter)?;
+    let vault = next_account_info(accounts_iter)?;
+    let mint = next_account_info(accounts_iter)?;
+    let seller_token = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !seller.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if item_amount == 0 || min_bid == 0 || duration_slots == 0 {
+        msg!("Item amount, minimum bid, and duration must be greater than zero");
+        return Err(AuctionError::InvalidParameters.into());
+    }
+
+    if *token_program.key != spl_token::id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let (expected_auction, bump) = Pubkey::find_program_address(
+        &[AUCTION_SEED, seller.key.as_ref(), mint.key.as_ref()],
+        program_id,
+    );
+    if expected_auction != *auction_account.key {
+        msg!("Auction account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let (expected_vault, vault_bump) =
+        Pubkey::find_program_address(&[VAULT_SEED, auction_account.key.as_ref()], program_id);
+    if expected_vault != *vault.key {
+        msg!("Vault account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let clock = Clock::get()?;
+    let rent = Rent::get()?;
+
+    invoke_signed(
+        &system_instruction::create_account(
+            seller.key,
+            auction_account.key,
+            rent.minimum_balance(AUCTION_ACCOUNT_SIZE),
+            AUCTION_ACCOUNT_SIZE as u64,
+            program_id,
+        ),
+        &[seller.clone(), auction_account.clone(), system_program.clone()],
+        &[&[AUCTION_SEED, seller.key.as_ref(), mint.key.as_ref(), &[bump]]],
+    )?;
+
+    // Create the item vault, owned by the auction PDA, and deposit the item
+    invoke_signed(
+        &system_instruction::create_account(
+            seller.key,
+            vault.key,
+            rent.minimum_balance(TokenAccount::LEN),
+            TokenAccount::LEN as u64,
+            &spl_token::id(),
+        ),
+        &[seller.clone(), vault.clone(), system_program.clone()],
+        &[&[VAULT_SEED, auction_account.key.as_ref(), &[vault_bump]]],
+    )?;
+    invoke(
+        &spl_token::instruction::initialize_account3(
+            &spl_token::id(),
+            vault.key,
+            mint.key,
+            auction_account.key,
+        )?,
+        &[vault.clone(), mint.clone()],
+    )?;
+    invoke(
+        &spl_token::instruction::transfer(
+            &spl_token::id(),
+            seller_token.key,
+            vault.key,
+            seller.key,
+            &[],
+            item_amount,
+        )?,
+        &[seller_token.clone(), vault.clone(), seller.clone()],
+    )?;
+
+    let auction = AuctionAccount {
+        is_initialized: true,
+        seller: *seller.key,
+        mint: *mint.key,
+        item_amount,
+        min_bid,
+        min_increment,
+        end_slot: clock
+            .slot
+            .checked_add(duration_slots)
+            .ok_or(AuctionError::InvalidParameters)?,
+        extension_slots,
+        highest_bidder: None,
+        highest_bid: 0,
+        settled: false,
+        claimed: false,
+        bump,
+        vault_bump,
+    };
+    auction.serialize(&mut &mut auction_account.data.borrow_mut()[..])?;
+
+    msg!("Auction open until slot {} with minimum bid {}", auction.end_slot, min_bid);
+
+    Ok(())
+}
+
+fn bid(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let bidder = next_account_info(accounts_iter)?;
+    let auction_account = next_account_info(accounts_iter)?;
+    let previous_bidder = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !bidder.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut auction = load_auction(program_id, auction_account)?;
+
+    if *bidder.key == auction.seller {
+        return Err(AuctionError::SellerCannotBid.into());
+    }
+
+    let clock = Clock::get()?;
+    if clock.slot >= auction.end_slot {
+        msg!("Auction ended at slot {}", auction.end_slot);
+        return Err(AuctionError::AuctionEnded.into());
+    }
+
+    let minimum = auction.minimum_next_bid().ok_or(ProgramError::ArithmeticOverflow)?;
+    if amount < minimum {
+        msg!("Bid must be at least {} lamports", minimum);
+        return Err(AuctionError::BidTooLow.into());
+    }
+
+    if let Some(previous) = auction.highest_bidder {
+        if previous != *previous_bidder.key {
+            msg!("Previous bidder account must be {}", previous);
+            return Err(AuctionError::WrongPreviousBidder.into());
+        }
+    }
+
+    // Escrow the new bid
+    invoke(
+        &system_instruction::transfer(bidder.key, auction_account.key, amount),
+        &[bidder.clone(), auction_account.clone(), system_program.clone()],
+    )?;
+
+    // Refund the bid being replaced
+    if auction.highest_bidder.is_some() {
+        **auction_account.try_borrow_mut_lamports()? -= auction.highest_bid;
+        **previous_bidder.try_borrow_mut_lamports()? += auction.highest_bid;
+    }
+
+    auction.highest_bidder = Some(*bidder.key);
+    auction.highest_bid = amount;
+    auction.end_slot = auction.extended_end_slot(clock.slot);
+    auction.serialize(&mut &mut auction_account.data.borrow_mut()[..])?;
+
+    msg!("New highest bid {} by {}; ends at slot {}", amount, bidder.key, auction.end_slot);
+
+    Ok(())
+}
+
+fn settle(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let seller = next_account_info(accounts_iter)?;
+    let auction_account = next_account_info(accounts_iter)?;
+    let vault = next_account_info(accounts_iter)?;
+    let seller_token = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+
+    let mut auction = load_auction(program_id, auction_account)?;
+
+    if auction.seller != *seller.key {
+        msg!("Seller account does not match the auction");
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    if auction.settled {
+        return Err(AuctionError::AlreadySettled.into());
+    }
+
+    let clock = Clock::get()?;
+    if clock.slot < auction.end_slot {
+        msg!("Auction runs until slot {}", auction.end_slot);
+        return Err(AuctionError::AuctionNotEnded.into());
+    }
+
+    auction.settled = true;
+
+    if auction.highest_bidder.is_some() {
+        **auction_account.try_borrow_mut_lamports()? -= auction.highest_bid;
+        **seller.try_borrow_mut_lamports()? += auction.highest_bid;
+        msg!("Seller paid {} lamports", auction.highest_bid);
+    } else {
+        // No bids: the item goes back to the seller
+        let seller_account = TokenAccount::unpack(&seller_token.data.borrow())?;
+        if seller_account.owner != auction.seller {
+            msg!("Return token account must belong to the seller");
+            return Err(ProgramError::InvalidAccountData);
+        }
+        release_item(program_id, auction_account, vault, seller_token, token_program, &auction)?;
+        auction.claimed = true;
+        msg!("No bids; item returned to seller");
+    }
+
+    auction.serialize(&mut &mut auction_account.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+fn claim(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let winner = next_account_info(accounts_iter)?;
+    let auction_account = next_account_info(accounts_iter)?;
+    let vault = next_account_info(accounts_iter)?;
+    let winner_token = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+
+    if !winner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut auction = load_auction(program_id, auction_account)?;
+
+    if auction.claimed {
+        return Err(AuctionError::AlreadyClaimed.into());
+    }
+
+    let clock = Clock::get()?;
+    if clock.slot < auction.end_slot {
+        msg!("Auction runs until slot {}", auction.end_slot);
+        return Err(AuctionError::AuctionNotEnded.into());
+    }
+
+    if auction.highest_bidder != Some(*winner.key) {
+        return Err(AuctionError::NotWinner.into());
+    }
+
+    auction.claimed = true;
+    release_item(program_id, auction_account, vault, winner_token, token_program, &auction)?;
+    auction.serialize(&mut &mut auction_account.data.borrow_mut()[..])?;
+
+    msg!("Item delivered to {}", winner.key);
+
+    Ok(())
+}
+
+/// Validate the auction account, returning its state
+fn load_auction(program_id: &Pubkey, auction_account: &AccountInfo) -> Result<AuctionAccount, ProgramError> {
+    if auction_account.owner != program_id {
+        msg!("Auction account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let auction = AuctionAccount::try_from_slice(&auction_account.data.borrow())?;
+    if !auction.is_initialized {
+        return Err(AuctionError::NotInitialized.into());
+    }
+
+    Ok(auction)
+}
+
+/// Move the item from the vault to `destination`, signed by the auction PDA
+fn release_item<'a>(
+    program_id: &Pubkey,
+    auction_account: &AccountInfo<'a>,
+    vault: &AccountInfo<'a>,
+    destination: &AccountInfo<'a>,
+    token_program: &AccountInfo<'a>,
+    auction: &AuctionAccount,
+) -> ProgramResult {
+    if *token_program.key != spl_token::id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let expected_vault = Pubkey::create_program_address(
+        &[VAULT_SEED, auction_account.key.as_ref(), &[auction.vault_bump]],
+        program_id,
+    )?;
+    if expected_vault != *vault.key {
+        msg!("Vault account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    invoke_signed(
+        &spl_token::instruction::transfer(
+            &spl_token::id(),
+            vault.key,
+            destination.key,
+            auction_account.key,
+            &[],
+            auction.item_amount,
+        )?,
+        &[vault.clone(), destination.clone(), auction_account.clone()],
+        &[&[AUCTION_SEED, auction.seller.as_ref(), auction.mint.as_ref(), &[auction.bump]]],
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_program::{clock::Epoch, entrypoint::SUCCESS, program_stubs};
+
+    /// Serves a Clock sysvar at a fixed slot so handlers can run off-chain
+    struct TestSyscallStubs {
+        slot: u64,
+    }
+
+    impl program_stubs::SyscallStubs for TestSyscallStubs {
+        fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+            unsafe {
+                *(var_addr as *mut Clock) = Clock {
+                    slot: self.slot,
+                    ..Clock::default()
+                };
+            }
+            SUCCESS
+        }
+    }
+
+    fn auction(highest_bidder: Option<Pubkey>) -> AuctionAccount {
+        AuctionAccount {
+            is_initialized: true,
+            seller: Pubkey::new_unique(),
+            mint: Pubkey::new_unique(),
+            item_amount: 1,
+            min_bid: 1_000,
+            min_increment: 100,
+            end_slot: 200,
+            extension_slots: 20,
+            highest_bidder,
+            highest_bid: if highest_bidder.is_some() { 1_500 } else { 0 },
+            settled: false,
+            claimed: false,
+            bump: 255,
+            vault_bump: 255,
+        }
+    }
+
+    #[test]
+    fn test_minimum_bid_and_anti_sniping_extension() {
+        assert_eq!(auction(None).minimum_next_bid(), Some(1_000));
+
+        let running = auction(Some(Pubkey::new_unique()));
+        assert_eq!(running.minimum_next_bid(), Some(1_600));
+
+        // Early bids leave the end alone; bids in the last 20 slots push it out
+        assert_eq!(running.extended_end_slot(100), 200);
+        assert_eq!(running.extended_end_slot(180), 200);
+        assert_eq!(running.extended_end_slot(195), 215);
+    }
+
+    #[test]
+    fn test_bid_below_increment_fails() {
+        program_stubs::set_syscall_stubs(Box::new(TestSyscallStubs { slot: 50 }));
+
+        let program_id = Pubkey::new_unique();
+        let previous_key = Pubkey::new_unique();
+        let bidder_key = Pubkey::new_unique();
+        let auction_key = Pubkey::new_unique();
+        let system_program_id = solana_program::system_program::id();
+
+        let mut auction_data = auction(Some(previous_key)).try_to_vec().unwrap();
+
+        let keys = [bidder_key, auction_key, previous_key, system_program_id];
+        let mut lamports: Vec<u64> = vec![10_000; keys.len()];
+        let mut data: Vec<Vec<u8>> = vec![vec![]; keys.len()];
+        data[1] = std::mem::take(&mut auction_data);
+
+        let accounts: Vec<AccountInfo> = keys
+            .iter()
+            .zip(lamports.iter_mut())
+            .zip(data.iter_mut())
+            .enumerate()
+            .map(|(i, ((key, lamports), data))| {
+                AccountInfo::new(key, i == 0, true, lamports, data, &program_id, false, Epoch::default())
+            })
+            .collect();
+
+        let instruction_data = AuctionInstruction::Bid { amount: 1_550 }.try_to_vec().unwrap();
+        let result = process_instruction(&program_id, &accounts, &instruction_data);
+        assert_eq!(result, Err(AuctionError::BidTooLow.into()));
+    }
+}
@@ -0,0 +1,193 @@
+//! Compute-unit measurements shared by the `cu-report` binary and the
+//! ceiling tests in `tests/cu_ceilings.rs`: each program's key instructions,
+//! run under solana-program-test against the SBF builds in `SBF_OUT_DIR`.
+
+use client::{
+    counter::{decrement, increment, initialize_counter},
+    crowdfund::{batch_refund, contribute, contributor_record_address, initialize_campaign, refund},
+    hello_world::{add_writer, get_message, reply, set_cooldown, set_message, SetMessageOptions},
+    hello_world_zero_copy,
+};
+use smart_contract_comparison::{
+    counter::CounterAccount,
+    crowdfund::{ContributorAccount, CrowdfundAccount},
+    hello_world::MESSAGE_ACCOUNT_SIZE,
+    hello_world_zero_copy::ZeroCopyMessageAccount,
+};
+use solana_program_test::{ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    instruction::Instruction,
+    pubkey::Pubkey,
+    rent::Rent,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::Transaction,
+};
+
+const WALLET_LAMPORTS: u64 = 1_000_000_000;
+
+/// Compute units one instruction consumed
+pub struct Measurement {
+    pub program: &'static str,
+    pub instruction: &'static str,
+    pub compute_units: u64,
+}
+
+struct Bench {
+    context: ProgramTestContext,
+    measurements: Vec<Measurement>,
+}
+
+impl Bench {
+    /// Process `instruction` in its own transaction, failing the run if it
+    /// doesn't succeed, and record its compute units
+    async fn measure(
+        &mut self,
+        program: &'static str,
+        name: &'static str,
+        instruction: Instruction,
+        signers: &[&Keypair],
+    ) {
+        let blockhash = self.context.get_new_latest_blockhash().await.unwrap();
+        let payer = &self.context.payer;
+        let mut keypairs = vec![payer];
+        keypairs.extend_from_slice(signers);
+        let transaction =
+            Transaction::new_signed_with_payer(&[instruction], Some(&payer.pubkey()), &keypairs, blockhash);
+
+        let outcome = self.context.banks_client.process_transaction_with_metadata(transaction).await.unwrap();
+        if let Err(error) = outcome.result {
+            panic!("{} {} failed: {}", program, name, error);
+        }
+        self.measurements.push(Measurement {
+            program,
+            instruction: name,
+            compute_units: outcome.metadata.unwrap().compute_units_consumed,
+        });
+    }
+}
+
+fn program_account(owner: Pubkey, len: usize) -> Account {
+    Account::new(Rent::default().minimum_balance(len), len, &owner)
+}
+
+/// Upper bound on the compute units of every instruction `measure_all`
+/// runs, as (program, instruction, ceiling). CI's `compute-units` job runs
+/// `tests/cu_ceilings.rs` against the SBF builds, so an instruction that
+/// starts re-serializing whole accounts or logging in a loop fails it. The
+/// ceilings start as generous bounds; once CI's `cu-report` has measured an
+/// instruction, set its ceiling about 20% above the measurement. Lower a
+/// ceiling when an optimization lands; raise one only with the reason in the
+/// commit.
+pub const CEILINGS: &[(&str, &str, u64)] = &[
+    ("counter", "Initialize", 8_000),
+    ("counter", "Increment", 6_000),
+    ("counter", "Decrement", 6_000),
+    ("crowdfund", "Initialize", 10_000),
+    ("crowdfund", "Contribute", 30_000),
+    ("crowdfund", "Contribute (second contributor)", 30_000),
+    ("crowdfund", "Refund", 20_000),
+    ("crowdfund", "BatchRefund", 25_000),
+    ("hello-world", "SetMessage (initialize)", 30_000),
+    ("hello-world", "SetMessage (update)", 25_000),
+    ("hello-world", "GetMessage", 15_000),
+    ("hello-world", "Reply", 40_000),
+    ("hello-world", "AddWriter", 20_000),
+    ("hello-world", "SetCooldown", 20_000),
+    ("hello-world-zero-copy", "SetMessage", 8_000),
+    ("hello-world-zero-copy", "GetMessage", 6_000),
+];
+
+/// The ceiling for `instruction` of `program`, if it has one
+pub fn ceiling(program: &str, instruction: &str) -> Option<u64> {
+    CEILINGS
+        .iter()
+        .find(|(ceiling_program, ceiling_instruction, _)| {
+            *ceiling_program == program && *ceiling_instruction == instruction
+        })
+        .map(|(_, _, ceiling)| *ceiling)
+}
+
+/// Run each program's key instructions in order against fresh accounts and
+/// return their compute units. Panics if an instruction fails.
+pub async fn measure_all() -> Vec<Measurement> {
+    let counter_id = smart_contract_comparison::counter::ID;
+    let crowdfund_id = smart_contract_comparison::crowdfund::ID;
+    let hello_world_id = smart_contract_comparison::hello_world::ID;
+    let zero_copy_id = smart_contract_comparison::hello_world_zero_copy::ID;
+
+    let mut program_test = ProgramTest::default();
+    program_test.prefer_bpf(true);
+    program_test.add_program("counter", counter_id, None);
+    program_test.add_program("crowdfund", crowdfund_id, None);
+    program_test.add_program("hello_world", hello_world_id, None);
+    program_test.add_program("hello_world_zero_copy", zero_copy_id, None);
+
+    let counter = Pubkey::new_unique();
+    program_test.add_account(counter, program_account(counter_id, CounterAccount::LEN));
+
+    let campaign = Pubkey::new_unique();
+    let contributors = [Keypair::new(), Keypair::new()];
+    program_test.add_account(campaign, program_account(crowdfund_id, CrowdfundAccount::LEN));
+    for contributor in &contributors {
+        let record = contributor_record_address(&campaign, &contributor.pubkey());
+        program_test.add_account(record, program_account(crowdfund_id, ContributorAccount::LEN));
+        program_test.add_account(contributor.pubkey(), Account::new(WALLET_LAMPORTS, 0, &system_program::id()));
+    }
+
+    let message = Pubkey::new_unique();
+    program_test.add_account(message, program_account(hello_world_id, MESSAGE_ACCOUNT_SIZE));
+
+    let zero_copy_message = Pubkey::new_unique();
+    program_test.add_account(zero_copy_message, program_account(zero_copy_id, ZeroCopyMessageAccount::LEN));
+
+    let context = program_test.start_with_context().await;
+    let payer = context.payer.pubkey();
+    let mut bench = Bench {
+        context,
+        measurements: Vec::new(),
+    };
+
+    // counter
+    bench.measure("counter", "Initialize", initialize_counter(&counter_id, &counter, &payer), &[]).await;
+    bench.measure("counter", "Increment", increment(&counter_id, &counter, &payer), &[]).await;
+    bench.measure("counter", "Decrement", decrement(&counter_id, &counter, &payer), &[]).await;
+
+    // crowdfund: a campaign that misses its goal, so both refund paths run
+    let duration_slots = 10;
+    let ix = initialize_campaign(&crowdfund_id, &campaign, &payer, WALLET_LAMPORTS, duration_slots);
+    bench.measure("crowdfund", "Initialize", ix, &[]).await;
+    for (contributor, name) in contributors.iter().zip(["Contribute", "Contribute (second contributor)"]) {
+        let ix = contribute(&crowdfund_id, &campaign, &contributor.pubkey(), 1_000, None);
+        bench.measure("crowdfund", name, ix, &[contributor]).await;
+    }
+    let slot = bench.context.banks_client.get_root_slot().await.unwrap();
+    bench.context.warp_to_slot(slot + duration_slots + 1).unwrap();
+    let ix = refund(&crowdfund_id, &campaign, &contributors[0].pubkey());
+    bench.measure("crowdfund", "Refund", ix, &[&contributors[0]]).await;
+    let ix = batch_refund(&crowdfund_id, &campaign, &[contributors[1].pubkey()]);
+    bench.measure("crowdfund", "BatchRefund", ix, &[]).await;
+
+    // hello-world; the cooldown goes last since it adds accounts to SetMessage
+    let options = SetMessageOptions::default();
+    let ix = set_message(&hello_world_id, &message, &payer, "Hello, Solana!", &options);
+    bench.measure("hello-world", "SetMessage (initialize)", ix, &[]).await;
+    let ix = set_message(&hello_world_id, &message, &payer, "Hello again, Solana!", &options);
+    bench.measure("hello-world", "SetMessage (update)", ix, &[]).await;
+    bench.measure("hello-world", "GetMessage", get_message(&hello_world_id, &message), &[]).await;
+    let ix = reply(&hello_world_id, &message, 0, &payer, "gm");
+    bench.measure("hello-world", "Reply", ix, &[]).await;
+    let ix = add_writer(&hello_world_id, &message, &payer, &Pubkey::new_unique());
+    bench.measure("hello-world", "AddWriter", ix, &[]).await;
+    let ix = set_cooldown(&hello_world_id, &message, &payer, 1);
+    bench.measure("hello-world", "SetCooldown", ix, &[]).await;
+
+    // hello-world-zero-copy
+    let ix = hello_world_zero_copy::set_message(&zero_copy_id, &zero_copy_message, &payer, "Hello, Solana!");
+    bench.measure("hello-world-zero-copy", "SetMessage", ix, &[]).await;
+    let ix = hello_world_zero_copy::get_message(&zero_copy_id, &zero_copy_message);
+    bench.measure("hello-world-zero-copy", "GetMessage", ix, &[]).await;
+
+    bench.measurements
+}
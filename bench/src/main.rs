@@ -0,0 +1,47 @@
+//! Compute-unit report: runs each program's key instructions under
+//! solana-program-test against the SBF builds and records the compute units
+//! every instruction consumed.
+//!
+//! Build the programs first; solana-program-test loads them from `SBF_OUT_DIR`:
+//!
+//! ```bash
+//! cargo build-sbf
+//! SBF_OUT_DIR=$PWD/target/deploy cargo run -p bench --bin cu-report -- target/cu-report.json
+//! ```
+//!
+//! The report is a JSON array of `{ "program", "instruction", "compute_units" }`
+//! objects, written to the path given (default `cu-report.json`); a Markdown
+//! table of the same numbers, next to each instruction's ceiling from
+//! `bench::CEILINGS`, is printed to stdout.
+
+use bench::{ceiling, measure_all};
+
+#[tokio::main]
+async fn main() {
+    let path = std::env::args().nth(1).unwrap_or_else(|| "cu-report.json".to_string());
+    let measurements = measure_all().await;
+
+    println!("| Program | Instruction | CU | Ceiling |");
+    println!("|---------|-------------|----|---------|");
+    for measurement in &measurements {
+        let ceiling = ceiling(measurement.program, measurement.instruction)
+            .map_or_else(|| "-".to_string(), |ceiling| ceiling.to_string());
+        println!(
+            "| {} | {} | {} | {} |",
+            measurement.program, measurement.instruction, measurement.compute_units, ceiling
+        );
+    }
+
+    let report: Vec<_> = measurements
+        .iter()
+        .map(|measurement| {
+            serde_json::json!({
+                "program": measurement.program,
+                "instruction": measurement.instruction,
+                "compute_units": measurement.compute_units,
+            })
+        })
+        .collect();
+    std::fs::write(&path, serde_json::to_string_pretty(&report).unwrap() + "\n").unwrap();
+    println!("\nWrote {}", path);
+}
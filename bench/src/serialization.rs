@@ -0,0 +1,172 @@
+//! Serialization report: for each fixed-size account type, compares the borsh
+//! layout the programs store with the `Pod` layout from the programs'
+//! `zero-copy` feature. Prints a Markdown table of the size of each layout
+//! and the host time of one read-modify-write of its account data:
+//! deserialize, update, serialize for borsh, and borrow in place, update for
+//! `Pod`.
+//!
+//! ```bash
+//! cargo run --release -p bench --bin serialization-report
+//! ```
+
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use borsh::BorshSerialize;
+use common::{load_account, load_versioned, Discriminator, Versioned};
+use smart_contract_comparison::{
+    counter::{zero_copy::CounterAccountPod, CounterAccount},
+    crowdfund::{
+        zero_copy::{ContributorAccountPod, CrowdfundAccountPod},
+        ContributorAccount, CrowdfundAccount,
+    },
+    hello_world::{zero_copy::UpdaterCooldownPod, UpdaterCooldown},
+};
+use solana_sdk::pubkey::Pubkey;
+
+const ITERATIONS: u32 = 1_000_000;
+
+/// Zeroed account data of `len` bytes, backed by u64s so it is 8-byte
+/// aligned like on-chain data
+fn account_data(len: usize) -> Vec<u64> {
+    vec![0; len.div_ceil(8)]
+}
+
+/// Average time of `update` over `ITERATIONS` runs
+fn time(mut update: impl FnMut()) -> Duration {
+    let started = Instant::now();
+    for _ in 0..ITERATIONS {
+        update();
+    }
+    started.elapsed() / ITERATIONS
+}
+
+struct Row {
+    account: &'static str,
+    borsh_len: usize,
+    pod_len: usize,
+    borsh: Duration,
+    pod: Duration,
+}
+
+fn counter() -> Row {
+    let state = CounterAccount {
+        discriminator: CounterAccount::DISCRIMINATOR,
+        version: CounterAccount::VERSION,
+        is_initialized: true,
+        count: 0,
+        owner: Pubkey::new_unique(),
+    };
+
+    let mut borsh_backing = account_data(CounterAccount::LEN);
+    let borsh_data: &mut [u8] = bytemuck::cast_slice_mut(&mut borsh_backing);
+    state.serialize(&mut &mut borsh_data[..]).unwrap();
+    let borsh = time(|| {
+        let mut counter: CounterAccount = load_versioned(black_box(&borsh_data[..])).unwrap();
+        counter.count += 1;
+        counter.serialize(&mut &mut borsh_data[..]).unwrap();
+    });
+
+    let mut pod_backing = account_data(CounterAccountPod::LEN);
+    let pod_data: &mut [u8] = bytemuck::cast_slice_mut(&mut pod_backing);
+    pod_data.copy_from_slice(bytemuck::bytes_of(&CounterAccountPod::from(&state)));
+    let pod = time(|| CounterAccountPod::load_mut(black_box(&mut pod_data[..])).unwrap().count += 1);
+
+    Row { account: "CounterAccount", borsh_len: CounterAccount::LEN, pod_len: CounterAccountPod::LEN, borsh, pod }
+}
+
+fn campaign() -> Row {
+    let state = CrowdfundAccount {
+        discriminator: CrowdfundAccount::DISCRIMINATOR,
+        version: CrowdfundAccount::VERSION,
+        is_initialized: true,
+        owner: Pubkey::new_unique(),
+        goal: u64::MAX,
+        deadline: 1_000,
+        total_raised: 0,
+        finalized: false,
+        allowlist: Some(Pubkey::new_unique()),
+    };
+
+    let mut borsh_backing = account_data(CrowdfundAccount::LEN);
+    let borsh_data: &mut [u8] = bytemuck::cast_slice_mut(&mut borsh_backing);
+    state.serialize(&mut &mut borsh_data[..]).unwrap();
+    let borsh = time(|| {
+        let mut campaign: CrowdfundAccount = load_versioned(black_box(&borsh_data[..])).unwrap();
+        campaign.total_raised += 1;
+        campaign.serialize(&mut &mut borsh_data[..]).unwrap();
+    });
+
+    let mut pod_backing = account_data(CrowdfundAccountPod::LEN);
+    let pod_data: &mut [u8] = bytemuck::cast_slice_mut(&mut pod_backing);
+    pod_data.copy_from_slice(bytemuck::bytes_of(&CrowdfundAccountPod::from(&state)));
+    let pod = time(|| CrowdfundAccountPod::load_mut(black_box(&mut pod_data[..])).unwrap().total_raised += 1);
+
+    Row {
+        account: "CrowdfundAccount",
+        borsh_len: CrowdfundAccount::LEN,
+        pod_len: CrowdfundAccountPod::LEN,
+        borsh,
+        pod,
+    }
+}
+
+fn contributor() -> Row {
+    let state = ContributorAccount { discriminator: ContributorAccount::DISCRIMINATOR, amount: 0 };
+
+    let mut borsh_backing = account_data(ContributorAccount::LEN);
+    let borsh_data: &mut [u8] = bytemuck::cast_slice_mut(&mut borsh_backing);
+    state.serialize(&mut &mut borsh_data[..]).unwrap();
+    let borsh = time(|| {
+        let mut record: ContributorAccount = load_account(black_box(&borsh_data[..])).unwrap();
+        record.amount += 1;
+        record.serialize(&mut &mut borsh_data[..]).unwrap();
+    });
+
+    let mut pod_backing = account_data(ContributorAccountPod::LEN);
+    let pod_data: &mut [u8] = bytemuck::cast_slice_mut(&mut pod_backing);
+    pod_data.copy_from_slice(bytemuck::bytes_of(&ContributorAccountPod::from(&state)));
+    let pod = time(|| ContributorAccountPod::load_mut(black_box(&mut pod_data[..])).unwrap().amount += 1);
+
+    Row {
+        account: "ContributorAccount",
+        borsh_len: ContributorAccount::LEN,
+        pod_len: ContributorAccountPod::LEN,
+        borsh,
+        pod,
+    }
+}
+
+fn cooldown() -> Row {
+    let state = UpdaterCooldown { discriminator: UpdaterCooldown::DISCRIMINATOR, last_update_slot: 0 };
+    let borsh_len = state.try_to_vec().unwrap().len();
+
+    let mut borsh_backing = account_data(borsh_len);
+    let borsh_data: &mut [u8] = bytemuck::cast_slice_mut(&mut borsh_backing);
+    state.serialize(&mut &mut borsh_data[..]).unwrap();
+    let borsh = time(|| {
+        let mut cooldown: UpdaterCooldown = load_account(black_box(&borsh_data[..])).unwrap();
+        cooldown.last_update_slot += 1;
+        cooldown.serialize(&mut &mut borsh_data[..]).unwrap();
+    });
+
+    let mut pod_backing = account_data(UpdaterCooldownPod::LEN);
+    let pod_data: &mut [u8] = bytemuck::cast_slice_mut(&mut pod_backing);
+    pod_data.copy_from_slice(bytemuck::bytes_of(&UpdaterCooldownPod::from(&state)));
+    let pod = time(|| UpdaterCooldownPod::load_mut(black_box(&mut pod_data[..])).unwrap().last_update_slot += 1);
+
+    Row { account: "UpdaterCooldown", borsh_len, pod_len: UpdaterCooldownPod::LEN, borsh, pod }
+}
+
+fn main() {
+    println!("| Account | Borsh bytes | Pod bytes | Borsh read-modify-write | Pod read-modify-write |");
+    println!("|---|---|---|---|---|");
+    for row in [counter(), campaign(), contributor(), cooldown()] {
+        println!(
+            "| {} | {} | {} | {:?} | {:?} |",
+            row.account, row.borsh_len, row.pod_len, row.borsh, row.pod
+        );
+    }
+}
@@ -0,0 +1,32 @@
+//! Fails when an instruction's compute units exceed its ceiling in
+//! `bench::CEILINGS`. Needs the SBF builds, so it is ignored by default and
+//! run by CI's `compute-units` job:
+//!
+//! ```bash
+//! cargo build-sbf
+//! SBF_OUT_DIR=$PWD/target/deploy cargo test -p bench --test cu_ceilings -- --ignored
+//! ```
+
+use bench::{ceiling, measure_all, CEILINGS};
+
+#[tokio::test]
+#[ignore = "requires SBF builds of the programs"]
+async fn test_compute_units_under_ceilings() {
+    let measurements = measure_all().await;
+
+    let mut over = Vec::new();
+    for measurement in &measurements {
+        let ceiling = ceiling(measurement.program, measurement.instruction)
+            .unwrap_or_else(|| panic!("no ceiling for {} {}", measurement.program, measurement.instruction));
+        if measurement.compute_units > ceiling {
+            over.push(format!(
+                "{} {}: {} CU, ceiling {}",
+                measurement.program, measurement.instruction, measurement.compute_units, ceiling
+            ));
+        }
+    }
+    assert!(over.is_empty(), "over their compute-unit ceilings:\n{}", over.join("\n"));
+
+    // A ceiling left behind after its instruction was dropped from the run
+    assert_eq!(measurements.len(), CEILINGS.len());
+}
@@ -0,0 +1,128 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+/**
+ * @title Blog
+ * @dev Authors, posts and comments linked by IDs. Posts and comments can be
+ * edited for a limited time; comments can be deleted by their commenter or by
+ * the post's author, and a post only once its comments are gone.
+ */
+contract Blog {
+    uint256 public constant MAX_NAME_LENGTH = 32;
+    uint256 public constant MAX_TITLE_LENGTH = 64;
+    uint256 public constant MAX_POST_LENGTH = 1000;
+    uint256 public constant MAX_COMMENT_LENGTH = 280;
+    uint256 public constant POST_EDIT_WINDOW = 1 days;
+    uint256 public constant COMMENT_EDIT_WINDOW = 15 minutes;
+
+    struct Post {
+        address author;
+        string title;
+        string body;
+        uint256 createdAt;
+        uint256 updatedAt;
+        uint256 liveComments;
+        bool exists;
+    }
+
+    struct Comment {
+        uint256 postId;
+        address commenter;
+        string body;
+        uint256 createdAt;
+        uint256 updatedAt;
+        bool exists;
+    }
+
+    mapping(address => string) public profiles;
+    mapping(uint256 => Post) public posts;
+    mapping(uint256 => Comment) public comments;
+    uint256 public postCount;
+    uint256 public commentCount;
+
+    event ProfileCreated(address indexed author, string name);
+    event PostCreated(uint256 indexed postId, address indexed author, string title);
+    event PostEdited(uint256 indexed postId);
+    event PostDeleted(uint256 indexed postId);
+    event CommentAdded(uint256 indexed commentId, uint256 indexed postId, address indexed commenter);
+    event CommentEdited(uint256 indexed commentId);
+    event CommentDeleted(uint256 indexed commentId);
+
+    modifier validLength(string calldata text, uint256 max) {
+        require(bytes(text).length > 0 && bytes(text).length <= max, "Invalid length");
+        _;
+    }
+
+    function createProfile(string calldata name) external validLength(name, MAX_NAME_LENGTH) {
+        require(bytes(profiles[msg.sender]).length == 0, "Profile exists");
+        profiles[msg.sender] = name;
+        emit ProfileCreated(msg.sender, name);
+    }
+
+    function createPost(string calldata title, string calldata body)
+        external
+        validLength(title, MAX_TITLE_LENGTH)
+        validLength(body, MAX_POST_LENGTH)
+        returns (uint256 postId)
+    {
+        require(bytes(profiles[msg.sender]).length > 0, "No profile");
+        postId = postCount++;
+        posts[postId] = Post(msg.sender, title, body, block.timestamp, block.timestamp, 0, true);
+        emit PostCreated(postId, msg.sender, title);
+    }
+
+    function editPost(uint256 postId, string calldata title, string calldata body)
+        external
+        validLength(title, MAX_TITLE_LENGTH)
+        validLength(body, MAX_POST_LENGTH)
+    {
+        Post storage post = posts[postId];
+        require(post.exists && post.author == msg.sender, "Not authorized");
+        require(block.timestamp < post.createdAt + POST_EDIT_WINDOW, "Edit window closed");
+        post.title = title;
+        post.body = body;
+        post.updatedAt = block.timestamp;
+        emit PostEdited(postId);
+    }
+
+    function deletePost(uint256 postId) external {
+        Post storage post = posts[postId];
+        require(post.exists && post.author == msg.sender, "Not authorized");
+        require(post.liveComments == 0, "Post has comments");
+        delete posts[postId];
+        emit PostDeleted(postId);
+    }
+
+    function addComment(uint256 postId, string calldata body)
+        external
+        validLength(body, MAX_COMMENT_LENGTH)
+        returns (uint256 commentId)
+    {
+        require(posts[postId].exists, "No such post");
+        commentId = commentCount++;
+        comments[commentId] = Comment(postId, msg.sender, body, block.timestamp, block.timestamp, true);
+        posts[postId].liveComments++;
+        emit CommentAdded(commentId, postId, msg.sender);
+    }
+
+    function editComment(uint256 commentId, string calldata body) external validLength(body, MAX_COMMENT_LENGTH) {
+        Comment storage comment = comments[commentId];
+        require(comment.exists && comment.commenter == msg.sender, "Not authorized");
+        require(block.timestamp < comment.createdAt + COMMENT_EDIT_WINDOW, "Edit window closed");
+        comment.body = body;
+        comment.updatedAt = block.timestamp;
+        emit CommentEdited(commentId);
+    }
+
+    function deleteComment(uint256 commentId) external {
+        Comment storage comment = comments[commentId];
+        require(comment.exists, "No such comment");
+        require(
+            msg.sender == comment.commenter || msg.sender == posts[comment.postId].author,
+            "Not authorized"
+        );
+        posts[comment.postId].liveComments--;
+        delete comments[commentId];
+        emit CommentDeleted(commentId);
+    }
+}
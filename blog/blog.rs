@@ -0,0 +1,654 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint,
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// Seed prefix for author profile PDAs: ["profile", author]
+pub const PROFILE_SEED: &[u8] = b"profile";
+
+/// Seed prefix for post PDAs: ["post", author, post_index (u64 LE)]
+pub const POST_SEED: &[u8] = b"post";
+
+/// Seed prefix for comment PDAs: ["comment", post, comment_index (u64 LE)]
+pub const COMMENT_SEED: &[u8] = b"comment";
+
+pub const MAX_NAME_LENGTH: usize = 32;
+pub const MAX_TITLE_LENGTH: usize = 64;
+pub const MAX_POST_LENGTH: usize = 1_000;
+pub const MAX_COMMENT_LENGTH: usize = 280;
+
+/// How long after publishing a post can still be edited
+pub const POST_EDIT_WINDOW_SECONDS: i64 = 86_400;
+
+/// How long after posting a comment can still be edited
+pub const COMMENT_EDIT_WINDOW_SECONDS: i64 = 900;
+
+/// Size of a `ProfileAccount` with a name of the maximum length
+pub const PROFILE_ACCOUNT_SIZE: usize = 1 // is_initialized
+    + 32 // author
+    + 4 + MAX_NAME_LENGTH // name
+    + 8 // post_count
+    + 1; // bump
+
+/// Size of a `PostAccount` with a title and body of the maximum length
+pub const POST_ACCOUNT_SIZE: usize = 1 // is_initialized
+    + 32 // author
+    + 8 // index
+    + 4 + MAX_TITLE_LENGTH // title
+    + 4 + MAX_POST_LENGTH // body
+    + 8 // created_at
+    + 8 // updated_at
+    + 8 // comment_count
+    + 8 // live_comments
+    + 1; // bump
+
+/// Size of a `CommentAccount` with a body of the maximum length
+pub const COMMENT_ACCOUNT_SIZE: usize = 1 // is_initialized
+    + 32 // post
+    + 32 // commenter
+    + 8 // index
+    + 4 + MAX_COMMENT_LENGTH // body
+    + 8 // created_at
+    + 8 // updated_at
+    + 1; // bump
+
+/// Errors returned by the program as `ProgramError::Custom` codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlogError {
+    /// The signer may not change this profile, post or comment
+    Unauthorized = 0,
+    /// The account has not been initialized
+    NotInitialized = 1,
+    /// A name, title or body is empty or too long
+    InvalidLength = 2,
+    /// The edit window has passed
+    EditWindowClosed = 3,
+    /// A post cannot be deleted while it has comments
+    PostHasComments = 4,
+    /// The comment does not belong to this post
+    WrongPost = 5,
+}
+
+impl From<BlogError> for ProgramError {
+    fn from(e: BlogError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+/// An author; `post_count` numbers the author's posts
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct ProfileAccount {
+    pub is_initialized: bool,
+    pub author: Pubkey,
+    pub name: String,
+    /// Index of the next post. Deleted posts keep their index, so this only grows
+    pub post_count: u64,
+    pub bump: u8,
+}
+
+/// A post, linked to its author by its seeds
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct PostAccount {
+    pub is_initialized: bool,
+    pub author: Pubkey,
+    pub index: u64,
+    pub title: String,
+    pub body: String,
+    pub created_at: i64,
+    pub updated_at: i64,
+    /// Index of the next comment; only grows
+    pub comment_count: u64,
+    /// Comments not yet deleted
+    pub live_comments: u64,
+    pub bump: u8,
+}
+
+/// A comment, linked to its post by its seeds and the `post` field
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct CommentAccount {
+    pub is_initialized: bool,
+    pub post: Pubkey,
+    /// Paid the rent, so receives it back on deletion
+    pub commenter: Pubkey,
+    pub index: u64,
+    pub body: String,
+    pub created_at: i64,
+    pub updated_at: i64,
+    pub bump: u8,
+}
+
+/// Whether something created at `created_at` can still be edited at `now`
+pub fn within_edit_window(created_at: i64, now: i64, window_seconds: i64) -> bool {
+    now < created_at.saturating_add(window_seconds)
+}
+
+pub fn post_address(program_id: &Pubkey, author: &Pubkey, index: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[POST_SEED, author.as_ref(), &index.to_le_bytes()], program_id)
+}
+
+pub fn comment_address(program_id: &Pubkey, post: &Pubkey, index: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[COMMENT_SEED, post.as_ref(), &index.to_le_bytes()], program_id)
+}
+
+fn check_length(text: &str, max: usize) -> Result<(), BlogError> {
+    if text.is_empty() || text.len() > max {
+        msg!("Text must be 1-{} bytes, got {}", max, text.len());
+        return Err(BlogError::InvalidLength);
+    }
+    Ok(())
+}
+
+entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = BlogInstruction::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        BlogInstruction::CreateProfile { name } => create_profile(program_id, accounts, name),
+        BlogInstruction::CreatePost { title, body } => create_post(program_id, accounts, title, body),
+        BlogInstruction::EditPost { title, body } => edit_post(program_id, accounts, title, body),
+        BlogInstruction::DeletePost => delete_post(program_id, accounts),
+        BlogInstruction::AddComment { body } => add_comment(program_id, accounts, body),
+        BlogInstruction::EditComment { body } => edit_comment(program_id, accounts, body),
+        BlogInstruction::DeleteComment => delete_comment(program_id, accounts),
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub enum BlogInstruction {
+    /// Create the signer's author profile
+    /// Accounts: [writable, signer] author, [writable] profile PDA, [] system program
+    CreateProfile { name: String },
+    /// Publish a post at the profile's next post index
+    /// Accounts: [writable, signer] author, [writable] profile PDA, [writable] post PDA,
+    /// [] system program
+    CreatePost { title: String, body: String },
+    /// Replace a post's title and body within `POST_EDIT_WINDOW_SECONDS`
+    /// Accounts: [signer] author, [writable] post PDA
+    EditPost { title: String, body: String },
+    /// Delete a post with no remaining comments, refunding its rent
+    /// Accounts: [writable, signer] author, [writable] post PDA
+    DeletePost,
+    /// Comment on a post at its next comment index
+    /// Accounts: [writable, signer] commenter, [writable] post PDA, [writable] comment PDA,
+    /// [] system program
+    AddComment { body: String },
+    /// Replace a comment's body within `COMMENT_EDIT_WINDOW_SECONDS`
+    /// Accounts: [signer] commenter, [writable] comment PDA
+    EditComment { body: String },
+    /// Delete a comment, as its commenter or the post's author (moderation);
+    /// the rent goes back to the commenter
+    /// Accounts: [signer] commenter or post author, [writable] post PDA, [writable] comment PDA,
+    /// [writable] commenter
+    DeleteComment,
+}
+
+fn create_profile(program_id: &Pubkey, accounts: &[AccountInfo], name: String) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let author = next_account_info(accounts_iter)?;
+    let profile_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !author.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    check_length(&name, MAX_NAME_LENGTH)?;
+
+    let (expected_profile, bump) =
+        Pubkey::find_program_address(&[PROFILE_SEED, author.key.as_ref()], program_id);
+    if expected_profile != *profile_account.key {
+        msg!("Profile account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    invoke_signed(
+        &system_instruction::create_account(
+            author.key,
+            profile_account.key,
+            Rent::get()?.minimum_balance(PROFILE_ACCOUNT_SIZE),
+            PROFILE_ACCOUNT_SIZE as u64,
+            program_id,
+        ),
+        &[author.clone(), profile_account.clone(), system_program.clone()],
+        &[&[PROFILE_SEED, author.key.as_ref(), &[bump]]],
+    )?;
+
+    let profile = ProfileAccount {
+        is_initialized: true,
+        author: *author.key,
+        name,
+        post_count: 0,
+        bump,
+    };
+    profile.serialize(&mut &mut profile_account.data.borrow_mut()[..])?;
+
+    msg!("Profile created for {}", author.key);
+
+    Ok(())
+}
+
+fn create_post(program_id: &Pubkey, accounts: &[AccountInfo], title: String, body: String) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let author = next_account_info(accounts_iter)?;
+    let profile_account = next_account_info(accounts_iter)?;
+    let post_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !author.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    check_length(&title, MAX_TITLE_LENGTH)?;
+    check_length(&body, MAX_POST_LENGTH)?;
+
+    if profile_account.owner != program_id {
+        msg!("Profile account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let mut profile = ProfileAccount::deserialize(&mut &profile_account.data.borrow()[..])?;
+    if !profile.is_initialized {
+        return Err(BlogError::NotInitialized.into());
+    }
+
+    if profile.author != *author.key {
+        msg!("Only the profile's author can post");
+        return Err(BlogError::Unauthorized.into());
+    }
+
+    let index = profile.post_count;
+    let (expected_post, bump) = post_address(program_id, author.key, index);
+    if expected_post != *post_account.key {
+        msg!("Post account does not match post index {}", index);
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    invoke_signed(
+        &system_instruction::create_account(
+            author.key,
+            post_account.key,
+            Rent::get()?.minimum_balance(POST_ACCOUNT_SIZE),
+            POST_ACCOUNT_SIZE as u64,
+            program_id,
+        ),
+        &[author.clone(), post_account.clone(), system_program.clone()],
+        &[&[POST_SEED, author.key.as_ref(), &index.to_le_bytes(), &[bump]]],
+    )?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let post = PostAccount {
+        is_initialized: true,
+        author: *author.key,
+        index,
+        title,
+        body,
+        created_at: now,
+        updated_at: now,
+        comment_count: 0,
+        live_comments: 0,
+        bump,
+    };
+    post.serialize(&mut &mut post_account.data.borrow_mut()[..])?;
+
+    profile.post_count = index.checked_add(1).ok_or(ProgramError::ArithmeticOverflow)?;
+    profile.serialize(&mut &mut profile_account.data.borrow_mut()[..])?;
+
+    msg!("Post {} published: {}", index, post.title);
+
+    Ok(())
+}
+
+fn edit_post(program_id: &Pubkey, accounts: &[AccountInfo], title: String, body: String) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let author = next_account_info(accounts_iter)?;
+    let post_account = next_account_info(accounts_iter)?;
+
+    check_length(&title, MAX_TITLE_LENGTH)?;
+    check_length(&body, MAX_POST_LENGTH)?;
+
+    let mut post = load_post_as_author(program_id, post_account, author)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    if !within_edit_window(post.created_at, now, POST_EDIT_WINDOW_SECONDS) {
+        msg!("Posts can only be edited for {} seconds", POST_EDIT_WINDOW_SECONDS);
+        return Err(BlogError::EditWindowClosed.into());
+    }
+
+    post.title = title;
+    post.body = body;
+    post.updated_at = now;
+    post.serialize(&mut &mut post_account.data.borrow_mut()[..])?;
+
+    msg!("Post {} edited", post.index);
+
+    Ok(())
+}
+
+fn delete_post(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let author = next_account_info(accounts_iter)?;
+    let post_account = next_account_info(accounts_iter)?;
+
+    let post = load_post_as_author(program_id, post_account, author)?;
+
+    // Comments point at the post; deleting it first would orphan them
+    if post.live_comments > 0 {
+        msg!("Post still has {} comments", post.live_comments);
+        return Err(BlogError::PostHasComments.into());
+    }
+
+    close_account(post_account, author)?;
+
+    msg!("Post {} deleted", post.index);
+
+    Ok(())
+}
+
+fn add_comment(program_id: &Pubkey, accounts: &[AccountInfo], body: String) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let commenter = next_account_info(accounts_iter)?;
+    let post_account = next_account_info(accounts_iter)?;
+    let comment_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !commenter.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    check_length(&body, MAX_COMMENT_LENGTH)?;
+
+    let mut post = load_post(program_id, post_account)?;
+
+    let index = post.comment_count;
+    let (expected_comment, bump) = comment_address(program_id, post_account.key, index);
+    if expected_comment != *comment_account.key {
+        msg!("Comment account does not match comment index {}", index);
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    invoke_signed(
+        &system_instruction::create_account(
+            commenter.key,
+            comment_account.key,
+            Rent::get()?.minimum_balance(COMMENT_ACCOUNT_SIZE),
+            COMMENT_ACCOUNT_SIZE as u64,
+            program_id,
+        ),
+        &[commenter.clone(), comment_account.clone(), system_program.clone()],
+        &[&[COMMENT_SEED, post_account.key.as_ref(), &index.to_le_bytes(), &[bump]]],
+    )?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let comment = CommentAccount {
+        is_initialized: true,
+        post: *post_account.key,
+        commenter: *commenter.key,
+        index,
+        body,
+        created_at: now,
+        updated_at: now,
+        bump,
+    };
+    comment.serialize(&mut &mut comment_account.data.borrow_mut()[..])?;
+
+    post.comment_count = index.checked_add(1).ok_or(ProgramError::ArithmeticOverflow)?;
+    post.live_comments = post.live_comments.checked_add(1).ok_or(ProgramError::ArithmeticOverflow)?;
+    post.serialize(&mut &mut post_account.data.borrow_mut()[..])?;
+
+    msg!("Comment {} added to post {}", index, post.index);
+
+    Ok(())
+}
+
+fn edit_comment(program_id: &Pubkey, accounts: &[AccountInfo], body: String) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let commenter = next_account_info(accounts_iter)?;
+    let comment_account = next_account_info(accounts_iter)?;
+
+    if !commenter.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    check_length(&body, MAX_COMMENT_LENGTH)?;
+
+    let mut comment = load_comment(program_id, comment_account)?;
+    if comment.commenter != *commenter.key {
+        msg!("Only the commenter can edit a comment");
+        return Err(BlogError::Unauthorized.into());
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    if !within_edit_window(comment.created_at, now, COMMENT_EDIT_WINDOW_SECONDS) {
+        msg!("Comments can only be edited for {} seconds", COMMENT_EDIT_WINDOW_SECONDS);
+        return Err(BlogError::EditWindowClosed.into());
+    }
+
+    comment.body = body;
+    comment.updated_at = now;
+    comment.serialize(&mut &mut comment_account.data.borrow_mut()[..])?;
+
+    msg!("Comment {} edited", comment.index);
+
+    Ok(())
+}
+
+fn delete_comment(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let signer = next_account_info(accounts_iter)?;
+    let post_account = next_account_info(accounts_iter)?;
+    let comment_account = next_account_info(accounts_iter)?;
+    let commenter = next_account_info(accounts_iter)?;
+
+    if !signer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut post = load_post(program_id, post_account)?;
+    let comment = load_comment(program_id, comment_account)?;
+
+    if comment.post != *post_account.key {
+        return Err(BlogError::WrongPost.into());
+    }
+
+    if *signer.key != comment.commenter && *signer.key != post.author {
+        msg!("Only the commenter or the post's author can delete a comment");
+        return Err(BlogError::Unauthorized.into());
+    }
+
+    // The commenter paid the rent, so the refund goes to them even when the author moderates
+    if *commenter.key != comment.commenter {
+        msg!("Rent must be refunded to the commenter");
+        return Err(BlogError::Unauthorized.into());
+    }
+
+    close_account(comment_account, commenter)?;
+
+    post.live_comments = post.live_comments.saturating_sub(1);
+    post.serialize(&mut &mut post_account.data.borrow_mut()[..])?;
+
+    msg!("Comment {} deleted from post {}", comment.index, post.index);
+
+    Ok(())
+}
+
+/// Move all of `account`'s lamports to `destination` and clear its data
+fn close_account(account: &AccountInfo, destination: &AccountInfo) -> ProgramResult {
+    let lamports = account.lamports();
+    **account.try_borrow_mut_lamports()? = 0;
+    **destination.try_borrow_mut_lamports()? = destination
+        .lamports()
+        .checked_add(lamports)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    account.data.borrow_mut().fill(0);
+
+    Ok(())
+}
+
+// Strings make the encodings shorter than the fixed-size accounts, so these use `deserialize`
+
+fn load_post(program_id: &Pubkey, post_account: &AccountInfo) -> Result<PostAccount, ProgramError> {
+    if post_account.owner != program_id {
+        msg!("Post account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let post = PostAccount::deserialize(&mut &post_account.data.borrow()[..])?;
+    if !post.is_initialized {
+        return Err(BlogError::NotInitialized.into());
+    }
+
+    Ok(post)
+}
+
+fn load_post_as_author(
+    program_id: &Pubkey,
+    post_account: &AccountInfo,
+    author: &AccountInfo,
+) -> Result<PostAccount, ProgramError> {
+    if !author.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let post = load_post(program_id, post_account)?;
+
+    if post.author != *author.key {
+        msg!("Only the post's author can change it");
+        return Err(BlogError::Unauthorized.into());
+    }
+
+    Ok(post)
+}
+
+fn load_comment(program_id: &Pubkey, comment_account: &AccountInfo) -> Result<CommentAccount, ProgramError> {
+    if comment_account.owner != program_id {
+        msg!("Comment account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let comment = CommentAccount::deserialize(&mut &comment_account.data.borrow()[..])?;
+    if !comment.is_initialized {
+        return Err(BlogError::NotInitialized.into());
+    }
+
+    Ok(comment)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_program::clock::Epoch;
+
+    fn new_post(author: Pubkey, live_comments: u64) -> PostAccount {
+        PostAccount {
+            is_initialized: true,
+            author,
+            index: 0,
+            title: "t".repeat(MAX_TITLE_LENGTH),
+            body: "b".repeat(MAX_POST_LENGTH),
+            created_at: 1_700_000_000,
+            updated_at: 1_700_000_000,
+            comment_count: live_comments,
+            live_comments,
+            bump: 255,
+        }
+    }
+
+    #[test]
+    fn test_edit_window_and_sizes() {
+        let created = 1_700_000_000;
+        assert!(within_edit_window(created, created, COMMENT_EDIT_WINDOW_SECONDS));
+        assert!(within_edit_window(created, created + COMMENT_EDIT_WINDOW_SECONDS - 1, COMMENT_EDIT_WINDOW_SECONDS));
+        assert!(!within_edit_window(created, created + COMMENT_EDIT_WINDOW_SECONDS, COMMENT_EDIT_WINDOW_SECONDS));
+        assert!(within_edit_window(i64::MAX - 1, i64::MAX - 1, POST_EDIT_WINDOW_SECONDS));
+
+        // Fully populated accounts fit their allocated sizes
+        assert_eq!(new_post(Pubkey::new_unique(), 0).try_to_vec().unwrap().len(), POST_ACCOUNT_SIZE);
+        let comment = CommentAccount {
+            is_initialized: true,
+            post: Pubkey::new_unique(),
+            commenter: Pubkey::new_unique(),
+            index: 0,
+            body: "c".repeat(MAX_COMMENT_LENGTH),
+            created_at: created,
+            updated_at: created,
+            bump: 255,
+        };
+        assert_eq!(comment.try_to_vec().unwrap().len(), COMMENT_ACCOUNT_SIZE);
+
+        // Posts and comments link to their parents through their addresses
+        let program_id = Pubkey::new_unique();
+        let author = Pubkey::new_unique();
+        let (post, _) = post_address(&program_id, &author, 0);
+        assert_ne!(post, post_address(&program_id, &author, 1).0);
+        assert_ne!(comment_address(&program_id, &post, 0).0, comment_address(&program_id, &author, 0).0);
+    }
+
+    #[test]
+    fn test_author_moderation_refunds_commenter() {
+        let program_id = Pubkey::new_unique();
+        let author = Pubkey::new_unique();
+        let commenter = Pubkey::new_unique();
+        let post_key = Pubkey::new_unique();
+        let comment_key = Pubkey::new_unique();
+
+        let comment = CommentAccount {
+            is_initialized: true,
+            post: post_key,
+            commenter,
+            index: 0,
+            body: "first!".to_string(),
+            created_at: 1_700_000_000,
+            updated_at: 1_700_000_000,
+            bump: 255,
+        };
+
+        let keys = [author, post_key, comment_key, commenter];
+        let mut lamports: Vec<u64> = vec![10_000; keys.len()];
+        let mut data: Vec<Vec<u8>> = vec![vec![]; keys.len()];
+        data[1] = new_post(author, 1).try_to_vec().unwrap();
+        data[2] = comment.try_to_vec().unwrap();
+
+        let accounts: Vec<AccountInfo> = keys
+            .iter()
+            .zip(lamports.iter_mut())
+            .zip(data.iter_mut())
+            .enumerate()
+            .map(|(i, ((key, lamports), data))| {
+                AccountInfo::new(key, i == 0, true, lamports, data, &program_id, false, Epoch::default())
+            })
+            .collect();
+
+        // The post cannot be deleted while the comment exists
+        let delete_post = BlogInstruction::DeletePost.try_to_vec().unwrap();
+        let result = process_instruction(&program_id, &accounts[..2], &delete_post);
+        assert_eq!(result, Err(BlogError::PostHasComments.into()));
+
+        let instruction_data = BlogInstruction::DeleteComment.try_to_vec().unwrap();
+        process_instruction(&program_id, &accounts, &instruction_data).unwrap();
+
+        assert_eq!(accounts[2].lamports(), 0);
+        assert_eq!(accounts[3].lamports(), 20_000);
+        let post = PostAccount::deserialize(&mut &accounts[1].data.borrow()[..]).unwrap();
+        assert_eq!(post.live_comments, 0);
+        assert_eq!(post.comment_count, 1);
+
+        process_instruction(&program_id, &accounts[..2], &delete_post).unwrap();
+        assert_eq!(accounts[0].lamports(), 20_000);
+    }
+}
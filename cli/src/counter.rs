@@ -0,0 +1,78 @@
+//! `scc counter`: create a counter and move it up or down as its owner
+
+use clap::Subcommand;
+use client::counter::{decode_counter, decrement, increment, initialize_counter};
+use smart_contract_comparison::counter::CounterAccount;
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+use crate::{Config, Result};
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Deployed counter program
+    #[arg(long, default_value_t = smart_contract_comparison::counter::ID)]
+    pub program_id: Pubkey,
+
+    #[command(subcommand)]
+    pub command: Action,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Action {
+    /// Create a counter owned by the signer
+    Create,
+    /// Add one (owner only)
+    Inc {
+        #[arg(long)]
+        counter: Pubkey,
+    },
+    /// Subtract one (owner only)
+    Dec {
+        #[arg(long)]
+        counter: Pubkey,
+    },
+    /// Print the count and owner
+    Show {
+        #[arg(long)]
+        counter: Pubkey,
+    },
+}
+
+pub fn run(config: &Config, args: Args) -> Result<()> {
+    let program_id = &args.program_id;
+    let owner = config.payer.pubkey();
+
+    match args.command {
+        Action::Create => {
+            let counter = Keypair::new();
+            config.send(
+                &[
+                    config.create_account(&counter, CounterAccount::LEN, program_id)?,
+                    initialize_counter(program_id, &counter.pubkey(), &owner),
+                ],
+                &[&counter],
+            )?;
+            println!("Counter: {}", counter.pubkey());
+        }
+        Action::Inc { counter } => {
+            config.send(&[increment(program_id, &counter, &owner)], &[])?;
+            show(config, &counter)?;
+        }
+        Action::Dec { counter } => {
+            config.send(&[decrement(program_id, &counter, &owner)], &[])?;
+            show(config, &counter)?;
+        }
+        Action::Show { counter } => show(config, &counter)?,
+    }
+    Ok(())
+}
+
+fn show(config: &Config, counter: &Pubkey) -> Result<()> {
+    let state = decode_counter(&config.account_data(counter)?)?;
+    println!("Count: {}", state.count);
+    println!("Owner: {}", state.owner);
+    Ok(())
+}
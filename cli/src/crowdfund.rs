@@ -0,0 +1,114 @@
+//! `scc crowdfund`: run a campaign from creation to withdrawal or refunds
+
+use clap::Subcommand;
+use client::crowdfund::{
+    contribute, contributor_record_address, decode_campaign, decode_contributor, initialize_campaign, refund,
+    withdraw,
+};
+use smart_contract_comparison::crowdfund::CrowdfundAccount;
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+use crate::{Config, Result};
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Deployed crowdfund program
+    #[arg(long, default_value_t = smart_contract_comparison::crowdfund::ID)]
+    pub program_id: Pubkey,
+
+    #[command(subcommand)]
+    pub command: Action,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Action {
+    /// Create a campaign owned by the signer
+    Init {
+        /// Lamports to raise
+        #[arg(long)]
+        goal: u64,
+        /// Slots from now until the deadline
+        #[arg(long)]
+        duration_slots: u64,
+    },
+    /// Contribute lamports before the deadline. The allowlist membership PDA
+    /// is added automatically when the campaign is in allowlist mode.
+    Contribute {
+        #[arg(long)]
+        campaign: Pubkey,
+        #[arg(long)]
+        amount: u64,
+    },
+    /// Withdraw the funds of a successful campaign (owner only)
+    Withdraw {
+        #[arg(long)]
+        campaign: Pubkey,
+    },
+    /// Reclaim the signer's contribution to a failed campaign
+    Refund {
+        #[arg(long)]
+        campaign: Pubkey,
+    },
+    /// Print the campaign and the signer's contribution
+    Show {
+        #[arg(long)]
+        campaign: Pubkey,
+    },
+}
+
+pub fn run(config: &Config, args: Args) -> Result<()> {
+    let program_id = &args.program_id;
+    let signer = config.payer.pubkey();
+
+    match args.command {
+        Action::Init { goal, duration_slots } => {
+            let campaign = Keypair::new();
+            config.send(
+                &[
+                    config.create_account(&campaign, CrowdfundAccount::LEN, program_id)?,
+                    initialize_campaign(program_id, &campaign.pubkey(), &signer, goal, duration_slots),
+                ],
+                &[&campaign],
+            )?;
+            println!("Campaign: {}", campaign.pubkey());
+        }
+        Action::Contribute { campaign, amount } => {
+            let state = decode_campaign(&config.account_data(&campaign)?)?;
+            config.send(&[contribute(program_id, &campaign, &signer, amount, state.allowlist.as_ref())], &[])?;
+            show(config, &campaign)?;
+        }
+        Action::Withdraw { campaign } => {
+            config.send(&[withdraw(program_id, &campaign, &signer)], &[])?;
+        }
+        Action::Refund { campaign } => {
+            config.send(&[refund(program_id, &campaign, &signer)], &[])?;
+        }
+        Action::Show { campaign } => show(config, &campaign)?,
+    }
+    Ok(())
+}
+
+fn show(config: &Config, campaign: &Pubkey) -> Result<()> {
+    let state = decode_campaign(&config.account_data(campaign)?)?;
+    let slot = config.rpc.get_slot()?;
+    println!("Owner: {}", state.owner);
+    println!("Raised: {} / {} lamports", state.total_raised, state.goal);
+    println!(
+        "Deadline: slot {} ({})",
+        state.deadline,
+        if slot < state.deadline { "active" } else { "ended" }
+    );
+    println!("Finalized: {}", state.finalized);
+    if let Some(list) = state.allowlist {
+        println!("Allowlist: {}", list);
+    }
+
+    let record = contributor_record_address(campaign, &config.payer.pubkey());
+    if let Ok(data) = config.account_data(&record) {
+        println!("Your contribution: {} lamports", decode_contributor(&data)?.amount);
+    }
+    Ok(())
+}
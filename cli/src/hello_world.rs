@@ -0,0 +1,120 @@
+//! `scc hello`: post, read and reply to messages
+
+use clap::Subcommand;
+use client::hello_world::{decode_message, reply, reply_address, set_message, SetMessageOptions};
+use smart_contract_comparison::hello_world::MESSAGE_ACCOUNT_SIZE;
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+use crate::{Config, Result};
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Deployed hello-world program
+    #[arg(long, default_value_t = smart_contract_comparison::hello_world::ID)]
+    pub program_id: Pubkey,
+
+    #[command(subcommand)]
+    pub command: Action,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Action {
+    /// Set the message of an account, creating a new one without `--account`
+    SetMessage {
+        message: String,
+        #[arg(long)]
+        account: Option<Pubkey>,
+        /// Expire the message this many slots from now
+        #[arg(long)]
+        ttl_slots: Option<u64>,
+        /// The signer's token account, for token-gated messages
+        #[arg(long)]
+        token_account: Option<Pubkey>,
+    },
+    /// Reply to a message
+    Reply {
+        message: String,
+        #[arg(long)]
+        parent: Pubkey,
+    },
+    /// Print a message account
+    Show {
+        #[arg(long)]
+        account: Pubkey,
+    },
+}
+
+pub fn run(config: &Config, args: Args) -> Result<()> {
+    let program_id = &args.program_id;
+    let signer = config.payer.pubkey();
+
+    match args.command {
+        Action::SetMessage {
+            message,
+            account,
+            ttl_slots,
+            token_account,
+        } => {
+            let mut options = SetMessageOptions {
+                ttl_slots,
+                token_account,
+                ..SetMessageOptions::default()
+            };
+            let account = match account {
+                Some(account) => {
+                    // Guard against concurrent edits, and pass the cooldown
+                    // accounts if the author configured one
+                    let state = decode_message(&config.account_data(&account)?)?;
+                    options.expected_revision = Some(state.revision);
+                    options.cooldown = state.cooldown_slots > 0;
+                    config.send(&[set_message(program_id, &account, &signer, &message, &options)], &[])?;
+                    account
+                }
+                None => {
+                    let account = Keypair::new();
+                    config.send(
+                        &[
+                            config.create_account(&account, MESSAGE_ACCOUNT_SIZE, program_id)?,
+                            set_message(program_id, &account.pubkey(), &signer, &message, &options),
+                        ],
+                        &[&account],
+                    )?;
+                    account.pubkey()
+                }
+            };
+            show(config, &account)?;
+        }
+        Action::Reply { message, parent } => {
+            let index = decode_message(&config.account_data(&parent)?)?.reply_count;
+            config.send(&[reply(program_id, &parent, index, &signer, &message)], &[])?;
+            show(config, &reply_address(program_id, &parent, index))?;
+        }
+        Action::Show { account } => show(config, &account)?,
+    }
+    Ok(())
+}
+
+fn show(config: &Config, account: &Pubkey) -> Result<()> {
+    let state = decode_message(&config.account_data(account)?)?;
+    println!("Account: {}", account);
+    if state.hidden {
+        println!("Message: (hidden by moderator {})", state.moderator);
+    } else {
+        println!("Message: {}", state.message);
+    }
+    println!("Author: {}", state.author);
+    println!("Revision: {}", state.revision);
+    if let Some(parent) = state.parent {
+        println!("Reply to: {}", parent);
+    }
+    if state.reply_count > 0 {
+        println!("Replies: {}", state.reply_count);
+    }
+    if let Some(expires_at_slot) = state.expires_at_slot {
+        println!("Expires at slot: {}", expires_at_slot);
+    }
+    Ok(())
+}
@@ -0,0 +1,163 @@
+//! `scc`: demo the workspace's programs from the command line.
+//!
+//! Every subcommand loads the signer keypair, builds its instructions with the
+//! `client` crate, fills in PDAs, and sends them to `--url`. Accounts a program
+//! expects to be allocated up front (counters, campaigns, message accounts) are
+//! created in the same transaction from a fresh keypair.
+
+mod counter;
+mod crowdfund;
+mod hello_world;
+
+use std::error::Error;
+
+use clap::{Parser, Subcommand};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{read_keypair_file, Keypair, Signature, Signer},
+    system_instruction,
+    transaction::Transaction,
+};
+
+pub type Result<T> = std::result::Result<T, Box<dyn Error>>;
+
+#[derive(Parser, Debug)]
+#[command(name = "scc", version, about = "Demo the smart-contract-comparison programs")]
+struct Cli {
+    /// RPC endpoint of the cluster the programs are deployed to
+    #[arg(long, short, global = true, default_value = "http://localhost:8899")]
+    url: String,
+
+    /// Keypair that pays for and signs every transaction [default: ~/.config/solana/id.json]
+    #[arg(long, short, global = true)]
+    keypair: Option<String>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Owner-only counter
+    Counter(counter::Args),
+    /// Deadline-based crowdfunding campaign
+    Crowdfund(crowdfund::Args),
+    /// Message board
+    Hello(hello_world::Args),
+}
+
+/// Connection and signer shared by every subcommand
+pub struct Config {
+    pub rpc: RpcClient,
+    pub payer: Keypair,
+}
+
+impl Config {
+    /// Sign `instructions` with the payer and `signers`, send, and wait for confirmation
+    pub fn send(&self, instructions: &[Instruction], signers: &[&Keypair]) -> Result<Signature> {
+        let mut keypairs = vec![&self.payer];
+        keypairs.extend_from_slice(signers);
+        let blockhash = self.rpc.get_latest_blockhash()?;
+        let transaction =
+            Transaction::new_signed_with_payer(instructions, Some(&self.payer.pubkey()), &keypairs, blockhash);
+        let signature = self.rpc.send_and_confirm_transaction(&transaction)?;
+        println!("Signature: {}", signature);
+        Ok(signature)
+    }
+
+    /// Instruction creating a rent-exempt account of `space` bytes at `account`,
+    /// owned by `program_id`
+    pub fn create_account(&self, account: &Keypair, space: usize, program_id: &Pubkey) -> Result<Instruction> {
+        let lamports = self.rpc.get_minimum_balance_for_rent_exemption(space)?;
+        Ok(system_instruction::create_account(
+            &self.payer.pubkey(),
+            &account.pubkey(),
+            lamports,
+            space as u64,
+            program_id,
+        ))
+    }
+
+    /// Data of `account`, failing if it doesn't exist
+    pub fn account_data(&self, account: &Pubkey) -> Result<Vec<u8>> {
+        Ok(self.rpc.get_account_data(account)?)
+    }
+}
+
+fn default_keypair_path() -> String {
+    let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+    format!("{}/.config/solana/id.json", home)
+}
+
+fn main() {
+    let cli = Cli::parse();
+
+    let keypair_path = cli.keypair.unwrap_or_else(default_keypair_path);
+    let payer = match read_keypair_file(&keypair_path) {
+        Ok(payer) => payer,
+        Err(error) => {
+            eprintln!("Error: could not read keypair {}: {}", keypair_path, error);
+            std::process::exit(1);
+        }
+    };
+    let config = Config {
+        rpc: RpcClient::new_with_commitment(cli.url, CommitmentConfig::confirmed()),
+        payer,
+    };
+
+    let result = match cli.command {
+        Command::Counter(args) => counter::run(&config, args),
+        Command::Crowdfund(args) => crowdfund::run(&config, args),
+        Command::Hello(args) => hello_world::run(&config, args),
+    };
+    if let Err(error) = result {
+        eprintln!("Error: {}", error);
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn test_cli_definition() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_parse_subcommands() {
+        let program_id = Pubkey::new_unique();
+        let campaign = Pubkey::new_unique();
+
+        let cli = Cli::try_parse_from([
+            "scc",
+            "crowdfund",
+            "--program-id",
+            &program_id.to_string(),
+            "contribute",
+            "--campaign",
+            &campaign.to_string(),
+            "--amount",
+            "5000",
+        ])
+        .unwrap();
+        assert_eq!(cli.url, "http://localhost:8899");
+        match cli.command {
+            Command::Crowdfund(args) => {
+                assert_eq!(args.program_id, program_id);
+                assert!(matches!(
+                    args.command,
+                    crowdfund::Action::Contribute { campaign: c, amount: 5000 } if c == campaign
+                ));
+            }
+            other => panic!("unexpected command {:?}", other),
+        }
+
+        assert!(Cli::try_parse_from(["scc", "counter", "--program-id", "not-a-pubkey", "show"]).is_err());
+    }
+}
@@ -0,0 +1,189 @@
+//! Address lookup tables (ALTs) for the [crowdfund](../../programs/crowdfund) batch refund.
+//!
+//! A legacy transaction lists every account as a 32-byte key, so the 1232-byte
+//! packet limit caps it at ~35 accounts. A v0 transaction can instead reference
+//! accounts stored in a lookup table by a 1-byte index. The flow:
+//! 1. `create_lookup_table`, then `extend_lookup_table` with the refund accounts
+//! 2. `wait_until_active`: new entries are usable from the next slot
+//! 3. `batch_refund_transaction` compiles a v0 message against the table
+
+use crate::crowdfund::batch_refund;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    address_lookup_table::{instruction, state::AddressLookupTable, AddressLookupTableAccount},
+    commitment_config::CommitmentConfig,
+    hash::Hash,
+    instruction::Instruction,
+    message::{v0, VersionedMessage},
+    pubkey::Pubkey,
+    signature::{Keypair, Signature, Signer},
+    transaction::{Transaction, VersionedTransaction},
+};
+use std::{thread, time::Duration};
+
+/// Addresses per extend transaction; more would overflow a legacy transaction
+pub const MAX_ADDRESSES_PER_EXTEND: usize = 20;
+
+/// Contributors per batch refund. Each adds two writable accounts, and a
+/// transaction may lock at most 64 accounts, including the payer and program.
+pub const MAX_REFUNDS_PER_TX: usize = 28;
+
+/// Every account a batch refund touches except the program itself, which must
+/// stay a static key because invoked programs cannot be loaded from a table
+pub fn lookup_addresses(program_id: &Pubkey, campaign: &Pubkey, contributors: &[Pubkey]) -> Vec<Pubkey> {
+    batch_refund(program_id, campaign, contributors)
+        .accounts
+        .into_iter()
+        .map(|meta| meta.pubkey)
+        .collect()
+}
+
+/// Create an empty table owned by `authority`. The address derives from the
+/// authority and a recent slot, so no keypair is needed.
+pub fn create_lookup_table(rpc: &RpcClient, authority: &Keypair) -> Result<Pubkey, Box<dyn std::error::Error>> {
+    let recent_slot = rpc.get_slot_with_commitment(CommitmentConfig::finalized())?;
+    let (ix, table) = instruction::create_lookup_table(authority.pubkey(), authority.pubkey(), recent_slot);
+    send_legacy(rpc, authority, &[ix])?;
+    Ok(table)
+}
+
+/// Append `addresses`, a few per transaction
+pub fn extend_lookup_table(
+    rpc: &RpcClient,
+    authority: &Keypair,
+    table: &Pubkey,
+    addresses: &[Pubkey],
+) -> Result<(), Box<dyn std::error::Error>> {
+    for chunk in addresses.chunks(MAX_ADDRESSES_PER_EXTEND) {
+        let ix = instruction::extend_lookup_table(*table, authority.pubkey(), Some(authority.pubkey()), chunk.to_vec());
+        send_legacy(rpc, authority, &[ix])?;
+    }
+    Ok(())
+}
+
+/// Fetch the table in the form `v0::Message::try_compile` takes
+pub fn fetch_lookup_table(rpc: &RpcClient, table: &Pubkey) -> Result<AddressLookupTableAccount, Box<dyn std::error::Error>> {
+    let account = rpc.get_account(table)?;
+    let state = AddressLookupTable::deserialize(&account.data)?;
+    Ok(AddressLookupTableAccount {
+        key: *table,
+        addresses: state.addresses.to_vec(),
+    })
+}
+
+/// Poll until the table holds `expected_len` addresses and the slot it was last
+/// extended in has passed; until then, transactions using the new entries fail
+pub fn wait_until_active(
+    rpc: &RpcClient,
+    table: &Pubkey,
+    expected_len: usize,
+) -> Result<AddressLookupTableAccount, Box<dyn std::error::Error>> {
+    for _ in 0..30 {
+        let account = rpc.get_account(table)?;
+        let state = AddressLookupTable::deserialize(&account.data)?;
+        if state.addresses.len() >= expected_len && rpc.get_slot()? > state.meta.last_extended_slot {
+            return Ok(AddressLookupTableAccount {
+                key: *table,
+                addresses: state.addresses.to_vec(),
+            });
+        }
+        thread::sleep(Duration::from_millis(400));
+    }
+    Err("lookup table did not become active".into())
+}
+
+/// Compile a v0 batch refund that loads its accounts from `table`
+pub fn batch_refund_transaction(
+    payer: &Keypair,
+    program_id: &Pubkey,
+    campaign: &Pubkey,
+    contributors: &[Pubkey],
+    table: &AddressLookupTableAccount,
+    blockhash: Hash,
+) -> Result<VersionedTransaction, Box<dyn std::error::Error>> {
+    let ix = batch_refund(program_id, campaign, contributors);
+    let message = v0::Message::try_compile(&payer.pubkey(), &[ix], std::slice::from_ref(table), blockhash)?;
+    Ok(VersionedTransaction::try_new(VersionedMessage::V0(message), &[payer])?)
+}
+
+/// End to end: put every refund account in a new table, then refund all
+/// contributors in as few v0 transactions as the lock limit allows
+pub fn refund_all(
+    rpc: &RpcClient,
+    payer: &Keypair,
+    program_id: &Pubkey,
+    campaign: &Pubkey,
+    contributors: &[Pubkey],
+) -> Result<Vec<Signature>, Box<dyn std::error::Error>> {
+    let addresses = lookup_addresses(program_id, campaign, contributors);
+    let table = create_lookup_table(rpc, payer)?;
+    extend_lookup_table(rpc, payer, &table, &addresses)?;
+    let table = wait_until_active(rpc, &table, addresses.len())?;
+
+    let mut signatures = Vec::new();
+    for batch in contributors.chunks(MAX_REFUNDS_PER_TX) {
+        let blockhash = rpc.get_latest_blockhash()?;
+        let tx = batch_refund_transaction(payer, program_id, campaign, batch, &table, blockhash)?;
+        signatures.push(rpc.send_and_confirm_transaction(&tx)?);
+    }
+    Ok(signatures)
+}
+
+fn send_legacy(rpc: &RpcClient, payer: &Keypair, instructions: &[Instruction]) -> Result<Signature, Box<dyn std::error::Error>> {
+    let blockhash = rpc.get_latest_blockhash()?;
+    let tx = Transaction::new_signed_with_payer(instructions, Some(&payer.pubkey()), &[payer], blockhash);
+    Ok(rpc.send_and_confirm_transaction(&tx)?)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::crowdfund::contributor_record_address;
+    use solana_sdk::packet::PACKET_DATA_SIZE;
+
+    #[test]
+    fn test_lookup_addresses_match_instruction() {
+        let program_id = Pubkey::new_unique();
+        let campaign = Pubkey::new_unique();
+        let contributors = [Pubkey::new_unique(), Pubkey::new_unique()];
+
+        let addresses = lookup_addresses(&program_id, &campaign, &contributors);
+        assert_eq!(addresses.len(), 5);
+        assert_eq!(addresses[0], campaign);
+        assert_eq!(addresses[1], contributor_record_address(&campaign, &contributors[0]));
+        assert_eq!(addresses[2], contributors[0]);
+        assert!(!addresses.contains(&program_id));
+
+        let ix = batch_refund(&program_id, &campaign, &contributors);
+        assert_eq!(ix.data, vec![1, 4]);
+        assert!(ix.accounts.iter().all(|meta| meta.is_writable && !meta.is_signer));
+    }
+
+    #[test]
+    fn test_full_batch_fits_only_with_lookup_table() {
+        let payer = Keypair::new();
+        let program_id = Pubkey::new_unique();
+        let campaign = Pubkey::new_unique();
+        let contributors: Vec<Pubkey> = (0..MAX_REFUNDS_PER_TX).map(|_| Pubkey::new_unique()).collect();
+        let table = AddressLookupTableAccount {
+            key: Pubkey::new_unique(),
+            addresses: lookup_addresses(&program_id, &campaign, &contributors),
+        };
+        let blockhash = Hash::new_unique();
+
+        let tx = batch_refund_transaction(&payer, &program_id, &campaign, &contributors, &table, blockhash).unwrap();
+        let size = bincode::serialize(&tx).unwrap().len();
+        assert!(size <= PACKET_DATA_SIZE, "v0 transaction is {} bytes", size);
+
+        // Only the payer and program are static keys; the rest are 1-byte indexes
+        let VersionedMessage::V0(message) = &tx.message else {
+            panic!("expected a v0 message");
+        };
+        assert_eq!(message.account_keys, vec![payer.pubkey(), program_id]);
+        assert_eq!(message.address_table_lookups[0].writable_indexes.len(), 2 * MAX_REFUNDS_PER_TX + 1);
+
+        let ix = batch_refund(&program_id, &campaign, &contributors);
+        let legacy = Transaction::new_signed_with_payer(&[ix], Some(&payer.pubkey()), &[&payer], blockhash);
+        assert!(bincode::serialize(&legacy).unwrap().len() > PACKET_DATA_SIZE);
+    }
+}
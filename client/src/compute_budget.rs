@@ -0,0 +1,173 @@
+//! Compute budget and priority fees for the [compute-budget](../compute-budget) program.
+//!
+//! Every transaction gets 200,000 CU per instruction unless it asks otherwise,
+//! and pays only the base fee. Prepending `ComputeBudgetInstruction`s changes both:
+//! - `SetComputeUnitLimit` raises (up to 1.4M) or lowers the limit
+//! - `SetComputeUnitPrice` bids micro-lamports per requested CU for priority
+//!
+//! The priority fee is charged on the requested limit, not the units used, so
+//! the limit should be measured (`simulate_units`) rather than maxed out.
+
+use borsh::BorshSerialize;
+use solana_client::{rpc_client::RpcClient, rpc_response::RpcPrioritizationFee};
+use solana_sdk::{
+    compute_budget::ComputeBudgetInstruction,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signature, Signer},
+    transaction::Transaction,
+};
+
+/// Must match `WORK_SEED` in `compute-budget/compute_budget.rs`
+pub const WORK_SEED: &[u8] = b"work";
+
+/// Most compute a transaction can request
+pub const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+/// Headroom over measured usage; account state can change between simulation and execution
+pub const LIMIT_MARGIN_PERCENT: u32 = 10;
+
+/// Mirror of the program's `ComputeInstruction`; variant order must match
+#[derive(BorshSerialize, Debug)]
+pub enum ComputeInstruction {
+    Initialize,
+    Hash { iterations: u32 },
+}
+
+pub fn work_address(program_id: &Pubkey, authority: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[WORK_SEED, authority.as_ref()], program_id).0
+}
+
+pub fn hash_instruction(program_id: &Pubkey, authority: &Pubkey, iterations: u32) -> Instruction {
+    Instruction::new_with_bytes(
+        *program_id,
+        &ComputeInstruction::Hash { iterations }.try_to_vec().expect("instructions always serialize"),
+        vec![
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new(work_address(program_id, authority), false),
+        ],
+    )
+}
+
+/// Limit to request for `units_consumed` measured units
+pub fn compute_unit_limit(units_consumed: u64) -> u32 {
+    let with_margin = units_consumed.saturating_mul(100 + LIMIT_MARGIN_PERCENT as u64) / 100;
+    with_margin.min(MAX_COMPUTE_UNIT_LIMIT as u64) as u32
+}
+
+/// Prepend the limit and price instructions. A price of 0 adds no priority.
+pub fn with_compute_budget(instructions: &[Instruction], unit_limit: u32, micro_lamports_per_cu: u64) -> Vec<Instruction> {
+    let mut all = vec![ComputeBudgetInstruction::set_compute_unit_limit(unit_limit)];
+    if micro_lamports_per_cu > 0 {
+        all.push(ComputeBudgetInstruction::set_compute_unit_price(micro_lamports_per_cu));
+    }
+    all.extend_from_slice(instructions);
+    all
+}
+
+/// Priority fee on top of the base fee, in lamports (rounded up, as the runtime does)
+pub fn priority_fee_lamports(unit_limit: u32, micro_lamports_per_cu: u64) -> u64 {
+    (unit_limit as u128 * micro_lamports_per_cu as u128).div_ceil(1_000_000) as u64
+}
+
+/// The `percentile` (0-100) of recent per-slot minimum prices. Higher
+/// percentiles land faster during congestion at a higher cost.
+pub fn price_at_percentile(fees: &[RpcPrioritizationFee], percentile: u8) -> u64 {
+    let mut prices: Vec<u64> = fees.iter().map(|fee| fee.prioritization_fee).collect();
+    if prices.is_empty() {
+        return 0;
+    }
+    prices.sort_unstable();
+    let index = (prices.len() - 1) * percentile.min(100) as usize / 100;
+    prices[index]
+}
+
+/// Recent prices paid by transactions writing to `writable_accounts`; fee
+/// markets are local, so only contention on these accounts matters
+pub fn fetch_price(rpc: &RpcClient, writable_accounts: &[Pubkey], percentile: u8) -> Result<u64, Box<dyn std::error::Error>> {
+    let fees = rpc.get_recent_prioritization_fees(writable_accounts)?;
+    Ok(price_at_percentile(&fees, percentile))
+}
+
+/// Measure units by simulating at the maximum limit
+pub fn simulate_units(rpc: &RpcClient, payer: &Keypair, instructions: &[Instruction]) -> Result<u64, Box<dyn std::error::Error>> {
+    let instructions = with_compute_budget(instructions, MAX_COMPUTE_UNIT_LIMIT, 0);
+    let blockhash = rpc.get_latest_blockhash()?;
+    let tx = Transaction::new_signed_with_payer(&instructions, Some(&payer.pubkey()), &[payer], blockhash);
+    let result = rpc.simulate_transaction(&tx)?.value;
+    if let Some(err) = result.err {
+        return Err(format!("simulation failed: {}", err).into());
+    }
+    result.units_consumed.ok_or_else(|| "simulation did not report units consumed".into())
+}
+
+/// Measure, then send `Hash { iterations }` with a tight limit and a 75th
+/// percentile priority price
+pub fn send_hash(
+    rpc: &RpcClient,
+    authority: &Keypair,
+    program_id: &Pubkey,
+    iterations: u32,
+) -> Result<Signature, Box<dyn std::error::Error>> {
+    let ix = hash_instruction(program_id, &authority.pubkey(), iterations);
+    let unit_limit = compute_unit_limit(simulate_units(rpc, authority, std::slice::from_ref(&ix))?);
+    let price = fetch_price(rpc, &[work_address(program_id, &authority.pubkey())], 75)?;
+
+    let instructions = with_compute_budget(&[ix], unit_limit, price);
+    let blockhash = rpc.get_latest_blockhash()?;
+    let tx = Transaction::new_signed_with_payer(&instructions, Some(&authority.pubkey()), &[authority], blockhash);
+    Ok(rpc.send_and_confirm_transaction(&tx)?)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_sdk::compute_budget;
+
+    #[test]
+    fn test_requested_limits() {
+        let program_id = Pubkey::new_unique();
+        let authority = Pubkey::new_unique();
+
+        // 2,000 iterations measured at ~266,000 CU: above the 200,000 default
+        let unit_limit = compute_unit_limit(266_000);
+        assert_eq!(unit_limit, 292_600);
+        assert_eq!(compute_unit_limit(u64::MAX), MAX_COMPUTE_UNIT_LIMIT);
+
+        let instructions = with_compute_budget(&[hash_instruction(&program_id, &authority, 2_000)], unit_limit, 5_000);
+        assert_eq!(instructions.len(), 3);
+        assert!(instructions[..2].iter().all(|ix| ix.program_id == compute_budget::id()));
+        assert_eq!(instructions[0], ComputeBudgetInstruction::set_compute_unit_limit(292_600));
+        // SetComputeUnitLimit is tag 2, then the limit as a little-endian u32
+        assert_eq!(instructions[0].data[0], 2);
+        assert_eq!(instructions[0].data[1..], 292_600u32.to_le_bytes());
+        assert_eq!(instructions[1], ComputeBudgetInstruction::set_compute_unit_price(5_000));
+        assert_eq!(instructions[2].program_id, program_id);
+
+        // No price means no price instruction
+        assert_eq!(with_compute_budget(&[], unit_limit, 0).len(), 1);
+    }
+
+    #[test]
+    fn test_priority_fee_and_price_selection() {
+        // Paid on the requested limit: 292,600 CU at 5,000 micro-lamports
+        assert_eq!(priority_fee_lamports(292_600, 5_000), 1_463);
+        // A maxed-out limit costs almost 5x as much for the same work
+        assert_eq!(priority_fee_lamports(MAX_COMPUTE_UNIT_LIMIT, 5_000), 7_000);
+        assert_eq!(priority_fee_lamports(1, 1), 1);
+
+        let fees: Vec<RpcPrioritizationFee> = [0, 100, 2_000, 50, 10]
+            .into_iter()
+            .enumerate()
+            .map(|(slot, prioritization_fee)| RpcPrioritizationFee {
+                slot: slot as u64,
+                prioritization_fee,
+            })
+            .collect();
+        assert_eq!(price_at_percentile(&fees, 0), 0);
+        assert_eq!(price_at_percentile(&fees, 50), 50);
+        assert_eq!(price_at_percentile(&fees, 75), 100);
+        assert_eq!(price_at_percentile(&fees, 100), 2_000);
+        assert_eq!(price_at_percentile(&[], 75), 0);
+    }
+}
@@ -0,0 +1,94 @@
+//! Instruction builders and account decoder for the [counter](../../programs/counter) program.
+
+use common::{load_versioned, VersionedInstruction};
+use smart_contract_comparison::counter::{CounterAccount, CounterInstruction};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_program,
+};
+
+fn instruction(program_id: &Pubkey, data: CounterInstruction, counter: &Pubkey, owner: &Pubkey) -> Instruction {
+    Instruction::new_with_bytes(
+        *program_id,
+        &data.pack(),
+        vec![AccountMeta::new(*counter, false), AccountMeta::new_readonly(*owner, true)],
+    )
+}
+
+/// `Initialize` a counter account the caller has already allocated, owned by the program
+pub fn initialize_counter(program_id: &Pubkey, counter: &Pubkey, owner: &Pubkey) -> Instruction {
+    instruction(program_id, CounterInstruction::Initialize, counter, owner)
+}
+
+/// `Increment` the counter (owner only)
+pub fn increment(program_id: &Pubkey, counter: &Pubkey, owner: &Pubkey) -> Instruction {
+    instruction(program_id, CounterInstruction::Increment, counter, owner)
+}
+
+/// `Decrement` the counter (owner only)
+pub fn decrement(program_id: &Pubkey, counter: &Pubkey, owner: &Pubkey) -> Instruction {
+    instruction(program_id, CounterInstruction::Decrement, counter, owner)
+}
+
+/// `Migrate` a counter written by an older program version, with `payer` covering the extra rent
+pub fn migrate(program_id: &Pubkey, counter: &Pubkey, payer: &Pubkey) -> Instruction {
+    Instruction::new_with_bytes(
+        *program_id,
+        &CounterInstruction::Migrate.pack(),
+        vec![
+            AccountMeta::new(*counter, false),
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Decode a counter account, rejecting data of any other account type or an older layout
+pub fn decode_counter(data: &[u8]) -> Result<CounterAccount, ProgramError> {
+    load_versioned(data)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use borsh::BorshSerialize;
+    use common::{Discriminator, Versioned};
+
+    #[test]
+    fn test_instruction_data() {
+        let program_id = Pubkey::new_unique();
+        let counter = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+
+        assert_eq!(initialize_counter(&program_id, &counter, &owner).data, vec![1, 0]);
+        assert_eq!(increment(&program_id, &counter, &owner).data, vec![1, 1]);
+        assert_eq!(migrate(&program_id, &counter, &owner).data, vec![1, 3]);
+
+        let ix = decrement(&program_id, &counter, &owner);
+        assert_eq!(ix.data, vec![1, 2]);
+        assert_eq!(ix.accounts, vec![AccountMeta::new(counter, false), AccountMeta::new_readonly(owner, true)]);
+    }
+
+    #[test]
+    fn test_decode_counter() {
+        let owner = Pubkey::new_unique();
+        let mut data = CounterAccount {
+            discriminator: CounterAccount::DISCRIMINATOR,
+            version: CounterAccount::VERSION,
+            is_initialized: true,
+            count: 3,
+            owner,
+        }
+        .try_to_vec()
+        .unwrap();
+
+        let counter = decode_counter(&data).unwrap();
+        assert_eq!((counter.count, counter.owner), (3, owner));
+        assert!(decode_counter(&data[..16]).is_err());
+
+        data[..8].copy_from_slice(b"campaign");
+        assert_eq!(decode_counter(&data).unwrap_err(), ProgramError::InvalidAccountData);
+    }
+}
@@ -0,0 +1,190 @@
+//! Instruction builders, PDA finders and account decoders for the
+//! [crowdfund](../../programs/crowdfund) program.
+
+use common::{load_account, load_versioned, VersionedInstruction};
+use smart_contract_comparison::crowdfund::{
+    ContributorAccount, CrowdfundAccount, CrowdfundInstruction, ALLOWLIST_MEMBER_SEED, ALLOWLIST_PROGRAM_ID,
+};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_program,
+};
+
+pub use smart_contract_comparison::crowdfund::contributor_record_address;
+
+/// The allowlist program's membership PDA for (`list`, `contributor`), which
+/// `Contribute` takes as its last account in allowlist mode
+pub fn allowlist_membership_address(list: &Pubkey, contributor: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[ALLOWLIST_MEMBER_SEED, list.as_ref(), contributor.as_ref()], &ALLOWLIST_PROGRAM_ID).0
+}
+
+fn instruction(program_id: &Pubkey, data: CrowdfundInstruction, accounts: Vec<AccountMeta>) -> Instruction {
+    Instruction::new_with_bytes(*program_id, &data.pack(), accounts)
+}
+
+/// `Initialize` a campaign account the caller has already allocated
+/// (`CrowdfundAccount::LEN` bytes, owned by the program)
+pub fn initialize_campaign(
+    program_id: &Pubkey,
+    campaign: &Pubkey,
+    owner: &Pubkey,
+    goal: u64,
+    duration_slots: u64,
+) -> Instruction {
+    instruction(
+        program_id,
+        CrowdfundInstruction::Initialize { goal, duration_slots },
+        vec![
+            AccountMeta::new(*campaign, false),
+            AccountMeta::new_readonly(*owner, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// `Contribute` `amount` lamports; pass the campaign's list when it is in allowlist mode
+pub fn contribute(
+    program_id: &Pubkey,
+    campaign: &Pubkey,
+    contributor: &Pubkey,
+    amount: u64,
+    allowlist: Option<&Pubkey>,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*campaign, false),
+        AccountMeta::new(contributor_record_address(campaign, contributor), false),
+        AccountMeta::new(*contributor, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    if let Some(list) = allowlist {
+        accounts.push(AccountMeta::new_readonly(allowlist_membership_address(list, contributor), false));
+    }
+    instruction(program_id, CrowdfundInstruction::Contribute { amount }, accounts)
+}
+
+/// `Withdraw` the raised funds to the owner of a successful campaign
+pub fn withdraw(program_id: &Pubkey, campaign: &Pubkey, owner: &Pubkey) -> Instruction {
+    instruction(
+        program_id,
+        CrowdfundInstruction::Withdraw,
+        vec![
+            AccountMeta::new(*campaign, false),
+            AccountMeta::new(*owner, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// `Refund` the contributor's own contribution to a failed campaign
+pub fn refund(program_id: &Pubkey, campaign: &Pubkey, contributor: &Pubkey) -> Instruction {
+    instruction(
+        program_id,
+        CrowdfundInstruction::Refund,
+        vec![
+            AccountMeta::new(*campaign, false),
+            AccountMeta::new(contributor_record_address(campaign, contributor), false),
+            AccountMeta::new(*contributor, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// `BatchRefund` for `contributors`: the campaign, then a (record, contributor) pair each
+pub fn batch_refund(program_id: &Pubkey, campaign: &Pubkey, contributors: &[Pubkey]) -> Instruction {
+    let mut accounts = vec![AccountMeta::new(*campaign, false)];
+    for contributor in contributors {
+        accounts.push(AccountMeta::new(contributor_record_address(campaign, contributor), false));
+        accounts.push(AccountMeta::new(*contributor, false));
+    }
+    instruction(program_id, CrowdfundInstruction::BatchRefund, accounts)
+}
+
+/// `SetAllowlist` to turn allowlist mode on with `list`, or off with `None`
+pub fn set_allowlist(program_id: &Pubkey, campaign: &Pubkey, owner: &Pubkey, list: Option<Pubkey>) -> Instruction {
+    instruction(
+        program_id,
+        CrowdfundInstruction::SetAllowlist { allowlist: list },
+        vec![AccountMeta::new(*campaign, false), AccountMeta::new_readonly(*owner, true)],
+    )
+}
+
+/// `Migrate` a campaign written by an older program version, with `payer` covering the extra rent
+pub fn migrate(program_id: &Pubkey, campaign: &Pubkey, payer: &Pubkey) -> Instruction {
+    instruction(
+        program_id,
+        CrowdfundInstruction::Migrate,
+        vec![
+            AccountMeta::new(*campaign, false),
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Decode a campaign account; the data may be longer than the state it holds
+pub fn decode_campaign(data: &[u8]) -> Result<CrowdfundAccount, ProgramError> {
+    load_versioned(data)
+}
+
+/// Decode a contributor record
+pub fn decode_contributor(data: &[u8]) -> Result<ContributorAccount, ProgramError> {
+    load_account(data)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use borsh::BorshSerialize;
+    use common::{Discriminator, Versioned};
+
+    #[test]
+    fn test_contribute_accounts() {
+        let program_id = Pubkey::new_unique();
+        let campaign = Pubkey::new_unique();
+        let contributor = Pubkey::new_unique();
+        let list = Pubkey::new_unique();
+
+        let ix = contribute(&program_id, &campaign, &contributor, 500, None);
+        assert!(matches!(
+            CrowdfundInstruction::unpack(&ix.data).unwrap(),
+            CrowdfundInstruction::Contribute { amount: 500 }
+        ));
+        assert_eq!(ix.accounts.len(), 4);
+        assert_eq!(ix.accounts[1].pubkey, contributor_record_address(&campaign, &contributor));
+        assert!(ix.accounts[2].is_signer && ix.accounts[2].is_writable);
+
+        let ix = contribute(&program_id, &campaign, &contributor, 500, Some(&list));
+        assert_eq!(ix.accounts[4].pubkey, allowlist_membership_address(&list, &contributor));
+        assert!(!ix.accounts[4].is_writable);
+    }
+
+    #[test]
+    fn test_decode_campaign() {
+        let campaign = CrowdfundAccount {
+            discriminator: CrowdfundAccount::DISCRIMINATOR,
+            version: CrowdfundAccount::VERSION,
+            is_initialized: true,
+            owner: Pubkey::new_unique(),
+            goal: 1_000,
+            deadline: 50,
+            total_raised: 250,
+            finalized: false,
+            allowlist: None,
+        };
+        let mut data = campaign.try_to_vec().unwrap();
+        // Room left for switching allowlist mode on later
+        data.resize(CrowdfundAccount::LEN, 0);
+
+        let decoded = decode_campaign(&data).unwrap();
+        assert_eq!(decoded.owner, campaign.owner);
+        assert_eq!(decoded.total_raised, 250);
+
+        let record = ContributorAccount { discriminator: ContributorAccount::DISCRIMINATOR, amount: 7 };
+        assert_eq!(decode_contributor(&record.try_to_vec().unwrap()).unwrap().amount, 7);
+        // A campaign is never mistaken for a contributor record, or vice versa
+        assert!(decode_contributor(&data).is_err());
+        assert!(decode_campaign(&record.try_to_vec().unwrap()).is_err());
+    }
+}
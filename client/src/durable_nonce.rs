@@ -0,0 +1,197 @@
+//! Offline signing for the `durable-nonce` example program.
+//!
+//! A regular transaction expires ~60-90 seconds after its recent blockhash, too
+//! soon for a key kept on an air-gapped machine. A durable nonce transaction
+//! uses the blockhash stored in a nonce account instead, which stays valid
+//! until the nonce is advanced. The flow:
+//! 1. Online: `create_nonce_account` once, then `fetch_durable_blockhash`
+//! 2. Offline: `withdraw_message` and `sign_offline`, then `encode_transaction`
+//! 3. Online: `decode_transaction`, add the fee payer's signature, `submit`
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use borsh::BorshSerialize;
+use solana_client::{nonce_utils, rpc_client::RpcClient};
+use solana_sdk::{
+    account::Account,
+    hash::Hash,
+    instruction::{AccountMeta, Instruction},
+    message::Message,
+    nonce::State as NonceState,
+    pubkey::Pubkey,
+    signature::{Signature, Signer},
+    signer::SignerError,
+    system_instruction, sysvar,
+    transaction::Transaction,
+};
+
+/// Must match `TREASURY_SEED` in `durable-nonce/durable_nonce.rs`
+pub const TREASURY_SEED: &[u8] = b"treasury";
+
+/// Mirror of the program's `NonceInstruction`; variant order must match
+#[derive(BorshSerialize, Debug)]
+pub enum NonceInstruction {
+    Initialize { nonce_account: Pubkey },
+    Withdraw { amount: u64 },
+    SetNonceAccount { nonce_account: Pubkey },
+}
+
+pub fn treasury_address(program_id: &Pubkey, authority: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[TREASURY_SEED, authority.as_ref()], program_id).0
+}
+
+/// Create and initialize a nonce account controlled by `authority`. The nonce
+/// account is a fresh keypair; it only needs to sign this once.
+pub fn create_nonce_account(
+    rpc: &RpcClient,
+    payer: &dyn Signer,
+    nonce_account: &dyn Signer,
+    authority: &Pubkey,
+) -> Result<Signature, Box<dyn std::error::Error>> {
+    let lamports = rpc.get_minimum_balance_for_rent_exemption(NonceState::size())?;
+    let instructions =
+        system_instruction::create_nonce_account(&payer.pubkey(), &nonce_account.pubkey(), authority, lamports);
+    let blockhash = rpc.get_latest_blockhash()?;
+    let tx = Transaction::new_signed_with_payer(&instructions, Some(&payer.pubkey()), &[payer, nonce_account], blockhash);
+    Ok(rpc.send_and_confirm_transaction(&tx)?)
+}
+
+/// The blockhash to sign against, read from the nonce account
+pub fn fetch_durable_blockhash(rpc: &RpcClient, nonce_account: &Pubkey) -> Result<Hash, nonce_utils::Error> {
+    let account = nonce_utils::get_account(rpc, nonce_account)?;
+    durable_blockhash(&account)
+}
+
+/// Parse the stored blockhash out of a nonce account
+pub fn durable_blockhash(account: &Account) -> Result<Hash, nonce_utils::Error> {
+    Ok(nonce_utils::data_from_account(account)?.blockhash())
+}
+
+/// Everything an offline signer needs to know about the nonce account
+#[derive(Debug, Clone, Copy)]
+pub struct OfflineNonce {
+    pub account: Pubkey,
+    /// Must sign the transaction to advance the nonce
+    pub authority: Pubkey,
+    /// From `fetch_durable_blockhash`, carried to the offline machine
+    pub blockhash: Hash,
+}
+
+/// Build a treasury withdrawal that starts by advancing the nonce account, as
+/// the program requires
+pub fn withdraw_message(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    recipient: &Pubkey,
+    amount: u64,
+    nonce: &OfflineNonce,
+    fee_payer: &Pubkey,
+) -> Message {
+    let withdraw = Instruction::new_with_bytes(
+        *program_id,
+        &NonceInstruction::Withdraw { amount }.try_to_vec().expect("instructions always serialize"),
+        vec![
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new(treasury_address(program_id, authority), false),
+            AccountMeta::new(*recipient, false),
+            AccountMeta::new_readonly(sysvar::instructions::id(), false),
+        ],
+    );
+    // Prepends `AdvanceNonceAccount`
+    let mut message = Message::new_with_nonce(vec![withdraw], Some(fee_payer), &nonce.account, &nonce.authority);
+    message.recent_blockhash = nonce.blockhash;
+    message
+}
+
+/// Sign with the offline keys. Other signatures, such as an online fee
+/// payer's, stay empty until added with `Transaction::try_partial_sign`.
+pub fn sign_offline(message: Message, signers: &[&dyn Signer]) -> Result<Transaction, SignerError> {
+    let blockhash = message.recent_blockhash;
+    let mut tx = Transaction::new_unsigned(message);
+    tx.try_partial_sign(signers, blockhash)?;
+    Ok(tx)
+}
+
+/// Encode a transaction for carrying off the offline machine
+pub fn encode_transaction(tx: &Transaction) -> String {
+    STANDARD.encode(bincode::serialize(tx).expect("transactions always serialize"))
+}
+
+pub fn decode_transaction(encoded: &str) -> Result<Transaction, Box<dyn std::error::Error>> {
+    Ok(bincode::deserialize(&STANDARD.decode(encoded)?)?)
+}
+
+/// Send a fully signed durable nonce transaction. Submitting it advances the
+/// nonce, so the same signed bytes can never land twice.
+pub fn submit(rpc: &RpcClient, tx: &Transaction) -> Result<Signature, Box<dyn std::error::Error>> {
+    if !tx.is_signed() {
+        return Err("transaction is missing signatures".into());
+    }
+    Ok(rpc.send_and_confirm_transaction(tx)?)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_sdk::{
+        nonce::{
+            state::{Data, DurableNonce, Versions},
+            State,
+        },
+        signature::Keypair,
+        system_program,
+        transaction::uses_durable_nonce,
+    };
+
+    #[test]
+    fn test_durable_blockhash_from_account() {
+        let authority = Pubkey::new_unique();
+        let stored = Hash::new_unique();
+        let durable_nonce = DurableNonce::from_blockhash(&stored);
+        let state = Versions::new(State::Initialized(Data::new(authority, durable_nonce, 5_000)));
+
+        let mut account = Account::new(1_000_000, NonceState::size(), &system_program::id());
+        account.data = bincode::serialize(&state).unwrap();
+        account.data.resize(NonceState::size(), 0);
+
+        // The stored hash is derived from the blockhash at the time of advancing
+        assert_eq!(durable_blockhash(&account).unwrap(), *durable_nonce.as_hash());
+
+        let uninitialized = Account::new(1_000_000, NonceState::size(), &system_program::id());
+        assert!(durable_blockhash(&uninitialized).is_err());
+    }
+
+    #[test]
+    fn test_offline_sign_then_add_fee_payer() {
+        let program_id = Pubkey::new_unique();
+        let cold_key = Keypair::new();
+        let fee_payer = Keypair::new();
+        let nonce_account = Pubkey::new_unique();
+        let blockhash = Hash::new_unique();
+
+        // The cold key is both treasury authority and nonce authority
+        let nonce = OfflineNonce {
+            account: nonce_account,
+            authority: cold_key.pubkey(),
+            blockhash,
+        };
+        let message = withdraw_message(
+            &program_id,
+            &cold_key.pubkey(),
+            &Pubkey::new_unique(),
+            600,
+            &nonce,
+            &fee_payer.pubkey(),
+        );
+
+        let tx = sign_offline(message, &[&cold_key]).unwrap();
+        assert!(!tx.is_signed());
+        let advance = uses_durable_nonce(&tx).expect("first instruction advances the nonce");
+        assert_eq!(tx.message.account_keys[advance.accounts[0] as usize], nonce_account);
+
+        let mut tx = decode_transaction(&encode_transaction(&tx)).unwrap();
+        tx.try_partial_sign(&[&fee_payer], blockhash).unwrap();
+        assert!(tx.is_signed());
+        assert!(tx.verify().is_ok());
+        assert_eq!(tx.message.recent_blockhash, blockhash);
+    }
+}
@@ -0,0 +1,227 @@
+//! Off-chain decoding of program events.
+//!
+//! The workspace's programs log their events with `common::emit_event!`;
+//! `decode_emitted` and `emitted_from_logs` read them back as the program's
+//! own event types, e.g. `emitted_from_logs::<crowdfund::Contributed>`.
+//!
+//! The rest of the module decodes the `events` example program, whose events
+//! arrive in one of two places in a confirmed transaction:
+//! - `meta.log_messages`, as `Program data: <base64>` lines (`emit_log`)
+//! - `meta.inner_instructions`, as self-invocations of the program whose data
+//!   starts with `EVENT_IX_TAG_LE` (`emit_cpi`)
+//!
+//! Every payload is an 8-byte discriminator followed by the Borsh-encoded event.
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use borsh::BorshDeserialize;
+use common::Event;
+use solana_program::pubkey::Pubkey;
+
+/// Must match `EVENT_IX_TAG_LE` in `events/events.rs`
+pub const EVENT_IX_TAG_LE: [u8; 8] = [228, 69, 165, 46, 81, 203, 154, 29];
+
+/// Mirror of the program's `CounterIncremented`
+#[derive(BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct CounterIncremented {
+    pub counter: Pubkey,
+    pub authority: Pubkey,
+    pub amount: u64,
+    pub count: u64,
+}
+
+/// Mirror of the program's `CounterReset`
+#[derive(BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct CounterReset {
+    pub counter: Pubkey,
+    pub authority: Pubkey,
+    pub previous_count: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CounterEvent {
+    Incremented(CounterIncremented),
+    Reset(CounterReset),
+}
+
+const COUNTER_INCREMENTED: [u8; 8] = [219, 181, 183, 220, 88, 58, 114, 198];
+const COUNTER_RESET: [u8; 8] = [156, 40, 166, 79, 155, 217, 103, 87];
+
+/// Decode a discriminator-prefixed event payload. Unknown discriminators and
+/// malformed payloads yield `None`, so newer event types do not break old indexers.
+pub fn decode_event(bytes: &[u8]) -> Option<CounterEvent> {
+    if bytes.len() < 8 {
+        return None;
+    }
+    let (discriminator, mut data) = bytes.split_at(8);
+    match discriminator.try_into().ok()? {
+        COUNTER_INCREMENTED => CounterIncremented::deserialize(&mut data).ok().map(CounterEvent::Incremented),
+        COUNTER_RESET => CounterReset::deserialize(&mut data).ok().map(CounterEvent::Reset),
+        _ => None,
+    }
+}
+
+/// Decode one field logged by `common::emit_event!` as a `T`. Fields of
+/// other event types and malformed payloads yield `None`.
+pub fn decode_emitted<T: Event + BorshDeserialize>(bytes: &[u8]) -> Option<T> {
+    let data = bytes.strip_prefix(&T::DISCRIMINATOR)?;
+    T::try_from_slice(data).ok()
+}
+
+/// Every `T` that `program_id` logged with `common::emit_event!`, in order
+pub fn emitted_from_logs<T: Event + BorshDeserialize>(program_id: &Pubkey, logs: &[String]) -> Vec<T> {
+    program_data(program_id, logs).iter().filter_map(|field| decode_emitted(field)).collect()
+}
+
+/// Collect events from a transaction's log messages
+pub fn events_from_logs(program_id: &Pubkey, logs: &[String]) -> Vec<CounterEvent> {
+    program_data(program_id, logs).iter().filter_map(|field| decode_event(field)).collect()
+}
+
+/// The decoded `sol_log_data` fields `program_id` logged. `Program data:`
+/// lines are attributed to whichever program is executing, so the invoke
+/// stack is tracked to skip data logged by other programs, including ones
+/// this program calls.
+fn program_data(program_id: &Pubkey, logs: &[String]) -> Vec<Vec<u8>> {
+    let program = program_id.to_string();
+    let mut stack: Vec<&str> = Vec::new();
+    let mut fields = Vec::new();
+
+    for log in logs {
+        if let Some(rest) = log.strip_prefix("Program data: ") {
+            if stack.last() != Some(&program.as_str()) {
+                continue;
+            }
+            // One line per sol_log_data call, one base64 field per slice
+            fields.extend(rest.split(' ').filter_map(|field| STANDARD.decode(field).ok()));
+        } else if let Some(rest) = log.strip_prefix("Program ") {
+            let mut words = rest.split(' ');
+            let (Some(id), Some(status)) = (words.next(), words.next()) else {
+                continue;
+            };
+            match status {
+                "invoke" => stack.push(id),
+                "success" | "failed:" => {
+                    stack.pop();
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fields
+}
+
+/// Collect events from a transaction's inner instructions, given as
+/// (program id, instruction data) pairs with the data already base58-decoded.
+/// Only self-invocations of `program_id` carrying the event tag count;
+/// the program rejects such instructions unless it signed them itself.
+pub fn events_from_inner_instructions(program_id: &Pubkey, instructions: &[(Pubkey, Vec<u8>)]) -> Vec<CounterEvent> {
+    instructions
+        .iter()
+        .filter(|(id, _)| id == program_id)
+        .filter_map(|(_, data)| data.strip_prefix(&EVENT_IX_TAG_LE))
+        .filter_map(decode_event)
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use borsh::BorshSerialize;
+
+    #[derive(BorshSerialize)]
+    struct Incremented {
+        counter: Pubkey,
+        authority: Pubkey,
+        amount: u64,
+        count: u64,
+    }
+
+    fn incremented_bytes(counter: Pubkey, authority: Pubkey) -> Vec<u8> {
+        let mut bytes = COUNTER_INCREMENTED.to_vec();
+        Incremented {
+            counter,
+            authority,
+            amount: 3,
+            count: 10,
+        }
+        .serialize(&mut bytes)
+        .unwrap();
+        bytes
+    }
+
+    #[test]
+    fn test_events_from_logs_skip_other_programs() {
+        let program_id = Pubkey::new_unique();
+        let other_program = Pubkey::new_unique();
+        let (counter, authority) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let encoded = STANDARD.encode(incremented_bytes(counter, authority));
+
+        let logs: Vec<String> = vec![
+            format!("Program {} invoke [1]", program_id),
+            "Program log: Instruction: Increment".to_string(),
+            format!("Program {} invoke [2]", other_program),
+            // Same bytes, but logged by another program: not ours
+            format!("Program data: {}", encoded),
+            format!("Program {} success", other_program),
+            format!("Program data: {}", encoded),
+            format!("Program {} consumed 4213 of 200000 compute units", program_id),
+            format!("Program {} success", program_id),
+        ];
+
+        let events = events_from_logs(&program_id, &logs);
+        assert_eq!(
+            events,
+            vec![CounterEvent::Incremented(CounterIncremented {
+                counter,
+                authority,
+                amount: 3,
+                count: 10,
+            })]
+        );
+    }
+
+    #[test]
+    fn test_events_from_inner_instructions_require_tag() {
+        let program_id = Pubkey::new_unique();
+        let payload = incremented_bytes(Pubkey::new_unique(), Pubkey::new_unique());
+        let mut tagged = EVENT_IX_TAG_LE.to_vec();
+        tagged.extend(&payload);
+
+        let instructions = vec![
+            (program_id, tagged.clone()),
+            // Untagged data from the program is a regular instruction
+            (program_id, payload.clone()),
+            // Tagged data sent to another program is not ours
+            (Pubkey::new_unique(), tagged),
+        ];
+
+        let events = events_from_inner_instructions(&program_id, &instructions);
+        assert_eq!(events.len(), 1);
+        assert_eq!(decode_event(&payload), Some(events[0].clone()));
+        assert_eq!(decode_event(&[0; 4]), None);
+    }
+
+    #[test]
+    fn test_emitted_from_logs() {
+        use smart_contract_comparison::crowdfund::{Contributed, GoalReached};
+
+        let program_id = Pubkey::new_unique();
+        let event = Contributed { campaign: Pubkey::new_unique(), contributor: Pubkey::new_unique(), amount: 5 };
+        let contributed = STANDARD.encode(common::event_data(&event).unwrap());
+        let reached = STANDARD.encode(
+            common::event_data(&GoalReached { campaign: event.campaign, total_raised: 5 }).unwrap(),
+        );
+
+        let logs: Vec<String> = vec![
+            format!("Program {} invoke [1]", program_id),
+            format!("Program data: {}", reached),
+            format!("Program data: {}", contributed),
+            format!("Program {} success", program_id),
+        ];
+
+        assert_eq!(emitted_from_logs::<Contributed>(&program_id, &logs), vec![event]);
+        assert_eq!(emitted_from_logs::<GoalReached>(&program_id, &logs).len(), 1);
+        assert_eq!(decode_emitted::<Contributed>(&STANDARD.decode(reached).unwrap()), None);
+    }
+}
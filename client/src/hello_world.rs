@@ -0,0 +1,274 @@
+//! Instruction builders, PDA finders and account decoders for the
+//! [hello-world](../../programs/hello-world) program.
+
+use common::{load_account, load_versioned, VersionedInstruction};
+use smart_contract_comparison::hello_world::{
+    HelloWorldAccount, HelloWorldInstruction, TokenGate, UpdaterCooldown, COOLDOWN_SEED, REPLY_SEED,
+};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_program, sysvar,
+};
+
+/// Address of the `index`th reply to `parent`; the next reply uses the
+/// parent's current `reply_count`
+pub fn reply_address(program_id: &Pubkey, parent: &Pubkey, index: u64) -> Pubkey {
+    Pubkey::find_program_address(&[REPLY_SEED, parent.as_ref(), &index.to_le_bytes()], program_id).0
+}
+
+/// Address of `updater`'s cooldown record for the `message` account
+pub fn cooldown_address(program_id: &Pubkey, message: &Pubkey, updater: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[COOLDOWN_SEED, message.as_ref(), updater.as_ref()], program_id).0
+}
+
+fn instruction(program_id: &Pubkey, data: HelloWorldInstruction, accounts: Vec<AccountMeta>) -> Instruction {
+    Instruction::new_with_bytes(*program_id, &data.pack(), accounts)
+}
+
+/// Message account, then the author or moderator as signer
+fn authorized(program_id: &Pubkey, data: HelloWorldInstruction, account: &Pubkey, signer: &Pubkey) -> Instruction {
+    instruction(
+        program_id,
+        data,
+        vec![AccountMeta::new(*account, false), AccountMeta::new_readonly(*signer, true)],
+    )
+}
+
+/// Optional `SetMessage` arguments, and the optional accounts the message
+/// account's configuration requires
+#[derive(Debug, Default, Clone)]
+pub struct SetMessageOptions {
+    pub ttl_slots: Option<u64>,
+    pub expected_revision: Option<u64>,
+    /// Only allowed on the call that initializes the account
+    pub moderator: Option<Pubkey>,
+    /// Set when the account has a cooldown: adds the updater's cooldown PDA
+    /// and the system program, and makes the updater writable to pay for it
+    pub cooldown: bool,
+    /// The updater's token account when the account is token gated
+    pub token_account: Option<Pubkey>,
+}
+
+/// `SetMessage` on `account`, signed by `updater`
+pub fn set_message(
+    program_id: &Pubkey,
+    account: &Pubkey,
+    updater: &Pubkey,
+    message: &str,
+    options: &SetMessageOptions,
+) -> Instruction {
+    let mut accounts = vec![AccountMeta::new(*account, false)];
+    if options.cooldown {
+        accounts.push(AccountMeta::new(*updater, true));
+        accounts.push(AccountMeta::new(cooldown_address(program_id, account, updater), false));
+        accounts.push(AccountMeta::new_readonly(system_program::id(), false));
+    } else {
+        accounts.push(AccountMeta::new_readonly(*updater, true));
+    }
+    if let Some(token_account) = options.token_account {
+        accounts.push(AccountMeta::new_readonly(token_account, false));
+    }
+    instruction(
+        program_id,
+        HelloWorldInstruction::SetMessage {
+            message: message.to_string(),
+            ttl_slots: options.ttl_slots,
+            expected_revision: options.expected_revision,
+            moderator: options.moderator,
+        },
+        accounts,
+    )
+}
+
+/// `GetMessage`; the account state comes back as return data
+pub fn get_message(program_id: &Pubkey, account: &Pubkey) -> Instruction {
+    instruction(program_id, HelloWorldInstruction::GetMessage, vec![AccountMeta::new_readonly(*account, false)])
+}
+
+/// `Reap` an expired message, paying the bounty to `caller`
+pub fn reap(program_id: &Pubkey, account: &Pubkey, caller: &Pubkey) -> Instruction {
+    instruction(
+        program_id,
+        HelloWorldInstruction::Reap,
+        vec![AccountMeta::new(*account, false), AccountMeta::new(*caller, true)],
+    )
+}
+
+/// `Reply` to `parent`; `reply_index` must be the parent's current `reply_count`
+pub fn reply(program_id: &Pubkey, parent: &Pubkey, reply_index: u64, author: &Pubkey, message: &str) -> Instruction {
+    instruction(
+        program_id,
+        HelloWorldInstruction::Reply {
+            message: message.to_string(),
+        },
+        vec![
+            AccountMeta::new(*parent, false),
+            AccountMeta::new(reply_address(program_id, parent, reply_index), false),
+            AccountMeta::new(*author, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// `SetCooldown` (author only)
+pub fn set_cooldown(program_id: &Pubkey, account: &Pubkey, author: &Pubkey, cooldown_slots: u64) -> Instruction {
+    authorized(program_id, HelloWorldInstruction::SetCooldown { cooldown_slots }, account, author)
+}
+
+/// `AddWriter` (author only)
+pub fn add_writer(program_id: &Pubkey, account: &Pubkey, author: &Pubkey, writer: &Pubkey) -> Instruction {
+    authorized(program_id, HelloWorldInstruction::AddWriter { writer_key: *writer }, account, author)
+}
+
+/// `RemoveWriter` (author only)
+pub fn remove_writer(program_id: &Pubkey, account: &Pubkey, author: &Pubkey, writer: &Pubkey) -> Instruction {
+    authorized(program_id, HelloWorldInstruction::RemoveWriter { writer_key: *writer }, account, author)
+}
+
+/// `SetTokenGate`, or remove the gate with `None` (author only)
+pub fn set_token_gate(
+    program_id: &Pubkey,
+    account: &Pubkey,
+    author: &Pubkey,
+    token_gate: Option<TokenGate>,
+) -> Instruction {
+    authorized(program_id, HelloWorldInstruction::SetTokenGate { token_gate }, account, author)
+}
+
+/// `SetSigningAuthority`, or remove it with `None` (author only)
+pub fn set_signing_authority(
+    program_id: &Pubkey,
+    account: &Pubkey,
+    author: &Pubkey,
+    signing_authority: Option<Pubkey>,
+) -> Instruction {
+    authorized(program_id, HelloWorldInstruction::SetSigningAuthority { signing_authority }, account, author)
+}
+
+/// `SetSignedMessage`; must follow an ed25519 instruction verifying the
+/// authority's signature over `signed_message_bytes(account, revision, message)`
+pub fn set_signed_message(program_id: &Pubkey, account: &Pubkey, message: &str) -> Instruction {
+    instruction(
+        program_id,
+        HelloWorldInstruction::SetSignedMessage {
+            message: message.to_string(),
+        },
+        vec![AccountMeta::new(*account, false), AccountMeta::new_readonly(sysvar::instructions::id(), false)],
+    )
+}
+
+/// `SetAnchoredContent` (author only)
+pub fn set_anchored_content(
+    program_id: &Pubkey,
+    account: &Pubkey,
+    author: &Pubkey,
+    content: Vec<u8>,
+    uri: &str,
+) -> Instruction {
+    authorized(
+        program_id,
+        HelloWorldInstruction::SetAnchoredContent {
+            content,
+            uri: uri.to_string(),
+        },
+        account,
+        author,
+    )
+}
+
+/// `VerifyContent` against the anchored hash
+pub fn verify_content(program_id: &Pubkey, account: &Pubkey, content: Vec<u8>) -> Instruction {
+    instruction(
+        program_id,
+        HelloWorldInstruction::VerifyContent { content },
+        vec![AccountMeta::new_readonly(*account, false)],
+    )
+}
+
+/// `FlagMessage` to hide it (moderator only)
+pub fn flag_message(program_id: &Pubkey, account: &Pubkey, moderator: &Pubkey) -> Instruction {
+    authorized(program_id, HelloWorldInstruction::FlagMessage, account, moderator)
+}
+
+/// `UnflagMessage` to show it again (moderator only)
+pub fn unflag_message(program_id: &Pubkey, account: &Pubkey, moderator: &Pubkey) -> Instruction {
+    authorized(program_id, HelloWorldInstruction::UnflagMessage, account, moderator)
+}
+
+/// `Migrate` a message written by an older program version, with `payer` covering the extra rent
+pub fn migrate(program_id: &Pubkey, account: &Pubkey, payer: &Pubkey) -> Instruction {
+    instruction(
+        program_id,
+        HelloWorldInstruction::Migrate,
+        vec![
+            AccountMeta::new(*account, false),
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Decode a message account; the data is usually longer than the state it holds
+pub fn decode_message(data: &[u8]) -> Result<HelloWorldAccount, ProgramError> {
+    load_versioned(data)
+}
+
+/// Decode an updater's cooldown record
+pub fn decode_cooldown(data: &[u8]) -> Result<UpdaterCooldown, ProgramError> {
+    load_account(data)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use common::Discriminator;
+
+    #[test]
+    fn test_set_message_optional_accounts() {
+        let program_id = Pubkey::new_unique();
+        let account = Pubkey::new_unique();
+        let updater = Pubkey::new_unique();
+        let token_account = Pubkey::new_unique();
+
+        let ix = set_message(&program_id, &account, &updater, "gm", &SetMessageOptions::default());
+        assert_eq!(ix.accounts.len(), 2);
+        assert!(!ix.accounts[1].is_writable);
+
+        let options = SetMessageOptions {
+            ttl_slots: Some(10),
+            cooldown: true,
+            token_account: Some(token_account),
+            ..SetMessageOptions::default()
+        };
+        let ix = set_message(&program_id, &account, &updater, "gm", &options);
+        assert!(ix.accounts[1].is_signer && ix.accounts[1].is_writable);
+        assert_eq!(ix.accounts[2].pubkey, cooldown_address(&program_id, &account, &updater));
+        assert_eq!(ix.accounts[3].pubkey, system_program::id());
+        assert_eq!(ix.accounts[4].pubkey, token_account);
+
+        match HelloWorldInstruction::unpack(&ix.data).unwrap() {
+            HelloWorldInstruction::SetMessage { message, ttl_slots, .. } => {
+                assert_eq!((message.as_str(), ttl_slots), ("gm", Some(10)));
+            }
+            other => panic!("unexpected instruction {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_reply_address_follows_reply_count() {
+        let program_id = Pubkey::new_unique();
+        let parent = Pubkey::new_unique();
+        let author = Pubkey::new_unique();
+
+        let ix = reply(&program_id, &parent, 2, &author, "re");
+        assert_eq!(ix.accounts[1].pubkey, reply_address(&program_id, &parent, 2));
+        assert_ne!(reply_address(&program_id, &parent, 2), reply_address(&program_id, &parent, 3));
+
+        let mut data = UpdaterCooldown::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(&5u64.to_le_bytes());
+        assert!(decode_cooldown(&data).is_ok_and(|cooldown| cooldown.last_update_slot == 5));
+        assert!(decode_message(&data).is_err());
+    }
+}
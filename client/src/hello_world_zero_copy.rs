@@ -0,0 +1,62 @@
+//! Instruction builders and account decoder for the
+//! [hello-world-zero-copy](../../programs/hello-world-zero-copy) program.
+
+use common::{check_discriminator, VersionedInstruction};
+use smart_contract_comparison::hello_world_zero_copy::{ZeroCopyInstruction, ZeroCopyMessageAccount};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+
+/// `SetMessage` on an account of `ZeroCopyMessageAccount::LEN` bytes owned by the program
+pub fn set_message(program_id: &Pubkey, account: &Pubkey, updater: &Pubkey, message: &str) -> Instruction {
+    Instruction::new_with_bytes(
+        *program_id,
+        &ZeroCopyInstruction::SetMessage { message: message.to_string() }.pack(),
+        vec![AccountMeta::new(*account, false), AccountMeta::new_readonly(*updater, true)],
+    )
+}
+
+/// `GetMessage`, which logs the stored message
+pub fn get_message(program_id: &Pubkey, account: &Pubkey) -> Instruction {
+    Instruction::new_with_bytes(
+        *program_id,
+        &ZeroCopyInstruction::GetMessage.pack(),
+        vec![AccountMeta::new_readonly(*account, false)],
+    )
+}
+
+/// Copy the fixed layout out of account data; RPC buffers carry no
+/// alignment guarantee, so this reads unaligned instead of casting in place.
+/// `None` if the data is too short or tagged as another account type.
+pub fn decode_message(data: &[u8]) -> Option<ZeroCopyMessageAccount> {
+    check_discriminator::<ZeroCopyMessageAccount>(data).ok()?;
+    data.get(..ZeroCopyMessageAccount::LEN).map(bytemuck::pod_read_unaligned)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use bytemuck::Zeroable;
+    use common::Discriminator;
+
+    #[test]
+    fn test_decode_message() {
+        let mut state = ZeroCopyMessageAccount::zeroed();
+        state.discriminator = ZeroCopyMessageAccount::DISCRIMINATOR;
+        state.revision = 4;
+        state.message_len = 2;
+        state.is_initialized = 1;
+        state.message[..2].copy_from_slice(b"gm");
+
+        // Offset by one byte so the buffer is misaligned for the struct
+        let mut data = vec![0u8];
+        data.extend_from_slice(bytemuck::bytes_of(&state));
+        let decoded = decode_message(&data[1..]).unwrap();
+        assert_eq!((decoded.revision, decoded.message().unwrap()), (4, "gm"));
+
+        assert!(decode_message(&data[2..]).is_none());
+        // Shifted by a byte, the tag no longer matches
+        assert!(decode_message(&data[..ZeroCopyMessageAccount::LEN]).is_none());
+    }
+}
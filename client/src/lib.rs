@@ -0,0 +1,14 @@
+//! Off-chain Rust code that talks to the example programs.
+//!
+//! `counter`, `crowdfund`, `hello_world` and `hello_world_zero_copy` build
+//! typed instructions, derive PDAs and decode accounts for the workspace's
+//! programs; the other modules cover one RPC flow each.
+
+pub mod address_lookup_table;
+pub mod compute_budget;
+pub mod counter;
+pub mod crowdfund;
+pub mod durable_nonce;
+pub mod event_decoder;
+pub mod hello_world;
+pub mod hello_world_zero_copy;
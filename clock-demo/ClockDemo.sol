@@ -0,0 +1,75 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+/**
+ * @title ClockDemo
+ * @dev Per-user timers using block.timestamp, with block.number kept for
+ * comparison. Post-merge blocks arrive every 12 seconds, so block numbers
+ * drift far less than Solana slots, but deadlines should still use time.
+ */
+contract ClockDemo {
+    uint256 public constant SECONDS_PER_BLOCK = 12;
+    uint256 public constant MAX_DURATION_SECONDS = 365 days;
+
+    struct Timer {
+        uint256 startBlock;
+        uint256 startTimestamp;
+        uint256 deadline;
+        uint256 deadlineBlockEstimate;
+    }
+
+    mapping(address => Timer) public timers;
+
+    event TimerStarted(address indexed owner, uint256 deadline, uint256 deadlineBlockEstimate);
+    event TimerClosed(address indexed owner);
+
+    /// Blocks covering at least `seconds_`, rounding up
+    function durationToBlocks(uint256 seconds_) public pure returns (uint256) {
+        return (seconds_ + SECONDS_PER_BLOCK - 1) / SECONDS_PER_BLOCK;
+    }
+
+    function secondsRemaining(address owner) public view returns (uint256) {
+        uint256 deadline = timers[owner].deadline;
+        return block.timestamp >= deadline ? 0 : deadline - block.timestamp;
+    }
+
+    function startTimer(uint256 durationSeconds) external {
+        require(durationSeconds > 0 && durationSeconds <= MAX_DURATION_SECONDS, "Invalid duration");
+        require(timers[msg.sender].deadline == 0, "Timer exists");
+
+        Timer memory timer = Timer({
+            startBlock: block.number,
+            startTimestamp: block.timestamp,
+            deadline: block.timestamp + durationSeconds,
+            deadlineBlockEstimate: block.number + durationToBlocks(durationSeconds)
+        });
+        timers[msg.sender] = timer;
+
+        emit TimerStarted(msg.sender, timer.deadline, timer.deadlineBlockEstimate);
+    }
+
+    /// Elapsed blocks and seconds, and whether each deadline has passed
+    function checkTimer(address owner)
+        external
+        view
+        returns (uint256 elapsedBlocks, uint256 elapsedSeconds, bool expiredByTime, bool expiredByBlock)
+    {
+        Timer memory timer = timers[owner];
+        require(timer.deadline != 0, "No timer");
+
+        elapsedBlocks = block.number - timer.startBlock;
+        elapsedSeconds = block.timestamp - timer.startTimestamp;
+        expiredByTime = block.timestamp >= timer.deadline;
+        expiredByBlock = block.number >= timer.deadlineBlockEstimate;
+    }
+
+    function closeTimer() external {
+        Timer memory timer = timers[msg.sender];
+        require(timer.deadline != 0, "No timer");
+        require(block.timestamp >= timer.deadline, "Timer not expired");
+
+        delete timers[msg.sender];
+
+        emit TimerClosed(msg.sender);
+    }
+}
@@ -0,0 +1,378 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::{Clock, Slot, UnixTimestamp},
+    entrypoint,
+    entrypoint::ProgramResult,
+    epoch_schedule::EpochSchedule,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// Seed prefix for timer PDAs: ["timer", owner]
+pub const TIMER_SEED: &[u8] = b"timer";
+
+/// Target slot time. Real slots run anywhere from ~400ms to well over 500ms
+/// depending on network conditions, so slot-based durations drift.
+pub const DEFAULT_MS_PER_SLOT: u64 = 400;
+
+/// Longest timer accepted (one year)
+pub const MAX_DURATION_SECONDS: i64 = 365 * 24 * 60 * 60;
+
+/// Size of a `TimerAccount`
+pub const TIMER_ACCOUNT_SIZE: usize = 1 // is_initialized
+    + 32 // owner
+    + 8 // start_slot
+    + 8 // start_epoch
+    + 8 // start_timestamp
+    + 8 // deadline
+    + 8 // deadline_slot_estimate
+    + 1; // bump
+
+/// Errors returned by the program as `ProgramError::Custom` codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClockError {
+    /// Duration is zero or longer than `MAX_DURATION_SECONDS`
+    InvalidDuration = 0,
+    /// The deadline has not passed yet
+    TimerNotExpired = 1,
+    /// Only the timer owner can close it
+    Unauthorized = 2,
+    /// The timer has not been initialized
+    NotInitialized = 3,
+}
+
+impl From<ClockError> for ProgramError {
+    fn from(e: ClockError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+/// A deadline recorded both as a timestamp and as an estimated slot, so the
+/// two can be compared as time passes
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct TimerAccount {
+    pub is_initialized: bool,
+    pub owner: Pubkey,
+    pub start_slot: Slot,
+    pub start_epoch: u64,
+    pub start_timestamp: UnixTimestamp,
+    /// Authoritative deadline
+    pub deadline: UnixTimestamp,
+    /// Where the deadline would fall at `DEFAULT_MS_PER_SLOT`; for comparison only
+    pub deadline_slot_estimate: Slot,
+    pub bump: u8,
+}
+
+/// Slots covering at least `seconds` at `ms_per_slot`, rounding up
+pub fn duration_to_slots(seconds: u64, ms_per_slot: u64) -> u64 {
+    (seconds.saturating_mul(1_000)).div_ceil(ms_per_slot)
+}
+
+/// Approximate seconds spanned by `slots` at `ms_per_slot`
+pub fn slots_to_seconds(slots: u64, ms_per_slot: u64) -> u64 {
+    slots.saturating_mul(ms_per_slot) / 1_000
+}
+
+/// Average slot time observed between two clock readings, if any slots passed
+pub fn observed_ms_per_slot(
+    start_slot: Slot,
+    start_timestamp: UnixTimestamp,
+    clock: &Clock,
+) -> Option<u64> {
+    let slots = clock.slot.checked_sub(start_slot).filter(|slots| *slots > 0)?;
+    let seconds = u64::try_from(clock.unix_timestamp.checked_sub(start_timestamp)?).ok()?;
+    Some(seconds.saturating_mul(1_000) / slots)
+}
+
+/// Seconds until `deadline`, 0 once it has passed
+pub fn seconds_remaining(clock: &Clock, deadline: UnixTimestamp) -> u64 {
+    deadline.saturating_sub(clock.unix_timestamp).max(0) as u64
+}
+
+entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = ClockInstruction::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        ClockInstruction::LogClock => log_clock(),
+        ClockInstruction::StartTimer { duration_seconds } => start_timer(program_id, accounts, duration_seconds),
+        ClockInstruction::CheckTimer => check_timer(program_id, accounts),
+        ClockInstruction::CloseTimer => close_timer(program_id, accounts),
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub enum ClockInstruction {
+    /// Log the Clock and EpochSchedule sysvars
+    /// Accounts: none
+    LogClock,
+    /// Start a timer ending `duration_seconds` from now
+    /// Accounts: [writable, signer] owner, [writable] timer PDA, [] system program
+    StartTimer { duration_seconds: i64 },
+    /// Log elapsed slots and seconds, the observed slot time, and how far the
+    /// slot estimate has drifted from the timestamp deadline
+    /// Accounts: [] timer PDA
+    CheckTimer,
+    /// Close an expired timer, refunding its rent
+    /// Accounts: [writable, signer] owner, [writable] timer PDA
+    CloseTimer,
+}
+
+fn log_clock() -> ProgramResult {
+    let clock = Clock::get()?;
+    let schedule = EpochSchedule::get()?;
+
+    // slot: advances every ~400ms, the finest unit of on-chain time
+    // unix_timestamp: stake-weighted median of validator votes, can lag wall clock
+    // epoch: slots_per_epoch slots (432,000 on mainnet, ~2 days)
+    let epoch_start = schedule.get_first_slot_in_epoch(clock.epoch);
+    let slots_in_epoch = schedule.get_slots_in_epoch(clock.epoch);
+    msg!("Slot {} at unix time {}", clock.slot, clock.unix_timestamp);
+    msg!(
+        "Epoch {}: slot {} of {} (epoch started at unix time {})",
+        clock.epoch,
+        clock.slot.saturating_sub(epoch_start),
+        slots_in_epoch,
+        clock.epoch_start_timestamp
+    );
+    msg!(
+        "Epoch {} ends in ~{} seconds",
+        clock.epoch,
+        slots_to_seconds(schedule.get_last_slot_in_epoch(clock.epoch).saturating_sub(clock.slot), DEFAULT_MS_PER_SLOT)
+    );
+
+    Ok(())
+}
+
+fn start_timer(program_id: &Pubkey, accounts: &[AccountInfo], duration_seconds: i64) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let owner = next_account_info(accounts_iter)?;
+    let timer_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if duration_seconds <= 0 || duration_seconds > MAX_DURATION_SECONDS {
+        return Err(ClockError::InvalidDuration.into());
+    }
+
+    let (expected_timer, bump) = Pubkey::find_program_address(&[TIMER_SEED, owner.key.as_ref()], program_id);
+    if expected_timer != *timer_account.key {
+        msg!("Timer account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    invoke_signed(
+        &system_instruction::create_account(
+            owner.key,
+            timer_account.key,
+            Rent::get()?.minimum_balance(TIMER_ACCOUNT_SIZE),
+            TIMER_ACCOUNT_SIZE as u64,
+            program_id,
+        ),
+        &[owner.clone(), timer_account.clone(), system_program.clone()],
+        &[&[TIMER_SEED, owner.key.as_ref(), &[bump]]],
+    )?;
+
+    let clock = Clock::get()?;
+    let timer = TimerAccount {
+        is_initialized: true,
+        owner: *owner.key,
+        start_slot: clock.slot,
+        start_epoch: clock.epoch,
+        start_timestamp: clock.unix_timestamp,
+        deadline: clock.unix_timestamp + duration_seconds,
+        deadline_slot_estimate: clock.slot + duration_to_slots(duration_seconds as u64, DEFAULT_MS_PER_SLOT),
+        bump,
+    };
+    timer.serialize(&mut &mut timer_account.data.borrow_mut()[..])?;
+
+    msg!(
+        "Timer ends at unix time {} (~slot {})",
+        timer.deadline,
+        timer.deadline_slot_estimate
+    );
+
+    Ok(())
+}
+
+fn check_timer(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let timer_account = next_account_info(accounts_iter)?;
+
+    let timer = load_timer(program_id, timer_account)?;
+    let clock = Clock::get()?;
+
+    msg!(
+        "Elapsed: {} slots, {} seconds, {} epochs",
+        clock.slot.saturating_sub(timer.start_slot),
+        clock.unix_timestamp.saturating_sub(timer.start_timestamp),
+        clock.epoch.saturating_sub(timer.start_epoch)
+    );
+
+    if let Some(ms_per_slot) = observed_ms_per_slot(timer.start_slot, timer.start_timestamp, &clock) {
+        msg!("Observed {} ms per slot (target {})", ms_per_slot, DEFAULT_MS_PER_SLOT);
+    }
+
+    // Slots usually run slower than the target, so the slot estimate is
+    // reached later than the timestamp deadline
+    let slot_expired = clock.slot >= timer.deadline_slot_estimate;
+    let time_expired = clock.unix_timestamp >= timer.deadline;
+    msg!(
+        "{} seconds remaining; expired by timestamp: {}, by slot estimate: {}",
+        seconds_remaining(&clock, timer.deadline),
+        time_expired,
+        slot_expired
+    );
+
+    Ok(())
+}
+
+fn close_timer(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let owner = next_account_info(accounts_iter)?;
+    let timer_account = next_account_info(accounts_iter)?;
+
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let timer = load_timer(program_id, timer_account)?;
+
+    if timer.owner != *owner.key {
+        return Err(ClockError::Unauthorized.into());
+    }
+
+    // Deadlines are enforced on the timestamp, never the slot estimate
+    let clock = Clock::get()?;
+    if clock.unix_timestamp < timer.deadline {
+        msg!("Timer has {} seconds remaining", seconds_remaining(&clock, timer.deadline));
+        return Err(ClockError::TimerNotExpired.into());
+    }
+
+    let lamports = timer_account.lamports();
+    **timer_account.try_borrow_mut_lamports()? = 0;
+    **owner.try_borrow_mut_lamports()? += lamports;
+    timer_account.data.borrow_mut().fill(0);
+
+    msg!("Timer closed");
+
+    Ok(())
+}
+
+/// Validate the timer account and return it
+fn load_timer(program_id: &Pubkey, timer_account: &AccountInfo) -> Result<TimerAccount, ProgramError> {
+    if timer_account.owner != program_id {
+        msg!("Timer account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let timer = TimerAccount::try_from_slice(&timer_account.data.borrow())?;
+
+    if !timer.is_initialized {
+        return Err(ClockError::NotInitialized.into());
+    }
+
+    Ok(timer)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_program::{clock::Epoch, entrypoint::SUCCESS, program_stubs};
+
+    /// Serves a Clock sysvar at a fixed slot and time so handlers can run off-chain
+    struct TestSyscallStubs {
+        slot: u64,
+        unix_timestamp: i64,
+    }
+
+    impl program_stubs::SyscallStubs for TestSyscallStubs {
+        fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+            unsafe {
+                *(var_addr as *mut Clock) = Clock {
+                    slot: self.slot,
+                    unix_timestamp: self.unix_timestamp,
+                    ..Clock::default()
+                };
+            }
+            SUCCESS
+        }
+    }
+
+    #[test]
+    fn test_duration_conversions_and_drift() {
+        // One hour at 400ms is 9,000 slots; a partial slot rounds up
+        assert_eq!(duration_to_slots(3_600, DEFAULT_MS_PER_SLOT), 9_000);
+        assert_eq!(duration_to_slots(1, 300), 4);
+        assert_eq!(slots_to_seconds(9_000, DEFAULT_MS_PER_SLOT), 3_600);
+
+        // If slots actually take 500ms, those 9,000 slots last 75 minutes
+        let clock = Clock {
+            slot: 1_009_000,
+            unix_timestamp: 1_700_004_500,
+            ..Clock::default()
+        };
+        assert_eq!(observed_ms_per_slot(1_000_000, 1_700_000_000, &clock), Some(500));
+        assert_eq!(observed_ms_per_slot(1_009_000, 1_700_000_000, &clock), None);
+
+        assert_eq!(seconds_remaining(&clock, 1_700_004_600), 100);
+        assert_eq!(seconds_remaining(&clock, 1_700_000_000), 0);
+    }
+
+    #[test]
+    fn test_close_timer_before_deadline_fails() {
+        program_stubs::set_syscall_stubs(Box::new(TestSyscallStubs {
+            slot: 1_000_100,
+            unix_timestamp: 1_700_000_040,
+        }));
+
+        let program_id = Pubkey::new_unique();
+        let owner_key = Pubkey::new_unique();
+
+        let timer = TimerAccount {
+            is_initialized: true,
+            owner: owner_key,
+            start_slot: 1_000_000,
+            start_epoch: 500,
+            start_timestamp: 1_700_000_000,
+            deadline: 1_700_000_060,
+            // The slot estimate has already passed, but the timestamp deadline has not
+            deadline_slot_estimate: 1_000_050,
+            bump: 255,
+        };
+
+        let keys = [owner_key, Pubkey::new_unique()];
+        let mut lamports: Vec<u64> = vec![10_000; keys.len()];
+        let mut data: Vec<Vec<u8>> = vec![vec![]; keys.len()];
+        data[1] = timer.try_to_vec().unwrap();
+
+        let accounts: Vec<AccountInfo> = keys
+            .iter()
+            .zip(lamports.iter_mut())
+            .zip(data.iter_mut())
+            .enumerate()
+            .map(|(i, ((key, lamports), data))| {
+                AccountInfo::new(key, i == 0, true, lamports, data, &program_id, false, Epoch::default())
+            })
+            .collect();
+
+        let instruction_data = ClockInstruction::CloseTimer.try_to_vec().unwrap();
+        let result = process_instruction(&program_id, &accounts, &instruction_data);
+        assert_eq!(result, Err(ClockError::TimerNotExpired.into()));
+    }
+}
@@ -0,0 +1,829 @@
+//! Account validation shared by the workspace's programs.
+//!
+//! Every check logs the offending account and fails with the same
+//! `ProgramError` in every program:
+//! - not a signer: `MissingRequiredSignature`
+//! - not writable: `InvalidAccountData`
+//! - wrong owner: `IncorrectProgramId`
+//! - state not initialized / already initialized: `UninitializedAccount` /
+//!   `AccountAlreadyInitialized`
+//! - address is not the expected PDA: `InvalidSeeds`
+//! - data tagged as a different account type: `InvalidAccountData`
+//! - balance below the rent-exempt minimum for its size: `AccountNotRentExempt`
+//!
+//! `transfer_lamports` moves lamports out of program-owned accounts with
+//! checked arithmetic, failing with `InsufficientFunds` or
+//! `ArithmeticOverflow` instead of panicking. `top_up_rent` and
+//! `create_pda_account` fund accounts to the rent-exempt minimum for their
+//! size from a payer through the system program.
+//!
+//! Handlers read the time through a `ClockSource` rather than `Clock::get()`:
+//! `SysvarClock` on-chain, `FixedClock` in unit tests.
+//!
+//! `emit_event!` logs an `Event` with `sol_log_data`, prefixed with its
+//! discriminator so `client::event_decoder` can tell event types apart.
+//! `debug_msg!` logs only in programs built with their `debug-logs` feature;
+//! failures keep logging with `msg!`.
+//!
+//! `tagged_instruction!` gives an instruction enum fixed one-byte tags, so
+//! reordering or inserting variants cannot change the wire format.
+//! `VersionedInstruction` puts a format version byte ahead of the tag, so a
+//! program can reject data from clients it doesn't understand, or accept two
+//! formats while clients migrate.
+//!
+//! `Versioned` account types carry a layout version after the discriminator.
+//! `load_versioned` rejects accounts in an older layout, and
+//! `migrate_account` grows them, paying the extra rent, and rewrites them.
+//!
+//! With the `zero-copy` feature, `load_pod` and `load_pod_mut` borrow account
+//! data in place as a `bytemuck::Pod` layout instead of deserializing it.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::AccountInfo,
+    clock::{Clock, Slot},
+    entrypoint::ProgramResult,
+    log::sol_log_data,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+
+pub use solana_program::program_pack::IsInitialized;
+
+/// Account types whose state starts with an 8-byte tag, so one type's data is
+/// never accepted as another's. Tags are the type's name in ASCII, zero-padded.
+pub trait Discriminator {
+    const DISCRIMINATOR: [u8; 8];
+}
+
+/// Deserialize `T` from account data after checking its discriminator.
+/// Accounts may be allocated larger than the state they hold. Data that was
+/// never written (an all-zero tag) is accepted, so instructions can still
+/// tell an uninitialized account apart with `IsInitialized`.
+pub fn load_account<T: Discriminator + BorshDeserialize>(data: &[u8]) -> Result<T, ProgramError> {
+    check_discriminator::<T>(data)?;
+    Ok(T::deserialize(&mut &data[..])?)
+}
+
+/// Require `data` to start with `T`'s discriminator or with a zeroed one
+pub fn check_discriminator<T: Discriminator>(data: &[u8]) -> ProgramResult {
+    match data.get(..8) {
+        Some(tag) if tag == T::DISCRIMINATOR || tag == [0; 8] => Ok(()),
+        _ => {
+            msg!("Account data is not a {}", std::any::type_name::<T>());
+            Err(ProgramError::InvalidAccountData)
+        }
+    }
+}
+
+/// Account types whose layout can change after deployment. The byte after
+/// the discriminator holds the layout version; 0 means never written.
+///
+/// The unversioned layouts had `is_initialized` there, which is 1 in every
+/// live account, so they read as version 1 and the first versioned layout is
+/// version 2. Adding a field means bumping `VERSION`, growing `SPACE` if
+/// needed, and handling the previous version in `migrate_in_place`.
+pub trait Versioned: Discriminator {
+    /// Version of the layout this code reads and writes
+    const VERSION: u8;
+    /// Size accounts in an older layout are grown to before migrating
+    const SPACE: usize;
+
+    /// Rewrite `data` from an older layout to the current one. `data` is at
+    /// least `SPACE` bytes, zeroed past what the old layout used.
+    fn migrate_in_place(data: &mut [u8]) -> ProgramResult;
+}
+
+/// `load_account` for `Versioned` types, which also fails on accounts still
+/// in an older layout until they are migrated
+pub fn load_versioned<T: Versioned + BorshDeserialize>(data: &[u8]) -> Result<T, ProgramError> {
+    check_discriminator::<T>(data)?;
+    match data.get(8) {
+        Some(&version) if version != T::VERSION && version != 0 => {
+            let name = std::any::type_name::<T>();
+            msg!("{} has layout version {}, expected {}: migrate it first", name, version, T::VERSION);
+            Err(ProgramError::InvalidAccountData)
+        }
+        _ => load_account(data),
+    }
+}
+
+/// Migrate version 1, the unversioned layout, by shifting everything after
+/// the discriminator one byte right and writing `version` in the gap. The
+/// last byte of `data` must be unused.
+pub fn insert_version_byte(data: &mut [u8], version: u8) -> ProgramResult {
+    match data.last() {
+        Some(0) if data.len() > 9 => {
+            let len = data.len();
+            data.copy_within(8..len - 1, 9);
+            data[8] = version;
+            Ok(())
+        }
+        _ => {
+            msg!("No room to insert the version byte");
+            Err(ProgramError::AccountDataTooSmall)
+        }
+    }
+}
+
+/// Bring `account` to `T`'s current layout. Accounts smaller than
+/// `T::SPACE` are topped up to rent exemption from `payer` and reallocated
+/// first. Accounts already at `T::VERSION`, or never written, are left alone.
+pub fn migrate_account<'a, T: Versioned>(
+    account: &AccountInfo<'a>,
+    payer: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+) -> ProgramResult {
+    check_discriminator::<T>(&account.data.borrow())?;
+    let version = account.data.borrow().get(8).copied().unwrap_or(0);
+    if version == T::VERSION || version == 0 {
+        msg!("{} is already at layout version {}", account.key, T::VERSION);
+        return Ok(());
+    }
+
+    if account.data_len() < T::SPACE {
+        top_up_rent(account, payer, system_program, T::SPACE)?;
+        account.realloc(T::SPACE, true)?;
+    }
+
+    T::migrate_in_place(&mut account.data.borrow_mut())?;
+    msg!("Migrated {} from layout version {} to {}", account.key, version, T::VERSION);
+    Ok(())
+}
+
+/// Borrow account data in place as `T` after checking its discriminator.
+/// Only the first `size_of::<T>()` bytes are used; they must be aligned for
+/// `T`, which on-chain account data always is.
+#[cfg(feature = "zero-copy")]
+pub fn load_pod<T: bytemuck::Pod + Discriminator>(data: &[u8]) -> Result<&T, ProgramError> {
+    check_discriminator::<T>(data)?;
+    data.get(..std::mem::size_of::<T>())
+        .and_then(|data| bytemuck::try_from_bytes(data).ok())
+        .ok_or_else(wrong_pod_layout)
+}
+
+/// Mutably borrow account data in place as `T`; see `load_pod`
+#[cfg(feature = "zero-copy")]
+pub fn load_pod_mut<T: bytemuck::Pod + Discriminator>(data: &mut [u8]) -> Result<&mut T, ProgramError> {
+    check_discriminator::<T>(data)?;
+    data.get_mut(..std::mem::size_of::<T>())
+        .and_then(|data| bytemuck::try_from_bytes_mut(data).ok())
+        .ok_or_else(wrong_pod_layout)
+}
+
+#[cfg(feature = "zero-copy")]
+fn wrong_pod_layout() -> ProgramError {
+    msg!("Account data has the wrong size or alignment");
+    ProgramError::AccountDataTooSmall
+}
+
+/// The tag to write when converting state tagged `tag` to layout `T`: `T`'s
+/// discriminator, or a zeroed one for state that was never written
+pub fn convert_discriminator<T: Discriminator>(tag: [u8; 8]) -> [u8; 8] {
+    if tag == [0; 8] {
+        [0; 8]
+    } else {
+        T::DISCRIMINATOR
+    }
+}
+
+/// Events logged by `emit_event!`. The 8-byte tag comes first in the log
+/// field, ahead of the Borsh-encoded event, and follows the same ASCII naming
+/// as account discriminators.
+pub trait Event: BorshSerialize {
+    const DISCRIMINATOR: [u8; 8];
+}
+
+/// `event`'s log field: its discriminator followed by its Borsh encoding
+pub fn event_data<T: Event>(event: &T) -> Result<Vec<u8>, ProgramError> {
+    let mut data = T::DISCRIMINATOR.to_vec();
+    event.serialize(&mut data)?;
+    Ok(data)
+}
+
+/// Write `event` to the program log as a single `sol_log_data` field. Use
+/// `emit_event!` rather than calling this directly.
+pub fn log_event<T: Event>(event: &T) -> ProgramResult {
+    sol_log_data(&[&event_data(event)?]);
+    Ok(())
+}
+
+/// Log a typed event for off-chain indexers, evaluating to a `ProgramResult`:
+///
+/// ```ignore
+/// emit_event!(CountChanged { counter: *counter_account.key, count })?;
+/// ```
+#[macro_export]
+macro_rules! emit_event {
+    ($event:expr) => {
+        $crate::log_event(&$event)
+    };
+}
+
+/// `msg!` for success-path detail, compiled in only when the calling program
+/// is built with its own `debug-logs` feature. The arguments are type-checked
+/// either way, so values that are only logged don't become unused.
+///
+/// ```ignore
+/// debug_msg!("Counter incremented to {}", counter_data.count);
+/// ```
+#[macro_export]
+macro_rules! debug_msg {
+    ($($arg:tt)*) => {
+        if cfg!(feature = "debug-logs") {
+            ::solana_program::msg!($($arg)*);
+        }
+    };
+}
+
+/// Borsh encoding for an instruction enum with an explicit one-byte tag per
+/// variant, followed by the variant's fields in the order listed. Derived
+/// Borsh tags are the variant's position, so inserting a variant would
+/// silently renumber every instruction after it; here the tags are fixed, and
+/// a tag listed twice fails with `unreachable_patterns`. Also adds a `tag()`
+/// method. Unknown tags fail to decode with `InvalidData`.
+///
+/// ```ignore
+/// tagged_instruction! {
+///     CrowdfundInstruction {
+///         0 => Initialize { goal, duration_slots },
+///         1 => Contribute { amount },
+///         2 => Withdraw,
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! tagged_instruction {
+    ($name:ident { $($tag:literal => $variant:ident $({ $($field:ident),* $(,)? })?),* $(,)? }) => {
+        impl $name {
+            /// The instruction's first byte on the wire
+            pub fn tag(&self) -> u8 {
+                match self {
+                    $(Self::$variant { .. } => $tag,)*
+                }
+            }
+        }
+
+        impl ::borsh::BorshSerialize for $name {
+            fn serialize<W: ::borsh::maybestd::io::Write>(&self, writer: &mut W) -> ::borsh::maybestd::io::Result<()> {
+                writer.write_all(&[self.tag()])?;
+                match self {
+                    $(Self::$variant $({ $($field),* })? => {
+                        $($(::borsh::BorshSerialize::serialize($field, writer)?;)*)?
+                    })*
+                }
+                Ok(())
+            }
+        }
+
+        impl ::borsh::BorshDeserialize for $name {
+            fn deserialize_reader<R: ::borsh::maybestd::io::Read>(
+                reader: &mut R,
+            ) -> ::borsh::maybestd::io::Result<Self> {
+                let tag = <u8 as ::borsh::BorshDeserialize>::deserialize_reader(reader)?;
+                match tag {
+                    $($tag => Ok(Self::$variant $({
+                        $($field: ::borsh::BorshDeserialize::deserialize_reader(reader)?),*
+                    })?),)*
+                    _ => Err(::borsh::maybestd::io::Error::new(
+                        ::borsh::maybestd::io::ErrorKind::InvalidData,
+                        format!("unknown {} tag {}", stringify!($name), tag),
+                    )),
+                }
+            }
+        }
+    };
+}
+
+/// Instruction data versioned by a leading format byte: `[FORMAT, tag,
+/// fields...]`. `unpack` reads it in `process_instruction`, so every
+/// instruction goes through the same check.
+///
+/// To change an instruction's encoding, bump `FORMAT` and, while existing
+/// clients migrate, override `decode` to also accept the previous format:
+///
+/// ```ignore
+/// fn decode(format: u8, data: &[u8]) -> Result<Self, ProgramError> {
+///     match format {
+///         1 => decode_borsh::<InstructionV1>(data).map(Self::from),
+///         2 => decode_borsh(data),
+///         _ => Err(unsupported_format::<Self>(format)),
+///     }
+/// }
+/// ```
+///
+/// Once clients have moved over, drop the old arm.
+pub trait VersionedInstruction: BorshSerialize + BorshDeserialize {
+    /// Format version `pack` writes
+    const FORMAT: u8 = 1;
+
+    /// Decode the data after the format byte. Accepts only `FORMAT` unless
+    /// overridden.
+    fn decode(format: u8, data: &[u8]) -> Result<Self, ProgramError> {
+        if format != Self::FORMAT {
+            return Err(unsupported_format::<Self>(format));
+        }
+        decode_borsh(data)
+    }
+
+    /// Instruction data: the format byte, then the Borsh encoding
+    fn pack(&self) -> Vec<u8> {
+        let mut data = vec![Self::FORMAT];
+        self.serialize(&mut data).expect("instructions always serialize");
+        data
+    }
+
+    /// Split off the format byte and decode the rest, failing with
+    /// `InvalidInstructionData` on an unknown format or malformed data
+    fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        let (&format, data) = data.split_first().ok_or_else(|| {
+            msg!("Instruction data is empty");
+            ProgramError::InvalidInstructionData
+        })?;
+        Self::decode(format, data)
+    }
+}
+
+/// Decode the whole of `data` as `T`, failing with `InvalidInstructionData`
+pub fn decode_borsh<T: BorshDeserialize>(data: &[u8]) -> Result<T, ProgramError> {
+    T::try_from_slice(data).map_err(|_| ProgramError::InvalidInstructionData)
+}
+
+/// Log and return the error for instruction data in a format `T` doesn't
+/// accept
+pub fn unsupported_format<T: VersionedInstruction>(format: u8) -> ProgramError {
+    msg!("Unsupported instruction format {}, expected {}", format, T::FORMAT);
+    ProgramError::InvalidInstructionData
+}
+
+/// Require `account` to have signed the transaction
+pub fn assert_signer(account: &AccountInfo) -> ProgramResult {
+    if !account.is_signer {
+        msg!("{} must be a signer", account.key);
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    Ok(())
+}
+
+/// Require `account` to be passed as writable
+pub fn assert_writable(account: &AccountInfo) -> ProgramResult {
+    if !account.is_writable {
+        msg!("{} must be writable", account.key);
+        return Err(ProgramError::InvalidAccountData);
+    }
+    Ok(())
+}
+
+/// Require `account` to be owned by `owner`, usually the calling program
+pub fn assert_owned_by(account: &AccountInfo, owner: &Pubkey) -> ProgramResult {
+    if account.owner != owner {
+        msg!("{} is owned by {}, expected {}", account.key, account.owner, owner);
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    Ok(())
+}
+
+/// Require deserialized account state to be initialized
+pub fn assert_initialized<T: IsInitialized>(state: &T) -> ProgramResult {
+    if !state.is_initialized() {
+        msg!("Account has not been initialized");
+        return Err(ProgramError::UninitializedAccount);
+    }
+    Ok(())
+}
+
+/// Require deserialized account state not to be initialized yet
+pub fn assert_uninitialized<T: IsInitialized>(state: &T) -> ProgramResult {
+    if state.is_initialized() {
+        msg!("Account is already initialized");
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+    Ok(())
+}
+
+/// Require `account` to be the canonical PDA of `seeds` under `program_id`,
+/// returning its bump seed for `invoke_signed`
+pub fn assert_pda(account: &AccountInfo, seeds: &[&[u8]], program_id: &Pubkey) -> Result<u8, ProgramError> {
+    let (expected, bump) = Pubkey::find_program_address(seeds, program_id);
+    if expected != *account.key {
+        msg!("{} does not match the expected PDA {}", account.key, expected);
+        return Err(ProgramError::InvalidSeeds);
+    }
+    Ok(bump)
+}
+
+/// Move `amount` lamports from `from`, which the program must own, to `to`
+pub fn transfer_lamports(from: &AccountInfo, to: &AccountInfo, amount: u64) -> ProgramResult {
+    if from.key == to.key {
+        return Ok(());
+    }
+    let from_lamports = from.lamports().checked_sub(amount).ok_or_else(|| {
+        msg!("{} holds fewer than {} lamports", from.key, amount);
+        ProgramError::InsufficientFunds
+    })?;
+    let to_lamports = to.lamports().checked_add(amount).ok_or(ProgramError::ArithmeticOverflow)?;
+    **from.try_borrow_mut_lamports()? = from_lamports;
+    **to.try_borrow_mut_lamports()? = to_lamports;
+    Ok(())
+}
+
+/// Lamports an account of `space` bytes must hold to be rent exempt
+pub fn rent_exempt_minimum(space: usize) -> Result<u64, ProgramError> {
+    Ok(Rent::get()?.minimum_balance(space))
+}
+
+/// Require `account` to hold the rent-exempt minimum for its current size
+pub fn assert_rent_exempt(account: &AccountInfo) -> ProgramResult {
+    let minimum = rent_exempt_minimum(account.data_len())?;
+    if account.lamports() < minimum {
+        msg!("{} holds {} lamports, below the rent-exempt minimum of {}", account.key, account.lamports(), minimum);
+        return Err(ProgramError::AccountNotRentExempt);
+    }
+    Ok(())
+}
+
+/// Transfer from `payer` whatever `account` lacks of the rent-exempt minimum
+/// for `space` bytes; nothing when it already holds enough
+pub fn top_up_rent<'a>(
+    account: &AccountInfo<'a>,
+    payer: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    space: usize,
+) -> ProgramResult {
+    let shortfall = rent_exempt_minimum(space)?.saturating_sub(account.lamports());
+    if shortfall > 0 {
+        invoke(
+            &system_instruction::transfer(payer.key, account.key, shortfall),
+            &[payer.clone(), account.clone(), system_program.clone()],
+        )?;
+    }
+    Ok(())
+}
+
+/// Create the PDA `account` with `space` bytes owned by `owner`, rent exempt
+/// and paid for by `payer`. Unlike `create_account`, this still works when
+/// someone has already sent lamports to the address.
+pub fn create_pda_account<'a>(
+    account: &AccountInfo<'a>,
+    payer: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    space: usize,
+    owner: &Pubkey,
+    signer_seeds: &[&[u8]],
+) -> ProgramResult {
+    top_up_rent(account, payer, system_program, space)?;
+    let accounts = [account.clone(), system_program.clone()];
+    invoke_signed(&system_instruction::allocate(account.key, space as u64), &accounts, &[signer_seeds])?;
+    invoke_signed(&system_instruction::assign(account.key, owner), &accounts, &[signer_seeds])
+}
+
+/// Where handlers read the current slot and time from. Handlers that take a
+/// `&impl ClockSource` instead of calling `Clock::get()` can have their
+/// deadline logic unit tested without syscall stubs.
+pub trait ClockSource {
+    fn clock(&self) -> Result<Clock, ProgramError>;
+
+    fn slot(&self) -> Result<Slot, ProgramError> {
+        Ok(self.clock()?.slot)
+    }
+}
+
+/// The Clock sysvar, the source every program uses on-chain
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SysvarClock;
+
+impl ClockSource for SysvarClock {
+    fn clock(&self) -> Result<Clock, ProgramError> {
+        Clock::get()
+    }
+}
+
+/// A clock stopped at a set time, for tests
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FixedClock(pub Clock);
+
+impl FixedClock {
+    pub fn at_slot(slot: Slot) -> Self {
+        Self(Clock { slot, ..Clock::default() })
+    }
+}
+
+impl ClockSource for FixedClock {
+    fn clock(&self) -> Result<Clock, ProgramError> {
+        Ok(self.0.clone())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use borsh::BorshSerialize;
+    use test_utils::account::TestAccount;
+
+    struct State(bool);
+
+    impl IsInitialized for State {
+        fn is_initialized(&self) -> bool {
+            self.0
+        }
+    }
+
+    #[test]
+    fn test_account_flags() {
+        let program_id = Pubkey::new_unique();
+        let mut test_account = TestAccount::new(Pubkey::new_unique()).owner(program_id);
+        let mut account = test_account.info();
+
+        assert_eq!(assert_signer(&account), Err(ProgramError::MissingRequiredSignature));
+        assert_eq!(assert_writable(&account), Err(ProgramError::InvalidAccountData));
+        assert_eq!(assert_owned_by(&account, &Pubkey::new_unique()), Err(ProgramError::IncorrectProgramId));
+        assert_eq!(assert_owned_by(&account, &program_id), Ok(()));
+
+        account.is_signer = true;
+        account.is_writable = true;
+        assert_eq!(assert_signer(&account), Ok(()));
+        assert_eq!(assert_writable(&account), Ok(()));
+
+        assert_eq!(assert_initialized(&State(false)), Err(ProgramError::UninitializedAccount));
+        assert_eq!(assert_initialized(&State(true)), Ok(()));
+        assert_eq!(assert_uninitialized(&State(true)), Err(ProgramError::AccountAlreadyInitialized));
+        assert_eq!(assert_uninitialized(&State(false)), Ok(()));
+    }
+
+    #[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq)]
+    struct Tagged {
+        discriminator: [u8; 8],
+        value: u64,
+    }
+
+    impl Discriminator for Tagged {
+        const DISCRIMINATOR: [u8; 8] = *b"tagged\0\0";
+    }
+
+    #[test]
+    fn test_load_account() {
+        let state = Tagged { discriminator: Tagged::DISCRIMINATOR, value: 7 };
+        let mut data = state.try_to_vec().unwrap();
+        data.resize(32, 0);
+        assert_eq!(load_account::<Tagged>(&data), Ok(state));
+
+        // Never written
+        assert_eq!(load_account::<Tagged>(&[0; 16]).unwrap().value, 0);
+
+        data[..8].copy_from_slice(b"other\0\0\0");
+        assert_eq!(load_account::<Tagged>(&data), Err(ProgramError::InvalidAccountData));
+        assert_eq!(load_account::<Tagged>(&[]), Err(ProgramError::InvalidAccountData));
+    }
+
+    #[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq)]
+    struct Happened {
+        value: u64,
+    }
+
+    impl Event for Happened {
+        const DISCRIMINATOR: [u8; 8] = *b"happened";
+    }
+
+    #[test]
+    fn test_event_data() {
+        let data = event_data(&Happened { value: 7 }).unwrap();
+        assert_eq!(&data[..8], b"happened");
+        assert_eq!(Happened::try_from_slice(&data[8..]).unwrap(), Happened { value: 7 });
+        assert_eq!(emit_event!(Happened { value: 7 }), Ok(()));
+    }
+
+    #[cfg(feature = "zero-copy")]
+    #[test]
+    fn test_load_pod() {
+        #[repr(C)]
+        #[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+        struct TaggedPod {
+            discriminator: [u8; 8],
+            value: u64,
+        }
+
+        impl Discriminator for TaggedPod {
+            const DISCRIMINATOR: [u8; 8] = *b"tagpod\0\0";
+        }
+
+        // Backed by u64s so the data is 8-byte aligned like on-chain data
+        let mut backing = [0u64; 3];
+        let data: &mut [u8] = bytemuck::cast_slice_mut(&mut backing);
+        load_pod_mut::<TaggedPod>(data).unwrap().value = 7;
+        assert_eq!(load_pod::<TaggedPod>(data).unwrap().value, 7);
+
+        assert_eq!(load_pod::<TaggedPod>(&data[..12]).err(), Some(ProgramError::AccountDataTooSmall));
+        data[..8].copy_from_slice(b"other\0\0\0");
+        assert_eq!(load_pod::<TaggedPod>(data).err(), Some(ProgramError::InvalidAccountData));
+        assert_eq!(convert_discriminator::<TaggedPod>(*b"other\0\0\0"), TaggedPod::DISCRIMINATOR);
+        assert_eq!(convert_discriminator::<TaggedPod>([0; 8]), [0; 8]);
+    }
+
+    #[test]
+    fn test_versions() {
+        #[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq)]
+        struct Versions {
+            discriminator: [u8; 8],
+            version: u8,
+            is_initialized: bool,
+            value: u64,
+        }
+
+        impl Discriminator for Versions {
+            const DISCRIMINATOR: [u8; 8] = *b"versions";
+        }
+
+        impl Versioned for Versions {
+            const VERSION: u8 = 2;
+            const SPACE: usize = 8 + 1 + 1 + 8;
+
+            fn migrate_in_place(data: &mut [u8]) -> ProgramResult {
+                insert_version_byte(data, Self::VERSION)
+            }
+        }
+
+        // The unversioned layout: no version byte, is_initialized in its place
+        let mut data = b"versions".to_vec();
+        data.push(1);
+        data.extend(u64::MAX.to_le_bytes());
+        assert_eq!(load_versioned::<Versions>(&data), Err(ProgramError::InvalidAccountData));
+        assert_eq!(insert_version_byte(&mut data, 2), Err(ProgramError::AccountDataTooSmall));
+
+        data.resize(Versions::SPACE, 0);
+        Versions::migrate_in_place(&mut data).unwrap();
+        let state = load_versioned::<Versions>(&data).unwrap();
+        assert_eq!((state.version, state.is_initialized, state.value), (2, true, u64::MAX));
+        // Never written
+        assert_eq!(load_versioned::<Versions>(&[0; Versions::SPACE]).unwrap().version, 0);
+    }
+
+    #[test]
+    fn test_assert_pda() {
+        let program_id = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let seeds: &[&[u8]] = &[b"counter", owner.as_ref()];
+        let (pda, bump) = Pubkey::find_program_address(seeds, &program_id);
+        let mut test_account = TestAccount::new(pda).writable().owner(program_id);
+        let account = test_account.info();
+
+        assert_eq!(assert_pda(&account, seeds, &program_id), Ok(bump));
+        assert_eq!(assert_pda(&account, &[b"counter"], &program_id), Err(ProgramError::InvalidSeeds));
+        assert_eq!(assert_pda(&account, seeds, &Pubkey::new_unique()), Err(ProgramError::InvalidSeeds));
+    }
+
+    #[test]
+    fn test_transfer_lamports() {
+        let program_id = Pubkey::new_unique();
+        let mut from = TestAccount::new(Pubkey::new_unique()).writable().lamports(100).owner(program_id);
+        let mut to = TestAccount::new(Pubkey::new_unique()).writable().lamports(u64::MAX - 50).owner(program_id);
+        let (from, to) = (from.info(), to.info());
+
+        assert_eq!(transfer_lamports(&from, &to, 101), Err(ProgramError::InsufficientFunds));
+        assert_eq!(transfer_lamports(&from, &to, 60), Err(ProgramError::ArithmeticOverflow));
+        assert_eq!(transfer_lamports(&from, &to, 50), Ok(()));
+        assert_eq!((from.lamports(), to.lamports()), (50, u64::MAX));
+        // Paying an account to itself is a no-op
+        assert_eq!(transfer_lamports(&from, &from, 50), Ok(()));
+        assert_eq!(from.lamports(), 50);
+    }
+
+    /// Serves the default Rent sysvar and performs the system program's
+    /// `Transfer`, so the rent helpers can run off-chain
+    struct RentStubs;
+
+    impl solana_program::program_stubs::SyscallStubs for RentStubs {
+        fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+            unsafe { *(var_addr as *mut Rent) = Rent::default() };
+            solana_program::entrypoint::SUCCESS
+        }
+
+        fn sol_invoke_signed(
+            &self,
+            instruction: &solana_program::instruction::Instruction,
+            account_infos: &[AccountInfo],
+            _signers_seeds: &[&[&[u8]]],
+        ) -> ProgramResult {
+            let amount = u64::from_le_bytes(instruction.data[4..12].try_into().unwrap());
+            transfer_lamports(&account_infos[0], &account_infos[1], amount)
+        }
+    }
+
+    #[test]
+    fn test_rent_exempt() {
+        solana_program::program_stubs::set_syscall_stubs(Box::new(RentStubs));
+        let minimum = Rent::default().minimum_balance(16);
+        assert_eq!(rent_exempt_minimum(16), Ok(minimum));
+
+        let owner = Pubkey::new_unique();
+        let mut payer = TestAccount::new(Pubkey::new_unique()).signer().writable().lamports(10 * minimum).owner(owner);
+        let mut account =
+            TestAccount::new(Pubkey::new_unique()).writable().data_len(16).lamports(minimum - 1).owner(owner);
+        let (payer, account) = (payer.info(), account.info());
+
+        assert_eq!(assert_rent_exempt(&account), Err(ProgramError::AccountNotRentExempt));
+        top_up_rent(&account, &payer, &payer, 16).unwrap();
+        assert_eq!(assert_rent_exempt(&account), Ok(()));
+        assert_eq!((account.lamports(), payer.lamports()), (minimum, 10 * minimum - 1));
+
+        // Already enough: nothing moves
+        top_up_rent(&account, &payer, &payer, 8).unwrap();
+        assert_eq!(account.lamports(), minimum);
+    }
+
+    #[derive(Debug, PartialEq)]
+    enum TestInstruction {
+        Unit,
+        Fields { flag: bool, amount: Option<u64> },
+        Text { text: String },
+    }
+
+    tagged_instruction! {
+        TestInstruction {
+            0 => Unit,
+            7 => Fields { flag, amount },
+            2 => Text { text },
+        }
+    }
+
+    impl VersionedInstruction for TestInstruction {}
+
+    /// `TestInstruction` in a second format, in which `Fields` gained a
+    /// `memo`, still accepting the first from clients that haven't upgraded
+    #[derive(Debug, PartialEq)]
+    enum TestInstructionV2 {
+        Unit,
+        Fields { flag: bool, amount: Option<u64>, memo: u8 },
+        Text { text: String },
+    }
+
+    tagged_instruction! {
+        TestInstructionV2 {
+            0 => Unit,
+            7 => Fields { flag, amount, memo },
+            2 => Text { text },
+        }
+    }
+
+    impl From<TestInstruction> for TestInstructionV2 {
+        fn from(instruction: TestInstruction) -> Self {
+            match instruction {
+                TestInstruction::Unit => Self::Unit,
+                TestInstruction::Fields { flag, amount } => Self::Fields { flag, amount, memo: 0 },
+                TestInstruction::Text { text } => Self::Text { text },
+            }
+        }
+    }
+
+    impl VersionedInstruction for TestInstructionV2 {
+        const FORMAT: u8 = 2;
+
+        fn decode(format: u8, data: &[u8]) -> Result<Self, ProgramError> {
+            match format {
+                1 => decode_borsh::<TestInstruction>(data).map(Self::from),
+                2 => decode_borsh(data),
+                _ => Err(unsupported_format::<Self>(format)),
+            }
+        }
+    }
+
+    #[test]
+    fn test_tagged_instruction() {
+        let fields = TestInstruction::Fields { flag: true, amount: Some(5) };
+        assert_eq!(fields.try_to_vec().unwrap(), [7, 1, 1, 5, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(TestInstruction::Unit.try_to_vec().unwrap(), [0]);
+        assert_eq!(TestInstruction::Text { text: "gm".to_string() }.tag(), 2);
+
+        for instruction in [TestInstruction::Unit, fields, TestInstruction::Text { text: "gm".to_string() }] {
+            let data = instruction.try_to_vec().unwrap();
+            assert_eq!(TestInstruction::try_from_slice(&data).unwrap(), instruction);
+        }
+
+        // Tags are not positions
+        assert!(TestInstruction::try_from_slice(&[1]).is_err());
+    }
+
+    #[test]
+    fn test_versioned_instruction() {
+        let v1 = TestInstruction::Fields { flag: true, amount: None };
+        assert_eq!(v1.pack(), [1, 7, 1, 0]);
+        assert_eq!(TestInstruction::unpack(&v1.pack()), Ok(v1));
+        assert_eq!(TestInstruction::unpack(&[2, 0]), Err(ProgramError::InvalidInstructionData));
+        assert_eq!(TestInstruction::unpack(&[]), Err(ProgramError::InvalidInstructionData));
+        assert_eq!(TestInstruction::unpack(&[1, 0, 0]), Err(ProgramError::InvalidInstructionData));
+
+        // While clients migrate, both formats decode
+        let v2 = TestInstructionV2::Fields { flag: true, amount: None, memo: 3 };
+        assert_eq!(v2.pack(), [2, 7, 1, 0, 3]);
+        assert_eq!(TestInstructionV2::unpack(&v2.pack()), Ok(v2));
+        assert_eq!(
+            TestInstructionV2::unpack(&[1, 7, 1, 0]),
+            Ok(TestInstructionV2::Fields { flag: true, amount: None, memo: 0 })
+        );
+        assert_eq!(TestInstructionV2::unpack(&[3, 0]), Err(ProgramError::InvalidInstructionData));
+    }
+}
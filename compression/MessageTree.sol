@@ -0,0 +1,116 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+/**
+ * @title MessageTree
+ * @dev Keeps messages out of storage: each message is emitted in an event and
+ * only its hash is added to an append-only Merkle tree. Anyone holding the
+ * message and a proof can verify it against a recent root.
+ */
+contract MessageTree {
+    uint256 public constant MAX_MESSAGE_LENGTH = 280;
+    uint32 public constant ROOT_HISTORY_SIZE = 64;
+
+    uint32 public immutable depth;
+    uint32 public nextIndex;
+    uint32 public currentRootIndex;
+
+    // Hash of an empty subtree at each level
+    bytes32[] public zeros;
+    // Left-hand node at each level on the path of the most recent leaf
+    bytes32[] public filledSubtrees;
+    // Recent roots, so proofs fetched before other appends still verify
+    bytes32[ROOT_HISTORY_SIZE] public roots;
+
+    event MessagePosted(
+        uint32 indexed index,
+        address indexed author,
+        string message,
+        bytes32 leaf,
+        bytes32 root
+    );
+
+    constructor(uint32 _depth) {
+        require(_depth > 0 && _depth <= 30, "Invalid depth");
+        depth = _depth;
+
+        bytes32 zero = bytes32(0);
+        for (uint32 i = 0; i < _depth; i++) {
+            zeros.push(zero);
+            filledSubtrees.push(zero);
+            zero = hashPair(zero, zero);
+        }
+        roots[0] = zero;
+    }
+
+    /**
+     * @dev Log `message` and append its hash to the tree
+     */
+    function postMessage(string calldata message) external returns (uint32 index) {
+        uint256 length = bytes(message).length;
+        require(length > 0 && length <= MAX_MESSAGE_LENGTH, "Invalid message length");
+        require(nextIndex < uint32(1) << depth, "Tree is full");
+
+        index = nextIndex;
+        bytes32 leaf = leafHash(msg.sender, index, message);
+
+        bytes32 node = leaf;
+        uint32 position = index;
+        for (uint32 level = 0; level < depth; level++) {
+            if (position % 2 == 0) {
+                filledSubtrees[level] = node;
+                node = hashPair(node, zeros[level]);
+            } else {
+                node = hashPair(filledSubtrees[level], node);
+            }
+            position /= 2;
+        }
+
+        currentRootIndex = (currentRootIndex + 1) % ROOT_HISTORY_SIZE;
+        roots[currentRootIndex] = node;
+        nextIndex = index + 1;
+
+        emit MessagePosted(index, msg.sender, message, leaf, node);
+    }
+
+    /**
+     * @dev Check that `message` was posted by `author` at `index`, given a
+     * proof ordered from the leaf's sibling upward
+     */
+    function verifyMessage(
+        bytes32 root,
+        address author,
+        uint32 index,
+        string calldata message,
+        bytes32[] calldata proof
+    ) external view returns (bool) {
+        if (proof.length != depth || !isKnownRoot(root)) {
+            return false;
+        }
+
+        bytes32 node = leafHash(author, index, message);
+        uint32 position = index;
+        for (uint256 level = 0; level < proof.length; level++) {
+            node = position % 2 == 0 ? hashPair(node, proof[level]) : hashPair(proof[level], node);
+            position /= 2;
+        }
+        return node == root;
+    }
+
+    function isKnownRoot(bytes32 root) public view returns (bool) {
+        for (uint32 i = 0; i < ROOT_HISTORY_SIZE; i++) {
+            if (roots[i] == root && root != bytes32(0)) {
+                return true;
+            }
+        }
+        return false;
+    }
+
+    function leafHash(address author, uint32 index, string calldata message) public pure returns (bytes32) {
+        return keccak256(abi.encodePacked(author, index, message));
+    }
+
+    function hashPair(bytes32 left, bytes32 right) internal pure returns (bytes32) {
+        return keccak256(abi.encodePacked(left, right));
+    }
+}
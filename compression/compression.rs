@@ -0,0 +1,473 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint,
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    keccak,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    pubkey,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// SPL Account Compression program, owner of every concurrent Merkle tree
+pub const SPL_ACCOUNT_COMPRESSION_ID: Pubkey = pubkey!("cmtDvXumGCrqC1Age74AVPhSRVXJMd8PJS91L8KbNCK");
+
+/// SPL Noop program, used to put leaf data into the transaction's inner instructions
+pub const SPL_NOOP_ID: Pubkey = pubkey!("noopb9bkMVfRPU8AsbpTUg8AQkHtKwMYZiFUjNRtMmV");
+
+/// Anchor instruction discriminators of the compression program: sha256("global:<name>")[..8]
+pub const INIT_EMPTY_MERKLE_TREE_DISCRIMINATOR: [u8; 8] = [191, 11, 119, 7, 180, 107, 220, 110];
+pub const APPEND_DISCRIMINATOR: [u8; 8] = [149, 120, 18, 222, 236, 225, 88, 203];
+pub const VERIFY_LEAF_DISCRIMINATOR: [u8; 8] = [124, 220, 22, 223, 104, 10, 250, 224];
+
+/// Seed prefix for tree config PDAs: ["tree_config", merkle_tree]. The PDA is
+/// also the tree's authority, so only this program can append to it.
+pub const TREE_CONFIG_SEED: &[u8] = b"tree_config";
+
+/// Longest message, in bytes, that can be posted
+pub const MAX_MESSAGE_LEN: usize = 280;
+
+/// Deepest tree supported by the compression program (2^30 leaves)
+pub const MAX_TREE_DEPTH: u32 = 30;
+
+/// Size of a `TreeConfig`
+pub const TREE_CONFIG_SIZE: usize = 1 // is_initialized
+    + 32 // creator
+    + 32 // merkle_tree
+    + 4 // capacity
+    + 4 // message_count
+    + 1; // bump
+
+/// Size of the compression program's header in front of the tree
+const TREE_HEADER_SIZE: usize = 56;
+
+/// Errors returned by the program as `ProgramError::Custom` codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompressionError {
+    /// Tree depth or buffer size is out of range
+    InvalidParameters = 0,
+    /// Message is empty or longer than `MAX_MESSAGE_LEN`
+    MessageTooLong = 1,
+    /// Every leaf of the tree is used
+    TreeFull = 2,
+    /// The tree config has not been initialized
+    NotInitialized = 3,
+    /// The Merkle tree account does not belong to this config
+    InvalidTree = 4,
+}
+
+impl From<CompressionError> for ProgramError {
+    fn from(e: CompressionError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+/// Program-side record of a message tree. Messages themselves are never
+/// stored in an account; only their hashes are folded into the tree's root.
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct TreeConfig {
+    pub is_initialized: bool,
+    pub creator: Pubkey,
+    pub merkle_tree: Pubkey,
+    /// 2^max_depth
+    pub capacity: u32,
+    /// Also the leaf index of the next message
+    pub message_count: u32,
+    pub bump: u8,
+}
+
+/// A posted message, as logged through the noop program for indexers
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct MessageLeaf {
+    pub author: Pubkey,
+    pub index: u32,
+    pub message: String,
+}
+
+impl MessageLeaf {
+    /// The 32-byte leaf stored in the tree
+    pub fn hash(&self) -> [u8; 32] {
+        keccak::hashv(&[self.author.as_ref(), &self.index.to_le_bytes(), self.message.as_bytes()]).to_bytes()
+    }
+}
+
+/// Bytes a tree account needs, to be allocated by the client before `CreateTree`.
+/// Trees of any useful size exceed the 10KB limit on accounts created through CPI.
+pub fn merkle_tree_account_size(max_depth: u32, max_buffer_size: u32, canopy_depth: u32) -> usize {
+    let depth = max_depth as usize;
+    let path = depth * 32 + 32 + 4 + 4; // proof nodes, leaf, index, padding
+    let change_log = 32 + depth * 32 + 4 + 4; // root, path nodes, index, padding
+    let tree = 8 + 8 + 8 // sequence_number, active_index, buffer_size
+        + max_buffer_size as usize * change_log
+        + path; // rightmost proof
+    let canopy = ((1usize << (canopy_depth + 1)) - 2) * 32;
+    TREE_HEADER_SIZE + tree + canopy
+}
+
+/// Build the compression program's `init_empty_merkle_tree` instruction
+pub fn init_empty_merkle_tree_instruction(
+    merkle_tree: &Pubkey,
+    authority: &Pubkey,
+    max_depth: u32,
+    max_buffer_size: u32,
+) -> Instruction {
+    let mut data = INIT_EMPTY_MERKLE_TREE_DISCRIMINATOR.to_vec();
+    data.extend_from_slice(&max_depth.to_le_bytes());
+    data.extend_from_slice(&max_buffer_size.to_le_bytes());
+    Instruction {
+        program_id: SPL_ACCOUNT_COMPRESSION_ID,
+        accounts: vec![
+            AccountMeta::new(*merkle_tree, false),
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new_readonly(SPL_NOOP_ID, false),
+        ],
+        data,
+    }
+}
+
+/// Build the compression program's `append` instruction
+pub fn append_instruction(merkle_tree: &Pubkey, authority: &Pubkey, leaf: [u8; 32]) -> Instruction {
+    let mut data = APPEND_DISCRIMINATOR.to_vec();
+    data.extend_from_slice(&leaf);
+    Instruction {
+        program_id: SPL_ACCOUNT_COMPRESSION_ID,
+        accounts: vec![
+            AccountMeta::new(*merkle_tree, false),
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new_readonly(SPL_NOOP_ID, false),
+        ],
+        data,
+    }
+}
+
+/// Build the compression program's `verify_leaf` instruction. Proof nodes are
+/// passed as account keys, ordered from the leaf's sibling upward.
+pub fn verify_leaf_instruction(
+    merkle_tree: &Pubkey,
+    root: [u8; 32],
+    leaf: [u8; 32],
+    index: u32,
+    proof: &[Pubkey],
+) -> Instruction {
+    let mut data = VERIFY_LEAF_DISCRIMINATOR.to_vec();
+    data.extend_from_slice(&root);
+    data.extend_from_slice(&leaf);
+    data.extend_from_slice(&index.to_le_bytes());
+    let mut accounts = vec![AccountMeta::new_readonly(*merkle_tree, false)];
+    accounts.extend(proof.iter().map(|node| AccountMeta::new_readonly(*node, false)));
+    Instruction {
+        program_id: SPL_ACCOUNT_COMPRESSION_ID,
+        accounts,
+        data,
+    }
+}
+
+entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = CompressionInstruction::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        CompressionInstruction::CreateTree { max_depth, max_buffer_size } => {
+            create_tree(program_id, accounts, max_depth, max_buffer_size)
+        }
+        CompressionInstruction::PostMessage { message } => post_message(program_id, accounts, message),
+        CompressionInstruction::VerifyMessage { root, leaf } => verify_message(program_id, accounts, root, leaf),
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub enum CompressionInstruction {
+    /// Initialize a client-allocated tree account with the config PDA as its authority
+    /// Accounts: [writable, signer] creator, [writable] tree config PDA,
+    /// [writable] Merkle tree (owned by the compression program), [] noop program,
+    /// [] compression program, [] system program
+    CreateTree { max_depth: u32, max_buffer_size: u32 },
+    /// Log the message through the noop program and append its hash to the tree
+    /// Accounts: [signer] author, [writable] tree config PDA, [writable] Merkle tree,
+    /// [] noop program, [] compression program
+    PostMessage { message: String },
+    /// Check a message against a root of the tree, failing the transaction if the proof is wrong
+    /// Accounts: [] tree config PDA, [] Merkle tree, [] compression program, then one
+    /// account per proof node
+    VerifyMessage { root: [u8; 32], leaf: MessageLeaf },
+}
+
+fn create_tree(program_id: &Pubkey, accounts: &[AccountInfo], max_depth: u32, max_buffer_size: u32) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let creator = next_account_info(accounts_iter)?;
+    let config_account = next_account_info(accounts_iter)?;
+    let merkle_tree = next_account_info(accounts_iter)?;
+    let noop_program = next_account_info(accounts_iter)?;
+    let compression_program = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !creator.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    check_programs(noop_program, compression_program)?;
+
+    // The compression program only accepts a few buffer sizes per depth and
+    // rejects the rest, so only the depth is checked here
+    if max_depth == 0 || max_depth > MAX_TREE_DEPTH || max_buffer_size == 0 {
+        return Err(CompressionError::InvalidParameters.into());
+    }
+
+    if merkle_tree.owner != &SPL_ACCOUNT_COMPRESSION_ID {
+        msg!("Merkle tree account must be allocated and owned by the compression program");
+        return Err(CompressionError::InvalidTree.into());
+    }
+
+    let (expected_config, bump) =
+        Pubkey::find_program_address(&[TREE_CONFIG_SEED, merkle_tree.key.as_ref()], program_id);
+    if expected_config != *config_account.key {
+        msg!("Tree config account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let config_seeds: &[&[u8]] = &[TREE_CONFIG_SEED, merkle_tree.key.as_ref(), &[bump]];
+
+    invoke_signed(
+        &system_instruction::create_account(
+            creator.key,
+            config_account.key,
+            Rent::get()?.minimum_balance(TREE_CONFIG_SIZE),
+            TREE_CONFIG_SIZE as u64,
+            program_id,
+        ),
+        &[creator.clone(), config_account.clone(), system_program.clone()],
+        &[config_seeds],
+    )?;
+
+    invoke_signed(
+        &init_empty_merkle_tree_instruction(merkle_tree.key, config_account.key, max_depth, max_buffer_size),
+        &[merkle_tree.clone(), config_account.clone(), noop_program.clone()],
+        &[config_seeds],
+    )?;
+
+    let config = TreeConfig {
+        is_initialized: true,
+        creator: *creator.key,
+        merkle_tree: *merkle_tree.key,
+        capacity: 1 << max_depth,
+        message_count: 0,
+        bump,
+    };
+    config.serialize(&mut &mut config_account.data.borrow_mut()[..])?;
+
+    msg!("Tree {} created with room for {} messages", merkle_tree.key, config.capacity);
+
+    Ok(())
+}
+
+fn post_message(program_id: &Pubkey, accounts: &[AccountInfo], message: String) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let author = next_account_info(accounts_iter)?;
+    let config_account = next_account_info(accounts_iter)?;
+    let merkle_tree = next_account_info(accounts_iter)?;
+    let noop_program = next_account_info(accounts_iter)?;
+    let compression_program = next_account_info(accounts_iter)?;
+
+    if !author.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    check_programs(noop_program, compression_program)?;
+
+    let mut config = load_tree_config(program_id, config_account, merkle_tree)?;
+
+    if message.is_empty() || message.len() > MAX_MESSAGE_LEN {
+        msg!("Message must be 1 to {} bytes, got {}", MAX_MESSAGE_LEN, message.len());
+        return Err(CompressionError::MessageTooLong.into());
+    }
+
+    if config.message_count >= config.capacity {
+        return Err(CompressionError::TreeFull.into());
+    }
+
+    let leaf = MessageLeaf {
+        author: *author.key,
+        index: config.message_count,
+        message,
+    };
+
+    // The tree only keeps the hash; logging the full leaf through a CPI puts it
+    // in the transaction's inner instructions, where indexers can read it back
+    // without the log truncation that affects msg!
+    invoke(
+        &Instruction {
+            program_id: SPL_NOOP_ID,
+            accounts: vec![],
+            data: leaf.try_to_vec()?,
+        },
+        std::slice::from_ref(noop_program),
+    )?;
+
+    invoke_signed(
+        &append_instruction(merkle_tree.key, config_account.key, leaf.hash()),
+        &[merkle_tree.clone(), config_account.clone(), noop_program.clone()],
+        &[&[TREE_CONFIG_SEED, merkle_tree.key.as_ref(), &[config.bump]]],
+    )?;
+
+    config.message_count += 1;
+    config.serialize(&mut &mut config_account.data.borrow_mut()[..])?;
+
+    msg!("Message {} posted by {}", leaf.index, author.key);
+
+    Ok(())
+}
+
+fn verify_message(program_id: &Pubkey, accounts: &[AccountInfo], root: [u8; 32], leaf: MessageLeaf) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let config_account = next_account_info(accounts_iter)?;
+    let merkle_tree = next_account_info(accounts_iter)?;
+    let compression_program = next_account_info(accounts_iter)?;
+    let proof_nodes: Vec<AccountInfo> = accounts_iter.cloned().collect();
+
+    if *compression_program.key != SPL_ACCOUNT_COMPRESSION_ID {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let config = load_tree_config(program_id, config_account, merkle_tree)?;
+
+    if leaf.index >= config.message_count {
+        msg!("Message {} has not been posted; the tree holds {}", leaf.index, config.message_count);
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    // The compression program accepts any root still in the tree's change log
+    // buffer, so proofs fetched shortly before other appends still verify
+    let proof: Vec<Pubkey> = proof_nodes.iter().map(|node| *node.key).collect();
+    let mut account_infos = vec![merkle_tree.clone()];
+    account_infos.extend(proof_nodes);
+
+    invoke(
+        &verify_leaf_instruction(merkle_tree.key, root, leaf.hash(), leaf.index, &proof),
+        &account_infos,
+    )?;
+
+    msg!("Message {} by {} verified", leaf.index, leaf.author);
+
+    Ok(())
+}
+
+/// Validate the tree config and that it belongs to `merkle_tree`, returning the config
+fn load_tree_config(
+    program_id: &Pubkey,
+    config_account: &AccountInfo,
+    merkle_tree: &AccountInfo,
+) -> Result<TreeConfig, ProgramError> {
+    if config_account.owner != program_id {
+        msg!("Tree config account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let config = TreeConfig::try_from_slice(&config_account.data.borrow())?;
+
+    if !config.is_initialized {
+        return Err(CompressionError::NotInitialized.into());
+    }
+
+    if config.merkle_tree != *merkle_tree.key {
+        msg!("Merkle tree does not belong to this config");
+        return Err(CompressionError::InvalidTree.into());
+    }
+
+    Ok(config)
+}
+
+/// Make sure the noop and compression programs are the real ones
+fn check_programs(noop_program: &AccountInfo, compression_program: &AccountInfo) -> ProgramResult {
+    if *noop_program.key != SPL_NOOP_ID || *compression_program.key != SPL_ACCOUNT_COMPRESSION_ID {
+        msg!("Expected the SPL noop and account compression programs");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_program::{clock::Epoch, hash::hash};
+
+    #[test]
+    fn test_tree_size_discriminators_and_leaf_hash() {
+        // Depth 14 with a 64-entry buffer holds 16,384 leaves in 31,800 bytes
+        assert_eq!(merkle_tree_account_size(14, 64, 0), 31_800);
+        assert_eq!(merkle_tree_account_size(14, 64, 10), 31_800 + 2046 * 32);
+
+        assert_eq!(hash(b"global:init_empty_merkle_tree").to_bytes()[..8], INIT_EMPTY_MERKLE_TREE_DISCRIMINATOR);
+        assert_eq!(hash(b"global:append").to_bytes()[..8], APPEND_DISCRIMINATOR);
+        assert_eq!(hash(b"global:verify_leaf").to_bytes()[..8], VERIFY_LEAF_DISCRIMINATOR);
+
+        // The same text at another index is a different leaf
+        let leaf = MessageLeaf {
+            author: Pubkey::new_unique(),
+            index: 0,
+            message: "gm".to_string(),
+        };
+        let repost = MessageLeaf { index: 1, ..leaf.clone() };
+        assert_ne!(leaf.hash(), repost.hash());
+
+        let ix = verify_leaf_instruction(&Pubkey::new_unique(), [1; 32], leaf.hash(), 5, &[Pubkey::new_unique()]);
+        assert_eq!(ix.data.len(), 8 + 32 + 32 + 4);
+        assert_eq!(ix.data[72..], 5u32.to_le_bytes());
+        assert_eq!(ix.accounts.len(), 2);
+    }
+
+    #[test]
+    fn test_post_message_rejects_oversized_message() {
+        let program_id = Pubkey::new_unique();
+        let merkle_tree_key = Pubkey::new_unique();
+
+        let config = TreeConfig {
+            is_initialized: true,
+            creator: Pubkey::new_unique(),
+            merkle_tree: merkle_tree_key,
+            capacity: 1 << 14,
+            message_count: 0,
+            bump: 255,
+        };
+
+        let keys = [
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            merkle_tree_key,
+            SPL_NOOP_ID,
+            SPL_ACCOUNT_COMPRESSION_ID,
+        ];
+        let mut lamports: Vec<u64> = vec![10_000; keys.len()];
+        let mut data: Vec<Vec<u8>> = vec![vec![]; keys.len()];
+        data[1] = config.try_to_vec().unwrap();
+
+        let accounts: Vec<AccountInfo> = keys
+            .iter()
+            .zip(lamports.iter_mut())
+            .zip(data.iter_mut())
+            .enumerate()
+            .map(|(i, ((key, lamports), data))| {
+                AccountInfo::new(key, i == 0, true, lamports, data, &program_id, false, Epoch::default())
+            })
+            .collect();
+
+        let instruction_data = CompressionInstruction::PostMessage {
+            message: "a".repeat(MAX_MESSAGE_LEN + 1),
+        }
+        .try_to_vec()
+        .unwrap();
+        let result = process_instruction(&program_id, &accounts, &instruction_data);
+        assert_eq!(result, Err(CompressionError::MessageTooLong.into()));
+    }
+}
@@ -0,0 +1,44 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+/**
+ * @title HashChain
+ * @dev Gas-heavy iterated hashing. On Ethereum the sender sets a gas limit and
+ * an EIP-1559 priority fee in the transaction itself; the contract can only
+ * observe what remains through gasleft().
+ */
+contract HashChain {
+    uint32 public constant MAX_ITERATIONS = 10_000;
+
+    struct Work {
+        bytes32 hash;
+        uint64 totalIterations;
+    }
+
+    mapping(address => Work) public works;
+
+    event Hashed(address indexed authority, uint32 iterations, uint64 totalIterations, uint256 gasUsed);
+
+    function initialize() external {
+        require(works[msg.sender].hash == bytes32(0), "Already initialized");
+        works[msg.sender].hash = bytes32(uint256(uint160(msg.sender)));
+    }
+
+    function hash(uint32 iterations) external {
+        require(iterations > 0 && iterations <= MAX_ITERATIONS, "Invalid iterations");
+        Work storage work = works[msg.sender];
+        require(work.hash != bytes32(0), "Not initialized");
+
+        uint256 gasBefore = gasleft();
+        bytes32 current = work.hash;
+        for (uint32 i = 0; i < iterations; i++) {
+            current = sha256(abi.encodePacked(current));
+        }
+        uint256 gasUsed = gasBefore - gasleft();
+
+        work.hash = current;
+        work.totalIterations += iterations;
+
+        emit Hashed(msg.sender, iterations, work.totalIterations, gasUsed);
+    }
+}
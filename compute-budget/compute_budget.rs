@@ -0,0 +1,258 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint,
+    entrypoint::ProgramResult,
+    hash::{hashv, Hash},
+    log::sol_log_compute_units,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// Seed prefix for work PDAs: ["work", authority]
+pub const WORK_SEED: &[u8] = b"work";
+
+/// Default limit when a transaction does not request one, per instruction
+pub const DEFAULT_COMPUTE_UNIT_LIMIT: u32 = 200_000;
+
+/// Most compute a transaction can request
+pub const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+/// Measured cost of the `Hash` handler outside the loop, with headroom
+pub const HASH_BASE_UNITS: u32 = 6_000;
+
+/// Measured cost of one loop iteration: the sha256 syscall plus loop overhead
+pub const UNITS_PER_ITERATION: u32 = 130;
+
+/// Largest `Hash` request that fits under `MAX_COMPUTE_UNIT_LIMIT`
+pub const MAX_ITERATIONS: u32 = 10_000;
+
+/// Size of a `WorkAccount`
+pub const WORK_ACCOUNT_SIZE: usize = 1 // is_initialized
+    + 32 // authority
+    + 32 // hash
+    + 8 // total_iterations
+    + 1; // bump
+
+/// Errors returned by the program as `ProgramError::Custom` codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ComputeError {
+    /// Iterations are zero or above `MAX_ITERATIONS`
+    InvalidIterations = 0,
+    /// The signer is not the work account's authority
+    Unauthorized = 1,
+    /// The work account has not been initialized
+    NotInitialized = 2,
+}
+
+impl From<ComputeError> for ProgramError {
+    fn from(e: ComputeError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+/// The running result of a hash chain
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct WorkAccount {
+    pub is_initialized: bool,
+    pub authority: Pubkey,
+    pub hash: [u8; 32],
+    pub total_iterations: u64,
+    pub bump: u8,
+}
+
+/// Hash `seed` repeatedly, `iterations` times
+pub fn hash_chain(seed: Hash, iterations: u32) -> Hash {
+    (0..iterations).fold(seed, |hash, _| hashv(&[hash.as_ref()]))
+}
+
+/// Compute units to request for `Hash { iterations }`
+pub fn estimated_compute_units(iterations: u32) -> u32 {
+    HASH_BASE_UNITS
+        .saturating_add(iterations.saturating_mul(UNITS_PER_ITERATION))
+        .min(MAX_COMPUTE_UNIT_LIMIT)
+}
+
+entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = ComputeInstruction::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        ComputeInstruction::Initialize => initialize(program_id, accounts),
+        ComputeInstruction::Hash { iterations } => hash(program_id, accounts, iterations),
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub enum ComputeInstruction {
+    /// Create a work account seeded with the authority's key
+    /// Accounts: [writable, signer] authority, [writable] work PDA, [] system program
+    Initialize,
+    /// Extend the hash chain by `iterations` (~130 CU each). More than ~1,500
+    /// iterations exceed the default 200,000 CU limit, so the transaction must
+    /// request a higher limit.
+    /// Accounts: [signer] authority, [writable] work PDA
+    Hash { iterations: u32 },
+}
+
+fn initialize(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let authority = next_account_info(accounts_iter)?;
+    let work_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let (expected_work, bump) = Pubkey::find_program_address(&[WORK_SEED, authority.key.as_ref()], program_id);
+    if expected_work != *work_account.key {
+        msg!("Work account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    invoke_signed(
+        &system_instruction::create_account(
+            authority.key,
+            work_account.key,
+            Rent::get()?.minimum_balance(WORK_ACCOUNT_SIZE),
+            WORK_ACCOUNT_SIZE as u64,
+            program_id,
+        ),
+        &[authority.clone(), work_account.clone(), system_program.clone()],
+        &[&[WORK_SEED, authority.key.as_ref(), &[bump]]],
+    )?;
+
+    let work = WorkAccount {
+        is_initialized: true,
+        authority: *authority.key,
+        hash: authority.key.to_bytes(),
+        total_iterations: 0,
+        bump,
+    };
+    work.serialize(&mut &mut work_account.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+fn hash(program_id: &Pubkey, accounts: &[AccountInfo], iterations: u32) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let authority = next_account_info(accounts_iter)?;
+    let work_account = next_account_info(accounts_iter)?;
+
+    if !authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if iterations == 0 || iterations > MAX_ITERATIONS {
+        msg!("Iterations must be between 1 and {}", MAX_ITERATIONS);
+        return Err(ComputeError::InvalidIterations.into());
+    }
+
+    let mut work = load_work(program_id, work_account)?;
+
+    if work.authority != *authority.key {
+        return Err(ComputeError::Unauthorized.into());
+    }
+
+    // Logs "Program consumption: <remaining> units remaining"; the difference
+    // between the two lines is the cost of the loop
+    sol_log_compute_units();
+    work.hash = hash_chain(Hash::new_from_array(work.hash), iterations).to_bytes();
+    sol_log_compute_units();
+
+    work.total_iterations = work.total_iterations.saturating_add(iterations as u64);
+    work.serialize(&mut &mut work_account.data.borrow_mut()[..])?;
+
+    msg!("Hashed {} times, {} in total", iterations, work.total_iterations);
+
+    Ok(())
+}
+
+/// Validate the work account and return it
+fn load_work(program_id: &Pubkey, work_account: &AccountInfo) -> Result<WorkAccount, ProgramError> {
+    if work_account.owner != program_id {
+        msg!("Work account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let work = WorkAccount::try_from_slice(&work_account.data.borrow())?;
+
+    if !work.is_initialized {
+        return Err(ComputeError::NotInitialized.into());
+    }
+
+    Ok(work)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_program::clock::Epoch;
+
+    #[test]
+    fn test_estimates_and_limits() {
+        assert_eq!(estimated_compute_units(1), 6_130);
+        // The default limit covers small requests only
+        assert!(estimated_compute_units(1_000) <= DEFAULT_COMPUTE_UNIT_LIMIT);
+        assert!(estimated_compute_units(2_000) > DEFAULT_COMPUTE_UNIT_LIMIT);
+        // The largest request still fits in a transaction
+        assert!(estimated_compute_units(MAX_ITERATIONS) < MAX_COMPUTE_UNIT_LIMIT);
+        assert_eq!(estimated_compute_units(u32::MAX), MAX_COMPUTE_UNIT_LIMIT);
+
+        let seed = Hash::new_unique();
+        assert_eq!(hash_chain(seed, 0), seed);
+        assert_eq!(hash_chain(hash_chain(seed, 2), 3), hash_chain(seed, 5));
+    }
+
+    #[test]
+    fn test_hash_updates_chain() {
+        let program_id = Pubkey::new_unique();
+        let authority_key = Pubkey::new_unique();
+
+        let work = WorkAccount {
+            is_initialized: true,
+            authority: authority_key,
+            hash: authority_key.to_bytes(),
+            total_iterations: 0,
+            bump: 255,
+        };
+
+        let keys = [authority_key, Pubkey::new_unique()];
+        let mut lamports: Vec<u64> = vec![0; keys.len()];
+        let mut data: Vec<Vec<u8>> = vec![vec![], work.try_to_vec().unwrap()];
+
+        let accounts: Vec<AccountInfo> = keys
+            .iter()
+            .zip(lamports.iter_mut())
+            .zip(data.iter_mut())
+            .enumerate()
+            .map(|(i, ((key, lamports), data))| {
+                AccountInfo::new(key, i == 0, true, lamports, data, &program_id, false, Epoch::default())
+            })
+            .collect();
+
+        let too_many = ComputeInstruction::Hash { iterations: MAX_ITERATIONS + 1 }.try_to_vec().unwrap();
+        let result = process_instruction(&program_id, &accounts, &too_many);
+        assert_eq!(result, Err(ComputeError::InvalidIterations.into()));
+
+        let instruction_data = ComputeInstruction::Hash { iterations: 10 }.try_to_vec().unwrap();
+        let result = process_instruction(&program_id, &accounts, &instruction_data);
+        assert!(result.is_ok());
+
+        let stored = WorkAccount::try_from_slice(&accounts[1].data.borrow()).unwrap();
+        assert_eq!(stored.total_iterations, 10);
+        assert_eq!(Hash::new_from_array(stored.hash), hash_chain(Hash::new_from_array(authority_key.to_bytes()), 10));
+    }
+}
@@ -0,0 +1,81 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+/**
+ * @title ProtocolConfig
+ * @dev Program-wide settings (admin, fee, pause flag) with a two-step admin
+ * transfer. On Ethereum the contract's own storage is the singleton.
+ */
+contract ProtocolConfig {
+    uint16 public constant BPS_DENOMINATOR = 10_000;
+    uint16 public constant MAX_FEE_BPS = 1_000;
+
+    address public admin;
+    address public pendingAdmin;
+    uint16 public feeBps;
+    bool public paused;
+
+    event FeeChanged(uint16 oldFeeBps, uint16 newFeeBps);
+    event PausedSet(bool paused);
+    event AdminTransferProposed(address indexed admin, address indexed pendingAdmin);
+    event AdminChanged(address indexed oldAdmin, address indexed newAdmin);
+
+    modifier onlyAdmin() {
+        require(msg.sender == admin, "Only admin");
+        _;
+    }
+
+    // Protocol functions that must stop while paused use this
+    modifier whenNotPaused() {
+        require(!paused, "Paused");
+        _;
+    }
+
+    // The deployer sets the admin in the same transaction that creates the
+    // contract, so there is no initialization to front-run
+    constructor(address _admin, uint16 _feeBps) {
+        require(_admin != address(0), "Invalid admin");
+        require(_feeBps <= MAX_FEE_BPS, "Fee too high");
+        admin = _admin;
+        feeBps = _feeBps;
+    }
+
+    /**
+     * @dev Protocol fee on `amount`, rounded down
+     */
+    function feeFor(uint256 amount) public view returns (uint256) {
+        return (amount * feeBps) / BPS_DENOMINATOR;
+    }
+
+    function setFee(uint16 _feeBps) external onlyAdmin {
+        require(_feeBps <= MAX_FEE_BPS, "Fee too high");
+        emit FeeChanged(feeBps, _feeBps);
+        feeBps = _feeBps;
+    }
+
+    function setPaused(bool _paused) external onlyAdmin {
+        paused = _paused;
+        emit PausedSet(_paused);
+    }
+
+    /**
+     * @dev Start an admin transfer; address(0) cancels a pending one
+     */
+    function proposeAdmin(address newAdmin) external onlyAdmin {
+        pendingAdmin = newAdmin;
+        emit AdminTransferProposed(admin, newAdmin);
+    }
+
+    /**
+     * @dev Complete the admin transfer. Requiring the new admin's own call
+     * proves the address is controlled by someone.
+     */
+    function acceptAdmin() external {
+        require(pendingAdmin != address(0), "No pending admin");
+        require(msg.sender == pendingAdmin, "Only pending admin");
+
+        emit AdminChanged(admin, msg.sender);
+        admin = msg.sender;
+        pendingAdmin = address(0);
+    }
+}
@@ -0,0 +1,388 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    bpf_loader_upgradeable,
+    entrypoint,
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// Seed of the program-wide config PDA: ["config"]
+pub const CONFIG_SEED: &[u8] = b"config";
+
+/// Fees are expressed in basis points
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Highest fee the admin can set (10%)
+pub const MAX_FEE_BPS: u16 = 1_000;
+
+/// Size of a `ConfigAccount`
+pub const CONFIG_ACCOUNT_SIZE: usize = 1 // is_initialized
+    + 32 // admin
+    + 1 + 32 // pending_admin
+    + 2 // fee_bps
+    + 1 // paused
+    + 1; // bump
+
+/// Errors returned by the program as `ProgramError::Custom` codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigError {
+    /// The signer is not the admin, or not the pending admin
+    Unauthorized = 0,
+    /// Fee is above `MAX_FEE_BPS`
+    InvalidFee = 1,
+    /// No admin transfer is in progress
+    NoPendingAdmin = 2,
+    /// The protocol is paused
+    Paused = 3,
+    /// The config has not been initialized
+    NotInitialized = 4,
+    /// The program data account is not this program's, or has no upgrade authority
+    InvalidProgramData = 5,
+}
+
+impl From<ConfigError> for ProgramError {
+    fn from(e: ConfigError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+/// Settings shared by every instruction of the program. There is exactly one,
+/// at a PDA derived from a constant seed.
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct ConfigAccount {
+    pub is_initialized: bool,
+    pub admin: Pubkey,
+    /// Proposed admin, who must accept before the transfer takes effect
+    pub pending_admin: Option<Pubkey>,
+    pub fee_bps: u16,
+    pub paused: bool,
+    pub bump: u8,
+}
+
+impl ConfigAccount {
+    /// Protocol fee on `amount`, rounded down
+    pub fn fee_for(&self, amount: u64) -> u64 {
+        (amount as u128 * self.fee_bps as u128 / BPS_DENOMINATOR as u128) as u64
+    }
+
+    /// Guard for instructions that must stop while the protocol is paused
+    pub fn require_active(&self) -> Result<(), ConfigError> {
+        if self.paused {
+            return Err(ConfigError::Paused);
+        }
+        Ok(())
+    }
+}
+
+/// Read the upgrade authority from this program's `ProgramData` account.
+/// Layout: u32 variant (3 = ProgramData), u64 slot, Option<Pubkey> authority.
+pub fn upgrade_authority(program_id: &Pubkey, program_data: &AccountInfo) -> Result<Pubkey, ProgramError> {
+    let (expected, _) = Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::id());
+    if expected != *program_data.key || *program_data.owner != bpf_loader_upgradeable::id() {
+        msg!("Not this program's program data account");
+        return Err(ConfigError::InvalidProgramData.into());
+    }
+
+    let data = program_data.data.borrow();
+    if data.len() < 45 || data[0..4] != 3u32.to_le_bytes() || data[12] != 1 {
+        msg!("Program is immutable or the program data is malformed");
+        return Err(ConfigError::InvalidProgramData.into());
+    }
+
+    Ok(Pubkey::new_from_array(data[13..45].try_into().unwrap()))
+}
+
+entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = ConfigInstruction::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        ConfigInstruction::Initialize { admin, fee_bps } => initialize(program_id, accounts, admin, fee_bps),
+        ConfigInstruction::SetFee { fee_bps } => set_fee(program_id, accounts, fee_bps),
+        ConfigInstruction::SetPaused { paused } => set_paused(program_id, accounts, paused),
+        ConfigInstruction::ProposeAdmin { new_admin } => propose_admin(program_id, accounts, new_admin),
+        ConfigInstruction::AcceptAdmin => accept_admin(program_id, accounts),
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub enum ConfigInstruction {
+    /// Create the config. Only the program's upgrade authority may do this, so
+    /// nobody can front-run the deployment and make themselves admin.
+    /// Accounts: [writable, signer] upgrade authority, [writable] config PDA,
+    /// [] program data account, [] system program
+    Initialize { admin: Pubkey, fee_bps: u16 },
+    /// Change the protocol fee
+    /// Accounts: [signer] admin, [writable] config PDA
+    SetFee { fee_bps: u16 },
+    /// Pause or unpause the protocol
+    /// Accounts: [signer] admin, [writable] config PDA
+    SetPaused { paused: bool },
+    /// Start an admin transfer; `None` cancels a pending one
+    /// Accounts: [signer] admin, [writable] config PDA
+    ProposeAdmin { new_admin: Option<Pubkey> },
+    /// Complete the admin transfer
+    /// Accounts: [signer] pending admin, [writable] config PDA
+    AcceptAdmin,
+}
+
+fn initialize(program_id: &Pubkey, accounts: &[AccountInfo], admin: Pubkey, fee_bps: u16) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let authority = next_account_info(accounts_iter)?;
+    let config_account = next_account_info(accounts_iter)?;
+    let program_data = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if upgrade_authority(program_id, program_data)? != *authority.key {
+        msg!("Only the upgrade authority can initialize the config");
+        return Err(ConfigError::Unauthorized.into());
+    }
+
+    if fee_bps > MAX_FEE_BPS {
+        return Err(ConfigError::InvalidFee.into());
+    }
+
+    // A constant seed gives one address per program: the singleton
+    let (expected_config, bump) = Pubkey::find_program_address(&[CONFIG_SEED], program_id);
+    if expected_config != *config_account.key {
+        msg!("Config account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    // Fails if the config already exists, so it can only be initialized once
+    invoke_signed(
+        &system_instruction::create_account(
+            authority.key,
+            config_account.key,
+            Rent::get()?.minimum_balance(CONFIG_ACCOUNT_SIZE),
+            CONFIG_ACCOUNT_SIZE as u64,
+            program_id,
+        ),
+        &[authority.clone(), config_account.clone(), system_program.clone()],
+        &[&[CONFIG_SEED, &[bump]]],
+    )?;
+
+    let config = ConfigAccount {
+        is_initialized: true,
+        admin,
+        pending_admin: None,
+        fee_bps,
+        paused: false,
+        bump,
+    };
+    config.serialize(&mut &mut config_account.data.borrow_mut()[..])?;
+
+    msg!("Config initialized: admin {}, fee {} bps", admin, fee_bps);
+
+    Ok(())
+}
+
+fn set_fee(program_id: &Pubkey, accounts: &[AccountInfo], fee_bps: u16) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let admin = next_account_info(accounts_iter)?;
+    let config_account = next_account_info(accounts_iter)?;
+
+    let mut config = load_config_as_admin(program_id, config_account, admin)?;
+
+    if fee_bps > MAX_FEE_BPS {
+        msg!("Fee of {} bps exceeds the maximum of {}", fee_bps, MAX_FEE_BPS);
+        return Err(ConfigError::InvalidFee.into());
+    }
+
+    msg!("Fee changed from {} to {} bps", config.fee_bps, fee_bps);
+    config.fee_bps = fee_bps;
+    config.serialize(&mut &mut config_account.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+fn set_paused(program_id: &Pubkey, accounts: &[AccountInfo], paused: bool) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let admin = next_account_info(accounts_iter)?;
+    let config_account = next_account_info(accounts_iter)?;
+
+    let mut config = load_config_as_admin(program_id, config_account, admin)?;
+
+    config.paused = paused;
+    config.serialize(&mut &mut config_account.data.borrow_mut()[..])?;
+
+    msg!("Protocol {}", if paused { "paused" } else { "unpaused" });
+
+    Ok(())
+}
+
+fn propose_admin(program_id: &Pubkey, accounts: &[AccountInfo], new_admin: Option<Pubkey>) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let admin = next_account_info(accounts_iter)?;
+    let config_account = next_account_info(accounts_iter)?;
+
+    let mut config = load_config_as_admin(program_id, config_account, admin)?;
+
+    // Nothing changes until the new admin signs, so a typo cannot lock the
+    // protocol; proposing again or proposing `None` replaces the pending admin
+    config.pending_admin = new_admin;
+    config.serialize(&mut &mut config_account.data.borrow_mut()[..])?;
+
+    match new_admin {
+        Some(new_admin) => msg!("Admin transfer to {} proposed", new_admin),
+        None => msg!("Admin transfer cancelled"),
+    }
+
+    Ok(())
+}
+
+fn accept_admin(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let new_admin = next_account_info(accounts_iter)?;
+    let config_account = next_account_info(accounts_iter)?;
+
+    if !new_admin.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut config = load_config(program_id, config_account)?;
+
+    match config.pending_admin {
+        None => return Err(ConfigError::NoPendingAdmin.into()),
+        Some(pending) if pending != *new_admin.key => {
+            msg!("Signer is not the pending admin");
+            return Err(ConfigError::Unauthorized.into());
+        }
+        Some(_) => {}
+    }
+
+    msg!("Admin changed from {} to {}", config.admin, new_admin.key);
+    config.admin = *new_admin.key;
+    config.pending_admin = None;
+    config.serialize(&mut &mut config_account.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+/// Validate the config account and return it. Other instructions of a
+/// protocol load the config this way before checking `paused` or using the fee.
+pub fn load_config(program_id: &Pubkey, config_account: &AccountInfo) -> Result<ConfigAccount, ProgramError> {
+    if config_account.owner != program_id {
+        msg!("Config account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    // Any account this program owns could be passed in, so make sure it is the singleton
+    let (expected_config, _) = Pubkey::find_program_address(&[CONFIG_SEED], program_id);
+    if expected_config != *config_account.key {
+        msg!("Config account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    // pending_admin makes the encoding shorter than the account when unset
+    let config = ConfigAccount::deserialize(&mut &config_account.data.borrow()[..])?;
+
+    if !config.is_initialized {
+        return Err(ConfigError::NotInitialized.into());
+    }
+
+    Ok(config)
+}
+
+/// Load the config and check `admin` signed and is the admin
+fn load_config_as_admin(
+    program_id: &Pubkey,
+    config_account: &AccountInfo,
+    admin: &AccountInfo,
+) -> Result<ConfigAccount, ProgramError> {
+    if !admin.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let config = load_config(program_id, config_account)?;
+
+    if config.admin != *admin.key {
+        msg!("Only the admin can change the config");
+        return Err(ConfigError::Unauthorized.into());
+    }
+
+    Ok(config)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_program::clock::Epoch;
+
+    #[test]
+    fn test_fee_and_pause_guard() {
+        let mut config = ConfigAccount {
+            is_initialized: true,
+            admin: Pubkey::new_unique(),
+            pending_admin: None,
+            fee_bps: 30,
+            paused: false,
+            bump: 255,
+        };
+
+        assert_eq!(config.fee_for(1_000_000), 3_000);
+        // Rounds down, in the user's favor
+        assert_eq!(config.fee_for(333), 0);
+        assert_eq!(config.fee_for(u64::MAX), 55_340_232_221_128_654);
+        assert_eq!(config.require_active(), Ok(()));
+
+        config.paused = true;
+        assert_eq!(config.require_active(), Err(ConfigError::Paused));
+
+        // A set pending admin still fits the allocated size
+        config.pending_admin = Some(Pubkey::new_unique());
+        assert_eq!(config.try_to_vec().unwrap().len(), CONFIG_ACCOUNT_SIZE);
+    }
+
+    #[test]
+    fn test_accept_admin_requires_pending_admin() {
+        let program_id = Pubkey::new_unique();
+        let (config_key, bump) = Pubkey::find_program_address(&[CONFIG_SEED], &program_id);
+        let pending_admin = Pubkey::new_unique();
+
+        let config = ConfigAccount {
+            is_initialized: true,
+            admin: Pubkey::new_unique(),
+            pending_admin: Some(pending_admin),
+            fee_bps: 30,
+            paused: false,
+            bump,
+        };
+
+        let keys = [Pubkey::new_unique(), config_key];
+        let mut lamports: Vec<u64> = vec![10_000; keys.len()];
+        let mut data: Vec<Vec<u8>> = vec![vec![]; keys.len()];
+        data[1] = config.try_to_vec().unwrap();
+
+        let accounts: Vec<AccountInfo> = keys
+            .iter()
+            .zip(lamports.iter_mut())
+            .zip(data.iter_mut())
+            .enumerate()
+            .map(|(i, ((key, lamports), data))| {
+                AccountInfo::new(key, i == 0, true, lamports, data, &program_id, false, Epoch::default())
+            })
+            .collect();
+
+        let instruction_data = ConfigInstruction::AcceptAdmin.try_to_vec().unwrap();
+        let result = process_instruction(&program_id, &accounts, &instruction_data);
+        assert_eq!(result, Err(ConfigError::Unauthorized.into()));
+    }
+}
@@ -0,0 +1,26 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+/**
+ * @title Callee
+ * @dev Per-caller counters; the counter incremented is always the one owned by msg.sender
+ */
+contract Callee {
+    mapping(address => uint256) public counts;
+
+    event Incremented(address indexed authority, uint256 count);
+
+    /**
+     * @dev Add `amount` to the caller's counter and return the new count
+     */
+    function increment(uint256 amount) external returns (uint256) {
+        require(amount > 0, "Amount must be greater than zero");
+
+        // Checked arithmetic reverts on overflow
+        uint256 count = counts[msg.sender] + amount;
+        counts[msg.sender] = count;
+
+        emit Incremented(msg.sender, count);
+        return count;
+    }
+}
@@ -0,0 +1,229 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint,
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke_signed, set_return_data},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// Seed prefix for counter PDAs: ["counter", authority]
+pub const COUNTER_SEED: &[u8] = b"counter";
+
+/// Size of a `CounterAccount`
+pub const COUNTER_ACCOUNT_SIZE: usize = 1 // is_initialized
+    + 32 // authority
+    + 8 // count
+    + 1; // bump
+
+/// Errors returned by the program as `ProgramError::Custom` codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CalleeError {
+    /// The increment would overflow the counter
+    Overflow = 0,
+    /// The signer is not the counter's authority
+    Unauthorized = 1,
+    /// The counter has not been initialized
+    NotInitialized = 2,
+}
+
+impl From<CalleeError> for ProgramError {
+    fn from(e: CalleeError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+/// A counter only its authority can increment. The authority may be a
+/// wallet or another program's PDA.
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct CounterAccount {
+    pub is_initialized: bool,
+    pub authority: Pubkey,
+    pub count: u64,
+    pub bump: u8,
+}
+
+impl CounterAccount {
+    /// Add `amount`, failing instead of wrapping
+    pub fn increment(&mut self, amount: u64) -> Result<u64, CalleeError> {
+        self.count = self.count.checked_add(amount).ok_or(CalleeError::Overflow)?;
+        Ok(self.count)
+    }
+}
+
+entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = CalleeInstruction::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        CalleeInstruction::Initialize => initialize(program_id, accounts),
+        CalleeInstruction::Increment { amount } => increment(program_id, accounts, amount),
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub enum CalleeInstruction {
+    /// Create a counter for `authority`, which does not need to sign
+    /// Accounts: [writable, signer] payer, [] authority, [writable] counter PDA, [] system program
+    Initialize,
+    /// Add `amount` to the counter and return the new count as return data (u64, little endian)
+    /// Accounts: [signer] authority, [writable] counter PDA
+    Increment { amount: u64 },
+}
+
+fn initialize(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let payer = next_account_info(accounts_iter)?;
+    let authority = next_account_info(accounts_iter)?;
+    let counter_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !payer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let (expected_counter, bump) =
+        Pubkey::find_program_address(&[COUNTER_SEED, authority.key.as_ref()], program_id);
+    if expected_counter != *counter_account.key {
+        msg!("Counter account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    invoke_signed(
+        &system_instruction::create_account(
+            payer.key,
+            counter_account.key,
+            Rent::get()?.minimum_balance(COUNTER_ACCOUNT_SIZE),
+            COUNTER_ACCOUNT_SIZE as u64,
+            program_id,
+        ),
+        &[payer.clone(), counter_account.clone(), system_program.clone()],
+        &[&[COUNTER_SEED, authority.key.as_ref(), &[bump]]],
+    )?;
+
+    let counter = CounterAccount {
+        is_initialized: true,
+        authority: *authority.key,
+        count: 0,
+        bump,
+    };
+    counter.serialize(&mut &mut counter_account.data.borrow_mut()[..])?;
+
+    msg!("Counter created for {}", authority.key);
+
+    Ok(())
+}
+
+fn increment(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let authority = next_account_info(accounts_iter)?;
+    let counter_account = next_account_info(accounts_iter)?;
+
+    // When called through CPI, a PDA authority arrives here as a signer if
+    // the caller used invoke_signed with its seeds
+    if !authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if counter_account.owner != program_id {
+        msg!("Counter account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let mut counter = CounterAccount::try_from_slice(&counter_account.data.borrow())?;
+
+    if !counter.is_initialized {
+        return Err(CalleeError::NotInitialized.into());
+    }
+
+    if counter.authority != *authority.key {
+        msg!("Signer is not the counter authority");
+        return Err(CalleeError::Unauthorized.into());
+    }
+
+    let count = counter.increment(amount)?;
+    counter.serialize(&mut &mut counter_account.data.borrow_mut()[..])?;
+
+    // Visible to the caller through get_return_data
+    set_return_data(&count.to_le_bytes());
+
+    msg!("Counter is now {}", count);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_program::clock::Epoch;
+
+    #[test]
+    fn test_increment_fails_on_overflow() {
+        let mut counter = CounterAccount {
+            is_initialized: true,
+            authority: Pubkey::new_unique(),
+            count: u64::MAX - 1,
+            bump: 255,
+        };
+
+        assert_eq!(counter.increment(1), Ok(u64::MAX));
+        assert_eq!(counter.increment(1), Err(CalleeError::Overflow));
+        assert_eq!(counter.count, u64::MAX);
+    }
+
+    #[test]
+    fn test_increment_requires_the_authority() {
+        let program_id = Pubkey::new_unique();
+        let authority_key = Pubkey::new_unique();
+        let counter_key = Pubkey::new_unique();
+
+        let counter = CounterAccount {
+            is_initialized: true,
+            authority: Pubkey::new_unique(),
+            count: 0,
+            bump: 255,
+        };
+        let mut counter_data = counter.try_to_vec().unwrap();
+        let mut authority_lamports = 0;
+        let mut counter_lamports = 0;
+        let mut authority_data = vec![];
+
+        let accounts = vec![
+            AccountInfo::new(
+                &authority_key,
+                true,
+                false,
+                &mut authority_lamports,
+                &mut authority_data,
+                &program_id,
+                false,
+                Epoch::default(),
+            ),
+            AccountInfo::new(
+                &counter_key,
+                false,
+                true,
+                &mut counter_lamports,
+                &mut counter_data,
+                &program_id,
+                false,
+                Epoch::default(),
+            ),
+        ];
+
+        let instruction_data = CalleeInstruction::Increment { amount: 1 }.try_to_vec().unwrap();
+        let result = process_instruction(&program_id, &accounts, &instruction_data);
+        assert_eq!(result, Err(CalleeError::Unauthorized.into()));
+    }
+}
@@ -0,0 +1,42 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+interface ICallee {
+    function increment(uint256 amount) external returns (uint256);
+}
+
+/**
+ * @title Caller
+ * @dev Calls Callee, reads its return value, and handles its failures with try/catch
+ */
+contract Caller {
+    ICallee public immutable callee;
+
+    event CalleeReturned(uint256 count);
+    event CalleeFailed(bytes reason);
+
+    constructor(ICallee _callee) {
+        callee = _callee;
+    }
+
+    /**
+     * @dev Increment this contract's counter in Callee; msg.sender there is this contract
+     */
+    function increment(uint256 amount) external returns (uint256 count) {
+        count = callee.increment(amount);
+        emit CalleeReturned(count);
+    }
+
+    /**
+     * @dev Same call, but a revert in Callee is caught instead of reverting this call
+     */
+    function tryIncrement(uint256 amount) external returns (bool success) {
+        try callee.increment(amount) returns (uint256 count) {
+            emit CalleeReturned(count);
+            return true;
+        } catch (bytes memory reason) {
+            emit CalleeFailed(reason);
+            return false;
+        }
+    }
+}
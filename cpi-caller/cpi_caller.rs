@@ -0,0 +1,210 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint,
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    msg,
+    program::{get_return_data, invoke, invoke_signed},
+    program_error::ProgramError,
+    pubkey,
+    pubkey::Pubkey,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// Program id of the deployed `cpi-callee` program; replace with your own deployment
+pub const CALLEE_PROGRAM_ID: Pubkey = pubkey!("5Tv91JqhF8FyM4MuhkWWtWBJG9fwdhQfEDGrQhxAZJRo");
+
+/// Seed of this program's signing PDA: ["authority"]
+pub const AUTHORITY_SEED: &[u8] = b"authority";
+
+/// Errors returned by the program as `ProgramError::Custom` codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CallerError {
+    /// Amount is zero
+    ZeroAmount = 0,
+    /// The callee set no return data
+    MissingReturnData = 1,
+    /// The return data came from another program or is not a u64
+    InvalidReturnData = 2,
+}
+
+impl From<CallerError> for ProgramError {
+    fn from(e: CallerError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+/// Mirror of `cpi-callee`'s instruction enum. Borsh encodes the variant index,
+/// so the order must match the callee exactly. A larger project would depend
+/// on the callee crate (built without its entrypoint) instead.
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub enum CalleeInstruction {
+    Initialize,
+    Increment { amount: u64 },
+}
+
+/// Build the callee's `Increment` instruction
+pub fn increment_instruction(authority: &Pubkey, counter: &Pubkey, amount: u64) -> Result<Instruction, ProgramError> {
+    Ok(Instruction {
+        program_id: CALLEE_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new(*counter, false),
+        ],
+        data: CalleeInstruction::Increment { amount }.try_to_vec()?,
+    })
+}
+
+/// Decode the callee's return data, checking that the callee set it. Return
+/// data persists until another program overwrites it, so the program id matters.
+pub fn parse_count(return_data: Option<(Pubkey, Vec<u8>)>) -> Result<u64, CallerError> {
+    let (program_id, data) = return_data.ok_or(CallerError::MissingReturnData)?;
+    if program_id != CALLEE_PROGRAM_ID {
+        return Err(CallerError::InvalidReturnData);
+    }
+    let bytes: [u8; 8] = data.try_into().map_err(|_| CallerError::InvalidReturnData)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = CallerInstruction::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        CallerInstruction::IncrementViaCpi { amount } => increment_via_cpi(accounts, amount),
+        CallerInstruction::IncrementAsPda { amount } => increment_as_pda(program_id, accounts, amount),
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub enum CallerInstruction {
+    /// Increment a counter owned by the signing user; the user's signature carries into the CPI
+    /// Accounts: [signer] user, [writable] callee counter PDA, [] callee program
+    IncrementViaCpi { amount: u64 },
+    /// Increment a counter whose authority is this program's PDA, signing with its seeds
+    /// Accounts: [] authority PDA, [writable] callee counter PDA, [] callee program
+    IncrementAsPda { amount: u64 },
+}
+
+fn increment_via_cpi(accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let user = next_account_info(accounts_iter)?;
+    let counter = next_account_info(accounts_iter)?;
+    let callee_program = next_account_info(accounts_iter)?;
+
+    if !user.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    check_callee(callee_program)?;
+
+    // An error inside the callee aborts the whole transaction and cannot be
+    // caught here, so check anything we want to report with our own error first
+    if amount == 0 {
+        return Err(CallerError::ZeroAmount.into());
+    }
+
+    invoke(
+        &increment_instruction(user.key, counter.key, amount)?,
+        &[user.clone(), counter.clone(), callee_program.clone()],
+    )?;
+
+    let count = parse_count(get_return_data())?;
+    msg!("Callee returned count {}", count);
+
+    Ok(())
+}
+
+fn increment_as_pda(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let authority = next_account_info(accounts_iter)?;
+    let counter = next_account_info(accounts_iter)?;
+    let callee_program = next_account_info(accounts_iter)?;
+
+    check_callee(callee_program)?;
+
+    if amount == 0 {
+        return Err(CallerError::ZeroAmount.into());
+    }
+
+    let (expected_authority, bump) = Pubkey::find_program_address(&[AUTHORITY_SEED], program_id);
+    if expected_authority != *authority.key {
+        msg!("Authority account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    // The runtime marks the PDA as a signer in the callee because these seeds
+    // derive it from this program's id
+    invoke_signed(
+        &increment_instruction(authority.key, counter.key, amount)?,
+        &[authority.clone(), counter.clone(), callee_program.clone()],
+        &[&[AUTHORITY_SEED, &[bump]]],
+    )?;
+
+    let count = parse_count(get_return_data())?;
+    msg!("Callee returned count {} for the program PDA", count);
+
+    Ok(())
+}
+
+/// Make sure we are about to call the real callee, not an impostor
+fn check_callee(callee_program: &AccountInfo) -> ProgramResult {
+    if *callee_program.key != CALLEE_PROGRAM_ID {
+        msg!("Expected callee program {}", CALLEE_PROGRAM_ID);
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_program::clock::Epoch;
+
+    #[test]
+    fn test_parse_count_checks_program_and_length() {
+        assert_eq!(parse_count(Some((CALLEE_PROGRAM_ID, 42u64.to_le_bytes().to_vec()))), Ok(42));
+        assert_eq!(parse_count(None), Err(CallerError::MissingReturnData));
+        assert_eq!(
+            parse_count(Some((Pubkey::new_unique(), 42u64.to_le_bytes().to_vec()))),
+            Err(CallerError::InvalidReturnData)
+        );
+        assert_eq!(
+            parse_count(Some((CALLEE_PROGRAM_ID, vec![1, 2, 3]))),
+            Err(CallerError::InvalidReturnData)
+        );
+
+        // The variant index must match the callee's enum
+        let ix = increment_instruction(&Pubkey::new_unique(), &Pubkey::new_unique(), 7).unwrap();
+        assert_eq!(ix.data[0], 1);
+        assert!(ix.accounts[0].is_signer);
+    }
+
+    #[test]
+    fn test_rejects_impostor_callee() {
+        let program_id = Pubkey::new_unique();
+        let keys = [Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()];
+        let mut lamports: Vec<u64> = vec![10_000; keys.len()];
+        let mut data: Vec<Vec<u8>> = vec![vec![]; keys.len()];
+
+        let accounts: Vec<AccountInfo> = keys
+            .iter()
+            .zip(lamports.iter_mut())
+            .zip(data.iter_mut())
+            .enumerate()
+            .map(|(i, ((key, lamports), data))| {
+                AccountInfo::new(key, i == 0, true, lamports, data, &program_id, false, Epoch::default())
+            })
+            .collect();
+
+        let instruction_data = CallerInstruction::IncrementViaCpi { amount: 1 }.try_to_vec().unwrap();
+        let result = process_instruction(&program_id, &accounts, &instruction_data);
+        assert_eq!(result, Err(ProgramError::IncorrectProgramId));
+    }
+}
@@ -0,0 +1,96 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+interface IERC20 {
+    function transfer(address to, uint256 amount) external returns (bool);
+    function transferFrom(address from, address to, uint256 amount) external returns (bool);
+    function approve(address spender, uint256 amount) external returns (bool);
+}
+
+/// @dev The parts of the AMM example this contract calls
+interface IAmm {
+    function tokenA() external view returns (IERC20);
+    function tokenB() external view returns (IERC20);
+    function swap(uint256 amountIn, uint256 minAmountOut, bool aToB) external returns (uint256 amountOut);
+}
+
+/**
+ * @title DcaScheduler
+ * @dev Dollar-cost averaging through the AMM example. Owners deposit a budget
+ * and pick a fill size and interval; anyone can call `crank` once a fill is
+ * due, and the output goes straight to the owner.
+ */
+contract DcaScheduler {
+    uint256 public constant MIN_INTERVAL = 60;
+
+    struct Plan {
+        address owner;
+        IAmm amm;
+        bool aToB;
+        uint256 amountPerFill;
+        uint256 minAmountOut;
+        uint256 interval;
+        uint256 nextFillAt;
+        uint256 remaining;
+        uint256 fills;
+        uint256 totalOut;
+    }
+
+    Plan[] public plans;
+
+    event PlanCreated(uint256 indexed planId, address indexed owner, uint256 budget);
+    event Filled(uint256 indexed planId, uint256 amountIn, uint256 amountOut, uint256 remaining);
+    event PlanClosed(uint256 indexed planId, uint256 returned);
+
+    function createPlan(
+        IAmm amm,
+        bool aToB,
+        uint256 budget,
+        uint256 amountPerFill,
+        uint256 minAmountOut,
+        uint256 interval
+    ) external returns (uint256 planId) {
+        require(budget > 0 && amountPerFill > 0 && interval >= MIN_INTERVAL, "Invalid parameters");
+
+        planId = plans.length;
+        plans.push(Plan(msg.sender, amm, aToB, amountPerFill, minAmountOut, interval, block.timestamp, budget, 0, 0));
+        require(_input(amm, aToB).transferFrom(msg.sender, address(this), budget), "Transfer failed");
+        emit PlanCreated(planId, msg.sender, budget);
+    }
+
+    /// @dev Anyone can trigger a due fill; a late crank does not cause catch-up fills
+    function crank(uint256 planId) external {
+        Plan storage plan = plans[planId];
+        require(plan.remaining > 0, "Budget exhausted");
+        require(block.timestamp >= plan.nextFillAt, "Not due");
+
+        uint256 amountIn = plan.amountPerFill < plan.remaining ? plan.amountPerFill : plan.remaining;
+        plan.remaining -= amountIn;
+        plan.fills += 1;
+        plan.nextFillAt = block.timestamp + plan.interval;
+
+        require(_input(plan.amm, plan.aToB).approve(address(plan.amm), amountIn), "Approve failed");
+        uint256 amountOut = plan.amm.swap(amountIn, plan.minAmountOut, plan.aToB);
+        plan.totalOut += amountOut;
+
+        IERC20 output = plan.aToB ? plan.amm.tokenB() : plan.amm.tokenA();
+        require(output.transfer(plan.owner, amountOut), "Transfer failed");
+        emit Filled(planId, amountIn, amountOut, plan.remaining);
+    }
+
+    function close(uint256 planId) external {
+        Plan storage plan = plans[planId];
+        require(msg.sender == plan.owner, "Only owner");
+
+        uint256 amount = plan.remaining;
+        plan.remaining = 0;
+        if (amount > 0) {
+            require(_input(plan.amm, plan.aToB).transfer(plan.owner, amount), "Transfer failed");
+        }
+        emit PlanClosed(planId, amount);
+    }
+
+    function _input(IAmm amm, bool aToB) private view returns (IERC20) {
+        return aToB ? amm.tokenA() : amm.tokenB();
+    }
+}
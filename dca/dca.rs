@@ -0,0 +1,581 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint,
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use spl_token::state::Account as TokenAccount;
+
+/// Program id of the deployed `amm` example; replace with your own deployment
+pub const AMM_PROGRAM_ID: Pubkey = pubkey!("AMMxDCA1111111111111111111111111111111111111");
+
+/// Seed prefix for plan PDAs: ["dca", owner, pool]
+pub const PLAN_SEED: &[u8] = b"dca";
+
+/// Seed prefix for the plan's input token vault: ["source", plan]
+pub const SOURCE_SEED: &[u8] = b"source";
+
+/// Shortest allowed interval between fills (1 minute)
+pub const MIN_INTERVAL_SECONDS: i64 = 60;
+
+/// Size of a `DcaPlan` account
+pub const PLAN_ACCOUNT_SIZE: usize = 1 // is_initialized
+    + 32 // owner
+    + 32 // pool
+    + 1 // a_to_b
+    + 32 // destination
+    + 8 // amount_per_fill
+    + 8 // min_amount_out
+    + 8 // interval_seconds
+    + 8 // next_fill_at
+    + 8 // remaining
+    + 4 // fills
+    + 8 // total_out
+    + 1 // bump
+    + 1; // source_bump
+
+/// Errors returned by the program as `ProgramError::Custom` codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DcaError {
+    /// Budget or fill amount is zero, or the interval is too short
+    InvalidParameters = 0,
+    /// The next fill is not due yet
+    NotDue = 1,
+    /// The whole budget has been spent
+    BudgetExhausted = 2,
+    /// Only the owner can do this
+    Unauthorized = 3,
+    /// The plan has not been initialized
+    NotInitialized = 4,
+    /// An account does not match the plan
+    WrongAccount = 5,
+}
+
+impl From<DcaError> for ProgramError {
+    fn from(e: DcaError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+/// Mirror of the `amm` example's instruction enum. Borsh encodes the variant
+/// index, so the order must match the AMM exactly.
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub enum AmmInstruction {
+    InitializePool {
+        fee_bps: u16,
+    },
+    AddLiquidity {
+        max_amount_a: u64,
+        max_amount_b: u64,
+        min_lp_out: u64,
+    },
+    RemoveLiquidity {
+        lp_amount: u64,
+        min_amount_a: u64,
+        min_amount_b: u64,
+    },
+    Swap {
+        amount_in: u64,
+        min_amount_out: u64,
+        a_to_b: bool,
+    },
+}
+
+/// A recurring buy: every `interval_seconds`, swap `amount_per_fill` of the
+/// input token through one AMM pool until the budget is spent
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct DcaPlan {
+    pub is_initialized: bool,
+    pub owner: Pubkey,
+    /// AMM pool the plan trades against
+    pub pool: Pubkey,
+    /// Direction through the pool; the input is mint A when true
+    pub a_to_b: bool,
+    /// Owner's token account that receives the output
+    pub destination: Pubkey,
+    pub amount_per_fill: u64,
+    /// Least output a fill accepts, protecting permissionless cranks from bad prices
+    pub min_amount_out: u64,
+    pub interval_seconds: i64,
+    pub next_fill_at: i64,
+    /// Input tokens still to be swapped
+    pub remaining: u64,
+    pub fills: u32,
+    /// Output received across all fills
+    pub total_out: u64,
+    pub bump: u8,
+    pub source_bump: u8,
+}
+
+impl DcaPlan {
+    /// Input amount for a fill at `now`; the last fill takes whatever is left
+    pub fn due_amount(&self, now: i64) -> Result<u64, DcaError> {
+        if self.remaining == 0 {
+            return Err(DcaError::BudgetExhausted);
+        }
+        if now < self.next_fill_at {
+            return Err(DcaError::NotDue);
+        }
+        Ok(self.amount_per_fill.min(self.remaining))
+    }
+
+    /// Record a fill. The next one is scheduled from `now`, so a late crank
+    /// never triggers a burst of catch-up fills.
+    pub fn record_fill(&mut self, now: i64, amount_in: u64, amount_out: u64) {
+        self.remaining -= amount_in;
+        self.fills += 1;
+        self.total_out = self.total_out.saturating_add(amount_out);
+        self.next_fill_at = now.saturating_add(self.interval_seconds);
+    }
+}
+
+/// Build the AMM `Swap` instruction with `authority` as the signing user
+#[allow(clippy::too_many_arguments)]
+pub fn swap_instruction(
+    pool: &Pubkey,
+    vault_a: &Pubkey,
+    vault_b: &Pubkey,
+    authority: &Pubkey,
+    source: &Pubkey,
+    destination: &Pubkey,
+    amount_in: u64,
+    min_amount_out: u64,
+    a_to_b: bool,
+) -> Result<Instruction, ProgramError> {
+    Ok(Instruction {
+        program_id: AMM_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new(*pool, false),
+            AccountMeta::new(*vault_a, false),
+            AccountMeta::new(*vault_b, false),
+            AccountMeta::new(*source, false),
+            AccountMeta::new(*destination, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+        data: AmmInstruction::Swap {
+            amount_in,
+            min_amount_out,
+            a_to_b,
+        }
+        .try_to_vec()?,
+    })
+}
+
+entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = DcaInstruction::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        DcaInstruction::CreatePlan(params) => create_plan(program_id, accounts, params),
+        DcaInstruction::Crank => crank(program_id, accounts),
+        DcaInstruction::Close => close(program_id, accounts),
+    }
+}
+
+/// Settings for `DcaInstruction::CreatePlan`
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct CreatePlanParams {
+    /// Input tokens deposited now and spent across all fills
+    pub budget: u64,
+    pub amount_per_fill: u64,
+    pub min_amount_out: u64,
+    pub interval_seconds: i64,
+    pub a_to_b: bool,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub enum DcaInstruction {
+    /// Create a plan against an AMM pool and deposit the budget; the first fill is due immediately
+    /// Accounts: [writable, signer] owner, [writable] plan PDA, [writable] source vault PDA, [] input mint,
+    /// [] AMM pool, [writable] owner input token account, [] owner output token account,
+    /// [] token program, [] system program
+    CreatePlan(CreatePlanParams),
+    /// Swap one fill through the AMM if it is due (anyone can call)
+    /// Accounts: [writable] plan PDA, [writable] source vault PDA, [writable] destination token account,
+    /// [] AMM program, [writable] AMM pool, [writable] AMM vault A, [writable] AMM vault B, [] token program
+    Crank,
+    /// Return the unspent budget and close the plan
+    /// Accounts: [writable, signer] owner, [writable] plan PDA, [writable] source vault PDA,
+    /// [writable] owner input token account, [] token program
+    Close,
+}
+
+fn create_plan(program_id: &Pubkey, accounts: &[AccountInfo], params: CreatePlanParams) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let owner = next_account_info(accounts_iter)?;
+    let plan_account = next_account_info(accounts_iter)?;
+    let source_vault = next_account_info(accounts_iter)?;
+    let input_mint = next_account_info(accounts_iter)?;
+    let pool = next_account_info(accounts_iter)?;
+    let owner_input = next_account_info(accounts_iter)?;
+    let owner_output = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if params.budget == 0 || params.amount_per_fill == 0 || params.interval_seconds < MIN_INTERVAL_SECONDS {
+        return Err(DcaError::InvalidParameters.into());
+    }
+
+    if *token_program.key != spl_token::id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    if *pool.owner != AMM_PROGRAM_ID {
+        msg!("Pool is not owned by the AMM program");
+        return Err(DcaError::WrongAccount.into());
+    }
+
+    let output = TokenAccount::unpack(&owner_output.data.borrow())?;
+    if output.owner != *owner.key {
+        msg!("Output token account must belong to the owner");
+        return Err(DcaError::WrongAccount.into());
+    }
+
+    let (expected_plan, bump) =
+        Pubkey::find_program_address(&[PLAN_SEED, owner.key.as_ref(), pool.key.as_ref()], program_id);
+    if expected_plan != *plan_account.key {
+        msg!("Plan account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let (expected_source, source_bump) =
+        Pubkey::find_program_address(&[SOURCE_SEED, plan_account.key.as_ref()], program_id);
+    if expected_source != *source_vault.key {
+        msg!("Source vault does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let rent = Rent::get()?;
+
+    invoke_signed(
+        &system_instruction::create_account(
+            owner.key,
+            plan_account.key,
+            rent.minimum_balance(PLAN_ACCOUNT_SIZE),
+            PLAN_ACCOUNT_SIZE as u64,
+            program_id,
+        ),
+        &[owner.clone(), plan_account.clone(), system_program.clone()],
+        &[&[PLAN_SEED, owner.key.as_ref(), pool.key.as_ref(), &[bump]]],
+    )?;
+
+    invoke_signed(
+        &system_instruction::create_account(
+            owner.key,
+            source_vault.key,
+            rent.minimum_balance(TokenAccount::LEN),
+            TokenAccount::LEN as u64,
+            &spl_token::id(),
+        ),
+        &[owner.clone(), source_vault.clone(), system_program.clone()],
+        &[&[SOURCE_SEED, plan_account.key.as_ref(), &[source_bump]]],
+    )?;
+    invoke(
+        &spl_token::instruction::initialize_account3(
+            &spl_token::id(),
+            source_vault.key,
+            input_mint.key,
+            plan_account.key,
+        )?,
+        &[source_vault.clone(), input_mint.clone()],
+    )?;
+
+    invoke(
+        &spl_token::instruction::transfer(
+            &spl_token::id(),
+            owner_input.key,
+            source_vault.key,
+            owner.key,
+            &[],
+            params.budget,
+        )?,
+        &[owner_input.clone(), source_vault.clone(), owner.clone()],
+    )?;
+
+    let plan = DcaPlan {
+        is_initialized: true,
+        owner: *owner.key,
+        pool: *pool.key,
+        a_to_b: params.a_to_b,
+        destination: *owner_output.key,
+        amount_per_fill: params.amount_per_fill,
+        min_amount_out: params.min_amount_out,
+        interval_seconds: params.interval_seconds,
+        next_fill_at: Clock::get()?.unix_timestamp,
+        remaining: params.budget,
+        fills: 0,
+        total_out: 0,
+        bump,
+        source_bump,
+    };
+    plan.serialize(&mut &mut plan_account.data.borrow_mut()[..])?;
+
+    msg!(
+        "DCA plan: {} every {} seconds from a budget of {}",
+        plan.amount_per_fill,
+        plan.interval_seconds,
+        plan.remaining
+    );
+
+    Ok(())
+}
+
+fn crank(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let plan_account = next_account_info(accounts_iter)?;
+    let source_vault = next_account_info(accounts_iter)?;
+    let destination = next_account_info(accounts_iter)?;
+    let amm_program = next_account_info(accounts_iter)?;
+    let pool = next_account_info(accounts_iter)?;
+    let vault_a = next_account_info(accounts_iter)?;
+    let vault_b = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+
+    let mut plan = load_plan(program_id, plan_account, source_vault, token_program)?;
+
+    if *amm_program.key != AMM_PROGRAM_ID || plan.pool != *pool.key {
+        msg!("AMM program or pool does not match the plan");
+        return Err(DcaError::WrongAccount.into());
+    }
+    if plan.destination != *destination.key {
+        return Err(DcaError::WrongAccount.into());
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    let amount_in = plan.due_amount(now)?;
+
+    // The AMM reports no return data, so measure the output from the destination balance
+    let balance_before = TokenAccount::unpack(&destination.data.borrow())?.amount;
+
+    invoke_signed(
+        &swap_instruction(
+            pool.key,
+            vault_a.key,
+            vault_b.key,
+            plan_account.key,
+            source_vault.key,
+            destination.key,
+            amount_in,
+            plan.min_amount_out,
+            plan.a_to_b,
+        )?,
+        &[
+            plan_account.clone(),
+            pool.clone(),
+            vault_a.clone(),
+            vault_b.clone(),
+            source_vault.clone(),
+            destination.clone(),
+            token_program.clone(),
+            amm_program.clone(),
+        ],
+        &[&[PLAN_SEED, plan.owner.as_ref(), plan.pool.as_ref(), &[plan.bump]]],
+    )?;
+
+    let amount_out = TokenAccount::unpack(&destination.data.borrow())?
+        .amount
+        .saturating_sub(balance_before);
+
+    plan.record_fill(now, amount_in, amount_out);
+    plan.serialize(&mut &mut plan_account.data.borrow_mut()[..])?;
+
+    msg!(
+        "Fill {}: {} in, {} out; {} remaining",
+        plan.fills,
+        amount_in,
+        amount_out,
+        plan.remaining
+    );
+
+    Ok(())
+}
+
+fn close(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let owner = next_account_info(accounts_iter)?;
+    let plan_account = next_account_info(accounts_iter)?;
+    let source_vault = next_account_info(accounts_iter)?;
+    let owner_input = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let plan = load_plan(program_id, plan_account, source_vault, token_program)?;
+
+    if plan.owner != *owner.key {
+        msg!("Only the owner can close the plan");
+        return Err(DcaError::Unauthorized.into());
+    }
+
+    let seeds: &[&[u8]] = &[PLAN_SEED, plan.owner.as_ref(), plan.pool.as_ref(), &[plan.bump]];
+
+    // Return the vault's actual balance, which includes anything sent to it directly
+    let balance = TokenAccount::unpack(&source_vault.data.borrow())?.amount;
+    if balance > 0 {
+        invoke_signed(
+            &spl_token::instruction::transfer(
+                &spl_token::id(),
+                source_vault.key,
+                owner_input.key,
+                plan_account.key,
+                &[],
+                balance,
+            )?,
+            &[source_vault.clone(), owner_input.clone(), plan_account.clone()],
+            &[seeds],
+        )?;
+    }
+
+    invoke_signed(
+        &spl_token::instruction::close_account(
+            &spl_token::id(),
+            source_vault.key,
+            owner.key,
+            plan_account.key,
+            &[],
+        )?,
+        &[source_vault.clone(), owner.clone(), plan_account.clone()],
+        &[seeds],
+    )?;
+
+    let lamports = plan_account.lamports();
+    **plan_account.try_borrow_mut_lamports()? = 0;
+    **owner.try_borrow_mut_lamports()? = owner
+        .lamports()
+        .checked_add(lamports)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    plan_account.data.borrow_mut().fill(0);
+
+    msg!("Plan closed after {} fills; returned {}", plan.fills, balance);
+
+    Ok(())
+}
+
+/// Validate the plan account and its source vault, returning the plan
+fn load_plan(
+    program_id: &Pubkey,
+    plan_account: &AccountInfo,
+    source_vault: &AccountInfo,
+    token_program: &AccountInfo,
+) -> Result<DcaPlan, ProgramError> {
+    if plan_account.owner != program_id {
+        msg!("Plan account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    if *token_program.key != spl_token::id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let plan = DcaPlan::try_from_slice(&plan_account.data.borrow())?;
+
+    if !plan.is_initialized {
+        return Err(DcaError::NotInitialized.into());
+    }
+
+    let expected_source = Pubkey::create_program_address(
+        &[SOURCE_SEED, plan_account.key.as_ref(), &[plan.source_bump]],
+        program_id,
+    )?;
+    if expected_source != *source_vault.key {
+        msg!("Source vault does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    Ok(plan)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn plan() -> DcaPlan {
+        DcaPlan {
+            is_initialized: true,
+            owner: Pubkey::new_unique(),
+            pool: Pubkey::new_unique(),
+            a_to_b: true,
+            destination: Pubkey::new_unique(),
+            amount_per_fill: 400,
+            min_amount_out: 1,
+            interval_seconds: 3_600,
+            next_fill_at: 1_000,
+            remaining: 1_000,
+            fills: 0,
+            total_out: 0,
+            bump: 255,
+            source_bump: 255,
+        }
+    }
+
+    #[test]
+    fn test_fills_follow_interval_and_budget() {
+        let mut plan = plan();
+
+        assert_eq!(plan.due_amount(999), Err(DcaError::NotDue));
+        assert_eq!(plan.due_amount(1_000), Ok(400));
+        plan.record_fill(1_000, 400, 38);
+
+        assert_eq!(plan.due_amount(4_599), Err(DcaError::NotDue));
+
+        // A crank that arrives late schedules the next fill from when it ran
+        plan.record_fill(9_000, 400, 40);
+        assert_eq!(plan.next_fill_at, 12_600);
+
+        assert_eq!(plan.due_amount(12_600), Ok(200));
+        plan.record_fill(12_600, 200, 19);
+        assert_eq!(plan.due_amount(i64::MAX), Err(DcaError::BudgetExhausted));
+        assert_eq!((plan.fills, plan.total_out), (3, 97));
+
+        assert_eq!(plan.try_to_vec().unwrap().len(), PLAN_ACCOUNT_SIZE);
+    }
+
+    #[test]
+    fn test_swap_instruction_matches_amm_layout() {
+        let plan_key = Pubkey::new_unique();
+        let keys: Vec<Pubkey> = (0..5).map(|_| Pubkey::new_unique()).collect();
+
+        let ix = swap_instruction(&keys[0], &keys[1], &keys[2], &plan_key, &keys[3], &keys[4], 400, 30, true).unwrap();
+
+        assert_eq!(ix.program_id, AMM_PROGRAM_ID);
+        // The plan PDA signs as the AMM's user, in the first slot
+        assert_eq!(ix.accounts[0], AccountMeta::new_readonly(plan_key, true));
+        assert_eq!(ix.accounts[1].pubkey, keys[0]);
+        assert_eq!(ix.accounts[6].pubkey, spl_token::id());
+
+        // Swap is the AMM's fourth variant
+        assert_eq!(ix.data[0], 3);
+        match AmmInstruction::try_from_slice(&ix.data).unwrap() {
+            AmmInstruction::Swap {
+                amount_in,
+                min_amount_out,
+                a_to_b,
+            } => assert_eq!((amount_in, min_amount_out, a_to_b), (400, 30, true)),
+            other => panic!("unexpected instruction {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,99 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+interface IOwnable {
+    function owner() external view returns (address);
+}
+
+/**
+ * @title ProgramDirectory
+ * @dev On-chain registry of deployed example contracts. A contract can only be
+ * registered by its owner, so listings cannot be spoofed.
+ */
+contract ProgramDirectory {
+    uint256 public constant MAX_NAME_LENGTH = 32;
+    uint256 public constant MAX_VERSION_LENGTH = 16;
+    uint256 public constant MAX_DESCRIPTION_LENGTH = 200;
+
+    struct Entry {
+        address registrant;
+        string name;
+        string version;
+        string description;
+        uint64 registeredAt;
+        uint64 updatedAt;
+    }
+
+    mapping(address => Entry) public entries;
+    address[] private programs;
+    mapping(address => uint256) private indexOf; // 1-based; 0 means not listed
+
+    event Registered(address indexed program, string name, string version);
+    event Updated(address indexed program, string version);
+    event Unregistered(address indexed program);
+
+    /**
+     * @dev List a contract; the caller must be its `owner()`
+     */
+    function register(address program, string calldata name, string calldata version, string calldata description)
+        external
+    {
+        require(indexOf[program] == 0, "Already registered");
+        require(IOwnable(program).owner() == msg.sender, "Only the contract owner");
+        _validate(name, version, description);
+
+        entries[program] = Entry(msg.sender, name, version, description, uint64(block.timestamp), uint64(block.timestamp));
+        programs.push(program);
+        indexOf[program] = programs.length;
+
+        emit Registered(program, name, version);
+    }
+
+    /**
+     * @dev Change the version and description after a redeploy (registrant only)
+     */
+    function update(address program, string calldata version, string calldata description) external {
+        Entry storage entry = entries[program];
+        require(indexOf[program] != 0, "Not registered");
+        require(entry.registrant == msg.sender, "Only registrant");
+        _validate(entry.name, version, description);
+
+        entry.version = version;
+        entry.description = description;
+        entry.updatedAt = uint64(block.timestamp);
+
+        emit Updated(program, version);
+    }
+
+    /**
+     * @dev Remove a listing (registrant only)
+     */
+    function unregister(address program) external {
+        uint256 index = indexOf[program];
+        require(index != 0, "Not registered");
+        require(entries[program].registrant == msg.sender, "Only registrant");
+
+        address last = programs[programs.length - 1];
+        programs[index - 1] = last;
+        indexOf[last] = index;
+        programs.pop();
+
+        delete indexOf[program];
+        delete entries[program];
+
+        emit Unregistered(program);
+    }
+
+    function getPrograms() external view returns (address[] memory) {
+        return programs;
+    }
+
+    function _validate(string memory name, string memory version, string memory description) private pure {
+        require(bytes(name).length > 0 && bytes(name).length <= MAX_NAME_LENGTH, "Invalid name");
+        require(bytes(version).length > 0 && bytes(version).length <= MAX_VERSION_LENGTH, "Invalid version");
+        require(
+            bytes(description).length > 0 && bytes(description).length <= MAX_DESCRIPTION_LENGTH,
+            "Invalid description"
+        );
+    }
+}
@@ -0,0 +1,523 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    bpf_loader_upgradeable,
+    clock::Clock,
+    entrypoint,
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// Seed of the singleton directory PDA: ["directory"]
+pub const DIRECTORY_SEED: &[u8] = b"directory";
+
+/// Seed prefix for entry PDAs: ["entry", registered_program]
+pub const ENTRY_SEED: &[u8] = b"entry";
+
+pub const MAX_NAME_LENGTH: usize = 32;
+pub const MAX_VERSION_LENGTH: usize = 16;
+pub const MAX_DESCRIPTION_LENGTH: usize = 200;
+
+/// Size of a `ProgramEntry` with every string at its maximum length
+pub const ENTRY_ACCOUNT_SIZE: usize = 1 // is_initialized
+    + 32 // program
+    + 32 // authority
+    + 4 + MAX_NAME_LENGTH // name
+    + 4 + MAX_VERSION_LENGTH // version
+    + 4 + MAX_DESCRIPTION_LENGTH // description
+    + 8 // registered_at
+    + 8 // updated_at
+    + 1; // bump
+
+/// Errors returned by the program as `ProgramError::Custom` codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DirectoryError {
+    /// Not the registered program's program data account, or the program is immutable
+    InvalidProgramData = 0,
+    /// Only the program's upgrade authority can register or change its entry
+    Unauthorized = 1,
+    /// Name, version or description is empty or too long
+    InvalidMetadata = 2,
+    /// Account does not match the expected PDA
+    WrongAccount = 3,
+    /// The directory or entry has not been initialized
+    NotInitialized = 4,
+}
+
+impl From<DirectoryError> for ProgramError {
+    fn from(e: DirectoryError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+/// Directory-wide counters
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct DirectoryAccount {
+    pub is_initialized: bool,
+    pub program_count: u32,
+    pub bump: u8,
+}
+
+impl DirectoryAccount {
+    pub const SIZE: usize = 1 // is_initialized
+        + 4 // program_count
+        + 1; // bump
+}
+
+/// One registered program
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ProgramEntry {
+    pub is_initialized: bool,
+    pub program: Pubkey,
+    /// Upgrade authority at registration; the only key that can edit the entry
+    pub authority: Pubkey,
+    /// Example name, e.g. "crowdfund"
+    pub name: String,
+    /// Free-form version string, e.g. "1.2.0"
+    pub version: String,
+    pub description: String,
+    pub registered_at: i64,
+    pub updated_at: i64,
+    pub bump: u8,
+}
+
+/// Check each metadata field is non-empty and within its limit
+pub fn validate_metadata(name: &str, version: &str, description: &str) -> Result<(), DirectoryError> {
+    let fields = [
+        (name, MAX_NAME_LENGTH),
+        (version, MAX_VERSION_LENGTH),
+        (description, MAX_DESCRIPTION_LENGTH),
+    ];
+    if fields.iter().any(|(field, max)| field.is_empty() || field.len() > *max) {
+        return Err(DirectoryError::InvalidMetadata);
+    }
+    Ok(())
+}
+
+/// Address of the singleton directory
+pub fn directory_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[DIRECTORY_SEED], program_id)
+}
+
+/// Address of `registered_program`'s entry
+pub fn entry_address(program_id: &Pubkey, registered_program: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[ENTRY_SEED, registered_program.as_ref()], program_id)
+}
+
+/// Read the upgrade authority from `registered_program`'s `ProgramData` account.
+/// Layout: u32 variant (3 = ProgramData), u64 slot, Option<Pubkey> authority.
+pub fn upgrade_authority(registered_program: &Pubkey, program_data: &AccountInfo) -> Result<Pubkey, ProgramError> {
+    let (expected, _) = Pubkey::find_program_address(&[registered_program.as_ref()], &bpf_loader_upgradeable::id());
+    if expected != *program_data.key || *program_data.owner != bpf_loader_upgradeable::id() {
+        msg!("Not the program data account of {}", registered_program);
+        return Err(DirectoryError::InvalidProgramData.into());
+    }
+
+    let data = program_data.data.borrow();
+    if data.len() < 45 || data[0..4] != 3u32.to_le_bytes() || data[12] != 1 {
+        msg!("Program is immutable or the program data is malformed");
+        return Err(DirectoryError::InvalidProgramData.into());
+    }
+
+    Ok(Pubkey::new_from_array(data[13..45].try_into().unwrap()))
+}
+
+entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = DirectoryInstruction::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        DirectoryInstruction::Initialize => initialize(program_id, accounts),
+        DirectoryInstruction::Register {
+            name,
+            version,
+            description,
+        } => register(program_id, accounts, name, version, description),
+        DirectoryInstruction::Update { version, description } => update(program_id, accounts, version, description),
+        DirectoryInstruction::Unregister => unregister(program_id, accounts),
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub enum DirectoryInstruction {
+    /// Create the directory (once, by anyone)
+    /// Accounts: [writable, signer] payer, [writable] directory PDA, [] system program
+    Initialize,
+    /// List a program; the signer must be its upgrade authority
+    /// Accounts: [writable, signer] authority, [] registered program, [] its program data,
+    ///           [writable] directory PDA, [writable] entry PDA, [] system program
+    Register {
+        name: String,
+        version: String,
+        description: String,
+    },
+    /// Change the version and description after a redeploy
+    /// Accounts: [signer] authority, [writable] entry PDA
+    Update { version: String, description: String },
+    /// Remove the entry and refund its rent
+    /// Accounts: [writable, signer] authority, [writable] directory PDA, [writable] entry PDA
+    Unregister,
+}
+
+fn initialize(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let payer = next_account_info(accounts_iter)?;
+    let directory_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !payer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let (expected_directory, bump) = directory_address(program_id);
+    if expected_directory != *directory_account.key {
+        msg!("Directory account does not match the expected PDA");
+        return Err(DirectoryError::WrongAccount.into());
+    }
+
+    invoke_signed(
+        &system_instruction::create_account(
+            payer.key,
+            directory_account.key,
+            Rent::get()?.minimum_balance(DirectoryAccount::SIZE),
+            DirectoryAccount::SIZE as u64,
+            program_id,
+        ),
+        &[payer.clone(), directory_account.clone(), system_program.clone()],
+        &[&[DIRECTORY_SEED, &[bump]]],
+    )?;
+
+    let directory = DirectoryAccount {
+        is_initialized: true,
+        program_count: 0,
+        bump,
+    };
+    directory.serialize(&mut &mut directory_account.data.borrow_mut()[..])?;
+
+    msg!("Directory initialized");
+
+    Ok(())
+}
+
+fn register(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    name: String,
+    version: String,
+    description: String,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let authority = next_account_info(accounts_iter)?;
+    let registered_program = next_account_info(accounts_iter)?;
+    let program_data = next_account_info(accounts_iter)?;
+    let directory_account = next_account_info(accounts_iter)?;
+    let entry_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    validate_metadata(&name, &version, &description)?;
+
+    // Only whoever can upgrade the program may speak for it
+    if upgrade_authority(registered_program.key, program_data)? != *authority.key {
+        msg!("Signer is not the upgrade authority of {}", registered_program.key);
+        return Err(DirectoryError::Unauthorized.into());
+    }
+
+    let mut directory = load_directory(program_id, directory_account)?;
+
+    let (expected_entry, bump) = entry_address(program_id, registered_program.key);
+    if expected_entry != *entry_account.key {
+        msg!("Entry account does not match the expected PDA");
+        return Err(DirectoryError::WrongAccount.into());
+    }
+
+    invoke_signed(
+        &system_instruction::create_account(
+            authority.key,
+            entry_account.key,
+            Rent::get()?.minimum_balance(ENTRY_ACCOUNT_SIZE),
+            ENTRY_ACCOUNT_SIZE as u64,
+            program_id,
+        ),
+        &[authority.clone(), entry_account.clone(), system_program.clone()],
+        &[&[ENTRY_SEED, registered_program.key.as_ref(), &[bump]]],
+    )?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let entry = ProgramEntry {
+        is_initialized: true,
+        program: *registered_program.key,
+        authority: *authority.key,
+        name,
+        version,
+        description,
+        registered_at: now,
+        updated_at: now,
+        bump,
+    };
+    entry.serialize(&mut &mut entry_account.data.borrow_mut()[..])?;
+
+    directory.program_count = directory
+        .program_count
+        .checked_add(1)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    directory.serialize(&mut &mut directory_account.data.borrow_mut()[..])?;
+
+    msg!("Registered {} v{} at {}", entry.name, entry.version, entry.program);
+
+    Ok(())
+}
+
+fn update(program_id: &Pubkey, accounts: &[AccountInfo], version: String, description: String) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let authority = next_account_info(accounts_iter)?;
+    let entry_account = next_account_info(accounts_iter)?;
+
+    let mut entry = load_entry_as_authority(program_id, entry_account, authority)?;
+
+    validate_metadata(&entry.name, &version, &description)?;
+
+    entry.version = version;
+    entry.description = description;
+    entry.updated_at = Clock::get()?.unix_timestamp;
+    entry.serialize(&mut &mut entry_account.data.borrow_mut()[..])?;
+
+    msg!("Updated {} to v{}", entry.name, entry.version);
+
+    Ok(())
+}
+
+fn unregister(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let authority = next_account_info(accounts_iter)?;
+    let directory_account = next_account_info(accounts_iter)?;
+    let entry_account = next_account_info(accounts_iter)?;
+
+    let entry = load_entry_as_authority(program_id, entry_account, authority)?;
+    let mut directory = load_directory(program_id, directory_account)?;
+
+    let lamports = entry_account.lamports();
+    **entry_account.try_borrow_mut_lamports()? = 0;
+    **authority.try_borrow_mut_lamports()? = authority
+        .lamports()
+        .checked_add(lamports)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    entry_account.data.borrow_mut().fill(0);
+
+    directory.program_count = directory.program_count.saturating_sub(1);
+    directory.serialize(&mut &mut directory_account.data.borrow_mut()[..])?;
+
+    msg!("Unregistered {} at {}", entry.name, entry.program);
+
+    Ok(())
+}
+
+fn load_directory(program_id: &Pubkey, directory_account: &AccountInfo) -> Result<DirectoryAccount, ProgramError> {
+    if directory_account.owner != program_id || *directory_account.key != directory_address(program_id).0 {
+        msg!("Not this program's directory account");
+        return Err(DirectoryError::WrongAccount.into());
+    }
+
+    let directory = DirectoryAccount::try_from_slice(&directory_account.data.borrow())?;
+
+    if !directory.is_initialized {
+        return Err(DirectoryError::NotInitialized.into());
+    }
+
+    Ok(directory)
+}
+
+fn load_entry_as_authority(
+    program_id: &Pubkey,
+    entry_account: &AccountInfo,
+    authority: &AccountInfo,
+) -> Result<ProgramEntry, ProgramError> {
+    if !authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if entry_account.owner != program_id {
+        msg!("Entry account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    // Allocated for the longest strings, so the entry may not fill the account
+    let entry = ProgramEntry::deserialize(&mut &entry_account.data.borrow()[..])?;
+
+    if !entry.is_initialized {
+        return Err(DirectoryError::NotInitialized.into());
+    }
+
+    if entry.authority != *authority.key {
+        msg!("Only {} can change this entry", entry.authority);
+        return Err(DirectoryError::Unauthorized.into());
+    }
+
+    Ok(entry)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_program::{clock::Epoch, entrypoint::SUCCESS, program_stubs};
+
+    /// Serves Clock at a fixed time so handlers can run off-chain
+    struct TestSyscallStubs {
+        unix_timestamp: i64,
+    }
+
+    impl program_stubs::SyscallStubs for TestSyscallStubs {
+        fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+            unsafe {
+                *(var_addr as *mut Clock) = Clock {
+                    unix_timestamp: self.unix_timestamp,
+                    ..Clock::default()
+                };
+            }
+            SUCCESS
+        }
+    }
+
+    fn program_data(authority: Option<Pubkey>) -> Vec<u8> {
+        let mut data = 3u32.to_le_bytes().to_vec();
+        data.extend_from_slice(&0u64.to_le_bytes());
+        match authority {
+            Some(authority) => {
+                data.push(1);
+                data.extend_from_slice(authority.as_ref());
+            }
+            None => data.extend_from_slice(&[0; 33]),
+        }
+        data
+    }
+
+    #[test]
+    fn test_metadata_and_upgrade_authority() {
+        assert_eq!(validate_metadata("crowdfund", "1.0.0", "All-or-nothing crowdfunding"), Ok(()));
+        assert_eq!(validate_metadata("", "1.0.0", "x"), Err(DirectoryError::InvalidMetadata));
+        let long_version = "1".repeat(MAX_VERSION_LENGTH + 1);
+        assert_eq!(validate_metadata("crowdfund", &long_version, "x"), Err(DirectoryError::InvalidMetadata));
+
+        let full = ProgramEntry {
+            is_initialized: true,
+            program: Pubkey::new_unique(),
+            authority: Pubkey::new_unique(),
+            name: "n".repeat(MAX_NAME_LENGTH),
+            version: "v".repeat(MAX_VERSION_LENGTH),
+            description: "d".repeat(MAX_DESCRIPTION_LENGTH),
+            registered_at: 0,
+            updated_at: 0,
+            bump: 255,
+        };
+        assert_eq!(full.try_to_vec().unwrap().len(), ENTRY_ACCOUNT_SIZE);
+
+        let registered_program = Pubkey::new_unique();
+        let authority = Pubkey::new_unique();
+        let loader = bpf_loader_upgradeable::id();
+        let (data_key, _) = Pubkey::find_program_address(&[registered_program.as_ref()], &loader);
+
+        let mut lamports = 1;
+        let mut data = program_data(Some(authority));
+        let upgradeable = AccountInfo::new(&data_key, false, false, &mut lamports, &mut data, &loader, false, 0);
+        assert_eq!(upgrade_authority(&registered_program, &upgradeable), Ok(authority));
+        // The program data of some other program does not count
+        let result = upgrade_authority(&Pubkey::new_unique(), &upgradeable);
+        assert_eq!(result, Err(DirectoryError::InvalidProgramData.into()));
+
+        let mut lamports = 1;
+        let mut data = program_data(None);
+        let immutable = AccountInfo::new(&data_key, false, false, &mut lamports, &mut data, &loader, false, 0);
+        let result = upgrade_authority(&registered_program, &immutable);
+        assert_eq!(result, Err(DirectoryError::InvalidProgramData.into()));
+    }
+
+    #[test]
+    fn test_update_and_unregister() {
+        program_stubs::set_syscall_stubs(Box::new(TestSyscallStubs { unix_timestamp: 500 }));
+
+        let program_id = Pubkey::new_unique();
+        let authority = Pubkey::new_unique();
+        let registered_program = Pubkey::new_unique();
+        let (directory_key, bump) = directory_address(&program_id);
+        let (entry_key, entry_bump) = entry_address(&program_id, &registered_program);
+
+        let directory = DirectoryAccount {
+            is_initialized: true,
+            program_count: 1,
+            bump,
+        };
+        let entry = ProgramEntry {
+            is_initialized: true,
+            program: registered_program,
+            authority,
+            name: "counter".to_string(),
+            version: "1.0.0".to_string(),
+            description: "Counter example".to_string(),
+            registered_at: 100,
+            updated_at: 100,
+            bump: entry_bump,
+        };
+        let mut entry_data = entry.try_to_vec().unwrap();
+        entry_data.resize(ENTRY_ACCOUNT_SIZE, 0);
+
+        let keys = [authority, directory_key, entry_key];
+        let mut lamports: Vec<u64> = vec![0, 1_000, 2_000];
+        let mut data: Vec<Vec<u8>> = vec![vec![], directory.try_to_vec().unwrap(), entry_data];
+
+        let accounts: Vec<AccountInfo> = keys
+            .iter()
+            .zip(lamports.iter_mut())
+            .zip(data.iter_mut())
+            .enumerate()
+            .map(|(i, ((key, lamports), data))| {
+                AccountInfo::new(key, i == 0, true, lamports, data, &program_id, false, Epoch::default())
+            })
+            .collect();
+
+        let update = DirectoryInstruction::Update {
+            version: "1.1.0".to_string(),
+            description: "Counter with reset".to_string(),
+        }
+        .try_to_vec()
+        .unwrap();
+        let mut update_accounts = [accounts[0].clone(), accounts[2].clone()];
+
+        // The authority has to sign
+        update_accounts[0].is_signer = false;
+        let result = process_instruction(&program_id, &update_accounts, &update);
+        assert_eq!(result, Err(ProgramError::MissingRequiredSignature));
+
+        update_accounts[0].is_signer = true;
+        process_instruction(&program_id, &update_accounts, &update).unwrap();
+
+        let stored = ProgramEntry::deserialize(&mut &accounts[2].data.borrow()[..]).unwrap();
+        assert_eq!(stored.version, "1.1.0");
+        assert_eq!(stored.registered_at, 100);
+        assert_eq!(stored.updated_at, 500);
+
+        let unregister = DirectoryInstruction::Unregister.try_to_vec().unwrap();
+        process_instruction(&program_id, &accounts, &unregister).unwrap();
+        assert_eq!(accounts[0].lamports(), 2_000);
+        let stored = DirectoryAccount::try_from_slice(&accounts[1].data.borrow()).unwrap();
+        assert_eq!(stored.program_count, 0);
+
+        // The closed entry cannot be edited
+        let result = process_instruction(&program_id, &update_accounts, &update);
+        assert_eq!(result, Err(DirectoryError::NotInitialized.into()));
+    }
+}
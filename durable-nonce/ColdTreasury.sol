@@ -0,0 +1,56 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+/**
+ * @title ColdTreasury
+ * @dev ETH held for an authority whose key is kept offline. Ethereum
+ * transactions never expire and carry a per-account nonce, so offline signing
+ * needs no special support; this contract shows the equivalent meta-transaction
+ * pattern, where the authority signs a withdrawal that anyone can relay and
+ * can cancel it by bumping the nonce.
+ */
+contract ColdTreasury {
+    address public immutable authority;
+    uint256 public nonce;
+    uint256 public totalWithdrawn;
+
+    event Deposited(address indexed from, uint256 amount);
+    event Withdrawn(address indexed recipient, uint256 amount, uint256 nonce);
+    event NonceAdvanced(uint256 nonce);
+
+    constructor(address _authority) {
+        require(_authority != address(0), "Invalid authority");
+        authority = _authority;
+    }
+
+    receive() external payable {
+        emit Deposited(msg.sender, msg.value);
+    }
+
+    /// Hash the authority signs offline (EIP-191 personal message)
+    function withdrawalHash(address recipient, uint256 amount, uint256 _nonce) public view returns (bytes32) {
+        bytes32 inner = keccak256(abi.encode(block.chainid, address(this), recipient, amount, _nonce));
+        return keccak256(abi.encodePacked("\x19Ethereum Signed Message:\n32", inner));
+    }
+
+    /// Relay a withdrawal signed offline against the current nonce
+    function withdraw(address payable recipient, uint256 amount, uint8 v, bytes32 r, bytes32 s) external {
+        address signer = ecrecover(withdrawalHash(recipient, amount, nonce), v, r, s);
+        require(signer != address(0) && signer == authority, "Invalid signature");
+        require(amount <= address(this).balance, "Insufficient funds");
+
+        emit Withdrawn(recipient, amount, nonce);
+        nonce++;
+        totalWithdrawn += amount;
+
+        (bool success, ) = recipient.call{value: amount}("");
+        require(success, "Transfer failed");
+    }
+
+    /// Invalidate any signed but unsubmitted withdrawal
+    function advanceNonce() external {
+        require(msg.sender == authority, "Only authority");
+        nonce++;
+        emit NonceAdvanced(nonce);
+    }
+}
@@ -0,0 +1,368 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint,
+    entrypoint::ProgramResult,
+    instruction::Instruction,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction, system_program,
+    sysvar::{instructions::load_instruction_at_checked, Sysvar},
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// Seed prefix for treasury PDAs: ["treasury", authority]
+pub const TREASURY_SEED: &[u8] = b"treasury";
+
+/// Bincode encoding of `SystemInstruction::AdvanceNonceAccount` (variant 4 as a u32)
+pub const ADVANCE_NONCE_DATA: [u8; 4] = [4, 0, 0, 0];
+
+/// Size of a `TreasuryAccount`
+pub const TREASURY_ACCOUNT_SIZE: usize = 1 // is_initialized
+    + 32 // authority
+    + 32 // nonce_account
+    + 8 // total_withdrawn
+    + 1; // bump
+
+/// Errors returned by the program as `ProgramError::Custom` codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NonceError {
+    /// The signer is not the treasury authority
+    Unauthorized = 0,
+    /// The treasury has not been initialized
+    NotInitialized = 1,
+    /// The transaction does not start by advancing the registered nonce account
+    NonceNotAdvanced = 2,
+    /// The withdrawal would leave the treasury below rent exemption
+    InsufficientFunds = 3,
+}
+
+impl From<NonceError> for ProgramError {
+    fn from(e: NonceError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+/// SOL held for an authority whose key is kept offline. Withdrawals must be
+/// signed against `nonce_account`, so an approved but unsubmitted withdrawal
+/// can be cancelled by advancing the nonce.
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct TreasuryAccount {
+    pub is_initialized: bool,
+    pub authority: Pubkey,
+    pub nonce_account: Pubkey,
+    pub total_withdrawn: u64,
+    pub bump: u8,
+}
+
+/// Whether `ix` advances `nonce_account`
+pub fn is_advance_nonce(ix: &Instruction, nonce_account: &Pubkey) -> bool {
+    ix.program_id == system_program::id()
+        && ix.data == ADVANCE_NONCE_DATA
+        && ix.accounts.first().map(|meta| &meta.pubkey) == Some(nonce_account)
+}
+
+entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = NonceInstruction::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        NonceInstruction::Initialize { nonce_account } => initialize(program_id, accounts, nonce_account),
+        NonceInstruction::Withdraw { amount } => withdraw(program_id, accounts, amount),
+        NonceInstruction::SetNonceAccount { nonce_account } => set_nonce_account(program_id, accounts, nonce_account),
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub enum NonceInstruction {
+    /// Create a treasury bound to an existing nonce account. Anyone can fund
+    /// it with a plain system transfer.
+    /// Accounts: [writable, signer] authority, [writable] treasury PDA, [] system program
+    Initialize { nonce_account: Pubkey },
+    /// Send `amount` lamports to a recipient. Must be in a durable nonce
+    /// transaction using the registered nonce account.
+    /// Accounts: [signer] authority, [writable] treasury PDA, [writable] recipient, [] instructions sysvar
+    Withdraw { amount: u64 },
+    /// Bind the treasury to a different nonce account
+    /// Accounts: [signer] authority, [writable] treasury PDA
+    SetNonceAccount { nonce_account: Pubkey },
+}
+
+fn initialize(program_id: &Pubkey, accounts: &[AccountInfo], nonce_account: Pubkey) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let authority = next_account_info(accounts_iter)?;
+    let treasury_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let (expected_treasury, bump) =
+        Pubkey::find_program_address(&[TREASURY_SEED, authority.key.as_ref()], program_id);
+    if expected_treasury != *treasury_account.key {
+        msg!("Treasury account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    invoke_signed(
+        &system_instruction::create_account(
+            authority.key,
+            treasury_account.key,
+            Rent::get()?.minimum_balance(TREASURY_ACCOUNT_SIZE),
+            TREASURY_ACCOUNT_SIZE as u64,
+            program_id,
+        ),
+        &[authority.clone(), treasury_account.clone(), system_program.clone()],
+        &[&[TREASURY_SEED, authority.key.as_ref(), &[bump]]],
+    )?;
+
+    let treasury = TreasuryAccount {
+        is_initialized: true,
+        authority: *authority.key,
+        nonce_account,
+        total_withdrawn: 0,
+        bump,
+    };
+    treasury.serialize(&mut &mut treasury_account.data.borrow_mut()[..])?;
+
+    msg!("Treasury created with nonce account {}", nonce_account);
+
+    Ok(())
+}
+
+fn withdraw(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let authority = next_account_info(accounts_iter)?;
+    let treasury_account = next_account_info(accounts_iter)?;
+    let recipient = next_account_info(accounts_iter)?;
+    let instructions_sysvar = next_account_info(accounts_iter)?;
+
+    if !authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut treasury = load_treasury(program_id, treasury_account)?;
+
+    if treasury.authority != *authority.key {
+        return Err(NonceError::Unauthorized.into());
+    }
+
+    require_durable_nonce(instructions_sysvar, &treasury.nonce_account)?;
+
+    let available = treasury_account
+        .lamports()
+        .saturating_sub(Rent::get()?.minimum_balance(treasury_account.data_len()));
+    if amount > available {
+        msg!("Only {} lamports available", available);
+        return Err(NonceError::InsufficientFunds.into());
+    }
+
+    // The treasury is owned by this program, so lamports move directly
+    **treasury_account.try_borrow_mut_lamports()? -= amount;
+    **recipient.try_borrow_mut_lamports()? += amount;
+
+    treasury.total_withdrawn = treasury
+        .total_withdrawn
+        .checked_add(amount)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    treasury.serialize(&mut &mut treasury_account.data.borrow_mut()[..])?;
+
+    msg!("Withdrew {} lamports to {}", amount, recipient.key);
+
+    Ok(())
+}
+
+fn set_nonce_account(program_id: &Pubkey, accounts: &[AccountInfo], nonce_account: Pubkey) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let authority = next_account_info(accounts_iter)?;
+    let treasury_account = next_account_info(accounts_iter)?;
+
+    if !authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut treasury = load_treasury(program_id, treasury_account)?;
+
+    if treasury.authority != *authority.key {
+        return Err(NonceError::Unauthorized.into());
+    }
+
+    treasury.nonce_account = nonce_account;
+    treasury.serialize(&mut &mut treasury_account.data.borrow_mut()[..])?;
+
+    msg!("Nonce account set to {}", nonce_account);
+
+    Ok(())
+}
+
+/// Require that the transaction's first instruction advances `nonce_account`.
+/// The runtime only accepts a durable nonce transaction when that instruction
+/// comes first, so this also proves the transaction was signed against it.
+fn require_durable_nonce(instructions_sysvar: &AccountInfo, nonce_account: &Pubkey) -> ProgramResult {
+    let first = load_instruction_at_checked(0, instructions_sysvar)?;
+    if !is_advance_nonce(&first, nonce_account) {
+        msg!("Expected the transaction to advance nonce account {}", nonce_account);
+        return Err(NonceError::NonceNotAdvanced.into());
+    }
+    Ok(())
+}
+
+/// Validate the treasury account and return it
+fn load_treasury(program_id: &Pubkey, treasury_account: &AccountInfo) -> Result<TreasuryAccount, ProgramError> {
+    if treasury_account.owner != program_id {
+        msg!("Treasury account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let treasury = TreasuryAccount::try_from_slice(&treasury_account.data.borrow())?;
+
+    if !treasury.is_initialized {
+        return Err(NonceError::NotInitialized.into());
+    }
+
+    Ok(treasury)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_program::{
+        clock::Epoch,
+        entrypoint::SUCCESS,
+        program_stubs, sysvar,
+        sysvar::instructions::{construct_instructions_data, BorrowedAccountMeta, BorrowedInstruction},
+    };
+
+    /// Serves a default Rent sysvar so handlers can run off-chain
+    struct TestSyscallStubs;
+
+    impl program_stubs::SyscallStubs for TestSyscallStubs {
+        fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+            unsafe {
+                *(var_addr as *mut Rent) = Rent::default();
+            }
+            SUCCESS
+        }
+    }
+
+    #[test]
+    fn test_is_advance_nonce() {
+        let nonce_account = Pubkey::new_unique();
+        let authority = Pubkey::new_unique();
+
+        let advance = system_instruction::advance_nonce_account(&nonce_account, &authority);
+        assert_eq!(advance.data, ADVANCE_NONCE_DATA);
+        assert!(is_advance_nonce(&advance, &nonce_account));
+
+        // Advancing some other nonce account does not count
+        let other = system_instruction::advance_nonce_account(&Pubkey::new_unique(), &authority);
+        assert!(!is_advance_nonce(&other, &nonce_account));
+
+        let transfer = system_instruction::transfer(&nonce_account, &authority, 4);
+        assert!(!is_advance_nonce(&transfer, &nonce_account));
+    }
+
+    #[test]
+    fn test_withdraw_requires_durable_nonce() {
+        program_stubs::set_syscall_stubs(Box::new(TestSyscallStubs));
+
+        let program_id = Pubkey::new_unique();
+        let authority_key = Pubkey::new_unique();
+        let nonce_key = Pubkey::new_unique();
+        let rent_exempt = Rent::default().minimum_balance(TREASURY_ACCOUNT_SIZE);
+
+        let treasury = TreasuryAccount {
+            is_initialized: true,
+            authority: authority_key,
+            nonce_account: nonce_key,
+            total_withdrawn: 0,
+            bump: 255,
+        };
+
+        let keys = [authority_key, Pubkey::new_unique(), Pubkey::new_unique()];
+        let mut lamports: Vec<u64> = vec![0, rent_exempt + 1_000, 0];
+        let mut data: Vec<Vec<u8>> = vec![vec![]; keys.len()];
+        data[1] = treasury.try_to_vec().unwrap();
+
+        let mut accounts: Vec<AccountInfo> = keys
+            .iter()
+            .zip(lamports.iter_mut())
+            .zip(data.iter_mut())
+            .enumerate()
+            .map(|(i, ((key, lamports), data))| {
+                AccountInfo::new(key, i == 0, true, lamports, data, &program_id, false, Epoch::default())
+            })
+            .collect();
+
+        let system_program_id = system_program::id();
+        let withdraw_data = NonceInstruction::Withdraw { amount: 600 }.try_to_vec().unwrap();
+        let sysvar_for = |first_data: &[u8]| {
+            construct_instructions_data(&[
+                BorrowedInstruction {
+                    program_id: &system_program_id,
+                    accounts: vec![BorrowedAccountMeta {
+                        pubkey: &nonce_key,
+                        is_signer: false,
+                        is_writable: true,
+                    }],
+                    data: first_data,
+                },
+                BorrowedInstruction {
+                    program_id: &program_id,
+                    accounts: vec![],
+                    data: &withdraw_data,
+                },
+            ])
+        };
+
+        let sysvar_key = sysvar::instructions::id();
+        let sysvar_owner = sysvar::id();
+
+        // A recent-blockhash transaction starting with some other instruction is rejected
+        let mut sysvar_lamports = 0;
+        let mut sysvar_data = sysvar_for(&[2, 0, 0, 0]);
+        accounts.push(AccountInfo::new(
+            &sysvar_key,
+            false,
+            false,
+            &mut sysvar_lamports,
+            &mut sysvar_data,
+            &sysvar_owner,
+            false,
+            Epoch::default(),
+        ));
+        let result = process_instruction(&program_id, &accounts, &withdraw_data);
+        assert_eq!(result, Err(NonceError::NonceNotAdvanced.into()));
+        accounts.pop();
+
+        let mut sysvar_lamports = 0;
+        let mut sysvar_data = sysvar_for(&ADVANCE_NONCE_DATA);
+        accounts.push(AccountInfo::new(
+            &sysvar_key,
+            false,
+            false,
+            &mut sysvar_lamports,
+            &mut sysvar_data,
+            &sysvar_owner,
+            false,
+            Epoch::default(),
+        ));
+        let result = process_instruction(&program_id, &accounts, &withdraw_data);
+        assert!(result.is_ok());
+        assert_eq!(accounts[1].lamports(), rent_exempt + 400);
+        assert_eq!(accounts[2].lamports(), 600);
+
+        // The rest would dip below rent exemption
+        let result = process_instruction(&program_id, &accounts, &withdraw_data);
+        assert_eq!(result, Err(NonceError::InsufficientFunds.into()));
+    }
+}
@@ -0,0 +1,107 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+interface IERC20 {
+    function transfer(address to, uint256 amount) external returns (bool);
+    function transferFrom(address from, address to, uint256 amount) external returns (bool);
+}
+
+/**
+ * @title DutchAuction
+ * @dev Declining-price sale of an ERC20 lot for ETH; the first buyer at the current price wins
+ */
+contract DutchAuction {
+    address payable public immutable seller;
+    IERC20 public immutable item;
+    uint256 public immutable itemAmount;
+    uint256 public immutable startPrice;
+    uint256 public immutable floorPrice;
+    uint256 public immutable startBlock;
+    uint256 public immutable durationBlocks;
+    // 0 = linear decay; otherwise the price drops every `stepBlocks` blocks
+    uint256 public immutable stepBlocks;
+
+    bool public closed;
+
+    event Sold(address indexed buyer, uint256 price);
+    event Cancelled();
+
+    constructor(
+        IERC20 _item,
+        uint256 _itemAmount,
+        uint256 _startPrice,
+        uint256 _floorPrice,
+        uint256 _durationBlocks,
+        uint256 _stepBlocks
+    ) {
+        require(_itemAmount > 0 && _durationBlocks > 0, "Invalid parameters");
+        require(_floorPrice <= _startPrice, "Floor above start price");
+        seller = payable(msg.sender);
+        item = _item;
+        itemAmount = _itemAmount;
+        startPrice = _startPrice;
+        floorPrice = _floorPrice;
+        startBlock = block.number;
+        durationBlocks = _durationBlocks;
+        stepBlocks = _stepBlocks;
+    }
+
+    /**
+     * @dev Price at the current block
+     */
+    function currentPrice() public view returns (uint256) {
+        uint256 elapsed = block.number - startBlock;
+        if (elapsed > durationBlocks) {
+            elapsed = durationBlocks;
+        }
+        if (stepBlocks > 0) {
+            elapsed -= elapsed % stepBlocks;
+        }
+        return startPrice - ((startPrice - floorPrice) * elapsed) / durationBlocks;
+    }
+
+    /**
+     * @dev Deposit the item; call after `approve`
+     */
+    function deposit() external {
+        require(msg.sender == seller, "Only seller");
+        require(item.transferFrom(msg.sender, address(this), itemAmount), "Transfer failed");
+    }
+
+    /**
+     * @dev Buy at the current price; any excess ETH is refunded
+     */
+    function buy() external payable {
+        require(!closed, "Auction closed");
+
+        uint256 price = currentPrice();
+        require(msg.value >= price, "Price exceeds payment");
+
+        closed = true;
+
+        require(item.transfer(msg.sender, itemAmount), "Transfer failed");
+
+        (bool paid, ) = seller.call{value: price}("");
+        require(paid, "Payment failed");
+
+        if (msg.value > price) {
+            (bool refunded, ) = msg.sender.call{value: msg.value - price}("");
+            require(refunded, "Refund failed");
+        }
+
+        emit Sold(msg.sender, price);
+    }
+
+    /**
+     * @dev Return the unsold item to the seller
+     */
+    function cancel() external {
+        require(msg.sender == seller, "Only seller");
+        require(!closed, "Auction closed");
+
+        closed = true;
+        require(item.transfer(seller, itemAmount), "Transfer failed");
+
+        emit Cancelled();
+    }
+}
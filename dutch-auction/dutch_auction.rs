@@ -0,0 +1,513 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint,
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use spl_token::state::Account as TokenAccount;
+
+/// Seed prefix for auction PDAs: ["dutch_auction", seller, mint]
+pub const AUCTION_SEED: &[u8] = b"dutch_auction";
+
+/// Seed prefix for the item vault PDA: ["vault", auction]
+pub const VAULT_SEED: &[u8] = b"vault";
+
+/// Size of a `DutchAuctionAccount`
+pub const AUCTION_ACCOUNT_SIZE: usize = 1 // is_initialized
+    + 32 // seller
+    + 32 // mint
+    + 8 // item_amount
+    + 8 // start_price
+    + 8 // floor_price
+    + 8 // start_slot
+    + 8 // duration_slots
+    + 1 + 8 // decay
+    + 1 // bump
+    + 1; // vault_bump
+
+/// Errors returned by the program as `ProgramError::Custom` codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DutchAuctionError {
+    /// Amount, duration, or step is zero, or the floor is above the start price
+    InvalidParameters = 0,
+    /// The current price is above the buyer's maximum
+    PriceExceedsMax = 1,
+    /// The auction has not been initialized
+    NotInitialized = 2,
+    /// A token account does not hold the expected mint or owner
+    InvalidTokenAccount = 3,
+}
+
+impl From<DutchAuctionError> for ProgramError {
+    fn from(e: DutchAuctionError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+/// How the price falls from `start_price` to `floor_price`
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecayMode {
+    /// The price drops a little every slot
+    Linear,
+    /// The price drops only every `step_slots` slots
+    Step { step_slots: u64 },
+}
+
+/// A declining-price sale of `item_amount` of `mint` for lamports
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct DutchAuctionAccount {
+    pub is_initialized: bool,
+    pub seller: Pubkey,
+    pub mint: Pubkey,
+    pub item_amount: u64,
+    /// Price in lamports at `start_slot`
+    pub start_price: u64,
+    /// Price in lamports from `start_slot + duration_slots` onward
+    pub floor_price: u64,
+    pub start_slot: u64,
+    pub duration_slots: u64,
+    pub decay: DecayMode,
+    pub bump: u8,
+    pub vault_bump: u8,
+}
+
+impl DutchAuctionAccount {
+    /// Price in lamports at `slot`
+    pub fn current_price(&self, slot: u64) -> u64 {
+        let elapsed = slot.saturating_sub(self.start_slot).min(self.duration_slots);
+        let elapsed = match self.decay {
+            DecayMode::Linear => elapsed,
+            DecayMode::Step { step_slots } => elapsed - elapsed % step_slots,
+        };
+        let drop = (self.start_price - self.floor_price) as u128 * elapsed as u128
+            / self.duration_slots as u128;
+        self.start_price - drop as u64
+    }
+}
+
+entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = DutchAuctionInstruction::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        DutchAuctionInstruction::CreateAuction {
+            item_amount,
+            start_price,
+            floor_price,
+            duration_slots,
+            decay,
+        } => create_auction(
+            program_id,
+            accounts,
+            item_amount,
+            start_price,
+            floor_price,
+            duration_slots,
+            decay,
+        ),
+        DutchAuctionInstruction::Buy { max_price } => buy(program_id, accounts, max_price),
+        DutchAuctionInstruction::Cancel => cancel(program_id, accounts),
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub enum DutchAuctionInstruction {
+    /// Create the auction and vault PDAs and deposit the item; the price starts falling immediately
+    /// Accounts: [writable, signer] seller, [writable] auction PDA, [writable] vault PDA, [] mint,
+    /// [writable] seller token account, [] token program, [] system program
+    CreateAuction {
+        item_amount: u64,
+        start_price: u64,
+        floor_price: u64,
+        duration_slots: u64,
+        decay: DecayMode,
+    },
+    /// Pay the current price to the seller and receive the item; fails if the
+    /// price is above `max_price`. Closes the auction.
+    /// Accounts: [writable, signer] buyer, [writable] seller, [writable] auction PDA, [writable] vault,
+    /// [writable] buyer token account, [] token program, [] system program
+    Buy { max_price: u64 },
+    /// Return the unsold item to the seller and close the auction
+    /// Accounts: [writable, signer] seller, [writable] auction PDA, [writable] vault,
+    /// [writable] seller token account, [] token program
+    Cancel,
+}
+
+fn create_auction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    item_amount: u64,
+    start_price: u64,
+    floor_price: u64,
+    duration_slots: u64,
+    decay: DecayMode,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let seller = next_account_info(accounts_iter)?;
+    let auction_account = next_account_info(accounts_iter)?;
+    let vault = next_account_info(accounts_iter)?;
+    let mint = next_account_info(accounts_iter)?;
+    let seller_token = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !seller.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if item_amount == 0 || duration_slots == 0 || floor_price > start_price {
+        msg!("Need a non-zero amount and duration, and floor price <= start price");
+        return Err(DutchAuctionError::InvalidParameters.into());
+    }
+
+    if decay == (DecayMode::Step { step_slots: 0 }) {
+        msg!("Step size must be greater than zero");
+        return Err(DutchAuctionError::InvalidParameters.into());
+    }
+
+    if *token_program.key != spl_token::id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let (expected_auction, bump) = Pubkey::find_program_address(
+        &[AUCTION_SEED, seller.key.as_ref(), mint.key.as_ref()],
+        program_id,
+    );
+    if expected_auction != *auction_account.key {
+        msg!("Auction account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let (expected_vault, vault_bump) =
+        Pubkey::find_program_address(&[VAULT_SEED, auction_account.key.as_ref()], program_id);
+    if expected_vault != *vault.key {
+        msg!("Vault account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let clock = Clock::get()?;
+    let rent = Rent::get()?;
+
+    invoke_signed(
+        &system_instruction::create_account(
+            seller.key,
+            auction_account.key,
+            rent.minimum_balance(AUCTION_ACCOUNT_SIZE),
+            AUCTION_ACCOUNT_SIZE as u64,
+            program_id,
+        ),
+        &[seller.clone(), auction_account.clone(), system_program.clone()],
+        &[&[AUCTION_SEED, seller.key.as_ref(), mint.key.as_ref(), &[bump]]],
+    )?;
+
+    // Create the item vault, owned by the auction PDA, and deposit the item
+    invoke_signed(
+        &system_instruction::create_account(
+            seller.key,
+            vault.key,
+            rent.minimum_balance(TokenAccount::LEN),
+            TokenAccount::LEN as u64,
+            &spl_token::id(),
+        ),
+        &[seller.clone(), vault.clone(), system_program.clone()],
+        &[&[VAULT_SEED, auction_account.key.as_ref(), &[vault_bump]]],
+    )?;
+    invoke(
+        &spl_token::instruction::initialize_account3(
+            &spl_token::id(),
+            vault.key,
+            mint.key,
+            auction_account.key,
+        )?,
+        &[vault.clone(), mint.clone()],
+    )?;
+    invoke(
+        &spl_token::instruction::transfer(
+            &spl_token::id(),
+            seller_token.key,
+            vault.key,
+            seller.key,
+            &[],
+            item_amount,
+        )?,
+        &[seller_token.clone(), vault.clone(), seller.clone()],
+    )?;
+
+    let auction = DutchAuctionAccount {
+        is_initialized: true,
+        seller: *seller.key,
+        mint: *mint.key,
+        item_amount,
+        start_price,
+        floor_price,
+        start_slot: clock.slot,
+        duration_slots,
+        decay,
+        bump,
+        vault_bump,
+    };
+    auction.serialize(&mut &mut auction_account.data.borrow_mut()[..])?;
+
+    msg!(
+        "Dutch auction: {} -> {} lamports over {} slots",
+        start_price,
+        floor_price,
+        duration_slots
+    );
+
+    Ok(())
+}
+
+fn buy(program_id: &Pubkey, accounts: &[AccountInfo], max_price: u64) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let buyer = next_account_info(accounts_iter)?;
+    let seller = next_account_info(accounts_iter)?;
+    let auction_account = next_account_info(accounts_iter)?;
+    let vault = next_account_info(accounts_iter)?;
+    let buyer_token = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !buyer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let auction = load_auction(program_id, auction_account, seller, vault, token_program)?;
+
+    let price = auction.current_price(Clock::get()?.slot);
+    if price > max_price {
+        msg!("Current price {} is above the maximum {}", price, max_price);
+        return Err(DutchAuctionError::PriceExceedsMax.into());
+    }
+
+    invoke(
+        &system_instruction::transfer(buyer.key, seller.key, price),
+        &[buyer.clone(), seller.clone(), system_program.clone()],
+    )?;
+
+    close_auction(auction_account, seller, vault, buyer_token, &auction)?;
+
+    msg!("Sold to {} for {} lamports", buyer.key, price);
+
+    Ok(())
+}
+
+fn cancel(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let seller = next_account_info(accounts_iter)?;
+    let auction_account = next_account_info(accounts_iter)?;
+    let vault = next_account_info(accounts_iter)?;
+    let seller_token = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+
+    if !seller.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let auction = load_auction(program_id, auction_account, seller, vault, token_program)?;
+
+    let seller_account = TokenAccount::unpack(&seller_token.data.borrow())?;
+    if seller_account.owner != auction.seller {
+        msg!("Return token account must belong to the seller");
+        return Err(DutchAuctionError::InvalidTokenAccount.into());
+    }
+
+    close_auction(auction_account, seller, vault, seller_token, &auction)?;
+
+    msg!("Auction cancelled");
+
+    Ok(())
+}
+
+/// Validate the auction account, its seller, and its vault, returning the state
+fn load_auction(
+    program_id: &Pubkey,
+    auction_account: &AccountInfo,
+    seller: &AccountInfo,
+    vault: &AccountInfo,
+    token_program: &AccountInfo,
+) -> Result<DutchAuctionAccount, ProgramError> {
+    if auction_account.owner != program_id {
+        msg!("Auction account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    if *token_program.key != spl_token::id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let auction = DutchAuctionAccount::try_from_slice(&auction_account.data.borrow())?;
+    if !auction.is_initialized {
+        return Err(DutchAuctionError::NotInitialized.into());
+    }
+
+    if auction.seller != *seller.key {
+        msg!("Seller account does not match the auction");
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let expected_vault = Pubkey::create_program_address(
+        &[VAULT_SEED, auction_account.key.as_ref(), &[auction.vault_bump]],
+        program_id,
+    )?;
+    if expected_vault != *vault.key {
+        msg!("Vault account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    Ok(auction)
+}
+
+/// Send the item to `destination`, close the vault, and close the auction
+/// account, returning all rent to the seller
+fn close_auction<'a>(
+    auction_account: &AccountInfo<'a>,
+    seller: &AccountInfo<'a>,
+    vault: &AccountInfo<'a>,
+    destination: &AccountInfo<'a>,
+    auction: &DutchAuctionAccount,
+) -> ProgramResult {
+    let signer_seeds: &[&[u8]] = &[AUCTION_SEED, auction.seller.as_ref(), auction.mint.as_ref(), &[auction.bump]];
+
+    invoke_signed(
+        &spl_token::instruction::transfer(
+            &spl_token::id(),
+            vault.key,
+            destination.key,
+            auction_account.key,
+            &[],
+            auction.item_amount,
+        )?,
+        &[vault.clone(), destination.clone(), auction_account.clone()],
+        &[signer_seeds],
+    )?;
+
+    invoke_signed(
+        &spl_token::instruction::close_account(
+            &spl_token::id(),
+            vault.key,
+            seller.key,
+            auction_account.key,
+            &[],
+        )?,
+        &[vault.clone(), seller.clone(), auction_account.clone()],
+        &[signer_seeds],
+    )?;
+
+    let lamports = auction_account.lamports();
+    **auction_account.try_borrow_mut_lamports()? -= lamports;
+    **seller.try_borrow_mut_lamports()? += lamports;
+    auction_account.data.borrow_mut().fill(0);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_program::{clock::Epoch, entrypoint::SUCCESS, program_stubs};
+
+    /// Serves a Clock sysvar at a fixed slot so handlers can run off-chain
+    struct TestSyscallStubs {
+        slot: u64,
+    }
+
+    impl program_stubs::SyscallStubs for TestSyscallStubs {
+        fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+            unsafe {
+                *(var_addr as *mut Clock) = Clock {
+                    slot: self.slot,
+                    ..Clock::default()
+                };
+            }
+            SUCCESS
+        }
+    }
+
+    fn auction(decay: DecayMode, vault_bump: u8) -> DutchAuctionAccount {
+        DutchAuctionAccount {
+            is_initialized: true,
+            seller: Pubkey::new_unique(),
+            mint: Pubkey::new_unique(),
+            item_amount: 1,
+            start_price: 1_000,
+            floor_price: 200,
+            start_slot: 100,
+            duration_slots: 80,
+            decay,
+            bump: 255,
+            vault_bump,
+        }
+    }
+
+    #[test]
+    fn test_linear_and_step_price_curves() {
+        let linear = auction(DecayMode::Linear, 255);
+        assert_eq!(linear.current_price(50), 1_000);
+        assert_eq!(linear.current_price(100), 1_000);
+        assert_eq!(linear.current_price(101), 990);
+        assert_eq!(linear.current_price(140), 600);
+        assert_eq!(linear.current_price(180), 200);
+        assert_eq!(linear.current_price(500), 200);
+
+        let step = auction(DecayMode::Step { step_slots: 20 }, 255);
+        assert_eq!(step.current_price(119), 1_000);
+        assert_eq!(step.current_price(120), 800);
+        assert_eq!(step.current_price(159), 600);
+        assert_eq!(step.current_price(180), 200);
+    }
+
+    #[test]
+    fn test_buy_above_max_price_fails() {
+        program_stubs::set_syscall_stubs(Box::new(TestSyscallStubs { slot: 140 }));
+
+        let program_id = Pubkey::new_unique();
+        let auction_key = Pubkey::new_unique();
+        let (vault_key, vault_bump) =
+            Pubkey::find_program_address(&[VAULT_SEED, auction_key.as_ref()], &program_id);
+        let state = auction(DecayMode::Linear, vault_bump);
+
+        let keys = [
+            Pubkey::new_unique(),
+            state.seller,
+            auction_key,
+            vault_key,
+            Pubkey::new_unique(),
+            spl_token::id(),
+            solana_program::system_program::id(),
+        ];
+        let mut lamports: Vec<u64> = vec![10_000; keys.len()];
+        let mut data: Vec<Vec<u8>> = vec![vec![]; keys.len()];
+        data[2] = state.try_to_vec().unwrap();
+
+        let accounts: Vec<AccountInfo> = keys
+            .iter()
+            .zip(lamports.iter_mut())
+            .zip(data.iter_mut())
+            .enumerate()
+            .map(|(i, ((key, lamports), data))| {
+                AccountInfo::new(key, i == 0, true, lamports, data, &program_id, false, Epoch::default())
+            })
+            .collect();
+
+        // The price at slot 140 is 600
+        let instruction_data = DutchAuctionInstruction::Buy { max_price: 599 }.try_to_vec().unwrap();
+        let result = process_instruction(&program_id, &accounts, &instruction_data);
+        assert_eq!(result, Err(DutchAuctionError::PriceExceedsMax.into()));
+    }
+}
@@ -0,0 +1,32 @@
+use num_derive::FromPrimitive;
+use thiserror::Error;
+
+/// Errors returned by the counter program
+#[derive(Clone, Copy, Debug, Eq, Error, FromPrimitive, PartialEq)]
+pub enum CounterError {
+    /// The signer is not the counter's owner
+    #[error("Only the owner can change the counter")]
+    NotOwner = 0,
+    /// Incrementing would pass `u64::MAX`
+    #[error("Counter would overflow")]
+    Overflow = 1,
+    /// Decrementing would go below zero
+    #[error("Counter is already zero")]
+    Underflow = 2,
+}
+
+crate::program_error!(CounterError);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_program::{decode_error::DecodeError, program_error::ProgramError};
+
+    #[test]
+    fn test_codes_are_stable() {
+        assert_eq!(ProgramError::from(CounterError::NotOwner), ProgramError::Custom(0));
+        assert_eq!(ProgramError::from(CounterError::Underflow), ProgramError::Custom(2));
+        assert_eq!(CounterError::decode_custom_error_to_enum(1), Some(CounterError::Overflow));
+        assert_eq!(CounterError::decode_custom_error_to_enum(3), None::<CounterError>);
+    }
+}
@@ -0,0 +1,60 @@
+use num_derive::FromPrimitive;
+use thiserror::Error;
+
+/// Errors returned by the crowdfund program
+#[derive(Clone, Copy, Debug, Eq, Error, FromPrimitive, PartialEq)]
+pub enum CrowdfundError {
+    /// `Initialize` with a goal of zero
+    #[error("Goal must be greater than zero")]
+    InvalidGoal = 0,
+    /// `Initialize` with a duration of zero slots
+    #[error("Duration must be greater than zero")]
+    InvalidDuration = 1,
+    /// A deadline, contribution or refund total would overflow `u64`
+    #[error("Arithmetic overflow")]
+    Overflow = 2,
+    /// `Contribute` with an amount of zero
+    #[error("Must contribute a positive amount")]
+    ZeroContribution = 3,
+    /// The deadline has passed
+    #[error("Campaign has ended")]
+    CampaignEnded = 4,
+    /// The owner has already withdrawn the funds
+    #[error("Campaign is already finalized")]
+    AlreadyFinalized = 5,
+    /// The contributor is not a member of the campaign's allowlist
+    #[error("Contributor is not on the campaign's allowlist")]
+    NotAllowlisted = 6,
+    /// The signer is not the campaign's owner
+    #[error("Only the campaign owner can do this")]
+    NotOwner = 7,
+    /// The deadline has not passed yet
+    #[error("Campaign is still active")]
+    CampaignActive = 8,
+    /// `Withdraw` on a campaign that missed its goal
+    #[error("Goal not reached")]
+    GoalNotReached = 9,
+    /// `Refund` or `BatchRefund` on a campaign that reached its goal
+    #[error("Goal was reached, no refunds")]
+    GoalReached = 10,
+    /// The contributor record holds nothing to refund
+    #[error("No contribution to refund")]
+    NothingToRefund = 11,
+}
+
+crate::program_error!(CrowdfundError);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_program::{decode_error::DecodeError, program_error::ProgramError};
+
+    #[test]
+    fn test_codes_are_stable() {
+        assert_eq!(ProgramError::from(CrowdfundError::InvalidGoal), ProgramError::Custom(0));
+        assert_eq!(ProgramError::from(CrowdfundError::CampaignActive), ProgramError::Custom(8));
+        assert_eq!(ProgramError::from(CrowdfundError::NothingToRefund), ProgramError::Custom(11));
+        assert_eq!(CrowdfundError::decode_custom_error_to_enum(10), Some(CrowdfundError::GoalReached));
+        assert_eq!(CrowdfundError::decode_custom_error_to_enum(12), None::<CrowdfundError>);
+    }
+}
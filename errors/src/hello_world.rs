@@ -0,0 +1,100 @@
+use num_derive::FromPrimitive;
+use thiserror::Error;
+
+/// Errors returned by the hello-world program
+#[derive(Clone, Copy, Debug, Eq, Error, FromPrimitive, PartialEq)]
+pub enum HelloWorldError {
+    /// The message is empty
+    #[error("Message cannot be empty")]
+    MessageEmpty = 0,
+    /// The message exceeds `MAX_MESSAGE_LENGTH` bytes
+    #[error("Message too long")]
+    MessageTooLong = 1,
+    /// The signer is not the message account's author
+    #[error("Only the author can perform this action")]
+    NotAuthor = 2,
+    /// The message account has not been initialized. No longer returned:
+    /// the shared checks fail with `ProgramError::UninitializedAccount`.
+    /// Kept so code 3 is not reused
+    #[deprecated(note = "uninitialized accounts fail with `ProgramError::UninitializedAccount`")]
+    #[error("Message account is not initialized")]
+    NotInitialized = 3,
+    /// The signer is not on the writer allowlist
+    #[error("Updater is not on the writer allowlist")]
+    NotWriter = 4,
+    /// The writer is already on the allowlist
+    #[error("Writer is already on the allowlist")]
+    WriterAlreadyListed = 5,
+    /// The writer allowlist is at `MAX_WRITERS`
+    #[error("Writer allowlist is full")]
+    WriterListFull = 6,
+    /// The writer is not on the allowlist
+    #[error("Writer is not on the allowlist")]
+    WriterNotListed = 7,
+    /// The updater's cooldown has not elapsed
+    #[error("Updater is cooling down")]
+    CoolingDown = 8,
+    /// The stored revision differs from the expected one
+    #[error("Revision mismatch")]
+    RevisionMismatch = 9,
+    /// A TTL of zero slots was requested
+    #[error("TTL must be greater than zero")]
+    InvalidTtl = 10,
+    /// The message has no expiry, so it cannot be reaped
+    #[error("Message does not expire")]
+    MessageDoesNotExpire = 11,
+    /// The message has not expired yet
+    #[error("Message has not expired yet")]
+    MessageNotExpired = 12,
+    /// The token account is not an SPL token account of the gate mint
+    /// owned by the updater
+    #[error("Token account must hold the gate mint and belong to the updater")]
+    InvalidTokenAccount = 13,
+    /// The updater holds fewer gate tokens than the minimum balance
+    #[error("Insufficient token balance")]
+    InsufficientTokenBalance = 14,
+    /// No off-chain signing authority is configured
+    #[error("No signing authority configured")]
+    NoSigningAuthority = 15,
+    /// The preceding instruction is not a matching ed25519 verification
+    #[error("Expected a matching ed25519 verification instruction")]
+    InvalidSignatureInstruction = 16,
+    /// The content URI exceeds `MAX_URI_LENGTH` bytes
+    #[error("URI too long")]
+    UriTooLong = 17,
+    /// The message account does not hold hash-anchored content
+    #[error("Message has no anchored content")]
+    NoContentAnchor = 18,
+    /// The supplied content does not hash to the anchored hash
+    #[error("Content does not match the anchored hash")]
+    ContentHashMismatch = 19,
+    /// The signer is not the message account's moderator
+    #[error("Only the moderator can perform this action")]
+    NotModerator = 20,
+    /// A revision, reply count or expiry slot would overflow `u64`
+    #[error("Arithmetic overflow")]
+    Overflow = 21,
+    /// `SetMessage` passed a moderator for an account that is already initialized
+    #[error("Moderator can only be set at initialization")]
+    ModeratorAlreadySet = 22,
+    /// `SetTokenGate` with a minimum balance of zero
+    #[error("Minimum balance must be greater than zero")]
+    InvalidMinBalance = 23,
+}
+
+crate::program_error!(HelloWorldError);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_program::{decode_error::DecodeError, program_error::ProgramError};
+
+    #[test]
+    fn test_codes_are_stable() {
+        assert_eq!(ProgramError::from(HelloWorldError::MessageEmpty), ProgramError::Custom(0));
+        assert_eq!(ProgramError::from(HelloWorldError::NotModerator), ProgramError::Custom(20));
+        assert_eq!(ProgramError::from(HelloWorldError::InvalidMinBalance), ProgramError::Custom(23));
+        assert_eq!(HelloWorldError::decode_custom_error_to_enum(9), Some(HelloWorldError::RevisionMismatch));
+        assert_eq!(HelloWorldError::decode_custom_error_to_enum(24), None::<HelloWorldError>);
+    }
+}
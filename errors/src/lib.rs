@@ -0,0 +1,52 @@
+//! Custom errors of the workspace's programs.
+//!
+//! Each program gets its own enum, returned as `ProgramError::Custom(code)`.
+//! The codes are part of the programs' interface: clients match on them, so
+//! variants are only ever appended and never renumbered. Account-shape
+//! failures (missing signer, wrong owner, uninitialized state) keep the
+//! built-in `ProgramError` variants from the `common` checks.
+//!
+//! The entrypoints log failures by name with `PrintProgramError`, so a failed
+//! transaction shows e.g. `Campaign is still active` instead of only
+//! `custom program error: 0x8`.
+
+mod counter;
+mod crowdfund;
+// Its derives still reference the deprecated `HelloWorldError::NotInitialized`
+#[allow(deprecated)]
+mod hello_world;
+
+pub use counter::CounterError;
+pub use crowdfund::CrowdfundError;
+pub use hello_world::HelloWorldError;
+
+/// `ProgramError` conversion, decoding and logging for a program's error enum
+macro_rules! program_error {
+    ($error:ident) => {
+        impl From<$error> for solana_program::program_error::ProgramError {
+            fn from(e: $error) -> Self {
+                solana_program::program_error::ProgramError::Custom(e as u32)
+            }
+        }
+
+        impl<T> solana_program::decode_error::DecodeError<T> for $error {
+            fn type_of() -> &'static str {
+                stringify!($error)
+            }
+        }
+
+        impl solana_program::program_error::PrintProgramError for $error {
+            fn print<E>(&self)
+            where
+                E: 'static
+                    + std::error::Error
+                    + solana_program::decode_error::DecodeError<E>
+                    + solana_program::program_error::PrintProgramError
+                    + num_traits::FromPrimitive,
+            {
+                solana_program::msg!("Error: {}", self);
+            }
+        }
+    };
+}
+pub(crate) use program_error;
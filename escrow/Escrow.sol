@@ -0,0 +1,86 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+interface IERC20 {
+    function transfer(address to, uint256 amount) external returns (bool);
+    function transferFrom(address from, address to, uint256 amount) external returns (bool);
+}
+
+/**
+ * @title Escrow
+ * @dev Atomic ERC20 swap: a maker deposits token A, and a taker pays token B to receive it
+ */
+contract Escrow {
+    struct Offer {
+        address maker;
+        IERC20 tokenA;
+        IERC20 tokenB;
+        uint256 amountA;
+        uint256 amountB;
+        uint256 expiresAtBlock;
+    }
+
+    uint256 public nextOfferId;
+    mapping(uint256 => Offer) public offers;
+
+    event OfferMade(uint256 indexed offerId, address indexed maker, uint256 amountA, uint256 amountB);
+    event OfferTaken(uint256 indexed offerId, address indexed taker);
+    event OfferCancelled(uint256 indexed offerId, address indexed caller);
+
+    /**
+     * @dev Deposit `amountA` of token A and request `amountB` of token B in return
+     * @return offerId The id of the new offer
+     */
+    function make(
+        IERC20 tokenA,
+        IERC20 tokenB,
+        uint256 amountA,
+        uint256 amountB,
+        uint256 durationBlocks
+    ) external returns (uint256 offerId) {
+        require(amountA > 0 && amountB > 0, "Amounts must be greater than zero");
+        require(durationBlocks > 0, "Duration must be greater than zero");
+
+        offerId = nextOfferId++;
+        offers[offerId] = Offer(msg.sender, tokenA, tokenB, amountA, amountB, block.number + durationBlocks);
+
+        require(tokenA.transferFrom(msg.sender, address(this), amountA), "Deposit failed");
+
+        emit OfferMade(offerId, msg.sender, amountA, amountB);
+    }
+
+    /**
+     * @dev Pay token B to the maker and receive the deposited token A
+     */
+    function take(uint256 offerId) external {
+        Offer memory offer = offers[offerId];
+        require(offer.maker != address(0), "Offer does not exist");
+        require(block.number < offer.expiresAtBlock, "Offer expired");
+
+        delete offers[offerId];
+
+        require(offer.tokenB.transferFrom(msg.sender, offer.maker, offer.amountB), "Payment failed");
+        require(offer.tokenA.transfer(msg.sender, offer.amountA), "Release failed");
+
+        emit OfferTaken(offerId, msg.sender);
+    }
+
+    /**
+     * @dev Return the deposit to the maker. The maker may cancel at any time;
+     * anyone may cancel once the offer has expired.
+     */
+    function cancel(uint256 offerId) external {
+        Offer memory offer = offers[offerId];
+        require(offer.maker != address(0), "Offer does not exist");
+        require(
+            msg.sender == offer.maker || block.number >= offer.expiresAtBlock,
+            "Only the maker can cancel before expiry"
+        );
+
+        delete offers[offerId];
+
+        require(offer.tokenA.transfer(offer.maker, offer.amountA), "Refund failed");
+
+        emit OfferCancelled(offerId, msg.sender);
+    }
+}
@@ -0,0 +1,553 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint,
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use spl_token::state::Account as TokenAccount;
+
+/// Seed prefix for escrow state PDAs: ["escrow", maker, seed]
+pub const ESCROW_SEED: &[u8] = b"escrow";
+
+/// Seed prefix for vault token account PDAs: ["vault", escrow]
+pub const VAULT_SEED: &[u8] = b"vault";
+
+/// Size of an `EscrowAccount`
+pub const ESCROW_ACCOUNT_SIZE: usize = 1 // is_initialized
+    + 32 // maker
+    + 32 // mint_a
+    + 32 // mint_b
+    + 8 // amount_a
+    + 8 // amount_b
+    + 8 // expires_at_slot
+    + 8 // seed
+    + 1 // bump
+    + 1; // vault_bump
+
+/// Errors returned by the program as `ProgramError::Custom` codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EscrowError {
+    /// Offered and requested amounts must be greater than zero
+    InvalidAmount = 0,
+    /// The escrow has expired and can no longer be taken
+    Expired = 1,
+    /// Only the maker may cancel before the escrow expires
+    NotExpired = 2,
+    /// A token account does not hold the expected mint or owner
+    InvalidTokenAccount = 3,
+    /// The escrow has not been initialized
+    NotInitialized = 4,
+}
+
+impl From<EscrowError> for ProgramError {
+    fn from(e: EscrowError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+/// An open offer: `amount_a` of `mint_a` sits in the vault until someone
+/// pays `amount_b` of `mint_b` to the maker
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct EscrowAccount {
+    pub is_initialized: bool,
+    /// Creator of the offer, who receives token B and all rent back
+    pub maker: Pubkey,
+    /// Mint of the deposited token
+    pub mint_a: Pubkey,
+    /// Mint the maker wants in return
+    pub mint_b: Pubkey,
+    /// Amount of token A held in the vault
+    pub amount_a: u64,
+    /// Amount of token B the taker must pay
+    pub amount_b: u64,
+    /// Slot after which the offer can no longer be taken
+    pub expires_at_slot: u64,
+    /// Maker-chosen seed, so one maker can have several open offers
+    pub seed: u64,
+    pub bump: u8,
+    pub vault_bump: u8,
+}
+
+entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = EscrowInstruction::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        EscrowInstruction::Make {
+            seed,
+            amount_a,
+            amount_b,
+            duration_slots,
+        } => make(program_id, accounts, seed, amount_a, amount_b, duration_slots),
+        EscrowInstruction::Take => take(program_id, accounts),
+        EscrowInstruction::Cancel => cancel(program_id, accounts),
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub enum EscrowInstruction {
+    /// Create the escrow and vault PDAs and deposit `amount_a` of token A
+    /// Accounts: [writable, signer] maker, [writable] escrow PDA, [writable] vault PDA,
+    /// [] mint_a, [] mint_b, [writable] maker token A account, [] token program, [] system program
+    Make {
+        seed: u64,
+        amount_a: u64,
+        amount_b: u64,
+        duration_slots: u64,
+    },
+    /// Pay `amount_b` of token B to the maker and receive the vault's token A;
+    /// closes the vault and escrow, returning rent to the maker
+    /// Accounts: [signer] taker, [writable] maker, [writable] escrow PDA, [writable] vault PDA,
+    /// [writable] taker token A account, [writable] taker token B account,
+    /// [writable] maker token B account, [] token program
+    Take,
+    /// Return the deposit to the maker and close the escrow. The maker may
+    /// cancel at any time; anyone may cancel once the escrow has expired.
+    /// Accounts: [signer] caller, [writable] maker, [writable] escrow PDA, [writable] vault PDA,
+    /// [writable] maker token A account, [] token program
+    Cancel,
+}
+
+fn make(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    seed: u64,
+    amount_a: u64,
+    amount_b: u64,
+    duration_slots: u64,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let maker = next_account_info(accounts_iter)?;
+    let escrow_account = next_account_info(accounts_iter)?;
+    let vault = next_account_info(accounts_iter)?;
+    let mint_a = next_account_info(accounts_iter)?;
+    let mint_b = next_account_info(accounts_iter)?;
+    let maker_token_a = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !maker.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if amount_a == 0 || amount_b == 0 {
+        msg!("Amounts must be greater than zero");
+        return Err(EscrowError::InvalidAmount.into());
+    }
+
+    if duration_slots == 0 {
+        msg!("Duration must be greater than zero");
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    if *token_program.key != spl_token::id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let seed_bytes = seed.to_le_bytes();
+    let (expected_escrow, bump) =
+        Pubkey::find_program_address(&[ESCROW_SEED, maker.key.as_ref(), &seed_bytes], program_id);
+    if expected_escrow != *escrow_account.key {
+        msg!("Escrow account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let (expected_vault, vault_bump) =
+        Pubkey::find_program_address(&[VAULT_SEED, escrow_account.key.as_ref()], program_id);
+    if expected_vault != *vault.key {
+        msg!("Vault account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let clock = Clock::get()?;
+
+    // Create the escrow state account
+    create_pda_account(
+        escrow_account,
+        maker,
+        system_program,
+        ESCROW_ACCOUNT_SIZE,
+        program_id,
+        &[ESCROW_SEED, maker.key.as_ref(), &seed_bytes, &[bump]],
+    )?;
+
+    // Create the vault token account, owned by the escrow PDA
+    create_pda_account(
+        vault,
+        maker,
+        system_program,
+        TokenAccount::LEN,
+        &spl_token::id(),
+        &[VAULT_SEED, escrow_account.key.as_ref(), &[vault_bump]],
+    )?;
+    invoke(
+        &spl_token::instruction::initialize_account3(
+            &spl_token::id(),
+            vault.key,
+            mint_a.key,
+            escrow_account.key,
+        )?,
+        &[vault.clone(), mint_a.clone()],
+    )?;
+
+    // Deposit token A
+    invoke(
+        &spl_token::instruction::transfer(
+            &spl_token::id(),
+            maker_token_a.key,
+            vault.key,
+            maker.key,
+            &[],
+            amount_a,
+        )?,
+        &[maker_token_a.clone(), vault.clone(), maker.clone()],
+    )?;
+
+    let escrow = EscrowAccount {
+        is_initialized: true,
+        maker: *maker.key,
+        mint_a: *mint_a.key,
+        mint_b: *mint_b.key,
+        amount_a,
+        amount_b,
+        expires_at_slot: clock
+            .slot
+            .checked_add(duration_slots)
+            .ok_or(ProgramError::InvalidInstructionData)?,
+        seed,
+        bump,
+        vault_bump,
+    };
+    escrow.serialize(&mut &mut escrow_account.data.borrow_mut()[..])?;
+
+    msg!(
+        "Escrow opened: {} of {} for {} of {}",
+        amount_a,
+        mint_a.key,
+        amount_b,
+        mint_b.key
+    );
+
+    Ok(())
+}
+
+fn take(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let taker = next_account_info(accounts_iter)?;
+    let maker = next_account_info(accounts_iter)?;
+    let escrow_account = next_account_info(accounts_iter)?;
+    let vault = next_account_info(accounts_iter)?;
+    let taker_token_a = next_account_info(accounts_iter)?;
+    let taker_token_b = next_account_info(accounts_iter)?;
+    let maker_token_b = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+
+    if !taker.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let escrow = load_escrow(program_id, escrow_account, maker, vault, token_program)?;
+
+    let clock = Clock::get()?;
+    if clock.slot >= escrow.expires_at_slot {
+        msg!("Escrow expired at slot {}", escrow.expires_at_slot);
+        return Err(EscrowError::Expired.into());
+    }
+
+    // Token B must reach the maker, not an account the taker controls
+    let maker_b = TokenAccount::unpack(&maker_token_b.data.borrow())?;
+    if maker_b.mint != escrow.mint_b || maker_b.owner != escrow.maker {
+        msg!("Maker token B account must hold mint B and belong to the maker");
+        return Err(EscrowError::InvalidTokenAccount.into());
+    }
+
+    invoke(
+        &spl_token::instruction::transfer(
+            &spl_token::id(),
+            taker_token_b.key,
+            maker_token_b.key,
+            taker.key,
+            &[],
+            escrow.amount_b,
+        )?,
+        &[taker_token_b.clone(), maker_token_b.clone(), taker.clone()],
+    )?;
+
+    release_vault(escrow_account, maker, vault, taker_token_a, &escrow)?;
+
+    msg!("Escrow taken by {}", taker.key);
+
+    Ok(())
+}
+
+fn cancel(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let caller = next_account_info(accounts_iter)?;
+    let maker = next_account_info(accounts_iter)?;
+    let escrow_account = next_account_info(accounts_iter)?;
+    let vault = next_account_info(accounts_iter)?;
+    let maker_token_a = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+
+    if !caller.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let escrow = load_escrow(program_id, escrow_account, maker, vault, token_program)?;
+
+    if *caller.key != escrow.maker {
+        let clock = Clock::get()?;
+        if clock.slot < escrow.expires_at_slot {
+            msg!("Only the maker can cancel before slot {}", escrow.expires_at_slot);
+            return Err(EscrowError::NotExpired.into());
+        }
+    }
+
+    let maker_a = TokenAccount::unpack(&maker_token_a.data.borrow())?;
+    if maker_a.owner != escrow.maker {
+        msg!("Refund token account must belong to the maker");
+        return Err(EscrowError::InvalidTokenAccount.into());
+    }
+
+    release_vault(escrow_account, maker, vault, maker_token_a, &escrow)?;
+
+    msg!("Escrow cancelled by {}", caller.key);
+
+    Ok(())
+}
+
+/// Validate the escrow state account and its vault, returning the state
+fn load_escrow(
+    program_id: &Pubkey,
+    escrow_account: &AccountInfo,
+    maker: &AccountInfo,
+    vault: &AccountInfo,
+    token_program: &AccountInfo,
+) -> Result<EscrowAccount, ProgramError> {
+    if escrow_account.owner != program_id {
+        msg!("Escrow account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    if *token_program.key != spl_token::id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let escrow = EscrowAccount::try_from_slice(&escrow_account.data.borrow())?;
+
+    if !escrow.is_initialized {
+        msg!("Escrow has not been initialized");
+        return Err(EscrowError::NotInitialized.into());
+    }
+
+    if escrow.maker != *maker.key {
+        msg!("Maker account does not match the escrow");
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let expected_vault = Pubkey::create_program_address(
+        &[VAULT_SEED, escrow_account.key.as_ref(), &[escrow.vault_bump]],
+        program_id,
+    )?;
+    if expected_vault != *vault.key {
+        msg!("Vault account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    Ok(escrow)
+}
+
+/// Send the vault's tokens to `destination`, close the vault, and close the
+/// escrow state account, returning all rent to the maker
+fn release_vault<'a>(
+    escrow_account: &AccountInfo<'a>,
+    maker: &AccountInfo<'a>,
+    vault: &AccountInfo<'a>,
+    destination: &AccountInfo<'a>,
+    escrow: &EscrowAccount,
+) -> ProgramResult {
+    let seed_bytes = escrow.seed.to_le_bytes();
+    let signer_seeds: &[&[u8]] = &[ESCROW_SEED, escrow.maker.as_ref(), &seed_bytes, &[escrow.bump]];
+
+    invoke_signed(
+        &spl_token::instruction::transfer(
+            &spl_token::id(),
+            vault.key,
+            destination.key,
+            escrow_account.key,
+            &[],
+            escrow.amount_a,
+        )?,
+        &[vault.clone(), destination.clone(), escrow_account.clone()],
+        &[signer_seeds],
+    )?;
+
+    invoke_signed(
+        &spl_token::instruction::close_account(
+            &spl_token::id(),
+            vault.key,
+            maker.key,
+            escrow_account.key,
+            &[],
+        )?,
+        &[vault.clone(), maker.clone(), escrow_account.clone()],
+        &[signer_seeds],
+    )?;
+
+    // Close the escrow state account
+    let maker_lamports = maker
+        .lamports()
+        .checked_add(escrow_account.lamports())
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    **maker.try_borrow_mut_lamports()? = maker_lamports;
+    **escrow_account.try_borrow_mut_lamports()? = 0;
+    escrow_account.data.borrow_mut().fill(0);
+
+    Ok(())
+}
+
+/// Create the PDA `account` with `space` bytes owned by `owner`, rent exempt
+/// and paid for by `payer`. `create_account` fails if anyone has already sent
+/// lamports to the address, so top it up, then allocate and assign instead.
+fn create_pda_account<'a>(
+    account: &AccountInfo<'a>,
+    payer: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    space: usize,
+    owner: &Pubkey,
+    signer_seeds: &[&[u8]],
+) -> ProgramResult {
+    let shortfall = Rent::get()?.minimum_balance(space).saturating_sub(account.lamports());
+    if shortfall > 0 {
+        invoke(
+            &system_instruction::transfer(payer.key, account.key, shortfall),
+            &[payer.clone(), account.clone(), system_program.clone()],
+        )?;
+    }
+    let accounts = [account.clone(), system_program.clone()];
+    invoke_signed(&system_instruction::allocate(account.key, space as u64), &accounts, &[signer_seeds])?;
+    invoke_signed(&system_instruction::assign(account.key, owner), &accounts, &[signer_seeds])
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_program::{clock::Epoch, entrypoint::SUCCESS, program_stubs};
+
+    /// Serves a Clock sysvar at a fixed slot so handlers can run off-chain
+    struct TestSyscallStubs {
+        slot: u64,
+    }
+
+    impl program_stubs::SyscallStubs for TestSyscallStubs {
+        fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+            unsafe {
+                *(var_addr as *mut Clock) = Clock {
+                    slot: self.slot,
+                    ..Clock::default()
+                };
+            }
+            SUCCESS
+        }
+    }
+
+    #[test]
+    fn test_make_rejects_zero_amount() {
+        let program_id = Pubkey::new_unique();
+        let keys: Vec<Pubkey> = (0..8).map(|_| Pubkey::new_unique()).collect();
+        let mut lamports: Vec<u64> = vec![0; 8];
+        let mut data: Vec<Vec<u8>> = vec![vec![]; 8];
+
+        let accounts: Vec<AccountInfo> = keys
+            .iter()
+            .zip(lamports.iter_mut())
+            .zip(data.iter_mut())
+            .enumerate()
+            .map(|(i, ((key, lamports), data))| {
+                AccountInfo::new(key, i == 0, true, lamports, data, &program_id, false, Epoch::default())
+            })
+            .collect();
+
+        let instruction = EscrowInstruction::Make {
+            seed: 0,
+            amount_a: 0,
+            amount_b: 100,
+            duration_slots: 100,
+        };
+        let instruction_data = instruction.try_to_vec().unwrap();
+
+        let result = process_instruction(&program_id, &accounts, &instruction_data);
+        assert_eq!(result, Err(EscrowError::InvalidAmount.into()));
+    }
+
+    #[test]
+    fn test_take_after_expiry_fails() {
+        program_stubs::set_syscall_stubs(Box::new(TestSyscallStubs { slot: 500 }));
+
+        let program_id = Pubkey::new_unique();
+        let maker_key = Pubkey::new_unique();
+        let taker_key = Pubkey::new_unique();
+        let escrow_key = Pubkey::new_unique();
+        let (vault_key, vault_bump) =
+            Pubkey::find_program_address(&[VAULT_SEED, escrow_key.as_ref()], &program_id);
+        let token_program_id = spl_token::id();
+
+        let escrow = EscrowAccount {
+            is_initialized: true,
+            maker: maker_key,
+            mint_a: Pubkey::new_unique(),
+            mint_b: Pubkey::new_unique(),
+            amount_a: 10,
+            amount_b: 20,
+            expires_at_slot: 500,
+            seed: 0,
+            bump: 255,
+            vault_bump,
+        };
+        let mut escrow_data = escrow.try_to_vec().unwrap();
+
+        let other_keys: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+        let keys = [
+            taker_key,
+            maker_key,
+            escrow_key,
+            vault_key,
+            other_keys[0],
+            other_keys[1],
+            other_keys[2],
+            token_program_id,
+        ];
+        let mut lamports: Vec<u64> = vec![0; keys.len()];
+        let mut data: Vec<Vec<u8>> = vec![vec![]; keys.len()];
+        data[2] = std::mem::take(&mut escrow_data);
+
+        let accounts: Vec<AccountInfo> = keys
+            .iter()
+            .zip(lamports.iter_mut())
+            .zip(data.iter_mut())
+            .enumerate()
+            .map(|(i, ((key, lamports), data))| {
+                AccountInfo::new(key, i == 0, true, lamports, data, &program_id, false, Epoch::default())
+            })
+            .collect();
+
+        let instruction_data = EscrowInstruction::Take.try_to_vec().unwrap();
+        let result = process_instruction(&program_id, &accounts, &instruction_data);
+        assert_eq!(result, Err(EscrowError::Expired.into()));
+    }
+}
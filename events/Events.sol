@@ -0,0 +1,27 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+/**
+ * @title Events
+ * @dev Counter that reports changes through events. Indexers subscribe to
+ * logs by the contract address and the event signature in topic 0.
+ */
+contract Events {
+    mapping(address => uint64) public counts;
+
+    // Indexed parameters become topics that clients can filter on;
+    // the rest is ABI-encoded in the log data
+    event CounterIncremented(address indexed authority, uint64 amount, uint64 count);
+    event CounterReset(address indexed authority, uint64 previousCount);
+
+    function increment(uint64 amount) external {
+        counts[msg.sender] += amount;
+        emit CounterIncremented(msg.sender, amount, counts[msg.sender]);
+    }
+
+    function reset() external {
+        uint64 previousCount = counts[msg.sender];
+        counts[msg.sender] = 0;
+        emit CounterReset(msg.sender, previousCount);
+    }
+}
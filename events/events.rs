@@ -0,0 +1,353 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint,
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    log::sol_log_data,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// Seed prefix for counter PDAs: ["counter", authority]
+pub const COUNTER_SEED: &[u8] = b"counter";
+
+/// Seed of the PDA that signs self-CPI events: ["__event_authority"], as in Anchor
+pub const EVENT_AUTHORITY_SEED: &[u8] = b"__event_authority";
+
+/// Prefix of self-CPI event instruction data: sha256("anchor:event")[..8] read as a
+/// big-endian u64 and written little-endian, matching Anchor's `EVENT_IX_TAG_LE`
+pub const EVENT_IX_TAG_LE: [u8; 8] = [228, 69, 165, 46, 81, 203, 154, 29];
+
+/// Size of a `CounterAccount`
+pub const COUNTER_ACCOUNT_SIZE: usize = 1 // is_initialized
+    + 32 // authority
+    + 8 // count
+    + 1; // bump
+
+/// Errors returned by the program as `ProgramError::Custom` codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventsError {
+    /// The signer is not the counter's authority
+    Unauthorized = 0,
+    /// The counter has not been initialized
+    NotInitialized = 1,
+    /// The event instruction was not signed by this program's event authority
+    InvalidEventAuthority = 2,
+}
+
+impl From<EventsError> for ProgramError {
+    fn from(e: EventsError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct CounterAccount {
+    pub is_initialized: bool,
+    pub authority: Pubkey,
+    pub count: u64,
+    pub bump: u8,
+}
+
+/// An event is an 8-byte discriminator, sha256("event:<Name>")[..8] as in
+/// Anchor, followed by the Borsh-encoded fields
+pub trait Event: BorshSerialize {
+    const DISCRIMINATOR: [u8; 8];
+
+    fn to_bytes(&self) -> Result<Vec<u8>, ProgramError> {
+        let mut bytes = Self::DISCRIMINATOR.to_vec();
+        self.serialize(&mut bytes)?;
+        Ok(bytes)
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct CounterIncremented {
+    pub counter: Pubkey,
+    pub authority: Pubkey,
+    pub amount: u64,
+    pub count: u64,
+}
+
+impl Event for CounterIncremented {
+    const DISCRIMINATOR: [u8; 8] = [219, 181, 183, 220, 88, 58, 114, 198];
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct CounterReset {
+    pub counter: Pubkey,
+    pub authority: Pubkey,
+    pub previous_count: u64,
+}
+
+impl Event for CounterReset {
+    const DISCRIMINATOR: [u8; 8] = [156, 40, 166, 79, 155, 217, 103, 87];
+}
+
+/// Emit through the program log as a `Program data: <base64>` line. Cheap, but
+/// RPC nodes truncate logs past 10KB per transaction, dropping later events.
+pub fn emit_log<E: Event>(event: &E) -> ProgramResult {
+    sol_log_data(&[&event.to_bytes()?]);
+    Ok(())
+}
+
+/// Emit by invoking this program with the event as instruction data. The event
+/// is recorded in the transaction's inner instructions, which are never
+/// truncated, at the cost of a CPI.
+pub fn emit_cpi<'a, E: Event>(
+    event: &E,
+    program_id: &Pubkey,
+    event_authority: &AccountInfo<'a>,
+    program: &AccountInfo<'a>,
+) -> ProgramResult {
+    let (expected_authority, bump) = Pubkey::find_program_address(&[EVENT_AUTHORITY_SEED], program_id);
+    if expected_authority != *event_authority.key || program.key != program_id {
+        msg!("Event authority or program account is not this program's");
+        return Err(EventsError::InvalidEventAuthority.into());
+    }
+
+    let mut data = EVENT_IX_TAG_LE.to_vec();
+    data.extend(event.to_bytes()?);
+
+    invoke_signed(
+        &Instruction {
+            program_id: *program_id,
+            accounts: vec![AccountMeta::new_readonly(*event_authority.key, true)],
+            data,
+        },
+        &[event_authority.clone(), program.clone()],
+        &[&[EVENT_AUTHORITY_SEED, &[bump]]],
+    )
+}
+
+entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    // Self-CPI events carry a tag no Borsh-encoded instruction starts with
+    if instruction_data.starts_with(&EVENT_IX_TAG_LE) {
+        return process_event(program_id, accounts);
+    }
+
+    let instruction = EventsInstruction::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        EventsInstruction::Initialize => initialize(program_id, accounts),
+        EventsInstruction::Increment { amount } => increment(program_id, accounts, amount),
+        EventsInstruction::Reset => reset(program_id, accounts),
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub enum EventsInstruction {
+    /// Create a counter for the signer
+    /// Accounts: [writable, signer] authority, [writable] counter PDA, [] system program
+    Initialize,
+    /// Add `amount`, emitting `CounterIncremented` through the program log
+    /// Accounts: [signer] authority, [writable] counter PDA
+    Increment { amount: u64 },
+    /// Set the count to zero, emitting `CounterReset` through a self-CPI
+    /// Accounts: [signer] authority, [writable] counter PDA, [] event authority PDA,
+    /// [] this program
+    Reset,
+}
+
+fn initialize(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let authority = next_account_info(accounts_iter)?;
+    let counter_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let (expected_counter, bump) =
+        Pubkey::find_program_address(&[COUNTER_SEED, authority.key.as_ref()], program_id);
+    if expected_counter != *counter_account.key {
+        msg!("Counter account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    invoke_signed(
+        &system_instruction::create_account(
+            authority.key,
+            counter_account.key,
+            Rent::get()?.minimum_balance(COUNTER_ACCOUNT_SIZE),
+            COUNTER_ACCOUNT_SIZE as u64,
+            program_id,
+        ),
+        &[authority.clone(), counter_account.clone(), system_program.clone()],
+        &[&[COUNTER_SEED, authority.key.as_ref(), &[bump]]],
+    )?;
+
+    let counter = CounterAccount {
+        is_initialized: true,
+        authority: *authority.key,
+        count: 0,
+        bump,
+    };
+    counter.serialize(&mut &mut counter_account.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+fn increment(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let authority = next_account_info(accounts_iter)?;
+    let counter_account = next_account_info(accounts_iter)?;
+
+    let mut counter = load_counter(program_id, counter_account, authority)?;
+
+    counter.count = counter.count.checked_add(amount).ok_or(ProgramError::ArithmeticOverflow)?;
+    counter.serialize(&mut &mut counter_account.data.borrow_mut()[..])?;
+
+    emit_log(&CounterIncremented {
+        counter: *counter_account.key,
+        authority: *authority.key,
+        amount,
+        count: counter.count,
+    })
+}
+
+fn reset(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let authority = next_account_info(accounts_iter)?;
+    let counter_account = next_account_info(accounts_iter)?;
+    let event_authority = next_account_info(accounts_iter)?;
+    let program = next_account_info(accounts_iter)?;
+
+    let mut counter = load_counter(program_id, counter_account, authority)?;
+
+    let previous_count = counter.count;
+    counter.count = 0;
+    counter.serialize(&mut &mut counter_account.data.borrow_mut()[..])?;
+
+    emit_cpi(
+        &CounterReset {
+            counter: *counter_account.key,
+            authority: *authority.key,
+            previous_count,
+        },
+        program_id,
+        event_authority,
+        program,
+    )
+}
+
+/// Accept an event self-CPI. Only this program can sign as the event authority,
+/// so indexers can trust any event instruction that reaches here.
+fn process_event(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let event_authority = next_account_info(accounts_iter)?;
+
+    let (expected_authority, _) = Pubkey::find_program_address(&[EVENT_AUTHORITY_SEED], program_id);
+    if !event_authority.is_signer || expected_authority != *event_authority.key {
+        msg!("Event instructions must be signed by the event authority");
+        return Err(EventsError::InvalidEventAuthority.into());
+    }
+
+    Ok(())
+}
+
+/// Validate the counter account and that `authority` signed for it, returning the counter
+fn load_counter(
+    program_id: &Pubkey,
+    counter_account: &AccountInfo,
+    authority: &AccountInfo,
+) -> Result<CounterAccount, ProgramError> {
+    if !authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if counter_account.owner != program_id {
+        msg!("Counter account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let counter = CounterAccount::try_from_slice(&counter_account.data.borrow())?;
+
+    if !counter.is_initialized {
+        return Err(EventsError::NotInitialized.into());
+    }
+
+    if counter.authority != *authority.key {
+        msg!("Signer is not the counter authority");
+        return Err(EventsError::Unauthorized.into());
+    }
+
+    Ok(counter)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_program::{clock::Epoch, hash::hash};
+
+    #[test]
+    fn test_discriminators_and_encoding() {
+        let tag = hash(b"anchor:event").to_bytes();
+        assert_eq!(
+            u64::from_be_bytes(tag[..8].try_into().unwrap()).to_le_bytes(),
+            EVENT_IX_TAG_LE
+        );
+        assert_eq!(hash(b"event:CounterIncremented").to_bytes()[..8], CounterIncremented::DISCRIMINATOR);
+        assert_eq!(hash(b"event:CounterReset").to_bytes()[..8], CounterReset::DISCRIMINATOR);
+
+        let event = CounterIncremented {
+            counter: Pubkey::new_unique(),
+            authority: Pubkey::new_unique(),
+            amount: 5,
+            count: 12,
+        };
+        let bytes = event.to_bytes().unwrap();
+        assert_eq!(bytes[..8], CounterIncremented::DISCRIMINATOR);
+        assert_eq!(CounterIncremented::try_from_slice(&bytes[8..]).unwrap(), event);
+
+        // No instruction variant index can collide with the event tag
+        assert_ne!(EventsInstruction::Reset.try_to_vec().unwrap()[0], EVENT_IX_TAG_LE[0]);
+    }
+
+    #[test]
+    fn test_forged_event_instruction_is_rejected() {
+        let program_id = Pubkey::new_unique();
+        let (event_authority, _) = Pubkey::find_program_address(&[EVENT_AUTHORITY_SEED], &program_id);
+
+        // Right key, but an outside caller cannot make the PDA a signer
+        let mut lamports = 0;
+        let mut data = vec![];
+        let accounts = vec![AccountInfo::new(
+            &event_authority,
+            false,
+            false,
+            &mut lamports,
+            &mut data,
+            &program_id,
+            false,
+            Epoch::default(),
+        )];
+
+        let mut instruction_data = EVENT_IX_TAG_LE.to_vec();
+        instruction_data.extend(
+            CounterReset {
+                counter: Pubkey::new_unique(),
+                authority: Pubkey::new_unique(),
+                previous_count: 1_000,
+            }
+            .to_bytes()
+            .unwrap(),
+        );
+        let result = process_instruction(&program_id, &accounts, &instruction_data);
+        assert_eq!(result, Err(EventsError::InvalidEventAuthority.into()));
+    }
+}
@@ -0,0 +1,78 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+/**
+ * @title FaucetToken
+ * @dev Test ERC20 that anyone can mint, up to a daily cap per wallet
+ */
+contract FaucetToken {
+    uint256 public constant WINDOW = 1 days;
+
+    string public name;
+    string public symbol;
+    uint8 public immutable decimals;
+    uint256 public immutable dailyCap;
+
+    uint256 public totalSupply;
+    mapping(address => uint256) public balanceOf;
+    mapping(address => mapping(address => uint256)) public allowance;
+
+    // Rolling 24-hour window per wallet
+    mapping(address => uint256) public windowStart;
+    mapping(address => uint256) public claimedInWindow;
+
+    event Transfer(address indexed from, address indexed to, uint256 value);
+    event Approval(address indexed owner, address indexed spender, uint256 value);
+
+    constructor(string memory _name, string memory _symbol, uint8 _decimals, uint256 _dailyCap) {
+        require(_dailyCap > 0, "Daily cap must be greater than zero");
+        name = _name;
+        symbol = _symbol;
+        decimals = _decimals;
+        dailyCap = _dailyCap;
+    }
+
+    /**
+     * @dev Mint `amount` to the caller, within the daily cap
+     */
+    function claim(uint256 amount) external {
+        require(amount > 0, "Amount must be greater than zero");
+
+        if (block.timestamp >= windowStart[msg.sender] + WINDOW) {
+            windowStart[msg.sender] = block.timestamp;
+            claimedInWindow[msg.sender] = 0;
+        }
+        require(claimedInWindow[msg.sender] + amount <= dailyCap, "Daily cap exceeded");
+
+        claimedInWindow[msg.sender] += amount;
+        totalSupply += amount;
+        balanceOf[msg.sender] += amount;
+
+        emit Transfer(address(0), msg.sender, amount);
+    }
+
+    function transfer(address to, uint256 amount) external returns (bool) {
+        _transfer(msg.sender, to, amount);
+        return true;
+    }
+
+    function approve(address spender, uint256 amount) external returns (bool) {
+        allowance[msg.sender][spender] = amount;
+        emit Approval(msg.sender, spender, amount);
+        return true;
+    }
+
+    function transferFrom(address from, address to, uint256 amount) external returns (bool) {
+        require(allowance[from][msg.sender] >= amount, "Insufficient allowance");
+        allowance[from][msg.sender] -= amount;
+        _transfer(from, to, amount);
+        return true;
+    }
+
+    function _transfer(address from, address to, uint256 amount) private {
+        require(balanceOf[from] >= amount, "Insufficient balance");
+        balanceOf[from] -= amount;
+        balanceOf[to] += amount;
+        emit Transfer(from, to, amount);
+    }
+}
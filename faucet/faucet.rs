@@ -0,0 +1,362 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint,
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use spl_token::state::Mint;
+
+/// Seed prefix for faucet PDAs: ["faucet", authority]
+pub const FAUCET_SEED: &[u8] = b"faucet";
+
+/// Seed prefix for the faucet's mint PDA: ["mint", faucet]
+pub const MINT_SEED: &[u8] = b"mint";
+
+/// Seed prefix for per-wallet claim records: ["claim", faucet, wallet]
+pub const CLAIM_SEED: &[u8] = b"claim";
+
+/// Length of one rate-limit window
+pub const SECONDS_PER_DAY: i64 = 86_400;
+
+/// Size of a `FaucetAccount`
+pub const FAUCET_ACCOUNT_SIZE: usize = 1 // is_initialized
+    + 32 // authority
+    + 32 // mint
+    + 8 // daily_cap
+    + 1 // bump
+    + 1; // mint_bump
+
+/// Size of a `ClaimRecord`
+pub const CLAIM_RECORD_SIZE: usize = 1 // is_initialized
+    + 32 // wallet
+    + 8 // window_start
+    + 8; // claimed_in_window
+
+/// Errors returned by the program as `ProgramError::Custom` codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FaucetError {
+    /// Amount or daily cap is zero
+    InvalidParameters = 0,
+    /// The claim would exceed the wallet's allowance for the current day
+    DailyCapExceeded = 1,
+    /// The faucet or claim record has not been initialized
+    NotInitialized = 2,
+    /// The mint does not belong to this faucet
+    WrongMint = 3,
+}
+
+impl From<FaucetError> for ProgramError {
+    fn from(e: FaucetError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+/// A test-token faucet; this PDA is the mint authority of `mint`
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct FaucetAccount {
+    pub is_initialized: bool,
+    pub authority: Pubkey,
+    pub mint: Pubkey,
+    /// Base units each wallet may claim per day
+    pub daily_cap: u64,
+    pub bump: u8,
+    pub mint_bump: u8,
+}
+
+/// One wallet's usage in its current 24-hour window
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct ClaimRecord {
+    pub is_initialized: bool,
+    pub wallet: Pubkey,
+    /// Unix timestamp at which the current window opened
+    pub window_start: i64,
+    pub claimed_in_window: u64,
+}
+
+impl ClaimRecord {
+    /// Record a claim of `amount` at `now`, opening a new window if the last
+    /// one has passed
+    pub fn try_claim(&mut self, now: i64, amount: u64, daily_cap: u64) -> Result<(), FaucetError> {
+        if now >= self.window_start.saturating_add(SECONDS_PER_DAY) {
+            self.window_start = now;
+            self.claimed_in_window = 0;
+        }
+
+        let claimed = self
+            .claimed_in_window
+            .checked_add(amount)
+            .filter(|&c| c <= daily_cap)
+            .ok_or(FaucetError::DailyCapExceeded)?;
+        self.claimed_in_window = claimed;
+
+        Ok(())
+    }
+}
+
+entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = FaucetInstruction::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        FaucetInstruction::InitializeFaucet { decimals, daily_cap } => {
+            initialize_faucet(program_id, accounts, decimals, daily_cap)
+        }
+        FaucetInstruction::Claim { amount } => claim(program_id, accounts, amount),
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub enum FaucetInstruction {
+    /// Create the faucet PDA and a new mint whose authority is the faucet PDA
+    /// Accounts: [writable, signer] authority, [writable] faucet PDA, [writable] mint PDA,
+    /// [] token program, [] system program
+    InitializeFaucet { decimals: u8, daily_cap: u64 },
+    /// Mint `amount` to the caller's token account, within the daily cap
+    /// Accounts: [writable, signer] wallet, [] faucet PDA, [writable] mint, [writable] claim record PDA,
+    /// [writable] destination token account, [] token program, [] system program
+    Claim { amount: u64 },
+}
+
+fn initialize_faucet(program_id: &Pubkey, accounts: &[AccountInfo], decimals: u8, daily_cap: u64) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let authority = next_account_info(accounts_iter)?;
+    let faucet_account = next_account_info(accounts_iter)?;
+    let mint = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if daily_cap == 0 {
+        msg!("Daily cap must be greater than zero");
+        return Err(FaucetError::InvalidParameters.into());
+    }
+
+    if *token_program.key != spl_token::id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let (expected_faucet, bump) =
+        Pubkey::find_program_address(&[FAUCET_SEED, authority.key.as_ref()], program_id);
+    if expected_faucet != *faucet_account.key {
+        msg!("Faucet account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let (expected_mint, mint_bump) =
+        Pubkey::find_program_address(&[MINT_SEED, faucet_account.key.as_ref()], program_id);
+    if expected_mint != *mint.key {
+        msg!("Mint account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let rent = Rent::get()?;
+
+    invoke_signed(
+        &system_instruction::create_account(
+            authority.key,
+            faucet_account.key,
+            rent.minimum_balance(FAUCET_ACCOUNT_SIZE),
+            FAUCET_ACCOUNT_SIZE as u64,
+            program_id,
+        ),
+        &[authority.clone(), faucet_account.clone(), system_program.clone()],
+        &[&[FAUCET_SEED, authority.key.as_ref(), &[bump]]],
+    )?;
+
+    // Create the mint; only the faucet PDA can ever mint it
+    invoke_signed(
+        &system_instruction::create_account(
+            authority.key,
+            mint.key,
+            rent.minimum_balance(Mint::LEN),
+            Mint::LEN as u64,
+            &spl_token::id(),
+        ),
+        &[authority.clone(), mint.clone(), system_program.clone()],
+        &[&[MINT_SEED, faucet_account.key.as_ref(), &[mint_bump]]],
+    )?;
+    invoke(
+        &spl_token::instruction::initialize_mint2(
+            &spl_token::id(),
+            mint.key,
+            faucet_account.key,
+            None,
+            decimals,
+        )?,
+        std::slice::from_ref(mint),
+    )?;
+
+    let faucet = FaucetAccount {
+        is_initialized: true,
+        authority: *authority.key,
+        mint: *mint.key,
+        daily_cap,
+        bump,
+        mint_bump,
+    };
+    faucet.serialize(&mut &mut faucet_account.data.borrow_mut()[..])?;
+
+    msg!("Faucet for {} created with daily cap {}", mint.key, daily_cap);
+
+    Ok(())
+}
+
+fn claim(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let wallet = next_account_info(accounts_iter)?;
+    let faucet_account = next_account_info(accounts_iter)?;
+    let mint = next_account_info(accounts_iter)?;
+    let claim_record_account = next_account_info(accounts_iter)?;
+    let destination = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !wallet.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if amount == 0 {
+        return Err(FaucetError::InvalidParameters.into());
+    }
+
+    if *token_program.key != spl_token::id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    if faucet_account.owner != program_id {
+        msg!("Faucet account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let faucet = FaucetAccount::try_from_slice(&faucet_account.data.borrow())?;
+    if !faucet.is_initialized {
+        return Err(FaucetError::NotInitialized.into());
+    }
+
+    if faucet.mint != *mint.key {
+        return Err(FaucetError::WrongMint.into());
+    }
+
+    let (expected_record, record_bump) = Pubkey::find_program_address(
+        &[CLAIM_SEED, faucet_account.key.as_ref(), wallet.key.as_ref()],
+        program_id,
+    );
+    if expected_record != *claim_record_account.key {
+        msg!("Claim record does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    // First claim from this wallet: create its record
+    let mut record = if claim_record_account.lamports() == 0 {
+        invoke_signed(
+            &system_instruction::create_account(
+                wallet.key,
+                claim_record_account.key,
+                Rent::get()?.minimum_balance(CLAIM_RECORD_SIZE),
+                CLAIM_RECORD_SIZE as u64,
+                program_id,
+            ),
+            &[wallet.clone(), claim_record_account.clone(), system_program.clone()],
+            &[&[CLAIM_SEED, faucet_account.key.as_ref(), wallet.key.as_ref(), &[record_bump]]],
+        )?;
+        ClaimRecord {
+            is_initialized: true,
+            wallet: *wallet.key,
+            window_start: i64::MIN,
+            claimed_in_window: 0,
+        }
+    } else {
+        ClaimRecord::try_from_slice(&claim_record_account.data.borrow())?
+    };
+
+    let now = Clock::get()?.unix_timestamp;
+    if let Err(e) = record.try_claim(now, amount, faucet.daily_cap) {
+        msg!(
+            "Already claimed {} of {} today; window resets at {}",
+            record.claimed_in_window,
+            faucet.daily_cap,
+            record.window_start.saturating_add(SECONDS_PER_DAY)
+        );
+        return Err(e.into());
+    }
+    record.serialize(&mut &mut claim_record_account.data.borrow_mut()[..])?;
+
+    invoke_signed(
+        &spl_token::instruction::mint_to(
+            &spl_token::id(),
+            mint.key,
+            destination.key,
+            faucet_account.key,
+            &[],
+            amount,
+        )?,
+        &[mint.clone(), destination.clone(), faucet_account.clone()],
+        &[&[FAUCET_SEED, faucet.authority.as_ref(), &[faucet.bump]]],
+    )?;
+
+    msg!("Minted {} to {}", amount, destination.key);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn new_record() -> ClaimRecord {
+        ClaimRecord {
+            is_initialized: true,
+            wallet: Pubkey::new_unique(),
+            window_start: i64::MIN,
+            claimed_in_window: 0,
+        }
+    }
+
+    #[test]
+    fn test_claims_are_capped_per_day() {
+        let mut record = new_record();
+        let start = 1_700_000_000;
+
+        record.try_claim(start, 600, 1_000).unwrap();
+        record.try_claim(start + 60, 400, 1_000).unwrap();
+        assert_eq!(
+            record.try_claim(start + 120, 1, 1_000),
+            Err(FaucetError::DailyCapExceeded)
+        );
+
+        // A failed claim leaves the record unchanged
+        assert_eq!(record.claimed_in_window, 1_000);
+        assert_eq!(record.window_start, start);
+    }
+
+    #[test]
+    fn test_window_resets_after_a_day() {
+        let mut record = new_record();
+        let start = 1_700_000_000;
+
+        record.try_claim(start, 1_000, 1_000).unwrap();
+        assert!(record.try_claim(start + SECONDS_PER_DAY - 1, 1, 1_000).is_err());
+
+        record.try_claim(start + SECONDS_PER_DAY, 1_000, 1_000).unwrap();
+        assert_eq!(record.window_start, start + SECONDS_PER_DAY);
+        assert_eq!(record.claimed_in_window, 1_000);
+    }
+}
@@ -0,0 +1,64 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+interface IFlashBorrower {
+    /// Called with the loan; must send back `amount + fee` before returning
+    function onFlashLoan(uint256 amount, uint256 fee, bytes calldata data) external;
+}
+
+/**
+ * @title FlashLoan
+ * @dev Lends ETH for the duration of a single call. The pool calls back into
+ * the borrower and checks its own balance afterwards, so repayment is verified
+ * before flashLoan() returns.
+ */
+contract FlashLoan {
+    uint256 public constant BPS_DENOMINATOR = 10_000;
+    uint256 public constant MAX_FEE_BPS = 100;
+
+    address public immutable authority;
+    uint256 public immutable feeBps;
+    uint256 public totalFees;
+    bool private locked;
+
+    event Loan(address indexed borrower, uint256 amount, uint256 fee);
+
+    constructor(uint256 _feeBps) {
+        require(_feeBps <= MAX_FEE_BPS, "Fee too high");
+        authority = msg.sender;
+        feeBps = _feeBps;
+    }
+
+    receive() external payable {}
+
+    /// Fee on `amount`, rounded up
+    function feeFor(uint256 amount) public view returns (uint256) {
+        return (amount * feeBps + BPS_DENOMINATOR - 1) / BPS_DENOMINATOR;
+    }
+
+    function flashLoan(IFlashBorrower borrower, uint256 amount, bytes calldata data) external {
+        require(!locked, "Loan outstanding");
+        require(amount > 0 && amount <= address(this).balance, "Insufficient liquidity");
+        locked = true;
+
+        uint256 fee = feeFor(amount);
+        uint256 balanceBefore = address(this).balance;
+
+        (bool sent, ) = address(borrower).call{value: amount}("");
+        require(sent, "Transfer failed");
+        borrower.onFlashLoan(amount, fee, data);
+
+        require(address(this).balance >= balanceBefore + fee, "Loan not repaid");
+
+        totalFees += fee;
+        locked = false;
+        emit Loan(address(borrower), amount, fee);
+    }
+
+    function withdraw(uint256 amount) external {
+        require(msg.sender == authority, "Only authority");
+        require(!locked, "Loan outstanding");
+        (bool success, ) = payable(authority).call{value: amount}("");
+        require(success, "Transfer failed");
+    }
+}
@@ -0,0 +1,485 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint,
+    entrypoint::ProgramResult,
+    instruction::{get_stack_height, Instruction, TRANSACTION_LEVEL_STACK_HEIGHT},
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::{
+        self,
+        instructions::{load_current_index_checked, load_instruction_at_checked},
+        Sysvar,
+    },
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// Seed prefix for pool PDAs: ["pool", authority]
+pub const POOL_SEED: &[u8] = b"pool";
+
+/// Fees are expressed in basis points
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Highest fee the authority can set (1%)
+pub const MAX_FEE_BPS: u16 = 100;
+
+/// Size of a `PoolAccount`
+pub const POOL_ACCOUNT_SIZE: usize = 1 // is_initialized
+    + 32 // authority
+    + 2 // fee_bps
+    + 8 // loan_amount
+    + 8 // total_fees
+    + 1; // bump
+
+/// Errors returned by the program as `ProgramError::Custom` codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlashLoanError {
+    /// Fee is above `MAX_FEE_BPS`
+    InvalidFee = 0,
+    /// The transaction has no later `Repay` for this pool
+    MissingRepay = 1,
+    /// A loan is already outstanding
+    LoanOutstanding = 2,
+    /// There is no loan to repay
+    NoLoan = 3,
+    /// Not enough liquidity
+    InsufficientLiquidity = 4,
+    /// The signer is not the pool authority
+    Unauthorized = 5,
+    /// The pool has not been initialized
+    NotInitialized = 6,
+    /// Borrow must be a top-level instruction
+    CpiNotAllowed = 7,
+}
+
+impl From<FlashLoanError> for ProgramError {
+    fn from(e: FlashLoanError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+/// Lamports lent within a single transaction
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct PoolAccount {
+    pub is_initialized: bool,
+    pub authority: Pubkey,
+    pub fee_bps: u16,
+    /// Outstanding loan; non-zero only between `Borrow` and `Repay`
+    pub loan_amount: u64,
+    pub total_fees: u64,
+    pub bump: u8,
+}
+
+/// Fee on a loan of `amount`, rounded up so small loans are never free
+pub fn fee_for(amount: u64, fee_bps: u16) -> u64 {
+    (amount as u128 * fee_bps as u128).div_ceil(BPS_DENOMINATOR as u128) as u64
+}
+
+/// Whether `ix` is a `Repay` to `pool` on this program
+pub fn is_repay(ix: &Instruction, program_id: &Pubkey, pool: &Pubkey) -> bool {
+    ix.program_id == *program_id
+        && FlashLoanInstruction::try_from_slice(&ix.data).is_ok_and(|ix| matches!(ix, FlashLoanInstruction::Repay))
+        && ix.accounts.get(1).map(|meta| &meta.pubkey) == Some(pool)
+}
+
+entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = FlashLoanInstruction::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        FlashLoanInstruction::Initialize { fee_bps } => initialize(program_id, accounts, fee_bps),
+        FlashLoanInstruction::Deposit { amount } => deposit(program_id, accounts, amount),
+        FlashLoanInstruction::Withdraw { amount } => withdraw(program_id, accounts, amount),
+        FlashLoanInstruction::Borrow { amount } => borrow(program_id, accounts, amount),
+        FlashLoanInstruction::Repay => repay(program_id, accounts),
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub enum FlashLoanInstruction {
+    /// Create a pool charging `fee_bps` per loan
+    /// Accounts: [writable, signer] authority, [writable] pool PDA, [] system program
+    Initialize { fee_bps: u16 },
+    /// Add liquidity
+    /// Accounts: [writable, signer] authority, [writable] pool PDA, [] system program
+    Deposit { amount: u64 },
+    /// Remove liquidity and earned fees
+    /// Accounts: [writable, signer] authority, [writable] pool PDA
+    Withdraw { amount: u64 },
+    /// Lend `amount` lamports. A later top-level instruction in the same
+    /// transaction must be `Repay` for this pool, or the borrow is refused.
+    /// Accounts: [writable, signer] borrower, [writable] pool PDA, [] instructions sysvar
+    Borrow { amount: u64 },
+    /// Return the outstanding loan plus fee
+    /// Accounts: [writable, signer] borrower, [writable] pool PDA, [] system program
+    Repay,
+}
+
+fn initialize(program_id: &Pubkey, accounts: &[AccountInfo], fee_bps: u16) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let authority = next_account_info(accounts_iter)?;
+    let pool_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if fee_bps > MAX_FEE_BPS {
+        msg!("Fee cannot exceed {} bps", MAX_FEE_BPS);
+        return Err(FlashLoanError::InvalidFee.into());
+    }
+
+    let (expected_pool, bump) = Pubkey::find_program_address(&[POOL_SEED, authority.key.as_ref()], program_id);
+    if expected_pool != *pool_account.key {
+        msg!("Pool account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    invoke_signed(
+        &system_instruction::create_account(
+            authority.key,
+            pool_account.key,
+            Rent::get()?.minimum_balance(POOL_ACCOUNT_SIZE),
+            POOL_ACCOUNT_SIZE as u64,
+            program_id,
+        ),
+        &[authority.clone(), pool_account.clone(), system_program.clone()],
+        &[&[POOL_SEED, authority.key.as_ref(), &[bump]]],
+    )?;
+
+    let pool = PoolAccount {
+        is_initialized: true,
+        authority: *authority.key,
+        fee_bps,
+        loan_amount: 0,
+        total_fees: 0,
+        bump,
+    };
+    pool.serialize(&mut &mut pool_account.data.borrow_mut()[..])?;
+
+    msg!("Pool created with a {} bps fee", fee_bps);
+
+    Ok(())
+}
+
+fn deposit(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let authority = next_account_info(accounts_iter)?;
+    let pool_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let pool = load_pool(program_id, pool_account)?;
+
+    if pool.authority != *authority.key {
+        return Err(FlashLoanError::Unauthorized.into());
+    }
+
+    invoke(
+        &system_instruction::transfer(authority.key, pool_account.key, amount),
+        &[authority.clone(), pool_account.clone(), system_program.clone()],
+    )?;
+
+    msg!("Deposited {} lamports", amount);
+
+    Ok(())
+}
+
+fn withdraw(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let authority = next_account_info(accounts_iter)?;
+    let pool_account = next_account_info(accounts_iter)?;
+
+    if !authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let pool = load_pool(program_id, pool_account)?;
+
+    if pool.authority != *authority.key {
+        return Err(FlashLoanError::Unauthorized.into());
+    }
+
+    if amount > available_liquidity(pool_account)? {
+        return Err(FlashLoanError::InsufficientLiquidity.into());
+    }
+
+    **pool_account.try_borrow_mut_lamports()? -= amount;
+    **authority.try_borrow_mut_lamports()? += amount;
+
+    msg!("Withdrew {} lamports", amount);
+
+    Ok(())
+}
+
+fn borrow(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let borrower = next_account_info(accounts_iter)?;
+    let pool_account = next_account_info(accounts_iter)?;
+    let instructions_sysvar = next_account_info(accounts_iter)?;
+
+    if !borrower.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // Through a CPI, the instructions sysvar would describe the caller's
+    // transaction position, not this borrow's
+    if get_stack_height() != TRANSACTION_LEVEL_STACK_HEIGHT {
+        msg!("Borrow must be called directly, not through CPI");
+        return Err(FlashLoanError::CpiNotAllowed.into());
+    }
+
+    let mut pool = load_pool(program_id, pool_account)?;
+
+    if pool.loan_amount != 0 {
+        return Err(FlashLoanError::LoanOutstanding.into());
+    }
+
+    if amount == 0 || amount > available_liquidity(pool_account)? {
+        return Err(FlashLoanError::InsufficientLiquidity.into());
+    }
+
+    require_later_repay(program_id, instructions_sysvar, pool_account.key)?;
+
+    pool.loan_amount = amount;
+    pool.serialize(&mut &mut pool_account.data.borrow_mut()[..])?;
+
+    **pool_account.try_borrow_mut_lamports()? -= amount;
+    **borrower.try_borrow_mut_lamports()? += amount;
+
+    msg!("Lent {} lamports, {} due", amount, amount + fee_for(amount, pool.fee_bps));
+
+    Ok(())
+}
+
+fn repay(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let borrower = next_account_info(accounts_iter)?;
+    let pool_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !borrower.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut pool = load_pool(program_id, pool_account)?;
+
+    if pool.loan_amount == 0 {
+        return Err(FlashLoanError::NoLoan.into());
+    }
+
+    let fee = fee_for(pool.loan_amount, pool.fee_bps);
+    let due = pool.loan_amount.checked_add(fee).ok_or(ProgramError::ArithmeticOverflow)?;
+
+    // Whoever repays, the pool ends the transaction with the loan plus fee
+    invoke(
+        &system_instruction::transfer(borrower.key, pool_account.key, due),
+        &[borrower.clone(), pool_account.clone(), system_program.clone()],
+    )?;
+
+    pool.loan_amount = 0;
+    pool.total_fees = pool.total_fees.checked_add(fee).ok_or(ProgramError::ArithmeticOverflow)?;
+    pool.serialize(&mut &mut pool_account.data.borrow_mut()[..])?;
+
+    msg!("Repaid {} lamports including a {} lamport fee", due, fee);
+
+    Ok(())
+}
+
+/// Require a `Repay` for `pool` after the current instruction. Transactions
+/// are atomic, so if that `Repay` fails, the borrow is undone with it.
+fn require_later_repay(program_id: &Pubkey, instructions_sysvar: &AccountInfo, pool: &Pubkey) -> ProgramResult {
+    if *instructions_sysvar.key != sysvar::instructions::id() {
+        msg!("Expected the instructions sysvar");
+        return Err(ProgramError::UnsupportedSysvar);
+    }
+
+    let current_index = load_current_index_checked(instructions_sysvar)? as usize;
+    let found = (current_index + 1..)
+        .map_while(|index| load_instruction_at_checked(index, instructions_sysvar).ok())
+        .any(|ix| is_repay(&ix, program_id, pool));
+
+    if !found {
+        msg!("Expected a Repay instruction for this pool later in the transaction");
+        return Err(FlashLoanError::MissingRepay.into());
+    }
+
+    Ok(())
+}
+
+/// Lamports above the pool's rent-exempt minimum
+fn available_liquidity(pool_account: &AccountInfo) -> Result<u64, ProgramError> {
+    Ok(pool_account
+        .lamports()
+        .saturating_sub(Rent::get()?.minimum_balance(pool_account.data_len())))
+}
+
+/// Validate the pool account and return it
+fn load_pool(program_id: &Pubkey, pool_account: &AccountInfo) -> Result<PoolAccount, ProgramError> {
+    if pool_account.owner != program_id {
+        msg!("Pool account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let pool = PoolAccount::try_from_slice(&pool_account.data.borrow())?;
+
+    if !pool.is_initialized {
+        return Err(FlashLoanError::NotInitialized.into());
+    }
+
+    Ok(pool)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_program::{
+        clock::Epoch,
+        entrypoint::SUCCESS,
+        instruction::AccountMeta,
+        program_stubs,
+        sysvar::instructions::{construct_instructions_data, BorrowedAccountMeta, BorrowedInstruction},
+    };
+
+    /// Serves a default Rent sysvar and a top-level stack height so handlers
+    /// can run off-chain
+    struct TestSyscallStubs;
+
+    impl program_stubs::SyscallStubs for TestSyscallStubs {
+        fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+            unsafe {
+                *(var_addr as *mut Rent) = Rent::default();
+            }
+            SUCCESS
+        }
+
+        fn sol_get_stack_height(&self) -> u64 {
+            TRANSACTION_LEVEL_STACK_HEIGHT as u64
+        }
+    }
+
+    #[test]
+    fn test_fee_and_repay_matching() {
+        assert_eq!(fee_for(1_000_000, 9), 900);
+        // Rounded up: a 1 lamport loan still pays 1 lamport
+        assert_eq!(fee_for(1, 9), 1);
+        assert_eq!(fee_for(1_000, 0), 0);
+        assert_eq!(fee_for(u64::MAX, MAX_FEE_BPS), u64::MAX / 100 + 1);
+
+        let program_id = Pubkey::new_unique();
+        let pool = Pubkey::new_unique();
+        let repay = |program_id: Pubkey, pool: Pubkey| {
+            Instruction::new_with_bytes(
+                program_id,
+                &FlashLoanInstruction::Repay.try_to_vec().unwrap(),
+                vec![AccountMeta::new(Pubkey::new_unique(), true), AccountMeta::new(pool, false)],
+            )
+        };
+        assert!(is_repay(&repay(program_id, pool), &program_id, &pool));
+        assert!(!is_repay(&repay(program_id, Pubkey::new_unique()), &program_id, &pool));
+        assert!(!is_repay(&repay(Pubkey::new_unique(), pool), &program_id, &pool));
+    }
+
+    #[test]
+    fn test_borrow_requires_later_repay() {
+        program_stubs::set_syscall_stubs(Box::new(TestSyscallStubs));
+
+        let program_id = Pubkey::new_unique();
+        let borrower_key = Pubkey::new_unique();
+        let pool_key = Pubkey::new_unique();
+        let rent_exempt = Rent::default().minimum_balance(POOL_ACCOUNT_SIZE);
+
+        let pool = PoolAccount {
+            is_initialized: true,
+            authority: Pubkey::new_unique(),
+            fee_bps: 9,
+            loan_amount: 0,
+            total_fees: 0,
+            bump: 255,
+        };
+
+        let borrow_data = FlashLoanInstruction::Borrow { amount: 1_000_000 }.try_to_vec().unwrap();
+        let repay_data = FlashLoanInstruction::Repay.try_to_vec().unwrap();
+        let borrow_then = |next_data: &[u8]| {
+            let mut data = construct_instructions_data(&[
+                BorrowedInstruction {
+                    program_id: &program_id,
+                    accounts: vec![],
+                    data: &borrow_data,
+                },
+                BorrowedInstruction {
+                    program_id: &program_id,
+                    accounts: vec![
+                        BorrowedAccountMeta {
+                            pubkey: &borrower_key,
+                            is_signer: true,
+                            is_writable: true,
+                        },
+                        BorrowedAccountMeta {
+                            pubkey: &pool_key,
+                            is_signer: false,
+                            is_writable: true,
+                        },
+                    ],
+                    data: next_data,
+                },
+            ]);
+            // Current instruction index (0) is stored in the last two bytes
+            let index_at = data.len() - 2;
+            data[index_at..].copy_from_slice(&0u16.to_le_bytes());
+            data
+        };
+
+        let keys = [borrower_key, pool_key, sysvar::instructions::id()];
+        let mut lamports: Vec<u64> = vec![0, rent_exempt + 2_000_000, 0];
+
+        // Followed by another Borrow instead of a Repay
+        let mut data: Vec<Vec<u8>> = vec![vec![], pool.try_to_vec().unwrap(), borrow_then(&borrow_data)];
+        let accounts: Vec<AccountInfo> = keys
+            .iter()
+            .zip(lamports.iter_mut())
+            .zip(data.iter_mut())
+            .enumerate()
+            .map(|(i, ((key, lamports), data))| {
+                AccountInfo::new(key, i == 0, i < 2, lamports, data, &program_id, false, Epoch::default())
+            })
+            .collect();
+        let result = process_instruction(&program_id, &accounts, &borrow_data);
+        assert_eq!(result, Err(FlashLoanError::MissingRepay.into()));
+        drop(accounts);
+
+        let mut data: Vec<Vec<u8>> = vec![vec![], pool.try_to_vec().unwrap(), borrow_then(&repay_data)];
+        let accounts: Vec<AccountInfo> = keys
+            .iter()
+            .zip(lamports.iter_mut())
+            .zip(data.iter_mut())
+            .enumerate()
+            .map(|(i, ((key, lamports), data))| {
+                AccountInfo::new(key, i == 0, i < 2, lamports, data, &program_id, false, Epoch::default())
+            })
+            .collect();
+        let result = process_instruction(&program_id, &accounts, &borrow_data);
+        assert!(result.is_ok());
+        assert_eq!(accounts[0].lamports(), 1_000_000);
+
+        let stored = PoolAccount::try_from_slice(&accounts[1].data.borrow()).unwrap();
+        assert_eq!(stored.loan_amount, 1_000_000);
+
+        // One loan at a time
+        let result = process_instruction(&program_id, &accounts, &borrow_data);
+        assert_eq!(result, Err(FlashLoanError::LoanOutstanding.into()));
+    }
+}
@@ -0,0 +1,51 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+/**
+ * @title FollowGraph
+ * @dev A directed social graph: users create a profile, then follow and
+ * unfollow each other. Follower and following counts are kept in step with
+ * the edges.
+ */
+contract FollowGraph {
+    struct Profile {
+        bool exists;
+        uint256 followers;
+        uint256 following;
+    }
+
+    mapping(address => Profile) public profiles;
+    /// follower => followee => edge exists
+    mapping(address => mapping(address => bool)) public isFollowing;
+
+    event ProfileCreated(address indexed user);
+    event Followed(address indexed follower, address indexed followee);
+    event Unfollowed(address indexed follower, address indexed followee);
+
+    function createProfile() external {
+        require(!profiles[msg.sender].exists, "Profile exists");
+        profiles[msg.sender].exists = true;
+        emit ProfileCreated(msg.sender);
+    }
+
+    function follow(address followee) external {
+        require(profiles[msg.sender].exists && profiles[followee].exists, "No profile");
+        require(followee != msg.sender, "Cannot follow yourself");
+        require(!isFollowing[msg.sender][followee], "Already following");
+
+        isFollowing[msg.sender][followee] = true;
+        profiles[msg.sender].following += 1;
+        profiles[followee].followers += 1;
+        emit Followed(msg.sender, followee);
+    }
+
+    function unfollow(address followee) external {
+        require(isFollowing[msg.sender][followee], "Not following");
+
+        // Clearing the slot earns a gas refund, the closest analogue to Solana's rent refund
+        delete isFollowing[msg.sender][followee];
+        profiles[msg.sender].following -= 1;
+        profiles[followee].followers -= 1;
+        emit Unfollowed(msg.sender, followee);
+    }
+}
@@ -0,0 +1,376 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint,
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// Seed prefix for profile PDAs: ["profile", user]
+pub const PROFILE_SEED: &[u8] = b"profile";
+
+/// Seed prefix for follow edge PDAs: ["follow", follower, followee]
+pub const FOLLOW_SEED: &[u8] = b"follow";
+
+/// Size of a `ProfileAccount`
+pub const PROFILE_ACCOUNT_SIZE: usize = 1 // is_initialized
+    + 32 // user
+    + 8 // followers
+    + 8 // following
+    + 1; // bump
+
+/// Size of a `FollowAccount`; the edge itself is the PDA address
+pub const FOLLOW_ACCOUNT_SIZE: usize = 1 // is_initialized
+    + 32 // follower
+    + 32 // followee
+    + 1; // bump
+
+/// Errors returned by the program as `ProgramError::Custom` codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FollowError {
+    /// A user cannot follow themselves
+    SelfFollow = 0,
+    /// The follow edge already exists
+    AlreadyFollowing = 1,
+    /// The account has not been initialized
+    NotInitialized = 2,
+    /// A profile or edge does not belong to the given users
+    WrongAccount = 3,
+}
+
+impl From<FollowError> for ProgramError {
+    fn from(e: FollowError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct ProfileAccount {
+    pub is_initialized: bool,
+    pub user: Pubkey,
+    /// Number of edges pointing at this user
+    pub followers: u64,
+    /// Number of edges starting at this user
+    pub following: u64,
+    pub bump: u8,
+}
+
+/// Existence of this account means `follower` follows `followee`
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct FollowAccount {
+    pub is_initialized: bool,
+    pub follower: Pubkey,
+    pub followee: Pubkey,
+    pub bump: u8,
+}
+
+/// Address of the edge from `follower` to `followee`; lets clients check a
+/// relationship with a single account lookup
+pub fn follow_address(program_id: &Pubkey, follower: &Pubkey, followee: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[FOLLOW_SEED, follower.as_ref(), followee.as_ref()], program_id)
+}
+
+/// Count a new edge on both ends
+pub fn link(follower: &mut ProfileAccount, followee: &mut ProfileAccount) -> Result<(), ProgramError> {
+    follower.following = follower.following.checked_add(1).ok_or(ProgramError::ArithmeticOverflow)?;
+    followee.followers = followee.followers.checked_add(1).ok_or(ProgramError::ArithmeticOverflow)?;
+    Ok(())
+}
+
+/// Remove an edge from both counts
+pub fn unlink(follower: &mut ProfileAccount, followee: &mut ProfileAccount) -> Result<(), ProgramError> {
+    follower.following = follower.following.checked_sub(1).ok_or(ProgramError::ArithmeticOverflow)?;
+    followee.followers = followee.followers.checked_sub(1).ok_or(ProgramError::ArithmeticOverflow)?;
+    Ok(())
+}
+
+entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = FollowInstruction::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        FollowInstruction::CreateProfile => create_profile(program_id, accounts),
+        FollowInstruction::Follow => follow(program_id, accounts),
+        FollowInstruction::Unfollow => unfollow(program_id, accounts),
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub enum FollowInstruction {
+    /// Create the signer's profile with zero counts
+    /// Accounts: [writable, signer] user, [writable] profile PDA, [] system program
+    CreateProfile,
+    /// Create the follow edge and bump both counters; the follower pays the edge rent
+    /// Accounts: [writable, signer] follower, [writable] follower profile, [writable] followee profile,
+    /// [writable] follow PDA, [] system program
+    Follow,
+    /// Close the follow edge, refund its rent to the follower and decrement both counters
+    /// Accounts: [writable, signer] follower, [writable] follower profile, [writable] followee profile,
+    /// [writable] follow PDA
+    Unfollow,
+}
+
+fn create_profile(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let user = next_account_info(accounts_iter)?;
+    let profile_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !user.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let (expected_profile, bump) = Pubkey::find_program_address(&[PROFILE_SEED, user.key.as_ref()], program_id);
+    if expected_profile != *profile_account.key {
+        msg!("Profile account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    invoke_signed(
+        &system_instruction::create_account(
+            user.key,
+            profile_account.key,
+            Rent::get()?.minimum_balance(PROFILE_ACCOUNT_SIZE),
+            PROFILE_ACCOUNT_SIZE as u64,
+            program_id,
+        ),
+        &[user.clone(), profile_account.clone(), system_program.clone()],
+        &[&[PROFILE_SEED, user.key.as_ref(), &[bump]]],
+    )?;
+
+    let profile = ProfileAccount {
+        is_initialized: true,
+        user: *user.key,
+        followers: 0,
+        following: 0,
+        bump,
+    };
+    profile.serialize(&mut &mut profile_account.data.borrow_mut()[..])?;
+
+    msg!("Profile created for {}", user.key);
+
+    Ok(())
+}
+
+fn follow(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let follower = next_account_info(accounts_iter)?;
+    let follower_profile_account = next_account_info(accounts_iter)?;
+    let followee_profile_account = next_account_info(accounts_iter)?;
+    let follow_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !follower.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut follower_profile = load_profile(program_id, follower_profile_account)?;
+    let mut followee_profile = load_profile(program_id, followee_profile_account)?;
+
+    if follower_profile.user != *follower.key {
+        return Err(FollowError::WrongAccount.into());
+    }
+    if followee_profile.user == follower_profile.user {
+        return Err(FollowError::SelfFollow.into());
+    }
+
+    let (expected_follow, bump) = follow_address(program_id, follower.key, &followee_profile.user);
+    if expected_follow != *follow_account.key {
+        msg!("Follow account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    if follow_account.lamports() > 0 {
+        return Err(FollowError::AlreadyFollowing.into());
+    }
+
+    invoke_signed(
+        &system_instruction::create_account(
+            follower.key,
+            follow_account.key,
+            Rent::get()?.minimum_balance(FOLLOW_ACCOUNT_SIZE),
+            FOLLOW_ACCOUNT_SIZE as u64,
+            program_id,
+        ),
+        &[follower.clone(), follow_account.clone(), system_program.clone()],
+        &[&[FOLLOW_SEED, follower.key.as_ref(), followee_profile.user.as_ref(), &[bump]]],
+    )?;
+
+    let edge = FollowAccount {
+        is_initialized: true,
+        follower: *follower.key,
+        followee: followee_profile.user,
+        bump,
+    };
+    edge.serialize(&mut &mut follow_account.data.borrow_mut()[..])?;
+
+    link(&mut follower_profile, &mut followee_profile)?;
+    follower_profile.serialize(&mut &mut follower_profile_account.data.borrow_mut()[..])?;
+    followee_profile.serialize(&mut &mut followee_profile_account.data.borrow_mut()[..])?;
+
+    msg!("{} now follows {}", follower.key, followee_profile.user);
+
+    Ok(())
+}
+
+fn unfollow(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let follower = next_account_info(accounts_iter)?;
+    let follower_profile_account = next_account_info(accounts_iter)?;
+    let followee_profile_account = next_account_info(accounts_iter)?;
+    let follow_account = next_account_info(accounts_iter)?;
+
+    if !follower.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut follower_profile = load_profile(program_id, follower_profile_account)?;
+    let mut followee_profile = load_profile(program_id, followee_profile_account)?;
+
+    if follow_account.owner != program_id {
+        msg!("Follow account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let edge = FollowAccount::try_from_slice(&follow_account.data.borrow())?;
+    if !edge.is_initialized {
+        return Err(FollowError::NotInitialized.into());
+    }
+
+    // The edge must join exactly these two profiles, or the counters would drift
+    if edge.follower != *follower.key
+        || follower_profile.user != edge.follower
+        || followee_profile.user != edge.followee
+    {
+        return Err(FollowError::WrongAccount.into());
+    }
+
+    unlink(&mut follower_profile, &mut followee_profile)?;
+    follower_profile.serialize(&mut &mut follower_profile_account.data.borrow_mut()[..])?;
+    followee_profile.serialize(&mut &mut followee_profile_account.data.borrow_mut()[..])?;
+
+    let lamports = follow_account.lamports();
+    **follow_account.try_borrow_mut_lamports()? = 0;
+    **follower.try_borrow_mut_lamports()? = follower
+        .lamports()
+        .checked_add(lamports)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    follow_account.data.borrow_mut().fill(0);
+
+    msg!("{} unfollowed {}; refunded {} lamports", follower.key, edge.followee, lamports);
+
+    Ok(())
+}
+
+fn load_profile(program_id: &Pubkey, profile_account: &AccountInfo) -> Result<ProfileAccount, ProgramError> {
+    if profile_account.owner != program_id {
+        msg!("Profile account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let profile = ProfileAccount::try_from_slice(&profile_account.data.borrow())?;
+
+    if !profile.is_initialized {
+        return Err(FollowError::NotInitialized.into());
+    }
+
+    Ok(profile)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_program::clock::Epoch;
+
+    fn profile(user: Pubkey, followers: u64, following: u64) -> ProfileAccount {
+        ProfileAccount {
+            is_initialized: true,
+            user,
+            followers,
+            following,
+            bump: 255,
+        }
+    }
+
+    #[test]
+    fn test_edges_are_directional() {
+        let program_id = Pubkey::new_unique();
+        let alice = Pubkey::new_unique();
+        let bob = Pubkey::new_unique();
+
+        assert_ne!(follow_address(&program_id, &alice, &bob), follow_address(&program_id, &bob, &alice));
+
+        let mut alice_profile = profile(alice, 0, 0);
+        let mut bob_profile = profile(bob, 0, 0);
+        link(&mut alice_profile, &mut bob_profile).unwrap();
+        assert_eq!((alice_profile.following, bob_profile.followers), (1, 1));
+        assert_eq!((alice_profile.followers, bob_profile.following), (0, 0));
+
+        unlink(&mut alice_profile, &mut bob_profile).unwrap();
+        assert!(unlink(&mut alice_profile, &mut bob_profile).is_err());
+
+        assert_eq!(alice_profile.try_to_vec().unwrap().len(), PROFILE_ACCOUNT_SIZE);
+    }
+
+    #[test]
+    fn test_unfollow_refunds_and_decrements() {
+        let program_id = Pubkey::new_unique();
+        let alice = Pubkey::new_unique();
+        let bob = Pubkey::new_unique();
+        let alice_profile_key = Pubkey::new_unique();
+        let bob_profile_key = Pubkey::new_unique();
+        let (edge_key, bump) = follow_address(&program_id, &alice, &bob);
+
+        let edge = FollowAccount {
+            is_initialized: true,
+            follower: alice,
+            followee: bob,
+            bump,
+        };
+
+        let keys = [alice, alice_profile_key, bob_profile_key, edge_key];
+        let mut lamports: Vec<u64> = vec![0, 1, 1, 900];
+        let mut data: Vec<Vec<u8>> = vec![
+            vec![],
+            profile(alice, 0, 1).try_to_vec().unwrap(),
+            profile(bob, 1, 0).try_to_vec().unwrap(),
+            edge.try_to_vec().unwrap(),
+        ];
+
+        let accounts: Vec<AccountInfo> = keys
+            .iter()
+            .zip(lamports.iter_mut())
+            .zip(data.iter_mut())
+            .enumerate()
+            .map(|(i, ((key, lamports), data))| {
+                AccountInfo::new(key, i == 0, true, lamports, data, &program_id, false, Epoch::default())
+            })
+            .collect();
+        let instruction_data = FollowInstruction::Unfollow.try_to_vec().unwrap();
+
+        process_instruction(&program_id, &accounts, &instruction_data).unwrap();
+
+        let alice_profile = ProfileAccount::try_from_slice(&accounts[1].data.borrow()).unwrap();
+        let bob_profile = ProfileAccount::try_from_slice(&accounts[2].data.borrow()).unwrap();
+        assert_eq!(alice_profile.following, 0);
+        assert_eq!(bob_profile.followers, 0);
+        assert_eq!(accounts[0].lamports(), 900);
+        assert_eq!(accounts[3].lamports(), 0);
+
+        // The cleared edge cannot be unfollowed twice
+        let result = process_instruction(&program_id, &accounts, &instruction_data);
+        assert_eq!(result, Err(FollowError::NotInitialized.into()));
+    }
+}
@@ -0,0 +1,25 @@
+#![no_main]
+
+//! Arbitrary account data through every account deserializer the programs
+//! run on data they don't control
+
+use common::{load_account, load_versioned};
+use counter::CounterAccount;
+use crowdfund::{ContributorAccount, CrowdfundAccount};
+use hello_world::{HelloWorldAccount, UpdaterCooldown};
+use hello_world_zero_copy::ZeroCopyMessageAccount;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    // The programs check the discriminator, and the layout version of
+    // versioned types, then `deserialize`, which ignores trailing bytes
+    let _ = load_versioned::<CounterAccount>(data);
+    let _ = load_versioned::<CrowdfundAccount>(data);
+    let _ = load_account::<ContributorAccount>(data);
+    let _ = load_versioned::<HelloWorldAccount>(data);
+    let _ = load_account::<UpdaterCooldown>(data);
+
+    if let Some(bytes) = data.get(..ZeroCopyMessageAccount::LEN) {
+        let _ = bytemuck::pod_read_unaligned::<ZeroCopyMessageAccount>(bytes).message();
+    }
+});
@@ -0,0 +1,6 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use smart_contract_comparison_fuzz::{run, FuzzInstruction};
+
+fuzz_target!(|input: FuzzInstruction| run(crowdfund::process_instruction, input));
@@ -0,0 +1,113 @@
+//! Harness shared by the fuzz targets: builds the `AccountInfo`s and sysvars
+//! a `process_instruction` runs against from arbitrary input.
+//!
+//! Errors are expected results; only panics (overflow, out-of-bounds slicing,
+//! failed `unwrap`s) count as crashes.
+
+use arbitrary::Arbitrary;
+use solana_program::{
+    account_info::AccountInfo,
+    clock::{Clock, Epoch},
+    entrypoint::{ProgramResult, SUCCESS},
+    program_stubs::{self, SyscallStubs},
+    pubkey::Pubkey,
+    rent::Rent,
+    system_program,
+};
+
+/// Stand-in for the program id, so accounts can be owned by the program
+pub const PROGRAM_ID: Pubkey = Pubkey::new_from_array([0xff; 32]);
+
+#[derive(Arbitrary, Debug)]
+pub struct FuzzAccount {
+    /// Pass the system program instead of a fresh key
+    pub is_system_program: bool,
+    pub is_signer: bool,
+    pub is_writable: bool,
+    /// Owned by `PROGRAM_ID` rather than the system program
+    pub program_owned: bool,
+    pub lamports: u64,
+    pub data: Vec<u8>,
+}
+
+#[derive(Arbitrary, Debug)]
+pub struct FuzzInstruction {
+    /// Slot served by the Clock sysvar
+    pub slot: u64,
+    pub accounts: Vec<FuzzAccount>,
+    pub data: Vec<u8>,
+}
+
+/// Serves the Clock and Rent sysvars and drops program logs, which would
+/// otherwise dominate each run
+struct FuzzSyscallStubs {
+    slot: u64,
+}
+
+impl SyscallStubs for FuzzSyscallStubs {
+    fn sol_log(&self, _message: &str) {}
+
+    fn sol_log_data(&self, _fields: &[&[u8]]) {}
+
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        unsafe {
+            *(var_addr as *mut Clock) = Clock {
+                slot: self.slot,
+                ..Clock::default()
+            };
+        }
+        SUCCESS
+    }
+
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        unsafe {
+            *(var_addr as *mut Rent) = Rent::default();
+        }
+        SUCCESS
+    }
+}
+
+/// Run `process_instruction` once against `input`
+pub fn run(process_instruction: fn(&Pubkey, &[AccountInfo], &[u8]) -> ProgramResult, input: FuzzInstruction) {
+    program_stubs::set_syscall_stubs(Box::new(FuzzSyscallStubs { slot: input.slot }));
+
+    let system_program_id = system_program::id();
+    let keys: Vec<Pubkey> = input
+        .accounts
+        .iter()
+        .enumerate()
+        .map(|(index, account)| {
+            if account.is_system_program {
+                system_program_id
+            } else {
+                let mut key = [0; 32];
+                key[..8].copy_from_slice(&(index as u64 + 1).to_le_bytes());
+                Pubkey::new_from_array(key)
+            }
+        })
+        .collect();
+    let mut lamports: Vec<u64> = input.accounts.iter().map(|account| account.lamports).collect();
+    let mut data: Vec<Vec<u8>> = input.accounts.iter().map(|account| account.data.clone()).collect();
+
+    let account_infos: Vec<AccountInfo> = input
+        .accounts
+        .iter()
+        .zip(&keys)
+        .zip(lamports.iter_mut().zip(data.iter_mut()))
+        .map(|((account, key), (lamports, data))| {
+            let owner = if account.program_owned { &PROGRAM_ID } else { &system_program_id };
+            AccountInfo::new(
+                key,
+                account.is_signer,
+                account.is_writable,
+                lamports,
+                data,
+                owner,
+                false,
+                Epoch::default(),
+            )
+        })
+        .collect();
+
+    let _ = process_instruction(&PROGRAM_ID, &account_infos, &input.data);
+}
@@ -0,0 +1,57 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+/**
+ * @title Guestbook
+ * @dev Visitors sign with a short note. Entries are appended to an array, so
+ * an entry's index is its sequence number and pages are index ranges.
+ */
+contract Guestbook {
+    uint256 public constant MAX_NOTE_LENGTH = 140;
+
+    struct Entry {
+        address signer;
+        uint64 visit;
+        uint64 timestamp;
+        string note;
+    }
+
+    address public immutable owner;
+    Entry[] private entries;
+    mapping(address => uint64) public visits;
+
+    event Signed(uint256 indexed sequence, address indexed signer, uint64 visit, string note);
+
+    constructor() {
+        owner = msg.sender;
+    }
+
+    function sign(string calldata note) external {
+        require(bytes(note).length > 0, "Empty note");
+        require(bytes(note).length <= MAX_NOTE_LENGTH, "Note too long");
+
+        uint64 visit = visits[msg.sender]++;
+        entries.push(Entry(msg.sender, visit, uint64(block.timestamp), note));
+        emit Signed(entries.length - 1, msg.sender, visit, note);
+    }
+
+    function entryCount() external view returns (uint256) {
+        return entries.length;
+    }
+
+    function getEntry(uint256 sequence) external view returns (Entry memory) {
+        return entries[sequence];
+    }
+
+    /// Page 0 holds the newest `pageSize` entries, returned oldest first
+    function getPage(uint256 page, uint256 pageSize) external view returns (Entry[] memory result) {
+        uint256 skipped = page * pageSize;
+        uint256 end = entries.length > skipped ? entries.length - skipped : 0;
+        uint256 start = end > pageSize ? end - pageSize : 0;
+
+        result = new Entry[](end - start);
+        for (uint256 i = start; i < end; i++) {
+            result[i - start] = entries[i];
+        }
+    }
+}
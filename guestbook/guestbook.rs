@@ -0,0 +1,384 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint,
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use std::ops::Range;
+
+/// Seed prefix for guestbook PDAs: ["guestbook", owner]
+pub const GUESTBOOK_SEED: &[u8] = b"guestbook";
+
+/// Seed prefix for entry PDAs: ["entry", guestbook, sequence (u64 LE)]
+pub const ENTRY_SEED: &[u8] = b"entry";
+
+/// Seed prefix for per-signer visit counters: ["visitor", guestbook, signer]
+pub const VISITOR_SEED: &[u8] = b"visitor";
+
+/// Maximum note length in bytes
+pub const MAX_NOTE_LENGTH: usize = 140;
+
+/// Size of a `GuestbookAccount`
+pub const GUESTBOOK_ACCOUNT_SIZE: usize = 1 // is_initialized
+    + 32 // owner
+    + 8 // entry_count
+    + 1; // bump
+
+/// Size of a `VisitorAccount`
+pub const VISITOR_ACCOUNT_SIZE: usize = 1 // is_initialized
+    + 32 // signer
+    + 8 // visits
+    + 1; // bump
+
+/// Size of an `EntryAccount` holding a note of the maximum length
+pub const ENTRY_ACCOUNT_SIZE: usize = 1 // is_initialized
+    + 32 // guestbook
+    + 8 // sequence
+    + 32 // signer
+    + 8 // visit
+    + 8 // timestamp
+    + 4 + MAX_NOTE_LENGTH // note
+    + 1; // bump
+
+/// Errors returned by the program as `ProgramError::Custom` codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GuestbookError {
+    /// Note is longer than `MAX_NOTE_LENGTH` bytes
+    NoteTooLong = 0,
+    /// Note is empty
+    EmptyNote = 1,
+    /// The guestbook has not been initialized
+    NotInitialized = 2,
+}
+
+impl From<GuestbookError> for ProgramError {
+    fn from(e: GuestbookError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+/// A guestbook; `entry_count` is both the number of entries and the next sequence number
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct GuestbookAccount {
+    pub is_initialized: bool,
+    pub owner: Pubkey,
+    pub entry_count: u64,
+    pub bump: u8,
+}
+
+/// How many times a signer has visited a guestbook
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct VisitorAccount {
+    pub is_initialized: bool,
+    pub signer: Pubkey,
+    pub visits: u64,
+    pub bump: u8,
+}
+
+/// One visit: the `visit`-th entry by `signer`, and the `sequence`-th in the guestbook
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct EntryAccount {
+    pub is_initialized: bool,
+    pub guestbook: Pubkey,
+    pub sequence: u64,
+    pub signer: Pubkey,
+    pub visit: u64,
+    pub timestamp: i64,
+    pub note: String,
+    pub bump: u8,
+}
+
+/// Address of the entry with `sequence`. Sequences have no gaps, so clients
+/// page through a guestbook by deriving addresses instead of scanning accounts
+pub fn entry_address(program_id: &Pubkey, guestbook: &Pubkey, sequence: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[ENTRY_SEED, guestbook.as_ref(), &sequence.to_le_bytes()], program_id)
+}
+
+/// Sequence numbers on `page` (zero-based) of a guestbook with `entry_count`
+/// entries. Page 0 holds the newest entries
+pub fn page_range(entry_count: u64, page: u64, page_size: u64) -> Range<u64> {
+    let skipped = page.saturating_mul(page_size);
+    let end = entry_count.saturating_sub(skipped);
+    let start = end.saturating_sub(page_size);
+    start..end
+}
+
+entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = GuestbookInstruction::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        GuestbookInstruction::Initialize => initialize(program_id, accounts),
+        GuestbookInstruction::Sign { note } => sign(program_id, accounts, note),
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub enum GuestbookInstruction {
+    /// Create a guestbook owned by the signer
+    /// Accounts: [writable, signer] owner, [writable] guestbook PDA, [] system program
+    Initialize,
+    /// Add an entry. The entry PDA uses the guestbook's current `entry_count` as
+    /// its sequence; the visitor PDA is created on the signer's first visit
+    /// Accounts: [writable, signer] signer, [writable] guestbook PDA, [writable] visitor PDA,
+    /// [writable] entry PDA, [] system program
+    Sign { note: String },
+}
+
+fn initialize(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let owner = next_account_info(accounts_iter)?;
+    let guestbook_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let (expected_guestbook, bump) =
+        Pubkey::find_program_address(&[GUESTBOOK_SEED, owner.key.as_ref()], program_id);
+    if expected_guestbook != *guestbook_account.key {
+        msg!("Guestbook account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    invoke_signed(
+        &system_instruction::create_account(
+            owner.key,
+            guestbook_account.key,
+            Rent::get()?.minimum_balance(GUESTBOOK_ACCOUNT_SIZE),
+            GUESTBOOK_ACCOUNT_SIZE as u64,
+            program_id,
+        ),
+        &[owner.clone(), guestbook_account.clone(), system_program.clone()],
+        &[&[GUESTBOOK_SEED, owner.key.as_ref(), &[bump]]],
+    )?;
+
+    let guestbook = GuestbookAccount {
+        is_initialized: true,
+        owner: *owner.key,
+        entry_count: 0,
+        bump,
+    };
+    guestbook.serialize(&mut &mut guestbook_account.data.borrow_mut()[..])?;
+
+    msg!("Guestbook {} created", guestbook_account.key);
+
+    Ok(())
+}
+
+fn sign(program_id: &Pubkey, accounts: &[AccountInfo], note: String) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let signer = next_account_info(accounts_iter)?;
+    let guestbook_account = next_account_info(accounts_iter)?;
+    let visitor_account = next_account_info(accounts_iter)?;
+    let entry_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !signer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if note.is_empty() {
+        return Err(GuestbookError::EmptyNote.into());
+    }
+
+    if note.len() > MAX_NOTE_LENGTH {
+        msg!("Note is {} bytes; the maximum is {}", note.len(), MAX_NOTE_LENGTH);
+        return Err(GuestbookError::NoteTooLong.into());
+    }
+
+    let mut guestbook = load_guestbook(program_id, guestbook_account)?;
+    let sequence = guestbook.entry_count;
+
+    // The client must pass the entry for the next sequence number; if another
+    // visitor signed first, this fails and the client retries with the new count
+    let (expected_entry, entry_bump) = entry_address(program_id, guestbook_account.key, sequence);
+    if expected_entry != *entry_account.key {
+        msg!("Entry account does not match sequence {}", sequence);
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let (expected_visitor, visitor_bump) = Pubkey::find_program_address(
+        &[VISITOR_SEED, guestbook_account.key.as_ref(), signer.key.as_ref()],
+        program_id,
+    );
+    if expected_visitor != *visitor_account.key {
+        msg!("Visitor account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let rent = Rent::get()?;
+
+    // First visit: create the signer's visit counter
+    let mut visitor = if visitor_account.lamports() == 0 {
+        invoke_signed(
+            &system_instruction::create_account(
+                signer.key,
+                visitor_account.key,
+                rent.minimum_balance(VISITOR_ACCOUNT_SIZE),
+                VISITOR_ACCOUNT_SIZE as u64,
+                program_id,
+            ),
+            &[signer.clone(), visitor_account.clone(), system_program.clone()],
+            &[&[VISITOR_SEED, guestbook_account.key.as_ref(), signer.key.as_ref(), &[visitor_bump]]],
+        )?;
+        VisitorAccount {
+            is_initialized: true,
+            signer: *signer.key,
+            visits: 0,
+            bump: visitor_bump,
+        }
+    } else {
+        if visitor_account.owner != program_id {
+            msg!("Visitor account does not have the correct program id");
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        VisitorAccount::try_from_slice(&visitor_account.data.borrow())?
+    };
+
+    invoke_signed(
+        &system_instruction::create_account(
+            signer.key,
+            entry_account.key,
+            rent.minimum_balance(ENTRY_ACCOUNT_SIZE),
+            ENTRY_ACCOUNT_SIZE as u64,
+            program_id,
+        ),
+        &[signer.clone(), entry_account.clone(), system_program.clone()],
+        &[&[
+            ENTRY_SEED,
+            guestbook_account.key.as_ref(),
+            &sequence.to_le_bytes(),
+            &[entry_bump],
+        ]],
+    )?;
+
+    let entry = EntryAccount {
+        is_initialized: true,
+        guestbook: *guestbook_account.key,
+        sequence,
+        signer: *signer.key,
+        visit: visitor.visits,
+        timestamp: Clock::get()?.unix_timestamp,
+        note,
+        bump: entry_bump,
+    };
+    entry.serialize(&mut &mut entry_account.data.borrow_mut()[..])?;
+
+    visitor.visits = visitor.visits.checked_add(1).ok_or(ProgramError::ArithmeticOverflow)?;
+    visitor.serialize(&mut &mut visitor_account.data.borrow_mut()[..])?;
+
+    guestbook.entry_count = sequence.checked_add(1).ok_or(ProgramError::ArithmeticOverflow)?;
+    guestbook.serialize(&mut &mut guestbook_account.data.borrow_mut()[..])?;
+
+    msg!("Entry {} signed by {} (visit {})", sequence, signer.key, entry.visit);
+
+    Ok(())
+}
+
+fn load_guestbook(program_id: &Pubkey, guestbook_account: &AccountInfo) -> Result<GuestbookAccount, ProgramError> {
+    if guestbook_account.owner != program_id {
+        msg!("Guestbook account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let guestbook = GuestbookAccount::try_from_slice(&guestbook_account.data.borrow())?;
+    if !guestbook.is_initialized {
+        return Err(GuestbookError::NotInitialized.into());
+    }
+
+    Ok(guestbook)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_program::clock::Epoch;
+
+    #[test]
+    fn test_page_range() {
+        // 25 entries in pages of 10: 15..25, 5..15, then the 5 oldest
+        assert_eq!(page_range(25, 0, 10), 15..25);
+        assert_eq!(page_range(25, 1, 10), 5..15);
+        assert_eq!(page_range(25, 2, 10), 0..5);
+        assert!(page_range(25, 3, 10).is_empty());
+        assert!(page_range(0, 0, 10).is_empty());
+        assert!(page_range(25, u64::MAX, u64::MAX).is_empty());
+
+        // A full entry fits the allocated size
+        let entry = EntryAccount {
+            is_initialized: true,
+            guestbook: Pubkey::new_unique(),
+            sequence: u64::MAX,
+            signer: Pubkey::new_unique(),
+            visit: u64::MAX,
+            timestamp: i64::MAX,
+            note: "x".repeat(MAX_NOTE_LENGTH),
+            bump: 255,
+        };
+        assert_eq!(entry.try_to_vec().unwrap().len(), ENTRY_ACCOUNT_SIZE);
+    }
+
+    #[test]
+    fn test_sign_checks_note_and_sequence() {
+        let program_id = Pubkey::new_unique();
+        let signer = Pubkey::new_unique();
+        let guestbook_key = Pubkey::new_unique();
+        let (visitor_key, _) = Pubkey::find_program_address(
+            &[VISITOR_SEED, guestbook_key.as_ref(), signer.as_ref()],
+            &program_id,
+        );
+
+        let guestbook = GuestbookAccount {
+            is_initialized: true,
+            owner: Pubkey::new_unique(),
+            entry_count: 3,
+            bump: 255,
+        };
+
+        // The entry for sequence 2 is already taken
+        let (stale_entry, _) = entry_address(&program_id, &guestbook_key, 2);
+
+        let keys = [signer, guestbook_key, visitor_key, stale_entry, Pubkey::default()];
+        let mut lamports: Vec<u64> = vec![10_000; keys.len()];
+        let mut data: Vec<Vec<u8>> = vec![vec![]; keys.len()];
+        data[1] = guestbook.try_to_vec().unwrap();
+
+        let accounts: Vec<AccountInfo> = keys
+            .iter()
+            .zip(lamports.iter_mut())
+            .zip(data.iter_mut())
+            .enumerate()
+            .map(|(i, ((key, lamports), data))| {
+                AccountInfo::new(key, i == 0, true, lamports, data, &program_id, false, Epoch::default())
+            })
+            .collect();
+
+        let too_long = GuestbookInstruction::Sign { note: "x".repeat(MAX_NOTE_LENGTH + 1) };
+        let result = process_instruction(&program_id, &accounts, &too_long.try_to_vec().unwrap());
+        assert_eq!(result, Err(GuestbookError::NoteTooLong.into()));
+
+        let empty = GuestbookInstruction::Sign { note: String::new() };
+        let result = process_instruction(&program_id, &accounts, &empty.try_to_vec().unwrap());
+        assert_eq!(result, Err(GuestbookError::EmptyNote.into()));
+
+        let stale = GuestbookInstruction::Sign { note: "hello".to_string() };
+        let result = process_instruction(&program_id, &accounts, &stale.try_to_vec().unwrap());
+        assert_eq!(result, Err(ProgramError::InvalidSeeds));
+    }
+}
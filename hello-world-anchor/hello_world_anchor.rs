@@ -0,0 +1,296 @@
+use anchor_lang::prelude::*;
+
+declare_id!("4YhCLBu9KNXjfKxhToPyGthmE6C7gFugw54XaxCh1dD7");
+
+/// Maximum message length (280 characters, similar to Twitter)
+pub const MAX_MESSAGE_LENGTH: usize = 280;
+
+/// Maximum number of writers on a message account's allowlist
+pub const MAX_WRITERS: usize = 5;
+
+/// Maximum length of the URI pointing at hash-anchored content
+pub const MAX_URI_LENGTH: usize = 200;
+
+#[program]
+pub mod hello_world_anchor {
+    use super::*;
+
+    /// Create the message account and store the first message
+    pub fn initialize(ctx: Context<Initialize>, message: String) -> Result<()> {
+        validate_message(&message)?;
+
+        let clock = Clock::get()?;
+        let account = &mut ctx.accounts.message_account;
+        let author = ctx.accounts.author.key();
+
+        account.is_initialized = true;
+        account.message = message;
+        account.last_updater = author;
+        account.author = author;
+        account.moderator = author;
+        account.revision = 1;
+        account.created_at = clock.unix_timestamp;
+        account.updated_at = clock.unix_timestamp;
+
+        emit!(MessageUpdated {
+            account: account.key(),
+            updater: author,
+            old_len: 0,
+            new_len: account.message.len() as u32,
+            slot: clock.slot,
+        });
+
+        msg!("Message initialized by {}", author);
+
+        Ok(())
+    }
+
+    /// Set a new message, optionally expiring `ttl_slots` slots from now.
+    /// If `expected_revision` is set, the update is rejected unless it
+    /// matches the stored revision (optimistic concurrency).
+    pub fn set_message(
+        ctx: Context<SetMessage>,
+        message: String,
+        ttl_slots: Option<u64>,
+        expected_revision: Option<u64>,
+    ) -> Result<()> {
+        validate_message(&message)?;
+
+        let clock = Clock::get()?;
+        let account = &mut ctx.accounts.message_account;
+        let updater = ctx.accounts.updater.key();
+
+        // Once an allowlist exists, only the author and listed writers may update
+        require!(
+            account.writers.is_empty()
+                || account.author == updater
+                || account.writers.contains(&updater),
+            HelloWorldError::NotWriter
+        );
+
+        if let Some(expected_revision) = expected_revision {
+            require_eq!(
+                expected_revision,
+                account.revision,
+                HelloWorldError::RevisionMismatch
+            );
+        }
+
+        account.expires_at_slot = match ttl_slots {
+            Some(0) => return err!(HelloWorldError::InvalidTtl),
+            Some(ttl) => Some(
+                clock
+                    .slot
+                    .checked_add(ttl)
+                    .ok_or(ProgramError::InvalidInstructionData)?,
+            ),
+            None => None,
+        };
+
+        let old_len = account.message.len();
+        account.message = message;
+        account.content_anchor = None;
+        account.last_updater = updater;
+        account.revision = account
+            .revision
+            .checked_add(1)
+            .ok_or(ProgramError::InvalidInstructionData)?;
+        account.updated_at = clock.unix_timestamp;
+
+        emit!(MessageUpdated {
+            account: account.key(),
+            updater,
+            old_len: old_len as u32,
+            new_len: account.message.len() as u32,
+            slot: clock.slot,
+        });
+
+        msg!("Message updated to: {}", account.message);
+        msg!("Updated by: {}", updater);
+        msg!("Revision: {}", account.revision);
+
+        Ok(())
+    }
+
+    /// Get the current message; Anchor writes the returned value as return data
+    /// Note: In production, reading data should be done off-chain via RPC calls
+    /// This instruction is included for demonstration purposes only
+    pub fn get_message(ctx: Context<GetMessage>) -> Result<HelloWorldAccount> {
+        let account = &ctx.accounts.message_account;
+
+        if let Some(expires_at_slot) = account.expires_at_slot {
+            if Clock::get()?.slot >= expires_at_slot {
+                msg!("Message is stale (expired at slot {})", expires_at_slot);
+            }
+        }
+
+        msg!("Current message: {}", account.message);
+        msg!("Last updated by: {}", account.last_updater);
+
+        Ok((**account).clone())
+    }
+}
+
+/// Validate message length
+fn validate_message(message: &str) -> Result<()> {
+    require!(!message.is_empty(), HelloWorldError::MessageEmpty);
+    require!(
+        message.len() <= MAX_MESSAGE_LENGTH,
+        HelloWorldError::MessageTooLong
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(init, payer = author, space = 8 + HelloWorldAccount::INIT_SPACE)]
+    pub message_account: Account<'info, HelloWorldAccount>,
+    #[account(mut)]
+    pub author: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetMessage<'info> {
+    #[account(mut)]
+    pub message_account: Account<'info, HelloWorldAccount>,
+    pub updater: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct GetMessage<'info> {
+    pub message_account: Account<'info, HelloWorldAccount>,
+}
+
+/// Same field order as the native `HelloWorldAccount`, so the bytes after
+/// Anchor's 8-byte discriminator decode with the native struct. Reply,
+/// cooldown, allowlist management, and token gating are only implemented
+/// natively.
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct HelloWorldAccount {
+    /// Always true once created (kept for layout parity with the native program)
+    pub is_initialized: bool,
+    /// The stored message
+    #[max_len(MAX_MESSAGE_LENGTH)]
+    pub message: String,
+    /// The public key of the last updater
+    pub last_updater: Pubkey,
+    /// Slot at which the message becomes stale (None = never expires)
+    pub expires_at_slot: Option<u64>,
+    /// The message this one replies to (None for top-level messages)
+    pub parent: Option<Pubkey>,
+    /// Number of replies created for this message
+    pub reply_count: u64,
+    /// The public key that created the message account
+    pub author: Pubkey,
+    /// Minimum number of slots between updates by the same updater (0 = no limit)
+    pub cooldown_slots: u64,
+    /// Pubkeys besides the author allowed to update the message
+    /// (empty = anyone may update)
+    #[max_len(MAX_WRITERS)]
+    pub writers: Vec<Pubkey>,
+    /// Number of times the message has changed, for optimistic concurrency
+    pub revision: u64,
+    /// Unix timestamp of the first write
+    pub created_at: i64,
+    /// Unix timestamp of the most recent write
+    pub updated_at: i64,
+    /// If set, updaters must hold at least `min_balance` of `mint`
+    pub token_gate: Option<TokenGate>,
+    /// Off-chain ed25519 key allowed to author messages (native `SetSignedMessage`)
+    pub signing_authority: Option<Pubkey>,
+    /// Hash and location of off-chain content (native `SetAnchoredContent`)
+    pub content_anchor: Option<ContentAnchor>,
+    /// Key allowed to hide the message (native `FlagMessage`)
+    pub moderator: Pubkey,
+    /// Set by the moderator; the content is kept but not displayed
+    pub hidden: bool,
+}
+
+/// Token-gating configuration for a message account
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Debug, PartialEq, Eq)]
+pub struct TokenGate {
+    /// The SPL token mint updaters must hold
+    pub mint: Pubkey,
+    /// Minimum token balance (in base units) required to update
+    pub min_balance: u64,
+}
+
+/// On-chain commitment to content stored off-chain
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Debug, PartialEq, Eq)]
+pub struct ContentAnchor {
+    /// `sha256` of the full content
+    pub content_hash: [u8; 32],
+    /// Length of the full content in bytes
+    pub content_len: u32,
+    /// Where the full content can be fetched
+    #[max_len(MAX_URI_LENGTH)]
+    pub uri: String,
+}
+
+/// Emitted whenever a message's content changes
+#[event]
+pub struct MessageUpdated {
+    /// The message account that changed
+    pub account: Pubkey,
+    /// The signer that made the change
+    pub updater: Pubkey,
+    /// Message length in bytes before the change
+    pub old_len: u32,
+    /// Message length in bytes after the change
+    pub new_len: u32,
+    /// Slot at which the change happened
+    pub slot: u64,
+}
+
+#[error_code]
+pub enum HelloWorldError {
+    #[msg("Message cannot be empty")]
+    MessageEmpty,
+    #[msg("Message too long (max 280 bytes)")]
+    MessageTooLong,
+    #[msg("Updater is not on the writer allowlist")]
+    NotWriter,
+    #[msg("Revision mismatch")]
+    RevisionMismatch,
+    #[msg("TTL must be greater than zero")]
+    InvalidTtl,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_init_space_fits_max_message() {
+        let account = HelloWorldAccount {
+            is_initialized: true,
+            message: "a".repeat(MAX_MESSAGE_LENGTH),
+            last_updater: Pubkey::new_unique(),
+            expires_at_slot: Some(u64::MAX),
+            parent: Some(Pubkey::new_unique()),
+            reply_count: u64::MAX,
+            author: Pubkey::new_unique(),
+            cooldown_slots: u64::MAX,
+            writers: vec![Pubkey::new_unique(); MAX_WRITERS],
+            revision: u64::MAX,
+            created_at: i64::MAX,
+            updated_at: i64::MAX,
+            token_gate: Some(TokenGate {
+                mint: Pubkey::new_unique(),
+                min_balance: u64::MAX,
+            }),
+            signing_authority: Some(Pubkey::new_unique()),
+            content_anchor: Some(ContentAnchor {
+                content_hash: [u8::MAX; 32],
+                content_len: u32::MAX,
+                uri: "u".repeat(MAX_URI_LENGTH),
+            }),
+            moderator: Pubkey::new_unique(),
+            hidden: true,
+        };
+
+        assert_eq!(account.try_to_vec().unwrap().len(), HelloWorldAccount::INIT_SPACE);
+    }
+}
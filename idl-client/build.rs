@@ -0,0 +1,158 @@
+//! Generates a client module for each IDL in idl/ into `OUT_DIR`, in the
+//! style of solores: for every instruction a `<Name>Keys` struct of its
+//! accounts, a `<Name>IxArgs` struct of its arguments, a `<NAME>_IX_DISCM`
+//! constant and a `<name>_ix` builder, plus a struct for every defined type.
+//! Only the IDL is read, so the output is what a client gets from the IDL
+//! alone.
+
+use std::{env, fmt::Write, fs, path::Path};
+
+use serde_json::Value;
+
+const PROGRAMS: &[&str] = &["counter", "crowdfund", "hello_world"];
+
+/// Format byte the programs expect ahead of the discriminant
+/// (`common::VersionedInstruction::FORMAT`), which the IDL has no field for
+const INSTRUCTION_FORMAT: u8 = 1;
+
+fn main() {
+    let idl_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("../idl");
+    let out_dir = env::var("OUT_DIR").unwrap();
+    for program in PROGRAMS {
+        let path = idl_dir.join(format!("{}.json", program));
+        println!("cargo:rerun-if-changed={}", path.display());
+        let idl: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        fs::write(Path::new(&out_dir).join(format!("{}.rs", program)), generate(program, &idl)).unwrap();
+    }
+}
+
+/// `durationSlots` or `BatchRefund` as `duration_slots` or `batch_refund`
+fn snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() {
+            if i > 0 {
+                snake.push('_');
+            }
+            snake.extend(c.to_lowercase());
+        } else {
+            snake.push(c);
+        }
+    }
+    snake
+}
+
+fn rust_type(ty: &Value) -> String {
+    match ty {
+        Value::String(name) => match name.as_str() {
+            "publicKey" => "Pubkey".to_string(),
+            "string" => "String".to_string(),
+            "bytes" => "Vec<u8>".to_string(),
+            primitive => primitive.to_string(),
+        },
+        Value::Object(map) => {
+            if let Some(inner) = map.get("option") {
+                format!("Option<{}>", rust_type(inner))
+            } else if let Some(inner) = map.get("vec") {
+                format!("Vec<{}>", rust_type(inner))
+            } else if let Some(array) = map.get("array") {
+                format!("[{}; {}]", rust_type(&array[0]), array[1])
+            } else if let Some(defined) = map.get("defined") {
+                defined.as_str().unwrap().to_string()
+            } else {
+                panic!("unsupported IDL type {}", ty)
+            }
+        }
+        _ => panic!("unsupported IDL type {}", ty),
+    }
+}
+
+fn doc(out: &mut String, indent: &str, text: Option<&str>) {
+    if let Some(text) = text {
+        writeln!(out, "{}/// {}", indent, text).unwrap();
+    }
+}
+
+fn generate(program: &str, idl: &Value) -> String {
+    let mut out = String::new();
+    writeln!(out, "// Generated by idl-client/build.rs from idl/{}.json", program).unwrap();
+    writeln!(out, "use solana_program::{{instruction::{{AccountMeta, Instruction}}, pubkey::Pubkey}};\n").unwrap();
+    writeln!(out, "/// Instruction format byte written ahead of each discriminant").unwrap();
+    writeln!(out, "pub const INSTRUCTION_FORMAT: u8 = {};\n", INSTRUCTION_FORMAT).unwrap();
+
+    for ty in idl["types"].as_array().unwrap() {
+        let fields = ty["type"]["fields"].as_array().expect("only struct types are supported");
+        writeln!(out, "#[derive(borsh::BorshSerialize, borsh::BorshDeserialize, Clone, Debug, PartialEq, Eq)]")
+            .unwrap();
+        writeln!(out, "pub struct {} {{", ty["name"].as_str().unwrap()).unwrap();
+        for field in fields {
+            let name = snake_case(field["name"].as_str().unwrap());
+            writeln!(out, "    pub {}: {},", name, rust_type(&field["type"])).unwrap();
+        }
+        writeln!(out, "}}\n").unwrap();
+    }
+
+    for ix in idl["instructions"].as_array().unwrap() {
+        let name = ix["name"].as_str().unwrap();
+        let snake = snake_case(name);
+        let accounts = ix["accounts"].as_array().unwrap();
+        let args = ix["args"].as_array().unwrap();
+
+        writeln!(out, "pub const {}_IX_DISCM: u8 = {};\n", snake.to_uppercase(), ix["discriminant"]["value"]).unwrap();
+
+        writeln!(out, "/// Accounts of `{}`; optional ones are left out when `None`", name).unwrap();
+        writeln!(out, "#[derive(Clone, Copy, Debug, PartialEq, Eq)]").unwrap();
+        writeln!(out, "pub struct {}Keys {{", name).unwrap();
+        for account in accounts {
+            doc(&mut out, "    ", account["desc"].as_str());
+            let optional = account["isOptional"].as_bool().unwrap_or(false);
+            let ty = if optional { "Option<Pubkey>" } else { "Pubkey" };
+            writeln!(out, "    pub {}: {},", snake_case(account["name"].as_str().unwrap()), ty).unwrap();
+        }
+        writeln!(out, "}}\n").unwrap();
+
+        writeln!(out, "impl {}Keys {{", name).unwrap();
+        writeln!(out, "    pub fn account_metas(&self) -> Vec<AccountMeta> {{").unwrap();
+        writeln!(out, "        [").unwrap();
+        for account in accounts {
+            let field = snake_case(account["name"].as_str().unwrap());
+            let constructor = if account["isMut"].as_bool().unwrap() { "new" } else { "new_readonly" };
+            let signer = account["isSigner"].as_bool().unwrap();
+            if account["isOptional"].as_bool().unwrap_or(false) {
+                let meta = format!("AccountMeta::{}(key, {})", constructor, signer);
+                writeln!(out, "            self.{}.map(|key| {}),", field, meta).unwrap();
+            } else {
+                writeln!(out, "            Some(AccountMeta::{}(self.{}, {})),", constructor, field, signer).unwrap();
+            }
+        }
+        writeln!(out, "        ]\n        .into_iter()\n        .flatten()\n        .collect()\n    }}\n}}\n").unwrap();
+
+        if !args.is_empty() {
+            writeln!(out, "#[derive(borsh::BorshSerialize, borsh::BorshDeserialize, Clone, Debug, PartialEq, Eq)]")
+                .unwrap();
+            writeln!(out, "pub struct {}IxArgs {{", name).unwrap();
+            for arg in args {
+                let field = snake_case(arg["name"].as_str().unwrap());
+                writeln!(out, "    pub {}: {},", field, rust_type(&arg["type"])).unwrap();
+            }
+            writeln!(out, "}}\n").unwrap();
+        }
+
+        if args.is_empty() {
+            writeln!(out, "pub fn {}_ix(program_id: Pubkey, keys: {}Keys) -> Instruction {{", snake, name).unwrap();
+            writeln!(out, "    let data = vec![INSTRUCTION_FORMAT, {}_IX_DISCM];", snake.to_uppercase()).unwrap();
+        } else {
+            writeln!(
+                out,
+                "pub fn {}_ix(program_id: Pubkey, keys: {}Keys, args: {}IxArgs) -> Instruction {{",
+                snake, name, name
+            )
+            .unwrap();
+            writeln!(out, "    let mut data = vec![INSTRUCTION_FORMAT, {}_IX_DISCM];", snake.to_uppercase()).unwrap();
+            writeln!(out, "    borsh::BorshSerialize::serialize(&args, &mut data).expect(\"args always serialize\");")
+                .unwrap();
+        }
+        writeln!(out, "    Instruction::new_with_bytes(program_id, &data, keys.account_metas())\n}}\n").unwrap();
+    }
+    out
+}
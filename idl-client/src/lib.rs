@@ -0,0 +1,21 @@
+//! Rust clients generated by build.rs from the shank IDLs in idl/, one module
+//! per program, regenerated whenever an IDL changes. They are the
+//! codegen side of the comparison with the hand-written `client` crate:
+//! `tests/matches_client.rs` checks both build byte-identical instructions.
+//!
+//! The IDLs only describe fixed account lists, so accounts a program takes
+//! beyond them (crowdfund's `BatchRefund` contributors) are appended by the
+//! caller, and accounts whose flags depend on the arguments (hello-world's
+//! updater, writable only with a cooldown) get the IDL's flags.
+
+pub mod counter {
+    include!(concat!(env!("OUT_DIR"), "/counter.rs"));
+}
+
+pub mod crowdfund {
+    include!(concat!(env!("OUT_DIR"), "/crowdfund.rs"));
+}
+
+pub mod hello_world {
+    include!(concat!(env!("OUT_DIR"), "/hello_world.rs"));
+}
@@ -0,0 +1,222 @@
+//! Every instruction built by the IDL-generated clients is byte-identical to
+//! the hand-written client's: same program, accounts and data.
+
+use client::{counter, crowdfund, hello_world};
+use solana_program::{instruction::AccountMeta, pubkey::Pubkey, system_program, sysvar};
+
+#[test]
+fn test_counter_matches_client() {
+    use idl_client::counter::*;
+
+    let (program_id, counter, owner) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+    assert_eq!(
+        initialize_ix(program_id, InitializeKeys { counter, owner }),
+        counter::initialize_counter(&program_id, &counter, &owner)
+    );
+    assert_eq!(
+        increment_ix(program_id, IncrementKeys { counter, owner }),
+        counter::increment(&program_id, &counter, &owner)
+    );
+    assert_eq!(
+        decrement_ix(program_id, DecrementKeys { counter, owner }),
+        counter::decrement(&program_id, &counter, &owner)
+    );
+    assert_eq!(
+        migrate_ix(program_id, MigrateKeys { counter, payer: owner, system_program: system_program::id() }),
+        counter::migrate(&program_id, &counter, &owner)
+    );
+}
+
+#[test]
+fn test_crowdfund_matches_client() {
+    use idl_client::crowdfund::*;
+
+    let (program_id, campaign, owner) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+    let contributor = Pubkey::new_unique();
+    let contributor_record = crowdfund::contributor_record_address(&campaign, &contributor);
+    let system_program = system_program::id();
+
+    assert_eq!(
+        initialize_ix(
+            program_id,
+            InitializeKeys { campaign, owner, system_program },
+            InitializeIxArgs { goal: 1_000, duration_slots: 100 },
+        ),
+        crowdfund::initialize_campaign(&program_id, &campaign, &owner, 1_000, 100)
+    );
+
+    let list = Pubkey::new_unique();
+    for allowlist in [None, Some(&list)] {
+        let keys = ContributeKeys {
+            campaign,
+            contributor_record,
+            contributor,
+            system_program,
+            membership: allowlist.map(|list| crowdfund::allowlist_membership_address(list, &contributor)),
+        };
+        assert_eq!(
+            contribute_ix(program_id, keys, ContributeIxArgs { amount: 600 }),
+            crowdfund::contribute(&program_id, &campaign, &contributor, 600, allowlist)
+        );
+    }
+
+    assert_eq!(
+        withdraw_ix(program_id, WithdrawKeys { campaign, owner, system_program }),
+        crowdfund::withdraw(&program_id, &campaign, &owner)
+    );
+    assert_eq!(
+        refund_ix(program_id, RefundKeys { campaign, contributor_record, contributor, system_program }),
+        crowdfund::refund(&program_id, &campaign, &contributor)
+    );
+
+    // The IDL has no remaining accounts, so the contributors are appended by hand
+    let mut batch = batch_refund_ix(program_id, BatchRefundKeys { campaign });
+    batch.accounts.extend([AccountMeta::new(contributor_record, false), AccountMeta::new(contributor, false)]);
+    assert_eq!(batch, crowdfund::batch_refund(&program_id, &campaign, &[contributor]));
+
+    assert_eq!(
+        set_allowlist_ix(
+            program_id,
+            SetAllowlistKeys { campaign, owner },
+            SetAllowlistIxArgs { allowlist: Some(list) },
+        ),
+        crowdfund::set_allowlist(&program_id, &campaign, &owner, Some(list))
+    );
+    assert_eq!(
+        migrate_ix(program_id, MigrateKeys { campaign, payer: owner, system_program }),
+        crowdfund::migrate(&program_id, &campaign, &owner)
+    );
+}
+
+#[test]
+fn test_hello_world_matches_client() {
+    use idl_client::hello_world::*;
+
+    let (program_id, message_account, author) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+    let writer = Pubkey::new_unique();
+    let system_program = system_program::id();
+
+    // Without a cooldown, the only case where the IDL's read-only updater is right
+    let token_account = Pubkey::new_unique();
+    let options = hello_world::SetMessageOptions {
+        ttl_slots: Some(100),
+        moderator: Some(author),
+        token_account: Some(token_account),
+        ..Default::default()
+    };
+    let keys = SetMessageKeys {
+        message_account,
+        updater: author,
+        cooldown: None,
+        system_program: None,
+        token_account: Some(token_account),
+    };
+    let args = SetMessageIxArgs {
+        message: "gm".to_string(),
+        ttl_slots: Some(100),
+        expected_revision: None,
+        moderator: Some(author),
+    };
+    assert_eq!(
+        set_message_ix(program_id, keys, args.clone()),
+        hello_world::set_message(&program_id, &message_account, &author, "gm", &options)
+    );
+
+    // With one, the data still matches
+    let cooldown = hello_world::SetMessageOptions { cooldown: true, ..options };
+    assert_eq!(
+        set_message_ix(program_id, keys, args).data,
+        hello_world::set_message(&program_id, &message_account, &author, "gm", &cooldown).data
+    );
+
+    assert_eq!(
+        get_message_ix(program_id, GetMessageKeys { message_account }),
+        hello_world::get_message(&program_id, &message_account)
+    );
+    assert_eq!(
+        reap_ix(program_id, ReapKeys { message_account, caller: author }),
+        hello_world::reap(&program_id, &message_account, &author)
+    );
+
+    let reply = hello_world::reply_address(&program_id, &message_account, 0);
+    assert_eq!(
+        reply_ix(
+            program_id,
+            ReplyKeys { parent: message_account, reply, author, system_program },
+            ReplyIxArgs { message: "gm".to_string() },
+        ),
+        hello_world::reply(&program_id, &message_account, 0, &author, "gm")
+    );
+
+    assert_eq!(
+        set_cooldown_ix(program_id, SetCooldownKeys { message_account, author }, SetCooldownIxArgs { cooldown_slots: 10 }),
+        hello_world::set_cooldown(&program_id, &message_account, &author, 10)
+    );
+    assert_eq!(
+        add_writer_ix(program_id, AddWriterKeys { message_account, author }, AddWriterIxArgs { writer_key: writer }),
+        hello_world::add_writer(&program_id, &message_account, &author, &writer)
+    );
+    assert_eq!(
+        remove_writer_ix(
+            program_id,
+            RemoveWriterKeys { message_account, author },
+            RemoveWriterIxArgs { writer_key: writer },
+        ),
+        hello_world::remove_writer(&program_id, &message_account, &author, &writer)
+    );
+
+    let mint = Pubkey::new_unique();
+    assert_eq!(
+        set_token_gate_ix(
+            program_id,
+            SetTokenGateKeys { message_account, author },
+            SetTokenGateIxArgs { token_gate: Some(TokenGate { mint, min_balance: 5 }) },
+        ),
+        hello_world::set_token_gate(
+            &program_id,
+            &message_account,
+            &author,
+            Some(smart_contract_comparison::hello_world::TokenGate { mint, min_balance: 5 }),
+        )
+    );
+    assert_eq!(
+        set_signing_authority_ix(
+            program_id,
+            SetSigningAuthorityKeys { message_account, author },
+            SetSigningAuthorityIxArgs { signing_authority: Some(writer) },
+        ),
+        hello_world::set_signing_authority(&program_id, &message_account, &author, Some(writer))
+    );
+    assert_eq!(
+        set_signed_message_ix(
+            program_id,
+            SetSignedMessageKeys { message_account, instructions: sysvar::instructions::id() },
+            SetSignedMessageIxArgs { message: "gm".to_string() },
+        ),
+        hello_world::set_signed_message(&program_id, &message_account, "gm")
+    );
+    assert_eq!(
+        set_anchored_content_ix(
+            program_id,
+            SetAnchoredContentKeys { message_account, author },
+            SetAnchoredContentIxArgs { content: vec![7], uri: "ipfs://x".to_string() },
+        ),
+        hello_world::set_anchored_content(&program_id, &message_account, &author, vec![7], "ipfs://x")
+    );
+    assert_eq!(
+        verify_content_ix(program_id, VerifyContentKeys { message_account }, VerifyContentIxArgs { content: vec![7] }),
+        hello_world::verify_content(&program_id, &message_account, vec![7])
+    );
+    assert_eq!(
+        flag_message_ix(program_id, FlagMessageKeys { message_account, moderator: author }),
+        hello_world::flag_message(&program_id, &message_account, &author)
+    );
+    assert_eq!(
+        unflag_message_ix(program_id, UnflagMessageKeys { message_account, moderator: author }),
+        hello_world::unflag_message(&program_id, &message_account, &author)
+    );
+    assert_eq!(
+        migrate_ix(program_id, MigrateKeys { message_account, payer: author, system_program }),
+        hello_world::migrate(&program_id, &message_account, &author)
+    );
+}
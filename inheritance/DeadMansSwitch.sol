@@ -0,0 +1,74 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+/**
+ * @title DeadMansSwitch
+ * @dev Holds ETH for an owner who must ping at least once per timeout.
+ * If the owner goes silent, the designated heir can claim the whole balance.
+ * Only the owner can reset the timer; deposits from others do not.
+ */
+contract DeadMansSwitch {
+    uint256 public constant MIN_TIMEOUT = 7 days;
+    uint256 public constant MAX_TIMEOUT = 730 days;
+
+    address public immutable owner;
+    address public heir;
+    uint256 public immutable timeout;
+    uint256 public lastPing;
+
+    event Pinged(uint256 deadline);
+    event HeirChanged(address indexed heir);
+    event Deposited(address indexed from, uint256 amount);
+    event Withdrawn(uint256 amount);
+    event Claimed(address indexed heir, uint256 amount);
+
+    modifier onlyOwnerAlive() {
+        require(msg.sender == owner, "Only owner");
+        require(block.timestamp < deadline(), "Expired");
+        _;
+        lastPing = block.timestamp;
+        emit Pinged(deadline());
+    }
+
+    constructor(address _heir, uint256 _timeout) payable {
+        require(_heir != msg.sender && _heir != address(0), "Invalid heir");
+        require(_timeout >= MIN_TIMEOUT && _timeout <= MAX_TIMEOUT, "Invalid timeout");
+        owner = msg.sender;
+        heir = _heir;
+        timeout = _timeout;
+        lastPing = block.timestamp;
+    }
+
+    receive() external payable {
+        emit Deposited(msg.sender, msg.value);
+    }
+
+    function deadline() public view returns (uint256) {
+        return lastPing + timeout;
+    }
+
+    function ping() external onlyOwnerAlive {}
+
+    function changeHeir(address newHeir) external onlyOwnerAlive {
+        require(newHeir != owner && newHeir != address(0), "Invalid heir");
+        heir = newHeir;
+        emit HeirChanged(newHeir);
+    }
+
+    function withdraw(uint256 amount) external onlyOwnerAlive {
+        require(amount > 0 && amount <= address(this).balance, "Invalid amount");
+        emit Withdrawn(amount);
+        (bool success, ) = payable(owner).call{value: amount}("");
+        require(success, "Transfer failed");
+    }
+
+    function claim() external {
+        require(msg.sender == heir, "Only heir");
+        require(block.timestamp >= deadline(), "Not expired");
+
+        uint256 amount = address(this).balance;
+        emit Claimed(msg.sender, amount);
+        (bool success, ) = payable(msg.sender).call{value: amount}("");
+        require(success, "Transfer failed");
+    }
+}
@@ -0,0 +1,445 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint,
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// Seed prefix for switch PDAs: ["switch", owner]
+pub const SWITCH_SEED: &[u8] = b"switch";
+
+/// Shortest allowed timeout (7 days), so a missed day cannot disinherit the owner
+pub const MIN_TIMEOUT_SECONDS: i64 = 7 * 86_400;
+
+/// Longest allowed timeout (2 years)
+pub const MAX_TIMEOUT_SECONDS: i64 = 730 * 86_400;
+
+/// Size of a `SwitchAccount`
+pub const SWITCH_ACCOUNT_SIZE: usize = 1 // is_initialized
+    + 32 // owner
+    + 32 // heir
+    + 8 // timeout_seconds
+    + 8 // last_ping
+    + 1; // bump
+
+/// Errors returned by the program as `ProgramError::Custom` codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InheritanceError {
+    /// Timeout is outside `MIN_TIMEOUT_SECONDS..=MAX_TIMEOUT_SECONDS`
+    InvalidTimeout = 0,
+    /// The owner pinged within the timeout
+    NotExpired = 1,
+    /// The switch has expired; the owner can no longer act
+    Expired = 2,
+    /// Signer is not allowed to do this
+    Unauthorized = 3,
+    /// The switch has not been initialized
+    NotInitialized = 4,
+    /// Amount is zero
+    InvalidAmount = 5,
+    /// The switch holds less than the amount above rent
+    InsufficientFunds = 6,
+    /// The heir cannot be the owner
+    InvalidHeir = 7,
+}
+
+impl From<InheritanceError> for ProgramError {
+    fn from(e: InheritanceError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+/// Funds that pass to `heir` if `owner` stops pinging for `timeout_seconds`
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct SwitchAccount {
+    pub is_initialized: bool,
+    pub owner: Pubkey,
+    pub heir: Pubkey,
+    pub timeout_seconds: i64,
+    pub last_ping: i64,
+    pub bump: u8,
+}
+
+impl SwitchAccount {
+    /// First moment the heir can claim
+    pub fn deadline(&self) -> i64 {
+        self.last_ping.saturating_add(self.timeout_seconds)
+    }
+
+    pub fn is_expired(&self, now: i64) -> bool {
+        now >= self.deadline()
+    }
+
+    /// Reset the timer; refused once expired so the owner cannot race a claim
+    pub fn ping(&mut self, now: i64) -> Result<(), InheritanceError> {
+        if self.is_expired(now) {
+            return Err(InheritanceError::Expired);
+        }
+        self.last_ping = now;
+        Ok(())
+    }
+}
+
+entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = InheritanceInstruction::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        InheritanceInstruction::Initialize { heir, timeout_seconds } => {
+            initialize(program_id, accounts, heir, timeout_seconds)
+        }
+        InheritanceInstruction::Deposit { amount } => deposit(program_id, accounts, amount),
+        InheritanceInstruction::Ping => ping(program_id, accounts),
+        InheritanceInstruction::ChangeHeir { heir } => change_heir(program_id, accounts, heir),
+        InheritanceInstruction::Withdraw { amount } => withdraw(program_id, accounts, amount),
+        InheritanceInstruction::Claim => claim(program_id, accounts),
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub enum InheritanceInstruction {
+    /// Create the switch and start the timer
+    /// Accounts: [writable, signer] owner, [writable] switch PDA, [] system program
+    Initialize { heir: Pubkey, timeout_seconds: i64 },
+    /// Add lamports; anyone can deposit, but deposits do not reset the timer
+    /// Accounts: [writable, signer] depositor, [writable] switch PDA, [] system program
+    Deposit { amount: u64 },
+    /// Prove the owner is still active
+    /// Accounts: [signer] owner, [writable] switch PDA
+    Ping,
+    /// Name a new heir; also counts as a ping
+    /// Accounts: [signer] owner, [writable] switch PDA
+    ChangeHeir { heir: Pubkey },
+    /// Take lamports out; also counts as a ping
+    /// Accounts: [signer] owner, [writable] switch PDA
+    Withdraw { amount: u64 },
+    /// After the timeout, the heir takes everything and the switch is closed
+    /// Accounts: [writable, signer] heir, [writable] switch PDA
+    Claim,
+}
+
+fn initialize(program_id: &Pubkey, accounts: &[AccountInfo], heir: Pubkey, timeout_seconds: i64) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let owner = next_account_info(accounts_iter)?;
+    let switch_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if !(MIN_TIMEOUT_SECONDS..=MAX_TIMEOUT_SECONDS).contains(&timeout_seconds) {
+        msg!("Timeout must be {}-{} seconds", MIN_TIMEOUT_SECONDS, MAX_TIMEOUT_SECONDS);
+        return Err(InheritanceError::InvalidTimeout.into());
+    }
+
+    if heir == *owner.key {
+        return Err(InheritanceError::InvalidHeir.into());
+    }
+
+    let (expected_switch, bump) = Pubkey::find_program_address(&[SWITCH_SEED, owner.key.as_ref()], program_id);
+    if expected_switch != *switch_account.key {
+        msg!("Switch account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    invoke_signed(
+        &system_instruction::create_account(
+            owner.key,
+            switch_account.key,
+            Rent::get()?.minimum_balance(SWITCH_ACCOUNT_SIZE),
+            SWITCH_ACCOUNT_SIZE as u64,
+            program_id,
+        ),
+        &[owner.clone(), switch_account.clone(), system_program.clone()],
+        &[&[SWITCH_SEED, owner.key.as_ref(), &[bump]]],
+    )?;
+
+    let switch = SwitchAccount {
+        is_initialized: true,
+        owner: *owner.key,
+        heir,
+        timeout_seconds,
+        last_ping: Clock::get()?.unix_timestamp,
+        bump,
+    };
+    switch.serialize(&mut &mut switch_account.data.borrow_mut()[..])?;
+
+    msg!("Switch created for heir {} with a {} second timeout", heir, timeout_seconds);
+
+    Ok(())
+}
+
+fn deposit(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let depositor = next_account_info(accounts_iter)?;
+    let switch_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !depositor.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if amount == 0 {
+        return Err(InheritanceError::InvalidAmount.into());
+    }
+
+    load_switch(program_id, switch_account)?;
+
+    invoke(
+        &system_instruction::transfer(depositor.key, switch_account.key, amount),
+        &[depositor.clone(), switch_account.clone(), system_program.clone()],
+    )?;
+
+    msg!("Deposited {} lamports", amount);
+
+    Ok(())
+}
+
+fn ping(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let owner = next_account_info(accounts_iter)?;
+    let switch_account = next_account_info(accounts_iter)?;
+
+    let mut switch = load_switch_as_owner(program_id, switch_account, owner)?;
+
+    switch.ping(Clock::get()?.unix_timestamp)?;
+    switch.serialize(&mut &mut switch_account.data.borrow_mut()[..])?;
+
+    msg!("Pinged; heir can claim from {}", switch.deadline());
+
+    Ok(())
+}
+
+fn change_heir(program_id: &Pubkey, accounts: &[AccountInfo], heir: Pubkey) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let owner = next_account_info(accounts_iter)?;
+    let switch_account = next_account_info(accounts_iter)?;
+
+    let mut switch = load_switch_as_owner(program_id, switch_account, owner)?;
+
+    if heir == switch.owner {
+        return Err(InheritanceError::InvalidHeir.into());
+    }
+
+    switch.ping(Clock::get()?.unix_timestamp)?;
+    switch.heir = heir;
+    switch.serialize(&mut &mut switch_account.data.borrow_mut()[..])?;
+
+    msg!("Heir changed to {}", heir);
+
+    Ok(())
+}
+
+fn withdraw(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let owner = next_account_info(accounts_iter)?;
+    let switch_account = next_account_info(accounts_iter)?;
+
+    let mut switch = load_switch_as_owner(program_id, switch_account, owner)?;
+
+    if amount == 0 {
+        return Err(InheritanceError::InvalidAmount.into());
+    }
+
+    switch.ping(Clock::get()?.unix_timestamp)?;
+
+    let available = switch_account
+        .lamports()
+        .saturating_sub(Rent::get()?.minimum_balance(switch_account.data_len()));
+    if amount > available {
+        msg!("Requested {} but only {} is available", amount, available);
+        return Err(InheritanceError::InsufficientFunds.into());
+    }
+
+    **switch_account.try_borrow_mut_lamports()? -= amount;
+    **owner.try_borrow_mut_lamports()? = owner
+        .lamports()
+        .checked_add(amount)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    switch.serialize(&mut &mut switch_account.data.borrow_mut()[..])?;
+
+    msg!("Withdrew {} lamports", amount);
+
+    Ok(())
+}
+
+fn claim(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let heir = next_account_info(accounts_iter)?;
+    let switch_account = next_account_info(accounts_iter)?;
+
+    if !heir.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let switch = load_switch(program_id, switch_account)?;
+
+    if switch.heir != *heir.key {
+        msg!("Only the designated heir can claim");
+        return Err(InheritanceError::Unauthorized.into());
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    if !switch.is_expired(now) {
+        msg!("Claimable from {}, now {}", switch.deadline(), now);
+        return Err(InheritanceError::NotExpired.into());
+    }
+
+    let lamports = switch_account.lamports();
+    **switch_account.try_borrow_mut_lamports()? = 0;
+    **heir.try_borrow_mut_lamports()? = heir
+        .lamports()
+        .checked_add(lamports)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    switch_account.data.borrow_mut().fill(0);
+
+    msg!("Heir {} claimed {} lamports", heir.key, lamports);
+
+    Ok(())
+}
+
+fn load_switch(program_id: &Pubkey, switch_account: &AccountInfo) -> Result<SwitchAccount, ProgramError> {
+    if switch_account.owner != program_id {
+        msg!("Switch account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let switch = SwitchAccount::try_from_slice(&switch_account.data.borrow())?;
+
+    if !switch.is_initialized {
+        return Err(InheritanceError::NotInitialized.into());
+    }
+
+    Ok(switch)
+}
+
+fn load_switch_as_owner(
+    program_id: &Pubkey,
+    switch_account: &AccountInfo,
+    owner: &AccountInfo,
+) -> Result<SwitchAccount, ProgramError> {
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let switch = load_switch(program_id, switch_account)?;
+
+    if switch.owner != *owner.key {
+        msg!("Only the owner can do this");
+        return Err(InheritanceError::Unauthorized.into());
+    }
+
+    Ok(switch)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_program::{clock::Epoch, entrypoint::SUCCESS, program_stubs};
+
+    /// Serves Clock at a fixed time and the default Rent so handlers can run off-chain
+    struct TestSyscallStubs {
+        unix_timestamp: i64,
+    }
+
+    impl program_stubs::SyscallStubs for TestSyscallStubs {
+        fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+            unsafe {
+                *(var_addr as *mut Clock) = Clock {
+                    unix_timestamp: self.unix_timestamp,
+                    ..Clock::default()
+                };
+            }
+            SUCCESS
+        }
+
+        fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+            unsafe {
+                *(var_addr as *mut Rent) = Rent::default();
+            }
+            SUCCESS
+        }
+    }
+
+    fn new_switch(owner: Pubkey, heir: Pubkey, last_ping: i64) -> SwitchAccount {
+        SwitchAccount {
+            is_initialized: true,
+            owner,
+            heir,
+            timeout_seconds: MIN_TIMEOUT_SECONDS,
+            last_ping,
+            bump: 255,
+        }
+    }
+
+    #[test]
+    fn test_ping_extends_deadline() {
+        let start = 1_700_000_000;
+        let mut switch = new_switch(Pubkey::new_unique(), Pubkey::new_unique(), start);
+
+        assert_eq!(switch.deadline(), start + MIN_TIMEOUT_SECONDS);
+        assert!(!switch.is_expired(start + MIN_TIMEOUT_SECONDS - 1));
+        assert!(switch.is_expired(start + MIN_TIMEOUT_SECONDS));
+
+        switch.ping(start + 86_400).unwrap();
+        assert!(!switch.is_expired(start + MIN_TIMEOUT_SECONDS));
+
+        // Once expired the owner cannot revive it ahead of the heir's claim
+        assert_eq!(switch.ping(switch.deadline()), Err(InheritanceError::Expired));
+        assert_eq!(switch.try_to_vec().unwrap().len(), SWITCH_ACCOUNT_SIZE);
+    }
+
+    #[test]
+    fn test_claim_after_timeout() {
+        let start = 1_700_000_000;
+        let program_id = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let heir = Pubkey::new_unique();
+        let switch_key = Pubkey::new_unique();
+
+        let keys = [heir, switch_key];
+        let mut lamports: Vec<u64> = vec![0, 1_000_000];
+        let mut data: Vec<Vec<u8>> = vec![vec![], new_switch(owner, heir, start).try_to_vec().unwrap()];
+
+        let accounts: Vec<AccountInfo> = keys
+            .iter()
+            .zip(lamports.iter_mut())
+            .zip(data.iter_mut())
+            .enumerate()
+            .map(|(i, ((key, lamports), data))| {
+                AccountInfo::new(key, i == 0, true, lamports, data, &program_id, false, Epoch::default())
+            })
+            .collect();
+        let instruction_data = InheritanceInstruction::Claim.try_to_vec().unwrap();
+
+        program_stubs::set_syscall_stubs(Box::new(TestSyscallStubs {
+            unix_timestamp: start + MIN_TIMEOUT_SECONDS - 1,
+        }));
+        let result = process_instruction(&program_id, &accounts, &instruction_data);
+        assert_eq!(result, Err(InheritanceError::NotExpired.into()));
+
+        program_stubs::set_syscall_stubs(Box::new(TestSyscallStubs {
+            unix_timestamp: start + MIN_TIMEOUT_SECONDS,
+        }));
+        process_instruction(&program_id, &accounts, &instruction_data).unwrap();
+        assert_eq!(accounts[0].lamports(), 1_000_000);
+        assert_eq!(accounts[1].lamports(), 0);
+        assert!(accounts[1].data.borrow().iter().all(|b| *b == 0));
+    }
+}
@@ -0,0 +1,78 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+/**
+ * @title MutualInsurance
+ * @dev Members pay a fixed premium per period into a shared pool. While
+ * covered they can file claims, which an adjuster approves or rejects.
+ * Payouts are capped per claim and can never exceed the pool's balance.
+ */
+contract MutualInsurance {
+    enum ClaimStatus { Pending, Paid, Rejected }
+
+    struct Claim {
+        address payable member;
+        uint256 amount;
+        ClaimStatus status;
+    }
+
+    address public immutable adjuster;
+    uint256 public immutable premium;
+    uint256 public immutable period;
+    uint256 public immutable maxClaim;
+
+    mapping(address => uint256) public coveredUntil;
+    Claim[] public claims;
+
+    event PremiumPaid(address indexed member, uint256 coveredUntil);
+    event ClaimFiled(uint256 indexed claimId, address indexed member, uint256 amount);
+    event ClaimPaid(uint256 indexed claimId, address indexed member, uint256 amount);
+    event ClaimRejected(uint256 indexed claimId);
+
+    modifier onlyAdjuster() {
+        require(msg.sender == adjuster, "Only adjuster");
+        _;
+    }
+
+    constructor(uint256 _premium, uint256 _period, uint256 _maxClaim) {
+        require(_premium > 0 && _period > 0 && _maxClaim > 0, "Invalid parameters");
+        adjuster = msg.sender;
+        premium = _premium;
+        period = _period;
+        maxClaim = _maxClaim;
+    }
+
+    function payPremium() external payable {
+        require(msg.value == premium, "Wrong premium");
+        uint256 start = coveredUntil[msg.sender] > block.timestamp ? coveredUntil[msg.sender] : block.timestamp;
+        coveredUntil[msg.sender] = start + period;
+        emit PremiumPaid(msg.sender, coveredUntil[msg.sender]);
+    }
+
+    function fileClaim(uint256 amount) external returns (uint256 claimId) {
+        require(block.timestamp < coveredUntil[msg.sender], "Not covered");
+        require(amount > 0 && amount <= maxClaim, "Invalid claim amount");
+
+        claimId = claims.length;
+        claims.push(Claim(payable(msg.sender), amount, ClaimStatus.Pending));
+        emit ClaimFiled(claimId, msg.sender, amount);
+    }
+
+    function approveClaim(uint256 claimId) external onlyAdjuster {
+        Claim storage claim = claims[claimId];
+        require(claim.status == ClaimStatus.Pending, "Not pending");
+        require(claim.amount <= address(this).balance, "Insolvent");
+
+        claim.status = ClaimStatus.Paid;
+        emit ClaimPaid(claimId, claim.member, claim.amount);
+        (bool success, ) = claim.member.call{value: claim.amount}("");
+        require(success, "Transfer failed");
+    }
+
+    function rejectClaim(uint256 claimId) external onlyAdjuster {
+        Claim storage claim = claims[claimId];
+        require(claim.status == ClaimStatus.Pending, "Not pending");
+        claim.status = ClaimStatus.Rejected;
+        emit ClaimRejected(claimId);
+    }
+}
@@ -0,0 +1,100 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+/**
+ * @title SplitInvoice
+ * @dev Invoices with line items that several allowed payers can pay in parts.
+ * Payments are held until the total is reached, then forwarded to the issuer.
+ * The issuer can cancel an open invoice, refunding every payer.
+ */
+contract SplitInvoice {
+    uint256 public constant MAX_LINE_ITEMS = 8;
+    uint256 public constant MAX_PAYERS = 5;
+
+    struct LineItem {
+        string description;
+        uint256 amount;
+    }
+
+    struct Invoice {
+        address payable issuer;
+        uint256 total;
+        uint256 paid;
+        bool settled;
+        bool closed;
+        address[] payers;
+    }
+
+    Invoice[] private invoices;
+    mapping(uint256 => LineItem[]) private lineItems;
+    /// invoiceId => payer => amount contributed
+    mapping(uint256 => mapping(address => uint256)) public contributed;
+    mapping(uint256 => mapping(address => bool)) public isPayer;
+
+    event InvoiceCreated(uint256 indexed invoiceId, address indexed issuer, uint256 total);
+    event Paid(uint256 indexed invoiceId, address indexed payer, uint256 amount, uint256 paid);
+    event Settled(uint256 indexed invoiceId, uint256 total);
+    event Cancelled(uint256 indexed invoiceId, uint256 refunded);
+
+    function createInvoice(LineItem[] calldata items, address[] calldata payers) external returns (uint256 invoiceId) {
+        require(items.length > 0 && items.length <= MAX_LINE_ITEMS, "Invalid line items");
+        require(payers.length > 0 && payers.length <= MAX_PAYERS, "Invalid payers");
+
+        invoiceId = invoices.length;
+        Invoice storage invoice = invoices.push();
+        invoice.issuer = payable(msg.sender);
+
+        for (uint256 i = 0; i < items.length; i++) {
+            require(items[i].amount > 0, "Zero amount");
+            invoice.total += items[i].amount;
+            lineItems[invoiceId].push(items[i]);
+        }
+        for (uint256 i = 0; i < payers.length; i++) {
+            require(!isPayer[invoiceId][payers[i]], "Duplicate payer");
+            isPayer[invoiceId][payers[i]] = true;
+            invoice.payers.push(payers[i]);
+        }
+
+        emit InvoiceCreated(invoiceId, msg.sender, invoice.total);
+    }
+
+    function pay(uint256 invoiceId) external payable {
+        Invoice storage invoice = invoices[invoiceId];
+        require(!invoice.settled && !invoice.closed, "Not open");
+        require(isPayer[invoiceId][msg.sender], "Payer not allowed");
+        require(msg.value > 0 && msg.value <= invoice.total - invoice.paid, "Invalid amount");
+
+        contributed[invoiceId][msg.sender] += msg.value;
+        invoice.paid += msg.value;
+        emit Paid(invoiceId, msg.sender, msg.value, invoice.paid);
+
+        if (invoice.paid == invoice.total) {
+            invoice.settled = true;
+            emit Settled(invoiceId, invoice.total);
+            (bool success, ) = invoice.issuer.call{value: invoice.total}("");
+            require(success, "Transfer failed");
+        }
+    }
+
+    function cancel(uint256 invoiceId) external {
+        Invoice storage invoice = invoices[invoiceId];
+        require(msg.sender == invoice.issuer, "Only issuer");
+        require(!invoice.settled && !invoice.closed, "Not open");
+        invoice.closed = true;
+
+        for (uint256 i = 0; i < invoice.payers.length; i++) {
+            address payer = invoice.payers[i];
+            uint256 amount = contributed[invoiceId][payer];
+            if (amount > 0) {
+                contributed[invoiceId][payer] = 0;
+                (bool success, ) = payable(payer).call{value: amount}("");
+                require(success, "Refund failed");
+            }
+        }
+        emit Cancelled(invoiceId, invoice.paid);
+    }
+
+    function getLineItems(uint256 invoiceId) external view returns (LineItem[] memory) {
+        return lineItems[invoiceId];
+    }
+}
@@ -0,0 +1,473 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint,
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// Seed prefix for invoice PDAs: ["invoice", issuer, invoice_id (u64 LE)]
+pub const INVOICE_SEED: &[u8] = b"invoice";
+
+/// Maximum number of line items per invoice
+pub const MAX_LINE_ITEMS: usize = 8;
+
+/// Maximum line item description length in bytes
+pub const MAX_DESCRIPTION_LENGTH: usize = 32;
+
+/// Maximum number of payers per invoice
+pub const MAX_PAYERS: usize = 5;
+
+/// Size of an `InvoiceAccount` with the maximum line items and payers
+pub const INVOICE_ACCOUNT_SIZE: usize = 1 // is_initialized
+    + 32 // issuer
+    + 8 // invoice_id
+    + 4 + MAX_LINE_ITEMS * (4 + MAX_DESCRIPTION_LENGTH + 8) // line_items
+    + 4 + MAX_PAYERS * (32 + 8) // payers
+    + 8 // total
+    + 8 // paid
+    + 1 // status
+    + 1; // bump
+
+/// Errors returned by the program as `ProgramError::Custom` codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvoiceError {
+    /// Line items or payers are empty, too many, duplicated or zero
+    InvalidParameters = 0,
+    /// The signer is not an allowed payer
+    PayerNotAllowed = 1,
+    /// The payment exceeds the amount still due
+    Overpayment = 2,
+    /// The invoice is not open for payment
+    NotOpen = 3,
+    /// Only the issuer can do this
+    Unauthorized = 4,
+    /// The invoice has not been initialized
+    NotInitialized = 5,
+    /// The refund accounts do not match the payer list
+    PayerMismatch = 6,
+}
+
+impl From<InvoiceError> for ProgramError {
+    fn from(e: InvoiceError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct LineItem {
+    pub description: String,
+    /// Lamports
+    pub amount: u64,
+}
+
+/// An allowed payer and how much they have paid so far
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Payer {
+    pub address: Pubkey,
+    pub contributed: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvoiceStatus {
+    /// Accepting payments
+    Open,
+    /// Fully paid and forwarded to the issuer
+    Settled,
+}
+
+/// An invoice that any of its payers can pay in parts. Payments are held in
+/// this PDA until the total is reached, then forwarded to the issuer.
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct InvoiceAccount {
+    pub is_initialized: bool,
+    pub issuer: Pubkey,
+    pub invoice_id: u64,
+    pub line_items: Vec<LineItem>,
+    pub payers: Vec<Payer>,
+    /// Sum of the line items
+    pub total: u64,
+    pub paid: u64,
+    pub status: InvoiceStatus,
+    pub bump: u8,
+}
+
+/// Sum of the line items, or `None` on overflow
+pub fn invoice_total(line_items: &[LineItem]) -> Option<u64> {
+    line_items.iter().try_fold(0u64, |sum, item| sum.checked_add(item.amount))
+}
+
+impl InvoiceAccount {
+    pub fn remaining(&self) -> u64 {
+        self.total - self.paid
+    }
+
+    /// Record `amount` from `payer`; returns whether the invoice is now fully paid
+    pub fn record_payment(&mut self, payer: &Pubkey, amount: u64) -> Result<bool, InvoiceError> {
+        if self.status != InvoiceStatus::Open {
+            return Err(InvoiceError::NotOpen);
+        }
+        if amount == 0 {
+            return Err(InvoiceError::InvalidParameters);
+        }
+        if amount > self.remaining() {
+            return Err(InvoiceError::Overpayment);
+        }
+
+        let entry = self
+            .payers
+            .iter_mut()
+            .find(|p| p.address == *payer)
+            .ok_or(InvoiceError::PayerNotAllowed)?;
+        entry.contributed += amount;
+        self.paid += amount;
+
+        Ok(self.paid == self.total)
+    }
+}
+
+entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = InvoiceInstruction::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        InvoiceInstruction::Create {
+            invoice_id,
+            line_items,
+            payers,
+        } => create(program_id, accounts, invoice_id, line_items, payers),
+        InvoiceInstruction::Pay { amount } => pay(program_id, accounts, amount),
+        InvoiceInstruction::Close => close(program_id, accounts),
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub enum InvoiceInstruction {
+    /// Create an invoice for the sum of `line_items`, payable by any of `payers`
+    /// Accounts: [writable, signer] issuer, [writable] invoice PDA, [] system program
+    Create {
+        invoice_id: u64,
+        line_items: Vec<LineItem>,
+        payers: Vec<Pubkey>,
+    },
+    /// Pay part or all of the amount due. The payment that completes the
+    /// total forwards everything to the issuer
+    /// Accounts: [writable, signer] payer, [writable] invoice PDA, [writable] issuer,
+    /// [] system program
+    Pay { amount: u64 },
+    /// Close the invoice and return its rent to the issuer. An open invoice is
+    /// cancelled first, refunding each payer's contribution
+    /// Accounts: [writable, signer] issuer, [writable] invoice PDA,
+    /// then for an open invoice: [writable] each payer, in payer-list order
+    Close,
+}
+
+fn create(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    invoice_id: u64,
+    line_items: Vec<LineItem>,
+    payers: Vec<Pubkey>,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let issuer = next_account_info(accounts_iter)?;
+    let invoice_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !issuer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if line_items.is_empty()
+        || line_items.len() > MAX_LINE_ITEMS
+        || line_items
+            .iter()
+            .any(|item| item.amount == 0 || item.description.len() > MAX_DESCRIPTION_LENGTH)
+    {
+        msg!("Need 1-{} line items with non-zero amounts", MAX_LINE_ITEMS);
+        return Err(InvoiceError::InvalidParameters.into());
+    }
+
+    if payers.is_empty()
+        || payers.len() > MAX_PAYERS
+        || payers.iter().enumerate().any(|(i, p)| payers[..i].contains(p))
+    {
+        msg!("Need 1-{} distinct payers", MAX_PAYERS);
+        return Err(InvoiceError::InvalidParameters.into());
+    }
+
+    let total = invoice_total(&line_items).ok_or(InvoiceError::InvalidParameters)?;
+
+    let id_bytes = invoice_id.to_le_bytes();
+    let (expected_invoice, bump) =
+        Pubkey::find_program_address(&[INVOICE_SEED, issuer.key.as_ref(), &id_bytes], program_id);
+    if expected_invoice != *invoice_account.key {
+        msg!("Invoice account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    invoke_signed(
+        &system_instruction::create_account(
+            issuer.key,
+            invoice_account.key,
+            Rent::get()?.minimum_balance(INVOICE_ACCOUNT_SIZE),
+            INVOICE_ACCOUNT_SIZE as u64,
+            program_id,
+        ),
+        &[issuer.clone(), invoice_account.clone(), system_program.clone()],
+        &[&[INVOICE_SEED, issuer.key.as_ref(), &id_bytes, &[bump]]],
+    )?;
+
+    let invoice = InvoiceAccount {
+        is_initialized: true,
+        issuer: *issuer.key,
+        invoice_id,
+        line_items,
+        payers: payers
+            .into_iter()
+            .map(|address| Payer { address, contributed: 0 })
+            .collect(),
+        total,
+        paid: 0,
+        status: InvoiceStatus::Open,
+        bump,
+    };
+    invoice.serialize(&mut &mut invoice_account.data.borrow_mut()[..])?;
+
+    msg!("Invoice {} for {} lamports created", invoice_id, total);
+
+    Ok(())
+}
+
+fn pay(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let payer = next_account_info(accounts_iter)?;
+    let invoice_account = next_account_info(accounts_iter)?;
+    let issuer = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !payer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut invoice = load_invoice(program_id, invoice_account)?;
+
+    if invoice.issuer != *issuer.key {
+        msg!("Issuer account does not match the invoice");
+        return Err(InvoiceError::Unauthorized.into());
+    }
+
+    if let Err(e) = invoice.record_payment(payer.key, amount) {
+        msg!("Payment of {} rejected; {} still due", amount, invoice.remaining());
+        return Err(e.into());
+    }
+
+    invoke(
+        &system_instruction::transfer(payer.key, invoice_account.key, amount),
+        &[payer.clone(), invoice_account.clone(), system_program.clone()],
+    )?;
+
+    // The final payment settles: forward the total and keep only the rent
+    if invoice.paid == invoice.total {
+        **invoice_account.try_borrow_mut_lamports()? -= invoice.total;
+        **issuer.try_borrow_mut_lamports()? = issuer
+            .lamports()
+            .checked_add(invoice.total)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        invoice.status = InvoiceStatus::Settled;
+        msg!("Invoice {} settled: {} lamports to the issuer", invoice.invoice_id, invoice.total);
+    } else {
+        msg!("Paid {} of {}", invoice.paid, invoice.total);
+    }
+
+    invoice.serialize(&mut &mut invoice_account.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+fn close(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let issuer = next_account_info(accounts_iter)?;
+    let invoice_account = next_account_info(accounts_iter)?;
+
+    if !issuer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let invoice = load_invoice(program_id, invoice_account)?;
+
+    if invoice.issuer != *issuer.key {
+        msg!("Only the issuer can close an invoice");
+        return Err(InvoiceError::Unauthorized.into());
+    }
+
+    // Cancelling an open invoice returns every partial payment
+    if invoice.status == InvoiceStatus::Open {
+        for payer in &invoice.payers {
+            let payer_account = next_account_info(accounts_iter)?;
+            if *payer_account.key != payer.address {
+                msg!("Expected payer {}", payer.address);
+                return Err(InvoiceError::PayerMismatch.into());
+            }
+            if payer.contributed > 0 {
+                **invoice_account.try_borrow_mut_lamports()? -= payer.contributed;
+                **payer_account.try_borrow_mut_lamports()? = payer_account
+                    .lamports()
+                    .checked_add(payer.contributed)
+                    .ok_or(ProgramError::ArithmeticOverflow)?;
+            }
+        }
+        msg!("Invoice {} cancelled; refunded {} lamports", invoice.invoice_id, invoice.paid);
+    }
+
+    // Whatever is left is the rent the issuer paid
+    let lamports = invoice_account.lamports();
+    **invoice_account.try_borrow_mut_lamports()? = 0;
+    **issuer.try_borrow_mut_lamports()? = issuer
+        .lamports()
+        .checked_add(lamports)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    invoice_account.data.borrow_mut().fill(0);
+
+    msg!("Invoice {} closed", invoice.invoice_id);
+
+    Ok(())
+}
+
+fn load_invoice(program_id: &Pubkey, invoice_account: &AccountInfo) -> Result<InvoiceAccount, ProgramError> {
+    if invoice_account.owner != program_id {
+        msg!("Invoice account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    // Shorter descriptions and lists leave unused space at the end of the account
+    let invoice = InvoiceAccount::deserialize(&mut &invoice_account.data.borrow()[..])?;
+
+    if !invoice.is_initialized {
+        return Err(InvoiceError::NotInitialized.into());
+    }
+
+    Ok(invoice)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_program::clock::Epoch;
+
+    fn new_invoice(payers: &[Pubkey]) -> InvoiceAccount {
+        let line_items = vec![
+            LineItem {
+                description: "Design".to_string(),
+                amount: 600,
+            },
+            LineItem {
+                description: "Hosting".to_string(),
+                amount: 400,
+            },
+        ];
+        InvoiceAccount {
+            is_initialized: true,
+            issuer: Pubkey::new_unique(),
+            invoice_id: 1,
+            total: invoice_total(&line_items).unwrap(),
+            line_items,
+            payers: payers
+                .iter()
+                .map(|&address| Payer { address, contributed: 0 })
+                .collect(),
+            paid: 0,
+            status: InvoiceStatus::Open,
+            bump: 255,
+        }
+    }
+
+    #[test]
+    fn test_partial_payments_accumulate() {
+        let alice = Pubkey::new_unique();
+        let bob = Pubkey::new_unique();
+        let mut invoice = new_invoice(&[alice, bob]);
+        assert_eq!(invoice.total, 1_000);
+
+        assert_eq!(invoice.record_payment(&alice, 300), Ok(false));
+        assert_eq!(invoice.record_payment(&Pubkey::new_unique(), 100), Err(InvoiceError::PayerNotAllowed));
+        assert_eq!(invoice.record_payment(&bob, 701), Err(InvoiceError::Overpayment));
+        assert_eq!(invoice.record_payment(&bob, 500), Ok(false));
+        assert_eq!(invoice.record_payment(&alice, 200), Ok(true));
+
+        assert_eq!(invoice.payers[0].contributed, 500);
+        assert_eq!(invoice.payers[1].contributed, 500);
+        assert_eq!(invoice.remaining(), 0);
+
+        let huge = LineItem {
+            description: String::new(),
+            amount: u64::MAX,
+        };
+        assert_eq!(invoice_total(&[huge.clone(), huge]), None);
+
+        // Maximum line items and payers fit the allocated size
+        let mut full = new_invoice(&[Pubkey::new_unique(); MAX_PAYERS]);
+        full.line_items = vec![
+            LineItem {
+                description: "x".repeat(MAX_DESCRIPTION_LENGTH),
+                amount: 1,
+            };
+            MAX_LINE_ITEMS
+        ];
+        assert_eq!(full.try_to_vec().unwrap().len(), INVOICE_ACCOUNT_SIZE);
+    }
+
+    #[test]
+    fn test_cancel_refunds_each_payer() {
+        let program_id = Pubkey::new_unique();
+        let alice = Pubkey::new_unique();
+        let bob = Pubkey::new_unique();
+        let invoice_key = Pubkey::new_unique();
+
+        let mut invoice = new_invoice(&[alice, bob]);
+        invoice.record_payment(&alice, 300).unwrap();
+        invoice.record_payment(&bob, 200).unwrap();
+        let issuer = invoice.issuer;
+
+        let keys = [issuer, invoice_key, alice, bob];
+        // The invoice holds 2,000 lamports of rent plus the 500 paid
+        let mut lamports: Vec<u64> = vec![0, 2_500, 0, 0];
+        let mut data: Vec<Vec<u8>> = vec![vec![]; keys.len()];
+        data[1] = invoice.try_to_vec().unwrap();
+
+        let accounts: Vec<AccountInfo> = keys
+            .iter()
+            .zip(lamports.iter_mut())
+            .zip(data.iter_mut())
+            .enumerate()
+            .map(|(i, ((key, lamports), data))| {
+                AccountInfo::new(key, i == 0, true, lamports, data, &program_id, false, Epoch::default())
+            })
+            .collect();
+
+        let instruction_data = InvoiceInstruction::Close.try_to_vec().unwrap();
+
+        // Payers out of order are rejected
+        let swapped = [accounts[0].clone(), accounts[1].clone(), accounts[3].clone(), accounts[2].clone()];
+        let result = process_instruction(&program_id, &swapped, &instruction_data);
+        assert_eq!(result, Err(InvoiceError::PayerMismatch.into()));
+
+        process_instruction(&program_id, &accounts, &instruction_data).unwrap();
+        assert_eq!(accounts[2].lamports(), 300);
+        assert_eq!(accounts[3].lamports(), 200);
+        assert_eq!(accounts[0].lamports(), 2_000);
+        assert_eq!(accounts[1].lamports(), 0);
+    }
+}
@@ -0,0 +1,78 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+/**
+ * @title KeyValueStore
+ * @dev Namespaced byte values per owner with optional expiry and compare-and-set writes
+ */
+contract KeyValueStore {
+    uint256 public constant MAX_KEY_LENGTH = 32;
+    uint256 public constant MAX_VALUE_LENGTH = 1024;
+
+    struct Entry {
+        bytes value;
+        uint64 version;
+        uint64 expiresAt;
+    }
+
+    // owner => namespace => key => entry
+    mapping(address => mapping(string => mapping(string => Entry))) private entries;
+
+    event EntrySet(address indexed owner, string namespace, string key, uint64 version);
+    event EntryDeleted(address indexed owner, string namespace, string key);
+
+    /**
+     * @dev Write a value under the caller's namespace. Pass ttl = 0 for no expiry and
+     * checkVersion = false to write unconditionally; a missing entry is at version 0
+     */
+    function set(
+        string calldata namespace,
+        string calldata key,
+        bytes calldata value,
+        uint64 ttl,
+        bool checkVersion,
+        uint64 expectedVersion
+    ) external {
+        require(bytes(namespace).length > 0 && bytes(namespace).length <= MAX_KEY_LENGTH, "Invalid namespace");
+        require(bytes(key).length > 0 && bytes(key).length <= MAX_KEY_LENGTH, "Invalid key");
+        require(value.length <= MAX_VALUE_LENGTH, "Value too large");
+
+        Entry storage entry = entries[msg.sender][namespace][key];
+        require(!checkVersion || entry.version == expectedVersion, "Version mismatch");
+
+        entry.value = value;
+        entry.version += 1;
+        entry.expiresAt = ttl == 0 ? 0 : uint64(block.timestamp) + ttl;
+
+        emit EntrySet(msg.sender, namespace, key, entry.version);
+    }
+
+    /**
+     * @dev Read a value; reverts if it does not exist or has expired
+     */
+    function get(address owner, string calldata namespace, string calldata key)
+        external
+        view
+        returns (bytes memory value, uint64 version)
+    {
+        Entry storage entry = entries[owner][namespace][key];
+        require(entry.version > 0, "Not found");
+        require(entry.expiresAt == 0 || block.timestamp < entry.expiresAt, "Expired");
+        return (entry.value, entry.version);
+    }
+
+    /**
+     * @dev Delete one of the caller's entries, optionally only at an expected version
+     */
+    function remove(string calldata namespace, string calldata key, bool checkVersion, uint64 expectedVersion)
+        external
+    {
+        Entry storage entry = entries[msg.sender][namespace][key];
+        require(entry.version > 0, "Not found");
+        require(!checkVersion || entry.version == expectedVersion, "Version mismatch");
+
+        delete entries[msg.sender][namespace][key];
+
+        emit EntryDeleted(msg.sender, namespace, key);
+    }
+}
@@ -0,0 +1,438 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint,
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed, set_return_data, MAX_RETURN_DATA},
+    program_error::ProgramError,
+    pubkey::{Pubkey, MAX_SEED_LEN},
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// Seed prefix for entry PDAs: ["kv", owner, namespace, key]
+pub const KV_SEED: &[u8] = b"kv";
+
+/// Namespaces and keys are PDA seeds, so each is limited to one seed
+pub const MAX_KEY_LENGTH: usize = MAX_SEED_LEN;
+
+/// Largest value; a whole value fits in return data for `Get`
+pub const MAX_VALUE_LENGTH: usize = MAX_RETURN_DATA;
+
+/// Errors returned by the program as `ProgramError::Custom` codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KvError {
+    /// Namespace or key is empty or longer than `MAX_KEY_LENGTH`
+    InvalidKey = 0,
+    /// Value is longer than `MAX_VALUE_LENGTH`
+    ValueTooLarge = 1,
+    /// Compare-and-set failed: the entry is at a different version
+    VersionMismatch = 2,
+    /// The entry's TTL has passed
+    Expired = 3,
+    /// Only the owner can write this entry
+    Unauthorized = 4,
+    /// The entry has not been initialized
+    NotInitialized = 5,
+    /// TTL must be positive
+    InvalidTtl = 6,
+}
+
+impl From<KvError> for ProgramError {
+    fn from(e: KvError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+/// One value under `owner`'s `namespace`/`key`. The account is sized to the
+/// value and reallocated when the value changes size.
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Eq)]
+pub struct KvEntry {
+    pub is_initialized: bool,
+    pub owner: Pubkey,
+    pub namespace: String,
+    pub key: String,
+    /// Starts at 1 and increases on every write; compare-and-set checks it
+    pub version: u64,
+    /// Unix time after which reads fail; `None` never expires
+    pub expires_at: Option<i64>,
+    pub bump: u8,
+    pub value: Vec<u8>,
+}
+
+impl KvEntry {
+    /// Exact serialized size, used to size the account
+    pub fn size(&self) -> usize {
+        1 // is_initialized
+            + 32 // owner
+            + 4 + self.namespace.len() // namespace
+            + 4 + self.key.len() // key
+            + 8 // version
+            + 1 + if self.expires_at.is_some() { 8 } else { 0 } // expires_at
+            + 1 // bump
+            + 4 + self.value.len() // value
+    }
+
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expires_at.is_some_and(|expires_at| now >= expires_at)
+    }
+
+    /// Compare-and-set: `None` writes unconditionally, `Some(v)` only if the
+    /// entry is at version `v` (0 for an entry that does not exist yet)
+    pub fn check_version(current: u64, expected: Option<u64>) -> Result<(), KvError> {
+        match expected {
+            Some(expected) if expected != current => Err(KvError::VersionMismatch),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Address of the entry for `owner`/`namespace`/`key`
+pub fn entry_address(program_id: &Pubkey, owner: &Pubkey, namespace: &str, key: &str) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[KV_SEED, owner.as_ref(), namespace.as_bytes(), key.as_bytes()],
+        program_id,
+    )
+}
+
+fn validate_key(namespace: &str, key: &str) -> Result<(), KvError> {
+    let valid = |s: &str| !s.is_empty() && s.len() <= MAX_KEY_LENGTH;
+    if !valid(namespace) || !valid(key) {
+        return Err(KvError::InvalidKey);
+    }
+    Ok(())
+}
+
+entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = KvInstruction::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        KvInstruction::Set {
+            namespace,
+            key,
+            value,
+            ttl_seconds,
+            expected_version,
+        } => set(program_id, accounts, namespace, key, value, ttl_seconds, expected_version),
+        KvInstruction::Get => get(program_id, accounts),
+        KvInstruction::Delete { expected_version } => delete(program_id, accounts, expected_version),
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub enum KvInstruction {
+    /// Create or overwrite an entry. `ttl_seconds` sets an expiry from now;
+    /// `expected_version` makes the write a compare-and-set.
+    /// Accounts: [writable, signer] owner, [writable] entry PDA, [] system program
+    Set {
+        namespace: String,
+        key: String,
+        value: Vec<u8>,
+        ttl_seconds: Option<i64>,
+        expected_version: Option<u64>,
+    },
+    /// Return the value through return data; fails once expired (for CPI callers)
+    /// Accounts: [] entry PDA
+    Get,
+    /// Close the entry and refund its rent, optionally only at `expected_version`
+    /// Accounts: [writable, signer] owner, [writable] entry PDA
+    Delete { expected_version: Option<u64> },
+}
+
+fn set(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    namespace: String,
+    key: String,
+    value: Vec<u8>,
+    ttl_seconds: Option<i64>,
+    expected_version: Option<u64>,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let owner = next_account_info(accounts_iter)?;
+    let entry_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    validate_key(&namespace, &key)?;
+
+    if value.len() > MAX_VALUE_LENGTH {
+        msg!("Value is {} bytes; the limit is {}", value.len(), MAX_VALUE_LENGTH);
+        return Err(KvError::ValueTooLarge.into());
+    }
+
+    let expires_at = match ttl_seconds {
+        Some(ttl) if ttl <= 0 => return Err(KvError::InvalidTtl.into()),
+        Some(ttl) => Some(Clock::get()?.unix_timestamp.saturating_add(ttl)),
+        None => None,
+    };
+
+    let (expected_entry, bump) = entry_address(program_id, owner.key, &namespace, &key);
+    if expected_entry != *entry_account.key {
+        msg!("Entry account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let rent = Rent::get()?;
+
+    // First write: create the account at exactly the entry's size
+    if entry_account.lamports() == 0 {
+        KvEntry::check_version(0, expected_version)?;
+
+        let entry = KvEntry {
+            is_initialized: true,
+            owner: *owner.key,
+            namespace,
+            key,
+            version: 1,
+            expires_at,
+            bump,
+            value,
+        };
+        let size = entry.size();
+
+        invoke_signed(
+            &system_instruction::create_account(
+                owner.key,
+                entry_account.key,
+                rent.minimum_balance(size),
+                size as u64,
+                program_id,
+            ),
+            &[owner.clone(), entry_account.clone(), system_program.clone()],
+            &[&[
+                KV_SEED,
+                owner.key.as_ref(),
+                entry.namespace.as_bytes(),
+                entry.key.as_bytes(),
+                &[bump],
+            ]],
+        )?;
+        entry.serialize(&mut &mut entry_account.data.borrow_mut()[..])?;
+
+        msg!("Created {}/{} ({} bytes)", entry.namespace, entry.key, entry.value.len());
+        return Ok(());
+    }
+
+    let mut entry = load_entry_as_owner(program_id, entry_account, owner)?;
+    KvEntry::check_version(entry.version, expected_version)?;
+
+    entry.value = value;
+    entry.expires_at = expires_at;
+    entry.version += 1;
+
+    let old_len = entry_account.data_len();
+    let new_len = entry.size();
+
+    if new_len > old_len {
+        // Top up rent before growing, so the account is never below the minimum for its size
+        let shortfall = rent.minimum_balance(new_len).saturating_sub(entry_account.lamports());
+        if shortfall > 0 {
+            invoke(
+                &system_instruction::transfer(owner.key, entry_account.key, shortfall),
+                &[owner.clone(), entry_account.clone(), system_program.clone()],
+            )?;
+        }
+        entry_account.realloc(new_len, false)?;
+        entry.serialize(&mut &mut entry_account.data.borrow_mut()[..])?;
+    } else {
+        // Write first: the entry fits the old size, and realloc then cuts off the stale tail
+        entry.serialize(&mut &mut entry_account.data.borrow_mut()[..])?;
+        if new_len < old_len {
+            entry_account.realloc(new_len, false)?;
+
+            let excess = entry_account.lamports().saturating_sub(rent.minimum_balance(new_len));
+            **entry_account.try_borrow_mut_lamports()? -= excess;
+            **owner.try_borrow_mut_lamports()? = owner
+                .lamports()
+                .checked_add(excess)
+                .ok_or(ProgramError::ArithmeticOverflow)?;
+        }
+    }
+
+    msg!("Set {}/{} to version {}", entry.namespace, entry.key, entry.version);
+
+    Ok(())
+}
+
+fn get(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let entry_account = next_account_info(accounts_iter)?;
+
+    let entry = load_entry(program_id, entry_account)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    if entry.is_expired(now) {
+        msg!("{}/{} expired", entry.namespace, entry.key);
+        return Err(KvError::Expired.into());
+    }
+
+    set_return_data(&entry.value);
+
+    Ok(())
+}
+
+fn delete(program_id: &Pubkey, accounts: &[AccountInfo], expected_version: Option<u64>) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let owner = next_account_info(accounts_iter)?;
+    let entry_account = next_account_info(accounts_iter)?;
+
+    let entry = load_entry_as_owner(program_id, entry_account, owner)?;
+    KvEntry::check_version(entry.version, expected_version)?;
+
+    let lamports = entry_account.lamports();
+    **entry_account.try_borrow_mut_lamports()? = 0;
+    **owner.try_borrow_mut_lamports()? = owner
+        .lamports()
+        .checked_add(lamports)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    entry_account.data.borrow_mut().fill(0);
+
+    msg!("Deleted {}/{}", entry.namespace, entry.key);
+
+    Ok(())
+}
+
+fn load_entry(program_id: &Pubkey, entry_account: &AccountInfo) -> Result<KvEntry, ProgramError> {
+    if entry_account.owner != program_id {
+        msg!("Entry account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let entry = KvEntry::try_from_slice(&entry_account.data.borrow())?;
+
+    if !entry.is_initialized {
+        return Err(KvError::NotInitialized.into());
+    }
+
+    Ok(entry)
+}
+
+fn load_entry_as_owner(
+    program_id: &Pubkey,
+    entry_account: &AccountInfo,
+    owner: &AccountInfo,
+) -> Result<KvEntry, ProgramError> {
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let entry = load_entry(program_id, entry_account)?;
+
+    if entry.owner != *owner.key {
+        msg!("Only the owner can write this entry");
+        return Err(KvError::Unauthorized.into());
+    }
+
+    Ok(entry)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_program::{clock::Epoch, entrypoint::SUCCESS, program_stubs};
+
+    /// Serves Clock at a fixed time so handlers can run off-chain
+    struct TestSyscallStubs {
+        unix_timestamp: i64,
+    }
+
+    impl program_stubs::SyscallStubs for TestSyscallStubs {
+        fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+            unsafe {
+                *(var_addr as *mut Clock) = Clock {
+                    unix_timestamp: self.unix_timestamp,
+                    ..Clock::default()
+                };
+            }
+            SUCCESS
+        }
+    }
+
+    fn entry(owner: Pubkey, expires_at: Option<i64>) -> KvEntry {
+        KvEntry {
+            is_initialized: true,
+            owner,
+            namespace: "config".to_string(),
+            key: "fee".to_string(),
+            version: 3,
+            expires_at,
+            bump: 255,
+            value: vec![1, 2, 3],
+        }
+    }
+
+    #[test]
+    fn test_versions_ttl_and_size() {
+        assert_eq!(KvEntry::check_version(3, None), Ok(()));
+        assert_eq!(KvEntry::check_version(3, Some(3)), Ok(()));
+        assert_eq!(KvEntry::check_version(3, Some(2)), Err(KvError::VersionMismatch));
+        // Create-if-absent is a compare-and-set against version 0
+        assert_eq!(KvEntry::check_version(0, Some(0)), Ok(()));
+
+        let forever = entry(Pubkey::new_unique(), None);
+        assert!(!forever.is_expired(i64::MAX));
+        let expiring = entry(Pubkey::new_unique(), Some(1_000));
+        assert!(!expiring.is_expired(999));
+        assert!(expiring.is_expired(1_000));
+
+        assert_eq!(forever.size(), forever.try_to_vec().unwrap().len());
+        assert_eq!(expiring.size(), expiring.try_to_vec().unwrap().len());
+
+        assert_eq!(validate_key("config", &"k".repeat(MAX_KEY_LENGTH)), Ok(()));
+        assert_eq!(validate_key("", "fee"), Err(KvError::InvalidKey));
+        assert_eq!(validate_key("config", &"k".repeat(MAX_KEY_LENGTH + 1)), Err(KvError::InvalidKey));
+    }
+
+    #[test]
+    fn test_get_and_compare_and_delete() {
+        let program_id = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let entry_key = Pubkey::new_unique();
+
+        let keys = [owner, entry_key];
+        let mut lamports: Vec<u64> = vec![0, 5_000];
+        let mut data: Vec<Vec<u8>> = vec![vec![], entry(owner, Some(1_000)).try_to_vec().unwrap()];
+
+        let accounts: Vec<AccountInfo> = keys
+            .iter()
+            .zip(lamports.iter_mut())
+            .zip(data.iter_mut())
+            .enumerate()
+            .map(|(i, ((key, lamports), data))| {
+                AccountInfo::new(key, i == 0, true, lamports, data, &program_id, false, Epoch::default())
+            })
+            .collect();
+        let get_data = KvInstruction::Get.try_to_vec().unwrap();
+
+        program_stubs::set_syscall_stubs(Box::new(TestSyscallStubs { unix_timestamp: 999 }));
+        process_instruction(&program_id, &accounts[1..], &get_data).unwrap();
+
+        program_stubs::set_syscall_stubs(Box::new(TestSyscallStubs { unix_timestamp: 1_000 }));
+        let result = process_instruction(&program_id, &accounts[1..], &get_data);
+        assert_eq!(result, Err(KvError::Expired.into()));
+
+        // A stale version loses the race
+        let stale = KvInstruction::Delete { expected_version: Some(2) }.try_to_vec().unwrap();
+        let result = process_instruction(&program_id, &accounts, &stale);
+        assert_eq!(result, Err(KvError::VersionMismatch.into()));
+
+        let current = KvInstruction::Delete { expected_version: Some(3) }.try_to_vec().unwrap();
+        process_instruction(&program_id, &accounts, &current).unwrap();
+        assert_eq!(accounts[0].lamports(), 5_000);
+        assert_eq!(accounts[1].lamports(), 0);
+    }
+}
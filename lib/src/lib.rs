@@ -0,0 +1,132 @@
+//! The workspace's programs re-exported under one crate.
+//!
+//! Each program crate only compiles its entrypoint for the SBF target, so on
+//! the host they link together and off-chain code can use their instruction
+//! and account types directly instead of mirroring them. They are depended
+//! on with `no-entrypoint`, so an on-chain program can use this crate too.
+
+pub use counter;
+pub use crowdfund;
+pub use hello_world;
+pub use hello_world_zero_copy;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use borsh::BorshSerialize;
+    use serde_json::Value;
+    use solana_program::pubkey::Pubkey;
+
+    fn idl(name: &str) -> Value {
+        let path = format!("{}/../idl/{}.json", env!("CARGO_MANIFEST_DIR"), name);
+        serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+    }
+
+    /// (name, discriminant, account count) of every instruction in an IDL
+    fn instructions(idl: &Value) -> Vec<(String, u8, usize)> {
+        idl["instructions"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|ix| {
+                let name = ix["name"].as_str().unwrap().to_string();
+                let discriminant = ix["discriminant"]["value"].as_u64().unwrap() as u8;
+                (name, discriminant, ix["accounts"].as_array().unwrap().len())
+            })
+            .collect()
+    }
+
+    /// The checked-in IDLs are regenerated with `shank idl`; catch a stale one
+    /// by comparing each discriminant with the Borsh tag the program decodes
+    #[test]
+    fn test_idl_discriminants_match_programs() {
+        use counter::CounterInstruction;
+        use crowdfund::CrowdfundInstruction;
+        use hello_world::HelloWorldInstruction;
+
+        let tag = |data: Vec<u8>| data[0];
+        let counter = [
+            ("Initialize", tag(CounterInstruction::Initialize.try_to_vec().unwrap())),
+            ("Increment", tag(CounterInstruction::Increment.try_to_vec().unwrap())),
+            ("Decrement", tag(CounterInstruction::Decrement.try_to_vec().unwrap())),
+            ("Migrate", tag(CounterInstruction::Migrate.try_to_vec().unwrap())),
+        ];
+        let crowdfund = [
+            ("Contribute", tag(CrowdfundInstruction::Contribute { amount: 1 }.try_to_vec().unwrap())),
+            ("BatchRefund", tag(CrowdfundInstruction::BatchRefund.try_to_vec().unwrap())),
+            ("SetAllowlist", tag(CrowdfundInstruction::SetAllowlist { allowlist: None }.try_to_vec().unwrap())),
+            ("Migrate", tag(CrowdfundInstruction::Migrate.try_to_vec().unwrap())),
+        ];
+        let hello_world = [
+            ("Reap", tag(HelloWorldInstruction::Reap.try_to_vec().unwrap())),
+            (
+                "AddWriter",
+                tag(HelloWorldInstruction::AddWriter { writer_key: Pubkey::default() }.try_to_vec().unwrap()),
+            ),
+            ("UnflagMessage", tag(HelloWorldInstruction::UnflagMessage.try_to_vec().unwrap())),
+            ("Migrate", tag(HelloWorldInstruction::Migrate.try_to_vec().unwrap())),
+        ];
+
+        let programs = [("counter", &counter[..]), ("crowdfund", &crowdfund[..]), ("hello_world", &hello_world[..])];
+        for (program, expected) in programs {
+            let instructions = instructions(&idl(program));
+            for (name, discriminant) in expected {
+                let found = instructions.iter().find(|ix| ix.0 == *name).unwrap();
+                assert_eq!(found.1, *discriminant, "{} {}", program, name);
+            }
+        }
+    }
+
+    #[test]
+    fn test_idl_accounts_match_state() {
+        let crowdfund = idl("crowdfund");
+        let crowdfund_instructions = instructions(&crowdfund);
+        assert_eq!(crowdfund_instructions.len(), 7);
+        // Contribute lists the optional allowlist membership PDA last
+        assert_eq!(crowdfund_instructions[1], ("Contribute".to_string(), 1, 5));
+        assert_eq!(crowdfund["instructions"][1]["accounts"][4]["isOptional"], true);
+
+        let fields = crowdfund["accounts"][0]["type"]["fields"].as_array().unwrap();
+        assert_eq!(crowdfund["accounts"][0]["name"], "CrowdfundAccount");
+        assert_eq!(fields.len(), 9);
+        assert_eq!(fields[0]["name"], "discriminator");
+        assert_eq!(fields[1]["name"], "version");
+        assert_eq!(fields[8]["type"]["option"], "publicKey");
+
+        let hello_world = idl("hello_world");
+        assert_eq!(instructions(&hello_world).len(), 15);
+        assert_eq!(hello_world["accounts"][0]["type"]["fields"].as_array().unwrap().len(), 19);
+    }
+
+    /// With the `serde` feature, state and instructions round-trip through JSON
+    #[test]
+    fn test_serde_round_trip() {
+        use counter::CounterAccount;
+        use crowdfund::CrowdfundInstruction;
+        use hello_world::{HelloWorldInstruction, TokenGate};
+
+        let counter = CounterAccount {
+            discriminator: *b"counter\0",
+            version: 2,
+            is_initialized: true,
+            count: 7,
+            owner: Pubkey::new_unique(),
+        };
+        let json = serde_json::to_value(&counter).unwrap();
+        assert_eq!(json["count"], 7);
+        let decoded: CounterAccount = serde_json::from_value(json).unwrap();
+        assert_eq!(decoded.try_to_vec().unwrap(), counter.try_to_vec().unwrap());
+
+        let contribute = CrowdfundInstruction::Contribute { amount: 600 };
+        let json = serde_json::to_string(&contribute).unwrap();
+        assert_eq!(json, r#"{"Contribute":{"amount":600}}"#);
+        let decoded: CrowdfundInstruction = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.try_to_vec().unwrap(), contribute.try_to_vec().unwrap());
+
+        let gate = HelloWorldInstruction::SetTokenGate {
+            token_gate: Some(TokenGate { mint: Pubkey::new_unique(), min_balance: 5 }),
+        };
+        let decoded: HelloWorldInstruction = serde_json::from_str(&serde_json::to_string(&gate).unwrap()).unwrap();
+        assert_eq!(decoded.try_to_vec().unwrap(), gate.try_to_vec().unwrap());
+    }
+}
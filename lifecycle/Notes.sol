@@ -0,0 +1,75 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+/**
+ * @title Notes
+ * @dev Storage lifecycle counterpart to the Solana account lifecycle example.
+ * Ethereum has no rent: storage is paid for in gas when written, and part of
+ * that gas is refunded when a slot is cleared.
+ */
+contract Notes {
+    struct Note {
+        address owner;
+        string content;
+    }
+
+    mapping(address => Note) public notes;
+
+    event NoteCreated(address indexed owner, uint256 length);
+    event NoteResized(address indexed owner, uint256 oldLength, uint256 newLength);
+    event NoteReassigned(address indexed from, address indexed to);
+    event NoteClosed(address indexed owner);
+
+    /**
+     * @dev Create the caller's note. Each new 32-byte slot costs 20,000 gas.
+     */
+    function create(string calldata content) external {
+        require(notes[msg.sender].owner == address(0), "Note already exists");
+
+        notes[msg.sender] = Note(msg.sender, content);
+
+        emit NoteCreated(msg.sender, bytes(content).length);
+    }
+
+    /**
+     * @dev Replace the content. Storage grows or shrinks with it; slots freed
+     * by a shorter string are cleared and partially refunded.
+     */
+    function resize(string calldata content) external {
+        Note storage note = notes[msg.sender];
+        require(note.owner == msg.sender, "Only the note owner");
+
+        uint256 oldLength = bytes(note.content).length;
+        note.content = content;
+
+        emit NoteResized(msg.sender, oldLength, bytes(content).length);
+    }
+
+    /**
+     * @dev Hand the note to `to`. Ethereum changes a field; Solana changes
+     * which program owns the account.
+     */
+    function reassign(address to) external {
+        require(to != address(0), "Invalid address");
+        require(notes[to].owner == address(0), "Recipient already has a note");
+        Note storage note = notes[msg.sender];
+        require(note.owner == msg.sender, "Only the note owner");
+
+        notes[to] = Note(to, note.content);
+        delete notes[msg.sender];
+
+        emit NoteReassigned(msg.sender, to);
+    }
+
+    /**
+     * @dev Delete the note, refunding part of the storage gas. Nothing is paid
+     * back in ETH, unlike the rent returned when a Solana account is closed.
+     */
+    function close() external {
+        require(notes[msg.sender].owner == msg.sender, "Only the note owner");
+
+        delete notes[msg.sender];
+
+        emit NoteClosed(msg.sender);
+    }
+}
@@ -0,0 +1,342 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint,
+    entrypoint::{ProgramResult, MAX_PERMITTED_DATA_INCREASE},
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction, system_program,
+    sysvar::Sysvar,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// Seed prefix for note PDAs: ["note", owner]
+pub const NOTE_SEED: &[u8] = b"note";
+
+/// Size of a `NoteAccount` without its content
+pub const NOTE_HEADER_SIZE: usize = 1 // is_initialized
+    + 32 // owner
+    + 1 // bump
+    + 4; // content length prefix
+
+/// Errors returned by the program as `ProgramError::Custom` codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LifecycleError {
+    /// Only the note owner can change it
+    Unauthorized = 0,
+    /// The note has not been initialized
+    NotInitialized = 1,
+    /// The account would grow by more than `MAX_PERMITTED_DATA_INCREASE` in one instruction
+    GrowthTooLarge = 2,
+}
+
+impl From<LifecycleError> for ProgramError {
+    fn from(e: LifecycleError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+/// A note sized exactly to its content, so the account grows and shrinks with it
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct NoteAccount {
+    pub is_initialized: bool,
+    pub owner: Pubkey,
+    pub bump: u8,
+    pub content: String,
+}
+
+/// Account size for a note holding `content_len` bytes
+pub fn note_account_size(content_len: usize) -> usize {
+    NOTE_HEADER_SIZE + content_len
+}
+
+/// Lamports to move so an account holding `current_lamports` is exactly
+/// rent-exempt at `new_len` bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RentAdjustment {
+    /// The payer must top the account up
+    Fund(u64),
+    /// The account holds more than it needs; return the excess
+    Refund(u64),
+    None,
+}
+
+pub fn rent_adjustment(rent: &Rent, current_lamports: u64, new_len: usize) -> RentAdjustment {
+    let required = rent.minimum_balance(new_len);
+    if required > current_lamports {
+        RentAdjustment::Fund(required - current_lamports)
+    } else if required < current_lamports {
+        RentAdjustment::Refund(current_lamports - required)
+    } else {
+        RentAdjustment::None
+    }
+}
+
+entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = LifecycleInstruction::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        LifecycleInstruction::Create { content } => create(program_id, accounts, content),
+        LifecycleInstruction::Resize { content } => resize(program_id, accounts, content),
+        LifecycleInstruction::Reassign { new_owner } => reassign(program_id, accounts, new_owner),
+        LifecycleInstruction::Close => close(program_id, accounts),
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub enum LifecycleInstruction {
+    /// Create a note sized for `content`, funded to be rent-exempt
+    /// Accounts: [writable, signer] owner, [writable] note PDA, [] system program
+    Create { content: String },
+    /// Replace the content, growing or shrinking the account to fit and
+    /// settling the rent difference with the owner
+    /// Accounts: [writable, signer] owner, [writable] note PDA, [] system program
+    Resize { content: String },
+    /// Wipe the note and hand the account, and its rent, to another program
+    /// Accounts: [signer] owner, [writable] note PDA
+    Reassign { new_owner: Pubkey },
+    /// Refund every lamport to the owner and return the account to the system program
+    /// Accounts: [writable, signer] owner, [writable] note PDA
+    Close,
+}
+
+fn create(program_id: &Pubkey, accounts: &[AccountInfo], content: String) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let owner = next_account_info(accounts_iter)?;
+    let note_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // Accounts created through CPI are subject to the same per-instruction limit as growth
+    let space = note_account_size(content.len());
+    if space > MAX_PERMITTED_DATA_INCREASE {
+        return Err(LifecycleError::GrowthTooLarge.into());
+    }
+
+    let (expected_note, bump) = Pubkey::find_program_address(&[NOTE_SEED, owner.key.as_ref()], program_id);
+    if expected_note != *note_account.key {
+        msg!("Note account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    // 1. Create: allocate the exact size and deposit the rent-exempt minimum for it
+    let lamports = Rent::get()?.minimum_balance(space);
+    invoke_signed(
+        &system_instruction::create_account(owner.key, note_account.key, lamports, space as u64, program_id),
+        &[owner.clone(), note_account.clone(), system_program.clone()],
+        &[&[NOTE_SEED, owner.key.as_ref(), &[bump]]],
+    )?;
+
+    let note = NoteAccount {
+        is_initialized: true,
+        owner: *owner.key,
+        bump,
+        content,
+    };
+    note.serialize(&mut &mut note_account.data.borrow_mut()[..])?;
+
+    msg!("Note created: {} bytes, {} lamports of rent", space, lamports);
+
+    Ok(())
+}
+
+fn resize(program_id: &Pubkey, accounts: &[AccountInfo], content: String) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let owner = next_account_info(accounts_iter)?;
+    let note_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut note = load_note(program_id, note_account, owner)?;
+
+    let old_len = note_account.data_len();
+    let new_len = note_account_size(content.len());
+    if new_len.saturating_sub(old_len) > MAX_PERMITTED_DATA_INCREASE {
+        msg!("Cannot grow by {} bytes in one instruction", new_len - old_len);
+        return Err(LifecycleError::GrowthTooLarge.into());
+    }
+
+    // 2. Realloc: settle rent first when growing, so the account is never
+    // left below the rent-exempt minimum for its size
+    match rent_adjustment(&Rent::get()?, note_account.lamports(), new_len) {
+        RentAdjustment::Fund(lamports) => {
+            // The system program can credit any account, including one owned by this program
+            invoke(
+                &system_instruction::transfer(owner.key, note_account.key, lamports),
+                &[owner.clone(), note_account.clone(), system_program.clone()],
+            )?;
+            note_account.realloc(new_len, false)?;
+            msg!("Grew note from {} to {} bytes, charged {} lamports", old_len, new_len, lamports);
+        }
+        RentAdjustment::Refund(lamports) => {
+            note_account.realloc(new_len, false)?;
+            // This program owns the note, so it can debit it directly
+            **note_account.try_borrow_mut_lamports()? -= lamports;
+            **owner.try_borrow_mut_lamports()? += lamports;
+            msg!("Shrank note from {} to {} bytes, refunded {} lamports", old_len, new_len, lamports);
+        }
+        RentAdjustment::None => note_account.realloc(new_len, false)?,
+    }
+
+    // Skipping zero_init is safe because the whole account is rewritten here
+    note.content = content;
+    note.serialize(&mut &mut note_account.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+fn reassign(program_id: &Pubkey, accounts: &[AccountInfo], new_owner: Pubkey) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let owner = next_account_info(accounts_iter)?;
+    let note_account = next_account_info(accounts_iter)?;
+
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    load_note(program_id, note_account, owner)?;
+
+    // 3. Reassign: the runtime only lets the current owner change an account's
+    // owner, and only once its data is zeroed. The size and the rent reserve
+    // go with it; the new owner program decides what happens to them.
+    note_account.data.borrow_mut().fill(0);
+    note_account.assign(&new_owner);
+
+    msg!("Note account reassigned to {}", new_owner);
+
+    Ok(())
+}
+
+fn close(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let owner = next_account_info(accounts_iter)?;
+    let note_account = next_account_info(accounts_iter)?;
+
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    load_note(program_id, note_account, owner)?;
+
+    // 4. Close: refund all lamports, then also shrink the account to nothing
+    // and give it back to the system program. With zero lamports the runtime
+    // deletes it after the transaction; if a later instruction in the same
+    // transaction refunds it, it comes back as an empty system account rather
+    // than a note with stale data.
+    let lamports = note_account.lamports();
+    **note_account.try_borrow_mut_lamports()? = 0;
+    **owner.try_borrow_mut_lamports()? += lamports;
+
+    note_account.realloc(0, false)?;
+    note_account.assign(&system_program::id());
+
+    msg!("Note closed; refunded {} lamports", lamports);
+
+    Ok(())
+}
+
+/// Validate the note account and that `owner` owns it, returning the note
+fn load_note(program_id: &Pubkey, note_account: &AccountInfo, owner: &AccountInfo) -> Result<NoteAccount, ProgramError> {
+    if note_account.owner != program_id {
+        msg!("Note account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let note = NoteAccount::try_from_slice(&note_account.data.borrow())?;
+
+    if !note.is_initialized {
+        return Err(LifecycleError::NotInitialized.into());
+    }
+
+    if note.owner != *owner.key {
+        msg!("Only the note owner can change it");
+        return Err(LifecycleError::Unauthorized.into());
+    }
+
+    Ok(note)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_program::clock::Epoch;
+
+    #[test]
+    fn test_rent_adjustment_tracks_size() {
+        let rent = Rent::default();
+        let small = note_account_size(10);
+        let large = note_account_size(500);
+        let funded = rent.minimum_balance(small);
+
+        // Every byte costs rent, so growing charges and shrinking refunds the same amount
+        let growth = rent.minimum_balance(large) - funded;
+        assert_eq!(rent_adjustment(&rent, funded, large), RentAdjustment::Fund(growth));
+        assert_eq!(rent_adjustment(&rent, funded + growth, small), RentAdjustment::Refund(growth));
+        assert_eq!(rent_adjustment(&rent, funded, small), RentAdjustment::None);
+
+        // Lamports someone sent to the account on top of rent are refunded too
+        assert_eq!(rent_adjustment(&rent, funded + 7, small), RentAdjustment::Refund(7));
+    }
+
+    #[test]
+    fn test_close_requires_note_owner() {
+        let program_id = Pubkey::new_unique();
+        let owner_key = Pubkey::new_unique();
+        let note_key = Pubkey::new_unique();
+
+        let note = NoteAccount {
+            is_initialized: true,
+            owner: Pubkey::new_unique(),
+            bump: 255,
+            content: "hello".to_string(),
+        };
+        let mut note_data = note.try_to_vec().unwrap();
+        assert_eq!(note_data.len(), note_account_size(5));
+        let mut owner_lamports = 0;
+        let mut note_lamports = 1_000;
+        let mut owner_data = vec![];
+
+        let accounts = vec![
+            AccountInfo::new(
+                &owner_key,
+                true,
+                true,
+                &mut owner_lamports,
+                &mut owner_data,
+                &program_id,
+                false,
+                Epoch::default(),
+            ),
+            AccountInfo::new(
+                &note_key,
+                false,
+                true,
+                &mut note_lamports,
+                &mut note_data,
+                &program_id,
+                false,
+                Epoch::default(),
+            ),
+        ];
+
+        let instruction_data = LifecycleInstruction::Close.try_to_vec().unwrap();
+        let result = process_instruction(&program_id, &accounts, &instruction_data);
+        assert_eq!(result, Err(LifecycleError::Unauthorized.into()));
+        assert_eq!(**accounts[1].lamports.borrow(), 1_000);
+    }
+}
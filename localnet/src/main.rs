@@ -0,0 +1,329 @@
+//! `localnet`: a populated cluster for demos and manual testing.
+//!
+//! Without `--url`, starts `solana-test-validator` with every program from
+//! `--deploy-dir` loaded at genesis and the signer as the mint, and keeps it
+//! running until Ctrl-C. With `--url`, deploys the programs to that cluster
+//! with `solana program deploy` instead.
+//!
+//! Either way it then creates one example of each program's accounts. Program
+//! ids and example accounts come from fixed seeds, so every run, and every
+//! command copied from a previous run, uses the same addresses; accounts that
+//! already exist are left alone.
+
+use std::{
+    error::Error,
+    path::{Path, PathBuf},
+    process::{Child, Command, Stdio},
+    thread::sleep,
+    time::{Duration, Instant},
+};
+
+use clap::Parser;
+use client::{counter, crowdfund, hello_world, hello_world_zero_copy};
+use smart_contract_comparison::{
+    counter::CounterAccount, crowdfund::CrowdfundAccount, hello_world::MESSAGE_ACCOUNT_SIZE,
+    hello_world_zero_copy::ZeroCopyMessageAccount,
+};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    hash::hash,
+    instruction::Instruction,
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::{keypair_from_seed, read_keypair_file, write_keypair_file, Keypair, Signer},
+    system_instruction,
+    transaction::Transaction,
+};
+
+type Result<T> = std::result::Result<T, Box<dyn Error>>;
+
+/// Programs to load, by the name of their `.so` in the deploy directory
+const PROGRAMS: [&str; 4] = ["counter", "crowdfund", "hello_world", "hello_world_zero_copy"];
+
+const LOCAL_URL: &str = "http://127.0.0.1:8899";
+
+#[derive(Parser, Debug)]
+#[command(version, about = "Start or connect to a validator, deploy the programs and create example accounts")]
+struct Args {
+    /// Use this cluster instead of starting solana-test-validator
+    #[arg(long, short)]
+    url: Option<String>,
+
+    /// Payer and owner of the example accounts [default: ~/.config/solana/id.json]
+    #[arg(long, short)]
+    keypair: Option<PathBuf>,
+
+    /// Where `cargo build-sbf` put the programs
+    #[arg(long, default_value = "target/deploy")]
+    deploy_dir: PathBuf,
+
+    /// Ledger of the started validator, reset on every run
+    #[arg(long, default_value = "target/localnet/ledger")]
+    ledger: PathBuf,
+}
+
+/// The same keypair for `label` on every run
+fn known_keypair(label: &str) -> Keypair {
+    let seed = hash(format!("smart-contract-comparison localnet {}", label).as_bytes());
+    keypair_from_seed(seed.as_ref()).expect("a hash is a valid 32-byte seed")
+}
+
+fn program_keypair(name: &str) -> Keypair {
+    known_keypair(&format!("program {}", name))
+}
+
+fn program_path(deploy_dir: &Path, name: &str) -> PathBuf {
+    deploy_dir.join(format!("{}.so", name))
+}
+
+struct Localnet {
+    rpc: RpcClient,
+    payer: Keypair,
+}
+
+impl Localnet {
+    fn send(&self, instructions: &[Instruction], signers: &[&Keypair]) -> Result<()> {
+        let mut keypairs = vec![&self.payer];
+        keypairs.extend_from_slice(signers);
+        let blockhash = self.rpc.get_latest_blockhash()?;
+        let transaction =
+            Transaction::new_signed_with_payer(instructions, Some(&self.payer.pubkey()), &keypairs, blockhash);
+        self.rpc.send_and_confirm_transaction(&transaction)?;
+        Ok(())
+    }
+
+    fn exists(&self, account: &Pubkey) -> bool {
+        self.rpc.get_account(account).is_ok()
+    }
+
+    /// Send `instructions` after creating `account` with `space` bytes owned by
+    /// `program_id`, unless it already exists. Returns whether it was created.
+    fn create(
+        &self,
+        account: &Keypair,
+        space: usize,
+        program_id: &Pubkey,
+        instructions: &[Instruction],
+    ) -> Result<bool> {
+        if self.exists(&account.pubkey()) {
+            return Ok(false);
+        }
+        let lamports = self.rpc.get_minimum_balance_for_rent_exemption(space)?;
+        let mut all = vec![system_instruction::create_account(
+            &self.payer.pubkey(),
+            &account.pubkey(),
+            lamports,
+            space as u64,
+            program_id,
+        )];
+        all.extend_from_slice(instructions);
+        self.send(&all, &[account])?;
+        Ok(true)
+    }
+}
+
+fn start_validator(args: &Args, mint: &Pubkey) -> Result<Child> {
+    let mut command = Command::new("solana-test-validator");
+    command.arg("--reset").arg("--quiet").arg("--ledger").arg(&args.ledger).arg("--mint").arg(mint.to_string());
+    for name in PROGRAMS {
+        command
+            .arg("--bpf-program")
+            .arg(program_keypair(name).pubkey().to_string())
+            .arg(program_path(&args.deploy_dir, name));
+    }
+    let child = command
+        .stdout(Stdio::null())
+        .spawn()
+        .map_err(|error| format!("could not start solana-test-validator: {}", error))?;
+    Ok(child)
+}
+
+fn wait_for_health(rpc: &RpcClient) -> Result<()> {
+    let started = Instant::now();
+    while rpc.get_health().is_err() {
+        if started.elapsed() > Duration::from_secs(60) {
+            return Err("validator did not become healthy within 60s".into());
+        }
+        sleep(Duration::from_millis(500));
+    }
+    Ok(())
+}
+
+/// Deploy every program that isn't on the cluster yet with the Solana CLI
+fn deploy(rpc: &RpcClient, url: &str, keypair_path: &Path, deploy_dir: &Path) -> Result<()> {
+    for name in PROGRAMS {
+        let program = program_keypair(name);
+        if rpc.get_account(&program.pubkey()).is_ok() {
+            continue;
+        }
+        let program_keypair_path = std::env::temp_dir().join(format!("localnet-{}-keypair.json", name));
+        write_keypair_file(&program, &program_keypair_path)?;
+
+        println!("Deploying {}...", name);
+        let status = Command::new("solana")
+            .arg("program")
+            .arg("deploy")
+            .arg(program_path(deploy_dir, name))
+            .arg("--program-id")
+            .arg(&program_keypair_path)
+            .arg("--url")
+            .arg(url)
+            .arg("--keypair")
+            .arg(keypair_path)
+            .status()
+            .map_err(|error| format!("could not run solana: {}", error))?;
+        if !status.success() {
+            return Err(format!("deploying {} failed", name).into());
+        }
+    }
+    Ok(())
+}
+
+/// Create the example accounts and print every address
+fn populate(localnet: &Localnet) -> Result<()> {
+    let owner = localnet.payer.pubkey();
+    let [counter_id, crowdfund_id, hello_world_id, zero_copy_id] = PROGRAMS.map(|name| program_keypair(name).pubkey());
+
+    // A counter at 1
+    let counter = known_keypair("counter");
+    localnet.create(
+        &counter,
+        CounterAccount::LEN,
+        &counter_id,
+        &[
+            counter::initialize_counter(&counter_id, &counter.pubkey(), &owner),
+            counter::increment(&counter_id, &counter.pubkey(), &owner),
+        ],
+    )?;
+
+    // An active campaign with a goal of 10 SOL, 1 SOL raised, ending in about a day
+    let campaign = known_keypair("campaign");
+    let created = localnet.create(
+        &campaign,
+        CrowdfundAccount::LEN,
+        &crowdfund_id,
+        &[crowdfund::initialize_campaign(&crowdfund_id, &campaign.pubkey(), &owner, 10 * LAMPORTS_PER_SOL, 216_000)],
+    )?;
+    if created {
+        let contribution = crowdfund::contribute(&crowdfund_id, &campaign.pubkey(), &owner, LAMPORTS_PER_SOL, None);
+        localnet.send(&[contribution], &[])?;
+    }
+
+    // A message with one reply
+    let message = known_keypair("message");
+    let options = hello_world::SetMessageOptions::default();
+    let created = localnet.create(
+        &message,
+        MESSAGE_ACCOUNT_SIZE,
+        &hello_world_id,
+        &[hello_world::set_message(&hello_world_id, &message.pubkey(), &owner, "Hello, Solana!", &options)],
+    )?;
+    if created {
+        localnet.send(&[hello_world::reply(&hello_world_id, &message.pubkey(), 0, &owner, "gm")], &[])?;
+    }
+
+    // The same message in the zero-copy layout
+    let zero_copy_message = known_keypair("zero-copy message");
+    localnet.create(
+        &zero_copy_message,
+        ZeroCopyMessageAccount::LEN,
+        &zero_copy_id,
+        &[hello_world_zero_copy::set_message(&zero_copy_id, &zero_copy_message.pubkey(), &owner, "Hello, Solana!")],
+    )?;
+
+    println!("Programs");
+    for name in PROGRAMS {
+        println!("  {:<24} {}", name, program_keypair(name).pubkey());
+    }
+    println!("Accounts (owner {})", owner);
+    println!("  {:<24} {}", "counter", counter.pubkey());
+    println!("  {:<24} {}", "crowdfund campaign", campaign.pubkey());
+    println!("  {:<24} {}", "hello-world message", message.pubkey());
+    println!("  {:<24} {}", "hello-world reply", hello_world::reply_address(&hello_world_id, &message.pubkey(), 0));
+    println!("  {:<24} {}", "zero-copy message", zero_copy_message.pubkey());
+    Ok(())
+}
+
+fn run(args: Args) -> Result<()> {
+    let keypair_path = args.keypair.clone().unwrap_or_else(|| {
+        let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+        PathBuf::from(home).join(".config/solana/id.json")
+    });
+    let payer = read_keypair_file(&keypair_path)
+        .map_err(|error| format!("could not read keypair {}: {}", keypair_path.display(), error))?;
+
+    for name in PROGRAMS {
+        let path = program_path(&args.deploy_dir, name);
+        if !path.exists() {
+            return Err(format!("{} not found; run `cargo build-sbf` first", path.display()).into());
+        }
+    }
+
+    let (url, validator) = match &args.url {
+        Some(url) => (url.clone(), None),
+        None => (LOCAL_URL.to_string(), Some(start_validator(&args, &payer.pubkey())?)),
+    };
+    let rpc = RpcClient::new_with_commitment(url.clone(), CommitmentConfig::confirmed());
+
+    let localnet = Localnet { rpc, payer };
+    let setup = match validator {
+        Some(_) => wait_for_health(&localnet.rpc),
+        None => deploy(&localnet.rpc, &url, &keypair_path, &args.deploy_dir),
+    }
+    .and_then(|()| populate(&localnet));
+
+    match validator {
+        // Don't leave a half-populated validator behind
+        Some(mut validator) if setup.is_err() => {
+            validator.kill().ok();
+            setup
+        }
+        Some(mut validator) => {
+            println!("\nValidator running at {}; press Ctrl-C to stop", url);
+            validator.wait()?;
+            Ok(())
+        }
+        None => setup,
+    }
+}
+
+fn main() {
+    if let Err(error) = run(Args::parse()) {
+        eprintln!("Error: {}", error);
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_known_keypairs_are_stable_and_distinct() {
+        assert_eq!(known_keypair("counter").pubkey(), known_keypair("counter").pubkey());
+
+        let mut labels: Vec<String> = PROGRAMS.iter().map(|name| format!("program {}", name)).collect();
+        labels.extend(["counter", "campaign", "message", "zero-copy message"].map(String::from));
+        let keys: HashSet<Pubkey> = labels.iter().map(|label| known_keypair(label).pubkey()).collect();
+        assert_eq!(keys.len(), labels.len());
+    }
+
+    #[test]
+    fn test_program_paths() {
+        let path = program_path(Path::new("target/deploy"), "hello_world_zero_copy");
+        assert_eq!(path, PathBuf::from("target/deploy/hello_world_zero_copy.so"));
+        assert_eq!(program_keypair("counter").pubkey(), known_keypair("program counter").pubkey());
+    }
+
+    /// Each program's default `declare_id!` is its localnet keypair's address,
+    /// so the programs derive PDAs under the id they are deployed at
+    #[test]
+    fn test_program_keypairs_match_declared_ids() {
+        use smart_contract_comparison::{counter, crowdfund, hello_world, hello_world_zero_copy};
+
+        let declared = [counter::ID, crowdfund::ID, hello_world::ID, hello_world_zero_copy::ID];
+        assert_eq!(PROGRAMS.map(|name| program_keypair(name).pubkey()), declared);
+    }
+}
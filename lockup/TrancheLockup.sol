@@ -0,0 +1,111 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+interface IERC20 {
+    function transfer(address to, uint256 amount) external returns (bool);
+    function transferFrom(address from, address to, uint256 amount) external returns (bool);
+}
+
+/**
+ * @title TrancheLockup
+ * @dev Token lockups that release in discrete tranches at fixed timestamps.
+ * Positions can be handed to a new holder, and the admin can send tranches
+ * that are still locked to a treasury if the lockup is revocable.
+ */
+contract TrancheLockup {
+    uint256 public constant MAX_TRANCHES = 12;
+
+    struct Tranche {
+        uint64 unlockAt;
+        uint256 amount;
+    }
+
+    struct Lockup {
+        address admin;
+        address holder;
+        address treasury;
+        IERC20 token;
+        uint256 claimedAmount;
+        bool revocable;
+    }
+
+    uint256 public nextLockupId;
+    mapping(uint256 => Lockup) public lockups;
+    mapping(uint256 => Tranche[]) private tranches;
+
+    event LockupCreated(uint256 indexed lockupId, address indexed holder, uint256 total);
+    event Claimed(uint256 indexed lockupId, address indexed holder, uint256 amount);
+    event PositionTransferred(uint256 indexed lockupId, address indexed from, address indexed to);
+    event Revoked(uint256 indexed lockupId, uint256 amount);
+
+    function createLockup(
+        address holder,
+        IERC20 token,
+        address treasury,
+        Tranche[] calldata schedule,
+        bool revocable
+    ) external returns (uint256 lockupId) {
+        require(schedule.length > 0 && schedule.length <= MAX_TRANCHES, "Invalid tranches");
+
+        lockupId = nextLockupId++;
+        uint256 total;
+        for (uint256 i = 0; i < schedule.length; i++) {
+            require(schedule[i].amount > 0, "Zero tranche");
+            require(i == 0 || schedule[i].unlockAt > schedule[i - 1].unlockAt, "Unordered tranches");
+            total += schedule[i].amount;
+            tranches[lockupId].push(schedule[i]);
+        }
+
+        lockups[lockupId] = Lockup(msg.sender, holder, treasury, token, 0, revocable);
+        require(token.transferFrom(msg.sender, address(this), total), "Transfer failed");
+        emit LockupCreated(lockupId, holder, total);
+    }
+
+    /// @dev Tranches unlock all at once; nothing accrues between them
+    function unlockedAmount(uint256 lockupId) public view returns (uint256 unlocked) {
+        Tranche[] storage list = tranches[lockupId];
+        for (uint256 i = 0; i < list.length && list[i].unlockAt <= block.timestamp; i++) {
+            unlocked += list[i].amount;
+        }
+    }
+
+    function claim(uint256 lockupId) external {
+        Lockup storage lockup = lockups[lockupId];
+        require(msg.sender == lockup.holder, "Only holder");
+
+        uint256 amount = unlockedAmount(lockupId) - lockup.claimedAmount;
+        require(amount > 0, "Nothing to claim");
+
+        lockup.claimedAmount += amount;
+        require(lockup.token.transfer(msg.sender, amount), "Transfer failed");
+        emit Claimed(lockupId, msg.sender, amount);
+    }
+
+    function transferPosition(uint256 lockupId, address newHolder) external {
+        Lockup storage lockup = lockups[lockupId];
+        require(msg.sender == lockup.holder, "Only holder");
+        lockup.holder = newHolder;
+        emit PositionTransferred(lockupId, msg.sender, newHolder);
+    }
+
+    function revoke(uint256 lockupId) external {
+        Lockup storage lockup = lockups[lockupId];
+        require(msg.sender == lockup.admin, "Only admin");
+        require(lockup.revocable, "Not revocable");
+
+        Tranche[] storage list = tranches[lockupId];
+        uint256 locked;
+        while (list.length > 0 && list[list.length - 1].unlockAt > block.timestamp) {
+            locked += list[list.length - 1].amount;
+            list.pop();
+        }
+        require(locked > 0, "Nothing to revoke");
+
+        require(lockup.token.transfer(lockup.treasury, locked), "Transfer failed");
+        emit Revoked(lockupId, locked);
+    }
+
+    function getTranches(uint256 lockupId) external view returns (Tranche[] memory) {
+        return tranches[lockupId];
+    }
+}
@@ -0,0 +1,531 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint,
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use spl_token::state::Account as TokenAccount;
+
+/// Seed prefix for lockup PDAs: ["lockup", admin, lockup_id]
+pub const LOCKUP_SEED: &[u8] = b"lockup";
+
+/// Seed prefix for vault token account PDAs: ["vault", lockup]
+pub const VAULT_SEED: &[u8] = b"vault";
+
+/// Most tranches a lockup can hold
+pub const MAX_TRANCHES: usize = 12;
+
+/// Size of a `LockupAccount` with `MAX_TRANCHES` tranches
+pub const LOCKUP_ACCOUNT_SIZE: usize = 1 // is_initialized
+    + 32 // admin
+    + 8 // lockup_id
+    + 32 // mint
+    + 32 // treasury
+    + 32 // holder
+    + 4 + MAX_TRANCHES * (8 + 8) // tranches
+    + 8 // claimed_amount
+    + 1 // revocable
+    + 1 // bump
+    + 1; // vault_bump
+
+/// Errors returned by the program as `ProgramError::Custom` codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LockupError {
+    /// No tranches, too many, a zero amount, or unlock times not strictly increasing
+    InvalidTranches = 0,
+    /// No unlocked tokens are waiting to be claimed
+    NothingToClaim = 1,
+    /// The lockup was created without the right to revoke it
+    NotRevocable = 2,
+    /// The signer is not the admin or holder this action requires
+    Unauthorized = 3,
+    /// The lockup has not been initialized
+    NotInitialized = 4,
+    /// The destination is not the lockup's treasury
+    WrongTreasury = 5,
+    /// Every tranche has already unlocked
+    NothingToRevoke = 6,
+}
+
+impl From<LockupError> for ProgramError {
+    fn from(e: LockupError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+/// `amount` tokens that unlock all at once at `unlock_at`
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tranche {
+    pub unlock_at: i64,
+    pub amount: u64,
+}
+
+/// Tokens released in discrete tranches to whoever currently holds the position
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct LockupAccount {
+    pub is_initialized: bool,
+    /// Funded the lockup; can revoke locked tranches if `revocable`
+    pub admin: Pubkey,
+    pub lockup_id: u64,
+    pub mint: Pubkey,
+    /// Token account that receives revoked tranches
+    pub treasury: Pubkey,
+    /// Current owner of the position; changes on transfer
+    pub holder: Pubkey,
+    /// Sorted by `unlock_at`; locked tranches are dropped on revoke
+    pub tranches: Vec<Tranche>,
+    /// Tokens already sent to holders
+    pub claimed_amount: u64,
+    pub revocable: bool,
+    pub bump: u8,
+    pub vault_bump: u8,
+}
+
+/// Check a tranche list and return its total
+pub fn validate_tranches(tranches: &[Tranche]) -> Result<u64, LockupError> {
+    if tranches.is_empty() || tranches.len() > MAX_TRANCHES {
+        return Err(LockupError::InvalidTranches);
+    }
+    if tranches.iter().any(|t| t.amount == 0) || tranches.windows(2).any(|w| w[0].unlock_at >= w[1].unlock_at) {
+        return Err(LockupError::InvalidTranches);
+    }
+    tranches
+        .iter()
+        .try_fold(0u64, |total, t| total.checked_add(t.amount))
+        .ok_or(LockupError::InvalidTranches)
+}
+
+impl LockupAccount {
+    /// Sum of tranches unlocked at `now`; there is no accrual between tranches
+    pub fn unlocked_amount(&self, now: i64) -> u64 {
+        self.tranches
+            .iter()
+            .take_while(|t| t.unlock_at <= now)
+            .map(|t| t.amount)
+            .sum()
+    }
+
+    /// Drop the tranches still locked at `now` and return their total
+    pub fn revoke_locked(&mut self, now: i64) -> u64 {
+        let unlocked = self.tranches.iter().take_while(|t| t.unlock_at <= now).count();
+        self.tranches.drain(unlocked..).map(|t| t.amount).sum()
+    }
+}
+
+entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = LockupInstruction::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        LockupInstruction::CreateLockup {
+            lockup_id,
+            holder,
+            tranches,
+            revocable,
+        } => create_lockup(program_id, accounts, lockup_id, holder, tranches, revocable),
+        LockupInstruction::Claim => claim(program_id, accounts),
+        LockupInstruction::TransferPosition { new_holder } => transfer_position(program_id, accounts, new_holder),
+        LockupInstruction::Revoke => revoke(program_id, accounts),
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub enum LockupInstruction {
+    /// Create a lockup for `holder` and fund its vault with the sum of `tranches`
+    /// Accounts: [writable, signer] admin, [] mint, [] treasury token account, [writable] lockup PDA,
+    /// [writable] vault PDA, [writable] admin token account, [] token program, [] system program
+    CreateLockup {
+        lockup_id: u64,
+        holder: Pubkey,
+        tranches: Vec<Tranche>,
+        revocable: bool,
+    },
+    /// Send all unlocked but unclaimed tokens to the current holder
+    /// Accounts: [signer] holder, [writable] lockup PDA, [writable] vault PDA,
+    /// [writable] holder token account, [] token program
+    Claim,
+    /// Hand the position, including anything unclaimed, to `new_holder`
+    /// Accounts: [signer] holder, [writable] lockup PDA
+    TransferPosition { new_holder: Pubkey },
+    /// Send every still-locked tranche to the treasury; unlocked tranches stay
+    /// claimable (admin only, revocable lockups only)
+    /// Accounts: [signer] admin, [writable] lockup PDA, [writable] vault PDA,
+    /// [writable] treasury token account, [] token program
+    Revoke,
+}
+
+fn create_lockup(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    lockup_id: u64,
+    holder: Pubkey,
+    tranches: Vec<Tranche>,
+    revocable: bool,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let admin = next_account_info(accounts_iter)?;
+    let mint = next_account_info(accounts_iter)?;
+    let treasury = next_account_info(accounts_iter)?;
+    let lockup_account = next_account_info(accounts_iter)?;
+    let vault = next_account_info(accounts_iter)?;
+    let admin_token = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !admin.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let total = validate_tranches(&tranches)?;
+
+    if *token_program.key != spl_token::id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    // Revoked tokens must land in an account of the same mint
+    let treasury_state = TokenAccount::unpack(&treasury.data.borrow())?;
+    if treasury_state.mint != *mint.key {
+        msg!("Treasury holds a different mint");
+        return Err(LockupError::WrongTreasury.into());
+    }
+
+    let (expected_lockup, bump) = Pubkey::find_program_address(
+        &[LOCKUP_SEED, admin.key.as_ref(), &lockup_id.to_le_bytes()],
+        program_id,
+    );
+    if expected_lockup != *lockup_account.key {
+        msg!("Lockup account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let (expected_vault, vault_bump) =
+        Pubkey::find_program_address(&[VAULT_SEED, lockup_account.key.as_ref()], program_id);
+    if expected_vault != *vault.key {
+        msg!("Vault account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let rent = Rent::get()?;
+
+    invoke_signed(
+        &system_instruction::create_account(
+            admin.key,
+            lockup_account.key,
+            rent.minimum_balance(LOCKUP_ACCOUNT_SIZE),
+            LOCKUP_ACCOUNT_SIZE as u64,
+            program_id,
+        ),
+        &[admin.clone(), lockup_account.clone(), system_program.clone()],
+        &[&[LOCKUP_SEED, admin.key.as_ref(), &lockup_id.to_le_bytes(), &[bump]]],
+    )?;
+
+    invoke_signed(
+        &system_instruction::create_account(
+            admin.key,
+            vault.key,
+            rent.minimum_balance(TokenAccount::LEN),
+            TokenAccount::LEN as u64,
+            &spl_token::id(),
+        ),
+        &[admin.clone(), vault.clone(), system_program.clone()],
+        &[&[VAULT_SEED, lockup_account.key.as_ref(), &[vault_bump]]],
+    )?;
+    invoke(
+        &spl_token::instruction::initialize_account3(&spl_token::id(), vault.key, mint.key, lockup_account.key)?,
+        &[vault.clone(), mint.clone()],
+    )?;
+
+    invoke(
+        &spl_token::instruction::transfer(&spl_token::id(), admin_token.key, vault.key, admin.key, &[], total)?,
+        &[admin_token.clone(), vault.clone(), admin.clone()],
+    )?;
+
+    let lockup = LockupAccount {
+        is_initialized: true,
+        admin: *admin.key,
+        lockup_id,
+        mint: *mint.key,
+        treasury: *treasury.key,
+        holder,
+        tranches,
+        claimed_amount: 0,
+        revocable,
+        bump,
+        vault_bump,
+    };
+    lockup.serialize(&mut &mut lockup_account.data.borrow_mut()[..])?;
+
+    msg!("Locked {} tokens in {} tranches for {}", total, lockup.tranches.len(), holder);
+
+    Ok(())
+}
+
+fn claim(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let holder = next_account_info(accounts_iter)?;
+    let lockup_account = next_account_info(accounts_iter)?;
+    let vault = next_account_info(accounts_iter)?;
+    let holder_token = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+
+    if !holder.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut lockup = load_lockup(program_id, lockup_account)?;
+    check_vault(program_id, lockup_account, &lockup, vault, token_program)?;
+
+    if lockup.holder != *holder.key {
+        msg!("Only the current holder can claim");
+        return Err(LockupError::Unauthorized.into());
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    let claimable = lockup.unlocked_amount(now).saturating_sub(lockup.claimed_amount);
+    if claimable == 0 {
+        msg!("Nothing unlocked at {}", now);
+        return Err(LockupError::NothingToClaim.into());
+    }
+
+    lockup.claimed_amount += claimable;
+    lockup.serialize(&mut &mut lockup_account.data.borrow_mut()[..])?;
+
+    transfer_from_vault(lockup_account, vault, holder_token, &lockup, claimable)?;
+
+    msg!("Claimed {} ({} so far)", claimable, lockup.claimed_amount);
+
+    Ok(())
+}
+
+fn transfer_position(program_id: &Pubkey, accounts: &[AccountInfo], new_holder: Pubkey) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let holder = next_account_info(accounts_iter)?;
+    let lockup_account = next_account_info(accounts_iter)?;
+
+    if !holder.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut lockup = load_lockup(program_id, lockup_account)?;
+
+    if lockup.holder != *holder.key {
+        msg!("Only the current holder can transfer the position");
+        return Err(LockupError::Unauthorized.into());
+    }
+
+    // The PDA seeds do not include the holder, so the record moves without a new account
+    lockup.holder = new_holder;
+    lockup.serialize(&mut &mut lockup_account.data.borrow_mut()[..])?;
+
+    msg!("Position {} transferred from {} to {}", lockup.lockup_id, holder.key, new_holder);
+
+    Ok(())
+}
+
+fn revoke(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let admin = next_account_info(accounts_iter)?;
+    let lockup_account = next_account_info(accounts_iter)?;
+    let vault = next_account_info(accounts_iter)?;
+    let treasury = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+
+    if !admin.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut lockup = load_lockup(program_id, lockup_account)?;
+    check_vault(program_id, lockup_account, &lockup, vault, token_program)?;
+
+    if lockup.admin != *admin.key {
+        msg!("Only the admin can revoke");
+        return Err(LockupError::Unauthorized.into());
+    }
+
+    if !lockup.revocable {
+        return Err(LockupError::NotRevocable.into());
+    }
+
+    if lockup.treasury != *treasury.key {
+        return Err(LockupError::WrongTreasury.into());
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    let locked = lockup.revoke_locked(now);
+    if locked == 0 {
+        return Err(LockupError::NothingToRevoke.into());
+    }
+
+    lockup.serialize(&mut &mut lockup_account.data.borrow_mut()[..])?;
+
+    transfer_from_vault(lockup_account, vault, treasury, &lockup, locked)?;
+
+    msg!("Revoked {} locked tokens to the treasury", locked);
+
+    Ok(())
+}
+
+fn load_lockup(program_id: &Pubkey, lockup_account: &AccountInfo) -> Result<LockupAccount, ProgramError> {
+    if lockup_account.owner != program_id {
+        msg!("Lockup account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    // Fewer than MAX_TRANCHES tranches leaves unused space at the end
+    let lockup = LockupAccount::deserialize(&mut &lockup_account.data.borrow()[..])?;
+
+    if !lockup.is_initialized {
+        return Err(LockupError::NotInitialized.into());
+    }
+
+    Ok(lockup)
+}
+
+fn check_vault(
+    program_id: &Pubkey,
+    lockup_account: &AccountInfo,
+    lockup: &LockupAccount,
+    vault: &AccountInfo,
+    token_program: &AccountInfo,
+) -> ProgramResult {
+    if *token_program.key != spl_token::id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let expected_vault = Pubkey::create_program_address(
+        &[VAULT_SEED, lockup_account.key.as_ref(), &[lockup.vault_bump]],
+        program_id,
+    )?;
+    if expected_vault != *vault.key {
+        msg!("Vault account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    Ok(())
+}
+
+/// Transfer `amount` from the vault, signed by the lockup PDA
+fn transfer_from_vault<'a>(
+    lockup_account: &AccountInfo<'a>,
+    vault: &AccountInfo<'a>,
+    destination: &AccountInfo<'a>,
+    lockup: &LockupAccount,
+    amount: u64,
+) -> ProgramResult {
+    invoke_signed(
+        &spl_token::instruction::transfer(
+            &spl_token::id(),
+            vault.key,
+            destination.key,
+            lockup_account.key,
+            &[],
+            amount,
+        )?,
+        &[vault.clone(), destination.clone(), lockup_account.clone()],
+        &[&[
+            LOCKUP_SEED,
+            lockup.admin.as_ref(),
+            &lockup.lockup_id.to_le_bytes(),
+            &[lockup.bump],
+        ]],
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_program::clock::Epoch;
+
+    fn lockup(holder: Pubkey) -> LockupAccount {
+        LockupAccount {
+            is_initialized: true,
+            admin: Pubkey::new_unique(),
+            lockup_id: 0,
+            mint: Pubkey::new_unique(),
+            treasury: Pubkey::new_unique(),
+            holder,
+            tranches: vec![
+                Tranche { unlock_at: 1_000, amount: 100 },
+                Tranche { unlock_at: 2_000, amount: 200 },
+                Tranche { unlock_at: 3_000, amount: 300 },
+            ],
+            claimed_amount: 0,
+            revocable: true,
+            bump: 255,
+            vault_bump: 255,
+        }
+    }
+
+    #[test]
+    fn test_tranches_unlock_in_steps() {
+        let mut lockup = lockup(Pubkey::new_unique());
+        assert_eq!(validate_tranches(&lockup.tranches), Ok(600));
+
+        assert_eq!(lockup.unlocked_amount(999), 0);
+        assert_eq!(lockup.unlocked_amount(1_000), 100);
+        assert_eq!(lockup.unlocked_amount(2_999), 300);
+        assert_eq!(lockup.unlocked_amount(3_000), 600);
+
+        // Revoking between tranches keeps the unlocked ones
+        assert_eq!(lockup.revoke_locked(2_500), 300);
+        assert_eq!(lockup.unlocked_amount(i64::MAX), 300);
+        assert_eq!(lockup.revoke_locked(2_500), 0);
+
+        let unordered = [Tranche { unlock_at: 2, amount: 1 }, Tranche { unlock_at: 2, amount: 1 }];
+        assert_eq!(validate_tranches(&unordered), Err(LockupError::InvalidTranches));
+        assert_eq!(validate_tranches(&[]), Err(LockupError::InvalidTranches));
+
+        lockup.tranches = vec![Tranche { unlock_at: 0, amount: 1 }; MAX_TRANCHES];
+        assert_eq!(lockup.try_to_vec().unwrap().len(), LOCKUP_ACCOUNT_SIZE);
+    }
+
+    #[test]
+    fn test_transfer_position() {
+        let program_id = Pubkey::new_unique();
+        let holder = Pubkey::new_unique();
+        let new_holder = Pubkey::new_unique();
+        let lockup_key = Pubkey::new_unique();
+
+        let mut lockup_data = vec![0; LOCKUP_ACCOUNT_SIZE];
+        lockup(holder).serialize(&mut &mut lockup_data[..]).unwrap();
+
+        let keys = [holder, lockup_key];
+        let mut lamports: Vec<u64> = vec![0, 0];
+        let mut data: Vec<Vec<u8>> = vec![vec![], lockup_data];
+
+        let accounts: Vec<AccountInfo> = keys
+            .iter()
+            .zip(lamports.iter_mut())
+            .zip(data.iter_mut())
+            .enumerate()
+            .map(|(i, ((key, lamports), data))| {
+                AccountInfo::new(key, i == 0, true, lamports, data, &program_id, false, Epoch::default())
+            })
+            .collect();
+        let instruction_data = LockupInstruction::TransferPosition { new_holder }.try_to_vec().unwrap();
+
+        process_instruction(&program_id, &accounts, &instruction_data).unwrap();
+        let stored = LockupAccount::deserialize(&mut &accounts[1].data.borrow()[..]).unwrap();
+        assert_eq!(stored.holder, new_holder);
+        assert_eq!(stored.tranches.len(), 3);
+
+        // The previous holder no longer controls the position
+        let result = process_instruction(&program_id, &accounts, &instruction_data);
+        assert_eq!(result, Err(LockupError::Unauthorized.into()));
+    }
+}
@@ -0,0 +1,77 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.18;
+
+/**
+ * @title Lottery
+ * @dev Sell tickets until a deadline, draw a winner from `block.prevrandao`, winner claims the pot
+ * @notice `prevrandao` can be biased by the block proposer; use a VRF for real value at stake
+ */
+contract Lottery {
+    address public immutable authority;
+    uint256 public immutable ticketPrice;
+    uint256 public immutable deadline;
+
+    address[] public tickets;
+    bool public drawn;
+    uint256 public winningTicket;
+    bool public claimed;
+
+    event TicketBought(uint256 indexed ticketIndex, address indexed buyer);
+    event WinnerDrawn(uint256 indexed ticketIndex, address indexed winner);
+    event PotClaimed(address indexed winner, uint256 amount);
+
+    constructor(uint256 _ticketPrice, uint256 durationBlocks) {
+        require(_ticketPrice > 0 && durationBlocks > 0, "Invalid parameters");
+        authority = msg.sender;
+        ticketPrice = _ticketPrice;
+        deadline = block.number + durationBlocks;
+    }
+
+    /**
+     * @dev Buy one ticket for exactly `ticketPrice`
+     */
+    function buyTicket() external payable {
+        require(block.number < deadline, "Sales closed");
+        require(msg.value == ticketPrice, "Wrong ticket price");
+
+        tickets.push(msg.sender);
+
+        emit TicketBought(tickets.length - 1, msg.sender);
+    }
+
+    /**
+     * @dev Pick the winning ticket (anyone, after the deadline)
+     */
+    function draw() external {
+        require(block.number >= deadline, "Sales open");
+        require(!drawn, "Already drawn");
+        require(tickets.length > 0, "No tickets");
+
+        drawn = true;
+        winningTicket = uint256(keccak256(abi.encodePacked(block.prevrandao, address(this), tickets.length)))
+            % tickets.length;
+
+        emit WinnerDrawn(winningTicket, tickets[winningTicket]);
+    }
+
+    /**
+     * @dev Pay the pot to the winner
+     */
+    function claim() external {
+        require(drawn, "Not drawn");
+        require(!claimed, "Already claimed");
+        require(msg.sender == tickets[winningTicket], "Not the winner");
+
+        claimed = true;
+        uint256 pot = address(this).balance;
+
+        (bool success, ) = msg.sender.call{value: pot}("");
+        require(success, "Transfer failed");
+
+        emit PotClaimed(msg.sender, pot);
+    }
+
+    function ticketCount() external view returns (uint256) {
+        return tickets.length;
+    }
+}
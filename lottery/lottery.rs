@@ -0,0 +1,457 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint,
+    entrypoint::ProgramResult,
+    hash::hashv,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::{self, Sysvar},
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// Seed prefix for lottery PDAs: ["lottery", authority, lottery_id]
+pub const LOTTERY_SEED: &[u8] = b"lottery";
+
+/// Seed prefix for ticket PDAs: ["ticket", lottery, ticket_index]
+pub const TICKET_SEED: &[u8] = b"ticket";
+
+/// Size of a `LotteryAccount`
+pub const LOTTERY_ACCOUNT_SIZE: usize = 1 // is_initialized
+    + 32 // authority
+    + 8 // lottery_id
+    + 8 // ticket_price
+    + 8 // deadline_slot
+    + 8 // ticket_count
+    + 1 + 8 // winning_ticket
+    + 1 // claimed
+    + 1; // bump
+
+/// Size of a `Ticket` account
+pub const TICKET_ACCOUNT_SIZE: usize = 32 + 32 + 8;
+
+/// Errors returned by the program as `ProgramError::Custom` codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LotteryError {
+    /// Ticket price or duration is zero
+    InvalidParameters = 0,
+    /// Ticket sales ended at the deadline
+    SalesClosed = 1,
+    /// The draw is only allowed after the deadline
+    SalesOpen = 2,
+    /// No tickets were sold
+    NoTickets = 3,
+    /// The winner has already been drawn
+    AlreadyDrawn = 4,
+    /// The winner has not been drawn yet
+    NotDrawn = 5,
+    /// The ticket is not the winning ticket, or the signer does not hold it
+    NotWinner = 6,
+    /// The pot has already been paid out
+    AlreadyClaimed = 7,
+    /// The lottery has not been initialized
+    NotInitialized = 8,
+}
+
+impl From<LotteryError> for ProgramError {
+    fn from(e: LotteryError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+/// A lottery round. The pot is the account's lamports above its
+/// rent-exempt minimum.
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct LotteryAccount {
+    pub is_initialized: bool,
+    /// Creator of the lottery
+    pub authority: Pubkey,
+    pub lottery_id: u64,
+    /// Lamports per ticket
+    pub ticket_price: u64,
+    /// Last slot in which tickets can be bought is `deadline_slot - 1`
+    pub deadline_slot: u64,
+    pub ticket_count: u64,
+    /// Index of the winning ticket, set by `Draw`
+    pub winning_ticket: Option<u64>,
+    pub claimed: bool,
+    pub bump: u8,
+}
+
+/// One ticket; its index is part of the PDA seeds, so tickets are numbered 0..ticket_count
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct Ticket {
+    pub lottery: Pubkey,
+    pub buyer: Pubkey,
+    pub index: u64,
+}
+
+/// Pick a ticket index from a slot hash, mixed with the lottery address and
+/// ticket count so different lotteries drawing in the same slot differ
+pub fn winning_index(slot_hash: &[u8], lottery: &Pubkey, ticket_count: u64) -> u64 {
+    let hash = hashv(&[slot_hash, lottery.as_ref(), &ticket_count.to_le_bytes()]);
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&hash.to_bytes()[..8]);
+    u64::from_le_bytes(bytes) % ticket_count
+}
+
+entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = LotteryInstruction::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        LotteryInstruction::CreateLottery {
+            lottery_id,
+            ticket_price,
+            duration_slots,
+        } => create_lottery(program_id, accounts, lottery_id, ticket_price, duration_slots),
+        LotteryInstruction::BuyTicket => buy_ticket(program_id, accounts),
+        LotteryInstruction::Draw => draw(program_id, accounts),
+        LotteryInstruction::Claim => claim(program_id, accounts),
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub enum LotteryInstruction {
+    /// Create the lottery PDA
+    /// Accounts: [writable, signer] authority, [writable] lottery PDA, [] system program
+    CreateLottery {
+        lottery_id: u64,
+        ticket_price: u64,
+        duration_slots: u64,
+    },
+    /// Pay `ticket_price` into the pot and create the next ticket PDA
+    /// Accounts: [writable, signer] buyer, [writable] lottery PDA, [writable] ticket PDA, [] system program
+    BuyTicket,
+    /// Pick the winning ticket from the most recent slot hash (anyone, after the deadline)
+    /// Accounts: [writable] lottery PDA, [] SlotHashes sysvar
+    Draw,
+    /// Pay the pot to the holder of the winning ticket
+    /// Accounts: [writable, signer] winner, [writable] lottery PDA, [] winning ticket PDA
+    Claim,
+}
+
+fn create_lottery(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    lottery_id: u64,
+    ticket_price: u64,
+    duration_slots: u64,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let authority = next_account_info(accounts_iter)?;
+    let lottery_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if ticket_price == 0 || duration_slots == 0 {
+        msg!("Ticket price and duration must be greater than zero");
+        return Err(LotteryError::InvalidParameters.into());
+    }
+
+    let id_bytes = lottery_id.to_le_bytes();
+    let (expected_lottery, bump) =
+        Pubkey::find_program_address(&[LOTTERY_SEED, authority.key.as_ref(), &id_bytes], program_id);
+    if expected_lottery != *lottery_account.key {
+        msg!("Lottery account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    invoke_signed(
+        &system_instruction::create_account(
+            authority.key,
+            lottery_account.key,
+            Rent::get()?.minimum_balance(LOTTERY_ACCOUNT_SIZE),
+            LOTTERY_ACCOUNT_SIZE as u64,
+            program_id,
+        ),
+        &[authority.clone(), lottery_account.clone(), system_program.clone()],
+        &[&[LOTTERY_SEED, authority.key.as_ref(), &id_bytes, &[bump]]],
+    )?;
+
+    let lottery = LotteryAccount {
+        is_initialized: true,
+        authority: *authority.key,
+        lottery_id,
+        ticket_price,
+        deadline_slot: Clock::get()?
+            .slot
+            .checked_add(duration_slots)
+            .ok_or(LotteryError::InvalidParameters)?,
+        ticket_count: 0,
+        winning_ticket: None,
+        claimed: false,
+        bump,
+    };
+    lottery.serialize(&mut &mut lottery_account.data.borrow_mut()[..])?;
+
+    msg!("Lottery {} open until slot {}", lottery_id, lottery.deadline_slot);
+
+    Ok(())
+}
+
+fn buy_ticket(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let buyer = next_account_info(accounts_iter)?;
+    let lottery_account = next_account_info(accounts_iter)?;
+    let ticket_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !buyer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut lottery = load_lottery(program_id, lottery_account)?;
+
+    let clock = Clock::get()?;
+    if clock.slot >= lottery.deadline_slot {
+        msg!("Ticket sales closed at slot {}", lottery.deadline_slot);
+        return Err(LotteryError::SalesClosed.into());
+    }
+
+    let index_bytes = lottery.ticket_count.to_le_bytes();
+    let (expected_ticket, bump) = Pubkey::find_program_address(
+        &[TICKET_SEED, lottery_account.key.as_ref(), &index_bytes],
+        program_id,
+    );
+    if expected_ticket != *ticket_account.key {
+        msg!("Ticket account does not match the next ticket PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    // Pay into the pot
+    invoke(
+        &system_instruction::transfer(buyer.key, lottery_account.key, lottery.ticket_price),
+        &[buyer.clone(), lottery_account.clone(), system_program.clone()],
+    )?;
+
+    invoke_signed(
+        &system_instruction::create_account(
+            buyer.key,
+            ticket_account.key,
+            Rent::get()?.minimum_balance(TICKET_ACCOUNT_SIZE),
+            TICKET_ACCOUNT_SIZE as u64,
+            program_id,
+        ),
+        &[buyer.clone(), ticket_account.clone(), system_program.clone()],
+        &[&[TICKET_SEED, lottery_account.key.as_ref(), &index_bytes, &[bump]]],
+    )?;
+
+    let ticket = Ticket {
+        lottery: *lottery_account.key,
+        buyer: *buyer.key,
+        index: lottery.ticket_count,
+    };
+    ticket.serialize(&mut &mut ticket_account.data.borrow_mut()[..])?;
+
+    lottery.ticket_count += 1;
+    lottery.serialize(&mut &mut lottery_account.data.borrow_mut()[..])?;
+
+    msg!("Ticket {} sold to {}", ticket.index, buyer.key);
+
+    Ok(())
+}
+
+fn draw(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let lottery_account = next_account_info(accounts_iter)?;
+    let slot_hashes = next_account_info(accounts_iter)?;
+
+    let mut lottery = load_lottery(program_id, lottery_account)?;
+
+    if lottery.winning_ticket.is_some() {
+        return Err(LotteryError::AlreadyDrawn.into());
+    }
+
+    let clock = Clock::get()?;
+    if clock.slot < lottery.deadline_slot {
+        msg!("Draw opens at slot {}", lottery.deadline_slot);
+        return Err(LotteryError::SalesOpen.into());
+    }
+
+    if lottery.ticket_count == 0 {
+        return Err(LotteryError::NoTickets.into());
+    }
+
+    if *slot_hashes.key != sysvar::slot_hashes::id() {
+        msg!("Expected the SlotHashes sysvar");
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    // SlotHashes is too large to deserialize on-chain; read the newest entry
+    // directly: [u64 entry count][u64 slot][32-byte hash]...
+    let data = slot_hashes.data.borrow();
+    if data.len() < 48 {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    let recent_hash = &data[16..48];
+
+    let winner = winning_index(recent_hash, lottery_account.key, lottery.ticket_count);
+    lottery.winning_ticket = Some(winner);
+    drop(data);
+    lottery.serialize(&mut &mut lottery_account.data.borrow_mut()[..])?;
+
+    msg!("Winning ticket: {} of {}", winner, lottery.ticket_count);
+
+    Ok(())
+}
+
+fn claim(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let winner = next_account_info(accounts_iter)?;
+    let lottery_account = next_account_info(accounts_iter)?;
+    let ticket_account = next_account_info(accounts_iter)?;
+
+    if !winner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut lottery = load_lottery(program_id, lottery_account)?;
+
+    let winning_ticket = lottery.winning_ticket.ok_or(LotteryError::NotDrawn)?;
+
+    if lottery.claimed {
+        return Err(LotteryError::AlreadyClaimed.into());
+    }
+
+    if ticket_account.owner != program_id {
+        msg!("Ticket account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let ticket = Ticket::try_from_slice(&ticket_account.data.borrow())?;
+    if ticket.lottery != *lottery_account.key || ticket.index != winning_ticket || ticket.buyer != *winner.key {
+        msg!("Signer does not hold winning ticket {}", winning_ticket);
+        return Err(LotteryError::NotWinner.into());
+    }
+
+    // Pay out everything above the rent-exempt minimum
+    let rent_minimum = Rent::get()?.minimum_balance(lottery_account.data_len());
+    let pot = lottery_account.lamports().saturating_sub(rent_minimum);
+
+    lottery.claimed = true;
+    lottery.serialize(&mut &mut lottery_account.data.borrow_mut()[..])?;
+
+    **lottery_account.try_borrow_mut_lamports()? -= pot;
+    **winner.try_borrow_mut_lamports()? += pot;
+
+    msg!("{} claimed the pot of {} lamports", winner.key, pot);
+
+    Ok(())
+}
+
+/// Validate the lottery account, returning its state
+fn load_lottery(program_id: &Pubkey, lottery_account: &AccountInfo) -> Result<LotteryAccount, ProgramError> {
+    if lottery_account.owner != program_id {
+        msg!("Lottery account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let lottery = LotteryAccount::try_from_slice(&lottery_account.data.borrow())?;
+    if !lottery.is_initialized {
+        return Err(LotteryError::NotInitialized.into());
+    }
+
+    Ok(lottery)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_program::{clock::Epoch, entrypoint::SUCCESS, program_stubs};
+
+    /// Serves a Clock sysvar at a fixed slot so handlers can run off-chain
+    struct TestSyscallStubs {
+        slot: u64,
+    }
+
+    impl program_stubs::SyscallStubs for TestSyscallStubs {
+        fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+            unsafe {
+                *(var_addr as *mut Clock) = Clock {
+                    slot: self.slot,
+                    ..Clock::default()
+                };
+            }
+            SUCCESS
+        }
+    }
+
+    #[test]
+    fn test_winning_index_is_in_range_and_deterministic() {
+        let lottery = Pubkey::new_unique();
+        let slot_hash = [7u8; 32];
+
+        for ticket_count in 1..50 {
+            let index = winning_index(&slot_hash, &lottery, ticket_count);
+            assert!(index < ticket_count);
+            assert_eq!(index, winning_index(&slot_hash, &lottery, ticket_count));
+        }
+    }
+
+    #[test]
+    fn test_draw_before_deadline_fails() {
+        program_stubs::set_syscall_stubs(Box::new(TestSyscallStubs { slot: 99 }));
+
+        let program_id = Pubkey::new_unique();
+        let lottery_key = Pubkey::new_unique();
+        let slot_hashes_key = sysvar::slot_hashes::id();
+        let sysvar_owner = sysvar::id();
+
+        let lottery = LotteryAccount {
+            is_initialized: true,
+            authority: Pubkey::new_unique(),
+            lottery_id: 0,
+            ticket_price: 1_000,
+            deadline_slot: 100,
+            ticket_count: 3,
+            winning_ticket: None,
+            claimed: false,
+            bump: 255,
+        };
+        let mut lottery_data = lottery.try_to_vec().unwrap();
+        let mut lottery_lamports = 0;
+        let mut slot_hashes_data = vec![0u8; 48];
+        let mut slot_hashes_lamports = 0;
+
+        let accounts = vec![
+            AccountInfo::new(
+                &lottery_key,
+                false,
+                true,
+                &mut lottery_lamports,
+                &mut lottery_data,
+                &program_id,
+                false,
+                Epoch::default(),
+            ),
+            AccountInfo::new(
+                &slot_hashes_key,
+                false,
+                false,
+                &mut slot_hashes_lamports,
+                &mut slot_hashes_data,
+                &sysvar_owner,
+                false,
+                Epoch::default(),
+            ),
+        ];
+
+        let instruction_data = LotteryInstruction::Draw.try_to_vec().unwrap();
+        let result = process_instruction(&program_id, &accounts, &instruction_data);
+        assert_eq!(result, Err(LotteryError::SalesOpen.into()));
+    }
+}
@@ -0,0 +1,107 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+interface IERC721 {
+    function transferFrom(address from, address to, uint256 tokenId) external;
+}
+
+interface IERC2981 {
+    function royaltyInfo(uint256 tokenId, uint256 salePrice) external view returns (address receiver, uint256 royaltyAmount);
+}
+
+interface IERC165 {
+    function supportsInterface(bytes4 interfaceId) external view returns (bool);
+}
+
+/**
+ * @title Marketplace
+ * @dev Fixed-price NFT marketplace that escrows listed NFTs and pays ERC-2981 royalties on sale
+ */
+contract Marketplace {
+    uint256 public constant BPS_DENOMINATOR = 10_000;
+    uint256 public constant MAX_FEE_BPS = 1_000;
+
+    struct Listing {
+        address seller;
+        uint256 price;
+    }
+
+    address public immutable feeAccount;
+    uint256 public immutable feeBps;
+
+    // nft contract => token id => listing
+    mapping(address => mapping(uint256 => Listing)) public listings;
+
+    event Listed(address indexed nft, uint256 indexed tokenId, address indexed seller, uint256 price);
+    event Delisted(address indexed nft, uint256 indexed tokenId);
+    event Sold(address indexed nft, uint256 indexed tokenId, address indexed buyer, uint256 price, uint256 royalty);
+
+    constructor(address _feeAccount, uint256 _feeBps) {
+        require(_feeBps <= MAX_FEE_BPS, "Fee too high");
+        feeAccount = _feeAccount;
+        feeBps = _feeBps;
+    }
+
+    /**
+     * @dev Move the NFT into escrow and list it (requires prior approval)
+     */
+    function list(address nft, uint256 tokenId, uint256 price) external {
+        require(price > 0, "Price must be greater than zero");
+
+        IERC721(nft).transferFrom(msg.sender, address(this), tokenId);
+        listings[nft][tokenId] = Listing(msg.sender, price);
+
+        emit Listed(nft, tokenId, msg.sender, price);
+    }
+
+    /**
+     * @dev Return the NFT to the seller
+     */
+    function delist(address nft, uint256 tokenId) external {
+        Listing memory listing = listings[nft][tokenId];
+        require(listing.seller == msg.sender, "Only seller can delist");
+
+        delete listings[nft][tokenId];
+        IERC721(nft).transferFrom(address(this), msg.sender, tokenId);
+
+        emit Delisted(nft, tokenId);
+    }
+
+    /**
+     * @dev Pay the exact listing price; proceeds are split between the marketplace, the creator, and the seller
+     */
+    function buy(address nft, uint256 tokenId) external payable {
+        Listing memory listing = listings[nft][tokenId];
+        require(listing.seller != address(0), "Not listed");
+        require(msg.value == listing.price, "Incorrect payment");
+
+        delete listings[nft][tokenId];
+
+        uint256 fee = (listing.price * feeBps) / BPS_DENOMINATOR;
+        (address royaltyReceiver, uint256 royalty) = _royalty(nft, tokenId, listing.price);
+        require(fee + royalty <= listing.price, "Royalty too high");
+
+        IERC721(nft).transferFrom(address(this), msg.sender, tokenId);
+
+        _pay(feeAccount, fee);
+        _pay(royaltyReceiver, royalty);
+        _pay(listing.seller, listing.price - fee - royalty);
+
+        emit Sold(nft, tokenId, msg.sender, listing.price, royalty);
+    }
+
+    function _royalty(address nft, uint256 tokenId, uint256 price) private view returns (address, uint256) {
+        try IERC165(nft).supportsInterface(type(IERC2981).interfaceId) returns (bool supported) {
+            if (supported) {
+                return IERC2981(nft).royaltyInfo(tokenId, price);
+            }
+        } catch {}
+        return (address(0), 0);
+    }
+
+    function _pay(address to, uint256 amount) private {
+        if (amount == 0) return;
+        (bool success, ) = to.call{value: amount}("");
+        require(success, "Transfer failed");
+    }
+}
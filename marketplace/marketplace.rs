@@ -0,0 +1,595 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint,
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use mpl_token_metadata::accounts::Metadata;
+use spl_token::state::{Account as TokenAccount, Mint};
+
+/// Seed prefix for marketplace PDAs: ["marketplace", authority]
+pub const MARKETPLACE_SEED: &[u8] = b"marketplace";
+
+/// Seed prefix for listing PDAs: ["listing", marketplace, mint]
+pub const LISTING_SEED: &[u8] = b"listing";
+
+/// Seed prefix for the escrow token account of a listing: ["escrow", listing]
+pub const ESCROW_SEED: &[u8] = b"escrow";
+
+/// Fees and royalties are expressed in basis points of the sale price
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Highest marketplace fee (10%)
+pub const MAX_FEE_BPS: u16 = 1_000;
+
+/// Size of a `MarketplaceAccount`
+pub const MARKETPLACE_ACCOUNT_SIZE: usize = 1 // is_initialized
+    + 32 // authority
+    + 32 // fee_account
+    + 2 // fee_bps
+    + 1; // bump
+
+/// Size of a `ListingAccount`
+pub const LISTING_ACCOUNT_SIZE: usize = 1 // is_initialized
+    + 32 // marketplace
+    + 32 // seller
+    + 32 // mint
+    + 8 // price
+    + 1 // bump
+    + 1; // escrow_bump
+
+/// Errors returned by the program as `ProgramError::Custom` codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MarketplaceError {
+    /// Price is zero or the fee is above `MAX_FEE_BPS`
+    InvalidParameters = 0,
+    /// The mint is not a single-supply, zero-decimal token
+    NotAnNft = 1,
+    /// The metadata account is not the Metaplex metadata of the mint
+    InvalidMetadata = 2,
+    /// The listing price differs from the price the buyer agreed to
+    PriceMismatch = 3,
+    /// The creator accounts do not match the metadata's creators
+    CreatorMismatch = 4,
+    /// The marketplace or listing has not been initialized
+    NotInitialized = 5,
+    /// Only the seller can delist
+    Unauthorized = 6,
+    /// The fee account or escrow does not belong to this marketplace or listing
+    InvalidMarketplaceAccount = 7,
+}
+
+impl From<MarketplaceError> for ProgramError {
+    fn from(e: MarketplaceError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+/// A marketplace and its fee settings
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct MarketplaceAccount {
+    pub is_initialized: bool,
+    pub authority: Pubkey,
+    /// Receives the marketplace fee on every sale
+    pub fee_account: Pubkey,
+    pub fee_bps: u16,
+    pub bump: u8,
+}
+
+/// An NFT for sale. The NFT sits in the escrow token account, owned by this PDA.
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct ListingAccount {
+    pub is_initialized: bool,
+    pub marketplace: Pubkey,
+    pub seller: Pubkey,
+    pub mint: Pubkey,
+    /// Price in lamports
+    pub price: u64,
+    pub bump: u8,
+    pub escrow_bump: u8,
+}
+
+/// How a sale price is divided
+#[derive(Debug, PartialEq, Eq)]
+pub struct Settlement {
+    pub marketplace_fee: u64,
+    /// One entry per creator, in metadata order
+    pub royalties: Vec<u64>,
+    pub seller: u64,
+}
+
+/// Split `price` into the marketplace fee, the creators' royalties, and the
+/// seller's proceeds. Creator amounts round down; the seller keeps the dust.
+pub fn settlement_amounts(
+    price: u64,
+    fee_bps: u16,
+    seller_fee_basis_points: u16,
+    creator_shares: &[u8],
+) -> Option<Settlement> {
+    let marketplace_fee = (price as u128 * fee_bps as u128 / BPS_DENOMINATOR as u128) as u64;
+    let royalty_total = (price as u128 * seller_fee_basis_points as u128 / BPS_DENOMINATOR as u128) as u64;
+    let royalties: Vec<u64> = creator_shares
+        .iter()
+        .map(|&share| (royalty_total as u128 * share as u128 / 100) as u64)
+        .collect();
+
+    let seller = price
+        .checked_sub(marketplace_fee)?
+        .checked_sub(royalties.iter().sum())?;
+
+    Some(Settlement {
+        marketplace_fee,
+        royalties,
+        seller,
+    })
+}
+
+entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = MarketplaceInstruction::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        MarketplaceInstruction::InitializeMarketplace { fee_bps } => {
+            initialize_marketplace(program_id, accounts, fee_bps)
+        }
+        MarketplaceInstruction::List { price } => list(program_id, accounts, price),
+        MarketplaceInstruction::Delist => delist(program_id, accounts),
+        MarketplaceInstruction::Buy { price } => buy(program_id, accounts, price),
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub enum MarketplaceInstruction {
+    /// Create a marketplace that charges `fee_bps` on each sale
+    /// Accounts: [writable, signer] authority, [writable] marketplace PDA, [] fee account,
+    /// [] system program
+    InitializeMarketplace { fee_bps: u16 },
+    /// Move the NFT into escrow and list it at `price` lamports
+    /// Accounts: [writable, signer] seller, [] marketplace PDA, [writable] listing PDA, [] mint,
+    /// [writable] seller token account, [writable] escrow PDA, [] token program, [] system program
+    List { price: u64 },
+    /// Return the NFT to the seller and close the listing
+    /// Accounts: [writable, signer] seller, [writable] listing PDA, [writable] escrow PDA,
+    /// [writable] seller token account, [] token program
+    Delist,
+    /// Pay `price` lamports, split between the marketplace, the creators, and
+    /// the seller, and receive the NFT
+    /// Accounts: [writable, signer] buyer, [] marketplace PDA, [writable] listing PDA,
+    /// [writable] seller, [writable] fee account, [] metadata, [writable] escrow PDA,
+    /// [writable] buyer token account, [] token program, [] system program,
+    /// then [writable] each creator in metadata order
+    Buy { price: u64 },
+}
+
+fn initialize_marketplace(program_id: &Pubkey, accounts: &[AccountInfo], fee_bps: u16) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let authority = next_account_info(accounts_iter)?;
+    let marketplace_account = next_account_info(accounts_iter)?;
+    let fee_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if fee_bps > MAX_FEE_BPS {
+        msg!("Fee must be at most {} bps", MAX_FEE_BPS);
+        return Err(MarketplaceError::InvalidParameters.into());
+    }
+
+    let (expected_marketplace, bump) =
+        Pubkey::find_program_address(&[MARKETPLACE_SEED, authority.key.as_ref()], program_id);
+    if expected_marketplace != *marketplace_account.key {
+        msg!("Marketplace account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    invoke_signed(
+        &system_instruction::create_account(
+            authority.key,
+            marketplace_account.key,
+            Rent::get()?.minimum_balance(MARKETPLACE_ACCOUNT_SIZE),
+            MARKETPLACE_ACCOUNT_SIZE as u64,
+            program_id,
+        ),
+        &[authority.clone(), marketplace_account.clone(), system_program.clone()],
+        &[&[MARKETPLACE_SEED, authority.key.as_ref(), &[bump]]],
+    )?;
+
+    let marketplace = MarketplaceAccount {
+        is_initialized: true,
+        authority: *authority.key,
+        fee_account: *fee_account.key,
+        fee_bps,
+        bump,
+    };
+    marketplace.serialize(&mut &mut marketplace_account.data.borrow_mut()[..])?;
+
+    msg!("Marketplace created with fee {} bps", fee_bps);
+
+    Ok(())
+}
+
+fn list(program_id: &Pubkey, accounts: &[AccountInfo], price: u64) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let seller = next_account_info(accounts_iter)?;
+    let marketplace_account = next_account_info(accounts_iter)?;
+    let listing_account = next_account_info(accounts_iter)?;
+    let mint = next_account_info(accounts_iter)?;
+    let seller_token = next_account_info(accounts_iter)?;
+    let escrow = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !seller.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if price == 0 {
+        msg!("Price must be greater than zero");
+        return Err(MarketplaceError::InvalidParameters.into());
+    }
+
+    if *token_program.key != spl_token::id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    load_marketplace(program_id, marketplace_account)?;
+
+    if mint.owner != token_program.key {
+        return Err(MarketplaceError::NotAnNft.into());
+    }
+    let mint_state = Mint::unpack(&mint.data.borrow())?;
+    if mint_state.decimals != 0 || mint_state.supply != 1 {
+        msg!("Only tokens with supply 1 and 0 decimals can be listed");
+        return Err(MarketplaceError::NotAnNft.into());
+    }
+
+    let (expected_listing, bump) = Pubkey::find_program_address(
+        &[LISTING_SEED, marketplace_account.key.as_ref(), mint.key.as_ref()],
+        program_id,
+    );
+    let (expected_escrow, escrow_bump) =
+        Pubkey::find_program_address(&[ESCROW_SEED, listing_account.key.as_ref()], program_id);
+    if expected_listing != *listing_account.key || expected_escrow != *escrow.key {
+        msg!("Listing or escrow account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let rent = Rent::get()?;
+
+    invoke_signed(
+        &system_instruction::create_account(
+            seller.key,
+            listing_account.key,
+            rent.minimum_balance(LISTING_ACCOUNT_SIZE),
+            LISTING_ACCOUNT_SIZE as u64,
+            program_id,
+        ),
+        &[seller.clone(), listing_account.clone(), system_program.clone()],
+        &[&[LISTING_SEED, marketplace_account.key.as_ref(), mint.key.as_ref(), &[bump]]],
+    )?;
+
+    // Escrow token account, owned by the listing PDA
+    invoke_signed(
+        &system_instruction::create_account(
+            seller.key,
+            escrow.key,
+            rent.minimum_balance(TokenAccount::LEN),
+            TokenAccount::LEN as u64,
+            &spl_token::id(),
+        ),
+        &[seller.clone(), escrow.clone(), system_program.clone()],
+        &[&[ESCROW_SEED, listing_account.key.as_ref(), &[escrow_bump]]],
+    )?;
+    invoke(
+        &spl_token::instruction::initialize_account3(&spl_token::id(), escrow.key, mint.key, listing_account.key)?,
+        &[escrow.clone(), mint.clone()],
+    )?;
+
+    invoke(
+        &spl_token::instruction::transfer(&spl_token::id(), seller_token.key, escrow.key, seller.key, &[], 1)?,
+        &[seller_token.clone(), escrow.clone(), seller.clone()],
+    )?;
+
+    let listing = ListingAccount {
+        is_initialized: true,
+        marketplace: *marketplace_account.key,
+        seller: *seller.key,
+        mint: *mint.key,
+        price,
+        bump,
+        escrow_bump,
+    };
+    listing.serialize(&mut &mut listing_account.data.borrow_mut()[..])?;
+
+    msg!("Listed {} for {} lamports", mint.key, price);
+
+    Ok(())
+}
+
+fn delist(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let seller = next_account_info(accounts_iter)?;
+    let listing_account = next_account_info(accounts_iter)?;
+    let escrow = next_account_info(accounts_iter)?;
+    let seller_token = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+
+    if !seller.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if *token_program.key != spl_token::id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let listing = load_listing(program_id, listing_account, escrow)?;
+
+    if listing.seller != *seller.key {
+        msg!("Only the seller can delist");
+        return Err(MarketplaceError::Unauthorized.into());
+    }
+
+    release_nft(&listing, listing_account, escrow, seller_token, seller)?;
+    close_listing(listing_account, seller)?;
+
+    msg!("Delisted {}", listing.mint);
+
+    Ok(())
+}
+
+fn buy(program_id: &Pubkey, accounts: &[AccountInfo], price: u64) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let buyer = next_account_info(accounts_iter)?;
+    let marketplace_account = next_account_info(accounts_iter)?;
+    let listing_account = next_account_info(accounts_iter)?;
+    let seller = next_account_info(accounts_iter)?;
+    let fee_account = next_account_info(accounts_iter)?;
+    let metadata_account = next_account_info(accounts_iter)?;
+    let escrow = next_account_info(accounts_iter)?;
+    let buyer_token = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+    let creator_accounts = accounts_iter.as_slice();
+
+    if !buyer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if *token_program.key != spl_token::id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let marketplace = load_marketplace(program_id, marketplace_account)?;
+    let listing = load_listing(program_id, listing_account, escrow)?;
+
+    if listing.marketplace != *marketplace_account.key
+        || listing.seller != *seller.key
+        || marketplace.fee_account != *fee_account.key
+    {
+        msg!("Seller or fee account does not match the listing");
+        return Err(MarketplaceError::InvalidMarketplaceAccount.into());
+    }
+
+    // Protects the buyer from a delist and relist at a higher price
+    if listing.price != price {
+        msg!("Listing price is {}, not {}", listing.price, price);
+        return Err(MarketplaceError::PriceMismatch.into());
+    }
+
+    // Royalties come from the Metaplex metadata of the listed mint
+    if *metadata_account.owner != mpl_token_metadata::ID
+        || Metadata::find_pda(&listing.mint).0 != *metadata_account.key
+    {
+        msg!("Metadata account is not the metadata of {}", listing.mint);
+        return Err(MarketplaceError::InvalidMetadata.into());
+    }
+    let metadata = Metadata::safe_deserialize(&metadata_account.data.borrow())
+        .map_err(|_| MarketplaceError::InvalidMetadata)?;
+    let creators = metadata.creators.unwrap_or_default();
+
+    if creators.len() != creator_accounts.len()
+        || creators.iter().zip(creator_accounts).any(|(c, a)| c.address != *a.key)
+    {
+        msg!("Pass the {} creator accounts in metadata order", creators.len());
+        return Err(MarketplaceError::CreatorMismatch.into());
+    }
+
+    let shares: Vec<u8> = creators.iter().map(|c| c.share).collect();
+    let settlement = settlement_amounts(price, marketplace.fee_bps, metadata.seller_fee_basis_points, &shares)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    let payouts = std::iter::once((fee_account, settlement.marketplace_fee))
+        .chain(creator_accounts.iter().zip(settlement.royalties.iter().copied()))
+        .chain(std::iter::once((seller, settlement.seller)));
+    for (recipient, amount) in payouts {
+        if amount == 0 {
+            continue;
+        }
+        invoke(
+            &system_instruction::transfer(buyer.key, recipient.key, amount),
+            &[buyer.clone(), recipient.clone(), system_program.clone()],
+        )?;
+    }
+
+    release_nft(&listing, listing_account, escrow, buyer_token, seller)?;
+    close_listing(listing_account, seller)?;
+
+    msg!(
+        "Sold {} for {}: fee {}, royalties {}, seller {}",
+        listing.mint,
+        price,
+        settlement.marketplace_fee,
+        settlement.royalties.iter().sum::<u64>(),
+        settlement.seller
+    );
+
+    Ok(())
+}
+
+/// Move the NFT out of escrow and close the escrow, returning its rent to the seller
+fn release_nft<'a>(
+    listing: &ListingAccount,
+    listing_account: &AccountInfo<'a>,
+    escrow: &AccountInfo<'a>,
+    destination: &AccountInfo<'a>,
+    seller: &AccountInfo<'a>,
+) -> ProgramResult {
+    let listing_seeds: &[&[u8]] = &[
+        LISTING_SEED,
+        listing.marketplace.as_ref(),
+        listing.mint.as_ref(),
+        &[listing.bump],
+    ];
+
+    invoke_signed(
+        &spl_token::instruction::transfer(&spl_token::id(), escrow.key, destination.key, listing_account.key, &[], 1)?,
+        &[escrow.clone(), destination.clone(), listing_account.clone()],
+        &[listing_seeds],
+    )?;
+    invoke_signed(
+        &spl_token::instruction::close_account(&spl_token::id(), escrow.key, seller.key, listing_account.key, &[])?,
+        &[escrow.clone(), seller.clone(), listing_account.clone()],
+        &[listing_seeds],
+    )?;
+
+    Ok(())
+}
+
+/// Close the listing account, returning its rent to the seller
+fn close_listing(listing_account: &AccountInfo, seller: &AccountInfo) -> ProgramResult {
+    let lamports = listing_account.lamports();
+    **listing_account.try_borrow_mut_lamports()? = 0;
+    **seller.try_borrow_mut_lamports()? += lamports;
+    listing_account.data.borrow_mut().fill(0);
+    Ok(())
+}
+
+/// Validate the marketplace account, returning its state
+fn load_marketplace(
+    program_id: &Pubkey,
+    marketplace_account: &AccountInfo,
+) -> Result<MarketplaceAccount, ProgramError> {
+    if marketplace_account.owner != program_id {
+        msg!("Marketplace account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let marketplace = MarketplaceAccount::try_from_slice(&marketplace_account.data.borrow())?;
+
+    if !marketplace.is_initialized {
+        msg!("Marketplace has not been initialized");
+        return Err(MarketplaceError::NotInitialized.into());
+    }
+
+    Ok(marketplace)
+}
+
+/// Validate the listing account and its escrow, returning the listing state
+fn load_listing(
+    program_id: &Pubkey,
+    listing_account: &AccountInfo,
+    escrow: &AccountInfo,
+) -> Result<ListingAccount, ProgramError> {
+    if listing_account.owner != program_id {
+        msg!("Listing account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let listing = ListingAccount::try_from_slice(&listing_account.data.borrow())?;
+
+    if !listing.is_initialized {
+        msg!("Listing has not been initialized");
+        return Err(MarketplaceError::NotInitialized.into());
+    }
+
+    let expected_escrow = Pubkey::create_program_address(
+        &[ESCROW_SEED, listing_account.key.as_ref(), &[listing.escrow_bump]],
+        program_id,
+    )?;
+    if expected_escrow != *escrow.key {
+        msg!("Escrow account does not belong to this listing");
+        return Err(MarketplaceError::InvalidMarketplaceAccount.into());
+    }
+
+    Ok(listing)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_program::clock::Epoch;
+
+    #[test]
+    fn test_settlement_amounts() {
+        // 2% marketplace fee, 5% royalty split 70/30
+        let settlement = settlement_amounts(1_000_000_000, 200, 500, &[70, 30]).unwrap();
+        assert_eq!(settlement.marketplace_fee, 20_000_000);
+        assert_eq!(settlement.royalties, vec![35_000_000, 15_000_000]);
+        assert_eq!(settlement.seller, 930_000_000);
+
+        // Rounding dust goes to the seller, so the parts always sum to the price
+        let settlement = settlement_amounts(101, 250, 333, &[33, 33, 34]).unwrap();
+        let total = settlement.marketplace_fee + settlement.royalties.iter().sum::<u64>() + settlement.seller;
+        assert_eq!(total, 101);
+
+        // No creators: everything but the fee goes to the seller
+        let settlement = settlement_amounts(1_000, 100, 500, &[]).unwrap();
+        assert_eq!((settlement.marketplace_fee, settlement.seller), (10, 990));
+    }
+
+    #[test]
+    fn test_delist_by_non_seller_fails() {
+        let program_id = Pubkey::new_unique();
+        let listing_key = Pubkey::new_unique();
+        let intruder_key = Pubkey::new_unique();
+        let token_program_key = spl_token::id();
+        let (escrow_key, escrow_bump) =
+            Pubkey::find_program_address(&[ESCROW_SEED, listing_key.as_ref()], &program_id);
+
+        let listing = ListingAccount {
+            is_initialized: true,
+            marketplace: Pubkey::new_unique(),
+            seller: Pubkey::new_unique(),
+            mint: Pubkey::new_unique(),
+            price: 1_000,
+            bump: 255,
+            escrow_bump,
+        };
+        let mut listing_data = listing.try_to_vec().unwrap();
+
+        let keys = [intruder_key, listing_key, escrow_key, Pubkey::new_unique(), token_program_key];
+        let mut lamports: Vec<u64> = vec![10_000; keys.len()];
+        let mut data: Vec<Vec<u8>> = vec![vec![]; keys.len()];
+        data[1] = std::mem::take(&mut listing_data);
+
+        let accounts: Vec<AccountInfo> = keys
+            .iter()
+            .zip(lamports.iter_mut())
+            .zip(data.iter_mut())
+            .enumerate()
+            .map(|(i, ((key, lamports), data))| {
+                AccountInfo::new(key, i == 0, true, lamports, data, &program_id, false, Epoch::default())
+            })
+            .collect();
+
+        let instruction_data = MarketplaceInstruction::Delist.try_to_vec().unwrap();
+        let result = process_instruction(&program_id, &accounts, &instruction_data);
+        assert_eq!(result, Err(MarketplaceError::Unauthorized.into()));
+    }
+}
@@ -0,0 +1,81 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+/// @dev The parts of the crowdfund example this contract reads
+interface ICrowdfund {
+    function owner() external view returns (address);
+    function goal() external view returns (uint256);
+    function deadline() external view returns (uint256);
+    function totalRaised() external view returns (uint256);
+}
+
+/**
+ * @title DonationMatcher
+ * @dev A sponsor escrows ETH to match new contributions to one crowdfund
+ * campaign at a fixed ratio, up to a cap. Anyone can call `crank` to match
+ * contributions made since the last call; grants are paid to the campaign
+ * owner once the goal is reached, and the sponsor recovers the rest after
+ * the campaign ends.
+ */
+contract DonationMatcher {
+    uint256 public constant FULL_MATCH_BPS = 10000;
+
+    address public immutable sponsor;
+    ICrowdfund public immutable campaign;
+    uint256 public immutable ratioBps;
+    uint256 public immutable cap;
+
+    uint256 public lastSeenRaised;
+    uint256 public matched;
+    uint256 public released;
+
+    event Matched(uint256 grant, uint256 matched);
+    event Released(address indexed owner, uint256 amount);
+    event Closed(uint256 returned);
+
+    constructor(ICrowdfund _campaign, uint256 _ratioBps) payable {
+        require(_ratioBps > 0 && _ratioBps <= FULL_MATCH_BPS && msg.value > 0, "Invalid parameters");
+        sponsor = msg.sender;
+        campaign = _campaign;
+        ratioBps = _ratioBps;
+        cap = msg.value;
+        // Only contributions made after registration are matched
+        lastSeenRaised = _campaign.totalRaised();
+    }
+
+    function crank() external {
+        uint256 raised = campaign.totalRaised();
+        if (raised > lastSeenRaised) {
+            uint256 grant = ((raised - lastSeenRaised) * ratioBps) / FULL_MATCH_BPS;
+            if (grant > cap - matched) {
+                grant = cap - matched;
+            }
+            lastSeenRaised = raised;
+            matched += grant;
+            emit Matched(grant, matched);
+        }
+
+        uint256 payout = matched - released;
+        if (raised >= campaign.goal() && payout > 0) {
+            released += payout;
+            address owner = campaign.owner();
+            emit Released(owner, payout);
+            (bool success, ) = payable(owner).call{value: payout}("");
+            require(success, "Transfer failed");
+        }
+    }
+
+    function close() external {
+        require(msg.sender == sponsor, "Only sponsor");
+        require(block.timestamp >= campaign.deadline(), "Campaign active");
+        uint256 raised = campaign.totalRaised();
+        if (raised >= campaign.goal()) {
+            require(lastSeenRaised == raised && matched == released, "Crank first");
+        }
+
+        uint256 amount = address(this).balance;
+        emit Closed(amount);
+        (bool success, ) = payable(sponsor).call{value: amount}("");
+        require(success, "Transfer failed");
+    }
+}
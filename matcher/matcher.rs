@@ -0,0 +1,439 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint,
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// Seed prefix for match PDAs: ["match", sponsor, campaign]
+pub const MATCH_SEED: &[u8] = b"match";
+
+/// 100% in basis points, i.e. a 1:1 match
+pub const FULL_MATCH_BPS: u16 = 10_000;
+
+/// Size of a `MatchAccount`
+pub const MATCH_ACCOUNT_SIZE: usize = 1 // is_initialized
+    + 32 // sponsor
+    + 32 // campaign_program
+    + 32 // campaign
+    + 2 // ratio_bps
+    + 8 // cap
+    + 8 // last_seen_raised
+    + 8 // matched
+    + 8 // released
+    + 1; // bump
+
+/// Errors returned by the program as `ProgramError::Custom` codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatcherError {
+    /// Ratio or cap is zero, or the ratio is above 1:1
+    InvalidParameters = 0,
+    /// The campaign account is not the registered one
+    WrongCampaign = 1,
+    /// The match cannot be closed yet
+    NotClosable = 2,
+    /// Only the sponsor can do this
+    Unauthorized = 3,
+    /// The account has not been initialized
+    NotInitialized = 4,
+    /// The recipient is not the campaign owner
+    WrongRecipient = 5,
+}
+
+impl From<MatcherError> for ProgramError {
+    fn from(e: MatcherError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+/// Read-only view of a campaign account in the crowdfund example.
+/// Field order must match `CrowdfundAccount` there.
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct CampaignState {
+    pub is_initialized: bool,
+    pub owner: Pubkey,
+    pub goal: u64,
+    pub deadline: u64, // slot number
+    pub total_raised: u64,
+    pub finalized: bool,
+}
+
+/// A sponsor's pledge to match one campaign; the PDA escrows `cap` lamports
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct MatchAccount {
+    pub is_initialized: bool,
+    pub sponsor: Pubkey,
+    /// Program that owns the campaign account
+    pub campaign_program: Pubkey,
+    pub campaign: Pubkey,
+    /// Lamports matched per lamport contributed, in basis points
+    pub ratio_bps: u16,
+    /// Most the sponsor will ever pay
+    pub cap: u64,
+    /// Campaign `total_raised` at the last crank
+    pub last_seen_raised: u64,
+    /// Total grant committed so far, never above `cap`
+    pub matched: u64,
+    /// Portion of `matched` already paid to the campaign owner
+    pub released: u64,
+    pub bump: u8,
+}
+
+/// Grant owed for `new_contributions`, limited by what is left of the cap
+pub fn matching_grant(new_contributions: u64, ratio_bps: u16, remaining_cap: u64) -> u64 {
+    let grant = new_contributions as u128 * ratio_bps as u128 / FULL_MATCH_BPS as u128;
+    grant.min(remaining_cap as u128) as u64
+}
+
+impl MatchAccount {
+    /// Match contributions made since the last crank; returns the newly committed grant
+    pub fn sync(&mut self, total_raised: u64) -> u64 {
+        let new_contributions = total_raised.saturating_sub(self.last_seen_raised);
+        self.last_seen_raised = self.last_seen_raised.max(total_raised);
+
+        let grant = matching_grant(new_contributions, self.ratio_bps, self.cap - self.matched);
+        self.matched += grant;
+        grant
+    }
+
+    /// Committed grant not yet paid out
+    pub fn unreleased(&self) -> u64 {
+        self.matched - self.released
+    }
+}
+
+entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = MatcherInstruction::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        MatcherInstruction::CreateMatch { ratio_bps, cap } => create_match(program_id, accounts, ratio_bps, cap),
+        MatcherInstruction::Crank => crank(program_id, accounts),
+        MatcherInstruction::Close => close(program_id, accounts),
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub enum MatcherInstruction {
+    /// Escrow `cap` lamports to match new contributions to a campaign at `ratio_bps`
+    /// Accounts: [writable, signer] sponsor, [writable] match PDA, [] campaign, [] system program
+    CreateMatch { ratio_bps: u16, cap: u64 },
+    /// Match contributions since the last crank and, once the campaign has met
+    /// its goal, pay the committed grant to its owner (anyone can call)
+    /// Accounts: [writable] match PDA, [] campaign, [writable] campaign owner
+    Crank,
+    /// Sponsor takes back the unspent escrow once the campaign has ended and
+    /// every contribution has been matched, or the campaign failed
+    /// Accounts: [writable, signer] sponsor, [writable] match PDA, [] campaign
+    Close,
+}
+
+fn create_match(program_id: &Pubkey, accounts: &[AccountInfo], ratio_bps: u16, cap: u64) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let sponsor = next_account_info(accounts_iter)?;
+    let match_account = next_account_info(accounts_iter)?;
+    let campaign_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !sponsor.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if ratio_bps == 0 || ratio_bps > FULL_MATCH_BPS || cap == 0 {
+        return Err(MatcherError::InvalidParameters.into());
+    }
+
+    let campaign = load_campaign(campaign_account)?;
+
+    let (expected_match, bump) = Pubkey::find_program_address(
+        &[MATCH_SEED, sponsor.key.as_ref(), campaign_account.key.as_ref()],
+        program_id,
+    );
+    if expected_match != *match_account.key {
+        msg!("Match account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    // The cap rides on top of rent, so the match PDA is the escrow
+    let lamports = Rent::get()?
+        .minimum_balance(MATCH_ACCOUNT_SIZE)
+        .checked_add(cap)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    invoke_signed(
+        &system_instruction::create_account(
+            sponsor.key,
+            match_account.key,
+            lamports,
+            MATCH_ACCOUNT_SIZE as u64,
+            program_id,
+        ),
+        &[sponsor.clone(), match_account.clone(), system_program.clone()],
+        &[&[MATCH_SEED, sponsor.key.as_ref(), campaign_account.key.as_ref(), &[bump]]],
+    )?;
+
+    // Only contributions made after registration are matched
+    let match_state = MatchAccount {
+        is_initialized: true,
+        sponsor: *sponsor.key,
+        campaign_program: *campaign_account.owner,
+        campaign: *campaign_account.key,
+        ratio_bps,
+        cap,
+        last_seen_raised: campaign.total_raised,
+        matched: 0,
+        released: 0,
+        bump,
+    };
+    match_state.serialize(&mut &mut match_account.data.borrow_mut()[..])?;
+
+    msg!("Matching {} bps of new contributions up to {}", ratio_bps, cap);
+
+    Ok(())
+}
+
+fn crank(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let match_account = next_account_info(accounts_iter)?;
+    let campaign_account = next_account_info(accounts_iter)?;
+    let campaign_owner = next_account_info(accounts_iter)?;
+
+    let mut match_state = load_match(program_id, match_account)?;
+    let campaign = load_registered_campaign(&match_state, campaign_account)?;
+
+    if campaign.owner != *campaign_owner.key {
+        return Err(MatcherError::WrongRecipient.into());
+    }
+
+    let grant = match_state.sync(campaign.total_raised);
+    if grant > 0 {
+        msg!("Committed {} more; {} of {} matched", grant, match_state.matched, match_state.cap);
+    }
+
+    // Grants are held until the goal is met, so a failed campaign costs the sponsor nothing
+    let payout = match_state.unreleased();
+    if campaign.total_raised >= campaign.goal && payout > 0 {
+        **match_account.try_borrow_mut_lamports()? = match_account
+            .lamports()
+            .checked_sub(payout)
+            .ok_or(ProgramError::InsufficientFunds)?;
+        **campaign_owner.try_borrow_mut_lamports()? = campaign_owner
+            .lamports()
+            .checked_add(payout)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        match_state.released += payout;
+
+        msg!("Released {} to {}", payout, campaign_owner.key);
+    }
+
+    match_state.serialize(&mut &mut match_account.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+fn close(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let sponsor = next_account_info(accounts_iter)?;
+    let match_account = next_account_info(accounts_iter)?;
+    let campaign_account = next_account_info(accounts_iter)?;
+
+    if !sponsor.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let match_state = load_match(program_id, match_account)?;
+
+    if match_state.sponsor != *sponsor.key {
+        msg!("Only the sponsor can close the match");
+        return Err(MatcherError::Unauthorized.into());
+    }
+
+    let campaign = load_registered_campaign(&match_state, campaign_account)?;
+
+    if Clock::get()?.slot < campaign.deadline {
+        msg!("Campaign runs until slot {}", campaign.deadline);
+        return Err(MatcherError::NotClosable.into());
+    }
+    let succeeded = campaign.total_raised >= campaign.goal;
+    if succeeded && (match_state.last_seen_raised < campaign.total_raised || match_state.unreleased() > 0) {
+        msg!("Crank the match before closing");
+        return Err(MatcherError::NotClosable.into());
+    }
+
+    let lamports = match_account.lamports();
+    **match_account.try_borrow_mut_lamports()? = 0;
+    **sponsor.try_borrow_mut_lamports()? = sponsor
+        .lamports()
+        .checked_add(lamports)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    match_account.data.borrow_mut().fill(0);
+
+    msg!("Match closed; returned {} to the sponsor", lamports);
+
+    Ok(())
+}
+
+fn load_match(program_id: &Pubkey, match_account: &AccountInfo) -> Result<MatchAccount, ProgramError> {
+    if match_account.owner != program_id {
+        msg!("Match account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let match_state = MatchAccount::try_from_slice(&match_account.data.borrow())?;
+
+    if !match_state.is_initialized {
+        return Err(MatcherError::NotInitialized.into());
+    }
+
+    Ok(match_state)
+}
+
+fn load_campaign(campaign_account: &AccountInfo) -> Result<CampaignState, ProgramError> {
+    // Campaign accounts may be allocated larger than the state they hold
+    let campaign = CampaignState::deserialize(&mut &campaign_account.data.borrow()[..])?;
+
+    if !campaign.is_initialized {
+        return Err(MatcherError::NotInitialized.into());
+    }
+
+    Ok(campaign)
+}
+
+/// Read the campaign only if it is the registered account, still owned by the
+/// registered program, so nobody can substitute a fake with inflated totals
+fn load_registered_campaign(
+    match_state: &MatchAccount,
+    campaign_account: &AccountInfo,
+) -> Result<CampaignState, ProgramError> {
+    if *campaign_account.key != match_state.campaign || *campaign_account.owner != match_state.campaign_program {
+        msg!("Campaign account is not the one registered for this match");
+        return Err(MatcherError::WrongCampaign.into());
+    }
+
+    load_campaign(campaign_account)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_program::clock::Epoch;
+
+    fn new_match(campaign_program: Pubkey, campaign: Pubkey) -> MatchAccount {
+        MatchAccount {
+            is_initialized: true,
+            sponsor: Pubkey::new_unique(),
+            campaign_program,
+            campaign,
+            ratio_bps: 5_000,
+            cap: 1_000,
+            last_seen_raised: 400,
+            matched: 0,
+            released: 0,
+            bump: 255,
+        }
+    }
+
+    #[test]
+    fn test_sync_matches_up_to_cap() {
+        let mut match_state = new_match(Pubkey::new_unique(), Pubkey::new_unique());
+
+        // Half of the 600 raised since registration
+        assert_eq!(match_state.sync(1_000), 300);
+        assert_eq!(match_state.sync(1_000), 0);
+
+        // Only 700 of the cap is left
+        assert_eq!(match_state.sync(5_000), 700);
+        assert_eq!(match_state.matched, 1_000);
+        assert_eq!(match_state.sync(6_000), 0);
+
+        assert_eq!(matching_grant(3, 5_000, 10), 1);
+        assert_eq!(match_state.try_to_vec().unwrap().len(), MATCH_ACCOUNT_SIZE);
+    }
+
+    #[test]
+    fn test_crank_releases_after_goal() {
+        let program_id = Pubkey::new_unique();
+        let campaign_program = Pubkey::new_unique();
+        let system_program = Pubkey::default();
+        let match_key = Pubkey::new_unique();
+        let campaign_key = Pubkey::new_unique();
+        let campaign_owner = Pubkey::new_unique();
+
+        let mut campaign = CampaignState {
+            is_initialized: true,
+            owner: campaign_owner,
+            goal: 2_000,
+            deadline: 100,
+            total_raised: 1_000,
+            finalized: false,
+        };
+
+        let mut match_lamports = 1_000;
+        let mut match_data = new_match(campaign_program, campaign_key).try_to_vec().unwrap();
+        let mut campaign_lamports = 0;
+        let mut campaign_data = campaign.try_to_vec().unwrap();
+        let mut owner_lamports = 0;
+        let mut owner_data = vec![];
+
+        let match_info = AccountInfo::new(
+            &match_key,
+            false,
+            true,
+            &mut match_lamports,
+            &mut match_data,
+            &program_id,
+            false,
+            Epoch::default(),
+        );
+        let campaign_info = AccountInfo::new(
+            &campaign_key,
+            false,
+            false,
+            &mut campaign_lamports,
+            &mut campaign_data,
+            &campaign_program,
+            false,
+            Epoch::default(),
+        );
+        let owner_info = AccountInfo::new(
+            &campaign_owner,
+            false,
+            true,
+            &mut owner_lamports,
+            &mut owner_data,
+            &system_program,
+            false,
+            Epoch::default(),
+        );
+        let accounts = vec![match_info, campaign_info, owner_info];
+        let instruction_data = MatcherInstruction::Crank.try_to_vec().unwrap();
+
+        // Below the goal the grant is committed but held
+        process_instruction(&program_id, &accounts, &instruction_data).unwrap();
+        assert_eq!(accounts[2].lamports(), 0);
+
+        campaign.total_raised = 2_000;
+        campaign.serialize(&mut &mut accounts[1].data.borrow_mut()[..]).unwrap();
+        process_instruction(&program_id, &accounts, &instruction_data).unwrap();
+        assert_eq!(accounts[2].lamports(), 800);
+        assert_eq!(accounts[0].lamports(), 200);
+
+        let match_state = MatchAccount::try_from_slice(&accounts[0].data.borrow()).unwrap();
+        assert_eq!(match_state.matched, 800);
+        assert_eq!(match_state.released, 800);
+    }
+}
@@ -0,0 +1,55 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+/**
+ * @title InvoicePayments
+ * @dev Invoices that must be paid with a matching reference. A contract
+ * cannot see the other calls in a transaction, so the reference is passed as
+ * an argument and emitted, where Solana checks for a separate memo instruction.
+ */
+contract InvoicePayments {
+    uint256 public constant MAX_REFERENCE_LEN = 64;
+
+    struct Invoice {
+        address payee;
+        uint256 amount;
+        string reference;
+        address paidBy;
+    }
+
+    // payee => invoiceId => invoice
+    mapping(address => mapping(uint64 => Invoice)) public invoices;
+
+    event InvoiceCreated(address indexed payee, uint64 indexed invoiceId, uint256 amount, string reference);
+    event InvoicePaid(address indexed payee, uint64 indexed invoiceId, address indexed payer, string memo);
+
+    function createInvoice(uint64 invoiceId, uint256 amount, string calldata reference) external {
+        require(amount > 0, "Invalid amount");
+        require(bytes(reference).length > 0 && bytes(reference).length <= MAX_REFERENCE_LEN, "Invalid reference");
+        require(invoices[msg.sender][invoiceId].payee == address(0), "Invoice exists");
+
+        invoices[msg.sender][invoiceId] = Invoice({
+            payee: msg.sender,
+            amount: amount,
+            reference: reference,
+            paidBy: address(0)
+        });
+
+        emit InvoiceCreated(msg.sender, invoiceId, amount, reference);
+    }
+
+    /// Pay an invoice; `memo` must equal its reference
+    function pay(address payee, uint64 invoiceId, string calldata memo) external payable {
+        Invoice storage invoice = invoices[payee][invoiceId];
+        require(invoice.payee != address(0), "No invoice");
+        require(invoice.paidBy == address(0), "Already paid");
+        require(msg.value == invoice.amount, "Wrong amount");
+        require(keccak256(bytes(memo)) == keccak256(bytes(invoice.reference)), "Memo does not match");
+
+        invoice.paidBy = msg.sender;
+        emit InvoicePaid(payee, invoiceId, msg.sender, memo);
+
+        (bool success, ) = payable(payee).call{value: msg.value}("");
+        require(success, "Transfer failed");
+    }
+}
@@ -0,0 +1,334 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint,
+    entrypoint::ProgramResult,
+    instruction::Instruction,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    pubkey,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::{self, instructions::load_instruction_at_checked, Sysvar},
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// SPL Memo program (v2), which checks that every account passed to it signed
+pub const SPL_MEMO_ID: Pubkey = pubkey!("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
+
+/// SPL Memo program (v1), still accepted by wallets and explorers
+pub const SPL_MEMO_V1_ID: Pubkey = pubkey!("Memo1UhkJRfHyvLMcVucJwxXeuD728EqVDDwQDxFMNo");
+
+/// Seed prefix for invoice PDAs: ["invoice", payee, invoice_id (u64 LE)]
+pub const INVOICE_SEED: &[u8] = b"invoice";
+
+/// Longest payment reference, in bytes
+pub const MAX_REFERENCE_LEN: usize = 64;
+
+/// Size of an `InvoiceAccount` with the longest reference
+pub const INVOICE_ACCOUNT_SIZE: usize = 1 // is_initialized
+    + 32 // payee
+    + 8 // invoice_id
+    + 8 // amount
+    + 4 + MAX_REFERENCE_LEN // reference
+    + 1 + 32 // paid_by
+    + 1; // bump
+
+/// Errors returned by the program as `ProgramError::Custom` codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemoError {
+    /// Reference is empty or longer than `MAX_REFERENCE_LEN`
+    InvalidReference = 0,
+    /// The transaction has no memo with the invoice reference signed by the payer
+    MissingMemo = 1,
+    /// The invoice has already been paid
+    AlreadyPaid = 2,
+    /// The invoice has not been initialized
+    NotInitialized = 3,
+    /// The payee account is not the invoice's payee
+    InvalidPayee = 4,
+    /// Amount must be greater than zero
+    InvalidAmount = 5,
+}
+
+impl From<MemoError> for ProgramError {
+    fn from(e: MemoError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+/// A payment request. Paying it requires the payer to attach the reference
+/// as a memo, so the tag is recorded in the transaction for compliance tooling.
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct InvoiceAccount {
+    pub is_initialized: bool,
+    pub payee: Pubkey,
+    pub invoice_id: u64,
+    pub amount: u64,
+    /// Exact memo text the payment must carry
+    pub reference: String,
+    pub paid_by: Option<Pubkey>,
+    pub bump: u8,
+}
+
+/// Whether `ix` is a memo of exactly `reference`, signed by `signer`
+pub fn is_signed_memo(ix: &Instruction, reference: &str, signer: &Pubkey) -> bool {
+    (ix.program_id == SPL_MEMO_ID || ix.program_id == SPL_MEMO_V1_ID)
+        && ix.data == reference.as_bytes()
+        && ix
+            .accounts
+            .iter()
+            .any(|meta| meta.pubkey == *signer && meta.is_signer)
+}
+
+entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = MemoInstruction::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        MemoInstruction::CreateInvoice { invoice_id, amount, reference } => {
+            create_invoice(program_id, accounts, invoice_id, amount, reference)
+        }
+        MemoInstruction::Pay => pay(program_id, accounts),
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub enum MemoInstruction {
+    /// Request `amount` lamports, tagged with `reference`
+    /// Accounts: [writable, signer] payee, [writable] invoice PDA, [] system program
+    CreateInvoice { invoice_id: u64, amount: u64, reference: String },
+    /// Pay an invoice. The same transaction must contain an SPL Memo whose
+    /// text is the invoice reference and whose accounts include the payer as signer.
+    /// Accounts: [writable, signer] payer, [writable] payee, [writable] invoice PDA, [] instructions sysvar, [] system program
+    Pay,
+}
+
+fn create_invoice(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    invoice_id: u64,
+    amount: u64,
+    reference: String,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let payee = next_account_info(accounts_iter)?;
+    let invoice_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !payee.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if amount == 0 {
+        return Err(MemoError::InvalidAmount.into());
+    }
+
+    if reference.is_empty() || reference.len() > MAX_REFERENCE_LEN {
+        msg!("Reference must be 1 to {} bytes", MAX_REFERENCE_LEN);
+        return Err(MemoError::InvalidReference.into());
+    }
+
+    let id_bytes = invoice_id.to_le_bytes();
+    let (expected_invoice, bump) =
+        Pubkey::find_program_address(&[INVOICE_SEED, payee.key.as_ref(), &id_bytes], program_id);
+    if expected_invoice != *invoice_account.key {
+        msg!("Invoice account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    invoke_signed(
+        &system_instruction::create_account(
+            payee.key,
+            invoice_account.key,
+            Rent::get()?.minimum_balance(INVOICE_ACCOUNT_SIZE),
+            INVOICE_ACCOUNT_SIZE as u64,
+            program_id,
+        ),
+        &[payee.clone(), invoice_account.clone(), system_program.clone()],
+        &[&[INVOICE_SEED, payee.key.as_ref(), &id_bytes, &[bump]]],
+    )?;
+
+    let invoice = InvoiceAccount {
+        is_initialized: true,
+        payee: *payee.key,
+        invoice_id,
+        amount,
+        reference,
+        paid_by: None,
+        bump,
+    };
+    invoice.serialize(&mut &mut invoice_account.data.borrow_mut()[..])?;
+
+    msg!("Invoice {} created for {} lamports", invoice_id, amount);
+
+    Ok(())
+}
+
+fn pay(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let payer = next_account_info(accounts_iter)?;
+    let payee = next_account_info(accounts_iter)?;
+    let invoice_account = next_account_info(accounts_iter)?;
+    let instructions_sysvar = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !payer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut invoice = load_invoice(program_id, invoice_account)?;
+
+    if invoice.payee != *payee.key {
+        return Err(MemoError::InvalidPayee.into());
+    }
+
+    if invoice.paid_by.is_some() {
+        return Err(MemoError::AlreadyPaid.into());
+    }
+
+    require_memo(instructions_sysvar, &invoice.reference, payer.key)?;
+
+    invoke(
+        &system_instruction::transfer(payer.key, payee.key, invoice.amount),
+        &[payer.clone(), payee.clone(), system_program.clone()],
+    )?;
+
+    invoice.paid_by = Some(*payer.key);
+    invoice.serialize(&mut &mut invoice_account.data.borrow_mut()[..])?;
+
+    msg!("Invoice {} paid with reference {}", invoice.invoice_id, invoice.reference);
+
+    Ok(())
+}
+
+/// Require a memo of `reference` signed by `signer` anywhere in the transaction.
+/// Only top-level instructions are visible, so the memo cannot come from a CPI.
+fn require_memo(instructions_sysvar: &AccountInfo, reference: &str, signer: &Pubkey) -> ProgramResult {
+    if *instructions_sysvar.key != sysvar::instructions::id() {
+        msg!("Expected the instructions sysvar");
+        return Err(ProgramError::UnsupportedSysvar);
+    }
+
+    let found = (0..)
+        .map_while(|index| load_instruction_at_checked(index, instructions_sysvar).ok())
+        .any(|ix| is_signed_memo(&ix, reference, signer));
+
+    if !found {
+        msg!("Expected a memo \"{}\" signed by {}", reference, signer);
+        return Err(MemoError::MissingMemo.into());
+    }
+
+    Ok(())
+}
+
+/// Validate the invoice account and return it
+fn load_invoice(program_id: &Pubkey, invoice_account: &AccountInfo) -> Result<InvoiceAccount, ProgramError> {
+    if invoice_account.owner != program_id {
+        msg!("Invoice account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let invoice = InvoiceAccount::deserialize(&mut &invoice_account.data.borrow()[..])?;
+
+    if !invoice.is_initialized {
+        return Err(MemoError::NotInitialized.into());
+    }
+
+    Ok(invoice)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_program::{
+        clock::Epoch,
+        instruction::AccountMeta,
+        sysvar::instructions::{construct_instructions_data, BorrowedAccountMeta, BorrowedInstruction},
+    };
+
+    #[test]
+    fn test_is_signed_memo() {
+        let payer = Pubkey::new_unique();
+        let memo = |program_id: Pubkey, data: &[u8], is_signer: bool| {
+            Instruction::new_with_bytes(program_id, data, vec![AccountMeta::new_readonly(payer, is_signer)])
+        };
+
+        assert!(is_signed_memo(&memo(SPL_MEMO_ID, b"INV-42", true), "INV-42", &payer));
+        assert!(is_signed_memo(&memo(SPL_MEMO_V1_ID, b"INV-42", true), "INV-42", &payer));
+        // Wrong text, unsigned, another signer, or another program
+        assert!(!is_signed_memo(&memo(SPL_MEMO_ID, b"INV-43", true), "INV-42", &payer));
+        assert!(!is_signed_memo(&memo(SPL_MEMO_ID, b"INV-42", false), "INV-42", &payer));
+        assert!(!is_signed_memo(&memo(SPL_MEMO_ID, b"INV-42", true), "INV-42", &Pubkey::new_unique()));
+        assert!(!is_signed_memo(&memo(Pubkey::new_unique(), b"INV-42", true), "INV-42", &payer));
+    }
+
+    #[test]
+    fn test_pay_without_matching_memo_fails() {
+        let program_id = Pubkey::new_unique();
+        let payer_key = Pubkey::new_unique();
+        let payee_key = Pubkey::new_unique();
+
+        let invoice = InvoiceAccount {
+            is_initialized: true,
+            payee: payee_key,
+            invoice_id: 42,
+            amount: 1_000,
+            reference: "INV-42".to_string(),
+            paid_by: None,
+            bump: 255,
+        };
+        let mut invoice_data = invoice.try_to_vec().unwrap();
+        invoice_data.resize(INVOICE_ACCOUNT_SIZE, 0);
+
+        // The transaction tags the payment with another invoice's reference
+        let memo_id = SPL_MEMO_ID;
+        let pay_data = MemoInstruction::Pay.try_to_vec().unwrap();
+        let sysvar_data = construct_instructions_data(&[
+            BorrowedInstruction {
+                program_id: &memo_id,
+                accounts: vec![BorrowedAccountMeta {
+                    pubkey: &payer_key,
+                    is_signer: true,
+                    is_writable: false,
+                }],
+                data: b"INV-41",
+            },
+            BorrowedInstruction {
+                program_id: &program_id,
+                accounts: vec![],
+                data: &pay_data,
+            },
+        ]);
+
+        let keys = [
+            payer_key,
+            payee_key,
+            Pubkey::new_unique(),
+            sysvar::instructions::id(),
+            Pubkey::default(),
+        ];
+        let mut lamports: Vec<u64> = vec![10_000, 0, 1_000_000, 0, 1];
+        let mut data: Vec<Vec<u8>> = vec![vec![], vec![], invoice_data, sysvar_data, vec![]];
+
+        let accounts: Vec<AccountInfo> = keys
+            .iter()
+            .zip(lamports.iter_mut())
+            .zip(data.iter_mut())
+            .enumerate()
+            .map(|(i, ((key, lamports), data))| {
+                AccountInfo::new(key, i == 0, i < 3, lamports, data, &program_id, false, Epoch::default())
+            })
+            .collect();
+
+        let result = process_instruction(&program_id, &accounts, &pay_data);
+        assert_eq!(result, Err(MemoError::MissingMemo.into()));
+    }
+}
@@ -0,0 +1,91 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+/**
+ * @title ProfilesV1
+ * @dev First implementation behind an upgradeable proxy
+ */
+contract ProfilesV1 {
+    struct Profile {
+        bool isInitialized;
+        uint64 points;
+    }
+
+    mapping(address => Profile) public profiles;
+
+    event ProfileCreated(address indexed owner);
+    event PointsAdded(address indexed owner, uint64 amount, uint64 total);
+
+    function initialize() external {
+        require(!profiles[msg.sender].isInitialized, "Profile exists");
+        profiles[msg.sender].isInitialized = true;
+        emit ProfileCreated(msg.sender);
+    }
+
+    function addPoints(uint64 amount) external virtual {
+        Profile storage profile = profiles[msg.sender];
+        require(profile.isInitialized, "No profile");
+        profile.points += amount;
+        emit PointsAdded(msg.sender, amount, profile.points);
+    }
+}
+
+/**
+ * @title ProfilesV2
+ * @dev Second implementation. The proxy keeps V1's storage, so new fields may
+ * only be appended: to the struct (each mapping entry has room after it) and
+ * as new state variables after the existing ones. Existing entries read the
+ * new fields as zero, so no per-profile migration transaction is needed.
+ */
+contract ProfilesV2 {
+    uint64 public constant POINTS_PER_LEVEL = 1_000;
+
+    struct Profile {
+        bool isInitialized;
+        uint64 points;
+        // Appended in V2
+        uint8 level;
+        uint64 lastActive;
+        address referrer;
+    }
+
+    mapping(address => Profile) public profiles;
+    // Appended in V2
+    uint8 public storageVersion;
+
+    event ProfileCreated(address indexed owner);
+    event PointsAdded(address indexed owner, uint64 amount, uint64 total);
+    event Upgraded(uint8 version);
+
+    /**
+     * @dev Run once through the proxy after the upgrade
+     */
+    function upgradeToV2() external {
+        require(storageVersion < 2, "Already upgraded");
+        storageVersion = 2;
+        emit Upgraded(2);
+    }
+
+    function initialize(address referrer) external {
+        require(!profiles[msg.sender].isInitialized, "Profile exists");
+        profiles[msg.sender] = Profile(true, 0, 0, uint64(block.timestamp), referrer);
+        emit ProfileCreated(msg.sender);
+    }
+
+    function addPoints(uint64 amount) external {
+        Profile storage profile = profiles[msg.sender];
+        require(profile.isInitialized, "No profile");
+
+        profile.points += amount;
+        // Profiles from V1 get their level filled in on their first update
+        profile.level = levelFor(profile.points);
+        profile.lastActive = uint64(block.timestamp);
+
+        emit PointsAdded(msg.sender, amount, profile.points);
+    }
+
+    function levelFor(uint64 points) public pure returns (uint8) {
+        uint64 level = points / POINTS_PER_LEVEL;
+        return level > type(uint8).max ? type(uint8).max : uint8(level);
+    }
+}
@@ -0,0 +1,336 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint,
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// Seed prefix for profile PDAs: ["profile", owner]
+pub const PROFILE_SEED: &[u8] = b"profile";
+
+/// Size of a `ProfileV1`, the layout shipped in the first deployment
+pub const PROFILE_V1_SIZE: usize = 1 // is_initialized
+    + 32 // owner
+    + 8 // points
+    + 1; // bump
+
+/// Size of a `ProfileV2`
+pub const PROFILE_V2_SIZE: usize = 1 // version
+    + 32 // owner
+    + 8 // points
+    + 1 // bump
+    + 1 // level
+    + 8 // last_active
+    + 32; // referrer
+
+/// Points needed per level
+pub const POINTS_PER_LEVEL: u64 = 1_000;
+
+/// Errors returned by the program as `ProgramError::Custom` codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MigrationError {
+    /// The account still has the V1 layout; run `MigrateV1ToV2` first
+    NeedsMigration = 0,
+    /// The account already has the V2 layout
+    AlreadyMigrated = 1,
+    /// Only the profile owner can update it
+    Unauthorized = 2,
+    /// The profile has not been initialized
+    NotInitialized = 3,
+}
+
+impl From<MigrationError> for ProgramError {
+    fn from(e: MigrationError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+/// Original layout. Its leading `is_initialized` byte is always 1 for live
+/// accounts, so V2 reuses that byte as a version number.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ProfileV1 {
+    pub is_initialized: bool,
+    pub owner: Pubkey,
+    pub points: u64,
+    pub bump: u8,
+}
+
+/// Current layout: V1's fields in the same positions, with new fields appended
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ProfileV2 {
+    /// 2 for this layout; 0 means uninitialized
+    pub version: u8,
+    pub owner: Pubkey,
+    pub points: u64,
+    pub bump: u8,
+    pub level: u8,
+    /// Unix timestamp of the last update, 0 if not updated since migration
+    pub last_active: i64,
+    /// `Pubkey::default()` if none
+    pub referrer: Pubkey,
+}
+
+/// A profile in whichever layout its account holds
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Profile {
+    V1(ProfileV1),
+    V2(ProfileV2),
+}
+
+impl Profile {
+    /// Decode an account by its version byte, checking the length matches the layout
+    pub fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        match (data.first(), data.len()) {
+            (Some(0), _) | (None, _) => Err(MigrationError::NotInitialized.into()),
+            (Some(1), PROFILE_V1_SIZE) => Ok(Profile::V1(ProfileV1::try_from_slice(data)?)),
+            (Some(2), PROFILE_V2_SIZE) => Ok(Profile::V2(ProfileV2::try_from_slice(data)?)),
+            (Some(version), len) => {
+                msg!("Unknown profile version {} with {} bytes", version, len);
+                Err(ProgramError::InvalidAccountData)
+            }
+        }
+    }
+}
+
+pub fn level_for(points: u64) -> u8 {
+    (points / POINTS_PER_LEVEL).min(u8::MAX as u64) as u8
+}
+
+/// Fill V2's new fields with defaults derived from V1 data
+pub fn migrate(v1: ProfileV1) -> ProfileV2 {
+    ProfileV2 {
+        version: 2,
+        owner: v1.owner,
+        points: v1.points,
+        bump: v1.bump,
+        level: level_for(v1.points),
+        last_active: 0,
+        referrer: Pubkey::default(),
+    }
+}
+
+entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = MigrationInstruction::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        MigrationInstruction::Initialize { referrer } => initialize(program_id, accounts, referrer),
+        MigrationInstruction::AddPoints { amount } => add_points(program_id, accounts, amount),
+        MigrationInstruction::MigrateV1ToV2 => migrate_v1_to_v2(program_id, accounts),
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub enum MigrationInstruction {
+    /// Create a profile; new profiles start in the V2 layout
+    /// Accounts: [writable, signer] owner, [writable] profile PDA, [] system program
+    Initialize { referrer: Option<Pubkey> },
+    /// Add points and recompute the level. Only accepts V2 profiles.
+    /// Accounts: [signer] owner, [writable] profile PDA
+    AddPoints { amount: u64 },
+    /// Grow a V1 profile to V2 in place. Anyone may pay for it, since the result
+    /// depends only on the account's own data.
+    /// Accounts: [writable, signer] payer, [writable] profile PDA, [] system program
+    MigrateV1ToV2,
+}
+
+fn initialize(program_id: &Pubkey, accounts: &[AccountInfo], referrer: Option<Pubkey>) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let owner = next_account_info(accounts_iter)?;
+    let profile_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let (expected_profile, bump) = Pubkey::find_program_address(&[PROFILE_SEED, owner.key.as_ref()], program_id);
+    if expected_profile != *profile_account.key {
+        msg!("Profile account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    invoke_signed(
+        &system_instruction::create_account(
+            owner.key,
+            profile_account.key,
+            Rent::get()?.minimum_balance(PROFILE_V2_SIZE),
+            PROFILE_V2_SIZE as u64,
+            program_id,
+        ),
+        &[owner.clone(), profile_account.clone(), system_program.clone()],
+        &[&[PROFILE_SEED, owner.key.as_ref(), &[bump]]],
+    )?;
+
+    let profile = ProfileV2 {
+        version: 2,
+        owner: *owner.key,
+        points: 0,
+        bump,
+        level: 0,
+        last_active: Clock::get()?.unix_timestamp,
+        referrer: referrer.unwrap_or_default(),
+    };
+    profile.serialize(&mut &mut profile_account.data.borrow_mut()[..])?;
+
+    msg!("Profile created for {}", owner.key);
+
+    Ok(())
+}
+
+fn add_points(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let owner = next_account_info(accounts_iter)?;
+    let profile_account = next_account_info(accounts_iter)?;
+
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if profile_account.owner != program_id {
+        msg!("Profile account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let mut profile = match Profile::unpack(&profile_account.data.borrow())? {
+        Profile::V2(profile) => profile,
+        Profile::V1(_) => {
+            msg!("Profile uses the V1 layout; migrate it first");
+            return Err(MigrationError::NeedsMigration.into());
+        }
+    };
+
+    if profile.owner != *owner.key {
+        return Err(MigrationError::Unauthorized.into());
+    }
+
+    profile.points = profile.points.checked_add(amount).ok_or(ProgramError::ArithmeticOverflow)?;
+    profile.level = level_for(profile.points);
+    profile.last_active = Clock::get()?.unix_timestamp;
+    profile.serialize(&mut &mut profile_account.data.borrow_mut()[..])?;
+
+    msg!("Profile has {} points, level {}", profile.points, profile.level);
+
+    Ok(())
+}
+
+fn migrate_v1_to_v2(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let payer = next_account_info(accounts_iter)?;
+    let profile_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !payer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if profile_account.owner != program_id {
+        msg!("Profile account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let v1 = match Profile::unpack(&profile_account.data.borrow())? {
+        Profile::V1(v1) => v1,
+        Profile::V2(_) => return Err(MigrationError::AlreadyMigrated.into()),
+    };
+
+    // Fund the larger size before growing, then write the whole V2 layout
+    let required = Rent::get()?.minimum_balance(PROFILE_V2_SIZE);
+    let shortfall = required.saturating_sub(profile_account.lamports());
+    if shortfall > 0 {
+        invoke(
+            &system_instruction::transfer(payer.key, profile_account.key, shortfall),
+            &[payer.clone(), profile_account.clone(), system_program.clone()],
+        )?;
+    }
+    profile_account.realloc(PROFILE_V2_SIZE, true)?;
+
+    let v2 = migrate(v1);
+    v2.serialize(&mut &mut profile_account.data.borrow_mut()[..])?;
+
+    msg!("Profile {} migrated to V2 (level {})", profile_account.key, v2.level);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_program::clock::Epoch;
+
+    /// A V1 profile as written by the first deployment: owner [7; 32],
+    /// 2_500 points, bump 254
+    const V1_FIXTURE: [u8; PROFILE_V1_SIZE] = [
+        1, // is_initialized
+        7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, // owner
+        0xc4, 0x09, 0, 0, 0, 0, 0, 0, // points
+        254, // bump
+    ];
+
+    #[test]
+    fn test_migrates_v1_fixture() {
+        let v1 = match Profile::unpack(&V1_FIXTURE).unwrap() {
+            Profile::V1(v1) => v1,
+            other => panic!("expected V1, got {:?}", other),
+        };
+        assert_eq!(v1.owner, Pubkey::new_from_array([7; 32]));
+        assert_eq!(v1.points, 2_500);
+
+        let v2 = migrate(v1);
+        assert_eq!(v2.version, 2);
+        assert_eq!(v2.owner, Pubkey::new_from_array([7; 32]));
+        assert_eq!(v2.points, 2_500);
+        assert_eq!(v2.bump, 254);
+        assert_eq!(v2.level, 2);
+        assert_eq!(v2.referrer, Pubkey::default());
+
+        // V1's fields keep their offsets, so the migrated bytes start with the fixture
+        let bytes = v2.try_to_vec().unwrap();
+        assert_eq!(bytes.len(), PROFILE_V2_SIZE);
+        assert_eq!(bytes[1..PROFILE_V1_SIZE], V1_FIXTURE[1..]);
+        assert_eq!(Profile::unpack(&bytes).unwrap(), Profile::V2(v2));
+
+        // A version byte that does not match the length is rejected
+        assert_eq!(Profile::unpack(&bytes[..PROFILE_V1_SIZE]), Err(ProgramError::InvalidAccountData));
+        assert_eq!(Profile::unpack(&[0; PROFILE_V1_SIZE]), Err(MigrationError::NotInitialized.into()));
+    }
+
+    #[test]
+    fn test_add_points_rejects_unmigrated_profile() {
+        let program_id = Pubkey::new_unique();
+        let owner_key = Pubkey::new_from_array([7; 32]);
+
+        let keys = [owner_key, Pubkey::new_unique()];
+        let mut lamports: Vec<u64> = vec![10_000; keys.len()];
+        let mut data: Vec<Vec<u8>> = vec![vec![]; keys.len()];
+        data[1] = V1_FIXTURE.to_vec();
+
+        let accounts: Vec<AccountInfo> = keys
+            .iter()
+            .zip(lamports.iter_mut())
+            .zip(data.iter_mut())
+            .enumerate()
+            .map(|(i, ((key, lamports), data))| {
+                AccountInfo::new(key, i == 0, true, lamports, data, &program_id, false, Epoch::default())
+            })
+            .collect();
+
+        let instruction_data = MigrationInstruction::AddPoints { amount: 100 }.try_to_vec().unwrap();
+        let result = process_instruction(&program_id, &accounts, &instruction_data);
+        assert_eq!(result, Err(MigrationError::NeedsMigration.into()));
+    }
+}
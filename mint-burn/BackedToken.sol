@@ -0,0 +1,55 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+/**
+ * @title BackedToken
+ * @dev A token fully backed by ETH: deposit ETH to mint 1:1, burn to redeem.
+ * The contract is the only minter, and every operation checks that its ETH
+ * balance covers the total supply.
+ */
+contract BackedToken {
+    string public constant name = "Backed Ether";
+    string public constant symbol = "bETH";
+    uint8 public constant decimals = 18;
+
+    uint256 public totalSupply;
+    mapping(address => uint256) public balanceOf;
+
+    event Transfer(address indexed from, address indexed to, uint256 value);
+    event Minted(address indexed account, uint256 amount);
+    event Burned(address indexed account, uint256 amount);
+
+    modifier collateralized() {
+        require(address(this).balance >= totalSupply, "Undercollateralized");
+        _;
+        require(address(this).balance >= totalSupply, "Undercollateralized");
+    }
+
+    function mint() external payable collateralized {
+        require(msg.value > 0, "Invalid amount");
+        totalSupply += msg.value;
+        balanceOf[msg.sender] += msg.value;
+        emit Transfer(address(0), msg.sender, msg.value);
+        emit Minted(msg.sender, msg.value);
+    }
+
+    function burn(uint256 amount) external collateralized {
+        require(amount > 0, "Invalid amount");
+        require(balanceOf[msg.sender] >= amount, "Insufficient balance");
+        balanceOf[msg.sender] -= amount;
+        totalSupply -= amount;
+        emit Transfer(msg.sender, address(0), amount);
+        emit Burned(msg.sender, amount);
+
+        (bool success, ) = payable(msg.sender).call{value: amount}("");
+        require(success, "Transfer failed");
+    }
+
+    function transfer(address to, uint256 amount) external returns (bool) {
+        require(balanceOf[msg.sender] >= amount, "Insufficient balance");
+        balanceOf[msg.sender] -= amount;
+        balanceOf[to] += amount;
+        emit Transfer(msg.sender, to, amount);
+        return true;
+    }
+}
@@ -0,0 +1,399 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint,
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use spl_token::state::Mint;
+
+/// Seed prefix for reserve PDAs: ["reserve", authority]
+pub const RESERVE_SEED: &[u8] = b"reserve";
+
+/// Seed prefix for the reserve's mint PDA: ["mint", reserve]
+pub const MINT_SEED: &[u8] = b"mint";
+
+/// Same as SOL, so one base unit of the token is backed by one lamport
+pub const DECIMALS: u8 = 9;
+
+/// Size of a `ReserveAccount`
+pub const RESERVE_ACCOUNT_SIZE: usize = 1 // is_initialized
+    + 32 // authority
+    + 32 // mint
+    + 1 // bump
+    + 1; // mint_bump
+
+/// Errors returned by the program as `ProgramError::Custom` codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MintBurnError {
+    /// Amount is zero
+    InvalidAmount = 0,
+    /// The reserve holds fewer lamports than the token supply
+    Undercollateralized = 1,
+    /// The reserve has not been initialized
+    NotInitialized = 2,
+    /// The mint does not belong to this reserve
+    WrongMint = 3,
+}
+
+impl From<MintBurnError> for ProgramError {
+    fn from(e: MintBurnError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+/// Lamport collateral for a token; this PDA is the mint authority of `mint`
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct ReserveAccount {
+    pub is_initialized: bool,
+    pub authority: Pubkey,
+    pub mint: Pubkey,
+    pub bump: u8,
+    pub mint_bump: u8,
+}
+
+/// Lamports backing the supply: the reserve's balance above its rent-exempt minimum
+pub fn collateral(reserve_lamports: u64, rent: &Rent) -> u64 {
+    reserve_lamports.saturating_sub(rent.minimum_balance(RESERVE_ACCOUNT_SIZE))
+}
+
+/// Whether every token in `supply` is backed by a lamport. Extra lamports
+/// (donations) are allowed
+pub fn is_collateralized(reserve_lamports: u64, supply: u64, rent: &Rent) -> bool {
+    collateral(reserve_lamports, rent) >= supply
+}
+
+/// Check the invariant against the live reserve and mint accounts
+pub fn check_collateralization(reserve: &AccountInfo, mint: &AccountInfo, rent: &Rent) -> ProgramResult {
+    let supply = Mint::unpack(&mint.data.borrow())?.supply;
+    if !is_collateralized(reserve.lamports(), supply, rent) {
+        msg!(
+            "Supply {} exceeds collateral {}",
+            supply,
+            collateral(reserve.lamports(), rent)
+        );
+        return Err(MintBurnError::Undercollateralized.into());
+    }
+
+    Ok(())
+}
+
+entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = MintBurnInstruction::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        MintBurnInstruction::Initialize => initialize(program_id, accounts),
+        MintBurnInstruction::Mint { amount } => mint(program_id, accounts, amount),
+        MintBurnInstruction::Burn { amount } => burn(program_id, accounts, amount),
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub enum MintBurnInstruction {
+    /// Create the reserve PDA and a mint whose authority is the reserve PDA
+    /// Accounts: [writable, signer] authority, [writable] reserve PDA, [writable] mint PDA,
+    /// [] token program, [] system program
+    Initialize,
+    /// Deposit `amount` lamports into the reserve and mint `amount` tokens
+    /// Accounts: [writable, signer] user, [writable] reserve PDA, [writable] mint,
+    /// [writable] destination token account, [] token program, [] system program
+    Mint { amount: u64 },
+    /// Burn `amount` tokens and redeem `amount` lamports from the reserve
+    /// Accounts: [writable, signer] user, [writable] reserve PDA, [writable] mint,
+    /// [writable] user's token account, [] token program
+    Burn { amount: u64 },
+}
+
+fn initialize(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let authority = next_account_info(accounts_iter)?;
+    let reserve_account = next_account_info(accounts_iter)?;
+    let mint = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if *token_program.key != spl_token::id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let (expected_reserve, bump) =
+        Pubkey::find_program_address(&[RESERVE_SEED, authority.key.as_ref()], program_id);
+    if expected_reserve != *reserve_account.key {
+        msg!("Reserve account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let (expected_mint, mint_bump) =
+        Pubkey::find_program_address(&[MINT_SEED, reserve_account.key.as_ref()], program_id);
+    if expected_mint != *mint.key {
+        msg!("Mint account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let rent = Rent::get()?;
+
+    invoke_signed(
+        &system_instruction::create_account(
+            authority.key,
+            reserve_account.key,
+            rent.minimum_balance(RESERVE_ACCOUNT_SIZE),
+            RESERVE_ACCOUNT_SIZE as u64,
+            program_id,
+        ),
+        &[authority.clone(), reserve_account.clone(), system_program.clone()],
+        &[&[RESERVE_SEED, authority.key.as_ref(), &[bump]]],
+    )?;
+
+    // No freeze authority: holders can always burn and redeem
+    invoke_signed(
+        &system_instruction::create_account(
+            authority.key,
+            mint.key,
+            rent.minimum_balance(Mint::LEN),
+            Mint::LEN as u64,
+            &spl_token::id(),
+        ),
+        &[authority.clone(), mint.clone(), system_program.clone()],
+        &[&[MINT_SEED, reserve_account.key.as_ref(), &[mint_bump]]],
+    )?;
+    invoke(
+        &spl_token::instruction::initialize_mint2(
+            &spl_token::id(),
+            mint.key,
+            reserve_account.key,
+            None,
+            DECIMALS,
+        )?,
+        std::slice::from_ref(mint),
+    )?;
+
+    let reserve = ReserveAccount {
+        is_initialized: true,
+        authority: *authority.key,
+        mint: *mint.key,
+        bump,
+        mint_bump,
+    };
+    reserve.serialize(&mut &mut reserve_account.data.borrow_mut()[..])?;
+
+    msg!("Reserve for {} created", mint.key);
+
+    Ok(())
+}
+
+fn load_reserve(program_id: &Pubkey, reserve_account: &AccountInfo, mint: &AccountInfo) -> Result<ReserveAccount, ProgramError> {
+    if reserve_account.owner != program_id {
+        msg!("Reserve account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let reserve = ReserveAccount::try_from_slice(&reserve_account.data.borrow())?;
+    if !reserve.is_initialized {
+        return Err(MintBurnError::NotInitialized.into());
+    }
+
+    if reserve.mint != *mint.key {
+        return Err(MintBurnError::WrongMint.into());
+    }
+
+    Ok(reserve)
+}
+
+fn mint(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let user = next_account_info(accounts_iter)?;
+    let reserve_account = next_account_info(accounts_iter)?;
+    let mint = next_account_info(accounts_iter)?;
+    let destination = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !user.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if amount == 0 {
+        return Err(MintBurnError::InvalidAmount.into());
+    }
+
+    if *token_program.key != spl_token::id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let reserve = load_reserve(program_id, reserve_account, mint)?;
+    let rent = Rent::get()?;
+    check_collateralization(reserve_account, mint, &rent)?;
+
+    // Collateral first, then the tokens it backs
+    invoke(
+        &system_instruction::transfer(user.key, reserve_account.key, amount),
+        &[user.clone(), reserve_account.clone(), system_program.clone()],
+    )?;
+
+    invoke_signed(
+        &spl_token::instruction::mint_to(
+            &spl_token::id(),
+            mint.key,
+            destination.key,
+            reserve_account.key,
+            &[],
+            amount,
+        )?,
+        &[mint.clone(), destination.clone(), reserve_account.clone()],
+        &[&[RESERVE_SEED, reserve.authority.as_ref(), &[reserve.bump]]],
+    )?;
+
+    check_collateralization(reserve_account, mint, &rent)?;
+
+    msg!("Minted {} for {} lamports", amount, amount);
+
+    Ok(())
+}
+
+fn burn(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let user = next_account_info(accounts_iter)?;
+    let reserve_account = next_account_info(accounts_iter)?;
+    let mint = next_account_info(accounts_iter)?;
+    let source = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+
+    if !user.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if amount == 0 {
+        return Err(MintBurnError::InvalidAmount.into());
+    }
+
+    if *token_program.key != spl_token::id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    load_reserve(program_id, reserve_account, mint)?;
+    let rent = Rent::get()?;
+    check_collateralization(reserve_account, mint, &rent)?;
+
+    // The token program checks that the user owns `source` and holds `amount`
+    invoke(
+        &spl_token::instruction::burn(
+            &spl_token::id(),
+            source.key,
+            mint.key,
+            user.key,
+            &[],
+            amount,
+        )?,
+        &[source.clone(), mint.clone(), user.clone()],
+    )?;
+
+    // The reserve is owned by this program, so lamports move directly
+    **reserve_account.try_borrow_mut_lamports()? = reserve_account
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(MintBurnError::Undercollateralized)?;
+    **user.try_borrow_mut_lamports()? = user
+        .lamports()
+        .checked_add(amount)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    check_collateralization(reserve_account, mint, &rent)?;
+
+    msg!("Burned {} and redeemed {} lamports", amount, amount);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_program::{clock::Epoch, program_option::COption};
+
+    #[test]
+    fn test_collateral_excludes_rent() {
+        let rent = Rent::default();
+        let minimum = rent.minimum_balance(RESERVE_ACCOUNT_SIZE);
+
+        assert_eq!(collateral(minimum, &rent), 0);
+        assert_eq!(collateral(minimum + 500, &rent), 500);
+        assert_eq!(collateral(minimum - 1, &rent), 0);
+
+        assert!(is_collateralized(minimum, 0, &rent));
+        assert!(is_collateralized(minimum + 500, 500, &rent));
+        assert!(is_collateralized(minimum + 600, 500, &rent));
+        assert!(!is_collateralized(minimum + 499, 500, &rent));
+    }
+
+    #[test]
+    fn test_check_collateralization_reads_mint_supply() {
+        let program_id = Pubkey::new_unique();
+        let reserve_key = Pubkey::new_unique();
+        let mint_key = Pubkey::new_unique();
+        let token_program = spl_token::id();
+        let rent = Rent::default();
+
+        let mut mint_data = vec![0u8; Mint::LEN];
+        Mint::pack(
+            Mint {
+                mint_authority: COption::Some(reserve_key),
+                supply: 1_000,
+                decimals: DECIMALS,
+                is_initialized: true,
+                freeze_authority: COption::None,
+            },
+            &mut mint_data,
+        )
+        .unwrap();
+        let mut mint_lamports = rent.minimum_balance(Mint::LEN);
+        let mint = AccountInfo::new(
+            &mint_key,
+            false,
+            true,
+            &mut mint_lamports,
+            &mut mint_data,
+            &token_program,
+            false,
+            Epoch::default(),
+        );
+
+        let mut reserve_lamports = rent.minimum_balance(RESERVE_ACCOUNT_SIZE) + 1_000;
+        let mut reserve_data = vec![0u8; RESERVE_ACCOUNT_SIZE];
+        let reserve = AccountInfo::new(
+            &reserve_key,
+            false,
+            true,
+            &mut reserve_lamports,
+            &mut reserve_data,
+            &program_id,
+            false,
+            Epoch::default(),
+        );
+
+        check_collateralization(&reserve, &mint, &rent).unwrap();
+
+        // One lamport short of backing the supply
+        **reserve.try_borrow_mut_lamports().unwrap() -= 1;
+        assert_eq!(
+            check_collateralization(&reserve, &mint, &rent),
+            Err(MintBurnError::Undercollateralized.into())
+        );
+    }
+}
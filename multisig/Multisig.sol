@@ -0,0 +1,92 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+/**
+ * @title Multisig
+ * @dev M-of-N wallet: owners propose arbitrary calls and execute them once enough owners approve
+ */
+contract Multisig {
+    uint256 public constant MAX_OWNERS = 10;
+
+    struct Transaction {
+        address proposer;
+        address target;
+        uint256 value;
+        bytes data;
+        uint256 approvalCount;
+        bool executed;
+    }
+
+    address[] public owners;
+    mapping(address => bool) public isOwner;
+    uint256 public immutable threshold;
+
+    Transaction[] public transactions;
+    mapping(uint256 => mapping(address => bool)) public approved;
+
+    event Proposed(uint256 indexed txId, address indexed proposer, address target, uint256 value);
+    event Approved(uint256 indexed txId, address indexed owner);
+    event Executed(uint256 indexed txId);
+
+    modifier onlyOwner() {
+        require(isOwner[msg.sender], "Not an owner");
+        _;
+    }
+
+    constructor(address[] memory _owners, uint256 _threshold) {
+        require(_owners.length > 0 && _owners.length <= MAX_OWNERS, "Need 1-10 owners");
+        require(_threshold > 0 && _threshold <= _owners.length, "Invalid threshold");
+
+        for (uint256 i = 0; i < _owners.length; i++) {
+            require(_owners[i] != address(0), "Invalid owner");
+            require(!isOwner[_owners[i]], "Duplicate owner");
+            isOwner[_owners[i]] = true;
+            owners.push(_owners[i]);
+        }
+        threshold = _threshold;
+    }
+
+    receive() external payable {}
+
+    /**
+     * @dev Propose a call from this wallet; counts as the proposer's approval
+     */
+    function propose(address target, uint256 value, bytes memory data) external onlyOwner returns (uint256 txId) {
+        transactions.push(Transaction(msg.sender, target, value, data, 1, false));
+        txId = transactions.length - 1;
+        approved[txId][msg.sender] = true;
+
+        emit Proposed(txId, msg.sender, target, value);
+        emit Approved(txId, msg.sender);
+    }
+
+    /**
+     * @dev Approve a proposed transaction
+     */
+    function approve(uint256 txId) external onlyOwner {
+        Transaction storage t = transactions[txId];
+        require(!t.executed, "Already executed");
+        require(!approved[txId][msg.sender], "Already approved");
+
+        approved[txId][msg.sender] = true;
+        t.approvalCount += 1;
+
+        emit Approved(txId, msg.sender);
+    }
+
+    /**
+     * @dev Execute a transaction once it has `threshold` approvals (callable by anyone)
+     */
+    function execute(uint256 txId) external {
+        Transaction storage t = transactions[txId];
+        require(!t.executed, "Already executed");
+        require(t.approvalCount >= threshold, "Not enough approvals");
+
+        t.executed = true;
+
+        (bool success, ) = t.target.call{value: t.value}(t.data);
+        require(success, "Call failed");
+
+        emit Executed(txId);
+    }
+}
@@ -0,0 +1,521 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint,
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// Maximum number of owners of a multisig
+pub const MAX_OWNERS: usize = 10;
+
+/// Seed prefix for the PDA that signs executed instructions: ["multisig", multisig]
+pub const SIGNER_SEED: &[u8] = b"multisig";
+
+/// Seed prefix for transaction PDAs: ["transaction", multisig, index]
+pub const TRANSACTION_SEED: &[u8] = b"transaction";
+
+/// Size of a `MultisigAccount` with `MAX_OWNERS` owners
+pub const MULTISIG_ACCOUNT_SIZE: usize = 1 // is_initialized
+    + 4 + 32 * MAX_OWNERS // owners
+    + 1 // threshold
+    + 8 // transaction_count
+    + 1; // signer_bump
+
+/// Errors returned by the program as `ProgramError::Custom` codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MultisigError {
+    /// Owners list is empty, too long, or has duplicates, or the threshold
+    /// is zero or above the owner count
+    InvalidOwners = 0,
+    /// The signer is not one of the multisig owners
+    NotOwner = 1,
+    /// The owner has already approved this transaction
+    AlreadyApproved = 2,
+    /// Fewer than `threshold` owners have approved
+    NotEnoughApprovals = 3,
+    /// The transaction has already been executed
+    AlreadyExecuted = 4,
+    /// The transaction belongs to a different multisig
+    WrongMultisig = 5,
+}
+
+impl From<MultisigError> for ProgramError {
+    fn from(e: MultisigError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct MultisigAccount {
+    pub is_initialized: bool,
+    pub owners: Vec<Pubkey>,
+    /// Approvals required to execute a transaction
+    pub threshold: u8,
+    /// Number of transactions proposed so far; the next transaction's index
+    pub transaction_count: u64,
+    /// Bump of the ["multisig", multisig] signer PDA
+    pub signer_bump: u8,
+}
+
+/// Serializable form of an `AccountMeta`
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TransactionAccount {
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+/// A proposed instruction awaiting approvals
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct TransactionAccountData {
+    pub multisig: Pubkey,
+    pub proposer: Pubkey,
+    /// Program the instruction is sent to
+    pub program_id: Pubkey,
+    pub accounts: Vec<TransactionAccount>,
+    pub data: Vec<u8>,
+    /// `approvals[i]` is true once `owners[i]` has approved
+    pub approvals: Vec<bool>,
+    pub executed: bool,
+}
+
+impl TransactionAccountData {
+    /// Number of owners who have approved
+    pub fn approval_count(&self) -> usize {
+        self.approvals.iter().filter(|approved| **approved).count()
+    }
+}
+
+entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = MultisigInstruction::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        MultisigInstruction::CreateMultisig { owners, threshold } => {
+            create_multisig(program_id, accounts, owners, threshold)
+        }
+        MultisigInstruction::Propose {
+            program_id: target_program,
+            accounts: target_accounts,
+            data,
+        } => propose(program_id, accounts, target_program, target_accounts, data),
+        MultisigInstruction::Approve => approve(program_id, accounts),
+        MultisigInstruction::Execute => execute(program_id, accounts),
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub enum MultisigInstruction {
+    /// Initialize an M-of-N multisig
+    /// Accounts: [writable] multisig account (program-owned, `MULTISIG_ACCOUNT_SIZE` bytes)
+    CreateMultisig { owners: Vec<Pubkey>, threshold: u8 },
+    /// Propose an instruction to be signed by the multisig signer PDA; counts
+    /// as the proposer's approval
+    /// Accounts: [writable, signer] proposer (owner, pays rent), [writable] multisig,
+    /// [writable] transaction PDA, [] system program
+    Propose {
+        program_id: Pubkey,
+        accounts: Vec<TransactionAccount>,
+        data: Vec<u8>,
+    },
+    /// Approve a proposed transaction
+    /// Accounts: [signer] owner, [] multisig, [writable] transaction PDA
+    Approve,
+    /// Execute a transaction once `threshold` owners have approved
+    /// (permissionless)
+    /// Accounts: [] multisig, [writable] transaction PDA, [] multisig signer PDA,
+    /// then every account the instruction uses, followed by the target program
+    Execute,
+}
+
+fn create_multisig(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    owners: Vec<Pubkey>,
+    threshold: u8,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let multisig_account = next_account_info(accounts_iter)?;
+
+    if multisig_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    if !multisig_account.is_writable {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let existing = MultisigAccount::deserialize(&mut &multisig_account.data.borrow()[..]);
+    if matches!(existing, Ok(MultisigAccount { is_initialized: true, .. })) {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+
+    let has_duplicates = owners
+        .iter()
+        .enumerate()
+        .any(|(i, owner)| owners[..i].contains(owner));
+    if owners.is_empty()
+        || owners.len() > MAX_OWNERS
+        || has_duplicates
+        || threshold == 0
+        || threshold as usize > owners.len()
+    {
+        msg!("Need 1-{} unique owners and 1 <= threshold <= owners", MAX_OWNERS);
+        return Err(MultisigError::InvalidOwners.into());
+    }
+
+    let (_, signer_bump) =
+        Pubkey::find_program_address(&[SIGNER_SEED, multisig_account.key.as_ref()], program_id);
+
+    let multisig = MultisigAccount {
+        is_initialized: true,
+        owners,
+        threshold,
+        transaction_count: 0,
+        signer_bump,
+    };
+    multisig.serialize(&mut &mut multisig_account.data.borrow_mut()[..])?;
+
+    msg!("Multisig created: {}-of-{}", threshold, multisig.owners.len());
+
+    Ok(())
+}
+
+fn propose(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    target_program: Pubkey,
+    target_accounts: Vec<TransactionAccount>,
+    data: Vec<u8>,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let proposer = next_account_info(accounts_iter)?;
+    let multisig_account = next_account_info(accounts_iter)?;
+    let transaction_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !proposer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut multisig = load_multisig(program_id, multisig_account)?;
+    let owner_index = owner_index(&multisig, proposer.key)?;
+
+    let index = multisig.transaction_count;
+    let index_bytes = index.to_le_bytes();
+    let (expected_transaction, bump) = Pubkey::find_program_address(
+        &[TRANSACTION_SEED, multisig_account.key.as_ref(), &index_bytes],
+        program_id,
+    );
+    if expected_transaction != *transaction_account.key {
+        msg!("Transaction account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let mut approvals = vec![false; multisig.owners.len()];
+    approvals[owner_index] = true;
+
+    let transaction = TransactionAccountData {
+        multisig: *multisig_account.key,
+        proposer: *proposer.key,
+        program_id: target_program,
+        accounts: target_accounts,
+        data,
+        approvals,
+        executed: false,
+    };
+    let transaction_data = transaction.try_to_vec()?;
+
+    invoke_signed(
+        &system_instruction::create_account(
+            proposer.key,
+            transaction_account.key,
+            Rent::get()?.minimum_balance(transaction_data.len()),
+            transaction_data.len() as u64,
+            program_id,
+        ),
+        &[proposer.clone(), transaction_account.clone(), system_program.clone()],
+        &[&[TRANSACTION_SEED, multisig_account.key.as_ref(), &index_bytes, &[bump]]],
+    )?;
+    transaction_account.data.borrow_mut().copy_from_slice(&transaction_data);
+
+    multisig.transaction_count += 1;
+    multisig.serialize(&mut &mut multisig_account.data.borrow_mut()[..])?;
+
+    msg!("Transaction {} proposed by {}", index, proposer.key);
+
+    Ok(())
+}
+
+fn approve(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let owner = next_account_info(accounts_iter)?;
+    let multisig_account = next_account_info(accounts_iter)?;
+    let transaction_account = next_account_info(accounts_iter)?;
+
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let multisig = load_multisig(program_id, multisig_account)?;
+    let mut transaction = load_transaction(program_id, multisig_account, transaction_account)?;
+    let owner_index = owner_index(&multisig, owner.key)?;
+
+    if transaction.approvals[owner_index] {
+        msg!("{} already approved", owner.key);
+        return Err(MultisigError::AlreadyApproved.into());
+    }
+
+    transaction.approvals[owner_index] = true;
+    transaction.serialize(&mut &mut transaction_account.data.borrow_mut()[..])?;
+
+    msg!(
+        "Approved by {} ({}/{})",
+        owner.key,
+        transaction.approval_count(),
+        multisig.threshold
+    );
+
+    Ok(())
+}
+
+fn execute(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let multisig_account = next_account_info(accounts_iter)?;
+    let transaction_account = next_account_info(accounts_iter)?;
+    let multisig_signer = next_account_info(accounts_iter)?;
+    let remaining_accounts: Vec<AccountInfo> = accounts_iter.cloned().collect();
+
+    let multisig = load_multisig(program_id, multisig_account)?;
+    let mut transaction = load_transaction(program_id, multisig_account, transaction_account)?;
+
+    if transaction.approval_count() < multisig.threshold as usize {
+        msg!(
+            "Only {} of {} required approvals",
+            transaction.approval_count(),
+            multisig.threshold
+        );
+        return Err(MultisigError::NotEnoughApprovals.into());
+    }
+
+    let signer_seeds: &[&[u8]] = &[
+        SIGNER_SEED,
+        multisig_account.key.as_ref(),
+        &[multisig.signer_bump],
+    ];
+    let expected_signer = Pubkey::create_program_address(signer_seeds, program_id)?;
+    if expected_signer != *multisig_signer.key {
+        msg!("Multisig signer does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    // Mark executed before the CPI so a re-entrant call cannot run it twice
+    transaction.executed = true;
+    transaction.serialize(&mut &mut transaction_account.data.borrow_mut()[..])?;
+
+    let instruction = Instruction {
+        program_id: transaction.program_id,
+        accounts: transaction
+            .accounts
+            .iter()
+            .map(|account| AccountMeta {
+                pubkey: account.pubkey,
+                is_signer: account.is_signer,
+                is_writable: account.is_writable,
+            })
+            .collect(),
+        data: transaction.data,
+    };
+
+    let mut account_infos = remaining_accounts;
+    account_infos.push(multisig_signer.clone());
+    invoke_signed(&instruction, &account_infos, &[signer_seeds])?;
+
+    msg!("Transaction executed");
+
+    Ok(())
+}
+
+/// Validate and load an initialized multisig account
+fn load_multisig(
+    program_id: &Pubkey,
+    multisig_account: &AccountInfo,
+) -> Result<MultisigAccount, ProgramError> {
+    if multisig_account.owner != program_id {
+        msg!("Multisig account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let multisig = MultisigAccount::deserialize(&mut &multisig_account.data.borrow()[..])?;
+    if !multisig.is_initialized {
+        return Err(ProgramError::UninitializedAccount);
+    }
+
+    Ok(multisig)
+}
+
+/// Validate and load a pending transaction belonging to `multisig_account`
+fn load_transaction(
+    program_id: &Pubkey,
+    multisig_account: &AccountInfo,
+    transaction_account: &AccountInfo,
+) -> Result<TransactionAccountData, ProgramError> {
+    if transaction_account.owner != program_id {
+        msg!("Transaction account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    if !transaction_account.is_writable {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let transaction =
+        TransactionAccountData::deserialize(&mut &transaction_account.data.borrow()[..])?;
+
+    if transaction.multisig != *multisig_account.key {
+        msg!("Transaction belongs to a different multisig");
+        return Err(MultisigError::WrongMultisig.into());
+    }
+
+    if transaction.executed {
+        msg!("Transaction already executed");
+        return Err(MultisigError::AlreadyExecuted.into());
+    }
+
+    Ok(transaction)
+}
+
+/// Position of `key` in the owner list
+fn owner_index(multisig: &MultisigAccount, key: &Pubkey) -> Result<usize, ProgramError> {
+    multisig.owners.iter().position(|owner| owner == key).ok_or_else(|| {
+        msg!("{} is not an owner", key);
+        MultisigError::NotOwner.into()
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_program::clock::Epoch;
+
+    #[test]
+    fn test_create_multisig_rejects_bad_threshold() {
+        let program_id = Pubkey::new_unique();
+        let key = Pubkey::new_unique();
+        let mut lamports = 0;
+        let mut data = vec![0; MULTISIG_ACCOUNT_SIZE];
+        let account = AccountInfo::new(
+            &key,
+            false,
+            true,
+            &mut lamports,
+            &mut data,
+            &program_id,
+            false,
+            Epoch::default(),
+        );
+        let accounts = vec![account];
+
+        let owners = vec![Pubkey::new_unique(), Pubkey::new_unique()];
+        for threshold in [0, 3] {
+            let instruction = MultisigInstruction::CreateMultisig {
+                owners: owners.clone(),
+                threshold,
+            };
+            let instruction_data = instruction.try_to_vec().unwrap();
+            let result = process_instruction(&program_id, &accounts, &instruction_data);
+            assert_eq!(result, Err(MultisigError::InvalidOwners.into()));
+        }
+
+        let instruction = MultisigInstruction::CreateMultisig { owners, threshold: 2 };
+        let instruction_data = instruction.try_to_vec().unwrap();
+        assert!(process_instruction(&program_id, &accounts, &instruction_data).is_ok());
+    }
+
+    #[test]
+    fn test_execute_requires_threshold() {
+        let program_id = Pubkey::new_unique();
+        let multisig_key = Pubkey::new_unique();
+        let transaction_key = Pubkey::new_unique();
+        let (signer_key, signer_bump) =
+            Pubkey::find_program_address(&[SIGNER_SEED, multisig_key.as_ref()], &program_id);
+
+        let owners = vec![Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()];
+        let mut multisig_lamports = 0;
+        let mut multisig_data = MultisigAccount {
+            is_initialized: true,
+            owners,
+            threshold: 2,
+            transaction_count: 1,
+            signer_bump,
+        }
+        .try_to_vec()
+        .unwrap();
+        let multisig = AccountInfo::new(
+            &multisig_key,
+            false,
+            false,
+            &mut multisig_lamports,
+            &mut multisig_data,
+            &program_id,
+            false,
+            Epoch::default(),
+        );
+
+        let mut transaction_lamports = 0;
+        let mut transaction_data = TransactionAccountData {
+            multisig: multisig_key,
+            proposer: Pubkey::new_unique(),
+            program_id: Pubkey::new_unique(),
+            accounts: vec![],
+            data: vec![],
+            approvals: vec![true, false, false],
+            executed: false,
+        }
+        .try_to_vec()
+        .unwrap();
+        let transaction = AccountInfo::new(
+            &transaction_key,
+            false,
+            true,
+            &mut transaction_lamports,
+            &mut transaction_data,
+            &program_id,
+            false,
+            Epoch::default(),
+        );
+
+        let mut signer_lamports = 0;
+        let mut signer_data = vec![];
+        let signer = AccountInfo::new(
+            &signer_key,
+            false,
+            false,
+            &mut signer_lamports,
+            &mut signer_data,
+            &program_id,
+            false,
+            Epoch::default(),
+        );
+
+        let accounts = vec![multisig, transaction, signer];
+        let instruction_data = MultisigInstruction::Execute.try_to_vec().unwrap();
+
+        let result = process_instruction(&program_id, &accounts, &instruction_data);
+        assert_eq!(result, Err(MultisigError::NotEnoughApprovals.into()));
+    }
+}
@@ -0,0 +1,37 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.20;
+
+import "@openzeppelin/contracts/token/ERC721/extensions/ERC721URIStorage.sol";
+
+/**
+ * @title NftMint
+ * @dev ERC721 collection with per-token URIs and a collection-size cap
+ */
+contract NftMint is ERC721URIStorage {
+    uint256 public immutable maxSupply;
+    uint256 public minted;
+
+    event Minted(uint256 indexed tokenId, address indexed to, string uri);
+
+    constructor(string memory name, string memory symbol, uint256 _maxSupply) ERC721(name, symbol) {
+        require(_maxSupply > 0, "Max supply must be greater than zero");
+        maxSupply = _maxSupply;
+    }
+
+    /**
+     * @dev Mint the next token to the caller with its metadata URI
+     * @return tokenId The id of the new token
+     */
+    function mint(string memory uri) external returns (uint256 tokenId) {
+        require(minted < maxSupply, "Collection is full");
+        require(bytes(uri).length <= 200, "URI too long (max 200 bytes)");
+
+        tokenId = minted;
+        minted += 1;
+
+        _safeMint(msg.sender, tokenId);
+        _setTokenURI(tokenId, uri);
+
+        emit Minted(tokenId, msg.sender, uri);
+    }
+}
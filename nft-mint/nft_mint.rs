@@ -0,0 +1,360 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint,
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use mpl_token_metadata::{
+    instructions::CreateMetadataAccountV3CpiBuilder,
+    types::DataV2,
+};
+use spl_token::{
+    instruction::AuthorityType,
+    state::{Account as TokenAccount, Mint},
+};
+
+/// Seed prefix for collection config PDAs: ["config", authority]
+pub const CONFIG_SEED: &[u8] = b"config";
+
+/// Token Metadata limits on name, symbol, and URI lengths
+pub const MAX_NAME_LENGTH: usize = 32;
+pub const MAX_SYMBOL_LENGTH: usize = 10;
+pub const MAX_URI_LENGTH: usize = 200;
+
+/// Size of a `CollectionConfig` with a maximum-length symbol
+pub const CONFIG_ACCOUNT_SIZE: usize = 1 // is_initialized
+    + 32 // authority
+    + 4 + MAX_SYMBOL_LENGTH // symbol
+    + 8 // max_supply
+    + 8 // minted
+    + 1; // bump
+
+/// Errors returned by the program as `ProgramError::Custom` codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NftMintError {
+    /// Every NFT in the collection has been minted
+    CollectionFull = 0,
+    /// Name, symbol, or URI exceeds the Token Metadata limits
+    InvalidMetadata = 1,
+    /// Max supply must be greater than zero
+    InvalidMaxSupply = 2,
+    /// The config has not been initialized
+    NotInitialized = 3,
+}
+
+impl From<NftMintError> for ProgramError {
+    fn from(e: NftMintError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+/// Collection-wide settings; the PDA is also the mint and update authority
+/// for every NFT it mints
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct CollectionConfig {
+    pub is_initialized: bool,
+    /// Creator of the collection
+    pub authority: Pubkey,
+    /// Symbol shared by every NFT in the collection
+    pub symbol: String,
+    /// Collection-size cap
+    pub max_supply: u64,
+    /// NFTs minted so far
+    pub minted: u64,
+    pub bump: u8,
+}
+
+entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = NftMintInstruction::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        NftMintInstruction::InitializeConfig { symbol, max_supply } => {
+            initialize_config(program_id, accounts, symbol, max_supply)
+        }
+        NftMintInstruction::Mint { name, uri } => mint(program_id, accounts, name, uri),
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub enum NftMintInstruction {
+    /// Create the collection config PDA
+    /// Accounts: [writable, signer] authority, [writable] config PDA, [] system program
+    InitializeConfig { symbol: String, max_supply: u64 },
+    /// Create a new mint, mint one token to the payer, attach metadata, and
+    /// remove the mint authority so the supply stays at one
+    /// Accounts: [writable, signer] payer, [writable] config PDA, [writable, signer] new mint,
+    /// [writable, signer] new token account, [writable] metadata PDA, [] token metadata program,
+    /// [] token program, [] system program
+    Mint { name: String, uri: String },
+}
+
+fn initialize_config(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    symbol: String,
+    max_supply: u64,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let authority = next_account_info(accounts_iter)?;
+    let config_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if symbol.len() > MAX_SYMBOL_LENGTH {
+        msg!("Symbol too long (max {} bytes)", MAX_SYMBOL_LENGTH);
+        return Err(NftMintError::InvalidMetadata.into());
+    }
+
+    if max_supply == 0 {
+        msg!("Max supply must be greater than zero");
+        return Err(NftMintError::InvalidMaxSupply.into());
+    }
+
+    let (expected_config, bump) =
+        Pubkey::find_program_address(&[CONFIG_SEED, authority.key.as_ref()], program_id);
+    if expected_config != *config_account.key {
+        msg!("Config account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    invoke_signed(
+        &system_instruction::create_account(
+            authority.key,
+            config_account.key,
+            Rent::get()?.minimum_balance(CONFIG_ACCOUNT_SIZE),
+            CONFIG_ACCOUNT_SIZE as u64,
+            program_id,
+        ),
+        &[authority.clone(), config_account.clone(), system_program.clone()],
+        &[&[CONFIG_SEED, authority.key.as_ref(), &[bump]]],
+    )?;
+
+    let config = CollectionConfig {
+        is_initialized: true,
+        authority: *authority.key,
+        symbol,
+        max_supply,
+        minted: 0,
+        bump,
+    };
+    config.serialize(&mut &mut config_account.data.borrow_mut()[..])?;
+
+    msg!("Collection {} created with max supply {}", config.symbol, max_supply);
+
+    Ok(())
+}
+
+fn mint(program_id: &Pubkey, accounts: &[AccountInfo], name: String, uri: String) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let payer = next_account_info(accounts_iter)?;
+    let config_account = next_account_info(accounts_iter)?;
+    let mint = next_account_info(accounts_iter)?;
+    let token_account = next_account_info(accounts_iter)?;
+    let metadata = next_account_info(accounts_iter)?;
+    let token_metadata_program = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !payer.is_signer || !mint.is_signer || !token_account.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if config_account.owner != program_id {
+        msg!("Config account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    if *token_program.key != spl_token::id() || *token_metadata_program.key != mpl_token_metadata::ID {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    if name.len() > MAX_NAME_LENGTH || uri.len() > MAX_URI_LENGTH {
+        msg!("Name max {} bytes, URI max {} bytes", MAX_NAME_LENGTH, MAX_URI_LENGTH);
+        return Err(NftMintError::InvalidMetadata.into());
+    }
+
+    let mut config = CollectionConfig::deserialize(&mut &config_account.data.borrow()[..])?;
+
+    if !config.is_initialized {
+        return Err(NftMintError::NotInitialized.into());
+    }
+
+    if config.minted >= config.max_supply {
+        msg!("Collection is full ({} minted)", config.minted);
+        return Err(NftMintError::CollectionFull.into());
+    }
+
+    let config_seeds: &[&[u8]] = &[CONFIG_SEED, config.authority.as_ref(), &[config.bump]];
+    let rent = Rent::get()?;
+
+    // Create a 0-decimal mint whose authority is the config PDA
+    invoke(
+        &system_instruction::create_account(
+            payer.key,
+            mint.key,
+            rent.minimum_balance(Mint::LEN),
+            Mint::LEN as u64,
+            &spl_token::id(),
+        ),
+        &[payer.clone(), mint.clone(), system_program.clone()],
+    )?;
+    invoke(
+        &spl_token::instruction::initialize_mint2(
+            &spl_token::id(),
+            mint.key,
+            config_account.key,
+            None,
+            0,
+        )?,
+        std::slice::from_ref(mint),
+    )?;
+
+    // Create the payer's token account and mint the single token into it
+    invoke(
+        &system_instruction::create_account(
+            payer.key,
+            token_account.key,
+            rent.minimum_balance(TokenAccount::LEN),
+            TokenAccount::LEN as u64,
+            &spl_token::id(),
+        ),
+        &[payer.clone(), token_account.clone(), system_program.clone()],
+    )?;
+    invoke(
+        &spl_token::instruction::initialize_account3(
+            &spl_token::id(),
+            token_account.key,
+            mint.key,
+            payer.key,
+        )?,
+        &[token_account.clone(), mint.clone()],
+    )?;
+    invoke_signed(
+        &spl_token::instruction::mint_to(
+            &spl_token::id(),
+            mint.key,
+            token_account.key,
+            config_account.key,
+            &[],
+            1,
+        )?,
+        &[mint.clone(), token_account.clone(), config_account.clone()],
+        &[config_seeds],
+    )?;
+
+    // Attach name/symbol/URI; the config PDA signs as mint and update authority
+    CreateMetadataAccountV3CpiBuilder::new(token_metadata_program)
+        .metadata(metadata)
+        .mint(mint)
+        .mint_authority(config_account)
+        .payer(payer)
+        .update_authority(config_account, true)
+        .system_program(system_program)
+        .data(DataV2 {
+            name,
+            symbol: config.symbol.clone(),
+            uri,
+            seller_fee_basis_points: 0,
+            creators: None,
+            collection: None,
+            uses: None,
+        })
+        .is_mutable(true)
+        .invoke_signed(&[config_seeds])?;
+
+    // Remove the mint authority so no second token can ever be minted
+    invoke_signed(
+        &spl_token::instruction::set_authority(
+            &spl_token::id(),
+            mint.key,
+            None,
+            AuthorityType::MintTokens,
+            config_account.key,
+            &[],
+        )?,
+        &[mint.clone(), config_account.clone()],
+        &[config_seeds],
+    )?;
+
+    config.minted += 1;
+    config.serialize(&mut &mut config_account.data.borrow_mut()[..])?;
+
+    msg!("Minted {} ({}/{})", mint.key, config.minted, config.max_supply);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_program::clock::Epoch;
+
+    #[test]
+    fn test_mint_rejects_when_collection_full() {
+        let program_id = Pubkey::new_unique();
+        let authority = Pubkey::new_unique();
+        let (config_key, bump) =
+            Pubkey::find_program_address(&[CONFIG_SEED, authority.as_ref()], &program_id);
+
+        let config = CollectionConfig {
+            is_initialized: true,
+            authority,
+            symbol: "CMP".to_string(),
+            max_supply: 2,
+            minted: 2,
+            bump,
+        };
+        let mut config_data = config.try_to_vec().unwrap();
+        config_data.resize(CONFIG_ACCOUNT_SIZE, 0);
+
+        let keys = [
+            Pubkey::new_unique(),
+            config_key,
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            mpl_token_metadata::ID,
+            spl_token::id(),
+            solana_program::system_program::id(),
+        ];
+        let mut lamports = vec![0u64; keys.len()];
+        let mut data: Vec<Vec<u8>> = vec![vec![]; keys.len()];
+        data[1] = config_data;
+
+        let accounts: Vec<AccountInfo> = keys
+            .iter()
+            .zip(lamports.iter_mut())
+            .zip(data.iter_mut())
+            .map(|((key, lamports), data)| {
+                AccountInfo::new(key, true, true, lamports, data, &program_id, false, Epoch::default())
+            })
+            .collect();
+
+        let instruction = NftMintInstruction::Mint {
+            name: "Comparison #3".to_string(),
+            uri: "https://example.com/3.json".to_string(),
+        };
+        let instruction_data = instruction.try_to_vec().unwrap();
+
+        let result = process_instruction(&program_id, &accounts, &instruction_data);
+        assert_eq!(result, Err(NftMintError::CollectionFull.into()));
+    }
+}
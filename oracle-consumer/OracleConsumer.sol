@@ -0,0 +1,80 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+interface IPyth {
+    struct Price {
+        int64 price;
+        uint64 conf;
+        int32 expo;
+        uint256 publishTime;
+    }
+
+    function getPriceNoOlderThan(bytes32 id, uint256 age) external view returns (Price memory);
+    function getUpdateFee(bytes[] calldata updateData) external view returns (uint256);
+    function updatePriceFeeds(bytes[] calldata updateData) external payable;
+}
+
+/**
+ * @title OracleConsumer
+ * @dev Reads one Pyth feed, checks staleness and confidence, and stores the price with 6 decimals
+ */
+contract OracleConsumer {
+    uint256 public constant PRICE_DECIMALS = 6;
+    uint256 public constant BPS_DENOMINATOR = 10_000;
+
+    IPyth public immutable pyth;
+    bytes32 public immutable feedId;
+    uint256 public immutable maxAgeSecs;
+    uint256 public immutable maxConfBps;
+
+    uint256 public price;
+    uint256 public conf;
+    uint256 public publishTime;
+
+    event PriceUpdated(uint256 price, uint256 conf, uint256 publishTime);
+
+    constructor(IPyth _pyth, bytes32 _feedId, uint256 _maxAgeSecs, uint256 _maxConfBps) {
+        require(_maxAgeSecs > 0 && _maxConfBps > 0, "Invalid limits");
+        pyth = _pyth;
+        feedId = _feedId;
+        maxAgeSecs = _maxAgeSecs;
+        maxConfBps = _maxConfBps;
+    }
+
+    /**
+     * @dev Push fresh Pyth update data (paying its fee), then validate and store the price
+     */
+    function updatePrice(bytes[] calldata updateData) external payable {
+        uint256 fee = pyth.getUpdateFee(updateData);
+        require(msg.value >= fee, "Insufficient update fee");
+        pyth.updatePriceFeeds{value: fee}(updateData);
+
+        // Reverts if the price is older than maxAgeSecs
+        IPyth.Price memory p = pyth.getPriceNoOlderThan(feedId, maxAgeSecs);
+        require(p.price > 0, "Invalid price");
+        require(p.publishTime > publishTime, "Price not newer");
+
+        uint256 rawPrice = uint256(uint64(p.price));
+        require(uint256(p.conf) * BPS_DENOMINATOR <= maxConfBps * rawPrice, "Confidence too wide");
+
+        price = _scale(rawPrice, p.expo);
+        conf = _scale(p.conf, p.expo);
+        publishTime = p.publishTime;
+        require(price > 0, "Invalid price");
+
+        emit PriceUpdated(price, conf, publishTime);
+
+        if (msg.value > fee) {
+            (bool success, ) = msg.sender.call{value: msg.value - fee}("");
+            require(success, "Refund failed");
+        }
+    }
+
+    function _scale(uint256 value, int32 expo) private pure returns (uint256) {
+        int256 shift = int256(PRICE_DECIMALS) + expo;
+        if (shift >= 0) {
+            return value * 10 ** uint256(shift);
+        }
+        return value / 10 ** uint256(-shift);
+    }
+}
@@ -0,0 +1,448 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint,
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// Seed prefix for consumer PDAs: ["consumer", authority, feed_id]
+pub const CONSUMER_SEED: &[u8] = b"consumer";
+
+/// Pyth Solana Receiver program, owner of every `PriceUpdateV2` account
+pub const PYTH_RECEIVER_PROGRAM_ID: Pubkey = pubkey!("rec5EKMGg6MxZYaMdyBfgwp4d5rB9T1VQH5pJv5LtFJ");
+
+/// Anchor account discriminator of `PriceUpdateV2`: sha256("account:PriceUpdateV2")[..8]
+pub const PRICE_UPDATE_V2_DISCRIMINATOR: [u8; 8] = [34, 241, 35, 99, 157, 126, 244, 205];
+
+/// Decimals of the stored price, e.g. 6 means 1_000_000 = $1.00 for a USD feed
+pub const PRICE_DECIMALS: u32 = 6;
+
+/// Confidence limits are expressed in basis points of the price
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Size of a `ConsumerAccount`
+pub const CONSUMER_ACCOUNT_SIZE: usize = 1 // is_initialized
+    + 32 // authority
+    + 32 // feed_id
+    + 8 // max_age_secs
+    + 2 // max_conf_bps
+    + 8 // price
+    + 8 // conf
+    + 8 // publish_time
+    + 1; // bump
+
+/// Errors returned by the program as `ProgramError::Custom` codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OracleError {
+    /// Maximum age or confidence limit is zero
+    InvalidParameters = 0,
+    /// The price update is for a different feed
+    WrongFeed = 1,
+    /// The price is older than the consumer's maximum age
+    StalePrice = 2,
+    /// The confidence interval is too wide relative to the price
+    ConfidenceTooWide = 3,
+    /// The price is not positive or cannot be converted
+    InvalidPrice = 4,
+    /// The account is not a fully verified Pyth price update
+    InvalidPriceAccount = 5,
+    /// The consumer has not been initialized
+    NotInitialized = 6,
+}
+
+impl From<OracleError> for ProgramError {
+    fn from(e: OracleError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+/// How many Wormhole guardian signatures the receiver checked
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerificationLevel {
+    Partial { num_signatures: u8 },
+    Full,
+}
+
+/// A price as published by Pyth
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PriceFeedMessage {
+    pub feed_id: [u8; 32],
+    /// `price * 10^exponent` is the price in the quote currency
+    pub price: i64,
+    pub conf: u64,
+    pub exponent: i32,
+    pub publish_time: i64,
+    pub prev_publish_time: i64,
+    pub ema_price: i64,
+    pub ema_conf: u64,
+}
+
+/// The Pyth Solana Receiver's `PriceUpdateV2` account, after its discriminator
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PriceUpdateV2 {
+    pub write_authority: Pubkey,
+    pub verification_level: VerificationLevel,
+    pub price_message: PriceFeedMessage,
+    pub posted_slot: u64,
+}
+
+/// Tracks one Pyth feed with the consumer's own safety limits
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct ConsumerAccount {
+    pub is_initialized: bool,
+    pub authority: Pubkey,
+    /// Pyth feed id, e.g. SOL/USD
+    pub feed_id: [u8; 32],
+    /// Oldest price, in seconds, that `UpdatePrice` accepts
+    pub max_age_secs: u64,
+    /// Widest confidence interval, in basis points of the price, that `UpdatePrice` accepts
+    pub max_conf_bps: u16,
+    /// Last accepted price with `PRICE_DECIMALS` decimals
+    pub price: u64,
+    /// Its confidence interval with `PRICE_DECIMALS` decimals
+    pub conf: u64,
+    pub publish_time: i64,
+    pub bump: u8,
+}
+
+/// Read a `PriceUpdateV2` from a Pyth receiver account
+pub fn load_price_update(price_account: &AccountInfo) -> Result<PriceUpdateV2, ProgramError> {
+    if *price_account.owner != PYTH_RECEIVER_PROGRAM_ID {
+        msg!("Price account is not owned by the Pyth receiver");
+        return Err(OracleError::InvalidPriceAccount.into());
+    }
+
+    let data = price_account.data.borrow();
+    if data.len() < 8 || data[..8] != PRICE_UPDATE_V2_DISCRIMINATOR {
+        msg!("Price account is not a PriceUpdateV2");
+        return Err(OracleError::InvalidPriceAccount.into());
+    }
+
+    PriceUpdateV2::deserialize(&mut &data[8..]).map_err(|_| OracleError::InvalidPriceAccount.into())
+}
+
+/// Check a price update against a consumer's feed and limits at time `now`
+pub fn validate_price(
+    update: &PriceUpdateV2,
+    feed_id: &[u8; 32],
+    now: i64,
+    max_age_secs: u64,
+    max_conf_bps: u16,
+) -> Result<(), OracleError> {
+    let message = &update.price_message;
+
+    if message.feed_id != *feed_id {
+        return Err(OracleError::WrongFeed);
+    }
+
+    // Partially verified updates were checked by fewer guardians than the quorum
+    if update.verification_level != VerificationLevel::Full {
+        return Err(OracleError::InvalidPriceAccount);
+    }
+
+    if message.price <= 0 {
+        return Err(OracleError::InvalidPrice);
+    }
+
+    let age = now.saturating_sub(message.publish_time);
+    if age > max_age_secs.min(i64::MAX as u64) as i64 {
+        return Err(OracleError::StalePrice);
+    }
+
+    if message.conf as u128 * BPS_DENOMINATOR as u128 > max_conf_bps as u128 * message.price as u128 {
+        return Err(OracleError::ConfidenceTooWide);
+    }
+
+    Ok(())
+}
+
+/// Convert `value * 10^exponent` to an integer with `decimals` decimals, rounding down
+pub fn scale_to_decimals(value: u64, exponent: i32, decimals: u32) -> Option<u64> {
+    let shift = decimals as i64 + exponent as i64;
+    let scaled = if shift >= 0 {
+        (value as u128).checked_mul(10u128.checked_pow(u32::try_from(shift).ok()?)?)?
+    } else {
+        10u128
+            .checked_pow(u32::try_from(-shift).ok()?)
+            .map_or(0, |divisor| value as u128 / divisor)
+    };
+    u64::try_from(scaled).ok()
+}
+
+entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = OracleInstruction::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        OracleInstruction::Initialize {
+            feed_id,
+            max_age_secs,
+            max_conf_bps,
+        } => initialize(program_id, accounts, feed_id, max_age_secs, max_conf_bps),
+        OracleInstruction::UpdatePrice => update_price(program_id, accounts),
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub enum OracleInstruction {
+    /// Create a consumer for one feed with its staleness and confidence limits
+    /// Accounts: [writable, signer] authority, [writable] consumer PDA, [] system program
+    Initialize {
+        feed_id: [u8; 32],
+        max_age_secs: u64,
+        max_conf_bps: u16,
+    },
+    /// Validate a Pyth price update and store its price; callable by anyone
+    /// Accounts: [writable] consumer PDA, [] Pyth `PriceUpdateV2` account
+    UpdatePrice,
+}
+
+fn initialize(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    feed_id: [u8; 32],
+    max_age_secs: u64,
+    max_conf_bps: u16,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let authority = next_account_info(accounts_iter)?;
+    let consumer_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if max_age_secs == 0 || max_conf_bps == 0 {
+        msg!("Maximum age and confidence limit must be greater than zero");
+        return Err(OracleError::InvalidParameters.into());
+    }
+
+    let (expected_consumer, bump) =
+        Pubkey::find_program_address(&[CONSUMER_SEED, authority.key.as_ref(), &feed_id], program_id);
+    if expected_consumer != *consumer_account.key {
+        msg!("Consumer account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    invoke_signed(
+        &system_instruction::create_account(
+            authority.key,
+            consumer_account.key,
+            Rent::get()?.minimum_balance(CONSUMER_ACCOUNT_SIZE),
+            CONSUMER_ACCOUNT_SIZE as u64,
+            program_id,
+        ),
+        &[authority.clone(), consumer_account.clone(), system_program.clone()],
+        &[&[CONSUMER_SEED, authority.key.as_ref(), &feed_id, &[bump]]],
+    )?;
+
+    let consumer = ConsumerAccount {
+        is_initialized: true,
+        authority: *authority.key,
+        feed_id,
+        max_age_secs,
+        max_conf_bps,
+        price: 0,
+        conf: 0,
+        publish_time: 0,
+        bump,
+    };
+    consumer.serialize(&mut &mut consumer_account.data.borrow_mut()[..])?;
+
+    msg!("Consumer created with max age {}s, max confidence {} bps", max_age_secs, max_conf_bps);
+
+    Ok(())
+}
+
+fn update_price(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let consumer_account = next_account_info(accounts_iter)?;
+    let price_account = next_account_info(accounts_iter)?;
+
+    let mut consumer = load_consumer(program_id, consumer_account)?;
+    let update = load_price_update(price_account)?;
+    let message = &update.price_message;
+
+    let now = Clock::get()?.unix_timestamp;
+    if let Err(e) = validate_price(&update, &consumer.feed_id, now, consumer.max_age_secs, consumer.max_conf_bps) {
+        msg!(
+            "Rejected price {} ± {} (expo {}) published at {}",
+            message.price,
+            message.conf,
+            message.exponent,
+            message.publish_time
+        );
+        return Err(e.into());
+    }
+
+    // Never let an older update overwrite a newer one
+    if message.publish_time <= consumer.publish_time {
+        msg!("Price update is not newer than the stored price");
+        return Err(OracleError::StalePrice.into());
+    }
+
+    let price = scale_to_decimals(message.price as u64, message.exponent, PRICE_DECIMALS)
+        .filter(|&p| p > 0)
+        .ok_or(OracleError::InvalidPrice)?;
+    let conf = scale_to_decimals(message.conf, message.exponent, PRICE_DECIMALS).ok_or(OracleError::InvalidPrice)?;
+
+    consumer.price = price;
+    consumer.conf = conf;
+    consumer.publish_time = message.publish_time;
+    consumer.serialize(&mut &mut consumer_account.data.borrow_mut()[..])?;
+
+    msg!("Stored price {} ± {} at {}", price, conf, message.publish_time);
+
+    Ok(())
+}
+
+/// Validate the consumer account, returning its state
+fn load_consumer(program_id: &Pubkey, consumer_account: &AccountInfo) -> Result<ConsumerAccount, ProgramError> {
+    if consumer_account.owner != program_id {
+        msg!("Consumer account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let consumer = ConsumerAccount::try_from_slice(&consumer_account.data.borrow())?;
+
+    if !consumer.is_initialized {
+        msg!("Consumer has not been initialized");
+        return Err(OracleError::NotInitialized.into());
+    }
+
+    Ok(consumer)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_program::{clock::Epoch, entrypoint::SUCCESS, program_stubs};
+
+    /// Serves a Clock sysvar at a fixed time so handlers can run off-chain
+    struct TestSyscallStubs {
+        unix_timestamp: i64,
+    }
+
+    impl program_stubs::SyscallStubs for TestSyscallStubs {
+        fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+            unsafe {
+                *(var_addr as *mut Clock) = Clock {
+                    unix_timestamp: self.unix_timestamp,
+                    ..Clock::default()
+                };
+            }
+            SUCCESS
+        }
+    }
+
+    fn price_update(feed_id: [u8; 32], price: i64, conf: u64, publish_time: i64) -> PriceUpdateV2 {
+        PriceUpdateV2 {
+            write_authority: Pubkey::new_unique(),
+            verification_level: VerificationLevel::Full,
+            price_message: PriceFeedMessage {
+                feed_id,
+                price,
+                conf,
+                exponent: -8,
+                publish_time,
+                prev_publish_time: publish_time - 1,
+                ema_price: price,
+                ema_conf: conf,
+            },
+            posted_slot: 0,
+        }
+    }
+
+    #[test]
+    fn test_validate_and_scale_price() {
+        let feed_id = [1u8; 32];
+        let now = 1_700_000_000;
+
+        // $150.12345678 with a $0.15 confidence interval (10 bps)
+        let update = price_update(feed_id, 15_012_345_678, 15_000_000, now - 10);
+        assert_eq!(validate_price(&update, &feed_id, now, 30, 50), Ok(()));
+        assert_eq!(validate_price(&update, &[2u8; 32], now, 30, 50), Err(OracleError::WrongFeed));
+        assert_eq!(validate_price(&update, &feed_id, now, 5, 50), Err(OracleError::StalePrice));
+        assert_eq!(validate_price(&update, &feed_id, now, 30, 5), Err(OracleError::ConfidenceTooWide));
+
+        let mut partial = update.clone();
+        partial.verification_level = VerificationLevel::Partial { num_signatures: 5 };
+        assert_eq!(validate_price(&partial, &feed_id, now, 30, 50), Err(OracleError::InvalidPriceAccount));
+
+        // Scaling rounds down to PRICE_DECIMALS, or scales up from coarser exponents
+        assert_eq!(scale_to_decimals(15_012_345_678, -8, 6), Some(150_123_456));
+        assert_eq!(scale_to_decimals(150, 0, 6), Some(150_000_000));
+        assert_eq!(scale_to_decimals(u64::MAX, 10, 6), None);
+    }
+
+    #[test]
+    fn test_update_price_rejects_stale_price() {
+        let now = 1_700_000_000;
+        program_stubs::set_syscall_stubs(Box::new(TestSyscallStubs { unix_timestamp: now }));
+
+        let program_id = Pubkey::new_unique();
+        let consumer_key = Pubkey::new_unique();
+        let price_key = Pubkey::new_unique();
+        let feed_id = [1u8; 32];
+
+        let consumer = ConsumerAccount {
+            is_initialized: true,
+            authority: Pubkey::new_unique(),
+            feed_id,
+            max_age_secs: 60,
+            max_conf_bps: 100,
+            price: 0,
+            conf: 0,
+            publish_time: 0,
+            bump: 255,
+        };
+        let mut consumer_data = consumer.try_to_vec().unwrap();
+        let mut price_data = PRICE_UPDATE_V2_DISCRIMINATOR.to_vec();
+        price_data.extend(price_update(feed_id, 15_000_000_000, 1_000_000, now - 61).try_to_vec().unwrap());
+        let mut consumer_lamports = 0;
+        let mut price_lamports = 0;
+
+        let accounts = vec![
+            AccountInfo::new(
+                &consumer_key,
+                false,
+                true,
+                &mut consumer_lamports,
+                &mut consumer_data,
+                &program_id,
+                false,
+                Epoch::default(),
+            ),
+            AccountInfo::new(
+                &price_key,
+                false,
+                false,
+                &mut price_lamports,
+                &mut price_data,
+                &PYTH_RECEIVER_PROGRAM_ID,
+                false,
+                Epoch::default(),
+            ),
+        ];
+
+        let instruction_data = OracleInstruction::UpdatePrice.try_to_vec().unwrap();
+        let result = process_instruction(&program_id, &accounts, &instruction_data);
+        assert_eq!(result, Err(OracleError::StalePrice.into()));
+    }
+}
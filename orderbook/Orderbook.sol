@@ -0,0 +1,195 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+interface IERC20 {
+    function transfer(address to, uint256 amount) external returns (bool);
+    function transferFrom(address from, address to, uint256 amount) external returns (bool);
+}
+
+/**
+ * @title Orderbook
+ * @dev Limit orderbook for one base/quote pair with price-time priority and maker/taker fees
+ */
+contract Orderbook {
+    uint256 public constant BPS_DENOMINATOR = 10_000;
+    uint256 public constant MAX_FEE_BPS = 1_000;
+    uint256 public constant MAX_ORDERS_PER_SIDE = 32;
+
+    enum Side { Bid, Ask }
+
+    struct Order {
+        address owner;
+        Side side;
+        uint256 price; // quote units per base unit
+        uint256 quantity;
+        uint256 remaining;
+    }
+
+    IERC20 public immutable baseToken;
+    IERC20 public immutable quoteToken;
+    address public immutable feeRecipient;
+    uint256 public immutable makerFeeBps;
+    uint256 public immutable takerFeeBps;
+
+    Order[] public orders;
+    // Order ids sorted best price first, then oldest first
+    uint256[] public bids;
+    uint256[] public asks;
+
+    mapping(address => uint256) public claimableBase;
+    mapping(address => uint256) public claimableQuote;
+    uint256 public feesBase;
+    uint256 public feesQuote;
+
+    event OrderPlaced(uint256 indexed orderId, address indexed owner, Side side, uint256 price, uint256 quantity);
+    event OrderMatched(uint256 indexed bidId, uint256 indexed askId, uint256 price, uint256 quantity);
+    event OrderCancelled(uint256 indexed orderId);
+    event Claimed(address indexed owner, uint256 baseAmount, uint256 quoteAmount);
+
+    constructor(
+        IERC20 _baseToken,
+        IERC20 _quoteToken,
+        uint256 _makerFeeBps,
+        uint256 _takerFeeBps
+    ) {
+        require(address(_baseToken) != address(_quoteToken), "Tokens must differ");
+        require(_makerFeeBps <= MAX_FEE_BPS && _takerFeeBps <= MAX_FEE_BPS, "Fee too high");
+        baseToken = _baseToken;
+        quoteToken = _quoteToken;
+        feeRecipient = msg.sender;
+        makerFeeBps = _makerFeeBps;
+        takerFeeBps = _takerFeeBps;
+    }
+
+    /**
+     * @dev Escrow funds and rest a limit order on the book
+     */
+    function placeOrder(Side side, uint256 price, uint256 quantity) external returns (uint256 orderId) {
+        require(price > 0 && quantity > 0, "Price and quantity must be greater than zero");
+
+        uint256[] storage book = side == Side.Bid ? bids : asks;
+        require(book.length < MAX_ORDERS_PER_SIDE, "Book full");
+
+        if (side == Side.Bid) {
+            require(quoteToken.transferFrom(msg.sender, address(this), price * quantity), "Transfer failed");
+        } else {
+            require(baseToken.transferFrom(msg.sender, address(this), quantity), "Transfer failed");
+        }
+
+        orderId = orders.length;
+        orders.push(Order(msg.sender, side, price, quantity, quantity));
+
+        // Insert behind every order with the same or a better price
+        uint256 position = book.length;
+        for (uint256 i = 0; i < book.length; i++) {
+            uint256 other = orders[book[i]].price;
+            if (side == Side.Bid ? other < price : other > price) {
+                position = i;
+                break;
+            }
+        }
+        book.push();
+        for (uint256 i = book.length - 1; i > position; i--) {
+            book[i] = book[i - 1];
+        }
+        book[position] = orderId;
+
+        emit OrderPlaced(orderId, msg.sender, side, price, quantity);
+    }
+
+    /**
+     * @dev Fill the best bid against the best ask at the maker's price (callable by anyone)
+     */
+    function matchOrders() external {
+        require(bids.length > 0 && asks.length > 0, "Empty book");
+        uint256 bidId = bids[0];
+        uint256 askId = asks[0];
+        Order storage bid = orders[bidId];
+        Order storage ask = orders[askId];
+        require(bid.price >= ask.price, "Orders do not cross");
+
+        // The older order is the maker and sets the price
+        bool bidIsMaker = bidId < askId;
+        uint256 price = bidIsMaker ? bid.price : ask.price;
+        uint256 quantity = bid.remaining < ask.remaining ? bid.remaining : ask.remaining;
+        uint256 notional = quantity * price;
+
+        uint256 baseFee = (quantity * (bidIsMaker ? makerFeeBps : takerFeeBps)) / BPS_DENOMINATOR;
+        uint256 quoteFee = (notional * (bidIsMaker ? takerFeeBps : makerFeeBps)) / BPS_DENOMINATOR;
+
+        bid.remaining -= quantity;
+        ask.remaining -= quantity;
+        claimableBase[bid.owner] += quantity - baseFee;
+        claimableQuote[bid.owner] += (bid.price - price) * quantity;
+        claimableQuote[ask.owner] += notional - quoteFee;
+        feesBase += baseFee;
+        feesQuote += quoteFee;
+
+        if (bid.remaining == 0) _removeAt(bids, 0);
+        if (ask.remaining == 0) _removeAt(asks, 0);
+
+        emit OrderMatched(bidId, askId, price, quantity);
+    }
+
+    /**
+     * @dev Take an order off the book and make its unfilled escrow claimable
+     */
+    function cancelOrder(uint256 orderId) external {
+        Order storage order = orders[orderId];
+        require(order.owner == msg.sender, "Not order owner");
+        require(order.remaining > 0, "Order closed");
+
+        uint256[] storage book = order.side == Side.Bid ? bids : asks;
+        for (uint256 i = 0; i < book.length; i++) {
+            if (book[i] == orderId) {
+                _removeAt(book, i);
+                break;
+            }
+        }
+
+        if (order.side == Side.Bid) {
+            claimableQuote[msg.sender] += order.remaining * order.price;
+        } else {
+            claimableBase[msg.sender] += order.remaining;
+        }
+        order.remaining = 0;
+
+        emit OrderCancelled(orderId);
+    }
+
+    /**
+     * @dev Withdraw the caller's claimable balances
+     */
+    function claim() external {
+        uint256 baseAmount = claimableBase[msg.sender];
+        uint256 quoteAmount = claimableQuote[msg.sender];
+        require(baseAmount > 0 || quoteAmount > 0, "Nothing to claim");
+
+        claimableBase[msg.sender] = 0;
+        claimableQuote[msg.sender] = 0;
+        if (baseAmount > 0) require(baseToken.transfer(msg.sender, baseAmount), "Transfer failed");
+        if (quoteAmount > 0) require(quoteToken.transfer(msg.sender, quoteAmount), "Transfer failed");
+
+        emit Claimed(msg.sender, baseAmount, quoteAmount);
+    }
+
+    /**
+     * @dev Withdraw the collected fees to the deployer
+     */
+    function collectFees() external {
+        require(msg.sender == feeRecipient, "Only fee recipient");
+        uint256 baseAmount = feesBase;
+        uint256 quoteAmount = feesQuote;
+        feesBase = 0;
+        feesQuote = 0;
+        if (baseAmount > 0) require(baseToken.transfer(msg.sender, baseAmount), "Transfer failed");
+        if (quoteAmount > 0) require(quoteToken.transfer(msg.sender, quoteAmount), "Transfer failed");
+    }
+
+    function _removeAt(uint256[] storage book, uint256 index) private {
+        for (uint256 i = index; i + 1 < book.length; i++) {
+            book[i] = book[i + 1];
+        }
+        book.pop();
+    }
+}
@@ -0,0 +1,999 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint,
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use spl_token::state::Account as TokenAccount;
+
+/// Seed prefix for market PDAs: ["market", base_mint, quote_mint]
+pub const MARKET_SEED: &[u8] = b"market";
+
+/// Seed prefix for the market's vault PDAs: ["vault", market, mint]
+pub const VAULT_SEED: &[u8] = b"vault";
+
+/// Seed prefix for order PDAs: ["order", market, order_id]
+pub const ORDER_SEED: &[u8] = b"order";
+
+/// Fees are expressed in basis points of the amount received
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Highest maker or taker fee a market may charge (10%)
+pub const MAX_FEE_BPS: u16 = 1_000;
+
+/// Resting orders kept per side of the book
+pub const MAX_ORDERS_PER_SIDE: usize = 32;
+
+/// Size of a `MarketAccount` with both sides of the book full
+pub const MARKET_ACCOUNT_SIZE: usize = 1 // is_initialized
+    + 32 // authority
+    + 32 // base_mint
+    + 32 // quote_mint
+    + 32 // base_vault
+    + 32 // quote_vault
+    + 2 // maker_fee_bps
+    + 2 // taker_fee_bps
+    + 8 // next_order_id
+    + 8 // fees_base
+    + 8 // fees_quote
+    + 1 // bump
+    + 4 + MAX_ORDERS_PER_SIDE * (8 + 8) // bids
+    + 4 + MAX_ORDERS_PER_SIDE * (8 + 8); // asks
+
+/// Size of an `OrderAccount`
+pub const ORDER_ACCOUNT_SIZE: usize = 1 // is_initialized
+    + 32 // market
+    + 32 // owner
+    + 8 // order_id
+    + 1 // side
+    + 8 // price
+    + 8 // quantity
+    + 8 // remaining
+    + 8 // base_claimable
+    + 8 // quote_claimable
+    + 1; // bump
+
+/// Errors returned by the program as `ProgramError::Custom` codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderbookError {
+    /// Price or quantity is zero, a fee is above `MAX_FEE_BPS`, or the mints are identical
+    InvalidParameters = 0,
+    /// This side of the book already holds `MAX_ORDERS_PER_SIDE` orders
+    BookFull = 1,
+    /// The market or order has not been initialized
+    NotInitialized = 2,
+    /// The best bid is below the best ask
+    OrdersDoNotCross = 3,
+    /// An order passed to `Match` is not at the top of its side of the book
+    NotBestOrder = 4,
+    /// The order has already been filled or cancelled
+    OrderClosed = 5,
+    /// Only the order owner or market authority may do this
+    Unauthorized = 6,
+    /// A vault, order, or token account does not belong to this market
+    InvalidMarketAccount = 7,
+    /// The order has nothing to claim
+    NothingToClaim = 8,
+}
+
+impl From<OrderbookError> for ProgramError {
+    fn from(e: OrderbookError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    /// Buy base with quote
+    Bid,
+    /// Sell base for quote
+    Ask,
+}
+
+/// A resting order's position in the book
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BookEntry {
+    pub order_id: u64,
+    pub price: u64,
+}
+
+/// A base/quote market. Each side of the book is kept sorted best price
+/// first, then oldest first, so the top entries are always the next to match.
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct MarketAccount {
+    pub is_initialized: bool,
+    /// Receives the collected fees
+    pub authority: Pubkey,
+    pub base_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    /// Token accounts holding all escrowed and claimable funds, owned by the market PDA
+    pub base_vault: Pubkey,
+    pub quote_vault: Pubkey,
+    /// Fee charged to the resting (older) order of a fill
+    pub maker_fee_bps: u16,
+    /// Fee charged to the incoming (newer) order of a fill
+    pub taker_fee_bps: u16,
+    /// Sequence number of the next order; lower ids were placed earlier
+    pub next_order_id: u64,
+    /// Fees collected and not yet withdrawn
+    pub fees_base: u64,
+    pub fees_quote: u64,
+    pub bump: u8,
+    /// Highest price first
+    pub bids: Vec<BookEntry>,
+    /// Lowest price first
+    pub asks: Vec<BookEntry>,
+}
+
+impl MarketAccount {
+    fn book_mut(&mut self, side: Side) -> &mut Vec<BookEntry> {
+        match side {
+            Side::Bid => &mut self.bids,
+            Side::Ask => &mut self.asks,
+        }
+    }
+
+    /// Insert an order behind every order with the same or a better price
+    pub fn insert_order(&mut self, side: Side, entry: BookEntry) -> Result<(), OrderbookError> {
+        let book = self.book_mut(side);
+        if book.len() >= MAX_ORDERS_PER_SIDE {
+            return Err(OrderbookError::BookFull);
+        }
+
+        let position = book
+            .iter()
+            .position(|e| match side {
+                Side::Bid => e.price < entry.price,
+                Side::Ask => e.price > entry.price,
+            })
+            .unwrap_or(book.len());
+        book.insert(position, entry);
+
+        Ok(())
+    }
+
+    /// Remove an order from the book, returning whether it was there
+    pub fn remove_order(&mut self, side: Side, order_id: u64) -> bool {
+        let book = self.book_mut(side);
+        match book.iter().position(|e| e.order_id == order_id) {
+            Some(position) => {
+                book.remove(position);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// A limit order. Its unfilled quantity is escrowed in the market vaults, and
+/// the proceeds of fills accumulate here until the owner claims them.
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct OrderAccount {
+    pub is_initialized: bool,
+    pub market: Pubkey,
+    pub owner: Pubkey,
+    pub order_id: u64,
+    pub side: Side,
+    /// Quote base units per base unit
+    pub price: u64,
+    pub quantity: u64,
+    /// Quantity not yet filled or cancelled
+    pub remaining: u64,
+    /// Base tokens owed to the owner
+    pub base_claimable: u64,
+    /// Quote tokens owed to the owner
+    pub quote_claimable: u64,
+    pub bump: u8,
+}
+
+/// The result of matching a bid against an ask
+#[derive(Debug, PartialEq, Eq)]
+pub struct Fill {
+    pub quantity: u64,
+    pub price: u64,
+    /// Fee taken from the base tokens the buyer receives
+    pub base_fee: u64,
+    /// Fee taken from the quote tokens the seller receives
+    pub quote_fee: u64,
+}
+
+/// `bps` of `amount`, rounded down
+pub fn fee_amount(amount: u64, bps: u16) -> u64 {
+    (amount as u128 * bps as u128 / BPS_DENOMINATOR as u128) as u64
+}
+
+/// Fill as much of `bid` and `ask` as possible at the maker's price, crediting
+/// each order with what it receives after fees. A bid that fills below its
+/// own price is refunded the difference.
+pub fn match_orders(
+    bid: &mut OrderAccount,
+    ask: &mut OrderAccount,
+    maker_fee_bps: u16,
+    taker_fee_bps: u16,
+) -> Result<Fill, ProgramError> {
+    if bid.price < ask.price {
+        return Err(OrderbookError::OrdersDoNotCross.into());
+    }
+
+    let bid_is_maker = bid.order_id < ask.order_id;
+    let (price, buyer_fee_bps, seller_fee_bps) = if bid_is_maker {
+        (bid.price, maker_fee_bps, taker_fee_bps)
+    } else {
+        (ask.price, taker_fee_bps, maker_fee_bps)
+    };
+
+    let quantity = bid.remaining.min(ask.remaining);
+    let notional = quantity.checked_mul(price).ok_or(ProgramError::ArithmeticOverflow)?;
+    let refund = (bid.price - price)
+        .checked_mul(quantity)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    let base_fee = fee_amount(quantity, buyer_fee_bps);
+    let quote_fee = fee_amount(notional, seller_fee_bps);
+
+    bid.remaining -= quantity;
+    ask.remaining -= quantity;
+    bid.base_claimable = bid
+        .base_claimable
+        .checked_add(quantity - base_fee)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    bid.quote_claimable = bid
+        .quote_claimable
+        .checked_add(refund)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    ask.quote_claimable = ask
+        .quote_claimable
+        .checked_add(notional - quote_fee)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    Ok(Fill {
+        quantity,
+        price,
+        base_fee,
+        quote_fee,
+    })
+}
+
+entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = OrderbookInstruction::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        OrderbookInstruction::InitializeMarket {
+            maker_fee_bps,
+            taker_fee_bps,
+        } => initialize_market(program_id, accounts, maker_fee_bps, taker_fee_bps),
+        OrderbookInstruction::PlaceOrder { side, price, quantity } => {
+            place_order(program_id, accounts, side, price, quantity)
+        }
+        OrderbookInstruction::Match => match_best_orders(program_id, accounts),
+        OrderbookInstruction::CancelOrder => cancel_order(program_id, accounts),
+        OrderbookInstruction::Claim => claim(program_id, accounts),
+        OrderbookInstruction::CollectFees => collect_fees(program_id, accounts),
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub enum OrderbookInstruction {
+    /// Create the market and both vaults
+    /// Accounts: [writable, signer] authority, [writable] market PDA, [] base mint, [] quote mint,
+    /// [writable] base vault PDA, [writable] quote vault PDA, [] token program, [] system program
+    InitializeMarket { maker_fee_bps: u16, taker_fee_bps: u16 },
+    /// Escrow funds and rest a limit order on the book (quote for a bid, base for an ask)
+    /// Accounts: [writable, signer] owner, [writable] market PDA, [writable] order PDA,
+    /// [writable] owner source token account, [writable] vault for the escrowed mint,
+    /// [] token program, [] system program
+    PlaceOrder { side: Side, price: u64, quantity: u64 },
+    /// Fill the best bid against the best ask; callable by anyone
+    /// Accounts: [writable] market PDA, [writable] best bid order, [writable] best ask order
+    Match,
+    /// Take an order off the book and make its unfilled escrow claimable
+    /// Accounts: [signer] owner, [writable] market PDA, [writable] order PDA
+    CancelOrder,
+    /// Pay out an order's claimable balances, closing it once it is no longer on the book
+    /// Accounts: [writable, signer] owner, [] market PDA, [writable] order PDA,
+    /// [writable] base vault, [writable] quote vault, [writable] owner base token account,
+    /// [writable] owner quote token account, [] token program
+    Claim,
+    /// Withdraw the collected fees to the market authority
+    /// Accounts: [signer] authority, [writable] market PDA, [writable] base vault,
+    /// [writable] quote vault, [writable] authority base token account,
+    /// [writable] authority quote token account, [] token program
+    CollectFees,
+}
+
+fn initialize_market(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    maker_fee_bps: u16,
+    taker_fee_bps: u16,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let authority = next_account_info(accounts_iter)?;
+    let market_account = next_account_info(accounts_iter)?;
+    let base_mint = next_account_info(accounts_iter)?;
+    let quote_mint = next_account_info(accounts_iter)?;
+    let base_vault = next_account_info(accounts_iter)?;
+    let quote_vault = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if maker_fee_bps > MAX_FEE_BPS || taker_fee_bps > MAX_FEE_BPS {
+        msg!("Fees must be at most {} bps", MAX_FEE_BPS);
+        return Err(OrderbookError::InvalidParameters.into());
+    }
+
+    if base_mint.key == quote_mint.key {
+        msg!("Base and quote mints must differ");
+        return Err(OrderbookError::InvalidParameters.into());
+    }
+
+    if *token_program.key != spl_token::id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let (expected_market, bump) = Pubkey::find_program_address(
+        &[MARKET_SEED, base_mint.key.as_ref(), quote_mint.key.as_ref()],
+        program_id,
+    );
+    if expected_market != *market_account.key {
+        msg!("Market account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let (expected_base_vault, base_vault_bump) = Pubkey::find_program_address(
+        &[VAULT_SEED, market_account.key.as_ref(), base_mint.key.as_ref()],
+        program_id,
+    );
+    let (expected_quote_vault, quote_vault_bump) = Pubkey::find_program_address(
+        &[VAULT_SEED, market_account.key.as_ref(), quote_mint.key.as_ref()],
+        program_id,
+    );
+    if expected_base_vault != *base_vault.key || expected_quote_vault != *quote_vault.key {
+        msg!("Vault account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let rent = Rent::get()?;
+
+    invoke_signed(
+        &system_instruction::create_account(
+            authority.key,
+            market_account.key,
+            rent.minimum_balance(MARKET_ACCOUNT_SIZE),
+            MARKET_ACCOUNT_SIZE as u64,
+            program_id,
+        ),
+        &[authority.clone(), market_account.clone(), system_program.clone()],
+        &[&[MARKET_SEED, base_mint.key.as_ref(), quote_mint.key.as_ref(), &[bump]]],
+    )?;
+
+    // Create both vaults, owned by the market PDA
+    for (vault, mint, vault_bump) in [
+        (base_vault, base_mint, base_vault_bump),
+        (quote_vault, quote_mint, quote_vault_bump),
+    ] {
+        invoke_signed(
+            &system_instruction::create_account(
+                authority.key,
+                vault.key,
+                rent.minimum_balance(TokenAccount::LEN),
+                TokenAccount::LEN as u64,
+                &spl_token::id(),
+            ),
+            &[authority.clone(), vault.clone(), system_program.clone()],
+            &[&[VAULT_SEED, market_account.key.as_ref(), mint.key.as_ref(), &[vault_bump]]],
+        )?;
+        invoke(
+            &spl_token::instruction::initialize_account3(
+                &spl_token::id(),
+                vault.key,
+                mint.key,
+                market_account.key,
+            )?,
+            &[vault.clone(), mint.clone()],
+        )?;
+    }
+
+    let market = MarketAccount {
+        is_initialized: true,
+        authority: *authority.key,
+        base_mint: *base_mint.key,
+        quote_mint: *quote_mint.key,
+        base_vault: *base_vault.key,
+        quote_vault: *quote_vault.key,
+        maker_fee_bps,
+        taker_fee_bps,
+        next_order_id: 0,
+        fees_base: 0,
+        fees_quote: 0,
+        bump,
+        bids: Vec::new(),
+        asks: Vec::new(),
+    };
+    market.serialize(&mut &mut market_account.data.borrow_mut()[..])?;
+
+    msg!(
+        "Market {} / {} created with maker fee {} bps, taker fee {} bps",
+        base_mint.key,
+        quote_mint.key,
+        maker_fee_bps,
+        taker_fee_bps
+    );
+
+    Ok(())
+}
+
+fn place_order(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    side: Side,
+    price: u64,
+    quantity: u64,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let owner = next_account_info(accounts_iter)?;
+    let market_account = next_account_info(accounts_iter)?;
+    let order_account = next_account_info(accounts_iter)?;
+    let source = next_account_info(accounts_iter)?;
+    let vault = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if price == 0 || quantity == 0 {
+        msg!("Price and quantity must be greater than zero");
+        return Err(OrderbookError::InvalidParameters.into());
+    }
+
+    if *token_program.key != spl_token::id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let mut market = load_market(program_id, market_account)?;
+
+    let (escrow_vault, escrow_amount) = match side {
+        Side::Bid => (
+            market.quote_vault,
+            quantity.checked_mul(price).ok_or(ProgramError::ArithmeticOverflow)?,
+        ),
+        Side::Ask => (market.base_vault, quantity),
+    };
+    if escrow_vault != *vault.key {
+        msg!("Vault does not match the escrowed side of the market");
+        return Err(OrderbookError::InvalidMarketAccount.into());
+    }
+
+    let order_id = market.next_order_id;
+    let (expected_order, order_bump) = Pubkey::find_program_address(
+        &[ORDER_SEED, market_account.key.as_ref(), &order_id.to_le_bytes()],
+        program_id,
+    );
+    if expected_order != *order_account.key {
+        msg!("Order account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    if let Err(e) = market.insert_order(side, BookEntry { order_id, price }) {
+        msg!("The {:?} side of the book is full", side);
+        return Err(e.into());
+    }
+    market.next_order_id += 1;
+
+    invoke_signed(
+        &system_instruction::create_account(
+            owner.key,
+            order_account.key,
+            Rent::get()?.minimum_balance(ORDER_ACCOUNT_SIZE),
+            ORDER_ACCOUNT_SIZE as u64,
+            program_id,
+        ),
+        &[owner.clone(), order_account.clone(), system_program.clone()],
+        &[&[ORDER_SEED, market_account.key.as_ref(), &order_id.to_le_bytes(), &[order_bump]]],
+    )?;
+
+    invoke(
+        &spl_token::instruction::transfer(&spl_token::id(), source.key, vault.key, owner.key, &[], escrow_amount)?,
+        &[source.clone(), vault.clone(), owner.clone()],
+    )?;
+
+    let order = OrderAccount {
+        is_initialized: true,
+        market: *market_account.key,
+        owner: *owner.key,
+        order_id,
+        side,
+        price,
+        quantity,
+        remaining: quantity,
+        base_claimable: 0,
+        quote_claimable: 0,
+        bump: order_bump,
+    };
+    order.serialize(&mut &mut order_account.data.borrow_mut()[..])?;
+    market.serialize(&mut &mut market_account.data.borrow_mut()[..])?;
+
+    msg!("Order {} placed: {:?} {} at {}", order_id, side, quantity, price);
+
+    Ok(())
+}
+
+fn match_best_orders(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let market_account = next_account_info(accounts_iter)?;
+    let bid_account = next_account_info(accounts_iter)?;
+    let ask_account = next_account_info(accounts_iter)?;
+
+    let mut market = load_market(program_id, market_account)?;
+    let mut bid = load_order(program_id, market_account, bid_account)?;
+    let mut ask = load_order(program_id, market_account, ask_account)?;
+
+    // Price-time priority: only the top of each side may trade
+    let best_bid = market.bids.first().map(|e| e.order_id);
+    let best_ask = market.asks.first().map(|e| e.order_id);
+    if bid.side != Side::Bid || best_bid != Some(bid.order_id) {
+        msg!("Bid order is not the best bid");
+        return Err(OrderbookError::NotBestOrder.into());
+    }
+    if ask.side != Side::Ask || best_ask != Some(ask.order_id) {
+        msg!("Ask order is not the best ask");
+        return Err(OrderbookError::NotBestOrder.into());
+    }
+
+    let fill = match_orders(&mut bid, &mut ask, market.maker_fee_bps, market.taker_fee_bps)?;
+
+    market.fees_base = market
+        .fees_base
+        .checked_add(fill.base_fee)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    market.fees_quote = market
+        .fees_quote
+        .checked_add(fill.quote_fee)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    if bid.remaining == 0 {
+        market.bids.remove(0);
+    }
+    if ask.remaining == 0 {
+        market.asks.remove(0);
+    }
+
+    bid.serialize(&mut &mut bid_account.data.borrow_mut()[..])?;
+    ask.serialize(&mut &mut ask_account.data.borrow_mut()[..])?;
+    market.serialize(&mut &mut market_account.data.borrow_mut()[..])?;
+
+    msg!(
+        "Filled {} at {} between bid {} and ask {}",
+        fill.quantity,
+        fill.price,
+        bid.order_id,
+        ask.order_id
+    );
+
+    Ok(())
+}
+
+fn cancel_order(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let owner = next_account_info(accounts_iter)?;
+    let market_account = next_account_info(accounts_iter)?;
+    let order_account = next_account_info(accounts_iter)?;
+
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut market = load_market(program_id, market_account)?;
+    let mut order = load_order(program_id, market_account, order_account)?;
+
+    if order.owner != *owner.key {
+        msg!("Only the order owner can cancel");
+        return Err(OrderbookError::Unauthorized.into());
+    }
+
+    if order.remaining == 0 || !market.remove_order(order.side, order.order_id) {
+        msg!("Order {} is no longer on the book", order.order_id);
+        return Err(OrderbookError::OrderClosed.into());
+    }
+
+    // Return the unfilled escrow through the claimable balances
+    match order.side {
+        Side::Bid => {
+            let refund = order
+                .remaining
+                .checked_mul(order.price)
+                .ok_or(ProgramError::ArithmeticOverflow)?;
+            order.quote_claimable = order
+                .quote_claimable
+                .checked_add(refund)
+                .ok_or(ProgramError::ArithmeticOverflow)?;
+        }
+        Side::Ask => {
+            order.base_claimable = order
+                .base_claimable
+                .checked_add(order.remaining)
+                .ok_or(ProgramError::ArithmeticOverflow)?;
+        }
+    }
+    let cancelled = order.remaining;
+    order.remaining = 0;
+
+    order.serialize(&mut &mut order_account.data.borrow_mut()[..])?;
+    market.serialize(&mut &mut market_account.data.borrow_mut()[..])?;
+
+    msg!("Order {} cancelled with {} unfilled", order.order_id, cancelled);
+
+    Ok(())
+}
+
+fn claim(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let owner = next_account_info(accounts_iter)?;
+    let market_account = next_account_info(accounts_iter)?;
+    let order_account = next_account_info(accounts_iter)?;
+    let base_vault = next_account_info(accounts_iter)?;
+    let quote_vault = next_account_info(accounts_iter)?;
+    let owner_base = next_account_info(accounts_iter)?;
+    let owner_quote = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if *token_program.key != spl_token::id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let market = load_market(program_id, market_account)?;
+    let mut order = load_order(program_id, market_account, order_account)?;
+
+    if order.owner != *owner.key {
+        msg!("Only the order owner can claim");
+        return Err(OrderbookError::Unauthorized.into());
+    }
+
+    if market.base_vault != *base_vault.key || market.quote_vault != *quote_vault.key {
+        msg!("Vault accounts do not match the market");
+        return Err(OrderbookError::InvalidMarketAccount.into());
+    }
+
+    let close = order.remaining == 0;
+    if order.base_claimable == 0 && order.quote_claimable == 0 && !close {
+        return Err(OrderbookError::NothingToClaim.into());
+    }
+
+    let (base_amount, quote_amount) = (order.base_claimable, order.quote_claimable);
+    pay_from_vaults(
+        &market,
+        market_account,
+        base_vault,
+        quote_vault,
+        owner_base,
+        owner_quote,
+        base_amount,
+        quote_amount,
+    )?;
+    order.base_claimable = 0;
+    order.quote_claimable = 0;
+
+    if close {
+        // Filled or cancelled orders are off the book, so the account can go
+        let lamports = order_account.lamports();
+        **order_account.try_borrow_mut_lamports()? = 0;
+        **owner.try_borrow_mut_lamports()? += lamports;
+        order_account.data.borrow_mut().fill(0);
+    } else {
+        order.serialize(&mut &mut order_account.data.borrow_mut()[..])?;
+    }
+
+    msg!("Order {} claimed {} base and {} quote", order.order_id, base_amount, quote_amount);
+
+    Ok(())
+}
+
+fn collect_fees(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let authority = next_account_info(accounts_iter)?;
+    let market_account = next_account_info(accounts_iter)?;
+    let base_vault = next_account_info(accounts_iter)?;
+    let quote_vault = next_account_info(accounts_iter)?;
+    let authority_base = next_account_info(accounts_iter)?;
+    let authority_quote = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+
+    if !authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if *token_program.key != spl_token::id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let mut market = load_market(program_id, market_account)?;
+
+    if market.authority != *authority.key {
+        msg!("Only the market authority can collect fees");
+        return Err(OrderbookError::Unauthorized.into());
+    }
+
+    if market.base_vault != *base_vault.key || market.quote_vault != *quote_vault.key {
+        msg!("Vault accounts do not match the market");
+        return Err(OrderbookError::InvalidMarketAccount.into());
+    }
+
+    let (base_amount, quote_amount) = (market.fees_base, market.fees_quote);
+    if base_amount == 0 && quote_amount == 0 {
+        return Err(OrderbookError::NothingToClaim.into());
+    }
+
+    pay_from_vaults(
+        &market,
+        market_account,
+        base_vault,
+        quote_vault,
+        authority_base,
+        authority_quote,
+        base_amount,
+        quote_amount,
+    )?;
+    market.fees_base = 0;
+    market.fees_quote = 0;
+    market.serialize(&mut &mut market_account.data.borrow_mut()[..])?;
+
+    msg!("Collected {} base and {} quote in fees", base_amount, quote_amount);
+
+    Ok(())
+}
+
+/// Transfer the given amounts out of the market vaults, skipping zero amounts
+#[allow(clippy::too_many_arguments)]
+fn pay_from_vaults<'a>(
+    market: &MarketAccount,
+    market_account: &AccountInfo<'a>,
+    base_vault: &AccountInfo<'a>,
+    quote_vault: &AccountInfo<'a>,
+    base_destination: &AccountInfo<'a>,
+    quote_destination: &AccountInfo<'a>,
+    base_amount: u64,
+    quote_amount: u64,
+) -> ProgramResult {
+    let market_seeds: &[&[u8]] = &[
+        MARKET_SEED,
+        market.base_mint.as_ref(),
+        market.quote_mint.as_ref(),
+        &[market.bump],
+    ];
+    for (vault, destination, amount) in [
+        (base_vault, base_destination, base_amount),
+        (quote_vault, quote_destination, quote_amount),
+    ] {
+        if amount == 0 {
+            continue;
+        }
+        invoke_signed(
+            &spl_token::instruction::transfer(
+                &spl_token::id(),
+                vault.key,
+                destination.key,
+                market_account.key,
+                &[],
+                amount,
+            )?,
+            &[vault.clone(), destination.clone(), market_account.clone()],
+            &[market_seeds],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Validate the market account, returning its state
+fn load_market(program_id: &Pubkey, market_account: &AccountInfo) -> Result<MarketAccount, ProgramError> {
+    if market_account.owner != program_id {
+        msg!("Market account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let market = MarketAccount::deserialize(&mut &market_account.data.borrow()[..])?;
+
+    if !market.is_initialized {
+        msg!("Market has not been initialized");
+        return Err(OrderbookError::NotInitialized.into());
+    }
+
+    Ok(market)
+}
+
+/// Validate an order account belonging to `market_account`, returning its state
+fn load_order(
+    program_id: &Pubkey,
+    market_account: &AccountInfo,
+    order_account: &AccountInfo,
+) -> Result<OrderAccount, ProgramError> {
+    if order_account.owner != program_id {
+        msg!("Order account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let order = OrderAccount::try_from_slice(&order_account.data.borrow())?;
+
+    if !order.is_initialized {
+        msg!("Order has not been initialized");
+        return Err(OrderbookError::NotInitialized.into());
+    }
+
+    if order.market != *market_account.key {
+        msg!("Order does not belong to this market");
+        return Err(OrderbookError::InvalidMarketAccount.into());
+    }
+
+    Ok(order)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_program::clock::Epoch;
+
+    fn new_market() -> MarketAccount {
+        MarketAccount {
+            is_initialized: true,
+            authority: Pubkey::new_unique(),
+            base_mint: Pubkey::new_unique(),
+            quote_mint: Pubkey::new_unique(),
+            base_vault: Pubkey::new_unique(),
+            quote_vault: Pubkey::new_unique(),
+            maker_fee_bps: 10,
+            taker_fee_bps: 30,
+            next_order_id: 0,
+            fees_base: 0,
+            fees_quote: 0,
+            bump: 255,
+            bids: Vec::new(),
+            asks: Vec::new(),
+        }
+    }
+
+    fn new_order(market: Pubkey, order_id: u64, side: Side, price: u64, quantity: u64) -> OrderAccount {
+        OrderAccount {
+            is_initialized: true,
+            market,
+            owner: Pubkey::new_unique(),
+            order_id,
+            side,
+            price,
+            quantity,
+            remaining: quantity,
+            base_claimable: 0,
+            quote_claimable: 0,
+            bump: 255,
+        }
+    }
+
+    #[test]
+    fn test_book_keeps_price_time_priority() {
+        let mut market = new_market();
+
+        for (order_id, price) in [(0, 100), (1, 105), (2, 100), (3, 99)] {
+            market.insert_order(Side::Bid, BookEntry { order_id, price }).unwrap();
+            market.insert_order(Side::Ask, BookEntry { order_id, price }).unwrap();
+        }
+
+        // Bids: highest price first; asks: lowest first; ties by order id
+        let bid_ids: Vec<u64> = market.bids.iter().map(|e| e.order_id).collect();
+        let ask_ids: Vec<u64> = market.asks.iter().map(|e| e.order_id).collect();
+        assert_eq!(bid_ids, vec![1, 0, 2, 3]);
+        assert_eq!(ask_ids, vec![3, 0, 2, 1]);
+
+        assert!(market.remove_order(Side::Bid, 0));
+        assert!(!market.remove_order(Side::Bid, 0));
+        assert_eq!(market.bids.first().map(|e| e.order_id), Some(1));
+
+        let mut full = new_market();
+        for order_id in 0..MAX_ORDERS_PER_SIDE as u64 {
+            full.insert_order(Side::Ask, BookEntry { order_id, price: 1 }).unwrap();
+        }
+        assert_eq!(
+            full.insert_order(Side::Ask, BookEntry { order_id: 99, price: 1 }),
+            Err(OrderbookError::BookFull)
+        );
+    }
+
+    #[test]
+    fn test_match_fills_at_maker_price_and_charges_fees() {
+        let program_id = Pubkey::new_unique();
+        let market_key = Pubkey::new_unique();
+        let bid_key = Pubkey::new_unique();
+        let ask_key = Pubkey::new_unique();
+
+        // The ask rests first at 100; a later bid at 110 takes 400 of its 500
+        let mut market = new_market();
+        market.insert_order(Side::Ask, BookEntry { order_id: 0, price: 100 }).unwrap();
+        market.insert_order(Side::Bid, BookEntry { order_id: 1, price: 110 }).unwrap();
+        market.next_order_id = 2;
+        let ask = new_order(market_key, 0, Side::Ask, 100, 500);
+        let bid = new_order(market_key, 1, Side::Bid, 110, 400);
+
+        let mut market_data = vec![0u8; MARKET_ACCOUNT_SIZE];
+        market.serialize(&mut &mut market_data[..]).unwrap();
+        let mut bid_data = bid.try_to_vec().unwrap();
+        let mut ask_data = ask.try_to_vec().unwrap();
+        let mut market_lamports = 0;
+        let mut bid_lamports = 0;
+        let mut ask_lamports = 0;
+
+        let accounts = vec![
+            AccountInfo::new(
+                &market_key,
+                false,
+                true,
+                &mut market_lamports,
+                &mut market_data,
+                &program_id,
+                false,
+                Epoch::default(),
+            ),
+            AccountInfo::new(
+                &bid_key,
+                false,
+                true,
+                &mut bid_lamports,
+                &mut bid_data,
+                &program_id,
+                false,
+                Epoch::default(),
+            ),
+            AccountInfo::new(
+                &ask_key,
+                false,
+                true,
+                &mut ask_lamports,
+                &mut ask_data,
+                &program_id,
+                false,
+                Epoch::default(),
+            ),
+        ];
+
+        let instruction_data = OrderbookInstruction::Match.try_to_vec().unwrap();
+        process_instruction(&program_id, &accounts, &instruction_data).unwrap();
+
+        let bid = OrderAccount::try_from_slice(&accounts[1].data.borrow()).unwrap();
+        let ask = OrderAccount::try_from_slice(&accounts[2].data.borrow()).unwrap();
+        let market = MarketAccount::deserialize(&mut &accounts[0].data.borrow()[..]).unwrap();
+
+        // Filled at the maker's 100: the buyer pays the 30 bps taker fee in base and
+        // gets back the 10 per unit it escrowed above the fill price; the seller
+        // pays the 10 bps maker fee in quote
+        assert_eq!(bid.remaining, 0);
+        assert_eq!(bid.base_claimable, 400 - 1);
+        assert_eq!(bid.quote_claimable, 4_000);
+        assert_eq!(ask.remaining, 100);
+        assert_eq!(ask.quote_claimable, 40_000 - 40);
+        assert_eq!((market.fees_base, market.fees_quote), (1, 40));
+
+        // The filled bid leaves the book; the partly filled ask stays on top
+        assert!(market.bids.is_empty());
+        assert_eq!(market.asks, vec![BookEntry { order_id: 0, price: 100 }]);
+    }
+}
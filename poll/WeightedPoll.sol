@@ -0,0 +1,91 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+/// Tokens with checkpointed balances, such as OpenZeppelin's ERC20Votes
+interface IVotesToken {
+    function getPastVotes(address account, uint256 blockNumber) external view returns (uint256);
+}
+
+/**
+ * @title WeightedPoll
+ * @dev Multiple-choice polls weighted by token balance at the block the poll
+ * was created. The token keeps balance checkpoints, so the contract can read
+ * past balances directly and tokens moved after creation carry no weight.
+ */
+contract WeightedPoll {
+    uint8 public constant MAX_CHOICES = 8;
+
+    struct Poll {
+        address creator;
+        uint256 snapshotBlock;
+        uint256 endBlock;
+        uint256[] tallies;
+        bool closed;
+        bool hasWinner;
+        uint8 winner;
+    }
+
+    IVotesToken public immutable token;
+    Poll[] private polls;
+    mapping(uint256 => mapping(address => bool)) public hasVoted;
+
+    event PollCreated(uint256 indexed pollId, address indexed creator, uint256 snapshotBlock, uint256 endBlock);
+    event Voted(uint256 indexed pollId, address indexed voter, uint8 choice, uint256 weight);
+    event PollClosed(uint256 indexed pollId, bool hasWinner, uint8 winner);
+
+    constructor(IVotesToken _token) {
+        token = _token;
+    }
+
+    function createPoll(uint8 numChoices, uint256 durationBlocks) external returns (uint256 pollId) {
+        require(numChoices >= 2 && numChoices <= MAX_CHOICES && durationBlocks > 0, "Invalid parameters");
+        pollId = polls.length;
+        Poll storage poll = polls.push();
+        poll.creator = msg.sender;
+        // getPastVotes needs a block that has already been mined
+        poll.snapshotBlock = block.number - 1;
+        poll.endBlock = block.number + durationBlocks;
+        poll.tallies = new uint256[](numChoices);
+        emit PollCreated(pollId, msg.sender, poll.snapshotBlock, poll.endBlock);
+    }
+
+    function vote(uint256 pollId, uint8 choice) external {
+        Poll storage poll = polls[pollId];
+        require(block.number < poll.endBlock, "Voting ended");
+        require(choice < poll.tallies.length, "Invalid choice");
+        require(!hasVoted[pollId][msg.sender], "Already voted");
+
+        uint256 weight = token.getPastVotes(msg.sender, poll.snapshotBlock);
+        require(weight > 0, "No weight");
+
+        hasVoted[pollId][msg.sender] = true;
+        poll.tallies[choice] += weight;
+        emit Voted(pollId, msg.sender, choice, weight);
+    }
+
+    /// Anyone can close a poll once voting has ended; a tie has no winner
+    function close(uint256 pollId) external {
+        Poll storage poll = polls[pollId];
+        require(block.number >= poll.endBlock, "Voting not ended");
+        require(!poll.closed, "Already closed");
+        poll.closed = true;
+
+        uint256 best;
+        bool tie;
+        for (uint8 i = 0; i < poll.tallies.length; i++) {
+            if (poll.tallies[i] > best) {
+                best = poll.tallies[i];
+                poll.winner = i;
+                tie = false;
+            } else if (poll.tallies[i] == best) {
+                tie = true;
+            }
+        }
+        poll.hasWinner = best > 0 && !tie;
+        emit PollClosed(pollId, poll.hasWinner, poll.winner);
+    }
+
+    function getTallies(uint256 pollId) external view returns (uint256[] memory) {
+        return polls[pollId].tallies;
+    }
+}
@@ -0,0 +1,533 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint,
+    entrypoint::ProgramResult,
+    hash::hashv,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use spl_token::state::Account as TokenAccount;
+
+/// Seed prefix for poll PDAs: ["poll", creator, poll_id (u64 LE)]
+pub const POLL_SEED: &[u8] = b"poll";
+
+/// Seed prefix for ballot PDAs: ["ballot", poll, voter]
+pub const BALLOT_SEED: &[u8] = b"ballot";
+
+/// Most choices a poll can offer
+pub const MAX_CHOICES: usize = 8;
+
+/// Longest Merkle proof accepted (trees of up to 2^20 holders)
+pub const MAX_PROOF_LENGTH: usize = 20;
+
+/// Size of a `PollAccount` with `MAX_CHOICES` choices and a winner
+pub const POLL_ACCOUNT_SIZE: usize = 1 // is_initialized
+    + 32 // creator
+    + 8 // poll_id
+    + 32 // mint
+    + 8 // snapshot_slot
+    + 32 // snapshot_root
+    + 8 // end_slot
+    + 4 + 8 * MAX_CHOICES // tallies
+    + 1 // closed
+    + 1 + 1 // winner
+    + 1; // bump
+
+/// Size of a `Ballot`
+pub const BALLOT_SIZE: usize = 32 // poll
+    + 32 // voter
+    + 1 // choice
+    + 8; // weight
+
+/// Errors returned by the program as `ProgramError::Custom` codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PollError {
+    /// Choice count or duration is out of range
+    InvalidParameters = 0,
+    /// The Merkle proof does not match the snapshot root
+    InvalidProof = 1,
+    /// Voting has ended
+    VotingEnded = 2,
+    /// Voting has not ended yet
+    VotingNotEnded = 3,
+    /// The voter already has a ballot for this poll
+    AlreadyVoted = 4,
+    /// Choice index is out of range
+    InvalidChoice = 5,
+    /// The token account is not the voter's account for the poll's mint
+    WrongTokenAccount = 6,
+    /// The poll has not been initialized
+    NotInitialized = 7,
+    /// The voter's weight is zero
+    NoWeight = 8,
+    /// The poll has already been closed
+    AlreadyClosed = 9,
+}
+
+impl From<PollError> for ProgramError {
+    fn from(e: PollError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+/// A token-weighted poll. Weights come from a snapshot of balances taken at
+/// `snapshot_slot`, committed to as a Merkle root of `(voter, balance)` leaves
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct PollAccount {
+    pub is_initialized: bool,
+    pub creator: Pubkey,
+    pub poll_id: u64,
+    pub mint: Pubkey,
+    /// Slot the poll was created in; the snapshot reflects balances at this slot
+    pub snapshot_slot: u64,
+    pub snapshot_root: [u8; 32],
+    /// Votes are accepted before this slot
+    pub end_slot: u64,
+    /// Total weight per choice
+    pub tallies: Vec<u64>,
+    pub closed: bool,
+    /// Set on close; `None` for no votes or a tie
+    pub winner: Option<u8>,
+    pub bump: u8,
+}
+
+impl PollAccount {
+    /// The choice with the most weight, or `None` if nobody voted or the top two tie
+    pub fn leader(&self) -> Option<u8> {
+        let max = *self.tallies.iter().max()?;
+        if max == 0 {
+            return None;
+        }
+        let mut leaders = self.tallies.iter().enumerate().filter(|(_, &t)| t == max);
+        let (index, _) = leaders.next()?;
+        if leaders.next().is_some() {
+            return None;
+        }
+        Some(index as u8)
+    }
+}
+
+/// One wallet's vote; its existence at ["ballot", poll, voter] prevents double voting
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct Ballot {
+    pub poll: Pubkey,
+    pub voter: Pubkey,
+    pub choice: u8,
+    pub weight: u64,
+}
+
+/// Snapshot leaf for a holder. The 0 prefix keeps leaves distinct from inner nodes
+pub fn snapshot_leaf(voter: &Pubkey, balance: u64) -> [u8; 32] {
+    hashv(&[&[0], voter.as_ref(), &balance.to_le_bytes()]).to_bytes()
+}
+
+/// Inner node over two children, sorted so proofs need no left/right flags
+fn hash_pair(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let (left, right) = if a <= b { (a, b) } else { (b, a) };
+    hashv(&[&[1], left, right]).to_bytes()
+}
+
+/// Root over `leaves`; an odd node at the end of a level is carried up unchanged.
+/// Used off-chain to build the snapshot
+pub fn merkle_root(leaves: &[[u8; 32]]) -> [u8; 32] {
+    let mut level = leaves.to_vec();
+    while level.len() > 1 {
+        level = level
+            .chunks(2)
+            .map(|pair| if pair.len() == 2 { hash_pair(&pair[0], &pair[1]) } else { pair[0] })
+            .collect();
+    }
+    level.first().copied().unwrap_or_default()
+}
+
+/// Proof for the leaf at `index`, matching `merkle_root`. Used off-chain
+pub fn merkle_proof(leaves: &[[u8; 32]], mut index: usize) -> Vec<[u8; 32]> {
+    let mut proof = Vec::new();
+    let mut level = leaves.to_vec();
+    while level.len() > 1 {
+        let sibling = index ^ 1;
+        if sibling < level.len() {
+            proof.push(level[sibling]);
+        }
+        level = level
+            .chunks(2)
+            .map(|pair| if pair.len() == 2 { hash_pair(&pair[0], &pair[1]) } else { pair[0] })
+            .collect();
+        index /= 2;
+    }
+    proof
+}
+
+pub fn verify_proof(leaf: [u8; 32], proof: &[[u8; 32]], root: &[u8; 32]) -> bool {
+    proof.iter().fold(leaf, |node, sibling| hash_pair(&node, sibling)) == *root
+}
+
+/// Weight a voter can cast: their snapshot balance, capped by what they still
+/// hold, so tokens sold after the snapshot cannot vote
+pub fn vote_weight(snapshot_balance: u64, current_balance: u64) -> u64 {
+    snapshot_balance.min(current_balance)
+}
+
+entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = PollInstruction::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        PollInstruction::CreatePoll {
+            poll_id,
+            num_choices,
+            duration_slots,
+            snapshot_root,
+        } => create_poll(program_id, accounts, poll_id, num_choices, duration_slots, snapshot_root),
+        PollInstruction::Vote {
+            choice,
+            snapshot_balance,
+            proof,
+        } => vote(program_id, accounts, choice, snapshot_balance, proof),
+        PollInstruction::Close => close(program_id, accounts),
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub enum PollInstruction {
+    /// Create a poll with `num_choices` choices, open for `duration_slots`.
+    /// `snapshot_root` commits to holders' balances of `mint` at the current slot
+    /// Accounts: [writable, signer] creator, [writable] poll PDA, [] mint, [] system program
+    CreatePoll {
+        poll_id: u64,
+        num_choices: u8,
+        duration_slots: u64,
+        snapshot_root: [u8; 32],
+    },
+    /// Vote for `choice` with the voter's snapshot balance, proven against the root
+    /// Accounts: [writable, signer] voter, [writable] poll PDA, [writable] ballot PDA,
+    /// [] voter's token account, [] system program
+    Vote {
+        choice: u8,
+        snapshot_balance: u64,
+        proof: Vec<[u8; 32]>,
+    },
+    /// Close the poll after voting ends and announce the winner (permissionless)
+    /// Accounts: [writable] poll PDA
+    Close,
+}
+
+fn create_poll(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    poll_id: u64,
+    num_choices: u8,
+    duration_slots: u64,
+    snapshot_root: [u8; 32],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let creator = next_account_info(accounts_iter)?;
+    let poll_account = next_account_info(accounts_iter)?;
+    let mint = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !creator.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if !(2..=MAX_CHOICES as u8).contains(&num_choices) || duration_slots == 0 {
+        msg!("Need 2-{} choices and a non-zero duration", MAX_CHOICES);
+        return Err(PollError::InvalidParameters.into());
+    }
+
+    if *mint.owner != spl_token::id() {
+        msg!("Mint is not an SPL token mint");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let id_bytes = poll_id.to_le_bytes();
+    let (expected_poll, bump) =
+        Pubkey::find_program_address(&[POLL_SEED, creator.key.as_ref(), &id_bytes], program_id);
+    if expected_poll != *poll_account.key {
+        msg!("Poll account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let snapshot_slot = Clock::get()?.slot;
+    let end_slot = snapshot_slot
+        .checked_add(duration_slots)
+        .ok_or(PollError::InvalidParameters)?;
+
+    invoke_signed(
+        &system_instruction::create_account(
+            creator.key,
+            poll_account.key,
+            Rent::get()?.minimum_balance(POLL_ACCOUNT_SIZE),
+            POLL_ACCOUNT_SIZE as u64,
+            program_id,
+        ),
+        &[creator.clone(), poll_account.clone(), system_program.clone()],
+        &[&[POLL_SEED, creator.key.as_ref(), &id_bytes, &[bump]]],
+    )?;
+
+    let poll = PollAccount {
+        is_initialized: true,
+        creator: *creator.key,
+        poll_id,
+        mint: *mint.key,
+        snapshot_slot,
+        snapshot_root,
+        end_slot,
+        tallies: vec![0; num_choices as usize],
+        closed: false,
+        winner: None,
+        bump,
+    };
+    poll.serialize(&mut &mut poll_account.data.borrow_mut()[..])?;
+
+    msg!("Poll {} created at slot {}, open until slot {}", poll_id, snapshot_slot, end_slot);
+
+    Ok(())
+}
+
+fn vote(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    choice: u8,
+    snapshot_balance: u64,
+    proof: Vec<[u8; 32]>,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let voter = next_account_info(accounts_iter)?;
+    let poll_account = next_account_info(accounts_iter)?;
+    let ballot_account = next_account_info(accounts_iter)?;
+    let token_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !voter.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut poll = load_poll(program_id, poll_account)?;
+
+    if Clock::get()?.slot >= poll.end_slot {
+        msg!("Voting ended at slot {}", poll.end_slot);
+        return Err(PollError::VotingEnded.into());
+    }
+
+    if choice as usize >= poll.tallies.len() {
+        return Err(PollError::InvalidChoice.into());
+    }
+
+    let (expected_ballot, bump) = Pubkey::find_program_address(
+        &[BALLOT_SEED, poll_account.key.as_ref(), voter.key.as_ref()],
+        program_id,
+    );
+    if expected_ballot != *ballot_account.key {
+        msg!("Ballot does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    if ballot_account.lamports() > 0 {
+        msg!("{} has already voted", voter.key);
+        return Err(PollError::AlreadyVoted.into());
+    }
+
+    if proof.len() > MAX_PROOF_LENGTH
+        || !verify_proof(snapshot_leaf(voter.key, snapshot_balance), &proof, &poll.snapshot_root)
+    {
+        msg!("{} with balance {} is not in the snapshot", voter.key, snapshot_balance);
+        return Err(PollError::InvalidProof.into());
+    }
+
+    // Read the live SPL balance to cap the snapshot weight
+    if *token_account.owner != spl_token::id() {
+        return Err(PollError::WrongTokenAccount.into());
+    }
+    let token = TokenAccount::unpack(&token_account.data.borrow())?;
+    if token.owner != *voter.key || token.mint != poll.mint {
+        msg!("Token account must be the voter's account for mint {}", poll.mint);
+        return Err(PollError::WrongTokenAccount.into());
+    }
+
+    let weight = vote_weight(snapshot_balance, token.amount);
+    if weight == 0 {
+        return Err(PollError::NoWeight.into());
+    }
+
+    invoke_signed(
+        &system_instruction::create_account(
+            voter.key,
+            ballot_account.key,
+            Rent::get()?.minimum_balance(BALLOT_SIZE),
+            BALLOT_SIZE as u64,
+            program_id,
+        ),
+        &[voter.clone(), ballot_account.clone(), system_program.clone()],
+        &[&[BALLOT_SEED, poll_account.key.as_ref(), voter.key.as_ref(), &[bump]]],
+    )?;
+
+    let ballot = Ballot {
+        poll: *poll_account.key,
+        voter: *voter.key,
+        choice,
+        weight,
+    };
+    ballot.serialize(&mut &mut ballot_account.data.borrow_mut()[..])?;
+
+    let tally = &mut poll.tallies[choice as usize];
+    *tally = tally.checked_add(weight).ok_or(ProgramError::ArithmeticOverflow)?;
+    poll.serialize(&mut &mut poll_account.data.borrow_mut()[..])?;
+
+    msg!("{} voted for choice {} with weight {}", voter.key, choice, weight);
+
+    Ok(())
+}
+
+fn close(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let poll_account = next_account_info(accounts_iter)?;
+
+    let mut poll = load_poll(program_id, poll_account)?;
+
+    let slot = Clock::get()?.slot;
+    if slot < poll.end_slot {
+        msg!("Voting ends at slot {}", poll.end_slot);
+        return Err(PollError::VotingNotEnded.into());
+    }
+
+    poll.closed = true;
+    poll.winner = poll.leader();
+    poll.serialize(&mut &mut poll_account.data.borrow_mut()[..])?;
+
+    match poll.winner {
+        Some(choice) => msg!("Poll {} closed: choice {} wins with {:?}", poll.poll_id, choice, poll.tallies),
+        None => msg!("Poll {} closed without a winner: {:?}", poll.poll_id, poll.tallies),
+    }
+
+    Ok(())
+}
+
+/// Validate an initialized, open poll account
+fn load_poll(program_id: &Pubkey, poll_account: &AccountInfo) -> Result<PollAccount, ProgramError> {
+    if poll_account.owner != program_id {
+        msg!("Poll account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    // Fewer than `MAX_CHOICES` tallies make the encoding shorter than the account
+    let poll = PollAccount::deserialize(&mut &poll_account.data.borrow()[..])?;
+
+    if !poll.is_initialized {
+        return Err(PollError::NotInitialized.into());
+    }
+
+    if poll.closed {
+        return Err(PollError::AlreadyClosed.into());
+    }
+
+    Ok(poll)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_program::{clock::Epoch, entrypoint::SUCCESS, program_stubs};
+
+    /// Serves a Clock sysvar at a fixed slot so `close` can run off-chain
+    struct TestSyscallStubs {
+        slot: u64,
+    }
+
+    impl program_stubs::SyscallStubs for TestSyscallStubs {
+        fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+            unsafe {
+                *(var_addr as *mut Clock) = Clock {
+                    slot: self.slot,
+                    ..Clock::default()
+                };
+            }
+            SUCCESS
+        }
+    }
+
+    #[test]
+    fn test_snapshot_proofs() {
+        let holders: Vec<(Pubkey, u64)> = (1..=5).map(|i| (Pubkey::new_unique(), i * 100)).collect();
+        let leaves: Vec<[u8; 32]> = holders.iter().map(|(k, b)| snapshot_leaf(k, *b)).collect();
+        let root = merkle_root(&leaves);
+
+        for (i, (holder, balance)) in holders.iter().enumerate() {
+            let proof = merkle_proof(&leaves, i);
+            assert!(verify_proof(snapshot_leaf(holder, *balance), &proof, &root));
+            // Claiming a larger balance than snapshotted fails
+            assert!(!verify_proof(snapshot_leaf(holder, balance + 1), &proof, &root));
+        }
+
+        // Weight is capped by what the voter still holds
+        assert_eq!(vote_weight(500, 1_000), 500);
+        assert_eq!(vote_weight(500, 200), 200);
+    }
+
+    #[test]
+    fn test_close_announces_winner_after_end() {
+        let program_id = Pubkey::new_unique();
+        let poll_key = Pubkey::new_unique();
+
+        let poll = PollAccount {
+            is_initialized: true,
+            creator: Pubkey::new_unique(),
+            poll_id: 1,
+            mint: Pubkey::new_unique(),
+            snapshot_slot: 100,
+            snapshot_root: [0; 32],
+            end_slot: 200,
+            tallies: vec![300, 700, 0],
+            closed: false,
+            winner: None,
+            bump: 255,
+        };
+        let mut data = poll.try_to_vec().unwrap();
+        data.resize(POLL_ACCOUNT_SIZE, 0);
+        let mut lamports = 10_000;
+        let accounts = vec![AccountInfo::new(
+            &poll_key,
+            false,
+            true,
+            &mut lamports,
+            &mut data,
+            &program_id,
+            false,
+            Epoch::default(),
+        )];
+        let instruction_data = PollInstruction::Close.try_to_vec().unwrap();
+
+        program_stubs::set_syscall_stubs(Box::new(TestSyscallStubs { slot: 199 }));
+        let result = process_instruction(&program_id, &accounts, &instruction_data);
+        assert_eq!(result, Err(PollError::VotingNotEnded.into()));
+
+        program_stubs::set_syscall_stubs(Box::new(TestSyscallStubs { slot: 200 }));
+        process_instruction(&program_id, &accounts, &instruction_data).unwrap();
+
+        let closed = PollAccount::deserialize(&mut &accounts[0].data.borrow()[..]).unwrap();
+        assert!(closed.closed);
+        assert_eq!(closed.winner, Some(1));
+
+        // Closing twice fails
+        let result = process_instruction(&program_id, &accounts, &instruction_data);
+        assert_eq!(result, Err(PollError::AlreadyClosed.into()));
+
+        // A tie has no winner
+        let tied = PollAccount { tallies: vec![5, 5], ..poll };
+        assert_eq!(tied.leader(), None);
+    }
+}
@@ -0,0 +1,98 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+/**
+ * @title PredictionMarket
+ * @dev Binary YES/NO markets priced by a parimutuel pool: one share per wei
+ * staked, and after an oracle resolves the market the winning side splits the
+ * whole pot pro-rata. An invalid outcome, or a winning side with no shares,
+ * refunds every stake.
+ */
+contract PredictionMarket {
+    enum Outcome { Pending, Yes, No, Invalid }
+
+    struct Market {
+        address oracle;
+        uint256 closeTime;
+        uint256 yesPool;
+        uint256 noPool;
+        Outcome outcome;
+    }
+
+    Market[] public markets;
+    /// marketId => user => shares
+    mapping(uint256 => mapping(address => uint256)) public yesShares;
+    mapping(uint256 => mapping(address => uint256)) public noShares;
+
+    event MarketCreated(uint256 indexed marketId, address indexed oracle, uint256 closeTime);
+    event SharesBought(uint256 indexed marketId, address indexed user, bool yes, uint256 amount);
+    event Resolved(uint256 indexed marketId, Outcome outcome);
+    event Redeemed(uint256 indexed marketId, address indexed user, uint256 payout);
+
+    function createMarket(address oracle, uint256 closeTime) external returns (uint256 marketId) {
+        require(closeTime > block.timestamp, "Close time in the past");
+        marketId = markets.length;
+        markets.push(Market(oracle, closeTime, 0, 0, Outcome.Pending));
+        emit MarketCreated(marketId, oracle, closeTime);
+    }
+
+    function buy(uint256 marketId, bool yes) external payable {
+        Market storage market = markets[marketId];
+        require(block.timestamp < market.closeTime && market.outcome == Outcome.Pending, "Betting closed");
+        require(msg.value > 0, "Zero amount");
+
+        if (yes) {
+            yesShares[marketId][msg.sender] += msg.value;
+            market.yesPool += msg.value;
+        } else {
+            noShares[marketId][msg.sender] += msg.value;
+            market.noPool += msg.value;
+        }
+        emit SharesBought(marketId, msg.sender, yes, msg.value);
+    }
+
+    function resolve(uint256 marketId, Outcome outcome) external {
+        Market storage market = markets[marketId];
+        require(msg.sender == market.oracle, "Only oracle");
+        require(market.outcome == Outcome.Pending, "Already resolved");
+        require(outcome != Outcome.Pending, "Invalid outcome");
+        require(block.timestamp >= market.closeTime, "Betting open");
+
+        market.outcome = outcome;
+        emit Resolved(marketId, outcome);
+    }
+
+    function redeem(uint256 marketId) external {
+        uint256 amount = payout(marketId, msg.sender);
+        yesShares[marketId][msg.sender] = 0;
+        noShares[marketId][msg.sender] = 0;
+
+        emit Redeemed(marketId, msg.sender, amount);
+        (bool success, ) = payable(msg.sender).call{value: amount}("");
+        require(success, "Transfer failed");
+    }
+
+    function payout(uint256 marketId, address user) public view returns (uint256) {
+        Market storage market = markets[marketId];
+        require(market.outcome != Outcome.Pending, "Not resolved");
+
+        uint256 yes = yesShares[marketId][user];
+        uint256 no = noShares[marketId][user];
+        uint256 winningPool = market.outcome == Outcome.Yes ? market.yesPool
+            : market.outcome == Outcome.No ? market.noPool
+            : 0;
+
+        if (winningPool == 0) {
+            return yes + no;
+        }
+        uint256 shares = market.outcome == Outcome.Yes ? yes : no;
+        return (shares * (market.yesPool + market.noPool)) / winningPool;
+    }
+
+    /// @dev Implied probability of YES in basis points
+    function yesPriceBps(uint256 marketId) external view returns (uint256) {
+        Market storage market = markets[marketId];
+        uint256 pot = market.yesPool + market.noPool;
+        return pot == 0 ? 5000 : (market.yesPool * 10000) / pot;
+    }
+}
@@ -0,0 +1,529 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint,
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// Seed prefix for market PDAs: ["market", creator, market_id]
+pub const MARKET_SEED: &[u8] = b"market";
+
+/// Seed prefix for position PDAs: ["position", market, user]
+pub const POSITION_SEED: &[u8] = b"position";
+
+/// Size of a `MarketAccount`
+pub const MARKET_ACCOUNT_SIZE: usize = 1 // is_initialized
+    + 32 // creator
+    + 8 // market_id
+    + 32 // oracle
+    + 8 // close_time
+    + 8 // yes_pool
+    + 8 // no_pool
+    + 1 // outcome
+    + 1; // bump
+
+/// Size of a `PositionAccount`
+pub const POSITION_ACCOUNT_SIZE: usize = 1 // is_initialized
+    + 32 // market
+    + 32 // owner
+    + 8 // yes_shares
+    + 8 // no_shares
+    + 1; // bump
+
+/// Errors returned by the program as `ProgramError::Custom` codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PredictionError {
+    /// Close time is in the past
+    InvalidParameters = 0,
+    /// Betting has closed
+    BettingClosed = 1,
+    /// Betting is still open, so the market cannot be resolved
+    BettingOpen = 2,
+    /// The market has already been resolved
+    AlreadyResolved = 3,
+    /// The market has not been resolved
+    NotResolved = 4,
+    /// Only the oracle can resolve
+    Unauthorized = 5,
+    /// The account has not been initialized
+    NotInitialized = 6,
+    /// Amount is zero
+    InvalidAmount = 7,
+    /// The position belongs to another market or user
+    WrongPosition = 8,
+}
+
+impl From<PredictionError> for ProgramError {
+    fn from(e: PredictionError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Yes,
+    No,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Pending,
+    Yes,
+    No,
+    /// The question could not be answered; every stake is refunded
+    Invalid,
+}
+
+/// A YES/NO question; the PDA holds every stake as the pot
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct MarketAccount {
+    pub is_initialized: bool,
+    pub creator: Pubkey,
+    pub market_id: u64,
+    /// The only key that can resolve the market
+    pub oracle: Pubkey,
+    /// Unix time after which no more shares are sold
+    pub close_time: i64,
+    pub yes_pool: u64,
+    pub no_pool: u64,
+    pub outcome: Outcome,
+    pub bump: u8,
+}
+
+/// A user's shares in one market; one share per lamport staked
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct PositionAccount {
+    pub is_initialized: bool,
+    pub market: Pubkey,
+    pub owner: Pubkey,
+    pub yes_shares: u64,
+    pub no_shares: u64,
+    pub bump: u8,
+}
+
+impl MarketAccount {
+    pub fn pot(&self) -> u64 {
+        self.yes_pool.saturating_add(self.no_pool)
+    }
+
+    /// Implied probability of YES in basis points, from the pool split
+    pub fn yes_price_bps(&self) -> u64 {
+        match self.pot() {
+            0 => 5_000,
+            pot => (self.yes_pool as u128 * 10_000 / pot as u128) as u64,
+        }
+    }
+
+    /// Parimutuel payout: winners split the whole pot pro-rata to their shares.
+    /// An invalid outcome, or a winning side nobody bought, refunds every stake.
+    pub fn payout(&self, position: &PositionAccount) -> Result<u64, PredictionError> {
+        let (shares, winning_pool) = match self.outcome {
+            Outcome::Pending => return Err(PredictionError::NotResolved),
+            Outcome::Yes => (position.yes_shares, self.yes_pool),
+            Outcome::No => (position.no_shares, self.no_pool),
+            Outcome::Invalid => (0, 0),
+        };
+
+        if winning_pool == 0 {
+            return Ok(position.yes_shares.saturating_add(position.no_shares));
+        }
+
+        // Rounds down; the dust stays in the market account
+        Ok((shares as u128 * self.pot() as u128 / winning_pool as u128) as u64)
+    }
+}
+
+entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = PredictionInstruction::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        PredictionInstruction::CreateMarket {
+            market_id,
+            oracle,
+            close_time,
+        } => create_market(program_id, accounts, market_id, oracle, close_time),
+        PredictionInstruction::Buy { side, amount } => buy(program_id, accounts, side, amount),
+        PredictionInstruction::Resolve { outcome } => resolve(program_id, accounts, outcome),
+        PredictionInstruction::Redeem => redeem(program_id, accounts),
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub enum PredictionInstruction {
+    /// Open a market resolved by `oracle`, selling shares until `close_time`
+    /// Accounts: [writable, signer] creator, [writable] market PDA, [] system program
+    CreateMarket {
+        market_id: u64,
+        oracle: Pubkey,
+        close_time: i64,
+    },
+    /// Stake `amount` lamports on `side` for as many shares
+    /// Accounts: [writable, signer] user, [writable] market PDA, [writable] position PDA, [] system program
+    Buy { side: Side, amount: u64 },
+    /// Oracle records the outcome after betting closes
+    /// Accounts: [signer] oracle, [writable] market PDA
+    Resolve { outcome: Outcome },
+    /// Collect the payout and close the position
+    /// Accounts: [writable, signer] user, [writable] market PDA, [writable] position PDA
+    Redeem,
+}
+
+fn create_market(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    market_id: u64,
+    oracle: Pubkey,
+    close_time: i64,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let creator = next_account_info(accounts_iter)?;
+    let market_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !creator.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if close_time <= Clock::get()?.unix_timestamp {
+        msg!("Close time must be in the future");
+        return Err(PredictionError::InvalidParameters.into());
+    }
+
+    let (expected_market, bump) = Pubkey::find_program_address(
+        &[MARKET_SEED, creator.key.as_ref(), &market_id.to_le_bytes()],
+        program_id,
+    );
+    if expected_market != *market_account.key {
+        msg!("Market account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    invoke_signed(
+        &system_instruction::create_account(
+            creator.key,
+            market_account.key,
+            Rent::get()?.minimum_balance(MARKET_ACCOUNT_SIZE),
+            MARKET_ACCOUNT_SIZE as u64,
+            program_id,
+        ),
+        &[creator.clone(), market_account.clone(), system_program.clone()],
+        &[&[MARKET_SEED, creator.key.as_ref(), &market_id.to_le_bytes(), &[bump]]],
+    )?;
+
+    let market = MarketAccount {
+        is_initialized: true,
+        creator: *creator.key,
+        market_id,
+        oracle,
+        close_time,
+        yes_pool: 0,
+        no_pool: 0,
+        outcome: Outcome::Pending,
+        bump,
+    };
+    market.serialize(&mut &mut market_account.data.borrow_mut()[..])?;
+
+    msg!("Market {} created; betting closes at {}", market_id, close_time);
+
+    Ok(())
+}
+
+fn buy(program_id: &Pubkey, accounts: &[AccountInfo], side: Side, amount: u64) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let user = next_account_info(accounts_iter)?;
+    let market_account = next_account_info(accounts_iter)?;
+    let position_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !user.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if amount == 0 {
+        return Err(PredictionError::InvalidAmount.into());
+    }
+
+    let mut market = load_market(program_id, market_account)?;
+
+    if Clock::get()?.unix_timestamp >= market.close_time || market.outcome != Outcome::Pending {
+        return Err(PredictionError::BettingClosed.into());
+    }
+
+    let (expected_position, position_bump) = Pubkey::find_program_address(
+        &[POSITION_SEED, market_account.key.as_ref(), user.key.as_ref()],
+        program_id,
+    );
+    if expected_position != *position_account.key {
+        msg!("Position account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    // First purchase in this market: create the user's position
+    let mut position = if position_account.lamports() == 0 {
+        invoke_signed(
+            &system_instruction::create_account(
+                user.key,
+                position_account.key,
+                Rent::get()?.minimum_balance(POSITION_ACCOUNT_SIZE),
+                POSITION_ACCOUNT_SIZE as u64,
+                program_id,
+            ),
+            &[user.clone(), position_account.clone(), system_program.clone()],
+            &[&[POSITION_SEED, market_account.key.as_ref(), user.key.as_ref(), &[position_bump]]],
+        )?;
+        PositionAccount {
+            is_initialized: true,
+            market: *market_account.key,
+            owner: *user.key,
+            yes_shares: 0,
+            no_shares: 0,
+            bump: position_bump,
+        }
+    } else {
+        load_position(program_id, position_account, market_account.key, user.key)?
+    };
+
+    invoke(
+        &system_instruction::transfer(user.key, market_account.key, amount),
+        &[user.clone(), market_account.clone(), system_program.clone()],
+    )?;
+
+    let (shares, pool) = match side {
+        Side::Yes => (&mut position.yes_shares, &mut market.yes_pool),
+        Side::No => (&mut position.no_shares, &mut market.no_pool),
+    };
+    *shares = shares.checked_add(amount).ok_or(ProgramError::ArithmeticOverflow)?;
+    *pool = pool.checked_add(amount).ok_or(ProgramError::ArithmeticOverflow)?;
+
+    market.serialize(&mut &mut market_account.data.borrow_mut()[..])?;
+    position.serialize(&mut &mut position_account.data.borrow_mut()[..])?;
+
+    msg!(
+        "Bought {} {:?} shares; YES now at {} bps",
+        amount,
+        side,
+        market.yes_price_bps()
+    );
+
+    Ok(())
+}
+
+fn resolve(program_id: &Pubkey, accounts: &[AccountInfo], outcome: Outcome) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let oracle = next_account_info(accounts_iter)?;
+    let market_account = next_account_info(accounts_iter)?;
+
+    if !oracle.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut market = load_market(program_id, market_account)?;
+
+    if market.oracle != *oracle.key {
+        msg!("Only the oracle can resolve this market");
+        return Err(PredictionError::Unauthorized.into());
+    }
+    if market.outcome != Outcome::Pending {
+        return Err(PredictionError::AlreadyResolved.into());
+    }
+    if outcome == Outcome::Pending {
+        return Err(ProgramError::InvalidArgument);
+    }
+    if Clock::get()?.unix_timestamp < market.close_time {
+        msg!("Betting is open until {}", market.close_time);
+        return Err(PredictionError::BettingOpen.into());
+    }
+
+    market.outcome = outcome;
+    market.serialize(&mut &mut market_account.data.borrow_mut()[..])?;
+
+    msg!("Market {} resolved: {:?}", market.market_id, outcome);
+
+    Ok(())
+}
+
+fn redeem(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let user = next_account_info(accounts_iter)?;
+    let market_account = next_account_info(accounts_iter)?;
+    let position_account = next_account_info(accounts_iter)?;
+
+    if !user.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let market = load_market(program_id, market_account)?;
+    let position = load_position(program_id, position_account, market_account.key, user.key)?;
+
+    let payout = market.payout(&position)?;
+
+    **market_account.try_borrow_mut_lamports()? = market_account
+        .lamports()
+        .checked_sub(payout)
+        .ok_or(ProgramError::InsufficientFunds)?;
+
+    // Closing the position returns its rent and stops a second redemption
+    let total = position_account
+        .lamports()
+        .checked_add(payout)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    **position_account.try_borrow_mut_lamports()? = 0;
+    **user.try_borrow_mut_lamports()? = user
+        .lamports()
+        .checked_add(total)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    position_account.data.borrow_mut().fill(0);
+
+    msg!("Redeemed {} lamports", payout);
+
+    Ok(())
+}
+
+fn load_market(program_id: &Pubkey, market_account: &AccountInfo) -> Result<MarketAccount, ProgramError> {
+    if market_account.owner != program_id {
+        msg!("Market account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let market = MarketAccount::try_from_slice(&market_account.data.borrow())?;
+
+    if !market.is_initialized {
+        return Err(PredictionError::NotInitialized.into());
+    }
+
+    Ok(market)
+}
+
+fn load_position(
+    program_id: &Pubkey,
+    position_account: &AccountInfo,
+    market: &Pubkey,
+    owner: &Pubkey,
+) -> Result<PositionAccount, ProgramError> {
+    if position_account.owner != program_id {
+        msg!("Position account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let position = PositionAccount::try_from_slice(&position_account.data.borrow())?;
+
+    if !position.is_initialized {
+        return Err(PredictionError::NotInitialized.into());
+    }
+    if position.market != *market || position.owner != *owner {
+        return Err(PredictionError::WrongPosition.into());
+    }
+
+    Ok(position)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_program::clock::Epoch;
+
+    fn new_market(yes_pool: u64, no_pool: u64, outcome: Outcome) -> MarketAccount {
+        MarketAccount {
+            is_initialized: true,
+            creator: Pubkey::new_unique(),
+            market_id: 0,
+            oracle: Pubkey::new_unique(),
+            close_time: 0,
+            yes_pool,
+            no_pool,
+            outcome,
+            bump: 255,
+        }
+    }
+
+    fn new_position(market: Pubkey, owner: Pubkey, yes_shares: u64, no_shares: u64) -> PositionAccount {
+        PositionAccount {
+            is_initialized: true,
+            market,
+            owner,
+            yes_shares,
+            no_shares,
+            bump: 255,
+        }
+    }
+
+    #[test]
+    fn test_parimutuel_payout() {
+        let position = new_position(Pubkey::new_unique(), Pubkey::new_unique(), 100, 50);
+
+        let market = new_market(300, 700, Outcome::Pending);
+        assert_eq!(market.yes_price_bps(), 3_000);
+        assert_eq!(market.payout(&position), Err(PredictionError::NotResolved));
+
+        // 100 of 300 YES shares take a third of the 1000 pot
+        let market = new_market(300, 700, Outcome::Yes);
+        assert_eq!(market.payout(&position), Ok(333));
+
+        let market = new_market(300, 700, Outcome::No);
+        assert_eq!(market.payout(&position), Ok(71));
+
+        // Nobody to pay on the winning side, or an invalid question: stakes come back
+        let market = new_market(0, 700, Outcome::Yes);
+        assert_eq!(market.payout(&position), Ok(150));
+        let market = new_market(300, 700, Outcome::Invalid);
+        assert_eq!(market.payout(&position), Ok(150));
+
+        assert_eq!(market.try_to_vec().unwrap().len(), MARKET_ACCOUNT_SIZE);
+        assert_eq!(position.try_to_vec().unwrap().len(), POSITION_ACCOUNT_SIZE);
+    }
+
+    #[test]
+    fn test_redeem_closes_position() {
+        let program_id = Pubkey::new_unique();
+        let user = Pubkey::new_unique();
+        let market_key = Pubkey::new_unique();
+        let position_key = Pubkey::new_unique();
+
+        let market = new_market(400, 600, Outcome::No);
+        let position = new_position(market_key, user, 0, 300);
+
+        let keys = [user, market_key, position_key];
+        let mut lamports: Vec<u64> = vec![0, 1_000 + 10, 20];
+        let mut data: Vec<Vec<u8>> = vec![
+            vec![],
+            market.try_to_vec().unwrap(),
+            position.try_to_vec().unwrap(),
+        ];
+
+        let accounts: Vec<AccountInfo> = keys
+            .iter()
+            .zip(lamports.iter_mut())
+            .zip(data.iter_mut())
+            .enumerate()
+            .map(|(i, ((key, lamports), data))| {
+                AccountInfo::new(key, i == 0, true, lamports, data, &program_id, false, Epoch::default())
+            })
+            .collect();
+        let instruction_data = PredictionInstruction::Redeem.try_to_vec().unwrap();
+
+        process_instruction(&program_id, &accounts, &instruction_data).unwrap();
+        // 300 / 600 of the 1000 pot, plus the position's rent
+        assert_eq!(accounts[0].lamports(), 500 + 20);
+        assert_eq!(accounts[1].lamports(), 510);
+        assert_eq!(accounts[2].lamports(), 0);
+
+        // The zeroed position cannot be redeemed again
+        let result = process_instruction(&program_id, &accounts, &instruction_data);
+        assert_eq!(result, Err(PredictionError::NotInitialized.into()));
+    }
+}
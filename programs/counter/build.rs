@@ -0,0 +1,14 @@
+//! Writes the program's `declare_id!` to `OUT_DIR`. The id defaults to the
+//! localnet deployment's, the address of `localnet`'s seeded keypair for
+//! `counter`; set `COUNTER_PROGRAM_ID` when building for devnet or mainnet.
+
+use std::{env, fs, path::Path};
+
+const LOCALNET_ID: &str = "EnSv2UawNVQ5Toeto3MYiLCnP3cXWiemPzvGL62E2MBG";
+
+fn main() {
+    println!("cargo:rerun-if-env-changed=COUNTER_PROGRAM_ID");
+    let id = env::var("COUNTER_PROGRAM_ID").unwrap_or_else(|_| LOCALNET_ID.to_string());
+    let out = Path::new(&env::var("OUT_DIR").unwrap()).join("id.rs");
+    fs::write(out, format!("solana_program::declare_id!(\"{}\");\n", id)).unwrap();
+}
@@ -0,0 +1,363 @@
+#[cfg(all(target_os = "solana", not(feature = "no-entrypoint")))]
+mod entrypoint;
+#[cfg(feature = "zero-copy")]
+pub mod zero_copy;
+
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use shank::{ShankAccount, ShankInstruction};
+use common::{
+    assert_initialized, assert_owned_by, assert_rent_exempt, assert_signer, assert_uninitialized, assert_writable,
+    debug_msg, emit_event, insert_version_byte, load_versioned, migrate_account, tagged_instruction, Discriminator,
+    Event, IsInitialized, Versioned, VersionedInstruction,
+};
+
+pub use errors::CounterError;
+
+// `declare_id!` of the localnet id, or `COUNTER_PROGRAM_ID` at build time (see build.rs)
+include!(concat!(env!("OUT_DIR"), "/id.rs"));
+
+/// Define the counter account structure
+#[derive(BorshSerialize, BorshDeserialize, Debug, ShankAccount)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CounterAccount {
+    pub discriminator: [u8; 8],
+    /// `CounterAccount::VERSION` once written
+    pub version: u8,
+    pub is_initialized: bool,
+    pub count: u64,
+    pub owner: Pubkey,
+}
+
+impl CounterAccount {
+    /// Serialized size, and the size to allocate counter accounts at
+    pub const LEN: usize = 8 // discriminator
+        + 1 // version
+        + 1 // is_initialized
+        + 8 // count
+        + 32; // owner
+}
+
+impl Discriminator for CounterAccount {
+    const DISCRIMINATOR: [u8; 8] = *b"counter\0";
+}
+
+impl Versioned for CounterAccount {
+    const VERSION: u8 = 2;
+    const SPACE: usize = Self::LEN;
+
+    fn migrate_in_place(data: &mut [u8]) -> ProgramResult {
+        match data[8] {
+            // Unversioned: `is_initialized` where the version byte goes
+            1 => insert_version_byte(data, Self::VERSION),
+            version => {
+                msg!("Unknown counter layout version {}", version);
+                Err(ProgramError::InvalidAccountData)
+            }
+        }
+    }
+}
+
+impl IsInitialized for CounterAccount {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = CounterInstruction::unpack(instruction_data)?;
+
+    match instruction {
+        CounterInstruction::Initialize => initialize(program_id, accounts),
+        CounterInstruction::Increment => increment(program_id, accounts),
+        CounterInstruction::Decrement => decrement(program_id, accounts),
+        CounterInstruction::Migrate => migrate(program_id, accounts),
+    }
+}
+
+/// Event emitted when a counter is initialized
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Eq)]
+pub struct CounterInitialized {
+    pub counter: Pubkey,
+    pub owner: Pubkey,
+}
+
+impl Event for CounterInitialized {
+    const DISCRIMINATOR: [u8; 8] = *b"ctrinit\0";
+}
+
+/// Event emitted by `Increment`, like `Counter.sol`'s `Incremented`
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Eq)]
+pub struct Incremented {
+    pub counter: Pubkey,
+    pub new_count: u64,
+}
+
+impl Event for Incremented {
+    const DISCRIMINATOR: [u8; 8] = *b"incremnt";
+}
+
+/// Event emitted by `Decrement`, like `Counter.sol`'s `Decremented`
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Eq)]
+pub struct Decremented {
+    pub counter: Pubkey,
+    pub new_count: u64,
+}
+
+impl Event for Decremented {
+    const DISCRIMINATOR: [u8; 8] = *b"decremnt";
+}
+
+#[derive(Debug, ShankInstruction)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CounterInstruction {
+    /// Initialize counter
+    #[account(0, writable, name = "counter", desc = "Counter account, allocated and owned by the program")]
+    #[account(1, signer, name = "owner", desc = "Becomes the counter's owner")]
+    Initialize,
+    /// Increment counter by 1
+    #[account(0, writable, name = "counter", desc = "Counter account")]
+    #[account(1, signer, name = "owner", desc = "The counter's owner")]
+    Increment,
+    /// Decrement counter by 1
+    #[account(0, writable, name = "counter", desc = "Counter account")]
+    #[account(1, signer, name = "owner", desc = "The counter's owner")]
+    Decrement,
+    /// Grow a counter written by an older program version to the current layout (anyone can call)
+    #[account(0, writable, name = "counter", desc = "Counter account")]
+    #[account(1, writable, signer, name = "payer", desc = "Pays the rent for the larger account")]
+    #[account(2, name = "system_program", desc = "System program")]
+    Migrate,
+}
+
+tagged_instruction! {
+    CounterInstruction {
+        0 => Initialize,
+        1 => Increment,
+        2 => Decrement,
+        3 => Migrate,
+    }
+}
+
+impl VersionedInstruction for CounterInstruction {}
+
+fn initialize(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let counter_account = next_account_info(accounts_iter)?;
+    let owner = next_account_info(accounts_iter)?;
+
+    assert_owned_by(counter_account, program_id)?;
+    assert_writable(counter_account)?;
+    assert_signer(owner)?;
+
+    let mut counter_data: CounterAccount = load_versioned(&counter_account.data.borrow())?;
+
+    assert_uninitialized(&counter_data)?;
+    assert_rent_exempt(counter_account)?;
+
+    counter_data.discriminator = CounterAccount::DISCRIMINATOR;
+    counter_data.version = CounterAccount::VERSION;
+    counter_data.is_initialized = true;
+    counter_data.count = 0;
+    counter_data.owner = *owner.key;
+
+    counter_data.serialize(&mut &mut counter_account.data.borrow_mut()[..])?;
+    debug_msg!("Counter initialized by {}", owner.key);
+    emit_event!(CounterInitialized { counter: *counter_account.key, owner: *owner.key })?;
+
+    Ok(())
+}
+
+fn increment(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let counter_account = next_account_info(accounts_iter)?;
+    let signer = next_account_info(accounts_iter)?;
+
+    assert_owned_by(counter_account, program_id)?;
+    assert_writable(counter_account)?;
+    assert_signer(signer)?;
+
+    let mut counter_data: CounterAccount = load_versioned(&counter_account.data.borrow())?;
+
+    assert_initialized(&counter_data)?;
+
+    if counter_data.owner != *signer.key {
+        msg!("Only owner can increment");
+        return Err(CounterError::NotOwner.into());
+    }
+
+    counter_data.count = counter_data
+        .count
+        .checked_add(1)
+        .ok_or(CounterError::Overflow)?;
+
+    counter_data.serialize(&mut &mut counter_account.data.borrow_mut()[..])?;
+    debug_msg!("Counter incremented to {}", counter_data.count);
+    emit_event!(Incremented { counter: *counter_account.key, new_count: counter_data.count })?;
+
+    Ok(())
+}
+
+fn decrement(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let counter_account = next_account_info(accounts_iter)?;
+    let signer = next_account_info(accounts_iter)?;
+
+    assert_owned_by(counter_account, program_id)?;
+    assert_writable(counter_account)?;
+    assert_signer(signer)?;
+
+    let mut counter_data: CounterAccount = load_versioned(&counter_account.data.borrow())?;
+
+    assert_initialized(&counter_data)?;
+
+    if counter_data.owner != *signer.key {
+        msg!("Only owner can decrement");
+        return Err(CounterError::NotOwner.into());
+    }
+
+    counter_data.count = counter_data
+        .count
+        .checked_sub(1)
+        .ok_or(CounterError::Underflow)?;
+
+    counter_data.serialize(&mut &mut counter_account.data.borrow_mut()[..])?;
+    debug_msg!("Counter decremented to {}", counter_data.count);
+    emit_event!(Decremented { counter: *counter_account.key, new_count: counter_data.count })?;
+
+    Ok(())
+}
+
+fn migrate(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let counter_account = next_account_info(accounts_iter)?;
+    let payer = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    assert_owned_by(counter_account, program_id)?;
+    assert_writable(counter_account)?;
+    assert_signer(payer)?;
+
+    migrate_account::<CounterAccount>(counter_account, payer, system_program)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_program::{entrypoint::SUCCESS, program_stubs, rent::Rent};
+    use test_utils::account::TestAccount;
+
+    /// Serves the default Rent sysvar so `Initialize` can check rent exemption off-chain
+    struct TestSyscallStubs;
+
+    impl program_stubs::SyscallStubs for TestSyscallStubs {
+        fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+            unsafe { *(var_addr as *mut Rent) = Rent::default() };
+            SUCCESS
+        }
+    }
+
+    #[test]
+    fn test_initialize() {
+        program_stubs::set_syscall_stubs(Box::new(TestSyscallStubs));
+        let program_id = Pubkey::default();
+        let mut counter = TestAccount::new(Pubkey::default())
+            .writable()
+            .data_len(CounterAccount::LEN)
+            .lamports(Rent::default().minimum_balance(CounterAccount::LEN) - 1)
+            .owner(program_id);
+        let mut owner = TestAccount::new(Pubkey::new_unique()).signer().owner(program_id);
+
+        let accounts = vec![counter.info(), owner.info()];
+        let instruction_data = CounterInstruction::Initialize.pack();
+
+        let result = process_instruction(&program_id, &accounts, &instruction_data);
+        assert_eq!(result, Err(ProgramError::AccountNotRentExempt));
+
+        **accounts[0].lamports.borrow_mut() += 1;
+        assert!(process_instruction(&program_id, &accounts, &instruction_data).is_ok());
+    }
+
+    #[test]
+    fn test_increment_overflow_protection() {
+        let program_id = Pubkey::default();
+        let owner_key = Pubkey::new_unique();
+
+        let counter_data = CounterAccount {
+            discriminator: CounterAccount::DISCRIMINATOR,
+            version: CounterAccount::VERSION,
+            is_initialized: true,
+            count: u64::MAX,
+            owner: owner_key,
+        };
+        let mut counter = TestAccount::new(Pubkey::default())
+            .writable()
+            .data(counter_data.try_to_vec().unwrap())
+            .data_len(CounterAccount::LEN)
+            .owner(program_id);
+        let mut owner = TestAccount::new(owner_key).signer().owner(program_id);
+
+        let accounts = vec![counter.info(), owner.info()];
+        let instruction_data = CounterInstruction::Increment.pack();
+
+        assert_eq!(
+            process_instruction(&program_id, &accounts, &instruction_data),
+            Err(CounterError::Overflow.into())
+        );
+    }
+
+    #[test]
+    fn test_rejects_other_account_types() {
+        let program_id = Pubkey::default();
+        let owner_key = Pubkey::new_unique();
+
+        // Same layout as a counter, tagged as something else
+        let counter_data = CounterAccount {
+            discriminator: *b"campaign",
+            version: CounterAccount::VERSION,
+            is_initialized: true,
+            count: 1,
+            owner: owner_key,
+        };
+        let mut counter = TestAccount::new(Pubkey::new_unique())
+            .writable()
+            .data(counter_data.try_to_vec().unwrap())
+            .owner(program_id);
+        let mut owner = TestAccount::new(owner_key).signer().owner(program_id);
+
+        let accounts = vec![counter.info(), owner.info()];
+        for instruction in [CounterInstruction::Initialize, CounterInstruction::Increment] {
+            let instruction_data = instruction.pack();
+            assert_eq!(
+                process_instruction(&program_id, &accounts, &instruction_data),
+                Err(ProgramError::InvalidAccountData)
+            );
+        }
+    }
+
+    #[test]
+    fn test_rejects_unknown_instruction_format() {
+        let program_id = Pubkey::default();
+        let mut counter = TestAccount::new(Pubkey::new_unique()).writable().data_len(CounterAccount::LEN);
+        let mut owner = TestAccount::new(Pubkey::new_unique()).signer();
+        let accounts = vec![counter.info(), owner.info()];
+
+        // A bare Borsh tag, as sent before the format byte, and a future format
+        for instruction_data in [vec![0], vec![CounterInstruction::FORMAT + 1, 1]] {
+            assert_eq!(
+                process_instruction(&program_id, &accounts, &instruction_data),
+                Err(ProgramError::InvalidInstructionData)
+            );
+        }
+    }
+}
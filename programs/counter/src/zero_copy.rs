@@ -0,0 +1,125 @@
+//! `Pod` layout of `CounterAccount`, borrowed in place with `bytemuck`
+//! instead of deserialized with borsh. Built with the `zero-copy` feature to
+//! compare the two; the program itself stores the borsh layout.
+
+use bytemuck::{Pod, Zeroable};
+use common::{convert_discriminator, load_pod, load_pod_mut, Discriminator, IsInitialized};
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
+use std::mem::size_of;
+
+use crate::CounterAccount;
+
+/// `CounterAccount` with its fields ordered so none needs padding before it
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Pod, Zeroable)]
+pub struct CounterAccountPod {
+    pub discriminator: [u8; 8],
+    pub count: u64,
+    pub owner: Pubkey,
+    /// `CounterAccount::VERSION` once written
+    pub version: u8,
+    /// 1 once initialized, 0 before
+    pub is_initialized: u8,
+    /// Explicit padding so the layout has no implicit gaps
+    pub _padding: [u8; 6],
+}
+
+impl CounterAccountPod {
+    /// Size of the account data in bytes
+    pub const LEN: usize = size_of::<Self>();
+
+    /// Borrow account data as a counter without copying
+    pub fn load(data: &[u8]) -> Result<&Self, ProgramError> {
+        load_pod(data)
+    }
+
+    /// Mutably borrow account data as a counter without copying
+    pub fn load_mut(data: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        load_pod_mut(data)
+    }
+}
+
+impl Discriminator for CounterAccountPod {
+    const DISCRIMINATOR: [u8; 8] = *b"zccountr";
+}
+
+impl IsInitialized for CounterAccountPod {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized != 0
+    }
+}
+
+impl From<&CounterAccount> for CounterAccountPod {
+    fn from(state: &CounterAccount) -> Self {
+        Self {
+            discriminator: convert_discriminator::<Self>(state.discriminator),
+            count: state.count,
+            owner: state.owner,
+            version: state.version,
+            is_initialized: state.is_initialized as u8,
+            _padding: [0; 6],
+        }
+    }
+}
+
+impl From<&CounterAccountPod> for CounterAccount {
+    fn from(state: &CounterAccountPod) -> Self {
+        Self {
+            discriminator: convert_discriminator::<Self>(state.discriminator),
+            version: state.version,
+            is_initialized: state.is_initialized(),
+            count: state.count,
+            owner: state.owner,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use borsh::BorshSerialize;
+    use common::Versioned;
+
+    #[test]
+    fn test_pod_round_trip() {
+        let state = CounterAccount {
+            discriminator: CounterAccount::DISCRIMINATOR,
+            version: CounterAccount::VERSION,
+            is_initialized: true,
+            count: 41,
+            owner: Pubkey::new_unique(),
+        };
+
+        // Backed by u64s so the data is 8-byte aligned like on-chain data
+        let mut backing = [0u64; CounterAccountPod::LEN / 8];
+        let data: &mut [u8] = bytemuck::cast_slice_mut(&mut backing);
+        data.copy_from_slice(bytemuck::bytes_of(&CounterAccountPod::from(&state)));
+
+        CounterAccountPod::load_mut(data).unwrap().count += 1;
+        let pod = CounterAccountPod::load(data).unwrap();
+        assert_eq!(pod.discriminator, CounterAccountPod::DISCRIMINATOR);
+
+        let back = CounterAccount::from(pod);
+        assert_eq!(back.try_to_vec().unwrap(), CounterAccount { count: 42, ..state }.try_to_vec().unwrap());
+    }
+
+    #[test]
+    fn test_pod_rejects_borsh_layout() {
+        let state = CounterAccount {
+            discriminator: CounterAccount::DISCRIMINATOR,
+            version: CounterAccount::VERSION,
+            is_initialized: true,
+            count: 1,
+            owner: Pubkey::new_unique(),
+        };
+        let mut backing = [0u64; CounterAccountPod::LEN / 8];
+        let data: &mut [u8] = bytemuck::cast_slice_mut(&mut backing);
+        state.serialize(&mut &mut data[..]).unwrap();
+
+        assert_eq!(CounterAccountPod::load(data).err(), Some(ProgramError::InvalidAccountData));
+
+        // Never written
+        data.fill(0);
+        assert!(!CounterAccountPod::load(data).unwrap().is_initialized());
+    }
+}
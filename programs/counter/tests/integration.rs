@@ -0,0 +1,197 @@
+//! Counter against the real runtime through `BanksClient`. The program runs
+//! natively via `processor!`, so no SBF build is needed.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use common::{Discriminator, Versioned, VersionedInstruction};
+use counter::{process_instruction, CounterAccount, CounterError, CounterInstruction};
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    pubkey::Pubkey,
+    rent::Rent,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::{Transaction, TransactionError},
+};
+use test_utils::fixtures::{add_fixture, AccountFixture};
+
+/// Start a test validator holding a program-owned counter account at
+/// `counter`, with `state` written into it when given
+async fn setup(program_id: Pubkey, counter: Pubkey, state: Option<CounterAccount>) -> ProgramTestContext {
+    let mut program_test = ProgramTest::new("counter", program_id, processor!(process_instruction));
+    let data = state.map_or(vec![0; CounterAccount::LEN], |state| state.try_to_vec().unwrap());
+    program_test.add_account(
+        counter,
+        Account {
+            lamports: 1_000_000_000,
+            data,
+            owner: program_id,
+            ..Account::default()
+        },
+    );
+    program_test.start_with_context().await
+}
+
+/// Send `data` against `counter` signed by `owner`, with a fresh blockhash so
+/// repeated instructions aren't rejected as duplicates
+async fn send(
+    context: &mut ProgramTestContext,
+    program_id: Pubkey,
+    counter: Pubkey,
+    owner: &Keypair,
+    data: CounterInstruction,
+) -> Result<(), BanksClientError> {
+    let instruction = Instruction::new_with_bytes(
+        program_id,
+        &data.pack(),
+        vec![AccountMeta::new(counter, false), AccountMeta::new_readonly(owner.pubkey(), true)],
+    );
+    let blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&context.payer.pubkey()),
+        &[&context.payer, owner],
+        blockhash,
+    );
+    context.banks_client.process_transaction(transaction).await
+}
+
+async fn load(context: &mut ProgramTestContext, counter: Pubkey) -> CounterAccount {
+    let account = context.banks_client.get_account(counter).await.unwrap().unwrap();
+    CounterAccount::try_from_slice(&account.data).unwrap()
+}
+
+fn program_error(result: Result<(), BanksClientError>) -> InstructionError {
+    match result.unwrap_err().unwrap() {
+        TransactionError::InstructionError(0, error) => error,
+        other => panic!("unexpected transaction error {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_counter_lifecycle() {
+    let program_id = counter::ID;
+    let counter = Pubkey::new_unique();
+    let owner = Keypair::new();
+    let mut context = setup(program_id, counter, None).await;
+
+    send(&mut context, program_id, counter, &owner, CounterInstruction::Initialize).await.unwrap();
+    send(&mut context, program_id, counter, &owner, CounterInstruction::Increment).await.unwrap();
+    send(&mut context, program_id, counter, &owner, CounterInstruction::Increment).await.unwrap();
+    send(&mut context, program_id, counter, &owner, CounterInstruction::Decrement).await.unwrap();
+
+    let state = load(&mut context, counter).await;
+    assert!(state.is_initialized);
+    assert_eq!((state.count, state.owner), (1, owner.pubkey()));
+
+    // A second Initialize would hand the counter to a new owner
+    let result = send(&mut context, program_id, counter, &Keypair::new(), CounterInstruction::Initialize).await;
+    assert_eq!(program_error(result), InstructionError::AccountAlreadyInitialized);
+}
+
+#[tokio::test]
+async fn test_counter_rejections() {
+    let program_id = counter::ID;
+    let counter = Pubkey::new_unique();
+    let owner = Keypair::new();
+    let state = CounterAccount {
+        discriminator: CounterAccount::DISCRIMINATOR,
+        version: CounterAccount::VERSION,
+        is_initialized: true,
+        count: u64::MAX,
+        owner: owner.pubkey(),
+    };
+    let mut context = setup(program_id, counter, Some(state)).await;
+
+    // Wrong signer
+    let result = send(&mut context, program_id, counter, &Keypair::new(), CounterInstruction::Decrement).await;
+    assert_eq!(program_error(result), InstructionError::Custom(CounterError::NotOwner as u32));
+
+    // Overflow leaves the count untouched
+    let result = send(&mut context, program_id, counter, &owner, CounterInstruction::Increment).await;
+    assert_eq!(program_error(result), InstructionError::Custom(CounterError::Overflow as u32));
+    assert_eq!(load(&mut context, counter).await.count, u64::MAX);
+}
+
+#[tokio::test]
+async fn test_migrate_unversioned_counter() {
+    let program_id = counter::ID;
+    let counter = Pubkey::new_unique();
+    let owner = Keypair::new();
+
+    // Written before the version byte: discriminator, is_initialized, count, owner
+    let mut legacy = CounterAccount::DISCRIMINATOR.to_vec();
+    legacy.push(1);
+    legacy.extend(7u64.to_le_bytes());
+    legacy.extend(owner.pubkey().to_bytes());
+    let mut program_test = ProgramTest::new("counter", program_id, processor!(process_instruction));
+    program_test.add_account(
+        counter,
+        Account {
+            lamports: Rent::default().minimum_balance(legacy.len()),
+            data: legacy,
+            owner: program_id,
+            ..Account::default()
+        },
+    );
+    let mut context = program_test.start_with_context().await;
+
+    let result = send(&mut context, program_id, counter, &owner, CounterInstruction::Increment).await;
+    assert_eq!(program_error(result), InstructionError::InvalidAccountData);
+
+    // Anyone can migrate; the payer covers the extra byte of rent
+    let migrate = Instruction::new_with_bytes(
+        program_id,
+        &CounterInstruction::Migrate.pack(),
+        vec![
+            AccountMeta::new(counter, false),
+            AccountMeta::new(context.payer.pubkey(), true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    );
+    let blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let transaction =
+        Transaction::new_signed_with_payer(&[migrate], Some(&context.payer.pubkey()), &[&context.payer], blockhash);
+    context.banks_client.process_transaction(transaction).await.unwrap();
+
+    let account = context.banks_client.get_account(counter).await.unwrap().unwrap();
+    assert_eq!(account.data.len(), CounterAccount::LEN);
+    assert_eq!(account.lamports, Rent::default().minimum_balance(CounterAccount::LEN));
+
+    send(&mut context, program_id, counter, &owner, CounterInstruction::Increment).await.unwrap();
+    let state = load(&mut context, counter).await;
+    assert_eq!((state.version, state.count, state.owner), (CounterAccount::VERSION, 8, owner.pubkey()));
+}
+
+#[tokio::test]
+async fn test_replay_unversioned_fixture() {
+    // An unversioned counter at 41, saved with `solana account --output json`
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/unversioned-counter.json");
+    let fixture = AccountFixture::load(path).unwrap();
+    let program_id = fixture.to_account().unwrap().owner;
+    let mut program_test = ProgramTest::new("counter", program_id, processor!(process_instruction));
+    let counter = add_fixture(&mut program_test, path).unwrap();
+    let mut context = program_test.start_with_context().await;
+
+    let migrate = Instruction::new_with_bytes(
+        program_id,
+        &CounterInstruction::Migrate.pack(),
+        vec![
+            AccountMeta::new(counter, false),
+            AccountMeta::new(context.payer.pubkey(), true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    );
+    let blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let transaction =
+        Transaction::new_signed_with_payer(&[migrate], Some(&context.payer.pubkey()), &[&context.payer], blockhash);
+    context.banks_client.process_transaction(transaction).await.unwrap();
+
+    let state = load(&mut context, counter).await;
+    assert_eq!((state.version, state.is_initialized, state.count), (CounterAccount::VERSION, true, 41));
+
+    // Still guarded by the owner from before the migration
+    let result = send(&mut context, program_id, counter, &Keypair::new(), CounterInstruction::Increment).await;
+    assert_eq!(program_error(result), InstructionError::Custom(CounterError::NotOwner as u32));
+}
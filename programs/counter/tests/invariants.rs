@@ -0,0 +1,83 @@
+//! Property tests: random sequences of instructions from the owner and from a
+//! stranger against a counter that may start near either bound. The count
+//! must follow a checked model exactly, and a rejected instruction must leave
+//! the account untouched, so the count never wraps past 0 or `u64::MAX`.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use common::{Discriminator, Versioned, VersionedInstruction};
+use counter::{process_instruction, CounterAccount, CounterInstruction};
+use proptest::prelude::*;
+use solana_program::pubkey::Pubkey;
+use test_utils::account::TestAccount;
+
+#[derive(Debug, Clone)]
+enum Op {
+    Initialize { by_owner: bool },
+    Increment { by_owner: bool },
+    Decrement { by_owner: bool },
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    // Mostly the owner, so sequences actually move the count
+    let by_owner = prop::bool::weighted(0.8);
+    prop_oneof![
+        1 => by_owner.prop_map(|by_owner| Op::Initialize { by_owner }),
+        4 => by_owner.prop_map(|by_owner| Op::Increment { by_owner }),
+        4 => by_owner.prop_map(|by_owner| Op::Decrement { by_owner }),
+    ]
+}
+
+fn starting_count() -> impl Strategy<Value = u64> {
+    prop_oneof![Just(0), Just(u64::MAX), 0..8u64, u64::MAX - 8..=u64::MAX, any::<u64>()]
+}
+
+proptest! {
+    #[test]
+    fn test_count_follows_checked_model(start in starting_count(), ops in prop::collection::vec(op(), 1..64)) {
+        let program_id = Pubkey::new_unique();
+        let owner_key = Pubkey::new_unique();
+
+        let state = CounterAccount {
+            discriminator: CounterAccount::DISCRIMINATOR,
+            version: CounterAccount::VERSION,
+            is_initialized: true,
+            count: start,
+            owner: owner_key,
+        };
+        let mut counter =
+            TestAccount::new(Pubkey::new_unique()).writable().data(state.try_to_vec().unwrap()).owner(program_id);
+        let mut owner = TestAccount::new(owner_key).signer().owner(program_id);
+        let mut stranger = TestAccount::new(Pubkey::new_unique()).signer().owner(program_id);
+        let (counter, owner, stranger) = (counter.info(), owner.info(), stranger.info());
+
+        let mut model = start;
+        for op in ops {
+            let (instruction, by_owner, expected) = match op {
+                // Already initialized, so never accepted
+                Op::Initialize { by_owner } => (CounterInstruction::Initialize, by_owner, None),
+                Op::Increment { by_owner } => (CounterInstruction::Increment, by_owner, model.checked_add(1)),
+                Op::Decrement { by_owner } => (CounterInstruction::Decrement, by_owner, model.checked_sub(1)),
+            };
+            let expected = expected.filter(|_| by_owner);
+            let signer = if by_owner { owner.clone() } else { stranger.clone() };
+
+            let before = counter.data.borrow().to_vec();
+            let data = instruction.pack();
+            let result = process_instruction(&program_id, &[counter.clone(), signer], &data);
+
+            match expected {
+                Some(count) => {
+                    prop_assert!(result.is_ok(), "{:?} from {} failed: {:?}", instruction, model, result);
+                    model = count;
+                }
+                None => {
+                    prop_assert!(result.is_err(), "{:?} from {} succeeded", instruction, model);
+                    prop_assert_eq!(&before, &*counter.data.borrow());
+                }
+            }
+
+            let state = CounterAccount::try_from_slice(&counter.data.borrow()).unwrap();
+            prop_assert_eq!((state.count, state.owner), (model, owner_key));
+        }
+    }
+}
@@ -0,0 +1,63 @@
+//! Golden bytes of every borsh type the program stores, logs or accepts,
+//! against tests/fixtures/layout.txt. A failure here means deployed counters
+//! or existing clients would no longer read the new layout.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use common::{event_data, Discriminator, Versioned};
+use counter::{CounterAccount, CounterInitialized, CounterInstruction, Decremented, Incremented};
+use solana_program::pubkey::Pubkey;
+use test_utils::golden::{read_fixture, GoldenBytes};
+
+const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/layout.txt");
+
+fn bytes(value: &impl BorshSerialize) -> Vec<u8> {
+    value.try_to_vec().unwrap()
+}
+
+#[test]
+fn test_layouts_match_fixture() {
+    let (counter, owner) = (Pubkey::new_from_array([1; 32]), Pubkey::new_from_array([2; 32]));
+    let mut golden = GoldenBytes::new();
+
+    golden.add(
+        "CounterAccount",
+        bytes(&CounterAccount {
+            discriminator: CounterAccount::DISCRIMINATOR,
+            version: CounterAccount::VERSION,
+            is_initialized: true,
+            count: 0x0102_0304_0506_0708,
+            owner,
+        }),
+    );
+
+    // Events as logged, behind their discriminators
+    golden
+        .add("CounterInitialized", event_data(&CounterInitialized { counter, owner }).unwrap())
+        .add("Incremented", event_data(&Incremented { counter, new_count: 7 }).unwrap())
+        .add("Decremented", event_data(&Decremented { counter, new_count: 6 }).unwrap());
+
+    golden
+        .add("CounterInstruction::Initialize", bytes(&CounterInstruction::Initialize))
+        .add("CounterInstruction::Increment", bytes(&CounterInstruction::Increment))
+        .add("CounterInstruction::Decrement", bytes(&CounterInstruction::Decrement))
+        .add("CounterInstruction::Migrate", bytes(&CounterInstruction::Migrate));
+
+    golden.check(FIXTURE);
+}
+
+/// Instruction data in the fixture, as existing clients send it, still
+/// decodes to the variant of that name, and the next tag is unassigned
+#[test]
+fn test_fixture_instructions_decode() {
+    for (name, data) in read_fixture(FIXTURE) {
+        let Some(variant) = name.strip_prefix("CounterInstruction::") else {
+            continue;
+        };
+        let instruction = CounterInstruction::try_from_slice(&data).unwrap();
+        assert_eq!(format!("{:?}", instruction).split(' ').next(), Some(variant));
+        assert_eq!(instruction.tag(), data[0]);
+        assert_eq!(bytes(&instruction), data);
+    }
+
+    assert!(CounterInstruction::try_from_slice(&[4]).is_err());
+}
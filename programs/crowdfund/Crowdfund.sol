@@ -1,6 +1,10 @@
 // SPDX-License-Identifier: MIT
 pragma solidity ^0.8.0;
 
+interface IAllowlist {
+    function isMember(uint256 listId, address account) external view returns (bool);
+}
+
 /**
  * @title Crowdfund
  * @dev Time-based crowdfunding with automatic refunds on failure
@@ -14,10 +18,15 @@ contract Crowdfund {
 
     mapping(address => uint256) public contributions;
 
+    // Allowlist mode: when set, only members of this list may contribute
+    IAllowlist public allowlist;
+    uint256 public allowlistId;
+
     event Contributed(address indexed contributor, uint256 amount);
     event GoalReached(uint256 totalRaised);
     event Refunded(address indexed contributor, uint256 amount);
     event Withdrawn(address indexed owner, uint256 amount);
+    event AllowlistSet(address indexed allowlist, uint256 listId);
 
     constructor(uint256 _goal, uint256 _durationBlocks) {
         require(_goal > 0, "Goal must be greater than zero");
@@ -35,6 +44,10 @@ contract Crowdfund {
         require(block.number < deadline, "Campaign has ended");
         require(!finalized, "Campaign already finalized");
         require(msg.value > 0, "Must contribute a positive amount");
+        require(
+            address(allowlist) == address(0) || allowlist.isMember(allowlistId, msg.sender),
+            "Not on allowlist"
+        );
 
         contributions[msg.sender] += msg.value;
         totalRaised += msg.value;
@@ -46,6 +59,19 @@ contract Crowdfund {
         }
     }
 
+    /**
+     * @dev Turn allowlist mode on, or off with address(0) (owner only, while active)
+     */
+    function setAllowlist(address _allowlist, uint256 _listId) external {
+        require(msg.sender == owner, "Only owner can set the allowlist");
+        require(block.number < deadline && !finalized, "Campaign has ended");
+
+        allowlist = IAllowlist(_allowlist);
+        allowlistId = _listId;
+
+        emit AllowlistSet(_allowlist, _listId);
+    }
+
     /**
      * @dev Check if campaign was successful
      */
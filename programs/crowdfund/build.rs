@@ -0,0 +1,32 @@
+//! Writes the program's `declare_id!` to `OUT_DIR`. The id defaults to the
+//! localnet deployment's, the address of `localnet`'s seeded keypair for
+//! `crowdfund`; set `CROWDFUND_PROGRAM_ID` when building for devnet or mainnet.
+//!
+//! Also writes `ALLOWLIST_PROGRAM_ID`, the `allowlist` deployment that
+//! allowlist mode trusts; set `ALLOWLIST_PROGRAM_ID` to point at your own.
+
+use std::{env, fs, path::Path};
+
+const LOCALNET_ID: &str = "9yzFvrvMZwhZNyb7t54AKm5SoY9kzkRV7zBmLYwcHeMD";
+
+const DEFAULT_ALLOWLIST_ID: &str = "CsL1Svk4ux5zMtEtvhFpASJ4sTYWhWob7StuRqceSdNi";
+
+fn main() {
+    println!("cargo:rerun-if-env-changed=CROWDFUND_PROGRAM_ID");
+    println!("cargo:rerun-if-env-changed=ALLOWLIST_PROGRAM_ID");
+    let out_dir = env::var("OUT_DIR").unwrap();
+
+    let id = env::var("CROWDFUND_PROGRAM_ID").unwrap_or_else(|_| LOCALNET_ID.to_string());
+    fs::write(Path::new(&out_dir).join("id.rs"), format!("solana_program::declare_id!(\"{}\");\n", id)).unwrap();
+
+    let allowlist_id = env::var("ALLOWLIST_PROGRAM_ID").unwrap_or_else(|_| DEFAULT_ALLOWLIST_ID.to_string());
+    fs::write(
+        Path::new(&out_dir).join("allowlist_id.rs"),
+        format!(
+            "/// Program id of the deployed `allowlist` program\n\
+             pub const ALLOWLIST_PROGRAM_ID: Pubkey = solana_program::pubkey!(\"{}\");\n",
+            allowlist_id
+        ),
+    )
+    .unwrap();
+}
@@ -0,0 +1,942 @@
+#[cfg(all(target_os = "solana", not(feature = "no-entrypoint")))]
+mod entrypoint;
+#[cfg(feature = "zero-copy")]
+pub mod zero_copy;
+
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_instruction,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use shank::{ShankAccount, ShankInstruction};
+use common::{
+    assert_initialized, assert_owned_by, assert_pda, assert_signer, assert_uninitialized, assert_writable,
+    assert_rent_exempt, create_pda_account, debug_msg, emit_event, insert_version_byte, load_account, load_versioned,
+    migrate_account, tagged_instruction, transfer_lamports, ClockSource, Discriminator, Event, IsInitialized,
+    SysvarClock, Versioned, VersionedInstruction,
+};
+
+pub use errors::CrowdfundError;
+
+// `declare_id!` of the localnet id, or `CROWDFUND_PROGRAM_ID` at build time (see build.rs)
+include!(concat!(env!("OUT_DIR"), "/id.rs"));
+
+/// Seed prefix for contributor record PDAs: ["contributor", campaign, contributor]
+pub const CONTRIBUTOR_SEED: &[u8] = b"contributor";
+
+// `ALLOWLIST_PROGRAM_ID`: the default deployment, or `ALLOWLIST_PROGRAM_ID` at build time (see build.rs)
+include!(concat!(env!("OUT_DIR"), "/allowlist_id.rs"));
+
+/// Must match `MEMBER_SEED` in `allowlist/allowlist.rs`
+pub const ALLOWLIST_MEMBER_SEED: &[u8] = b"member";
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, ShankAccount)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CrowdfundAccount {
+    pub discriminator: [u8; 8],
+    /// `CrowdfundAccount::VERSION` once written
+    pub version: u8,
+    pub is_initialized: bool,
+    pub owner: Pubkey,
+    pub goal: u64,
+    pub deadline: u64, // slot number
+    pub total_raised: u64,
+    pub finalized: bool,
+    /// Allowlist mode: only members of this `allowlist` list may contribute
+    pub allowlist: Option<Pubkey>,
+}
+
+impl CrowdfundAccount {
+    /// Allocate campaign accounts at this size so allowlist mode can be switched on later
+    pub const LEN: usize = 8 // discriminator
+        + 1 // version
+        + 1 // is_initialized
+        + 32 // owner
+        + 8 // goal
+        + 8 // deadline
+        + 8 // total_raised
+        + 1 // finalized
+        + 33; // allowlist
+}
+
+impl Discriminator for CrowdfundAccount {
+    const DISCRIMINATOR: [u8; 8] = *b"campaign";
+}
+
+impl Versioned for CrowdfundAccount {
+    const VERSION: u8 = 2;
+    const SPACE: usize = Self::LEN;
+
+    fn migrate_in_place(data: &mut [u8]) -> ProgramResult {
+        match data[8] {
+            // Unversioned: `is_initialized` where the version byte goes
+            1 => insert_version_byte(data, Self::VERSION),
+            version => {
+                msg!("Unknown campaign layout version {}", version);
+                Err(ProgramError::InvalidAccountData)
+            }
+        }
+    }
+}
+
+impl IsInitialized for CrowdfundAccount {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, ShankAccount)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ContributorAccount {
+    pub discriminator: [u8; 8],
+    pub amount: u64,
+}
+
+impl ContributorAccount {
+    /// Serialized size of a contributor record
+    pub const LEN: usize = 8 // discriminator
+        + 8; // amount
+}
+
+impl Discriminator for ContributorAccount {
+    const DISCRIMINATOR: [u8; 8] = *b"contrib\0";
+}
+
+/// Event emitted when a campaign is initialized
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Eq)]
+pub struct CampaignInitialized {
+    pub campaign: Pubkey,
+    pub owner: Pubkey,
+    pub goal: u64,
+    pub deadline: u64,
+}
+
+impl Event for CampaignInitialized {
+    const DISCRIMINATOR: [u8; 8] = *b"campinit";
+}
+
+/// Event emitted for every contribution, like `Crowdfund.sol`'s `Contributed`
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Eq)]
+pub struct Contributed {
+    pub campaign: Pubkey,
+    pub contributor: Pubkey,
+    pub amount: u64,
+}
+
+impl Event for Contributed {
+    const DISCRIMINATOR: [u8; 8] = *b"contribd";
+}
+
+/// Event emitted by every contribution that leaves the total at or above the goal
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Eq)]
+pub struct GoalReached {
+    pub campaign: Pubkey,
+    pub total_raised: u64,
+}
+
+impl Event for GoalReached {
+    const DISCRIMINATOR: [u8; 8] = *b"goalrchd";
+}
+
+/// Event emitted by `Refund`, and by `BatchRefund` once per contributor paid
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Eq)]
+pub struct Refunded {
+    pub campaign: Pubkey,
+    pub contributor: Pubkey,
+    pub amount: u64,
+}
+
+impl Event for Refunded {
+    const DISCRIMINATOR: [u8; 8] = *b"refunded";
+}
+
+/// Event emitted when the owner withdraws a successful campaign's funds
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Eq)]
+pub struct Withdrawn {
+    pub campaign: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+}
+
+impl Event for Withdrawn {
+    const DISCRIMINATOR: [u8; 8] = *b"withdrwn";
+}
+
+/// Event emitted when allowlist mode is switched on (`Some`) or off (`None`)
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Eq)]
+pub struct AllowlistSet {
+    pub campaign: Pubkey,
+    pub allowlist: Option<Pubkey>,
+}
+
+impl Event for AllowlistSet {
+    const DISCRIMINATOR: [u8; 8] = *b"allowset";
+}
+
+/// Canonical address of a contributor's record. Every handler that touches a
+/// record checks it is at this address, which ties it to its contributor.
+pub fn contributor_record_address(campaign: &Pubkey, contributor: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[CONTRIBUTOR_SEED, campaign.as_ref(), contributor.as_ref()], &crate::ID).0
+}
+
+/// Whether `membership` is the allowlist program's live membership PDA for
+/// (`list`, `contributor`). Checked by address and owner, without a CPI.
+pub fn is_allowlisted(list: &Pubkey, contributor: &Pubkey, membership: &AccountInfo) -> bool {
+    let (expected, _) = Pubkey::find_program_address(
+        &[ALLOWLIST_MEMBER_SEED, list.as_ref(), contributor.as_ref()],
+        &ALLOWLIST_PROGRAM_ID,
+    );
+    *membership.key == expected && *membership.owner == ALLOWLIST_PROGRAM_ID && membership.lamports() > 0
+}
+
+/// Read a campaign; accounts may be allocated larger than the state they hold
+fn load_campaign(campaign_account: &AccountInfo) -> Result<CrowdfundAccount, ProgramError> {
+    load_versioned(&campaign_account.data.borrow())
+}
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    process_instruction_with_clock(program_id, accounts, instruction_data, &SysvarClock)
+}
+
+/// `process_instruction` reading the slot from `clock` instead of the sysvar
+pub fn process_instruction_with_clock(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+    clock: &impl ClockSource,
+) -> ProgramResult {
+    let instruction = CrowdfundInstruction::unpack(instruction_data)?;
+
+    match instruction {
+        CrowdfundInstruction::Initialize { goal, duration_slots } => {
+            initialize(program_id, accounts, clock, goal, duration_slots)
+        }
+        CrowdfundInstruction::Contribute { amount } => {
+            contribute(program_id, accounts, clock, amount)
+        }
+        CrowdfundInstruction::Withdraw => withdraw(program_id, accounts, clock),
+        CrowdfundInstruction::Refund => refund(program_id, accounts, clock),
+        CrowdfundInstruction::BatchRefund => batch_refund(program_id, accounts, clock),
+        CrowdfundInstruction::SetAllowlist { allowlist } => set_allowlist(program_id, accounts, clock, allowlist),
+        CrowdfundInstruction::Migrate => migrate(program_id, accounts),
+    }
+}
+
+#[derive(Debug, ShankInstruction)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CrowdfundInstruction {
+    /// Initialize crowdfund campaign
+    #[account(0, writable, name = "campaign", desc = "Program-owned campaign account of CrowdfundAccount::LEN bytes")]
+    #[account(1, signer, name = "owner", desc = "Campaign owner")]
+    #[account(2, name = "system_program", desc = "System program")]
+    Initialize { goal: u64, duration_slots: u64 },
+    /// Contribute funds; in allowlist mode the contributor's membership PDA must follow
+    #[account(0, writable, name = "campaign", desc = "Campaign account")]
+    #[account(1, writable, name = "contributor_record", desc = "Contributor record PDA (contributor_record_address)")]
+    #[account(2, writable, signer, name = "contributor", desc = "Contributor paying the lamports")]
+    #[account(3, name = "system_program", desc = "System program")]
+    #[account(4, optional, name = "membership", desc = "Allowlist membership PDA (allowlist mode only)")]
+    Contribute { amount: u64 },
+    /// Withdraw funds if successful (owner only)
+    #[account(0, writable, name = "campaign", desc = "Campaign account")]
+    #[account(1, writable, signer, name = "owner", desc = "Campaign owner receiving the funds")]
+    #[account(2, name = "system_program", desc = "System program")]
+    Withdraw,
+    /// Refund contribution if failed
+    #[account(0, writable, name = "campaign", desc = "Campaign account")]
+    #[account(1, writable, name = "contributor_record", desc = "Contributor record PDA")]
+    #[account(2, writable, signer, name = "contributor", desc = "Contributor receiving the refund")]
+    #[account(3, name = "system_program", desc = "System program")]
+    Refund,
+    /// Refund many contributors of a failed campaign at once (anyone can call).
+    /// Remaining accounts, per contributor: [writable] contributor_record PDA, [writable] contributor
+    #[account(0, writable, name = "campaign", desc = "Campaign account")]
+    BatchRefund,
+    /// Turn allowlist mode on with an `allowlist` list, or off with `None` (owner only, while active)
+    #[account(0, writable, name = "campaign", desc = "Campaign account")]
+    #[account(1, signer, name = "owner", desc = "Campaign owner")]
+    SetAllowlist { allowlist: Option<Pubkey> },
+    /// Grow a campaign written by an older program version to the current layout (anyone can call)
+    #[account(0, writable, name = "campaign", desc = "Campaign account")]
+    #[account(1, writable, signer, name = "payer", desc = "Pays the rent for the larger account")]
+    #[account(2, name = "system_program", desc = "System program")]
+    Migrate,
+}
+
+tagged_instruction! {
+    CrowdfundInstruction {
+        0 => Initialize { goal, duration_slots },
+        1 => Contribute { amount },
+        2 => Withdraw,
+        3 => Refund,
+        4 => BatchRefund,
+        5 => SetAllowlist { allowlist },
+        6 => Migrate,
+    }
+}
+
+impl VersionedInstruction for CrowdfundInstruction {}
+
+fn initialize(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    clock: &impl ClockSource,
+    goal: u64,
+    duration_slots: u64,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let campaign_account = next_account_info(accounts_iter)?;
+    let owner = next_account_info(accounts_iter)?;
+
+    assert_owned_by(campaign_account, program_id)?;
+    assert_writable(campaign_account)?;
+    assert_signer(owner)?;
+    assert_uninitialized(&load_campaign(campaign_account)?)?;
+    assert_rent_exempt(campaign_account)?;
+
+    if goal == 0 {
+        msg!("Goal must be greater than zero");
+        return Err(CrowdfundError::InvalidGoal.into());
+    }
+
+    if duration_slots == 0 {
+        msg!("Duration must be greater than zero");
+        return Err(CrowdfundError::InvalidDuration.into());
+    }
+
+    let deadline = clock
+        .slot()?
+        .checked_add(duration_slots)
+        .ok_or(CrowdfundError::Overflow)?;
+
+    let campaign = CrowdfundAccount {
+        discriminator: CrowdfundAccount::DISCRIMINATOR,
+        version: CrowdfundAccount::VERSION,
+        is_initialized: true,
+        owner: *owner.key,
+        goal,
+        deadline,
+        total_raised: 0,
+        finalized: false,
+        allowlist: None,
+    };
+
+    campaign.serialize(&mut &mut campaign_account.data.borrow_mut()[..])?;
+    debug_msg!("Crowdfund initialized: goal={}, deadline={}", goal, deadline);
+    emit_event!(CampaignInitialized { campaign: *campaign_account.key, owner: *owner.key, goal, deadline })?;
+
+    Ok(())
+}
+
+fn contribute(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    clock: &impl ClockSource,
+    amount: u64,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let campaign_account = next_account_info(accounts_iter)?;
+    let contributor_record = next_account_info(accounts_iter)?;
+    let contributor = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    assert_owned_by(campaign_account, program_id)?;
+    assert_writable(campaign_account)?;
+    assert_signer(contributor)?;
+
+    if amount == 0 {
+        msg!("Must contribute a positive amount");
+        return Err(CrowdfundError::ZeroContribution.into());
+    }
+
+    let mut campaign = load_campaign(campaign_account)?;
+    assert_initialized(&campaign)?;
+
+    if clock.slot()? >= campaign.deadline {
+        msg!("Campaign has ended");
+        return Err(CrowdfundError::CampaignEnded.into());
+    }
+
+    if campaign.finalized {
+        msg!("Campaign already finalized");
+        return Err(CrowdfundError::AlreadyFinalized.into());
+    }
+
+    if let Some(list) = campaign.allowlist {
+        let membership = next_account_info(accounts_iter)?;
+        if !is_allowlisted(&list, contributor.key, membership) {
+            msg!("{} is not on the campaign's allowlist", contributor.key);
+            return Err(CrowdfundError::NotAllowlisted.into());
+        }
+    }
+
+    // The record holds no keys, so only its address ties it to this campaign and contributor
+    let seeds = [CONTRIBUTOR_SEED, campaign_account.key.as_ref(), contributor.key.as_ref()];
+    let bump = assert_pda(contributor_record, &seeds, &crate::ID)?;
+
+    // Transfer lamports from contributor to campaign account
+    invoke(
+        &system_instruction::transfer(contributor.key, campaign_account.key, amount),
+        &[contributor.clone(), campaign_account.clone(), system_program.clone()],
+    )?;
+
+    // Create the contributor record on the first contribution, then update it, tagging it on first write
+    if contributor_record.data_len() == 0 {
+        create_pda_account(
+            contributor_record,
+            contributor,
+            system_program,
+            ContributorAccount::LEN,
+            program_id,
+            &[CONTRIBUTOR_SEED, campaign_account.key.as_ref(), contributor.key.as_ref(), &[bump]],
+        )?;
+    }
+    assert_owned_by(contributor_record, program_id)?;
+
+    let mut contributor_data: ContributorAccount = load_account(&contributor_record.data.borrow())?;
+    contributor_data.discriminator = ContributorAccount::DISCRIMINATOR;
+
+    contributor_data.amount = contributor_data
+        .amount
+        .checked_add(amount)
+        .ok_or(CrowdfundError::Overflow)?;
+
+    contributor_data.serialize(&mut &mut contributor_record.data.borrow_mut()[..])?;
+
+    campaign.total_raised = campaign
+        .total_raised
+        .checked_add(amount)
+        .ok_or(CrowdfundError::Overflow)?;
+
+    campaign.serialize(&mut &mut campaign_account.data.borrow_mut()[..])?;
+
+    debug_msg!("Contributed {} lamports. Total raised: {}", amount, campaign.total_raised);
+    emit_event!(Contributed { campaign: *campaign_account.key, contributor: *contributor.key, amount })?;
+
+    if campaign.total_raised >= campaign.goal {
+        debug_msg!("Goal reached!");
+        emit_event!(GoalReached { campaign: *campaign_account.key, total_raised: campaign.total_raised })?;
+    }
+
+    Ok(())
+}
+
+fn withdraw(program_id: &Pubkey, accounts: &[AccountInfo], clock: &impl ClockSource) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let campaign_account = next_account_info(accounts_iter)?;
+    let owner = next_account_info(accounts_iter)?;
+
+    assert_owned_by(campaign_account, program_id)?;
+    assert_writable(campaign_account)?;
+    assert_signer(owner)?;
+
+    let mut campaign = load_campaign(campaign_account)?;
+    assert_initialized(&campaign)?;
+
+    if campaign.owner != *owner.key {
+        msg!("Only owner can withdraw");
+        return Err(CrowdfundError::NotOwner.into());
+    }
+
+    if clock.slot()? < campaign.deadline {
+        msg!("Campaign still active");
+        return Err(CrowdfundError::CampaignActive.into());
+    }
+
+    if campaign.finalized {
+        msg!("Already finalized");
+        return Err(CrowdfundError::AlreadyFinalized.into());
+    }
+
+    if campaign.total_raised < campaign.goal {
+        msg!("Goal not reached");
+        return Err(CrowdfundError::GoalNotReached.into());
+    }
+
+    campaign.finalized = true;
+    let amount = campaign.total_raised;
+
+    // Transfer funds to owner
+    transfer_lamports(campaign_account, owner, amount)?;
+
+    campaign.serialize(&mut &mut campaign_account.data.borrow_mut()[..])?;
+    debug_msg!("Withdrawn {} lamports", amount);
+    emit_event!(Withdrawn { campaign: *campaign_account.key, owner: *owner.key, amount })?;
+
+    Ok(())
+}
+
+fn refund(program_id: &Pubkey, accounts: &[AccountInfo], clock: &impl ClockSource) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let campaign_account = next_account_info(accounts_iter)?;
+    let contributor_record = next_account_info(accounts_iter)?;
+    let contributor = next_account_info(accounts_iter)?;
+
+    assert_owned_by(campaign_account, program_id)?;
+    assert_writable(campaign_account)?;
+    assert_signer(contributor)?;
+
+    let campaign = load_campaign(campaign_account)?;
+    assert_initialized(&campaign)?;
+
+    if clock.slot()? < campaign.deadline {
+        msg!("Campaign still active");
+        return Err(CrowdfundError::CampaignActive.into());
+    }
+
+    if campaign.total_raised >= campaign.goal {
+        msg!("Goal was reached, no refunds");
+        return Err(CrowdfundError::GoalReached.into());
+    }
+
+    assert_owned_by(contributor_record, program_id)?;
+    assert_writable(contributor_record)?;
+
+    // The record holds no keys, so only its address ties it to this campaign and signer
+    assert_pda(
+        contributor_record,
+        &[CONTRIBUTOR_SEED, campaign_account.key.as_ref(), contributor.key.as_ref()],
+        &crate::ID,
+    )?;
+
+    let mut contributor_data: ContributorAccount = load_account(&contributor_record.data.borrow())?;
+
+    if contributor_data.amount == 0 {
+        msg!("No contribution to refund");
+        return Err(CrowdfundError::NothingToRefund.into());
+    }
+
+    let amount = contributor_data.amount;
+    contributor_data.amount = 0;
+
+    // Transfer lamports back to contributor
+    transfer_lamports(campaign_account, contributor, amount)?;
+
+    contributor_data.serialize(&mut &mut contributor_record.data.borrow_mut()[..])?;
+    debug_msg!("Refunded {} lamports", amount);
+    emit_event!(Refunded { campaign: *campaign_account.key, contributor: *contributor.key, amount })?;
+
+    Ok(())
+}
+
+fn batch_refund(program_id: &Pubkey, accounts: &[AccountInfo], clock: &impl ClockSource) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let campaign_account = next_account_info(accounts_iter)?;
+
+    assert_owned_by(campaign_account, program_id)?;
+    assert_writable(campaign_account)?;
+
+    let campaign = load_campaign(campaign_account)?;
+    assert_initialized(&campaign)?;
+
+    if clock.slot()? < campaign.deadline {
+        msg!("Campaign still active");
+        return Err(CrowdfundError::CampaignActive.into());
+    }
+
+    if campaign.total_raised >= campaign.goal {
+        msg!("Goal was reached, no refunds");
+        return Err(CrowdfundError::GoalReached.into());
+    }
+
+    let pairs = accounts_iter.as_slice();
+    if pairs.is_empty() || !pairs.len().is_multiple_of(2) {
+        msg!("Expected (contributor_record, contributor) pairs");
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+
+    let mut refunded = 0u64;
+    let mut count = 0u32;
+
+    for pair in pairs.chunks_exact(2) {
+        let (contributor_record, contributor) = (&pair[0], &pair[1]);
+
+        assert_owned_by(contributor_record, program_id)?;
+
+        // No signer here, so the record must be the contributor's own PDA
+        assert_pda(
+            contributor_record,
+            &[CONTRIBUTOR_SEED, campaign_account.key.as_ref(), contributor.key.as_ref()],
+            &crate::ID,
+        )?;
+
+        let mut contributor_data: ContributorAccount = load_account(&contributor_record.data.borrow())?;
+
+        // Already refunded; skipping lets overlapping batches both succeed
+        if contributor_data.amount == 0 {
+            continue;
+        }
+
+        let amount = contributor_data.amount;
+        contributor_data.amount = 0;
+
+        transfer_lamports(campaign_account, contributor, amount)?;
+
+        contributor_data.serialize(&mut &mut contributor_record.data.borrow_mut()[..])?;
+        emit_event!(Refunded { campaign: *campaign_account.key, contributor: *contributor.key, amount })?;
+
+        refunded = refunded.checked_add(amount).ok_or(CrowdfundError::Overflow)?;
+        count += 1;
+    }
+
+    debug_msg!("Refunded {} lamports to {} contributors", refunded, count);
+
+    Ok(())
+}
+
+fn set_allowlist(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    clock: &impl ClockSource,
+    allowlist: Option<Pubkey>,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let campaign_account = next_account_info(accounts_iter)?;
+    let owner = next_account_info(accounts_iter)?;
+
+    assert_owned_by(campaign_account, program_id)?;
+    assert_writable(campaign_account)?;
+    assert_signer(owner)?;
+
+    let mut campaign = load_campaign(campaign_account)?;
+    assert_initialized(&campaign)?;
+
+    if campaign.owner != *owner.key {
+        msg!("Only owner can set the allowlist");
+        return Err(CrowdfundError::NotOwner.into());
+    }
+
+    if clock.slot()? >= campaign.deadline || campaign.finalized {
+        msg!("Campaign has ended");
+        return Err(CrowdfundError::CampaignEnded.into());
+    }
+
+    campaign.allowlist = allowlist;
+    campaign.serialize(&mut &mut campaign_account.data.borrow_mut()[..])?;
+
+    match allowlist {
+        Some(list) => debug_msg!("Allowlist mode on: {}", list),
+        None => debug_msg!("Allowlist mode off"),
+    }
+    emit_event!(AllowlistSet { campaign: *campaign_account.key, allowlist })?;
+
+    Ok(())
+}
+
+fn migrate(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let campaign_account = next_account_info(accounts_iter)?;
+    let payer = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    assert_owned_by(campaign_account, program_id)?;
+    assert_writable(campaign_account)?;
+    assert_signer(payer)?;
+
+    migrate_account::<CrowdfundAccount>(campaign_account, payer, system_program)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use common::FixedClock;
+    use solana_program::{entrypoint::SUCCESS, program_stubs, rent::Rent, system_program};
+    use test_utils::account::TestAccount;
+
+    /// Serves the default Rent sysvar so handlers can run off-chain; tests
+    /// pass the time as a `FixedClock`
+    struct TestSyscallStubs;
+
+    impl program_stubs::SyscallStubs for TestSyscallStubs {
+        fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+            unsafe { *(var_addr as *mut Rent) = Rent::default() };
+            SUCCESS
+        }
+    }
+
+    #[test]
+    fn test_initialize() {
+        program_stubs::set_syscall_stubs(Box::new(TestSyscallStubs));
+        let clock = FixedClock::at_slot(0);
+        let program_id = Pubkey::default();
+        let mut campaign = TestAccount::new(Pubkey::default())
+            .writable()
+            .data_len(CrowdfundAccount::LEN)
+            .rent_exempt()
+            .owner(program_id);
+        let mut owner = TestAccount::new(Pubkey::new_unique()).signer().owner(program_id);
+
+        let accounts = vec![campaign.info(), owner.info()];
+        let instruction = CrowdfundInstruction::Initialize {
+            goal: 1000,
+            duration_slots: 100,
+        };
+        let instruction_data = instruction.pack();
+
+        let result = process_instruction_with_clock(&program_id, &accounts, &instruction_data, &clock);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_withdraw_deadline() {
+        let program_id = Pubkey::new_unique();
+        let owner_key = Pubkey::new_unique();
+        let campaign = CrowdfundAccount {
+            discriminator: CrowdfundAccount::DISCRIMINATOR,
+            version: CrowdfundAccount::VERSION,
+            is_initialized: true,
+            owner: owner_key,
+            goal: 1_000,
+            deadline: 100,
+            total_raised: 1_000,
+            finalized: false,
+            allowlist: None,
+        };
+        let mut campaign = TestAccount::new(Pubkey::new_unique())
+            .writable()
+            .lamports(1_000)
+            .data(campaign.try_to_vec().unwrap())
+            .owner(program_id);
+        let mut owner = TestAccount::new(owner_key).signer().writable().owner(program_id);
+        let accounts = [campaign.info(), owner.info()];
+        let withdraw = CrowdfundInstruction::Withdraw.pack();
+
+        // The deadline slot is the first one after the campaign
+        let result = process_instruction_with_clock(&program_id, &accounts, &withdraw, &FixedClock::at_slot(99));
+        assert_eq!(result, Err(CrowdfundError::CampaignActive.into()));
+        process_instruction_with_clock(&program_id, &accounts, &withdraw, &FixedClock::at_slot(100)).unwrap();
+        assert_eq!(accounts[1].lamports(), 1_000);
+        assert!(load_campaign(&accounts[0]).unwrap().finalized);
+    }
+
+    #[test]
+    fn test_contribute_requires_own_record() {
+        program_stubs::set_syscall_stubs(Box::new(TestSyscallStubs));
+        let clock = FixedClock::at_slot(10);
+        let program_id = crate::ID;
+        let (alice, bob) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+        let campaign = CrowdfundAccount {
+            discriminator: CrowdfundAccount::DISCRIMINATOR,
+            version: CrowdfundAccount::VERSION,
+            is_initialized: true,
+            owner: Pubkey::new_unique(),
+            goal: 1_000,
+            deadline: 100,
+            total_raised: 100,
+            finalized: false,
+            allowlist: None,
+        };
+        let record = ContributorAccount { discriminator: ContributorAccount::DISCRIMINATOR, amount: 100 };
+
+        // campaign, alice's record, bob, alice, system program
+        let campaign_key = Pubkey::new_unique();
+        let mut test_accounts = [
+            TestAccount::new(campaign_key).writable().data(campaign.try_to_vec().unwrap()).owner(program_id),
+            TestAccount::new(contributor_record_address(&campaign_key, &alice))
+                .writable()
+                .data(record.try_to_vec().unwrap())
+                .owner(program_id),
+            TestAccount::new(bob).signer().writable().lamports(1_000),
+            TestAccount::new(alice).signer().writable().lamports(1_000),
+            TestAccount::new(system_program::id()),
+        ];
+        let accounts: Vec<AccountInfo> = test_accounts.iter_mut().map(TestAccount::info).collect();
+        let contribute = CrowdfundInstruction::Contribute { amount: 50 }.pack();
+
+        // Bob can't top up alice's record
+        let topped_up = [accounts[0].clone(), accounts[1].clone(), accounts[2].clone(), accounts[4].clone()];
+        let result = process_instruction_with_clock(&program_id, &topped_up, &contribute, &clock);
+        assert_eq!(result, Err(ProgramError::InvalidSeeds));
+
+        let own = [accounts[0].clone(), accounts[1].clone(), accounts[3].clone(), accounts[4].clone()];
+        process_instruction_with_clock(&program_id, &own, &contribute, &clock).unwrap();
+        let record: ContributorAccount = load_account(&accounts[1].data.borrow()).unwrap();
+        assert_eq!(record.amount, 150);
+    }
+
+    #[test]
+    fn test_refund_requires_own_record() {
+        program_stubs::set_syscall_stubs(Box::new(TestSyscallStubs));
+        let clock = FixedClock::at_slot(1_000);
+        let program_id = crate::ID;
+        let (alice, bob) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+        let campaign = CrowdfundAccount {
+            discriminator: CrowdfundAccount::DISCRIMINATOR,
+            version: CrowdfundAccount::VERSION,
+            is_initialized: true,
+            owner: Pubkey::new_unique(),
+            goal: 1_000,
+            deadline: 100,
+            total_raised: 100,
+            finalized: false,
+            allowlist: None,
+        };
+        let record = |campaign_key, contributor| {
+            let record = ContributorAccount { discriminator: ContributorAccount::DISCRIMINATOR, amount: 100 };
+            TestAccount::new(contributor_record_address(&campaign_key, &contributor))
+                .writable()
+                .data(record.try_to_vec().unwrap())
+                .owner(program_id)
+        };
+
+        // This campaign, alice's record in it, bob, alice, and a record of bob's from another campaign
+        let campaign_key = Pubkey::new_unique();
+        let mut test_accounts = [
+            TestAccount::new(campaign_key)
+                .writable()
+                .lamports(100)
+                .data(campaign.try_to_vec().unwrap())
+                .owner(program_id),
+            record(campaign_key, alice),
+            TestAccount::new(bob).signer().writable(),
+            TestAccount::new(alice).signer().writable(),
+            record(Pubkey::new_unique(), bob),
+        ];
+        let accounts: Vec<AccountInfo> = test_accounts.iter_mut().map(TestAccount::info).collect();
+        let refund = CrowdfundInstruction::Refund.pack();
+
+        // Bob can't claim alice's refund, nor his own from another campaign
+        for (record, signer) in [(1, 2), (4, 2)] {
+            let stolen = [accounts[0].clone(), accounts[record].clone(), accounts[signer].clone()];
+            let result = process_instruction_with_clock(&program_id, &stolen, &refund, &clock);
+            assert_eq!(result, Err(ProgramError::InvalidSeeds));
+        }
+        assert_eq!(accounts[0].lamports(), 100);
+
+        let own = [accounts[0].clone(), accounts[1].clone(), accounts[3].clone()];
+        process_instruction_with_clock(&program_id, &own, &refund, &clock).unwrap();
+        assert_eq!((accounts[0].lamports(), accounts[3].lamports()), (0, 100));
+    }
+
+    #[test]
+    fn test_batch_refund() {
+        program_stubs::set_syscall_stubs(Box::new(TestSyscallStubs));
+        let clock = FixedClock::at_slot(1_000);
+
+        let program_id = crate::ID;
+        let campaign_key = Pubkey::new_unique();
+        let contributors = [Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()];
+
+        let campaign = CrowdfundAccount {
+            discriminator: CrowdfundAccount::DISCRIMINATOR,
+            version: CrowdfundAccount::VERSION,
+            is_initialized: true,
+            owner: Pubkey::new_unique(),
+            goal: 1_000,
+            deadline: 100,
+            total_raised: 300,
+            finalized: false,
+            allowlist: None,
+        };
+
+        // campaign, then (record, contributor) pairs; the last contributor was already refunded
+        let campaign = TestAccount::new(campaign_key).writable().lamports(300).data(campaign.try_to_vec().unwrap());
+        let mut test_accounts = vec![campaign.owner(program_id)];
+        for (contributor, amount) in contributors.iter().zip([100u64, 200, 0]) {
+            let record = ContributorAccount { discriminator: ContributorAccount::DISCRIMINATOR, amount };
+            let record_key = contributor_record_address(&campaign_key, contributor);
+            let record = TestAccount::new(record_key).writable().data(record.try_to_vec().unwrap());
+            test_accounts.push(record.owner(program_id));
+            test_accounts.push(TestAccount::new(*contributor).writable().owner(program_id));
+        }
+        let accounts: Vec<AccountInfo> = test_accounts.iter_mut().map(TestAccount::info).collect();
+
+        let instruction_data = CrowdfundInstruction::BatchRefund.pack();
+
+        // A record paired with the wrong contributor is rejected
+        let swapped = vec![accounts[0].clone(), accounts[1].clone(), accounts[4].clone()];
+        let result = process_instruction_with_clock(&program_id, &swapped, &instruction_data, &clock);
+        assert_eq!(result, Err(ProgramError::InvalidSeeds));
+
+        let result = process_instruction_with_clock(&program_id, &accounts, &instruction_data, &clock);
+        assert!(result.is_ok());
+        assert_eq!(accounts[0].lamports(), 0);
+        assert_eq!(accounts[2].lamports(), 100);
+        assert_eq!(accounts[4].lamports(), 200);
+
+        // Running the same batch again refunds nothing more
+        let result = process_instruction_with_clock(&program_id, &accounts, &instruction_data, &clock);
+        assert!(result.is_ok());
+        assert_eq!(accounts[4].lamports(), 200);
+    }
+
+    #[test]
+    fn test_allowlist_mode() {
+        program_stubs::set_syscall_stubs(Box::new(TestSyscallStubs));
+        let clock = FixedClock::at_slot(10);
+
+        let program_id = crate::ID;
+        let owner = Pubkey::new_unique();
+        let contributor = Pubkey::new_unique();
+        let list = Pubkey::new_unique();
+
+        let campaign = CrowdfundAccount {
+            discriminator: CrowdfundAccount::DISCRIMINATOR,
+            version: CrowdfundAccount::VERSION,
+            is_initialized: true,
+            owner,
+            goal: 1_000,
+            deadline: 100,
+            total_raised: 0,
+            finalized: false,
+            allowlist: None,
+        };
+        let mut campaign_data = campaign.try_to_vec().unwrap();
+        campaign_data.resize(CrowdfundAccount::LEN, 0);
+        let campaign_key = Pubkey::new_unique();
+        let record_key = contributor_record_address(&campaign_key, &contributor);
+
+        // campaign, owner, contributor record, contributor, system program, membership
+        let (membership, _) = Pubkey::find_program_address(
+            &[ALLOWLIST_MEMBER_SEED, list.as_ref(), contributor.as_ref()],
+            &ALLOWLIST_PROGRAM_ID,
+        );
+        let mut test_accounts = [
+            TestAccount::new(campaign_key).writable().data(campaign_data).owner(program_id),
+            TestAccount::new(owner).signer().writable().owner(program_id),
+            TestAccount::new(record_key).writable().data_len(ContributorAccount::LEN).owner(program_id),
+            TestAccount::new(contributor).signer().writable().lamports(1_000).owner(program_id),
+            TestAccount::new(Pubkey::new_unique()).writable().owner(program_id),
+            TestAccount::new(membership).writable().lamports(500).owner(ALLOWLIST_PROGRAM_ID),
+        ];
+        let accounts: Vec<AccountInfo> = test_accounts.iter_mut().map(TestAccount::info).collect();
+
+        let set = CrowdfundInstruction::SetAllowlist { allowlist: Some(list) }.pack();
+        process_instruction_with_clock(&program_id, &accounts[..2], &set, &clock).unwrap();
+        assert_eq!(load_campaign(&accounts[0]).unwrap().allowlist, Some(list));
+
+        let contribute = CrowdfundInstruction::Contribute { amount: 100 }.pack();
+
+        // A membership PDA for someone else's wallet is refused
+        let impostor = [
+            accounts[0].clone(),
+            accounts[2].clone(),
+            accounts[1].clone(),
+            accounts[4].clone(),
+            accounts[5].clone(),
+        ];
+        let result = process_instruction_with_clock(&program_id, &impostor, &contribute, &clock);
+        assert_eq!(result, Err(CrowdfundError::NotAllowlisted.into()));
+
+        let member = [
+            accounts[0].clone(),
+            accounts[2].clone(),
+            accounts[3].clone(),
+            accounts[4].clone(),
+            accounts[5].clone(),
+        ];
+        process_instruction_with_clock(&program_id, &member, &contribute, &clock).unwrap();
+        assert_eq!(load_campaign(&accounts[0]).unwrap().total_raised, 100);
+    }
+}
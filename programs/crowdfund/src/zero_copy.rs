@@ -0,0 +1,200 @@
+//! `Pod` layouts of `CrowdfundAccount` and `ContributorAccount`, borrowed in
+//! place with `bytemuck` instead of deserialized with borsh. Built with the
+//! `zero-copy` feature to compare the two; the program itself stores the
+//! borsh layouts.
+
+use bytemuck::{Pod, Zeroable};
+use common::{convert_discriminator, load_pod, load_pod_mut, Discriminator, IsInitialized};
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
+use std::mem::size_of;
+
+use crate::{ContributorAccount, CrowdfundAccount};
+
+/// `CrowdfundAccount` with its fields ordered so none needs padding before
+/// it. `Option<Pubkey>` becomes a flag plus an always-present key, so the
+/// layout is the same size whether or not allowlist mode is on.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Pod, Zeroable)]
+pub struct CrowdfundAccountPod {
+    pub discriminator: [u8; 8],
+    pub goal: u64,
+    /// Slot number
+    pub deadline: u64,
+    pub total_raised: u64,
+    pub owner: Pubkey,
+    /// The allowlist when `has_allowlist` is 1, zeroed otherwise
+    pub allowlist: Pubkey,
+    /// `CrowdfundAccount::VERSION` once written
+    pub version: u8,
+    /// 1 once initialized, 0 before
+    pub is_initialized: u8,
+    /// 1 once the owner has withdrawn, 0 before
+    pub finalized: u8,
+    /// 1 in allowlist mode, 0 otherwise
+    pub has_allowlist: u8,
+    /// Explicit padding so the layout has no implicit gaps
+    pub _padding: [u8; 4],
+}
+
+impl CrowdfundAccountPod {
+    /// Size of the account data in bytes
+    pub const LEN: usize = size_of::<Self>();
+
+    /// Borrow account data as a campaign without copying
+    pub fn load(data: &[u8]) -> Result<&Self, ProgramError> {
+        load_pod(data)
+    }
+
+    /// Mutably borrow account data as a campaign without copying
+    pub fn load_mut(data: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        load_pod_mut(data)
+    }
+
+    /// The list contributors must belong to, if allowlist mode is on
+    pub fn allowlist(&self) -> Option<Pubkey> {
+        (self.has_allowlist != 0).then_some(self.allowlist)
+    }
+
+    /// Turn allowlist mode on with `allowlist`, or off with `None`
+    pub fn set_allowlist(&mut self, allowlist: Option<Pubkey>) {
+        self.has_allowlist = allowlist.is_some() as u8;
+        self.allowlist = allowlist.unwrap_or_default();
+    }
+}
+
+impl Discriminator for CrowdfundAccountPod {
+    const DISCRIMINATOR: [u8; 8] = *b"zccampgn";
+}
+
+impl IsInitialized for CrowdfundAccountPod {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized != 0
+    }
+}
+
+impl From<&CrowdfundAccount> for CrowdfundAccountPod {
+    fn from(state: &CrowdfundAccount) -> Self {
+        let mut pod = Self {
+            discriminator: convert_discriminator::<Self>(state.discriminator),
+            goal: state.goal,
+            deadline: state.deadline,
+            total_raised: state.total_raised,
+            owner: state.owner,
+            version: state.version,
+            is_initialized: state.is_initialized as u8,
+            finalized: state.finalized as u8,
+            ..Self::zeroed()
+        };
+        pod.set_allowlist(state.allowlist);
+        pod
+    }
+}
+
+impl From<&CrowdfundAccountPod> for CrowdfundAccount {
+    fn from(state: &CrowdfundAccountPod) -> Self {
+        Self {
+            discriminator: convert_discriminator::<Self>(state.discriminator),
+            version: state.version,
+            is_initialized: state.is_initialized(),
+            owner: state.owner,
+            goal: state.goal,
+            deadline: state.deadline,
+            total_raised: state.total_raised,
+            finalized: state.finalized != 0,
+            allowlist: state.allowlist(),
+        }
+    }
+}
+
+/// `ContributorAccount`, whose borsh layout is already fixed-size and aligned
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Pod, Zeroable)]
+pub struct ContributorAccountPod {
+    pub discriminator: [u8; 8],
+    pub amount: u64,
+}
+
+impl ContributorAccountPod {
+    /// Size of the account data in bytes
+    pub const LEN: usize = size_of::<Self>();
+
+    /// Borrow account data as a contributor record without copying
+    pub fn load(data: &[u8]) -> Result<&Self, ProgramError> {
+        load_pod(data)
+    }
+
+    /// Mutably borrow account data as a contributor record without copying
+    pub fn load_mut(data: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        load_pod_mut(data)
+    }
+}
+
+impl Discriminator for ContributorAccountPod {
+    const DISCRIMINATOR: [u8; 8] = *b"zccontrb";
+}
+
+impl From<&ContributorAccount> for ContributorAccountPod {
+    fn from(state: &ContributorAccount) -> Self {
+        Self { discriminator: convert_discriminator::<Self>(state.discriminator), amount: state.amount }
+    }
+}
+
+impl From<&ContributorAccountPod> for ContributorAccount {
+    fn from(state: &ContributorAccountPod) -> Self {
+        Self { discriminator: convert_discriminator::<Self>(state.discriminator), amount: state.amount }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use borsh::BorshSerialize;
+    use common::Versioned;
+
+    fn campaign(allowlist: Option<Pubkey>) -> CrowdfundAccount {
+        CrowdfundAccount {
+            discriminator: CrowdfundAccount::DISCRIMINATOR,
+            version: CrowdfundAccount::VERSION,
+            is_initialized: true,
+            owner: Pubkey::new_unique(),
+            goal: 1_000,
+            deadline: 50,
+            total_raised: 600,
+            finalized: false,
+            allowlist,
+        }
+    }
+
+    #[test]
+    fn test_campaign_pod_round_trip() {
+        for state in [campaign(None), campaign(Some(Pubkey::new_unique()))] {
+            let pod = CrowdfundAccountPod::from(&state);
+            assert_eq!(pod.allowlist(), state.allowlist);
+            let back = CrowdfundAccount::from(&pod);
+            assert_eq!(back.try_to_vec().unwrap(), state.try_to_vec().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_pods_in_place() {
+        // Backed by u64s so the data is 8-byte aligned like on-chain data
+        let mut backing = [0u64; CrowdfundAccountPod::LEN / 8];
+        let data: &mut [u8] = bytemuck::cast_slice_mut(&mut backing);
+        data.copy_from_slice(bytemuck::bytes_of(&CrowdfundAccountPod::from(&campaign(None))));
+
+        let list = Pubkey::new_unique();
+        let pod = CrowdfundAccountPod::load_mut(data).unwrap();
+        pod.total_raised += 400;
+        pod.set_allowlist(Some(list));
+        let pod = CrowdfundAccountPod::load(data).unwrap();
+        assert_eq!((pod.total_raised, pod.allowlist()), (1_000, Some(list)));
+        assert_eq!(ContributorAccountPod::load(data).err(), Some(ProgramError::InvalidAccountData));
+
+        let record = ContributorAccount { discriminator: ContributorAccount::DISCRIMINATOR, amount: 5 };
+        let mut backing = [0u64; ContributorAccountPod::LEN / 8];
+        let data: &mut [u8] = bytemuck::cast_slice_mut(&mut backing);
+        data.copy_from_slice(bytemuck::bytes_of(&ContributorAccountPod::from(&record)));
+        ContributorAccountPod::load_mut(data).unwrap().amount = 0;
+        assert_eq!(ContributorAccount::from(ContributorAccountPod::load(data).unwrap()).amount, 0);
+    }
+}
@@ -0,0 +1,295 @@
+//! Crowdfund against the real runtime through `BanksClient`: lamport
+//! transfers go through the system program CPI and deadlines through the
+//! clock sysvar, warped with `warp_to_slot`. The program runs natively via
+//! `processor!`, so no SBF build is needed.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use common::{Discriminator, Versioned, VersionedInstruction};
+use crowdfund::{
+    contributor_record_address, process_instruction, ContributorAccount, CrowdfundAccount, CrowdfundError,
+    CrowdfundInstruction,
+};
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    pubkey::Pubkey,
+    rent::Rent,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::{Transaction, TransactionError},
+};
+
+const STARTING_LAMPORTS: u64 = 1_000_000_000;
+
+struct Campaign {
+    program_id: Pubkey,
+    campaign: Pubkey,
+    owner: Keypair,
+    contributors: Vec<Keypair>,
+}
+
+/// Start a test validator with a program-owned campaign account holding
+/// `state` (zeroed when `None`), a funded owner, and `contributors` funded
+/// contributors; their record PDAs are created by their first contribution
+async fn setup(state: Option<CrowdfundAccount>, contributors: usize) -> (ProgramTestContext, Campaign) {
+    let program_id = crowdfund::ID;
+    let campaign = Pubkey::new_unique();
+    let owner = Keypair::new();
+    let contributors: Vec<_> = (0..contributors).map(|_| Keypair::new()).collect();
+
+    let mut program_test = ProgramTest::new("crowdfund", program_id, processor!(process_instruction));
+
+    let mut data = state.map_or(vec![], |state| state.try_to_vec().unwrap());
+    data.resize(CrowdfundAccount::LEN, 0);
+    program_test.add_account(campaign, program_account(program_id, data));
+
+    for wallet in std::iter::once(&owner).chain(&contributors) {
+        program_test.add_account(wallet.pubkey(), Account::new(STARTING_LAMPORTS, 0, &system_program::id()));
+    }
+
+    let context = program_test.start_with_context().await;
+    (
+        context,
+        Campaign {
+            program_id,
+            campaign,
+            owner,
+            contributors,
+        },
+    )
+}
+
+fn program_account(program_id: Pubkey, data: Vec<u8>) -> Account {
+    Account {
+        lamports: 10_000_000,
+        data,
+        owner: program_id,
+        ..Account::default()
+    }
+}
+
+impl Campaign {
+    fn instruction(&self, data: CrowdfundInstruction, accounts: Vec<AccountMeta>) -> Instruction {
+        Instruction::new_with_bytes(self.program_id, &data.pack(), accounts)
+    }
+
+    fn record(&self, contributor: &Keypair) -> Pubkey {
+        contributor_record_address(&self.campaign, &contributor.pubkey())
+    }
+
+    fn initialize(&self, goal: u64, duration_slots: u64) -> Instruction {
+        self.instruction(
+            CrowdfundInstruction::Initialize { goal, duration_slots },
+            vec![
+                AccountMeta::new(self.campaign, false),
+                AccountMeta::new_readonly(self.owner.pubkey(), true),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        )
+    }
+
+    fn contribute(&self, contributor: &Keypair, amount: u64) -> Instruction {
+        self.instruction(
+            CrowdfundInstruction::Contribute { amount },
+            vec![
+                AccountMeta::new(self.campaign, false),
+                AccountMeta::new(self.record(contributor), false),
+                AccountMeta::new(contributor.pubkey(), true),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        )
+    }
+
+    fn withdraw(&self, signer: &Keypair) -> Instruction {
+        self.instruction(
+            CrowdfundInstruction::Withdraw,
+            vec![
+                AccountMeta::new(self.campaign, false),
+                AccountMeta::new(signer.pubkey(), true),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        )
+    }
+
+    fn refund(&self, contributor: &Keypair) -> Instruction {
+        self.instruction(
+            CrowdfundInstruction::Refund,
+            vec![
+                AccountMeta::new(self.campaign, false),
+                AccountMeta::new(self.record(contributor), false),
+                AccountMeta::new(contributor.pubkey(), true),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        )
+    }
+
+    fn batch_refund(&self, contributors: &[&Keypair]) -> Instruction {
+        let mut accounts = vec![AccountMeta::new(self.campaign, false)];
+        for contributor in contributors {
+            accounts.push(AccountMeta::new(self.record(contributor), false));
+            accounts.push(AccountMeta::new(contributor.pubkey(), false));
+        }
+        self.instruction(CrowdfundInstruction::BatchRefund, accounts)
+    }
+}
+
+async fn send(
+    context: &mut ProgramTestContext,
+    instruction: Instruction,
+    signers: &[&Keypair],
+) -> Result<(), BanksClientError> {
+    let blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let mut keypairs = vec![&context.payer];
+    keypairs.extend_from_slice(signers);
+    let transaction =
+        Transaction::new_signed_with_payer(&[instruction], Some(&context.payer.pubkey()), &keypairs, blockhash);
+    context.banks_client.process_transaction(transaction).await
+}
+
+fn program_error(result: Result<(), BanksClientError>) -> InstructionError {
+    match result.unwrap_err().unwrap() {
+        TransactionError::InstructionError(0, error) => error,
+        other => panic!("unexpected transaction error {:?}", other),
+    }
+}
+
+async fn campaign_state(context: &mut ProgramTestContext, campaign: &Campaign) -> CrowdfundAccount {
+    let account = context.banks_client.get_account(campaign.campaign).await.unwrap().unwrap();
+    CrowdfundAccount::deserialize(&mut &account.data[..]).unwrap()
+}
+
+async fn contributed(context: &mut ProgramTestContext, campaign: &Campaign, contributor: &Keypair) -> u64 {
+    let account = context.banks_client.get_account(campaign.record(contributor)).await.unwrap().unwrap();
+    ContributorAccount::try_from_slice(&account.data).unwrap().amount
+}
+
+async fn balance(context: &mut ProgramTestContext, key: Pubkey) -> u64 {
+    context.banks_client.get_balance(key).await.unwrap()
+}
+
+/// What a contributor's first contribution costs on top of the amount: the
+/// rent-exempt balance of their new record
+fn record_rent() -> u64 {
+    Rent::default().minimum_balance(ContributorAccount::LEN)
+}
+
+#[tokio::test]
+async fn test_successful_campaign() {
+    let (mut context, campaign) = setup(None, 2).await;
+    let (alice, bob) = (&campaign.contributors[0], &campaign.contributors[1]);
+
+    send(&mut context, campaign.initialize(1_000, 50), &[&campaign.owner]).await.unwrap();
+    send(&mut context, campaign.contribute(alice, 600), &[alice]).await.unwrap();
+    send(&mut context, campaign.contribute(bob, 400), &[bob]).await.unwrap();
+
+    assert_eq!(contributed(&mut context, &campaign, alice).await, 600);
+    assert_eq!(balance(&mut context, alice.pubkey()).await, STARTING_LAMPORTS - 600 - record_rent());
+    let state = campaign_state(&mut context, &campaign).await;
+    assert_eq!(state.total_raised, 1_000);
+
+    // Goal reached, but the deadline hasn't passed
+    let result = send(&mut context, campaign.withdraw(&campaign.owner), &[&campaign.owner]).await;
+    assert_eq!(program_error(result), InstructionError::Custom(CrowdfundError::CampaignActive as u32));
+
+    context.warp_to_slot(state.deadline).unwrap();
+
+    // Deadline passed
+    let result = send(&mut context, campaign.contribute(alice, 1), &[alice]).await;
+    assert_eq!(program_error(result), InstructionError::Custom(CrowdfundError::CampaignEnded as u32));
+
+    // Wrong signer
+    let result = send(&mut context, campaign.withdraw(bob), &[bob]).await;
+    assert_eq!(program_error(result), InstructionError::Custom(CrowdfundError::NotOwner as u32));
+
+    send(&mut context, campaign.withdraw(&campaign.owner), &[&campaign.owner]).await.unwrap();
+    assert_eq!(balance(&mut context, campaign.owner.pubkey()).await, STARTING_LAMPORTS + 1_000);
+    assert!(campaign_state(&mut context, &campaign).await.finalized);
+
+    let result = send(&mut context, campaign.refund(alice), &[alice]).await;
+    assert_eq!(program_error(result), InstructionError::Custom(CrowdfundError::GoalReached as u32));
+}
+
+#[tokio::test]
+async fn test_failed_campaign_refunds() {
+    let (mut context, campaign) = setup(None, 2).await;
+    let (alice, bob) = (&campaign.contributors[0], &campaign.contributors[1]);
+
+    send(&mut context, campaign.initialize(1_000, 50), &[&campaign.owner]).await.unwrap();
+    send(&mut context, campaign.contribute(alice, 300), &[alice]).await.unwrap();
+    send(&mut context, campaign.contribute(bob, 200), &[bob]).await.unwrap();
+
+    let deadline = campaign_state(&mut context, &campaign).await.deadline;
+    context.warp_to_slot(deadline).unwrap();
+
+    let result = send(&mut context, campaign.withdraw(&campaign.owner), &[&campaign.owner]).await;
+    assert_eq!(program_error(result), InstructionError::Custom(CrowdfundError::GoalNotReached as u32));
+
+    send(&mut context, campaign.refund(alice), &[alice]).await.unwrap();
+    // Permissionless: only the fee payer signs for bob's refund
+    send(&mut context, campaign.batch_refund(&[bob]), &[]).await.unwrap();
+
+    for contributor in [alice, bob] {
+        assert_eq!(balance(&mut context, contributor.pubkey()).await, STARTING_LAMPORTS - record_rent());
+        assert_eq!(contributed(&mut context, &campaign, contributor).await, 0);
+    }
+
+    let result = send(&mut context, campaign.refund(alice), &[alice]).await;
+    assert_eq!(program_error(result), InstructionError::Custom(CrowdfundError::NothingToRefund as u32));
+}
+
+/// `BatchRefund` pays out the PDA records that `Contribute` created, for
+/// contributors who never sign again
+#[tokio::test]
+async fn test_contribute_then_batch_refund() {
+    let (mut context, campaign) = setup(None, 3).await;
+    let contributors: Vec<&Keypair> = campaign.contributors.iter().collect();
+
+    send(&mut context, campaign.initialize(10_000, 50), &[&campaign.owner]).await.unwrap();
+    for (contributor, amount) in contributors.iter().zip([100, 200, 300]) {
+        send(&mut context, campaign.contribute(contributor, amount), &[contributor]).await.unwrap();
+        // A second contribution tops up the same record
+        send(&mut context, campaign.contribute(contributor, amount), &[contributor]).await.unwrap();
+        assert_eq!(contributed(&mut context, &campaign, contributor).await, 2 * amount);
+    }
+    let campaign_before = balance(&mut context, campaign.campaign).await;
+
+    let deadline = campaign_state(&mut context, &campaign).await.deadline;
+    context.warp_to_slot(deadline).unwrap();
+
+    send(&mut context, campaign.batch_refund(&contributors), &[]).await.unwrap();
+    for contributor in &contributors {
+        assert_eq!(balance(&mut context, contributor.pubkey()).await, STARTING_LAMPORTS - record_rent());
+        assert_eq!(contributed(&mut context, &campaign, contributor).await, 0);
+    }
+    assert_eq!(balance(&mut context, campaign.campaign).await, campaign_before - 1_200);
+
+    // A repeated batch refunds nothing more
+    send(&mut context, campaign.batch_refund(&contributors), &[]).await.unwrap();
+    assert_eq!(balance(&mut context, campaign.campaign).await, campaign_before - 1_200);
+}
+
+#[tokio::test]
+async fn test_total_raised_overflow() {
+    let owner = Pubkey::new_unique();
+    let state = CrowdfundAccount {
+        discriminator: CrowdfundAccount::DISCRIMINATOR,
+        version: CrowdfundAccount::VERSION,
+        is_initialized: true,
+        owner,
+        goal: u64::MAX,
+        deadline: u64::MAX,
+        total_raised: u64::MAX - 10,
+        finalized: false,
+        allowlist: None,
+    };
+    let (mut context, campaign) = setup(Some(state), 1).await;
+    let alice = &campaign.contributors[0];
+
+    let result = send(&mut context, campaign.contribute(alice, 100), &[alice]).await;
+    assert_eq!(program_error(result), InstructionError::Custom(CrowdfundError::Overflow as u32));
+
+    // The failed instruction rolls back the transfer CPI as well
+    assert_eq!(balance(&mut context, alice.pubkey()).await, STARTING_LAMPORTS);
+    assert_eq!(campaign_state(&mut context, &campaign).await.total_raised, u64::MAX - 10);
+}
@@ -0,0 +1,207 @@
+//! Property tests: random sequences of contributions, withdrawals, refunds and
+//! clock advances against one campaign. After every instruction, accepted or
+//! not, the campaign must still hold enough lamports to repay every
+//! refundable contribution, and no lamports may be created or destroyed.
+//!
+//! The handlers run natively against a `FixedClock`. The stubs below serve
+//! the rent and perform the system program transfer CPI, and a rejected instruction's
+//! writes are rolled back the way the runtime would.
+
+use borsh::BorshDeserialize;
+use common::{transfer_lamports, FixedClock, VersionedInstruction};
+use crowdfund::{
+    contributor_record_address, process_instruction_with_clock, ContributorAccount, CrowdfundAccount,
+    CrowdfundInstruction,
+};
+use proptest::prelude::*;
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::{ProgramResult, SUCCESS},
+    instruction::Instruction,
+    program_stubs::{self, SyscallStubs},
+    pubkey::Pubkey,
+    rent::Rent,
+    system_program,
+};
+use test_utils::account::TestAccount;
+
+const CONTRIBUTORS: usize = 3;
+/// Above the default Rent sysvar's minimum for `CrowdfundAccount::LEN` bytes
+const CAMPAIGN_RENT: u64 = 2_000_000;
+const RECORD_RENT: u64 = 100_000;
+const STARTING_BALANCE: u64 = 10_000;
+
+struct TestSyscallStubs;
+
+impl SyscallStubs for TestSyscallStubs {
+    fn sol_log(&self, _message: &str) {}
+
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        unsafe { *(var_addr as *mut Rent) = Rent::default() };
+        SUCCESS
+    }
+
+    /// The only CPI is the system program's `Transfer`: a u32 tag, then the
+    /// lamports, moving from the first account to the second
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        _signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        assert_eq!(instruction.program_id, system_program::id());
+        let amount = u64::from_le_bytes(instruction.data[4..12].try_into().unwrap());
+        let account = |index: usize| {
+            account_infos
+                .iter()
+                .find(|info| *info.key == instruction.accounts[index].pubkey)
+                .unwrap()
+        };
+        transfer_lamports(account(0), account(1), amount)
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Op {
+    Contribute { contributor: usize, amount: u64 },
+    Withdraw { by_owner: bool },
+    Refund { contributor: usize },
+    BatchRefund { contributors: Vec<usize> },
+    Warp { slots: u64 },
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    let contributor = 0..CONTRIBUTORS;
+    prop_oneof![
+        4 => (contributor.clone(), 0..STARTING_BALANCE / 2)
+            .prop_map(|(contributor, amount)| Op::Contribute { contributor, amount }),
+        1 => prop::bool::weighted(0.8).prop_map(|by_owner| Op::Withdraw { by_owner }),
+        2 => contributor.clone().prop_map(|contributor| Op::Refund { contributor }),
+        1 => prop::collection::vec(contributor, 0..=CONTRIBUTORS)
+            .prop_map(|contributors| Op::BatchRefund { contributors }),
+        2 => (0..8u64).prop_map(|slots| Op::Warp { slots }),
+    ]
+}
+
+proptest! {
+    #[test]
+    fn test_campaign_stays_solvent(
+        goal in 1..STARTING_BALANCE * CONTRIBUTORS as u64,
+        duration_slots in 1..16u64,
+        ops in prop::collection::vec(op(), 1..48),
+    ) {
+        program_stubs::set_syscall_stubs(Box::new(TestSyscallStubs));
+        let mut slot = 0;
+
+        let program_id = crowdfund::ID;
+        let campaign_key = Pubkey::new_unique();
+        let contributor_keys: Vec<Pubkey> = (0..CONTRIBUTORS).map(|_| Pubkey::new_unique()).collect();
+
+        // Campaign, owner, contributors, their records, then the system program
+        let campaign = TestAccount::new(campaign_key)
+            .writable()
+            .lamports(CAMPAIGN_RENT)
+            .data_len(CrowdfundAccount::LEN)
+            .owner(program_id);
+        let mut test_accounts = vec![campaign, TestAccount::new(Pubkey::new_unique()).signer().writable()];
+        test_accounts.extend(
+            contributor_keys.iter().map(|key| TestAccount::new(*key).signer().writable().lamports(STARTING_BALANCE)),
+        );
+        test_accounts.extend(contributor_keys.iter().map(|key| {
+            TestAccount::new(contributor_record_address(&campaign_key, key))
+                .writable()
+                .lamports(RECORD_RENT)
+                .data_len(ContributorAccount::LEN)
+                .owner(program_id)
+        }));
+        test_accounts.push(TestAccount::new(system_program::id()).lamports(1).executable());
+        let accounts: Vec<AccountInfo> = test_accounts.iter_mut().map(TestAccount::info).collect();
+        let campaign = &accounts[0];
+        let owner = &accounts[1];
+        let contributor = |index: usize| &accounts[2 + index];
+        let record = |index: usize| &accounts[2 + CONTRIBUTORS + index];
+        let system_program = &accounts[accounts.len() - 1];
+        let total_lamports: u64 = accounts.iter().map(|account| account.lamports()).sum();
+
+        let initialize = CrowdfundInstruction::Initialize { goal, duration_slots };
+        let accounts_in = [campaign.clone(), owner.clone(), system_program.clone()];
+        let data = initialize.pack();
+        let initialized = process_instruction_with_clock(&program_id, &accounts_in, &data, &FixedClock::at_slot(slot));
+        prop_assert_eq!(initialized, Ok(()));
+
+        for op in ops {
+            let (instruction, accounts_in) = match &op {
+                Op::Contribute { contributor: index, amount } => (
+                    CrowdfundInstruction::Contribute { amount: *amount },
+                    vec![campaign.clone(), record(*index).clone(), contributor(*index).clone(), system_program.clone()],
+                ),
+                Op::Withdraw { by_owner } => (
+                    CrowdfundInstruction::Withdraw,
+                    vec![
+                        campaign.clone(),
+                        if *by_owner { owner.clone() } else { contributor(0).clone() },
+                        system_program.clone(),
+                    ],
+                ),
+                Op::Refund { contributor: index } => (
+                    CrowdfundInstruction::Refund,
+                    vec![campaign.clone(), record(*index).clone(), contributor(*index).clone(), system_program.clone()],
+                ),
+                Op::BatchRefund { contributors } => {
+                    let mut accounts_in = vec![campaign.clone()];
+                    for index in contributors {
+                        accounts_in.push(record(*index).clone());
+                        accounts_in.push(contributor(*index).clone());
+                    }
+                    (CrowdfundInstruction::BatchRefund, accounts_in)
+                }
+                Op::Warp { slots } => {
+                    slot += slots;
+                    continue;
+                }
+            };
+
+            let snapshot: Vec<(u64, Vec<u8>)> =
+                accounts.iter().map(|account| (account.lamports(), account.data.borrow().to_vec())).collect();
+            let before = CrowdfundAccount::deserialize(&mut &campaign.data.borrow()[..]).unwrap();
+
+            let data = instruction.pack();
+            let result = process_instruction_with_clock(&program_id, &accounts_in, &data, &FixedClock::at_slot(slot));
+            if result.is_err() {
+                for (account, (lamports, data)) in accounts.iter().zip(&snapshot) {
+                    **account.lamports.borrow_mut() = *lamports;
+                    account.data.borrow_mut().copy_from_slice(data);
+                }
+            }
+
+            let state = CrowdfundAccount::deserialize(&mut &campaign.data.borrow()[..]).unwrap();
+            let recorded: u64 = (0..CONTRIBUTORS)
+                .map(|index| ContributorAccount::try_from_slice(&record(index).data.borrow()).unwrap().amount)
+                .sum();
+
+            if result.is_ok() {
+                match op {
+                    Op::Contribute { .. } => prop_assert!(slot < state.deadline),
+                    Op::Withdraw { by_owner } => {
+                        prop_assert!(by_owner && slot >= state.deadline && before.total_raised >= goal);
+                    }
+                    Op::Refund { .. } | Op::BatchRefund { .. } => {
+                        prop_assert!(slot >= state.deadline && state.total_raised < goal);
+                    }
+                    Op::Warp { .. } => unreachable!(),
+                }
+            }
+
+            let refundable = if state.finalized { 0 } else { recorded };
+            prop_assert!(
+                campaign.lamports() >= CAMPAIGN_RENT + refundable,
+                "campaign holds {} lamports, owes {} after {:?}",
+                campaign.lamports(),
+                refundable,
+                op
+            );
+            prop_assert!(recorded <= state.total_raised);
+            prop_assert_eq!(accounts.iter().map(|account| account.lamports()).sum::<u64>(), total_lamports);
+        }
+    }
+}
@@ -0,0 +1,86 @@
+//! Golden bytes of every borsh type the program stores, logs or accepts,
+//! against tests/fixtures/layout.txt. A failure here means deployed campaigns
+//! and contributor records, or existing clients, would no longer read the new
+//! layout.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use common::{event_data, Discriminator, Versioned};
+use crowdfund::{
+    AllowlistSet, CampaignInitialized, ContributorAccount, Contributed, CrowdfundAccount, CrowdfundInstruction,
+    GoalReached, Refunded, Withdrawn,
+};
+use solana_program::pubkey::Pubkey;
+use test_utils::golden::{read_fixture, GoldenBytes};
+
+const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/layout.txt");
+
+fn bytes(value: &impl BorshSerialize) -> Vec<u8> {
+    value.try_to_vec().unwrap()
+}
+
+#[test]
+fn test_layouts_match_fixture() {
+    let campaign = Pubkey::new_from_array([1; 32]);
+    let owner = Pubkey::new_from_array([2; 32]);
+    let contributor = Pubkey::new_from_array([3; 32]);
+    let list = Pubkey::new_from_array([4; 32]);
+    let mut golden = GoldenBytes::new();
+
+    let state = CrowdfundAccount {
+        discriminator: CrowdfundAccount::DISCRIMINATOR,
+        version: CrowdfundAccount::VERSION,
+        is_initialized: true,
+        owner,
+        goal: 1_000,
+        deadline: 0x0102_0304_0506_0708,
+        total_raised: 600,
+        finalized: false,
+        allowlist: None,
+    };
+    golden
+        .add("CrowdfundAccount", bytes(&state))
+        .add("CrowdfundAccount/allowlist", bytes(&CrowdfundAccount { allowlist: Some(list), ..state }))
+        .add(
+            "ContributorAccount",
+            bytes(&ContributorAccount { discriminator: ContributorAccount::DISCRIMINATOR, amount: 600 }),
+        );
+
+    // Events as logged, behind their discriminators
+    let event = CampaignInitialized { campaign, owner, goal: 1_000, deadline: 100 };
+    golden
+        .add("CampaignInitialized", event_data(&event).unwrap())
+        .add("Contributed", event_data(&Contributed { campaign, contributor, amount: 600 }).unwrap())
+        .add("GoalReached", event_data(&GoalReached { campaign, total_raised: 1_000 }).unwrap())
+        .add("Refunded", event_data(&Refunded { campaign, contributor, amount: 600 }).unwrap())
+        .add("Withdrawn", event_data(&Withdrawn { campaign, owner, amount: 1_000 }).unwrap())
+        .add("AllowlistSet", event_data(&AllowlistSet { campaign, allowlist: Some(list) }).unwrap());
+
+    let initialize = CrowdfundInstruction::Initialize { goal: 1_000, duration_slots: 100 };
+    golden
+        .add("CrowdfundInstruction::Initialize", bytes(&initialize))
+        .add("CrowdfundInstruction::Contribute", bytes(&CrowdfundInstruction::Contribute { amount: 600 }))
+        .add("CrowdfundInstruction::Withdraw", bytes(&CrowdfundInstruction::Withdraw))
+        .add("CrowdfundInstruction::Refund", bytes(&CrowdfundInstruction::Refund))
+        .add("CrowdfundInstruction::BatchRefund", bytes(&CrowdfundInstruction::BatchRefund))
+        .add("CrowdfundInstruction::SetAllowlist", bytes(&CrowdfundInstruction::SetAllowlist { allowlist: Some(list) }))
+        .add("CrowdfundInstruction::Migrate", bytes(&CrowdfundInstruction::Migrate));
+
+    golden.check(FIXTURE);
+}
+
+/// Instruction data in the fixture, as existing clients send it, still
+/// decodes to the variant of that name, and the next tag is unassigned
+#[test]
+fn test_fixture_instructions_decode() {
+    for (name, data) in read_fixture(FIXTURE) {
+        let Some(variant) = name.strip_prefix("CrowdfundInstruction::") else {
+            continue;
+        };
+        let instruction = CrowdfundInstruction::try_from_slice(&data).unwrap();
+        assert_eq!(format!("{:?}", instruction).split(' ').next(), Some(variant));
+        assert_eq!(instruction.tag(), data[0]);
+        assert_eq!(bytes(&instruction), data);
+    }
+
+    assert!(CrowdfundInstruction::try_from_slice(&[7]).is_err());
+}
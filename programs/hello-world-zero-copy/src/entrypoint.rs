@@ -0,0 +1,6 @@
+//! Program entrypoint, compiled only when building for the SBF target without
+//! the `no-entrypoint` feature
+
+use crate::process_instruction;
+
+solana_program::entrypoint!(process_instruction);
@@ -0,0 +1,273 @@
+#[cfg(all(target_os = "solana", not(feature = "no-entrypoint")))]
+mod entrypoint;
+
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use bytemuck::{Pod, Zeroable};
+use common::{
+    assert_initialized, assert_owned_by, assert_signer, assert_writable, check_discriminator, debug_msg, emit_event,
+    tagged_instruction, Discriminator, Event, IsInitialized, VersionedInstruction,
+};
+use std::mem::size_of;
+
+// `declare_id!` of the localnet id, or `HELLO_WORLD_ZERO_COPY_PROGRAM_ID` at build time (see build.rs)
+include!(concat!(env!("OUT_DIR"), "/id.rs"));
+
+/// Maximum message length (280 characters, similar to Twitter)
+pub const MAX_MESSAGE_LENGTH: usize = 280;
+
+/// Fixed-size account layout read and written in place with `bytemuck`.
+/// Unlike the borsh `String` layout in the `hello-world` crate, the message lives in
+/// a fixed 280-byte buffer with a length prefix, so updates never
+/// deserialize or re-serialize the whole account.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct ZeroCopyMessageAccount {
+    /// `ZeroCopyMessageAccount::DISCRIMINATOR` once written
+    pub discriminator: [u8; 8],
+    /// Number of times the message has changed
+    pub revision: u64,
+    /// The public key of the last updater
+    pub last_updater: Pubkey,
+    /// Length in bytes of the UTF-8 message stored in `message`
+    pub message_len: u16,
+    /// 1 once the account has been written, 0 before
+    pub is_initialized: u8,
+    /// Explicit padding so the layout has no implicit gaps
+    pub _padding: [u8; 5],
+    /// UTF-8 message bytes; bytes past `message_len` are zero
+    pub message: [u8; MAX_MESSAGE_LENGTH],
+}
+
+impl ZeroCopyMessageAccount {
+    /// Size of the account data in bytes
+    pub const LEN: usize = size_of::<Self>();
+
+    /// The stored message as a string slice; fails on a length past the buffer
+    /// or invalid UTF-8 instead of trusting the stored bytes
+    pub fn message(&self) -> Result<&str, ProgramError> {
+        let bytes = self
+            .message
+            .get(..self.message_len as usize)
+            .ok_or(ProgramError::InvalidAccountData)?;
+        std::str::from_utf8(bytes).map_err(|_| ProgramError::InvalidAccountData)
+    }
+}
+
+impl Discriminator for ZeroCopyMessageAccount {
+    const DISCRIMINATOR: [u8; 8] = *b"zcmsg\0\0\0";
+}
+
+impl IsInitialized for ZeroCopyMessageAccount {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized != 0
+    }
+}
+
+/// Event emitted by `SetMessage`
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Eq)]
+pub struct MessageUpdated {
+    pub account: Pubkey,
+    pub updater: Pubkey,
+    /// The account's revision after the update
+    pub revision: u64,
+    /// Message length in bytes after the update
+    pub new_len: u16,
+}
+
+impl Event for MessageUpdated {
+    const DISCRIMINATOR: [u8; 8] = *b"zcmsgupd";
+}
+
+/// Program entrypoint's implementation
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = ZeroCopyInstruction::unpack(instruction_data)?;
+
+    match instruction {
+        ZeroCopyInstruction::SetMessage { message } => {
+            set_message(program_id, accounts, message)
+        }
+        ZeroCopyInstruction::GetMessage => {
+            get_message(program_id, accounts)
+        }
+    }
+}
+
+/// Instruction enum for the program
+#[derive(Debug)]
+pub enum ZeroCopyInstruction {
+    /// Set a new message
+    /// Accounts expected:
+    /// 0. `[writable]` The account to store the message (`ZeroCopyMessageAccount::LEN` bytes)
+    /// 1. `[signer]` The account of the person setting the message
+    SetMessage { message: String },
+
+    /// Get the current message (read-only)
+    /// Accounts expected:
+    /// 0. `[readable]` The account storing the message
+    GetMessage,
+}
+
+tagged_instruction! {
+    ZeroCopyInstruction {
+        0 => SetMessage { message },
+        1 => GetMessage,
+    }
+}
+
+impl VersionedInstruction for ZeroCopyInstruction {}
+
+/// Borrow the account data as a `ZeroCopyMessageAccount` without copying
+fn load(data: &[u8]) -> Result<&ZeroCopyMessageAccount, ProgramError> {
+    check_discriminator::<ZeroCopyMessageAccount>(data)?;
+    let len = data.len().min(ZeroCopyMessageAccount::LEN);
+    bytemuck::try_from_bytes(&data[..len]).map_err(|_| {
+        msg!("Account data has the wrong size or alignment");
+        ProgramError::AccountDataTooSmall
+    })
+}
+
+/// Mutably borrow the account data as a `ZeroCopyMessageAccount` without copying
+fn load_mut(data: &mut [u8]) -> Result<&mut ZeroCopyMessageAccount, ProgramError> {
+    check_discriminator::<ZeroCopyMessageAccount>(data)?;
+    let len = data.len().min(ZeroCopyMessageAccount::LEN);
+    bytemuck::try_from_bytes_mut(&mut data[..len]).map_err(|_| {
+        msg!("Account data has the wrong size or alignment");
+        ProgramError::AccountDataTooSmall
+    })
+}
+
+/// Set a new message, writing only the changed bytes in place
+fn set_message(program_id: &Pubkey, accounts: &[AccountInfo], new_message: String) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let account = next_account_info(accounts_iter)?;
+    let updater = next_account_info(accounts_iter)?;
+
+    assert_owned_by(account, program_id)?;
+    assert_writable(account)?;
+    assert_signer(updater)?;
+
+    if new_message.is_empty() {
+        msg!("Message cannot be empty");
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    if new_message.len() > MAX_MESSAGE_LENGTH {
+        msg!("Message too long (max {} bytes)", MAX_MESSAGE_LENGTH);
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    let mut data = account.data.borrow_mut();
+    let state = load_mut(&mut data)?;
+
+    let len = new_message.len();
+    state.message[..len].copy_from_slice(new_message.as_bytes());
+    // Zero the tail left over from a longer previous message
+    let old_len = (state.message_len as usize).clamp(len, MAX_MESSAGE_LENGTH);
+    state.message[len..old_len].fill(0);
+    state.message_len = len as u16;
+    state.last_updater = *updater.key;
+    state.discriminator = ZeroCopyMessageAccount::DISCRIMINATOR;
+    state.is_initialized = 1;
+    state.revision = state
+        .revision
+        .checked_add(1)
+        .ok_or(ProgramError::InvalidInstructionData)?;
+
+    debug_msg!("Message updated by {}", updater.key);
+    emit_event!(MessageUpdated {
+        account: *account.key,
+        updater: *updater.key,
+        revision: state.revision,
+        new_len: state.message_len,
+    })?;
+
+    Ok(())
+}
+
+/// Get the current message from the account
+/// Note: In production, reading data should be done off-chain via RPC calls
+/// This instruction is included for demonstration purposes only
+fn get_message(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let account = next_account_info(accounts_iter)?;
+
+    assert_owned_by(account, program_id)?;
+
+    let data = account.data.borrow();
+    let state = load(&data)?;
+
+    assert_initialized(state)?;
+
+    msg!("Current message: {}", state.message()?);
+    msg!("Last updated by: {}", state.last_updater);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_program::clock::Epoch;
+    use test_utils::account::TestAccount;
+
+    #[test]
+    fn test_set_message_in_place() {
+        let program_id = Pubkey::default();
+        let key = Pubkey::new_unique();
+        let mut lamports = 0;
+        // Back the account data with u64s so it is 8-byte aligned like on-chain
+        // data, which a `TestAccount`'s `Vec<u8>` doesn't guarantee
+        let mut backing = vec![0u64; ZeroCopyMessageAccount::LEN / 8];
+        let data: &mut [u8] = bytemuck::cast_slice_mut(&mut backing);
+
+        let account = AccountInfo::new(
+            &key,
+            false,
+            true,
+            &mut lamports,
+            data,
+            &program_id,
+            false,
+            Epoch::default(),
+        );
+
+        let mut updater = TestAccount::new(Pubkey::new_unique()).signer().owner(program_id);
+
+        let accounts = vec![account, updater.info()];
+
+        for message in ["A much longer first message", "Short"] {
+            let instruction = ZeroCopyInstruction::SetMessage {
+                message: message.to_string(),
+            };
+            let instruction_data = instruction.pack();
+            assert!(process_instruction(&program_id, &accounts, &instruction_data).is_ok());
+        }
+
+        let data = accounts[0].data.borrow();
+        let state: &ZeroCopyMessageAccount = bytemuck::from_bytes(&data[..]);
+        assert_eq!(state.message().unwrap(), "Short");
+        assert_eq!(state.revision, 2);
+        assert!(state.message["Short".len()..].iter().all(|b| *b == 0));
+    }
+
+    #[test]
+    fn test_message_len_past_buffer() {
+        let mut state = ZeroCopyMessageAccount::zeroed();
+        state.message_len = MAX_MESSAGE_LENGTH as u16 + 1;
+        assert_eq!(state.message(), Err(ProgramError::InvalidAccountData));
+
+        state.message_len = 2;
+        state.message[..2].copy_from_slice(b"gm");
+        assert_eq!(state.message(), Ok("gm"));
+    }
+}
@@ -0,0 +1,55 @@
+//! Golden bytes of the account layout and every borsh type the program logs
+//! or accepts, against tests/fixtures/layout.txt. A failure here means
+//! deployed messages or existing clients would no longer read the new layout.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use common::{event_data, Discriminator};
+use hello_world_zero_copy::{MessageUpdated, ZeroCopyInstruction, ZeroCopyMessageAccount, MAX_MESSAGE_LENGTH};
+use solana_program::pubkey::Pubkey;
+use test_utils::golden::{read_fixture, GoldenBytes};
+
+const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/layout.txt");
+
+#[test]
+fn test_layouts_match_fixture() {
+    let (account, updater) = (Pubkey::new_from_array([1; 32]), Pubkey::new_from_array([2; 32]));
+    let mut message = [0; MAX_MESSAGE_LENGTH];
+    message[..2].copy_from_slice(b"gm");
+    let state = ZeroCopyMessageAccount {
+        discriminator: ZeroCopyMessageAccount::DISCRIMINATOR,
+        revision: 0x0102_0304_0506_0708,
+        last_updater: updater,
+        message_len: 2,
+        is_initialized: 1,
+        _padding: [0; 5],
+        message,
+    };
+
+    let mut golden = GoldenBytes::new();
+    golden
+        .add("ZeroCopyMessageAccount", bytemuck::bytes_of(&state))
+        .add("MessageUpdated", event_data(&MessageUpdated { account, updater, revision: 1, new_len: 2 }).unwrap())
+        .add(
+            "ZeroCopyInstruction::SetMessage",
+            ZeroCopyInstruction::SetMessage { message: "gm".to_string() }.try_to_vec().unwrap(),
+        )
+        .add("ZeroCopyInstruction::GetMessage", ZeroCopyInstruction::GetMessage.try_to_vec().unwrap());
+    golden.check(FIXTURE);
+}
+
+/// Instruction data in the fixture, as existing clients send it, still
+/// decodes to the variant of that name, and the next tag is unassigned
+#[test]
+fn test_fixture_instructions_decode() {
+    for (name, data) in read_fixture(FIXTURE) {
+        let Some(variant) = name.strip_prefix("ZeroCopyInstruction::") else {
+            continue;
+        };
+        let instruction = ZeroCopyInstruction::try_from_slice(&data).unwrap();
+        assert_eq!(format!("{:?}", instruction).split(' ').next(), Some(variant));
+        assert_eq!(instruction.tag(), data[0]);
+        assert_eq!(instruction.try_to_vec().unwrap(), data);
+    }
+
+    assert!(ZeroCopyInstruction::try_from_slice(&[2]).is_err());
+}
@@ -0,0 +1,378 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+interface IERC20 {
+    function balanceOf(address account) external view returns (uint256);
+}
+
+/**
+ * @title HelloWorld
+ * @dev Store and retrieve a message with tracking of the last updater
+ */
+contract HelloWorld {
+    string private message;
+    address private lastUpdater;
+    uint256 private expiresAtBlock; // 0 = never expires
+    address private immutable author;
+    uint256 private cooldownBlocks; // 0 = no rate limit
+    mapping(address => uint256) private lastUpdateBlock;
+
+    uint256 public constant MAX_WRITERS = 5;
+    address[] private writers; // empty = anyone may update
+
+    uint256 private revision;
+    uint256 private immutable createdAt;
+    uint256 private updatedAt;
+
+    address private gateToken; // address(0) = not gated
+    uint256 private gateMinBalance;
+
+    address private signingAuthority; // address(0) = signed messages disabled
+
+    bytes32 private contentHash; // bytes32(0) = no anchored content
+    string private contentUri;
+
+    address private immutable moderator;
+    bool private hidden;
+
+    struct Reply {
+        string message;
+        address author;
+    }
+
+    Reply[] private replies;
+
+    event MessageUpdated(string newMessage, address updater);
+    event MessageReaped(address reaper);
+    event ReplyPosted(uint256 indexed replyIndex, address author);
+    event CooldownUpdated(uint256 cooldownBlocks);
+    event WriterAdded(address writer);
+    event WriterRemoved(address writer);
+    event TokenGateUpdated(address token, uint256 minBalance);
+    event SigningAuthorityUpdated(address signingAuthority);
+    event ContentAnchored(bytes32 contentHash, string uri, bytes content);
+    event MessageFlagged(address moderator);
+    event MessageUnflagged(address moderator);
+
+    modifier onlyAuthor() {
+        require(msg.sender == author, "Only the author can perform this action");
+        _;
+    }
+
+    modifier onlyModerator() {
+        require(msg.sender == moderator, "Only the moderator can perform this action");
+        _;
+    }
+
+    /**
+     * @param initialModerator Address allowed to hide the message, or address(0) for the deployer
+     */
+    constructor(address initialModerator) {
+        message = "Hello, World!";
+        lastUpdater = msg.sender;
+        author = msg.sender;
+        moderator = initialModerator == address(0) ? msg.sender : initialModerator;
+        revision = 1;
+        createdAt = block.timestamp;
+        updatedAt = block.timestamp;
+    }
+
+    /**
+     * @dev Set the minimum number of blocks between updates by the same address (author only)
+     * @param newCooldownBlocks The cooldown in blocks (0 disables rate limiting)
+     */
+    function setCooldown(uint256 newCooldownBlocks) public onlyAuthor {
+        cooldownBlocks = newCooldownBlocks;
+        emit CooldownUpdated(newCooldownBlocks);
+    }
+
+    /**
+     * @dev Allow an address to update the message (author only)
+     * @param writer The address to add to the allowlist
+     */
+    function addWriter(address writer) public onlyAuthor {
+        require(!isWriter(writer), "Writer is already on the allowlist");
+        require(writers.length < MAX_WRITERS, "Writer allowlist is full");
+        writers.push(writer);
+        emit WriterAdded(writer);
+    }
+
+    /**
+     * @dev Revoke an address's permission to update the message (author only)
+     * @param writer The address to remove from the allowlist
+     */
+    function removeWriter(address writer) public onlyAuthor {
+        for (uint256 i = 0; i < writers.length; i++) {
+            if (writers[i] == writer) {
+                writers[i] = writers[writers.length - 1];
+                writers.pop();
+                emit WriterRemoved(writer);
+                return;
+            }
+        }
+        revert("Writer is not on the allowlist");
+    }
+
+    /**
+     * @dev Require updaters to hold a minimum token balance (author only)
+     * @param token The ERC20 token to check, or address(0) to remove the gate
+     * @param minBalance Minimum balance required to update
+     */
+    function setTokenGate(address token, uint256 minBalance) public onlyAuthor {
+        require(token == address(0) || minBalance > 0, "Minimum balance must be greater than zero");
+        gateToken = token;
+        gateMinBalance = minBalance;
+        emit TokenGateUpdated(token, minBalance);
+    }
+
+    /**
+     * @dev Designate an off-chain key that may author messages (author only)
+     * @param newSigningAuthority The signer address, or address(0) to disable
+     */
+    function setSigningAuthority(address newSigningAuthority) public onlyAuthor {
+        signingAuthority = newSigningAuthority;
+        emit SigningAuthorityUpdated(newSigningAuthority);
+    }
+
+    /**
+     * @dev Hide the message without deleting it (moderator only)
+     */
+    function flagMessage() public onlyModerator {
+        hidden = true;
+        emit MessageFlagged(msg.sender);
+    }
+
+    /**
+     * @dev Show a previously hidden message again (moderator only)
+     */
+    function unflagMessage() public onlyModerator {
+        hidden = false;
+        emit MessageUnflagged(msg.sender);
+    }
+
+    /**
+     * @dev Check whether the moderator has hidden the message
+     * @return True if the message is hidden
+     */
+    function isHidden() public view returns (bool) {
+        return hidden;
+    }
+
+    /**
+     * @dev Check whether an address is on the writer allowlist
+     * @param account The address to check
+     * @return True if the address is an allowed writer
+     */
+    function isWriter(address account) public view returns (bool) {
+        for (uint256 i = 0; i < writers.length; i++) {
+            if (writers[i] == account) {
+                return true;
+            }
+        }
+        return false;
+    }
+
+    /**
+     * @dev Set a new message
+     * @param newMessage The message to store
+     */
+    function setMessage(string memory newMessage) public {
+        _setMessage(newMessage, 0);
+    }
+
+    /**
+     * @dev Set a new message only if nobody else changed it since `expectedRevision`
+     * @param newMessage The message to store
+     * @param expectedRevision The revision the caller last observed
+     */
+    function setMessageIfRevision(string memory newMessage, uint256 expectedRevision) public {
+        require(expectedRevision == revision, "Revision mismatch");
+        _setMessage(newMessage, 0);
+    }
+
+    /**
+     * @dev Set a new message that becomes stale after a number of blocks
+     * @param newMessage The message to store
+     * @param ttlBlocks Number of blocks until the message expires
+     */
+    function setMessageWithExpiry(string memory newMessage, uint256 ttlBlocks) public {
+        require(ttlBlocks > 0, "TTL must be greater than zero");
+        _setMessage(newMessage, block.number + ttlBlocks);
+    }
+
+    /**
+     * @dev Store a message signed off-chain by the signing authority (callable by anyone).
+     * The signature covers this contract, the current revision, and the message,
+     * so it cannot be replayed once the message changes.
+     * @param newMessage The message to store
+     * @param signature 65-byte ECDSA signature over the eth-signed message hash
+     */
+    function setSignedMessage(string memory newMessage, bytes memory signature) public {
+        require(signingAuthority != address(0), "No signing authority configured");
+        require(bytes(newMessage).length > 0, "Message cannot be empty");
+        require(bytes(newMessage).length <= 280, "Message too long (max 280 bytes)");
+        require(signature.length == 65, "Invalid signature length");
+
+        bytes32 digest = keccak256(
+            abi.encodePacked(
+                "\x19Ethereum Signed Message:\n32",
+                keccak256(abi.encodePacked(address(this), revision, newMessage))
+            )
+        );
+        bytes32 r;
+        bytes32 s;
+        uint8 v;
+        assembly {
+            r := mload(add(signature, 32))
+            s := mload(add(signature, 64))
+            v := byte(0, mload(add(signature, 96)))
+        }
+        require(ecrecover(digest, v, r, s) == signingAuthority, "Invalid signature");
+
+        message = newMessage;
+        delete contentHash;
+        delete contentUri;
+        lastUpdater = signingAuthority;
+        expiresAtBlock = 0;
+        revision += 1;
+        updatedAt = block.timestamp;
+        emit MessageUpdated(newMessage, signingAuthority);
+    }
+
+    /**
+     * @dev Replace the message with a hash of content too large to store, plus
+     * a URI where it is hosted; the full content is emitted in the event (author only)
+     * @param content The full content (may exceed 280 bytes)
+     * @param uri Where the content can be fetched
+     */
+    function setAnchoredContent(bytes memory content, string memory uri) public onlyAuthor {
+        require(content.length > 0, "Content cannot be empty");
+        require(bytes(uri).length <= 200, "URI too long (max 200 bytes)");
+        delete message;
+        contentHash = sha256(content);
+        contentUri = uri;
+        lastUpdater = msg.sender;
+        expiresAtBlock = 0;
+        revision += 1;
+        updatedAt = block.timestamp;
+        emit ContentAnchored(contentHash, uri, content);
+    }
+
+    /**
+     * @dev Check a content blob against the anchored hash
+     * @param content The content to check
+     * @return True if the content matches the anchored hash
+     */
+    function verifyContent(bytes memory content) public view returns (bool) {
+        require(contentHash != bytes32(0), "No anchored content");
+        return sha256(content) == contentHash;
+    }
+
+    /**
+     * @dev Get the anchored content hash and URI
+     * @return The content hash and URI
+     */
+    function getContentAnchor() public view returns (bytes32, string memory) {
+        return (contentHash, contentUri);
+    }
+
+    /**
+     * @dev Clear an expired message (callable by anyone)
+     */
+    function reap() public {
+        require(expiresAtBlock != 0, "Message does not expire");
+        require(block.number >= expiresAtBlock, "Message has not expired yet");
+        delete message;
+        expiresAtBlock = 0;
+        revision += 1;
+        updatedAt = block.timestamp;
+        emit MessageReaped(msg.sender);
+    }
+
+    /**
+     * @dev Post a reply to the current message
+     * @param replyMessage The reply to store
+     */
+    function reply(string memory replyMessage) public {
+        require(bytes(replyMessage).length > 0, "Message cannot be empty");
+        require(bytes(replyMessage).length <= 280, "Message too long (max 280 bytes)");
+        replies.push(Reply(replyMessage, msg.sender));
+        emit ReplyPosted(replies.length - 1, msg.sender);
+    }
+
+    /**
+     * @dev Get the current message
+     * @return The stored message
+     */
+    function getMessage() public view returns (string memory) {
+        return message;
+    }
+
+    /**
+     * @dev Get the address of who last updated the message
+     * @return The address of the last updater
+     */
+    function getLastUpdater() public view returns (address) {
+        return lastUpdater;
+    }
+
+    /**
+     * @dev Get the number of replies to the message
+     * @return The reply count
+     */
+    function getReplyCount() public view returns (uint256) {
+        return replies.length;
+    }
+
+    /**
+     * @dev Get a reply by index
+     * @param index The reply index
+     * @return The reply message and its author
+     */
+    function getReply(uint256 index) public view returns (string memory, address) {
+        Reply storage r = replies[index];
+        return (r.message, r.author);
+    }
+
+    /**
+     * @dev Get revision metadata for the message
+     * @return The revision number, creation timestamp, and last update timestamp
+     */
+    function getRevisionInfo() public view returns (uint256, uint256, uint256) {
+        return (revision, createdAt, updatedAt);
+    }
+
+    /**
+     * @dev Check whether the message has expired
+     * @return True if the message has an expiry that has passed
+     */
+    function isStale() public view returns (bool) {
+        return expiresAtBlock != 0 && block.number >= expiresAtBlock;
+    }
+
+    function _setMessage(string memory newMessage, uint256 newExpiresAtBlock) private {
+        require(bytes(newMessage).length > 0, "Message cannot be empty");
+        require(bytes(newMessage).length <= 280, "Message too long (max 280 bytes)");
+        require(
+            writers.length == 0 || msg.sender == author || isWriter(msg.sender),
+            "Updater is not on the writer allowlist"
+        );
+        require(
+            gateToken == address(0) || IERC20(gateToken).balanceOf(msg.sender) >= gateMinBalance,
+            "Token balance too low"
+        );
+        if (cooldownBlocks > 0) {
+            uint256 last = lastUpdateBlock[msg.sender];
+            require(last == 0 || block.number >= last + cooldownBlocks, "Updater is cooling down");
+            lastUpdateBlock[msg.sender] = block.number;
+        }
+        message = newMessage;
+        delete contentHash;
+        delete contentUri;
+        lastUpdater = msg.sender;
+        expiresAtBlock = newExpiresAtBlock;
+        revision += 1;
+        updatedAt = block.timestamp;
+        emit MessageUpdated(newMessage, msg.sender);
+    }
+}
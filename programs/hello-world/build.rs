@@ -0,0 +1,14 @@
+//! Writes the program's `declare_id!` to `OUT_DIR`. The id defaults to the
+//! localnet deployment's, the address of `localnet`'s seeded keypair for
+//! `hello_world`; set `HELLO_WORLD_PROGRAM_ID` when building for devnet or mainnet.
+
+use std::{env, fs, path::Path};
+
+const LOCALNET_ID: &str = "3BGEvjP4xjsBtccd5UjE9ZPyt5F1weVYxPbmQP6Xfgid";
+
+fn main() {
+    println!("cargo:rerun-if-env-changed=HELLO_WORLD_PROGRAM_ID");
+    let id = env::var("HELLO_WORLD_PROGRAM_ID").unwrap_or_else(|_| LOCALNET_ID.to_string());
+    let out = Path::new(&env::var("OUT_DIR").unwrap()).join("id.rs");
+    fs::write(out, format!("solana_program::declare_id!(\"{}\");\n", id)).unwrap();
+}
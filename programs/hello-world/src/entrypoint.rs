@@ -0,0 +1,19 @@
+//! Program entrypoint, compiled only when building for the SBF target without
+//! the `no-entrypoint` feature
+
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, program_error::PrintProgramError, pubkey::Pubkey,
+};
+
+use crate::HelloWorldError;
+
+solana_program::entrypoint!(process_instruction);
+
+/// Log custom errors by name before returning them
+fn process_instruction(program_id: &Pubkey, accounts: &[AccountInfo], instruction_data: &[u8]) -> ProgramResult {
+    if let Err(error) = crate::process_instruction(program_id, accounts, instruction_data) {
+        error.print::<HelloWorldError>();
+        return Err(error);
+    }
+    Ok(())
+}
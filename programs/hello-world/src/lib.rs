@@ -0,0 +1,2219 @@
+#[cfg(all(target_os = "solana", not(feature = "no-entrypoint")))]
+mod entrypoint;
+#[cfg(feature = "zero-copy")]
+pub mod zero_copy;
+
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    ed25519_program,
+    entrypoint::ProgramResult,
+    hash::hash,
+    msg,
+    program::{set_return_data, MAX_RETURN_DATA},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    program_pack::Pack,
+    sysvar::{
+        instructions::{load_current_index_checked, load_instruction_at_checked},
+        Sysvar,
+    },
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use shank::{ShankAccount, ShankInstruction};
+use spl_token::state::Account as TokenAccount;
+use common::{
+    assert_initialized, assert_owned_by, assert_pda, assert_rent_exempt, assert_signer, assert_writable,
+    create_pda_account, debug_msg, emit_event, insert_version_byte, load_account, load_versioned, migrate_account,
+    rent_exempt_minimum, tagged_instruction, transfer_lamports, Discriminator, Event, IsInitialized, Versioned,
+    VersionedInstruction,
+};
+
+// `declare_id!` of the localnet id, or `HELLO_WORLD_PROGRAM_ID` at build time (see build.rs)
+include!(concat!(env!("OUT_DIR"), "/id.rs"));
+
+/// Maximum message length (280 characters, similar to Twitter)
+pub const MAX_MESSAGE_LENGTH: usize = 280;
+
+/// Maximum length of the URI pointing at hash-anchored content
+pub const MAX_URI_LENGTH: usize = 200;
+
+/// Maximum bounty paid to whoever reaps an expired message
+pub const REAP_BOUNTY_LAMPORTS: u64 = 5_000;
+
+/// Maximum number of writers on a message account's allowlist
+pub const MAX_WRITERS: usize = 5;
+
+/// Seed prefix for reply account PDAs: ["reply", parent, reply_index]
+pub const REPLY_SEED: &[u8] = b"reply";
+
+/// Seed prefix for per-updater cooldown PDAs: ["cooldown", message, updater]
+pub const COOLDOWN_SEED: &[u8] = b"cooldown";
+
+/// Size of an `UpdaterCooldown` account
+pub const COOLDOWN_ACCOUNT_SIZE: usize = 8 // discriminator
+    + 8; // last_update_slot
+
+/// Size of a message account holding a message of the maximum length
+pub const MESSAGE_ACCOUNT_SIZE: usize = 8 // discriminator
+    + 1 // version
+    + 1 // is_initialized
+    + 4 + MAX_MESSAGE_LENGTH // message
+    + 32 // last_updater
+    + 1 + 8 // expires_at_slot
+    + 1 + 32 // parent
+    + 8 // reply_count
+    + 32 // author
+    + 8 // cooldown_slots
+    + 4 + 32 * MAX_WRITERS // writers
+    + 8 // revision
+    + 8 // created_at
+    + 8 // updated_at
+    + 1 + 32 + 8 // token_gate
+    + 1 + 32 // signing_authority
+    + 1 + 32 + 4 + 4 + MAX_URI_LENGTH // content_anchor
+    + 32 // moderator
+    + 1; // hidden
+
+pub use errors::HelloWorldError;
+
+/// Define the type of state stored in accounts
+#[derive(BorshSerialize, BorshDeserialize, Debug, ShankAccount)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HelloWorldAccount {
+    /// `HelloWorldAccount::DISCRIMINATOR` once written
+    pub discriminator: [u8; 8],
+    /// `HelloWorldAccount::VERSION` once written
+    pub version: u8,
+    /// Flag to track if the account has been initialized
+    pub is_initialized: bool,
+    /// The stored message
+    pub message: String,
+    /// The public key of the last updater
+    pub last_updater: Pubkey,
+    /// Slot at which the message becomes stale (None = never expires)
+    pub expires_at_slot: Option<u64>,
+    /// The message this one replies to (None for top-level messages)
+    pub parent: Option<Pubkey>,
+    /// Number of replies created for this message
+    pub reply_count: u64,
+    /// The public key that first initialized the message account
+    pub author: Pubkey,
+    /// Minimum number of slots between updates by the same updater (0 = no limit)
+    pub cooldown_slots: u64,
+    /// Pubkeys besides the author allowed to update the message
+    /// (empty = anyone may update)
+    pub writers: Vec<Pubkey>,
+    /// Number of times the message has changed, for optimistic concurrency
+    pub revision: u64,
+    /// Unix timestamp of the first write
+    pub created_at: i64,
+    /// Unix timestamp of the most recent write
+    pub updated_at: i64,
+    /// If set, updaters must hold at least `min_balance` of `mint`
+    pub token_gate: Option<TokenGate>,
+    /// Off-chain ed25519 key allowed to author messages via `SetSignedMessage`
+    pub signing_authority: Option<Pubkey>,
+    /// Hash and location of content too large to store on-chain (`message`
+    /// is empty while set)
+    pub content_anchor: Option<ContentAnchor>,
+    /// Key allowed to hide the message, fixed at initialization
+    pub moderator: Pubkey,
+    /// Set by the moderator; the content is kept but not displayed
+    pub hidden: bool,
+}
+
+impl HelloWorldAccount {
+    /// Serialized size of this state. Message accounts are allocated at
+    /// `MESSAGE_ACCOUNT_SIZE`, the space of the largest valid state.
+    pub fn space(&self) -> usize {
+        8 // discriminator
+            + 1 // version
+            + 1 // is_initialized
+            + 4 + self.message.len() // message
+            + 32 // last_updater
+            + 1 + self.expires_at_slot.map_or(0, |_| 8) // expires_at_slot
+            + 1 + self.parent.map_or(0, |_| 32) // parent
+            + 8 // reply_count
+            + 32 // author
+            + 8 // cooldown_slots
+            + 4 + 32 * self.writers.len() // writers
+            + 8 // revision
+            + 8 // created_at
+            + 8 // updated_at
+            + 1 + self.token_gate.as_ref().map_or(0, |_| 32 + 8) // token_gate
+            + 1 + self.signing_authority.map_or(0, |_| 32) // signing_authority
+            + 1 + self.content_anchor.as_ref().map_or(0, |anchor| 32 + 4 + 4 + anchor.uri.len()) // content_anchor
+            + 32 // moderator
+            + 1 // hidden
+    }
+}
+
+impl Discriminator for HelloWorldAccount {
+    const DISCRIMINATOR: [u8; 8] = *b"message\0";
+}
+
+impl Versioned for HelloWorldAccount {
+    const VERSION: u8 = 2;
+    const SPACE: usize = MESSAGE_ACCOUNT_SIZE;
+
+    fn migrate_in_place(data: &mut [u8]) -> ProgramResult {
+        match data[8] {
+            // Unversioned: `is_initialized` where the version byte goes
+            1 => insert_version_byte(data, Self::VERSION),
+            version => {
+                msg!("Unknown message layout version {}", version);
+                Err(ProgramError::InvalidAccountData)
+            }
+        }
+    }
+}
+
+impl IsInitialized for HelloWorldAccount {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+/// On-chain commitment to content stored off-chain
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ContentAnchor {
+    /// `sha256` of the full content
+    pub content_hash: [u8; 32],
+    /// Length of the full content in bytes
+    pub content_len: u32,
+    /// Where the full content can be fetched (e.g. `ipfs://`, `https://`)
+    pub uri: String,
+}
+
+/// Token-gating configuration for a message account
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TokenGate {
+    /// The SPL token mint updaters must hold
+    pub mint: Pubkey,
+    /// Minimum token balance (in base units) required to update
+    pub min_balance: u64,
+}
+
+/// Per-updater rate-limit record, stored at ["cooldown", message, updater]
+#[derive(BorshSerialize, BorshDeserialize, Debug, ShankAccount)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct UpdaterCooldown {
+    /// `UpdaterCooldown::DISCRIMINATOR`
+    pub discriminator: [u8; 8],
+    /// Slot of this updater's most recent SetMessage
+    pub last_update_slot: u64,
+}
+
+impl Discriminator for UpdaterCooldown {
+    const DISCRIMINATOR: [u8; 8] = *b"cooldown";
+}
+
+/// Event emitted whenever a message's content changes, so indexers can
+/// build change feeds without on-chain history
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Eq)]
+pub struct MessageUpdated {
+    /// The message account that changed
+    pub account: Pubkey,
+    /// The signer that made the change
+    pub updater: Pubkey,
+    /// Message length in bytes before the change
+    pub old_len: u32,
+    /// Message length in bytes after the change
+    pub new_len: u32,
+    /// Slot at which the change happened
+    pub slot: u64,
+}
+
+impl Event for MessageUpdated {
+    const DISCRIMINATOR: [u8; 8] = *b"msgupdtd";
+}
+
+/// Event emitted when content is anchored, carrying the full content so
+/// indexers can serve it alongside the on-chain hash
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Eq)]
+pub struct ContentAnchored {
+    /// The message account the content is anchored to
+    pub account: Pubkey,
+    /// `sha256` of `content`
+    pub content_hash: [u8; 32],
+    /// Where the content can be fetched
+    pub uri: String,
+    /// The full content
+    pub content: Vec<u8>,
+}
+
+impl Event for ContentAnchored {
+    const DISCRIMINATOR: [u8; 8] = *b"anchored";
+}
+
+/// Event emitted when an expired message is reaped
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Eq)]
+pub struct MessageReaped {
+    pub account: Pubkey,
+    pub reaper: Pubkey,
+    /// Lamports paid to the reaper
+    pub bounty: u64,
+}
+
+impl Event for MessageReaped {
+    const DISCRIMINATOR: [u8; 8] = *b"reaped\0\0";
+}
+
+/// Event emitted when a reply is created
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Eq)]
+pub struct ReplyPosted {
+    pub parent: Pubkey,
+    pub reply: Pubkey,
+    /// The reply's index among the parent's replies
+    pub reply_index: u64,
+    pub author: Pubkey,
+}
+
+impl Event for ReplyPosted {
+    const DISCRIMINATOR: [u8; 8] = *b"replied\0";
+}
+
+/// Event emitted by `SetCooldown`
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Eq)]
+pub struct CooldownUpdated {
+    pub account: Pubkey,
+    pub cooldown_slots: u64,
+}
+
+impl Event for CooldownUpdated {
+    const DISCRIMINATOR: [u8; 8] = *b"cooldnup";
+}
+
+/// Event emitted by `AddWriter`
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Eq)]
+pub struct WriterAdded {
+    pub account: Pubkey,
+    pub writer: Pubkey,
+}
+
+impl Event for WriterAdded {
+    const DISCRIMINATOR: [u8; 8] = *b"wradded\0";
+}
+
+/// Event emitted by `RemoveWriter`
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Eq)]
+pub struct WriterRemoved {
+    pub account: Pubkey,
+    pub writer: Pubkey,
+}
+
+impl Event for WriterRemoved {
+    const DISCRIMINATOR: [u8; 8] = *b"wrremovd";
+}
+
+/// Event emitted by `SetTokenGate`; `None` means the gate was removed
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Eq)]
+pub struct TokenGateUpdated {
+    pub account: Pubkey,
+    pub token_gate: Option<TokenGate>,
+}
+
+impl Event for TokenGateUpdated {
+    const DISCRIMINATOR: [u8; 8] = *b"gateupdt";
+}
+
+/// Event emitted by `SetSigningAuthority`; `None` means the authority was removed
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Eq)]
+pub struct SigningAuthorityUpdated {
+    pub account: Pubkey,
+    pub signing_authority: Option<Pubkey>,
+}
+
+impl Event for SigningAuthorityUpdated {
+    const DISCRIMINATOR: [u8; 8] = *b"authupdt";
+}
+
+/// Event emitted when the moderator hides a message
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Eq)]
+pub struct MessageFlagged {
+    pub account: Pubkey,
+    pub moderator: Pubkey,
+}
+
+impl Event for MessageFlagged {
+    const DISCRIMINATOR: [u8; 8] = *b"flagged\0";
+}
+
+/// Event emitted when the moderator shows a hidden message again
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Eq)]
+pub struct MessageUnflagged {
+    pub account: Pubkey,
+    pub moderator: Pubkey,
+}
+
+impl Event for MessageUnflagged {
+    const DISCRIMINATOR: [u8; 8] = *b"unflaggd";
+}
+
+/// Program entrypoint's implementation
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    debug_msg!("Hello World Solana program entrypoint");
+
+    // Deserialize the instruction data to get the new message
+    let instruction = HelloWorldInstruction::unpack(instruction_data)?;
+
+    match instruction {
+        HelloWorldInstruction::SetMessage {
+            message,
+            ttl_slots,
+            expected_revision,
+            moderator,
+        } => {
+            set_message(
+                program_id,
+                accounts,
+                message,
+                ttl_slots,
+                expected_revision,
+                moderator,
+            )
+        }
+        HelloWorldInstruction::GetMessage => {
+            get_message(accounts)
+        }
+        HelloWorldInstruction::Reap => {
+            reap(program_id, accounts)
+        }
+        HelloWorldInstruction::Reply { message } => {
+            reply(program_id, accounts, message)
+        }
+        HelloWorldInstruction::SetCooldown { cooldown_slots } => {
+            set_cooldown(program_id, accounts, cooldown_slots)
+        }
+        HelloWorldInstruction::AddWriter { writer_key } => {
+            add_writer(program_id, accounts, writer_key)
+        }
+        HelloWorldInstruction::RemoveWriter { writer_key } => {
+            remove_writer(program_id, accounts, writer_key)
+        }
+        HelloWorldInstruction::SetTokenGate { token_gate } => {
+            set_token_gate(program_id, accounts, token_gate)
+        }
+        HelloWorldInstruction::SetSigningAuthority { signing_authority } => {
+            set_signing_authority(program_id, accounts, signing_authority)
+        }
+        HelloWorldInstruction::SetSignedMessage { message } => {
+            set_signed_message(program_id, accounts, message)
+        }
+        HelloWorldInstruction::SetAnchoredContent { content, uri } => {
+            set_anchored_content(program_id, accounts, content, uri)
+        }
+        HelloWorldInstruction::VerifyContent { content } => {
+            verify_content(program_id, accounts, content)
+        }
+        HelloWorldInstruction::FlagMessage => {
+            set_hidden(program_id, accounts, true)
+        }
+        HelloWorldInstruction::UnflagMessage => {
+            set_hidden(program_id, accounts, false)
+        }
+        HelloWorldInstruction::Migrate => migrate(program_id, accounts),
+    }
+}
+
+/// Instruction enum for the program
+#[derive(Debug, ShankInstruction)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum HelloWorldInstruction {
+    /// Set a new message, optionally expiring `ttl_slots` slots from now.
+    /// If `expected_revision` is set, the update is rejected unless it
+    /// matches the stored revision (optimistic concurrency). `moderator` may
+    /// only be given by the call that initializes the account and defaults to
+    /// the updater. The cooldown accounts are only passed when a cooldown is
+    /// configured, and the token account only when the message is token gated
+    /// (right after the updater when there is no cooldown).
+    #[account(0, writable, name = "message_account", desc = "The account to store the message")]
+    #[account(1, signer, name = "updater", desc = "The person setting the message; writable if a cooldown is set")]
+    #[account(2, writable, optional, name = "cooldown", desc = "The updater's cooldown PDA")]
+    #[account(3, optional, name = "system_program", desc = "The system program")]
+    #[account(4, optional, name = "token_account", desc = "The updater's token account for the gate mint")]
+    SetMessage {
+        message: String,
+        ttl_slots: Option<u64>,
+        expected_revision: Option<u64>,
+        moderator: Option<Pubkey>,
+    },
+
+    /// Get the current message (read-only); the serialized `HelloWorldAccount`
+    /// is returned as return data
+    #[account(0, name = "message_account", desc = "The account storing the message")]
+    GetMessage,
+
+    /// Clear an expired message and pay a bounty to the caller (permissionless)
+    #[account(0, writable, name = "message_account", desc = "The account storing the message")]
+    #[account(1, writable, signer, name = "caller", desc = "The caller receiving the bounty")]
+    Reap,
+
+    /// Create a reply message PDA linked to a parent message
+    #[account(0, writable, name = "parent", desc = "The parent message account")]
+    #[account(1, writable, name = "reply", desc = "The reply PDA: [\"reply\", parent, parent.reply_count]")]
+    #[account(2, writable, signer, name = "author", desc = "The author, who pays for the reply account")]
+    #[account(3, name = "system_program", desc = "The system program")]
+    Reply { message: String },
+
+    /// Configure the per-updater cooldown between updates (author only)
+    #[account(0, writable, name = "message_account", desc = "The account storing the message")]
+    #[account(1, signer, name = "author", desc = "The author of the message account")]
+    SetCooldown { cooldown_slots: u64 },
+
+    /// Allow a pubkey to update the message (author only)
+    #[account(0, writable, name = "message_account", desc = "The account storing the message")]
+    #[account(1, signer, name = "author", desc = "The author of the message account")]
+    AddWriter { writer_key: Pubkey },
+
+    /// Revoke a pubkey's permission to update the message (author only)
+    #[account(0, writable, name = "message_account", desc = "The account storing the message")]
+    #[account(1, signer, name = "author", desc = "The author of the message account")]
+    RemoveWriter { writer_key: Pubkey },
+
+    /// Require updaters to hold a minimum token balance, or remove the
+    /// requirement with `None` (author only)
+    #[account(0, writable, name = "message_account", desc = "The account storing the message")]
+    #[account(1, signer, name = "author", desc = "The author of the message account")]
+    SetTokenGate { token_gate: Option<TokenGate> },
+
+    /// Designate an off-chain ed25519 key that may author messages, or remove
+    /// it with `None` (author only)
+    #[account(0, writable, name = "message_account", desc = "The account storing the message")]
+    #[account(1, signer, name = "author", desc = "The author of the message account")]
+    SetSigningAuthority { signing_authority: Option<Pubkey> },
+
+    /// Store a message signed off-chain by the signing authority. The
+    /// previous instruction in the transaction must be an ed25519 program
+    /// instruction verifying the authority's signature over
+    /// `signed_message_bytes(account, revision, message)`. Anyone may submit it.
+    #[account(0, writable, name = "message_account", desc = "The account storing the message")]
+    #[account(1, name = "instructions", desc = "The instructions sysvar")]
+    SetSignedMessage { message: String },
+
+    /// Replace the message with a `sha256` commitment to `content` (which may
+    /// exceed `MAX_MESSAGE_LENGTH`) and a URI where it is hosted; the full
+    /// content is emitted in a `ContentAnchored` event (author only)
+    #[account(0, writable, name = "message_account", desc = "The account storing the message")]
+    #[account(1, signer, name = "author", desc = "The author of the message account")]
+    SetAnchoredContent { content: Vec<u8>, uri: String },
+
+    /// Check that `content` hashes to the anchored content hash; fails with
+    /// `ContentHashMismatch` otherwise
+    #[account(0, name = "message_account", desc = "The account storing the message")]
+    VerifyContent { content: Vec<u8> },
+
+    /// Hide the message without deleting it (moderator only)
+    #[account(0, writable, name = "message_account", desc = "The account storing the message")]
+    #[account(1, signer, name = "moderator", desc = "The moderator of the message account")]
+    FlagMessage,
+
+    /// Show a previously hidden message again (moderator only)
+    #[account(0, writable, name = "message_account", desc = "The account storing the message")]
+    #[account(1, signer, name = "moderator", desc = "The moderator of the message account")]
+    UnflagMessage,
+
+    /// Grow a message written by an older program version to the current
+    /// layout (permissionless)
+    #[account(0, writable, name = "message_account", desc = "The account storing the message")]
+    #[account(1, writable, signer, name = "payer", desc = "Pays the rent for the larger account")]
+    #[account(2, name = "system_program", desc = "The system program")]
+    Migrate,
+}
+
+tagged_instruction! {
+    HelloWorldInstruction {
+        0 => SetMessage { message, ttl_slots, expected_revision, moderator },
+        1 => GetMessage,
+        2 => Reap,
+        3 => Reply { message },
+        4 => SetCooldown { cooldown_slots },
+        5 => AddWriter { writer_key },
+        6 => RemoveWriter { writer_key },
+        7 => SetTokenGate { token_gate },
+        8 => SetSigningAuthority { signing_authority },
+        9 => SetSignedMessage { message },
+        10 => SetAnchoredContent { content, uri },
+        11 => VerifyContent { content },
+        12 => FlagMessage,
+        13 => UnflagMessage,
+        14 => Migrate,
+    }
+}
+
+impl VersionedInstruction for HelloWorldInstruction {}
+
+/// Bytes the signing authority signs for `SetSignedMessage`: the message
+/// account, its current revision (which prevents replays), and the message
+pub fn signed_message_bytes(account: &Pubkey, revision: u64, message: &str) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(32 + 8 + message.len());
+    bytes.extend_from_slice(account.as_ref());
+    bytes.extend_from_slice(&revision.to_le_bytes());
+    bytes.extend_from_slice(message.as_bytes());
+    bytes
+}
+
+/// Set a new message in the account
+fn set_message(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    new_message: String,
+    ttl_slots: Option<u64>,
+    expected_revision: Option<u64>,
+    moderator: Option<Pubkey>,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let account = next_account_info(accounts_iter)?;
+    let updater = next_account_info(accounts_iter)?;
+
+    assert_owned_by(account, program_id)?;
+    assert_writable(account)?;
+    assert_signer(updater)?;
+
+    // Validate message length
+    if new_message.is_empty() {
+        msg!("Message cannot be empty");
+        return Err(HelloWorldError::MessageEmpty.into());
+    }
+    if new_message.len() > MAX_MESSAGE_LENGTH {
+        msg!("Message too long (max {} bytes)", MAX_MESSAGE_LENGTH);
+        return Err(HelloWorldError::MessageTooLong.into());
+    }
+
+    let clock = Clock::get()?;
+
+    // Compute the expiry slot, if any
+    let expires_at_slot = match ttl_slots {
+        Some(0) => {
+            msg!("TTL must be greater than zero");
+            return Err(HelloWorldError::InvalidTtl.into());
+        }
+        Some(ttl) => Some(
+            clock
+                .slot
+                .checked_add(ttl)
+                .ok_or(HelloWorldError::Overflow)?,
+        ),
+        None => None,
+    };
+
+    // Create or update the account data, keeping the reply links intact
+    let mut hello_world_account: HelloWorldAccount = load_versioned(&account.data.borrow())?;
+    if !hello_world_account.is_initialized {
+        assert_rent_exempt(account)?;
+        hello_world_account.discriminator = HelloWorldAccount::DISCRIMINATOR;
+        hello_world_account.version = HelloWorldAccount::VERSION;
+        hello_world_account.author = *updater.key;
+        hello_world_account.moderator = moderator.unwrap_or(*updater.key);
+        hello_world_account.created_at = clock.unix_timestamp;
+    } else if moderator.is_some() {
+        msg!("Moderator can only be set at initialization");
+        return Err(HelloWorldError::ModeratorAlreadySet.into());
+    }
+
+    // Reject the update if someone else changed the message in the meantime
+    if let Some(expected_revision) = expected_revision {
+        if expected_revision != hello_world_account.revision {
+            msg!(
+                "Revision mismatch: expected {}, found {}",
+                expected_revision,
+                hello_world_account.revision
+            );
+            return Err(HelloWorldError::RevisionMismatch.into());
+        }
+    }
+
+    // Once an allowlist exists, only the author and listed writers may update
+    if !hello_world_account.writers.is_empty()
+        && hello_world_account.author != *updater.key
+        && !hello_world_account.writers.contains(updater.key)
+    {
+        msg!("Updater is not on the writer allowlist");
+        return Err(HelloWorldError::NotWriter.into());
+    }
+
+    // Optional accounts, in order: the cooldown PDA and system program (if a
+    // cooldown is configured), then the updater's token account (if gated)
+    let cooldown_accounts = if hello_world_account.cooldown_slots > 0 {
+        Some((next_account_info(accounts_iter)?, next_account_info(accounts_iter)?))
+    } else {
+        None
+    };
+
+    // Token-gated messages require the updater to hold enough of the gate mint
+    if let Some(token_gate) = &hello_world_account.token_gate {
+        let token_account = next_account_info(accounts_iter)?;
+        verify_token_gate(token_gate, token_account, updater.key)?;
+    }
+
+    // Rate-limit each updater individually when a cooldown is configured
+    if let Some((cooldown_account, system_program)) = cooldown_accounts {
+        enforce_cooldown(
+            program_id,
+            account,
+            updater,
+            cooldown_account,
+            system_program,
+            hello_world_account.cooldown_slots,
+        )?;
+    }
+
+    let old_len = hello_world_account.message.len();
+
+    hello_world_account.is_initialized = true;
+    hello_world_account.message = new_message.clone();
+    hello_world_account.content_anchor = None;
+    hello_world_account.last_updater = *updater.key;
+    hello_world_account.expires_at_slot = expires_at_slot;
+    hello_world_account.revision = hello_world_account
+        .revision
+        .checked_add(1)
+        .ok_or(HelloWorldError::Overflow)?;
+    hello_world_account.updated_at = clock.unix_timestamp;
+
+    let required_size = hello_world_account.space();
+    if account.data_len() < required_size {
+        msg!("Account data size insufficient: {} < {}", account.data_len(), required_size);
+        return Err(ProgramError::AccountDataTooSmall);
+    }
+
+    // Serialize and save the data, clearing what a longer message left behind
+    account.data.borrow_mut().fill(0);
+    hello_world_account.serialize(&mut &mut account.data.borrow_mut()[..])?;
+
+    emit_event!(MessageUpdated {
+        account: *account.key,
+        updater: *updater.key,
+        old_len: old_len as u32,
+        new_len: new_message.len() as u32,
+        slot: clock.slot,
+    })?;
+
+    debug_msg!("Message updated to: {}", new_message);
+    debug_msg!("Updated by: {}", updater.key);
+    debug_msg!("Revision: {}", hello_world_account.revision);
+
+    Ok(())
+}
+
+/// Verify that `token_account` is an SPL token account of the gate mint,
+/// owned by the updater, holding at least the minimum balance
+fn verify_token_gate(
+    token_gate: &TokenGate,
+    token_account: &AccountInfo,
+    updater: &Pubkey,
+) -> ProgramResult {
+    if token_account.owner != &spl_token::id() {
+        msg!("Token account is not owned by the SPL Token program");
+        return Err(HelloWorldError::InvalidTokenAccount.into());
+    }
+
+    let token = TokenAccount::unpack(&token_account.data.borrow())?;
+
+    if token.mint != token_gate.mint || token.owner != *updater {
+        msg!("Token account must hold the gate mint and belong to the updater");
+        return Err(HelloWorldError::InvalidTokenAccount.into());
+    }
+
+    if token.amount < token_gate.min_balance {
+        msg!(
+            "Token balance too low: {} < {}",
+            token.amount,
+            token_gate.min_balance
+        );
+        return Err(HelloWorldError::InsufficientTokenBalance.into());
+    }
+
+    Ok(())
+}
+
+/// Check and record the updater's last update slot in its cooldown PDA,
+/// creating the PDA on the updater's first rate-limited update
+fn enforce_cooldown<'a>(
+    program_id: &Pubkey,
+    account: &AccountInfo<'a>,
+    updater: &AccountInfo<'a>,
+    cooldown_account: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    cooldown_slots: u64,
+) -> ProgramResult {
+    let bump = assert_pda(
+        cooldown_account,
+        &[COOLDOWN_SEED, account.key.as_ref(), updater.key.as_ref()],
+        &crate::ID,
+    )?;
+
+    let clock = Clock::get()?;
+
+    if cooldown_account.owner == program_id {
+        let cooldown: UpdaterCooldown = load_account(&cooldown_account.data.borrow())?;
+        let next_allowed_slot = cooldown.last_update_slot.saturating_add(cooldown_slots);
+        if clock.slot < next_allowed_slot {
+            msg!("Updater is cooling down until slot {}", next_allowed_slot);
+            return Err(HelloWorldError::CoolingDown.into());
+        }
+    } else {
+        create_pda_account(
+            cooldown_account,
+            updater,
+            system_program,
+            COOLDOWN_ACCOUNT_SIZE,
+            program_id,
+            &[COOLDOWN_SEED, account.key.as_ref(), updater.key.as_ref(), &[bump]],
+        )?;
+    }
+
+    let cooldown = UpdaterCooldown {
+        discriminator: UpdaterCooldown::DISCRIMINATOR,
+        last_update_slot: clock.slot,
+    };
+    cooldown.serialize(&mut &mut cooldown_account.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+/// Get the current message from the account
+/// The serialized account state is returned via `set_return_data` (truncated
+/// to `MAX_RETURN_DATA` bytes) so simulations and CPI callers can decode it
+/// Note: In production, reading data should be done off-chain via RPC calls
+/// This instruction is included for demonstration purposes only
+fn get_message(accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let account = next_account_info(accounts_iter)?;
+
+    // Deserialize the account data (the account may be larger than the data)
+    let hello_world_account: HelloWorldAccount = load_versioned(&account.data.borrow())?;
+
+    assert_initialized(&hello_world_account)?;
+
+    if let Some(expires_at_slot) = hello_world_account.expires_at_slot {
+        let clock = Clock::get()?;
+        if clock.slot >= expires_at_slot {
+            msg!("Message is stale (expired at slot {})", expires_at_slot);
+        }
+    }
+
+    if hello_world_account.hidden {
+        msg!("Message hidden by moderator {}", hello_world_account.moderator);
+    } else {
+        msg!("Current message: {}", hello_world_account.message);
+    }
+    msg!("Last updated by: {}", hello_world_account.last_updater);
+    msg!(
+        "Revision {} (created {}, updated {})",
+        hello_world_account.revision,
+        hello_world_account.created_at,
+        hello_world_account.updated_at
+    );
+
+    let return_data = hello_world_account.try_to_vec()?;
+    set_return_data(&return_data[..return_data.len().min(MAX_RETURN_DATA)]);
+
+    Ok(())
+}
+
+/// Clear an expired message and pay the caller a bounty from the account's
+/// lamports. Only lamports above the rent-exempt minimum are paid out.
+fn reap(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let account = next_account_info(accounts_iter)?;
+    let caller = next_account_info(accounts_iter)?;
+
+    assert_owned_by(account, program_id)?;
+    assert_writable(account)?;
+    assert_signer(caller)?;
+
+    let mut hello_world_account: HelloWorldAccount = load_versioned(&account.data.borrow())?;
+
+    assert_initialized(&hello_world_account)?;
+
+    let expires_at_slot = hello_world_account.expires_at_slot.ok_or_else(|| {
+        msg!("Message does not expire");
+        HelloWorldError::MessageDoesNotExpire
+    })?;
+
+    let clock = Clock::get()?;
+    if clock.slot < expires_at_slot {
+        msg!("Message has not expired yet");
+        return Err(HelloWorldError::MessageNotExpired.into());
+    }
+
+    // Clear the message, zeroing the old bytes so no stale data remains
+    let old_len = hello_world_account.message.len();
+    hello_world_account.message = String::new();
+    hello_world_account.expires_at_slot = None;
+    hello_world_account.revision = hello_world_account
+        .revision
+        .checked_add(1)
+        .ok_or(HelloWorldError::Overflow)?;
+    hello_world_account.updated_at = clock.unix_timestamp;
+    account.data.borrow_mut().fill(0);
+    hello_world_account.serialize(&mut &mut account.data.borrow_mut()[..])?;
+
+    emit_event!(MessageUpdated {
+        account: *account.key,
+        updater: *caller.key,
+        old_len: old_len as u32,
+        new_len: 0,
+        slot: clock.slot,
+    })?;
+
+    // Pay the bounty without dropping the account below rent exemption
+    let bounty = account
+        .lamports()
+        .saturating_sub(rent_exempt_minimum(account.data_len())?)
+        .min(REAP_BOUNTY_LAMPORTS);
+
+    transfer_lamports(account, caller, bounty)?;
+
+    debug_msg!("Message reaped by {}, bounty: {} lamports", caller.key, bounty);
+    emit_event!(MessageReaped { account: *account.key, reaper: *caller.key, bounty })?;
+
+    Ok(())
+}
+
+/// Create a reply account as a PDA of its parent and bump the parent's
+/// reply counter
+fn reply(program_id: &Pubkey, accounts: &[AccountInfo], message: String) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let parent_account = next_account_info(accounts_iter)?;
+    let reply_account = next_account_info(accounts_iter)?;
+    let author = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    assert_owned_by(parent_account, program_id)?;
+
+    assert_writable(parent_account)?;
+    assert_writable(reply_account)?;
+
+    assert_signer(author)?;
+
+    if message.is_empty() {
+        msg!("Message cannot be empty");
+        return Err(HelloWorldError::MessageEmpty.into());
+    }
+    if message.len() > MAX_MESSAGE_LENGTH {
+        msg!("Message too long (max {} bytes)", MAX_MESSAGE_LENGTH);
+        return Err(HelloWorldError::MessageTooLong.into());
+    }
+
+    let mut parent: HelloWorldAccount = load_versioned(&parent_account.data.borrow())?;
+
+    assert_initialized(&parent)?;
+
+    // The reply index makes every reply address unique and discoverable
+    let reply_index = parent.reply_count.to_le_bytes();
+    let bump = assert_pda(reply_account, &[REPLY_SEED, parent_account.key.as_ref(), &reply_index], &crate::ID)?;
+
+    let clock = Clock::get()?;
+    create_pda_account(
+        reply_account,
+        author,
+        system_program,
+        MESSAGE_ACCOUNT_SIZE,
+        program_id,
+        &[REPLY_SEED, parent_account.key.as_ref(), &reply_index, &[bump]],
+    )?;
+
+    let child = HelloWorldAccount {
+        discriminator: HelloWorldAccount::DISCRIMINATOR,
+        version: HelloWorldAccount::VERSION,
+        is_initialized: true,
+        message,
+        last_updater: *author.key,
+        expires_at_slot: None,
+        parent: Some(*parent_account.key),
+        reply_count: 0,
+        author: *author.key,
+        cooldown_slots: 0,
+        writers: Vec::new(),
+        revision: 1,
+        created_at: clock.unix_timestamp,
+        updated_at: clock.unix_timestamp,
+        token_gate: None,
+        signing_authority: None,
+        content_anchor: None,
+        moderator: parent.moderator,
+        hidden: false,
+    };
+    child.serialize(&mut &mut reply_account.data.borrow_mut()[..])?;
+
+    emit_event!(MessageUpdated {
+        account: *reply_account.key,
+        updater: *author.key,
+        old_len: 0,
+        new_len: child.message.len() as u32,
+        slot: clock.slot,
+    })?;
+
+    parent.reply_count = parent
+        .reply_count
+        .checked_add(1)
+        .ok_or(HelloWorldError::Overflow)?;
+    parent.serialize(&mut &mut parent_account.data.borrow_mut()[..])?;
+
+    debug_msg!("Reply {} created for {}", reply_account.key, parent_account.key);
+    emit_event!(ReplyPosted {
+        parent: *parent_account.key,
+        reply: *reply_account.key,
+        reply_index: parent.reply_count - 1,
+        author: *author.key,
+    })?;
+
+    Ok(())
+}
+
+/// Configure the per-updater cooldown for a message account
+fn set_cooldown(program_id: &Pubkey, accounts: &[AccountInfo], cooldown_slots: u64) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let account = next_account_info(accounts_iter)?;
+    let author = next_account_info(accounts_iter)?;
+
+    let mut hello_world_account = load_for_author(program_id, account, author)?;
+
+    hello_world_account.cooldown_slots = cooldown_slots;
+    hello_world_account.serialize(&mut &mut account.data.borrow_mut()[..])?;
+
+    debug_msg!("Cooldown set to {} slots", cooldown_slots);
+    emit_event!(CooldownUpdated { account: *account.key, cooldown_slots })?;
+
+    Ok(())
+}
+
+/// Add a pubkey to the message account's writer allowlist
+fn add_writer(program_id: &Pubkey, accounts: &[AccountInfo], writer: Pubkey) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let account = next_account_info(accounts_iter)?;
+    let author = next_account_info(accounts_iter)?;
+
+    let mut hello_world_account = load_for_author(program_id, account, author)?;
+
+    if hello_world_account.writers.contains(&writer) {
+        msg!("Writer is already on the allowlist");
+        return Err(HelloWorldError::WriterAlreadyListed.into());
+    }
+
+    if hello_world_account.writers.len() >= MAX_WRITERS {
+        msg!("Writer allowlist is full (max {})", MAX_WRITERS);
+        return Err(HelloWorldError::WriterListFull.into());
+    }
+
+    hello_world_account.writers.push(writer);
+    hello_world_account.serialize(&mut &mut account.data.borrow_mut()[..])?;
+
+    debug_msg!("Writer added: {}", writer);
+    emit_event!(WriterAdded { account: *account.key, writer })?;
+
+    Ok(())
+}
+
+/// Remove a pubkey from the message account's writer allowlist
+fn remove_writer(program_id: &Pubkey, accounts: &[AccountInfo], writer: Pubkey) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let account = next_account_info(accounts_iter)?;
+    let author = next_account_info(accounts_iter)?;
+
+    let mut hello_world_account = load_for_author(program_id, account, author)?;
+
+    let index = hello_world_account
+        .writers
+        .iter()
+        .position(|w| *w == writer)
+        .ok_or_else(|| {
+            msg!("Writer is not on the allowlist");
+            HelloWorldError::WriterNotListed
+        })?;
+    hello_world_account.writers.swap_remove(index);
+
+    // The list shrank, so clear the stale tail bytes before rewriting
+    account.data.borrow_mut().fill(0);
+    hello_world_account.serialize(&mut &mut account.data.borrow_mut()[..])?;
+
+    debug_msg!("Writer removed: {}", writer);
+    emit_event!(WriterRemoved { account: *account.key, writer })?;
+
+    Ok(())
+}
+
+/// Configure or remove the token gate for a message account
+fn set_token_gate(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    token_gate: Option<TokenGate>,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let account = next_account_info(accounts_iter)?;
+    let author = next_account_info(accounts_iter)?;
+
+    let mut hello_world_account = load_for_author(program_id, account, author)?;
+
+    match &token_gate {
+        Some(gate) if gate.min_balance == 0 => {
+            msg!("Minimum balance must be greater than zero");
+            return Err(HelloWorldError::InvalidMinBalance.into());
+        }
+        Some(gate) => debug_msg!("Token gate set: {} >= {}", gate.mint, gate.min_balance),
+        None => debug_msg!("Token gate removed"),
+    }
+
+    hello_world_account.token_gate = token_gate.clone();
+
+    // Removing the gate shrinks the data, so clear the stale tail bytes
+    account.data.borrow_mut().fill(0);
+    hello_world_account.serialize(&mut &mut account.data.borrow_mut()[..])?;
+
+    emit_event!(TokenGateUpdated { account: *account.key, token_gate })?;
+
+    Ok(())
+}
+
+/// Configure or remove the off-chain signing authority for a message account
+fn set_signing_authority(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    signing_authority: Option<Pubkey>,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let account = next_account_info(accounts_iter)?;
+    let author = next_account_info(accounts_iter)?;
+
+    let mut hello_world_account = load_for_author(program_id, account, author)?;
+
+    match &signing_authority {
+        Some(authority) => debug_msg!("Signing authority set: {}", authority),
+        None => debug_msg!("Signing authority removed"),
+    }
+
+    hello_world_account.signing_authority = signing_authority;
+
+    // Removing the authority shrinks the data, so clear the stale tail bytes
+    account.data.borrow_mut().fill(0);
+    hello_world_account.serialize(&mut &mut account.data.borrow_mut()[..])?;
+
+    emit_event!(SigningAuthorityUpdated { account: *account.key, signing_authority })?;
+
+    Ok(())
+}
+
+/// Store a message authored off-chain, after checking via instruction
+/// introspection that the ed25519 program verified the authority's signature
+fn set_signed_message(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    new_message: String,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let account = next_account_info(accounts_iter)?;
+    let instructions_sysvar = next_account_info(accounts_iter)?;
+
+    assert_owned_by(account, program_id)?;
+    assert_writable(account)?;
+
+    if new_message.is_empty() {
+        msg!("Message cannot be empty");
+        return Err(HelloWorldError::MessageEmpty.into());
+    }
+    if new_message.len() > MAX_MESSAGE_LENGTH {
+        msg!("Message too long (max {} bytes)", MAX_MESSAGE_LENGTH);
+        return Err(HelloWorldError::MessageTooLong.into());
+    }
+
+    let mut hello_world_account: HelloWorldAccount = load_versioned(&account.data.borrow())?;
+
+    assert_initialized(&hello_world_account)?;
+
+    let signing_authority = hello_world_account.signing_authority.ok_or_else(|| {
+        msg!("No signing authority configured");
+        HelloWorldError::NoSigningAuthority
+    })?;
+
+    let expected_message =
+        signed_message_bytes(account.key, hello_world_account.revision, &new_message);
+    verify_ed25519_instruction(instructions_sysvar, &signing_authority, &expected_message)?;
+
+    let clock = Clock::get()?;
+    let old_len = hello_world_account.message.len();
+
+    hello_world_account.message = new_message;
+    hello_world_account.content_anchor = None;
+    hello_world_account.last_updater = signing_authority;
+    hello_world_account.expires_at_slot = None;
+    hello_world_account.revision = hello_world_account
+        .revision
+        .checked_add(1)
+        .ok_or(HelloWorldError::Overflow)?;
+    hello_world_account.updated_at = clock.unix_timestamp;
+
+    // The new message may be shorter, so clear the stale tail bytes
+    account.data.borrow_mut().fill(0);
+    hello_world_account.serialize(&mut &mut account.data.borrow_mut()[..])?;
+
+    emit_event!(MessageUpdated {
+        account: *account.key,
+        updater: signing_authority,
+        old_len: old_len as u32,
+        new_len: hello_world_account.message.len() as u32,
+        slot: clock.slot,
+    })?;
+
+    debug_msg!("Signed message from {} stored", signing_authority);
+
+    Ok(())
+}
+
+/// Anchor content by hash and URI, logging the full content instead of
+/// storing it
+fn set_anchored_content(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    content: Vec<u8>,
+    uri: String,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let account = next_account_info(accounts_iter)?;
+    let author = next_account_info(accounts_iter)?;
+
+    let mut hello_world_account = load_for_author(program_id, account, author)?;
+
+    if content.is_empty() {
+        msg!("Content cannot be empty");
+        return Err(HelloWorldError::MessageEmpty.into());
+    }
+    if uri.len() > MAX_URI_LENGTH {
+        msg!("URI too long (max {} bytes)", MAX_URI_LENGTH);
+        return Err(HelloWorldError::UriTooLong.into());
+    }
+
+    let clock = Clock::get()?;
+    let old_len = hello_world_account.message.len();
+    let content_hash = hash(&content).to_bytes();
+    let content_len = content.len() as u32;
+
+    hello_world_account.message = String::new();
+    hello_world_account.content_anchor = Some(ContentAnchor {
+        content_hash,
+        content_len,
+        uri: uri.clone(),
+    });
+    hello_world_account.last_updater = *author.key;
+    hello_world_account.expires_at_slot = None;
+    hello_world_account.revision = hello_world_account
+        .revision
+        .checked_add(1)
+        .ok_or(HelloWorldError::Overflow)?;
+    hello_world_account.updated_at = clock.unix_timestamp;
+
+    account.data.borrow_mut().fill(0);
+    hello_world_account.serialize(&mut &mut account.data.borrow_mut()[..])?;
+
+    emit_event!(MessageUpdated {
+        account: *account.key,
+        updater: *author.key,
+        old_len: old_len as u32,
+        new_len: 0,
+        slot: clock.slot,
+    })?;
+    emit_event!(ContentAnchored {
+        account: *account.key,
+        content_hash,
+        uri,
+        content,
+    })?;
+
+    debug_msg!("Content anchored: {} bytes", content_len);
+
+    Ok(())
+}
+
+/// Check a content blob against the anchored hash
+fn verify_content(program_id: &Pubkey, accounts: &[AccountInfo], content: Vec<u8>) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let account = next_account_info(accounts_iter)?;
+
+    assert_owned_by(account, program_id)?;
+
+    let hello_world_account: HelloWorldAccount = load_versioned(&account.data.borrow())?;
+
+    assert_initialized(&hello_world_account)?;
+
+    let content_anchor = hello_world_account.content_anchor.ok_or_else(|| {
+        msg!("Message has no anchored content");
+        HelloWorldError::NoContentAnchor
+    })?;
+
+    if hash(&content).to_bytes() != content_anchor.content_hash {
+        msg!("Content does not match the anchored hash");
+        return Err(HelloWorldError::ContentHashMismatch.into());
+    }
+
+    debug_msg!("Content verified against {}", content_anchor.uri);
+
+    Ok(())
+}
+
+/// Check that the instruction before this one is an ed25519 program
+/// instruction verifying one signature by `signer` over `message`, with the
+/// key and message embedded in that instruction's own data
+fn verify_ed25519_instruction(
+    instructions_sysvar: &AccountInfo,
+    signer: &Pubkey,
+    message: &[u8],
+) -> ProgramResult {
+    // Layout: num_signatures (u8), padding (u8), then 7 little-endian u16
+    // offsets: signature offset, signature instruction index, public key
+    // offset, public key instruction index, message offset, message size,
+    // message instruction index
+    const OFFSETS_START: usize = 2;
+    const OFFSETS_LEN: usize = 14;
+    // Instruction index meaning "the ed25519 instruction itself"
+    const THIS_INSTRUCTION: usize = u16::MAX as usize;
+
+    let current_index = load_current_index_checked(instructions_sysvar)? as usize;
+    let ed25519_ix = current_index
+        .checked_sub(1)
+        .map(|index| load_instruction_at_checked(index, instructions_sysvar))
+        .transpose()?
+        .filter(|ix| ix.program_id == ed25519_program::id())
+        .ok_or_else(|| {
+            msg!("Expected an ed25519 verification instruction before this one");
+            HelloWorldError::InvalidSignatureInstruction
+        })?;
+
+    let data = &ed25519_ix.data;
+    if data.len() < OFFSETS_START + OFFSETS_LEN || data[0] != 1 {
+        msg!("Expected exactly one ed25519 signature");
+        return Err(HelloWorldError::InvalidSignatureInstruction.into());
+    }
+
+    let offset = |i: usize| {
+        let at = OFFSETS_START + i * 2;
+        u16::from_le_bytes([data[at], data[at + 1]]) as usize
+    };
+    let (signature_ix, key_offset, key_ix) = (offset(1), offset(2), offset(3));
+    let (message_offset, message_size, message_ix) = (offset(4), offset(5), offset(6));
+
+    if signature_ix != THIS_INSTRUCTION
+        || key_ix != THIS_INSTRUCTION
+        || message_ix != THIS_INSTRUCTION
+    {
+        msg!("ed25519 signature data must be inline");
+        return Err(HelloWorldError::InvalidSignatureInstruction.into());
+    }
+
+    let verified_key = data.get(key_offset..key_offset + 32);
+    let verified_message = data.get(message_offset..message_offset + message_size);
+
+    if verified_key != Some(signer.as_ref()) || verified_message != Some(message) {
+        msg!("ed25519 instruction does not match the signing authority and message");
+        return Err(HelloWorldError::InvalidSignatureInstruction.into());
+    }
+
+    Ok(())
+}
+
+/// Set or clear the moderator's hidden flag; the message itself is untouched
+fn set_hidden(program_id: &Pubkey, accounts: &[AccountInfo], hidden: bool) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let account = next_account_info(accounts_iter)?;
+    let moderator = next_account_info(accounts_iter)?;
+
+    assert_owned_by(account, program_id)?;
+    assert_writable(account)?;
+    assert_signer(moderator)?;
+
+    let mut hello_world_account: HelloWorldAccount = load_versioned(&account.data.borrow())?;
+
+    assert_initialized(&hello_world_account)?;
+
+    if hello_world_account.moderator != *moderator.key {
+        msg!("Only the moderator can perform this action");
+        return Err(HelloWorldError::NotModerator.into());
+    }
+
+    hello_world_account.hidden = hidden;
+    hello_world_account.serialize(&mut &mut account.data.borrow_mut()[..])?;
+
+    if hidden {
+        debug_msg!("Message hidden by {}", moderator.key);
+        emit_event!(MessageFlagged { account: *account.key, moderator: *moderator.key })?;
+    } else {
+        debug_msg!("Message unhidden by {}", moderator.key);
+        emit_event!(MessageUnflagged { account: *account.key, moderator: *moderator.key })?;
+    }
+
+    Ok(())
+}
+
+fn migrate(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let account = next_account_info(accounts_iter)?;
+    let payer = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    assert_owned_by(account, program_id)?;
+    assert_writable(account)?;
+    assert_signer(payer)?;
+
+    migrate_account::<HelloWorldAccount>(account, payer, system_program)
+}
+
+/// Validate a writable, initialized message account and that the signer is
+/// its author, returning the deserialized state
+fn load_for_author(
+    program_id: &Pubkey,
+    account: &AccountInfo,
+    author: &AccountInfo,
+) -> Result<HelloWorldAccount, ProgramError> {
+    assert_owned_by(account, program_id)?;
+    assert_writable(account)?;
+    assert_signer(author)?;
+
+    let hello_world_account: HelloWorldAccount = load_versioned(&account.data.borrow())?;
+
+    assert_initialized(&hello_world_account)?;
+
+    if hello_world_account.author != *author.key {
+        msg!("Only the author can perform this action");
+        return Err(HelloWorldError::NotAuthor.into());
+    }
+
+    Ok(hello_world_account)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use common::{ClockSource, FixedClock};
+    use solana_program::{
+        entrypoint::SUCCESS,
+        program_stubs,
+        rent::Rent,
+        sysvar,
+        sysvar::instructions::{construct_instructions_data, BorrowedInstruction},
+    };
+    use std::cell::RefCell;
+    use test_utils::account::TestAccount;
+
+    thread_local! {
+        /// Return data captured from the current test thread
+        static RETURN_DATA: RefCell<Vec<u8>> = const { RefCell::new(Vec::new()) };
+        /// `sol_log_data` fields captured from the current test thread
+        static LOG_DATA: RefCell<Vec<Vec<u8>>> = const { RefCell::new(Vec::new()) };
+        /// `msg!` lines captured from the current test thread
+        static LOGS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+        /// The Clock served to the current test thread, slot 0 unless set
+        static CLOCK: RefCell<FixedClock> = RefCell::new(FixedClock::default());
+    }
+
+    /// Serves the Clock and Rent sysvars and captures return data and logs
+    /// off-chain so handlers can run in unit tests
+    struct TestSyscallStubs;
+
+    impl program_stubs::SyscallStubs for TestSyscallStubs {
+        fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+            let clock = CLOCK.with(|clock| clock.borrow().clock().unwrap());
+            unsafe {
+                *(var_addr as *mut Clock) = clock;
+            }
+            SUCCESS
+        }
+
+        fn sol_log(&self, message: &str) {
+            LOGS.with(|logs| logs.borrow_mut().push(message.to_string()));
+        }
+
+        fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+            unsafe { *(var_addr as *mut Rent) = Rent::default() };
+            SUCCESS
+        }
+
+        fn sol_set_return_data(&self, data: &[u8]) {
+            RETURN_DATA.with(|return_data| *return_data.borrow_mut() = data.to_vec());
+        }
+
+        fn sol_log_data(&self, fields: &[&[u8]]) {
+            LOG_DATA.with(|log_data| {
+                log_data
+                    .borrow_mut()
+                    .extend(fields.iter().map(|field| field.to_vec()))
+            });
+        }
+    }
+
+    fn set_test_stubs() {
+        program_stubs::set_syscall_stubs(Box::new(TestSyscallStubs));
+    }
+
+    /// Serve `clock` to handlers run on this thread from now on
+    fn set_clock(clock: FixedClock) {
+        CLOCK.with(|current| *current.borrow_mut() = clock);
+    }
+
+    /// An initialized message by `author`, who also last updated it
+    fn message_state(author: Pubkey) -> HelloWorldAccount {
+        HelloWorldAccount {
+            discriminator: HelloWorldAccount::DISCRIMINATOR,
+            version: HelloWorldAccount::VERSION,
+            is_initialized: true,
+            message: "Hello, Solana!".to_string(),
+            last_updater: author,
+            expires_at_slot: None,
+            parent: None,
+            reply_count: 0,
+            author,
+            cooldown_slots: 0,
+            writers: Vec::new(),
+            revision: 1,
+            created_at: 0,
+            updated_at: 0,
+            token_gate: None,
+            signing_authority: None,
+            content_anchor: None,
+            moderator: Pubkey::default(),
+            hidden: false,
+        }
+    }
+
+    #[test]
+    fn test_hello_world() {
+        set_test_stubs();
+        let program_id = Pubkey::default();
+        let key = Pubkey::default();
+        let updater_key = Pubkey::default();
+
+        // Account should be owned by the program
+        let mut account = TestAccount::new(key)
+            .writable()
+            .lamports(Rent::default().minimum_balance(MESSAGE_ACCOUNT_SIZE))
+            .data_len(MESSAGE_ACCOUNT_SIZE)
+            .owner(program_id);
+
+        let mut updater_account = TestAccount::new(updater_key).signer().owner(program_id);
+
+        let accounts = vec![account.info(), updater_account.info()];
+
+        let instruction = HelloWorldInstruction::SetMessage {
+            message: "Hello, Solana!".to_string(),
+            ttl_slots: None,
+            expected_revision: None,
+            moderator: None,
+        };
+        let instruction_data = instruction.pack();
+
+        let result = process_instruction(&program_id, &accounts, &instruction_data);
+        assert!(result.is_ok());
+
+        let log_data = LOG_DATA.with(|log_data| log_data.borrow().clone());
+        let event = log_data.last().unwrap();
+        assert_eq!(event[..8], MessageUpdated::DISCRIMINATOR);
+        let event = MessageUpdated::try_from_slice(&event[8..]).unwrap();
+        assert_eq!(
+            event,
+            MessageUpdated {
+                account: key,
+                updater: updater_key,
+                old_len: 0,
+                new_len: "Hello, Solana!".len() as u32,
+                slot: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn test_shorter_message_clears_old_bytes() {
+        set_test_stubs();
+        let program_id = Pubkey::default();
+        let mut account = TestAccount::new(Pubkey::new_unique())
+            .writable()
+            .lamports(Rent::default().minimum_balance(MESSAGE_ACCOUNT_SIZE))
+            .data_len(MESSAGE_ACCOUNT_SIZE)
+            .owner(program_id);
+        // The updater becomes the moderator, whose key ends the serialized
+        // account, so the stale tail has non-zero bytes
+        let mut updater = TestAccount::new(Pubkey::new_from_array([7; 32])).signer();
+        let accounts = vec![account.info(), updater.info()];
+
+        for message in ["a much longer first message", "short"] {
+            let instruction = HelloWorldInstruction::SetMessage {
+                message: message.to_string(),
+                ttl_slots: None,
+                expected_revision: None,
+                moderator: None,
+            };
+            process_instruction(&program_id, &accounts, &instruction.pack()).unwrap();
+        }
+
+        let state: HelloWorldAccount = load_versioned(&accounts[0].data.borrow()).unwrap();
+        assert_eq!(state.message, "short");
+        let data = accounts[0].data.borrow();
+        assert!(data[state.try_to_vec().unwrap().len()..].iter().all(|byte| *byte == 0));
+    }
+
+    #[test]
+    fn test_message_too_long() {
+        set_test_stubs();
+        let program_id = Pubkey::default();
+        let key = Pubkey::default();
+        let updater_key = Pubkey::default();
+
+        let mut account = TestAccount::new(key)
+            .writable()
+            .lamports(Rent::default().minimum_balance(MESSAGE_ACCOUNT_SIZE))
+            .data_len(MESSAGE_ACCOUNT_SIZE)
+            .owner(program_id);
+
+        let mut updater_account = TestAccount::new(updater_key).signer().owner(program_id);
+
+        let accounts = vec![account.info(), updater_account.info()];
+
+        let instruction = HelloWorldInstruction::SetMessage {
+            message: "a".repeat(MAX_MESSAGE_LENGTH + 1),
+            ttl_slots: None,
+            expected_revision: None,
+            moderator: None,
+        };
+        let instruction_data = instruction.pack();
+
+        let result = process_instruction(&program_id, &accounts, &instruction_data);
+        assert_eq!(result, Err(HelloWorldError::MessageTooLong.into()));
+    }
+
+    #[test]
+    fn test_reap_without_expiry_fails() {
+        let program_id = Pubkey::default();
+        let key = Pubkey::default();
+        let caller_key = Pubkey::new_unique();
+
+        let hello_world_account = message_state(Pubkey::new_unique());
+
+        let mut account = TestAccount::new(key)
+            .writable()
+            .lamports(1_000_000)
+            .data(hello_world_account.try_to_vec().unwrap())
+            .data_len(1000)
+            .owner(program_id);
+
+        let mut caller_account = TestAccount::new(caller_key).signer().writable().owner(program_id);
+
+        let accounts = vec![account.info(), caller_account.info()];
+        let instruction_data = HelloWorldInstruction::Reap.pack();
+
+        let result = process_instruction(&program_id, &accounts, &instruction_data);
+        assert_eq!(result, Err(HelloWorldError::MessageDoesNotExpire.into()));
+    }
+
+    #[test]
+    fn test_reap_pays_capped_bounty() {
+        set_test_stubs();
+        set_clock(FixedClock::at_slot(100));
+        let program_id = Pubkey::default();
+        let rent_exempt = Rent::default().minimum_balance(MESSAGE_ACCOUNT_SIZE);
+
+        // A surplus over the bounty pays the bounty; a smaller one pays only the surplus
+        for surplus in [2 * REAP_BOUNTY_LAMPORTS, REAP_BOUNTY_LAMPORTS / 2] {
+            let hello_world_account = HelloWorldAccount {
+                expires_at_slot: Some(50),
+                ..message_state(Pubkey::new_unique())
+            };
+            let mut account = TestAccount::new(Pubkey::new_unique())
+                .writable()
+                .lamports(rent_exempt + surplus)
+                .data(hello_world_account.try_to_vec().unwrap())
+                .data_len(MESSAGE_ACCOUNT_SIZE)
+                .owner(program_id);
+            let mut caller_account = TestAccount::new(Pubkey::new_unique()).signer().writable();
+            let accounts = vec![account.info(), caller_account.info()];
+
+            process_instruction(&program_id, &accounts, &HelloWorldInstruction::Reap.pack()).unwrap();
+
+            let bounty = surplus.min(REAP_BOUNTY_LAMPORTS);
+            assert_eq!(accounts[1].lamports(), bounty);
+            assert_eq!(accounts[0].lamports(), rent_exempt + surplus - bounty);
+            assert!(accounts[0].lamports() >= rent_exempt);
+
+            let stored: HelloWorldAccount = load_versioned(&accounts[0].data.borrow()).unwrap();
+            assert!(stored.message.is_empty());
+            assert_eq!(stored.expires_at_slot, None);
+            assert_eq!(stored.revision, 2);
+        }
+    }
+
+    #[test]
+    fn test_reply_with_wrong_pda_fails() {
+        let program_id = Pubkey::new_unique();
+        let parent_key = Pubkey::new_unique();
+        let reply_key = Pubkey::new_unique();
+        let author_key = Pubkey::new_unique();
+        let system_program_id = solana_program::system_program::id();
+
+        let parent = message_state(Pubkey::new_unique());
+        let mut parent_account = TestAccount::new(parent_key)
+            .writable()
+            .lamports(1_000_000)
+            .data(parent.try_to_vec().unwrap())
+            .data_len(MESSAGE_ACCOUNT_SIZE)
+            .owner(program_id);
+
+        let mut reply_account = TestAccount::new(reply_key).writable();
+
+        let mut author_account = TestAccount::new(author_key).signer().writable().lamports(1_000_000);
+
+        let mut system_account = TestAccount::new(system_program_id).executable();
+
+        let accounts =
+            vec![parent_account.info(), reply_account.info(), author_account.info(), system_account.info()];
+        let instruction = HelloWorldInstruction::Reply {
+            message: "Hi back!".to_string(),
+        };
+        let instruction_data = instruction.pack();
+
+        let result = process_instruction(&program_id, &accounts, &instruction_data);
+        assert_eq!(result, Err(ProgramError::InvalidSeeds));
+    }
+
+    #[test]
+    fn test_set_cooldown_requires_author() {
+        let program_id = Pubkey::default();
+        let key = Pubkey::default();
+        let not_author_key = Pubkey::new_unique();
+
+        let hello_world_account = message_state(Pubkey::new_unique());
+
+        let mut account = TestAccount::new(key)
+            .writable()
+            .data(hello_world_account.try_to_vec().unwrap())
+            .data_len(MESSAGE_ACCOUNT_SIZE)
+            .owner(program_id);
+
+        let mut signer_account = TestAccount::new(not_author_key).signer().owner(program_id);
+
+        let accounts = vec![account.info(), signer_account.info()];
+        let instruction = HelloWorldInstruction::SetCooldown { cooldown_slots: 10 };
+        let instruction_data = instruction.pack();
+
+        let result = process_instruction(&program_id, &accounts, &instruction_data);
+        assert_eq!(result, Err(HelloWorldError::NotAuthor.into()));
+    }
+
+    #[test]
+    fn test_cooldown_rate_limits_updater() {
+        set_test_stubs();
+        let program_id = crate::ID;
+        let key = Pubkey::new_unique();
+        let author_key = Pubkey::new_unique();
+
+        let hello_world_account = HelloWorldAccount {
+            cooldown_slots: 10,
+            ..message_state(author_key)
+        };
+        let mut account = TestAccount::new(key)
+            .writable()
+            .data(hello_world_account.try_to_vec().unwrap())
+            .data_len(MESSAGE_ACCOUNT_SIZE)
+            .owner(program_id);
+        let mut author_account = TestAccount::new(author_key).signer().writable();
+
+        // An existing cooldown record, so no account is created
+        let (cooldown_key, _) =
+            Pubkey::find_program_address(&[COOLDOWN_SEED, key.as_ref(), author_key.as_ref()], &program_id);
+        let cooldown = UpdaterCooldown {
+            discriminator: UpdaterCooldown::DISCRIMINATOR,
+            last_update_slot: 0,
+        };
+        let mut cooldown_account = TestAccount::new(cooldown_key)
+            .writable()
+            .data(cooldown.try_to_vec().unwrap())
+            .owner(program_id);
+        let mut system_account = TestAccount::new(solana_program::system_program::id()).executable();
+
+        let accounts =
+            vec![account.info(), author_account.info(), cooldown_account.info(), system_account.info()];
+        let instruction_data = HelloWorldInstruction::SetMessage {
+            message: "Again".to_string(),
+            ttl_slots: None,
+            expected_revision: None,
+            moderator: None,
+        }
+        .pack();
+
+        set_clock(FixedClock::at_slot(100));
+        process_instruction(&program_id, &accounts, &instruction_data).unwrap();
+
+        // Inside the window that update opened
+        set_clock(FixedClock::at_slot(109));
+        let result = process_instruction(&program_id, &accounts, &instruction_data);
+        assert_eq!(result, Err(HelloWorldError::CoolingDown.into()));
+
+        set_clock(FixedClock::at_slot(110));
+        process_instruction(&program_id, &accounts, &instruction_data).unwrap();
+
+        let cooldown: UpdaterCooldown = load_account(&accounts[2].data.borrow()).unwrap();
+        assert_eq!(cooldown.last_update_slot, 110);
+        let stored: HelloWorldAccount = load_versioned(&accounts[0].data.borrow()).unwrap();
+        assert_eq!(stored.revision, 3);
+    }
+
+    #[test]
+    fn test_set_message_rejects_non_writer() {
+        set_test_stubs();
+        let program_id = Pubkey::default();
+        let key = Pubkey::default();
+        let outsider_key = Pubkey::new_unique();
+
+        let hello_world_account = HelloWorldAccount {
+            writers: vec![Pubkey::new_unique()],
+            ..message_state(Pubkey::new_unique())
+        };
+
+        let mut account = TestAccount::new(key)
+            .writable()
+            .data(hello_world_account.try_to_vec().unwrap())
+            .data_len(MESSAGE_ACCOUNT_SIZE)
+            .owner(program_id);
+
+        let mut outsider_account = TestAccount::new(outsider_key).signer().owner(program_id);
+
+        let accounts = vec![account.info(), outsider_account.info()];
+        let instruction = HelloWorldInstruction::SetMessage {
+            message: "Let me in".to_string(),
+            ttl_slots: None,
+            expected_revision: None,
+            moderator: None,
+        };
+        let instruction_data = instruction.pack();
+
+        let result = process_instruction(&program_id, &accounts, &instruction_data);
+        assert_eq!(result, Err(HelloWorldError::NotWriter.into()));
+    }
+
+    #[test]
+    fn test_add_and_remove_writers() {
+        set_test_stubs();
+        let program_id = Pubkey::default();
+        let author_key = Pubkey::new_unique();
+
+        // A non-zero moderator ends the serialized account, so a stale tail would show
+        let hello_world_account = HelloWorldAccount {
+            moderator: Pubkey::new_from_array([9; 32]),
+            ..message_state(author_key)
+        };
+        let mut account = TestAccount::new(Pubkey::new_unique())
+            .writable()
+            .data(hello_world_account.try_to_vec().unwrap())
+            .data_len(MESSAGE_ACCOUNT_SIZE)
+            .owner(program_id);
+        let mut author_account = TestAccount::new(author_key).signer();
+        let accounts = vec![account.info(), author_account.info()];
+
+        let writers: Vec<Pubkey> = (1..=MAX_WRITERS as u8).map(|i| Pubkey::new_from_array([i; 32])).collect();
+        let add = |writer_key: Pubkey| {
+            process_instruction(&program_id, &accounts, &HelloWorldInstruction::AddWriter { writer_key }.pack())
+        };
+        let remove = |writer_key: Pubkey| {
+            process_instruction(&program_id, &accounts, &HelloWorldInstruction::RemoveWriter { writer_key }.pack())
+        };
+
+        add(writers[0]).unwrap();
+        assert_eq!(add(writers[0]), Err(HelloWorldError::WriterAlreadyListed.into()));
+        for writer in &writers[1..] {
+            add(*writer).unwrap();
+        }
+        assert_eq!(add(Pubkey::new_unique()), Err(HelloWorldError::WriterListFull.into()));
+
+        remove(writers[1]).unwrap();
+        assert_eq!(remove(writers[1]), Err(HelloWorldError::WriterNotListed.into()));
+
+        let stored: HelloWorldAccount = load_versioned(&accounts[0].data.borrow()).unwrap();
+        let mut remaining = stored.writers.clone();
+        remaining.sort();
+        let mut expected: Vec<Pubkey> = writers.iter().filter(|w| **w != writers[1]).copied().collect();
+        expected.sort();
+        assert_eq!(remaining, expected);
+
+        // The freed slot's bytes no longer trail the shorter state
+        let data = accounts[0].data.borrow();
+        assert!(data[stored.try_to_vec().unwrap().len()..].iter().all(|byte| *byte == 0));
+    }
+
+    #[test]
+    fn test_set_message_rejects_stale_revision() {
+        set_test_stubs();
+        let program_id = Pubkey::default();
+        let key = Pubkey::default();
+        let author_key = Pubkey::new_unique();
+
+        let hello_world_account = HelloWorldAccount {
+            revision: 3,
+            ..message_state(author_key)
+        };
+
+        let mut account = TestAccount::new(key)
+            .writable()
+            .data(hello_world_account.try_to_vec().unwrap())
+            .data_len(MESSAGE_ACCOUNT_SIZE)
+            .owner(program_id);
+
+        let mut author_account = TestAccount::new(author_key).signer().owner(program_id);
+
+        let accounts = vec![account.info(), author_account.info()];
+        let instruction = HelloWorldInstruction::SetMessage {
+            message: "Edited".to_string(),
+            ttl_slots: None,
+            expected_revision: Some(2),
+            moderator: None,
+        };
+        let instruction_data = instruction.pack();
+
+        let result = process_instruction(&program_id, &accounts, &instruction_data);
+        assert_eq!(result, Err(HelloWorldError::RevisionMismatch.into()));
+    }
+
+    #[test]
+    fn test_get_message_sets_return_data() {
+        set_test_stubs();
+        let program_id = Pubkey::default();
+        let key = Pubkey::default();
+
+        let hello_world_account = message_state(Pubkey::new_unique());
+
+        let mut account = TestAccount::new(key)
+            .data(hello_world_account.try_to_vec().unwrap())
+            .data_len(MESSAGE_ACCOUNT_SIZE)
+            .owner(program_id);
+
+        let accounts = vec![account.info()];
+        let instruction_data = HelloWorldInstruction::GetMessage.pack();
+
+        let result = process_instruction(&program_id, &accounts, &instruction_data);
+        assert!(result.is_ok());
+
+        let return_data = RETURN_DATA.with(|return_data| return_data.borrow().clone());
+        let returned = HelloWorldAccount::try_from_slice(&return_data).unwrap();
+        assert_eq!(returned.message, "Hello, Solana!");
+    }
+
+    #[test]
+    fn test_get_message_logs_staleness() {
+        set_test_stubs();
+        let program_id = Pubkey::default();
+        let hello_world_account = HelloWorldAccount {
+            expires_at_slot: Some(50),
+            ..message_state(Pubkey::new_unique())
+        };
+        let mut account = TestAccount::new(Pubkey::new_unique())
+            .data(hello_world_account.try_to_vec().unwrap())
+            .data_len(MESSAGE_ACCOUNT_SIZE)
+            .owner(program_id);
+        let accounts = vec![account.info()];
+        let instruction_data = HelloWorldInstruction::GetMessage.pack();
+        let stale = "Message is stale (expired at slot 50)".to_string();
+
+        for (slot, is_stale) in [(49, false), (50, true)] {
+            set_clock(FixedClock::at_slot(slot));
+            LOGS.with(|logs| logs.borrow_mut().clear());
+            process_instruction(&program_id, &accounts, &instruction_data).unwrap();
+            assert_eq!(LOGS.with(|logs| logs.borrow().contains(&stale)), is_stale);
+        }
+    }
+
+    #[test]
+    fn test_token_gated_set_message_requires_balance() {
+        set_test_stubs();
+        let program_id = Pubkey::default();
+        let key = Pubkey::default();
+        let updater_key = Pubkey::new_unique();
+        let token_key = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let token_program_id = spl_token::id();
+
+        let hello_world_account = HelloWorldAccount {
+            token_gate: Some(TokenGate {
+                mint,
+                min_balance: 100,
+            }),
+            ..message_state(Pubkey::new_unique())
+        };
+
+        let mut account = TestAccount::new(key)
+            .writable()
+            .data(hello_world_account.try_to_vec().unwrap())
+            .data_len(MESSAGE_ACCOUNT_SIZE)
+            .owner(program_id);
+
+        let mut updater_account = TestAccount::new(updater_key).signer().owner(program_id);
+
+        let token = TokenAccount {
+            mint,
+            owner: updater_key,
+            amount: 99,
+            state: spl_token::state::AccountState::Initialized,
+            ..TokenAccount::default()
+        };
+        let mut token_data = vec![0; TokenAccount::LEN];
+        TokenAccount::pack(token, &mut token_data).unwrap();
+        let mut token_account = TestAccount::new(token_key).data(token_data).owner(token_program_id);
+
+        let accounts = vec![account.info(), updater_account.info(), token_account.info()];
+        let instruction = HelloWorldInstruction::SetMessage {
+            message: "Members only".to_string(),
+            ttl_slots: None,
+            expected_revision: None,
+            moderator: None,
+        };
+        let instruction_data = instruction.pack();
+
+        let result = process_instruction(&program_id, &accounts, &instruction_data);
+        assert_eq!(result, Err(HelloWorldError::InsufficientTokenBalance.into()));
+    }
+
+    #[test]
+    fn test_set_signed_message_with_ed25519_instruction() {
+        set_test_stubs();
+        let program_id = Pubkey::default();
+        let key = Pubkey::new_unique();
+        let authority = Pubkey::new_unique();
+
+        let hello_world_account = HelloWorldAccount {
+            revision: 7,
+            signing_authority: Some(authority),
+            ..message_state(Pubkey::new_unique())
+        };
+
+        let mut account = TestAccount::new(key)
+            .writable()
+            .data(hello_world_account.try_to_vec().unwrap())
+            .data_len(MESSAGE_ACCOUNT_SIZE)
+            .owner(program_id);
+
+        // ed25519 instruction with inline key, (unchecked) signature, and message
+        let message = "Signed offline";
+        let signed = signed_message_bytes(&key, 7, message);
+        let (key_offset, signature_offset, message_offset) = (16u16, 48u16, 112u16);
+        let mut ed25519_data = vec![1, 0];
+        for offset in [
+            signature_offset,
+            u16::MAX,
+            key_offset,
+            u16::MAX,
+            message_offset,
+            signed.len() as u16,
+            u16::MAX,
+        ] {
+            ed25519_data.extend_from_slice(&offset.to_le_bytes());
+        }
+        ed25519_data.extend_from_slice(authority.as_ref());
+        ed25519_data.extend_from_slice(&[0; 64]);
+        ed25519_data.extend_from_slice(&signed);
+
+        let ed25519_program_id = ed25519_program::id();
+        let instructions = [
+            BorrowedInstruction {
+                program_id: &ed25519_program_id,
+                accounts: vec![],
+                data: &ed25519_data,
+            },
+            BorrowedInstruction {
+                program_id: &program_id,
+                accounts: vec![],
+                data: &[],
+            },
+        ];
+        let mut sysvar_data = construct_instructions_data(&instructions);
+        // The current instruction index is stored in the last two bytes
+        let index_at = sysvar_data.len() - 2;
+        sysvar_data[index_at..].copy_from_slice(&1u16.to_le_bytes());
+
+        let sysvar_key = sysvar::instructions::id();
+        let sysvar_owner = sysvar::id();
+        let mut sysvar_account = TestAccount::new(sysvar_key).data(sysvar_data).owner(sysvar_owner);
+
+        let accounts = vec![account.info(), sysvar_account.info()];
+        let instruction = HelloWorldInstruction::SetSignedMessage {
+            message: message.to_string(),
+        };
+        let instruction_data = instruction.pack();
+
+        let result = process_instruction(&program_id, &accounts, &instruction_data);
+        assert!(result.is_ok());
+
+        let stored = HelloWorldAccount::deserialize(&mut &accounts[0].data.borrow()[..]).unwrap();
+        assert_eq!(stored.message, message);
+        assert_eq!(stored.last_updater, authority);
+        assert_eq!(stored.revision, 8);
+    }
+
+    #[test]
+    fn test_verify_anchored_content() {
+        set_test_stubs();
+        let program_id = Pubkey::default();
+        let key = Pubkey::new_unique();
+        let author_key = Pubkey::new_unique();
+
+        let hello_world_account = message_state(author_key);
+
+        let mut account = TestAccount::new(key)
+            .writable()
+            .data(hello_world_account.try_to_vec().unwrap())
+            .data_len(MESSAGE_ACCOUNT_SIZE)
+            .owner(program_id);
+
+        let mut author_account = TestAccount::new(author_key).signer().owner(program_id);
+
+        let accounts = vec![account.info(), author_account.info()];
+        let content = vec![b'a'; 2 * MAX_MESSAGE_LENGTH];
+        let instruction = HelloWorldInstruction::SetAnchoredContent {
+            content: content.clone(),
+            uri: "ipfs://example".to_string(),
+        };
+        let instruction_data = instruction.pack();
+        assert!(process_instruction(&program_id, &accounts, &instruction_data).is_ok());
+
+        let stored = HelloWorldAccount::deserialize(&mut &accounts[0].data.borrow()[..]).unwrap();
+        assert!(stored.message.is_empty());
+        assert_eq!(stored.content_anchor.unwrap().content_len, content.len() as u32);
+
+        let instruction = HelloWorldInstruction::VerifyContent { content };
+        let instruction_data = instruction.pack();
+        assert!(process_instruction(&program_id, &accounts[..1], &instruction_data).is_ok());
+
+        let instruction = HelloWorldInstruction::VerifyContent {
+            content: b"tampered".to_vec(),
+        };
+        let instruction_data = instruction.pack();
+        let result = process_instruction(&program_id, &accounts[..1], &instruction_data);
+        assert_eq!(result, Err(HelloWorldError::ContentHashMismatch.into()));
+    }
+
+    #[test]
+    fn test_flag_message_requires_moderator() {
+        let program_id = Pubkey::default();
+        let key = Pubkey::new_unique();
+        let author_key = Pubkey::new_unique();
+        let moderator_key = Pubkey::new_unique();
+
+        let hello_world_account = HelloWorldAccount {
+            moderator: moderator_key,
+            ..message_state(author_key)
+        };
+
+        let mut account = TestAccount::new(key)
+            .writable()
+            .data(hello_world_account.try_to_vec().unwrap())
+            .data_len(MESSAGE_ACCOUNT_SIZE)
+            .owner(program_id);
+        let account = account.info();
+
+        let mut author_account = TestAccount::new(author_key).signer().owner(program_id);
+
+        let mut moderator_account = TestAccount::new(moderator_key).signer().owner(program_id);
+
+        let instruction_data = HelloWorldInstruction::FlagMessage.pack();
+
+        // Authorship does not grant moderation rights
+        let accounts = vec![account.clone(), author_account.info()];
+        let result = process_instruction(&program_id, &accounts, &instruction_data);
+        assert_eq!(result, Err(HelloWorldError::NotModerator.into()));
+
+        let accounts = vec![account, moderator_account.info()];
+        assert!(process_instruction(&program_id, &accounts, &instruction_data).is_ok());
+
+        let stored = HelloWorldAccount::deserialize(&mut &accounts[0].data.borrow()[..]).unwrap();
+        assert!(stored.hidden);
+        assert_eq!(stored.message, "Hello, Solana!");
+    }
+
+    #[test]
+    fn test_flag_and_unflag_toggle_hidden() {
+        set_test_stubs();
+        let program_id = Pubkey::default();
+        let moderator_key = Pubkey::new_unique();
+
+        let hello_world_account = HelloWorldAccount {
+            moderator: moderator_key,
+            ..message_state(Pubkey::new_unique())
+        };
+        let mut account = TestAccount::new(Pubkey::new_unique())
+            .writable()
+            .data(hello_world_account.try_to_vec().unwrap())
+            .data_len(MESSAGE_ACCOUNT_SIZE)
+            .owner(program_id);
+        let mut moderator_account = TestAccount::new(moderator_key).signer();
+        let accounts = vec![account.info(), moderator_account.info()];
+        let text_logged = || {
+            LOGS.with(|logs| logs.borrow_mut().clear());
+            let instruction_data = HelloWorldInstruction::GetMessage.pack();
+            process_instruction(&program_id, &accounts[..1], &instruction_data).unwrap();
+            LOGS.with(|logs| logs.borrow().iter().any(|line| line.contains("Hello, Solana!")))
+        };
+
+        let toggles = [(HelloWorldInstruction::FlagMessage, true), (HelloWorldInstruction::UnflagMessage, false)];
+        for (instruction, hidden) in toggles {
+            process_instruction(&program_id, &accounts, &instruction.pack()).unwrap();
+
+            let stored: HelloWorldAccount = load_versioned(&accounts[0].data.borrow()).unwrap();
+            assert_eq!(stored.hidden, hidden);
+            assert_eq!(stored.message, "Hello, Solana!");
+            assert_eq!(text_logged(), !hidden);
+        }
+    }
+
+    #[test]
+    fn test_space_matches_serialized_size() {
+        let mut state = HelloWorldAccount {
+            moderator: Pubkey::new_unique(),
+            ..message_state(Pubkey::new_unique())
+        };
+        assert_eq!(state.space(), state.try_to_vec().unwrap().len());
+
+        // Every optional field set and every variable-length field at its maximum
+        state.message = "a".repeat(MAX_MESSAGE_LENGTH);
+        state.expires_at_slot = Some(100);
+        state.parent = Some(Pubkey::new_unique());
+        state.writers = vec![Pubkey::new_unique(); MAX_WRITERS];
+        state.token_gate = Some(TokenGate {
+            mint: Pubkey::new_unique(),
+            min_balance: 1,
+        });
+        state.signing_authority = Some(Pubkey::new_unique());
+        state.content_anchor = Some(ContentAnchor {
+            content_hash: [7; 32],
+            content_len: 1_000,
+            uri: "u".repeat(MAX_URI_LENGTH),
+        });
+        assert_eq!(state.space(), state.try_to_vec().unwrap().len());
+        assert_eq!(state.space(), MESSAGE_ACCOUNT_SIZE);
+    }
+
+    #[test]
+    fn test_migrate_full_unversioned_message() {
+        let state = HelloWorldAccount {
+            message: "a".repeat(MAX_MESSAGE_LENGTH),
+            expires_at_slot: Some(100),
+            reply_count: 3,
+            writers: vec![Pubkey::new_unique(); MAX_WRITERS],
+            revision: 9,
+            moderator: Pubkey::new_unique(),
+            hidden: true,
+            ..message_state(Pubkey::new_unique())
+        };
+        let current = state.try_to_vec().unwrap();
+
+        // The same state written before the version byte existed
+        let mut data = current.clone();
+        data.remove(8);
+        assert_eq!(load_versioned::<HelloWorldAccount>(&data).err(), Some(ProgramError::InvalidAccountData));
+
+        // `migrate_account` grows the account before rewriting it
+        data.resize(HelloWorldAccount::SPACE, 0);
+        HelloWorldAccount::migrate_in_place(&mut data).unwrap();
+        let migrated = load_versioned::<HelloWorldAccount>(&data).unwrap();
+        assert_eq!(migrated.try_to_vec().unwrap(), current);
+    }
+}
@@ -0,0 +1,83 @@
+//! `Pod` layout of `UpdaterCooldown`, borrowed in place with `bytemuck`
+//! instead of deserialized with borsh. Built with the `zero-copy` feature to
+//! compare the two; the program itself stores the borsh layout.
+//!
+//! `HelloWorldAccount` has variable-length fields and no `Pod` layout here;
+//! the `hello-world-zero-copy` program is its fixed-size counterpart.
+
+use bytemuck::{Pod, Zeroable};
+use common::{convert_discriminator, load_pod, load_pod_mut, Discriminator};
+use solana_program::program_error::ProgramError;
+use std::mem::size_of;
+
+use crate::UpdaterCooldown;
+
+/// `UpdaterCooldown`, whose borsh layout is already fixed-size and aligned
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Pod, Zeroable)]
+pub struct UpdaterCooldownPod {
+    pub discriminator: [u8; 8],
+    /// Slot of this updater's most recent SetMessage
+    pub last_update_slot: u64,
+}
+
+impl UpdaterCooldownPod {
+    /// Size of the account data in bytes
+    pub const LEN: usize = size_of::<Self>();
+
+    /// Borrow account data as a cooldown record without copying
+    pub fn load(data: &[u8]) -> Result<&Self, ProgramError> {
+        load_pod(data)
+    }
+
+    /// Mutably borrow account data as a cooldown record without copying
+    pub fn load_mut(data: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        load_pod_mut(data)
+    }
+}
+
+impl Discriminator for UpdaterCooldownPod {
+    const DISCRIMINATOR: [u8; 8] = *b"zccooldn";
+}
+
+impl From<&UpdaterCooldown> for UpdaterCooldownPod {
+    fn from(state: &UpdaterCooldown) -> Self {
+        Self {
+            discriminator: convert_discriminator::<Self>(state.discriminator),
+            last_update_slot: state.last_update_slot,
+        }
+    }
+}
+
+impl From<&UpdaterCooldownPod> for UpdaterCooldown {
+    fn from(state: &UpdaterCooldownPod) -> Self {
+        Self {
+            discriminator: convert_discriminator::<Self>(state.discriminator),
+            last_update_slot: state.last_update_slot,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use borsh::BorshSerialize;
+
+    #[test]
+    fn test_cooldown_pod_in_place() {
+        let state = UpdaterCooldown { discriminator: UpdaterCooldown::DISCRIMINATOR, last_update_slot: 10 };
+
+        // Backed by u64s so the data is 8-byte aligned like on-chain data
+        let mut backing = [0u64; UpdaterCooldownPod::LEN / 8];
+        let data: &mut [u8] = bytemuck::cast_slice_mut(&mut backing);
+        data.copy_from_slice(bytemuck::bytes_of(&UpdaterCooldownPod::from(&state)));
+
+        UpdaterCooldownPod::load_mut(data).unwrap().last_update_slot = 20;
+        let back = UpdaterCooldown::from(UpdaterCooldownPod::load(data).unwrap());
+        assert_eq!((back.discriminator, back.last_update_slot), (UpdaterCooldown::DISCRIMINATOR, 20));
+
+        // The borsh layout has the same bytes but another tag
+        data.copy_from_slice(&state.try_to_vec().unwrap());
+        assert_eq!(UpdaterCooldownPod::load(data).err(), Some(ProgramError::InvalidAccountData));
+    }
+}
@@ -0,0 +1,103 @@
+//! Compute-unit benchmark: borsh `String` layout (`hello-world`) vs the
+//! fixed-size zero-copy layout (`hello-world-zero-copy`).
+//!
+//! Build both programs with `cargo build-sbf` from the repository root first;
+//! solana-program-test loads `hello_world.so` and `hello_world_zero_copy.so`
+//! from `SBF_OUT_DIR`. Run with
+//! `SBF_OUT_DIR=$PWD/target/deploy cargo test -p hello-world --test cu_benchmark -- --ignored --nocapture`.
+
+use solana_program_test::ProgramTest;
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::Signer,
+    transaction::Transaction,
+};
+
+/// Data length of the borsh message account (at least `MESSAGE_ACCOUNT_SIZE`)
+const BORSH_ACCOUNT_LEN: usize = 1000;
+
+/// Data length of the zero-copy message account (`ZeroCopyMessageAccount::LEN`)
+const ZERO_COPY_ACCOUNT_LEN: usize = 336;
+
+/// Encode `SetMessage` by hand so this benchmark doesn't link either program
+/// crate: instruction format 1, tag 0, then the `String`, then `extra`
+/// trailing fields
+fn set_message_data(message: &str, extra: &[u8]) -> Vec<u8> {
+    let mut data = vec![1, 0];
+    data.extend_from_slice(&(message.len() as u32).to_le_bytes());
+    data.extend_from_slice(message.as_bytes());
+    data.extend_from_slice(extra);
+    data
+}
+
+/// Simulate `instruction_data` against a fresh message account and return the
+/// compute units consumed
+async fn measure(program_name: &str, account_len: usize, instruction_data: &[u8]) -> u64 {
+    let program_id = hello_world::ID;
+    let message_key = Pubkey::new_unique();
+
+    let mut program_test = ProgramTest::new(program_name, program_id, None);
+    program_test.prefer_bpf(true);
+    program_test.add_account(
+        message_key,
+        Account {
+            lamports: 10_000_000,
+            data: vec![0; account_len],
+            owner: program_id,
+            ..Account::default()
+        },
+    );
+
+    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+    let instruction = Instruction::new_with_bytes(
+        program_id,
+        instruction_data,
+        vec![
+            AccountMeta::new(message_key, false),
+            AccountMeta::new_readonly(payer.pubkey(), true),
+        ],
+    );
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&payer.pubkey()),
+        &[&payer],
+        recent_blockhash,
+    );
+
+    let simulation = banks_client.simulate_transaction(transaction).await.unwrap();
+    assert!(
+        matches!(simulation.result, Some(Ok(()))),
+        "{} SetMessage failed: {:?}",
+        program_name,
+        simulation.result
+    );
+    simulation.simulation_details.unwrap().units_consumed
+}
+
+#[tokio::test]
+#[ignore = "requires SBF builds of both hello-world programs"]
+async fn cu_benchmark() {
+    println!("| Message | Borsh `String` | Zero-copy |");
+    println!("|---------|----------------|-----------|");
+
+    for message in ["Hello, Solana!".to_string(), "a".repeat(280)] {
+        // ttl_slots: None, expected_revision: None, moderator: None
+        let borsh_cu = measure(
+            "hello_world",
+            BORSH_ACCOUNT_LEN,
+            &set_message_data(&message, &[0, 0, 0]),
+        )
+        .await;
+        let zero_copy_cu = measure(
+            "hello_world_zero_copy",
+            ZERO_COPY_ACCOUNT_LEN,
+            &set_message_data(&message, &[]),
+        )
+        .await;
+
+        println!("| {} bytes | {} CU | {} CU |", message.len(), borsh_cu, zero_copy_cu);
+    }
+}
@@ -0,0 +1,255 @@
+//! Hello-world against the real runtime through `BanksClient`: reply and
+//! cooldown PDAs are created by system program CPIs and paid from rent, and
+//! expiry is driven by `warp_to_slot`. The program runs natively via
+//! `processor!`, so no SBF build is needed.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use common::{Discriminator, Versioned, VersionedInstruction};
+use hello_world::{
+    process_instruction, HelloWorldAccount, HelloWorldError, HelloWorldInstruction, UpdaterCooldown,
+    COOLDOWN_SEED, MESSAGE_ACCOUNT_SIZE, REAP_BOUNTY_LAMPORTS, REPLY_SEED,
+};
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    pubkey::Pubkey,
+    rent::Rent,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::{Transaction, TransactionError},
+};
+
+const STARTING_LAMPORTS: u64 = 1_000_000_000;
+
+/// Start a test validator with a program-owned message account at `message`
+/// holding `state` (zeroed when `None`) and rent plus twice the reap bounty,
+/// and a funded wallet for each of `wallets`
+async fn setup(
+    program_id: Pubkey,
+    message: Pubkey,
+    state: Option<HelloWorldAccount>,
+    wallets: &[&Keypair],
+) -> ProgramTestContext {
+    let mut program_test = ProgramTest::new("hello_world", program_id, processor!(process_instruction));
+
+    let mut data = state.map_or(vec![], |state| state.try_to_vec().unwrap());
+    data.resize(MESSAGE_ACCOUNT_SIZE, 0);
+    program_test.add_account(
+        message,
+        Account {
+            lamports: Rent::default().minimum_balance(MESSAGE_ACCOUNT_SIZE) + 2 * REAP_BOUNTY_LAMPORTS,
+            data,
+            owner: program_id,
+            ..Account::default()
+        },
+    );
+    for wallet in wallets {
+        program_test.add_account(wallet.pubkey(), Account::new(STARTING_LAMPORTS, 0, &system_program::id()));
+    }
+
+    program_test.start_with_context().await
+}
+
+async fn send(
+    context: &mut ProgramTestContext,
+    program_id: Pubkey,
+    data: HelloWorldInstruction,
+    accounts: Vec<AccountMeta>,
+    signers: &[&Keypair],
+) -> Result<(), BanksClientError> {
+    let instruction = Instruction::new_with_bytes(program_id, &data.pack(), accounts);
+    let blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let mut keypairs = vec![&context.payer];
+    keypairs.extend_from_slice(signers);
+    let transaction =
+        Transaction::new_signed_with_payer(&[instruction], Some(&context.payer.pubkey()), &keypairs, blockhash);
+    context.banks_client.process_transaction(transaction).await
+}
+
+fn set_message(message: &str, ttl_slots: Option<u64>) -> HelloWorldInstruction {
+    HelloWorldInstruction::SetMessage {
+        message: message.to_string(),
+        ttl_slots,
+        expected_revision: None,
+        moderator: None,
+    }
+}
+
+fn program_error(result: Result<(), BanksClientError>) -> InstructionError {
+    match result.unwrap_err().unwrap() {
+        TransactionError::InstructionError(0, error) => error,
+        other => panic!("unexpected transaction error {:?}", other),
+    }
+}
+
+async fn load(context: &mut ProgramTestContext, key: Pubkey) -> (Account, HelloWorldAccount) {
+    let account = context.banks_client.get_account(key).await.unwrap().unwrap();
+    let state = HelloWorldAccount::deserialize(&mut &account.data[..]).unwrap();
+    (account, state)
+}
+
+#[tokio::test]
+async fn test_message_reply_and_reap() {
+    let program_id = hello_world::ID;
+    let message = Pubkey::new_unique();
+    let (author, replier, reaper) = (Keypair::new(), Keypair::new(), Keypair::new());
+    let mut context = setup(program_id, message, None, &[&author, &replier, &reaper]).await;
+
+    let accounts = vec![AccountMeta::new(message, false), AccountMeta::new_readonly(author.pubkey(), true)];
+    send(&mut context, program_id, set_message("gm", Some(20)), accounts, &[&author]).await.unwrap();
+
+    let (_, state) = load(&mut context, message).await;
+    assert_eq!((state.message.as_str(), state.author, state.revision), ("gm", author.pubkey(), 1));
+    let expires_at_slot = state.expires_at_slot.unwrap();
+
+    // The reply account is created by the program, paid for by the replier
+    let (reply, _) = Pubkey::find_program_address(&[REPLY_SEED, message.as_ref(), &0u64.to_le_bytes()], &program_id);
+    let accounts = vec![
+        AccountMeta::new(message, false),
+        AccountMeta::new(reply, false),
+        AccountMeta::new(replier.pubkey(), true),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    let data = HelloWorldInstruction::Reply {
+        message: "gm to you".to_string(),
+    };
+    send(&mut context, program_id, data, accounts, &[&replier]).await.unwrap();
+
+    let (reply_account, reply_state) = load(&mut context, reply).await;
+    let rent = Rent::default().minimum_balance(MESSAGE_ACCOUNT_SIZE);
+    assert_eq!((reply_account.owner, reply_account.lamports), (program_id, rent));
+    assert_eq!(reply_state.parent, Some(message));
+    assert_eq!(load(&mut context, message).await.1.reply_count, 1);
+    assert_eq!(context.banks_client.get_balance(replier.pubkey()).await.unwrap(), STARTING_LAMPORTS - rent);
+
+    // Too early to reap
+    let accounts = vec![AccountMeta::new(message, false), AccountMeta::new(reaper.pubkey(), true)];
+    let result = send(&mut context, program_id, HelloWorldInstruction::Reap, accounts.clone(), &[&reaper]).await;
+    assert_eq!(program_error(result), InstructionError::Custom(HelloWorldError::MessageNotExpired as u32));
+
+    context.warp_to_slot(expires_at_slot).unwrap();
+    send(&mut context, program_id, HelloWorldInstruction::Reap, accounts, &[&reaper]).await.unwrap();
+
+    let (account, state) = load(&mut context, message).await;
+    assert_eq!((state.message.as_str(), state.expires_at_slot), ("", None));
+    // Only the lamports above rent exemption are paid out
+    assert_eq!(account.lamports, rent + REAP_BOUNTY_LAMPORTS);
+    let reaper_balance = context.banks_client.get_balance(reaper.pubkey()).await.unwrap();
+    assert_eq!(reaper_balance, STARTING_LAMPORTS + REAP_BOUNTY_LAMPORTS);
+}
+
+#[tokio::test]
+async fn test_replies_are_indexed_pdas() {
+    let program_id = hello_world::ID;
+    let message = Pubkey::new_unique();
+    let (author, moderator, replier) = (Keypair::new(), Keypair::new(), Keypair::new());
+    let mut context = setup(program_id, message, None, &[&author, &moderator, &replier]).await;
+
+    let accounts = vec![AccountMeta::new(message, false), AccountMeta::new_readonly(author.pubkey(), true)];
+    let data = HelloWorldInstruction::SetMessage {
+        message: "gm".to_string(),
+        ttl_slots: None,
+        expected_revision: None,
+        moderator: Some(moderator.pubkey()),
+    };
+    send(&mut context, program_id, data, accounts, &[&author]).await.unwrap();
+
+    // Each reply lands at the PDA for the parent's reply count at the time
+    for (index, text) in ["first", "second"].into_iter().enumerate() {
+        let (reply, _) =
+            Pubkey::find_program_address(&[REPLY_SEED, message.as_ref(), &(index as u64).to_le_bytes()], &program_id);
+        let accounts = vec![
+            AccountMeta::new(message, false),
+            AccountMeta::new(reply, false),
+            AccountMeta::new(replier.pubkey(), true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ];
+        let data = HelloWorldInstruction::Reply {
+            message: text.to_string(),
+        };
+        send(&mut context, program_id, data, accounts, &[&replier]).await.unwrap();
+
+        let (reply_account, reply_state) = load(&mut context, reply).await;
+        assert_eq!(reply_account.owner, program_id);
+        assert_eq!(reply_state.message, text);
+        assert_eq!(reply_state.parent, Some(message));
+        assert_eq!(reply_state.author, replier.pubkey());
+        assert_eq!(reply_state.moderator, moderator.pubkey());
+        assert_eq!(load(&mut context, message).await.1.reply_count, index as u64 + 1);
+    }
+}
+
+#[tokio::test]
+async fn test_cooldown_and_rejections() {
+    let program_id = hello_world::ID;
+    let message = Pubkey::new_unique();
+    let (author, stranger) = (Keypair::new(), Keypair::new());
+    let mut context = setup(program_id, message, None, &[&author, &stranger]).await;
+
+    let accounts = vec![AccountMeta::new(message, false), AccountMeta::new_readonly(author.pubkey(), true)];
+    send(&mut context, program_id, set_message("gm", None), accounts, &[&author]).await.unwrap();
+
+    // Wrong signer
+    let accounts = vec![AccountMeta::new(message, false), AccountMeta::new_readonly(stranger.pubkey(), true)];
+    let data = HelloWorldInstruction::SetCooldown { cooldown_slots: 100 };
+    let result = send(&mut context, program_id, data, accounts, &[&stranger]).await;
+    assert_eq!(program_error(result), InstructionError::Custom(HelloWorldError::NotAuthor as u32));
+
+    let accounts = vec![AccountMeta::new(message, false), AccountMeta::new_readonly(author.pubkey(), true)];
+    let data = HelloWorldInstruction::SetCooldown { cooldown_slots: 100 };
+    send(&mut context, program_id, data, accounts, &[&author]).await.unwrap();
+
+    // The first update creates the stranger's cooldown PDA, the second hits it
+    let (cooldown, _) =
+        Pubkey::find_program_address(&[COOLDOWN_SEED, message.as_ref(), stranger.pubkey().as_ref()], &program_id);
+    let accounts = vec![
+        AccountMeta::new(message, false),
+        AccountMeta::new(stranger.pubkey(), true),
+        AccountMeta::new(cooldown, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    send(&mut context, program_id, set_message("hi", None), accounts.clone(), &[&stranger]).await.unwrap();
+
+    let cooldown_account = context.banks_client.get_account(cooldown).await.unwrap().unwrap();
+    assert_eq!(cooldown_account.owner, program_id);
+    assert!(UpdaterCooldown::try_from_slice(&cooldown_account.data).unwrap().last_update_slot > 0);
+
+    let result = send(&mut context, program_id, set_message("hi again", None), accounts, &[&stranger]).await;
+    assert_eq!(program_error(result), InstructionError::Custom(HelloWorldError::CoolingDown as u32));
+    assert_eq!(load(&mut context, message).await.1.message, "hi");
+}
+
+#[tokio::test]
+async fn test_revision_overflow() {
+    let program_id = hello_world::ID;
+    let message = Pubkey::new_unique();
+    let author = Keypair::new();
+    let state = HelloWorldAccount {
+        discriminator: HelloWorldAccount::DISCRIMINATOR,
+        version: HelloWorldAccount::VERSION,
+        is_initialized: true,
+        message: "gm".to_string(),
+        last_updater: author.pubkey(),
+        expires_at_slot: None,
+        parent: None,
+        reply_count: 0,
+        author: author.pubkey(),
+        cooldown_slots: 0,
+        writers: Vec::new(),
+        revision: u64::MAX,
+        created_at: 0,
+        updated_at: 0,
+        token_gate: None,
+        signing_authority: None,
+        content_anchor: None,
+        moderator: author.pubkey(),
+        hidden: false,
+    };
+    let mut context = setup(program_id, message, Some(state), &[&author]).await;
+
+    let accounts = vec![AccountMeta::new(message, false), AccountMeta::new_readonly(author.pubkey(), true)];
+    let result = send(&mut context, program_id, set_message("gn", None), accounts, &[&author]).await;
+    assert_eq!(program_error(result), InstructionError::Custom(HelloWorldError::Overflow as u32));
+    assert_eq!(load(&mut context, message).await.1.message, "gm");
+}
@@ -0,0 +1,145 @@
+//! Golden bytes of every borsh type the program stores, logs or accepts,
+//! against tests/fixtures/layout.txt. A failure here means deployed messages
+//! and cooldown records, or existing clients, would no longer read the new
+//! layout.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use common::{event_data, Discriminator, Versioned};
+use hello_world::{
+    ContentAnchor, ContentAnchored, CooldownUpdated, HelloWorldAccount, HelloWorldInstruction, MessageFlagged,
+    MessageReaped, MessageUnflagged, MessageUpdated, ReplyPosted, SigningAuthorityUpdated, TokenGate,
+    TokenGateUpdated, UpdaterCooldown, WriterAdded, WriterRemoved,
+};
+use solana_program::pubkey::Pubkey;
+use test_utils::golden::{read_fixture, GoldenBytes};
+
+const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/layout.txt");
+
+fn bytes(value: &impl BorshSerialize) -> Vec<u8> {
+    value.try_to_vec().unwrap()
+}
+
+#[test]
+fn test_layouts_match_fixture() {
+    let account = Pubkey::new_from_array([1; 32]);
+    let author = Pubkey::new_from_array([2; 32]);
+    let writer = Pubkey::new_from_array([3; 32]);
+    let mint = Pubkey::new_from_array([4; 32]);
+    let token_gate = TokenGate { mint, min_balance: 5 };
+    let anchor = ContentAnchor { content_hash: [6; 32], content_len: 1_000, uri: "ipfs://x".to_string() };
+    let mut golden = GoldenBytes::new();
+
+    // Every optional field unset, then every one set
+    let state = HelloWorldAccount {
+        discriminator: HelloWorldAccount::DISCRIMINATOR,
+        version: HelloWorldAccount::VERSION,
+        is_initialized: true,
+        message: "gm".to_string(),
+        last_updater: writer,
+        expires_at_slot: None,
+        parent: None,
+        reply_count: 0,
+        author,
+        cooldown_slots: 0,
+        writers: vec![],
+        revision: 1,
+        created_at: 0x0102_0304_0506_0708,
+        updated_at: -1,
+        token_gate: None,
+        signing_authority: None,
+        content_anchor: None,
+        moderator: author,
+        hidden: false,
+    };
+    golden.add("HelloWorldAccount", bytes(&state));
+    golden.add(
+        "HelloWorldAccount/full",
+        bytes(&HelloWorldAccount {
+            message: String::new(),
+            expires_at_slot: Some(100),
+            parent: Some(account),
+            reply_count: 2,
+            cooldown_slots: 10,
+            writers: vec![writer],
+            token_gate: Some(token_gate.clone()),
+            signing_authority: Some(writer),
+            content_anchor: Some(anchor.clone()),
+            hidden: true,
+            ..state
+        }),
+    );
+    golden.add(
+        "UpdaterCooldown",
+        bytes(&UpdaterCooldown { discriminator: UpdaterCooldown::DISCRIMINATOR, last_update_slot: 100 }),
+    );
+
+    // Events as logged, behind their discriminators
+    let updated = MessageUpdated { account, updater: writer, old_len: 2, new_len: 3, slot: 100 };
+    let anchored = ContentAnchored { account, content_hash: [6; 32], uri: "ipfs://x".to_string(), content: vec![7] };
+    let replied = ReplyPosted { parent: account, reply: writer, reply_index: 0, author };
+    let gate_updated = TokenGateUpdated { account, token_gate: Some(token_gate.clone()) };
+    golden
+        .add("MessageUpdated", event_data(&updated).unwrap())
+        .add("ContentAnchored", event_data(&anchored).unwrap())
+        .add("MessageReaped", event_data(&MessageReaped { account, reaper: writer, bounty: 5_000 }).unwrap())
+        .add("ReplyPosted", event_data(&replied).unwrap())
+        .add("CooldownUpdated", event_data(&CooldownUpdated { account, cooldown_slots: 10 }).unwrap())
+        .add("WriterAdded", event_data(&WriterAdded { account, writer }).unwrap())
+        .add("WriterRemoved", event_data(&WriterRemoved { account, writer }).unwrap())
+        .add("TokenGateUpdated", event_data(&gate_updated).unwrap())
+        .add(
+            "SigningAuthorityUpdated",
+            event_data(&SigningAuthorityUpdated { account, signing_authority: None }).unwrap(),
+        )
+        .add("MessageFlagged", event_data(&MessageFlagged { account, moderator: author }).unwrap())
+        .add("MessageUnflagged", event_data(&MessageUnflagged { account, moderator: author }).unwrap());
+
+    let set_message = HelloWorldInstruction::SetMessage {
+        message: "gm".to_string(),
+        ttl_slots: Some(100),
+        expected_revision: None,
+        moderator: Some(author),
+    };
+    let instructions = [
+        ("SetMessage", set_message),
+        ("GetMessage", HelloWorldInstruction::GetMessage),
+        ("Reap", HelloWorldInstruction::Reap),
+        ("Reply", HelloWorldInstruction::Reply { message: "gm".to_string() }),
+        ("SetCooldown", HelloWorldInstruction::SetCooldown { cooldown_slots: 10 }),
+        ("AddWriter", HelloWorldInstruction::AddWriter { writer_key: writer }),
+        ("RemoveWriter", HelloWorldInstruction::RemoveWriter { writer_key: writer }),
+        ("SetTokenGate", HelloWorldInstruction::SetTokenGate { token_gate: Some(token_gate) }),
+        ("SetSigningAuthority", HelloWorldInstruction::SetSigningAuthority { signing_authority: Some(writer) }),
+        ("SetSignedMessage", HelloWorldInstruction::SetSignedMessage { message: "gm".to_string() }),
+        (
+            "SetAnchoredContent",
+            HelloWorldInstruction::SetAnchoredContent { content: vec![7], uri: anchor.uri },
+        ),
+        ("VerifyContent", HelloWorldInstruction::VerifyContent { content: vec![7] }),
+        ("FlagMessage", HelloWorldInstruction::FlagMessage),
+        ("UnflagMessage", HelloWorldInstruction::UnflagMessage),
+        ("Migrate", HelloWorldInstruction::Migrate),
+    ];
+    for (name, instruction) in instructions {
+        golden.add(&format!("HelloWorldInstruction::{}", name), bytes(&instruction));
+    }
+
+    golden.check(FIXTURE);
+}
+
+/// Instruction data in the fixture, as existing clients send it, still
+/// decodes to the variant of that name, and the next tag is unassigned
+#[test]
+fn test_fixture_instructions_decode() {
+    for (name, data) in read_fixture(FIXTURE) {
+        let Some(variant) = name.strip_prefix("HelloWorldInstruction::") else {
+            continue;
+        };
+        let instruction = HelloWorldInstruction::try_from_slice(&data).unwrap();
+        assert_eq!(format!("{:?}", instruction).split(' ').next(), Some(variant));
+        assert_eq!(instruction.tag(), data[0]);
+        assert_eq!(bytes(&instruction), data);
+    }
+
+    assert!(HelloWorldInstruction::try_from_slice(&[15]).is_err());
+}
@@ -0,0 +1,48 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+/**
+ * @title RateLimiter
+ * @dev Shared token-bucket throttling. Each calling contract gets its own
+ * bucket per user, keyed by msg.sender, and chooses its own policy.
+ */
+contract RateLimiter {
+    struct Bucket {
+        uint64 tokens;
+        uint64 lastRefill;
+        bool initialized;
+    }
+
+    // caller contract => user => bucket
+    mapping(address => mapping(address => Bucket)) public buckets;
+
+    event QuotaConsumed(address indexed caller, address indexed user, uint64 amount, uint64 remaining);
+
+    /**
+     * @dev Take `amount` tokens from `user`'s bucket under the calling contract,
+     * creating it full on first use. Reverts if not enough tokens are available.
+     */
+    function consumeQuota(address user, uint64 amount, uint64 capacity, uint64 refillPerSecond)
+        external
+        returns (uint64 remaining)
+    {
+        require(amount > 0, "Zero amount");
+        require(capacity > 0 && refillPerSecond > 0, "Invalid policy");
+
+        Bucket storage bucket = buckets[msg.sender][user];
+        if (!bucket.initialized) {
+            bucket.initialized = true;
+            bucket.tokens = capacity;
+        } else {
+            uint256 refilled = uint256(bucket.tokens) + (block.timestamp - bucket.lastRefill) * refillPerSecond;
+            bucket.tokens = uint64(refilled > capacity ? capacity : refilled);
+        }
+        bucket.lastRefill = uint64(block.timestamp);
+
+        require(bucket.tokens >= amount, "Quota exceeded");
+        bucket.tokens -= amount;
+
+        emit QuotaConsumed(msg.sender, user, amount, bucket.tokens);
+        return bucket.tokens;
+    }
+}
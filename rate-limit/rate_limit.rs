@@ -0,0 +1,350 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint,
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    msg,
+    program::{invoke_signed, set_return_data},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction, system_program,
+    sysvar::Sysvar,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// Seed a caller program signs with when consuming quota: ["rate-limit"],
+/// derived under the *caller's* program id
+pub const CALLER_AUTHORITY_SEED: &[u8] = b"rate-limit";
+
+/// Seed prefix for bucket PDAs: ["bucket", caller_program, user]
+pub const BUCKET_SEED: &[u8] = b"bucket";
+
+/// Errors returned by the program as `ProgramError::Custom` codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateLimitError {
+    /// Not enough tokens left in the bucket
+    QuotaExceeded = 0,
+    /// Capacity or refill rate is zero
+    InvalidPolicy = 1,
+    /// The signer is not the calling program's rate-limit authority
+    UnauthorizedCaller = 2,
+    /// Amount is zero
+    ZeroAmount = 3,
+    /// Bucket account does not match the caller program and user
+    WrongBucket = 4,
+}
+
+impl From<RateLimitError> for ProgramError {
+    fn from(e: RateLimitError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+/// Throttling policy chosen by the calling program, sent with every call so
+/// a program can retune its limits without a migration
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Policy {
+    /// Most tokens a bucket can hold, i.e. the largest burst
+    pub capacity: u64,
+    /// Tokens added back per second
+    pub refill_per_second: u64,
+}
+
+/// Token bucket for one (caller program, user) pair
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct Bucket {
+    pub is_initialized: bool,
+    pub caller_program: Pubkey,
+    pub user: Pubkey,
+    pub policy: Policy,
+    pub tokens: u64,
+    /// Unix timestamp `tokens` was last brought up to date
+    pub last_refill: i64,
+    pub bump: u8,
+}
+
+impl Bucket {
+    pub const SIZE: usize = 1 // is_initialized
+        + 32 // caller_program
+        + 32 // user
+        + 16 // policy
+        + 8 // tokens
+        + 8 // last_refill
+        + 1; // bump
+
+    /// Add the tokens earned since `last_refill` under `policy`, capped at its capacity
+    pub fn refill(&mut self, policy: Policy, now: i64) {
+        let elapsed = now.saturating_sub(self.last_refill).max(0) as u64;
+        self.tokens = self
+            .tokens
+            .saturating_add(elapsed.saturating_mul(policy.refill_per_second))
+            .min(policy.capacity);
+        self.policy = policy;
+        self.last_refill = now;
+    }
+
+    /// Refill, then take `amount` tokens or fail without changing the balance
+    pub fn consume(&mut self, policy: Policy, amount: u64, now: i64) -> Result<(), RateLimitError> {
+        self.refill(policy, now);
+        self.tokens = self.tokens.checked_sub(amount).ok_or(RateLimitError::QuotaExceeded)?;
+        Ok(())
+    }
+}
+
+/// The PDA a caller program signs with to consume quota on its users' behalf
+pub fn caller_authority(caller_program: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CALLER_AUTHORITY_SEED], caller_program)
+}
+
+/// Address of the bucket for `user` under `caller_program`
+pub fn bucket_address(program_id: &Pubkey, caller_program: &Pubkey, user: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[BUCKET_SEED, caller_program.as_ref(), user.as_ref()], program_id)
+}
+
+/// Build a `ConsumeQuota` instruction; the caller invokes it signed with
+/// `[CALLER_AUTHORITY_SEED, &[bump]]`
+pub fn consume_quota_instruction(
+    program_id: &Pubkey,
+    caller_program: &Pubkey,
+    user: &Pubkey,
+    payer: &Pubkey,
+    amount: u64,
+    policy: Policy,
+) -> Result<Instruction, ProgramError> {
+    let (authority, _) = caller_authority(caller_program);
+    let (bucket, _) = bucket_address(program_id, caller_program, user);
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(authority, true),
+            AccountMeta::new_readonly(*caller_program, false),
+            AccountMeta::new_readonly(*user, false),
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(bucket, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: RateLimitInstruction::ConsumeQuota { amount, policy }.try_to_vec()?,
+    })
+}
+
+entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = RateLimitInstruction::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        RateLimitInstruction::ConsumeQuota { amount, policy } => consume_quota(program_id, accounts, amount, policy),
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub enum RateLimitInstruction {
+    /// Take `amount` tokens from the user's bucket, creating it full on first use.
+    /// Meant to be called via CPI; the remaining balance is set as return data (u64 LE).
+    /// Accounts: [signer] caller authority PDA, [] caller program, [] user,
+    ///           [writable, signer] payer, [writable] bucket PDA, [] system program
+    ConsumeQuota { amount: u64, policy: Policy },
+}
+
+fn consume_quota(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64, policy: Policy) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let authority = next_account_info(accounts_iter)?;
+    let caller_program = next_account_info(accounts_iter)?;
+    let user = next_account_info(accounts_iter)?;
+    let payer = next_account_info(accounts_iter)?;
+    let bucket_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if amount == 0 {
+        return Err(RateLimitError::ZeroAmount.into());
+    }
+
+    if policy.capacity == 0 || policy.refill_per_second == 0 {
+        return Err(RateLimitError::InvalidPolicy.into());
+    }
+
+    // Only the caller program can produce this signature, so buckets are
+    // namespaced per program and users cannot drain each other's quota
+    let (expected_authority, _) = caller_authority(caller_program.key);
+    if !authority.is_signer || expected_authority != *authority.key {
+        msg!("Signer is not the rate-limit authority of {}", caller_program.key);
+        return Err(RateLimitError::UnauthorizedCaller.into());
+    }
+
+    let (expected_bucket, bump) = bucket_address(program_id, caller_program.key, user.key);
+    if expected_bucket != *bucket_account.key {
+        msg!("Bucket account does not match the expected PDA");
+        return Err(RateLimitError::WrongBucket.into());
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+
+    let mut bucket = if bucket_account.lamports() == 0 {
+        invoke_signed(
+            &system_instruction::create_account(
+                payer.key,
+                bucket_account.key,
+                Rent::get()?.minimum_balance(Bucket::SIZE),
+                Bucket::SIZE as u64,
+                program_id,
+            ),
+            &[payer.clone(), bucket_account.clone(), system_program.clone()],
+            &[&[BUCKET_SEED, caller_program.key.as_ref(), user.key.as_ref(), &[bump]]],
+        )?;
+
+        Bucket {
+            is_initialized: true,
+            caller_program: *caller_program.key,
+            user: *user.key,
+            policy,
+            tokens: policy.capacity,
+            last_refill: now,
+            bump,
+        }
+    } else {
+        load_bucket(program_id, bucket_account)?
+    };
+
+    if let Err(e) = bucket.consume(policy, amount, now) {
+        msg!("{} requested {} tokens but only {} are available", user.key, amount, bucket.tokens);
+        return Err(e.into());
+    }
+
+    bucket.serialize(&mut &mut bucket_account.data.borrow_mut()[..])?;
+
+    set_return_data(&bucket.tokens.to_le_bytes());
+
+    msg!("{} consumed {} tokens, {} remaining", user.key, amount, bucket.tokens);
+
+    Ok(())
+}
+
+fn load_bucket(program_id: &Pubkey, bucket_account: &AccountInfo) -> Result<Bucket, ProgramError> {
+    if bucket_account.owner != program_id {
+        msg!("Bucket account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let bucket = Bucket::try_from_slice(&bucket_account.data.borrow())?;
+
+    if !bucket.is_initialized {
+        return Err(ProgramError::UninitializedAccount);
+    }
+
+    Ok(bucket)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_program::{clock::Epoch, entrypoint::SUCCESS, program_stubs};
+
+    /// Serves Clock at a fixed time so handlers can run off-chain
+    struct TestSyscallStubs {
+        unix_timestamp: i64,
+    }
+
+    impl program_stubs::SyscallStubs for TestSyscallStubs {
+        fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+            unsafe {
+                *(var_addr as *mut Clock) = Clock {
+                    unix_timestamp: self.unix_timestamp,
+                    ..Clock::default()
+                };
+            }
+            SUCCESS
+        }
+    }
+
+    const POLICY: Policy = Policy {
+        capacity: 10,
+        refill_per_second: 2,
+    };
+
+    fn bucket(tokens: u64, last_refill: i64) -> Bucket {
+        Bucket {
+            is_initialized: true,
+            caller_program: Pubkey::new_unique(),
+            user: Pubkey::new_unique(),
+            policy: POLICY,
+            tokens,
+            last_refill,
+            bump: 255,
+        }
+    }
+
+    #[test]
+    fn test_bucket_refills_up_to_capacity() {
+        let mut b = bucket(10, 0);
+        b.consume(POLICY, 8, 0).unwrap();
+        assert_eq!(b.tokens, 2);
+        // A failed request leaves the balance alone
+        assert_eq!(b.consume(POLICY, 3, 0), Err(RateLimitError::QuotaExceeded));
+        assert_eq!(b.tokens, 2);
+
+        b.consume(POLICY, 3, 1).unwrap();
+        assert_eq!(b.tokens, 1);
+
+        // Idle time never fills past capacity
+        b.refill(POLICY, 1_000);
+        assert_eq!(b.tokens, 10);
+
+        // A smaller capacity takes effect on the next call
+        let tighter = Policy { capacity: 4, refill_per_second: 1 };
+        b.consume(tighter, 1, 1_000).unwrap();
+        assert_eq!(b.tokens, 3);
+        assert_eq!(b.policy, tighter);
+
+        assert_eq!(b.try_to_vec().unwrap().len(), Bucket::SIZE);
+    }
+
+    #[test]
+    fn test_consume_requires_caller_signature() {
+        let program_id = Pubkey::new_unique();
+        let caller_program = Pubkey::new_unique();
+        let user = Pubkey::new_unique();
+        let (authority, _) = caller_authority(&caller_program);
+        let (bucket_key, _) = bucket_address(&program_id, &caller_program, &user);
+
+        let mut existing = bucket(3, 100);
+        existing.caller_program = caller_program;
+        existing.user = user;
+
+        let keys = [authority, caller_program, user, Pubkey::new_unique(), bucket_key, system_program::id()];
+        let mut lamports: Vec<u64> = vec![0, 0, 0, 1_000_000, 1_000_000, 0];
+        let mut data: Vec<Vec<u8>> = vec![vec![], vec![], vec![], vec![], existing.try_to_vec().unwrap(), vec![]];
+
+        let mut accounts: Vec<AccountInfo> = keys
+            .iter()
+            .zip(lamports.iter_mut())
+            .zip(data.iter_mut())
+            .enumerate()
+            .map(|(i, ((key, lamports), data))| {
+                AccountInfo::new(key, i == 0, true, lamports, data, &program_id, false, Epoch::default())
+            })
+            .collect();
+
+        program_stubs::set_syscall_stubs(Box::new(TestSyscallStubs { unix_timestamp: 100 }));
+        let consume = |amount| RateLimitInstruction::ConsumeQuota { amount, policy: POLICY }.try_to_vec().unwrap();
+
+        process_instruction(&program_id, &accounts, &consume(2)).unwrap();
+        let result = process_instruction(&program_id, &accounts, &consume(2));
+        assert_eq!(result, Err(RateLimitError::QuotaExceeded.into()));
+
+        // A user cannot spend quota without the caller program's signature
+        accounts[0].is_signer = false;
+        let result = process_instruction(&program_id, &accounts, &consume(1));
+        assert_eq!(result, Err(RateLimitError::UnauthorizedCaller.into()));
+
+        let stored = Bucket::try_from_slice(&accounts[4].data.borrow()).unwrap();
+        assert_eq!(stored.tokens, 1);
+    }
+}
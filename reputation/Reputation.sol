@@ -0,0 +1,85 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+/**
+ * @title Reputation
+ * @dev Registered raters adjust signed, bounded scores. Scores decay toward
+ * zero by a fixed amount per day without ratings, and each rater has an
+ * hourly rate limit.
+ */
+contract Reputation {
+    int256 public constant MIN_SCORE = -1000;
+    int256 public constant MAX_SCORE = 1000;
+    int256 public constant MAX_DELTA = 100;
+    int256 public constant DECAY_PER_DAY = 10;
+    uint256 public constant RATE_WINDOW = 1 hours;
+    uint256 public constant MAX_RATINGS_PER_WINDOW = 10;
+
+    struct Score {
+        int256 score;
+        uint256 lastUpdated;
+    }
+
+    struct RaterUsage {
+        uint256 windowStart;
+        uint256 ratingsInWindow;
+    }
+
+    address public immutable admin;
+    mapping(address => bool) public isRater;
+    mapping(address => RaterUsage) public usage;
+    mapping(address => Score) public scores;
+
+    event RaterRegistered(address indexed rater);
+    event RaterRemoved(address indexed rater);
+    event Rated(address indexed rater, address indexed subject, int256 delta, int256 newScore);
+
+    constructor() {
+        admin = msg.sender;
+    }
+
+    function registerRater(address rater) external {
+        require(msg.sender == admin, "Only admin");
+        require(!isRater[rater], "Already registered");
+        isRater[rater] = true;
+        emit RaterRegistered(rater);
+    }
+
+    function removeRater(address rater) external {
+        require(msg.sender == admin, "Only admin");
+        require(isRater[rater], "Not a rater");
+        isRater[rater] = false;
+        delete usage[rater];
+        emit RaterRemoved(rater);
+    }
+
+    /// Score after decay, moving toward zero but never past it
+    function currentScore(address subject) public view returns (int256) {
+        Score memory s = scores[subject];
+        int256 decay = int256((block.timestamp - s.lastUpdated) / 1 days) * DECAY_PER_DAY;
+        if (s.score > 0) {
+            return s.score > decay ? s.score - decay : int256(0);
+        }
+        return -s.score > decay ? s.score + decay : int256(0);
+    }
+
+    function rate(address subject, int256 delta) external {
+        require(isRater[msg.sender], "Not a rater");
+        require(delta != 0 && delta >= -MAX_DELTA && delta <= MAX_DELTA, "Invalid delta");
+
+        RaterUsage storage u = usage[msg.sender];
+        if (block.timestamp >= u.windowStart + RATE_WINDOW) {
+            u.windowStart = block.timestamp;
+            u.ratingsInWindow = 0;
+        }
+        require(u.ratingsInWindow < MAX_RATINGS_PER_WINDOW, "Rate limited");
+        u.ratingsInWindow++;
+
+        int256 next = currentScore(subject) + delta;
+        if (next > MAX_SCORE) next = MAX_SCORE;
+        if (next < MIN_SCORE) next = MIN_SCORE;
+
+        scores[subject] = Score(next, block.timestamp);
+        emit Rated(msg.sender, subject, delta, next);
+    }
+}
@@ -0,0 +1,568 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint,
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// Seed prefix for board PDAs: ["board", admin]
+pub const BOARD_SEED: &[u8] = b"board";
+
+/// Seed prefix for rater PDAs: ["rater", board, rater]
+pub const RATER_SEED: &[u8] = b"rater";
+
+/// Seed prefix for subject score PDAs: ["subject", board, subject]
+pub const SUBJECT_SEED: &[u8] = b"subject";
+
+/// Scores are clamped to `MIN_SCORE..=MAX_SCORE`
+pub const MIN_SCORE: i64 = -1_000;
+pub const MAX_SCORE: i64 = 1_000;
+
+/// Largest change a single rating can make, up or down
+pub const MAX_DELTA: i64 = 100;
+
+/// Points a score moves toward zero per full day without ratings
+pub const DECAY_PER_DAY: i64 = 10;
+
+pub const SECONDS_PER_DAY: i64 = 86_400;
+
+/// Length of a rater's rate-limit window
+pub const RATE_WINDOW_SECONDS: i64 = 3_600;
+
+/// Ratings each rater may submit per window
+pub const MAX_RATINGS_PER_WINDOW: u32 = 10;
+
+/// Size of a `BoardAccount`
+pub const BOARD_ACCOUNT_SIZE: usize = 1 // is_initialized
+    + 32 // admin
+    + 1; // bump
+
+/// Size of a `RaterAccount`
+pub const RATER_ACCOUNT_SIZE: usize = 1 // is_initialized
+    + 32 // board
+    + 32 // rater
+    + 8 // window_start
+    + 4 // ratings_in_window
+    + 1; // bump
+
+/// Size of a `SubjectAccount`
+pub const SUBJECT_ACCOUNT_SIZE: usize = 1 // is_initialized
+    + 32 // board
+    + 32 // subject
+    + 8 // score
+    + 8 // last_updated
+    + 1; // bump
+
+/// Errors returned by the program as `ProgramError::Custom` codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReputationError {
+    /// The signer is not the admin, or not a registered rater
+    Unauthorized = 0,
+    /// Delta is zero or larger than `MAX_DELTA`
+    InvalidDelta = 1,
+    /// The rater has used up its ratings for the current window
+    RateLimited = 2,
+    /// The board, rater or subject has not been initialized
+    NotInitialized = 3,
+}
+
+impl From<ReputationError> for ProgramError {
+    fn from(e: ReputationError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+/// A reputation system run by `admin`, who registers raters
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct BoardAccount {
+    pub is_initialized: bool,
+    pub admin: Pubkey,
+    pub bump: u8,
+}
+
+/// A registered rater and its usage in the current window
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct RaterAccount {
+    pub is_initialized: bool,
+    pub board: Pubkey,
+    pub rater: Pubkey,
+    /// Unix timestamp at which the current window opened
+    pub window_start: i64,
+    pub ratings_in_window: u32,
+    pub bump: u8,
+}
+
+impl RaterAccount {
+    /// Count one rating at `now`, opening a new window if the last one has passed
+    pub fn try_rate(&mut self, now: i64) -> Result<(), ReputationError> {
+        if now >= self.window_start.saturating_add(RATE_WINDOW_SECONDS) {
+            self.window_start = now;
+            self.ratings_in_window = 0;
+        }
+
+        if self.ratings_in_window >= MAX_RATINGS_PER_WINDOW {
+            return Err(ReputationError::RateLimited);
+        }
+        self.ratings_in_window += 1;
+
+        Ok(())
+    }
+}
+
+/// One subject's score as of `last_updated`
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct SubjectAccount {
+    pub is_initialized: bool,
+    pub board: Pubkey,
+    pub subject: Pubkey,
+    pub score: i64,
+    /// Unix timestamp of the last rating; decay is measured from here
+    pub last_updated: i64,
+    pub bump: u8,
+}
+
+impl SubjectAccount {
+    /// The score at `now`, after decay. Clients use this to read a live score
+    pub fn current_score(&self, now: i64) -> i64 {
+        decayed_score(self.score, now.saturating_sub(self.last_updated))
+    }
+}
+
+/// Move `score` toward zero by `DECAY_PER_DAY` per full day elapsed, never
+/// past zero, so both good and bad reputations fade
+pub fn decayed_score(score: i64, elapsed_seconds: i64) -> i64 {
+    let days = elapsed_seconds.max(0) / SECONDS_PER_DAY;
+    let decay = days.saturating_mul(DECAY_PER_DAY);
+    if score > 0 {
+        score.saturating_sub(decay).max(0)
+    } else {
+        score.saturating_add(decay).min(0)
+    }
+}
+
+/// Add `delta` to `score`, clamped to the score bounds
+pub fn apply_delta(score: i64, delta: i64) -> i64 {
+    score.saturating_add(delta).clamp(MIN_SCORE, MAX_SCORE)
+}
+
+entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = ReputationInstruction::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        ReputationInstruction::InitializeBoard => initialize_board(program_id, accounts),
+        ReputationInstruction::RegisterRater { rater } => register_rater(program_id, accounts, rater),
+        ReputationInstruction::RemoveRater => remove_rater(program_id, accounts),
+        ReputationInstruction::Rate { delta } => rate(program_id, accounts, delta),
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub enum ReputationInstruction {
+    /// Create a board with the signer as admin
+    /// Accounts: [writable, signer] admin, [writable] board PDA, [] system program
+    InitializeBoard,
+    /// Register `rater`, creating its rater PDA
+    /// Accounts: [writable, signer] admin, [] board PDA, [writable] rater PDA, [] system program
+    RegisterRater { rater: Pubkey },
+    /// Deregister a rater, closing its PDA and refunding the admin
+    /// Accounts: [writable, signer] admin, [] board PDA, [writable] rater PDA
+    RemoveRater,
+    /// Adjust a subject's score by `delta` (-MAX_DELTA..=MAX_DELTA, non-zero),
+    /// creating the subject's PDA on its first rating
+    /// Accounts: [writable, signer] rater, [] board PDA, [writable] rater PDA, [] subject,
+    /// [writable] subject PDA, [] system program
+    Rate { delta: i64 },
+}
+
+fn initialize_board(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let admin = next_account_info(accounts_iter)?;
+    let board_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !admin.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let (expected_board, bump) = Pubkey::find_program_address(&[BOARD_SEED, admin.key.as_ref()], program_id);
+    if expected_board != *board_account.key {
+        msg!("Board account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    invoke_signed(
+        &system_instruction::create_account(
+            admin.key,
+            board_account.key,
+            Rent::get()?.minimum_balance(BOARD_ACCOUNT_SIZE),
+            BOARD_ACCOUNT_SIZE as u64,
+            program_id,
+        ),
+        &[admin.clone(), board_account.clone(), system_program.clone()],
+        &[&[BOARD_SEED, admin.key.as_ref(), &[bump]]],
+    )?;
+
+    let board = BoardAccount {
+        is_initialized: true,
+        admin: *admin.key,
+        bump,
+    };
+    board.serialize(&mut &mut board_account.data.borrow_mut()[..])?;
+
+    msg!("Board {} created", board_account.key);
+
+    Ok(())
+}
+
+fn register_rater(program_id: &Pubkey, accounts: &[AccountInfo], rater: Pubkey) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let admin = next_account_info(accounts_iter)?;
+    let board_account = next_account_info(accounts_iter)?;
+    let rater_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    load_board_as_admin(program_id, board_account, admin)?;
+
+    let (expected_rater, bump) = Pubkey::find_program_address(
+        &[RATER_SEED, board_account.key.as_ref(), rater.as_ref()],
+        program_id,
+    );
+    if expected_rater != *rater_account.key {
+        msg!("Rater account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    // Fails if the rater is already registered
+    invoke_signed(
+        &system_instruction::create_account(
+            admin.key,
+            rater_account.key,
+            Rent::get()?.minimum_balance(RATER_ACCOUNT_SIZE),
+            RATER_ACCOUNT_SIZE as u64,
+            program_id,
+        ),
+        &[admin.clone(), rater_account.clone(), system_program.clone()],
+        &[&[RATER_SEED, board_account.key.as_ref(), rater.as_ref(), &[bump]]],
+    )?;
+
+    let rater_state = RaterAccount {
+        is_initialized: true,
+        board: *board_account.key,
+        rater,
+        window_start: i64::MIN,
+        ratings_in_window: 0,
+        bump,
+    };
+    rater_state.serialize(&mut &mut rater_account.data.borrow_mut()[..])?;
+
+    msg!("Rater {} registered", rater);
+
+    Ok(())
+}
+
+fn remove_rater(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let admin = next_account_info(accounts_iter)?;
+    let board_account = next_account_info(accounts_iter)?;
+    let rater_account = next_account_info(accounts_iter)?;
+
+    load_board_as_admin(program_id, board_account, admin)?;
+    let rater = load_rater(program_id, board_account, rater_account)?;
+
+    // Close the rater PDA: move its rent to the admin and clear its data
+    let rater_lamports = rater_account.lamports();
+    **rater_account.try_borrow_mut_lamports()? = 0;
+    **admin.try_borrow_mut_lamports()? = admin
+        .lamports()
+        .checked_add(rater_lamports)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    rater_account.data.borrow_mut().fill(0);
+
+    msg!("Rater {} removed", rater.rater);
+
+    Ok(())
+}
+
+fn rate(program_id: &Pubkey, accounts: &[AccountInfo], delta: i64) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let rater = next_account_info(accounts_iter)?;
+    let board_account = next_account_info(accounts_iter)?;
+    let rater_account = next_account_info(accounts_iter)?;
+    let subject = next_account_info(accounts_iter)?;
+    let subject_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !rater.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if delta == 0 || delta.unsigned_abs() > MAX_DELTA as u64 {
+        msg!("Delta must be non-zero and at most {} either way", MAX_DELTA);
+        return Err(ReputationError::InvalidDelta.into());
+    }
+
+    load_board(program_id, board_account)?;
+    let mut rater_state = load_rater(program_id, board_account, rater_account)?;
+    if rater_state.rater != *rater.key {
+        msg!("Signer is not the registered rater");
+        return Err(ReputationError::Unauthorized.into());
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    if let Err(e) = rater_state.try_rate(now) {
+        msg!(
+            "Rater used {} of {} ratings; window resets at {}",
+            rater_state.ratings_in_window,
+            MAX_RATINGS_PER_WINDOW,
+            rater_state.window_start.saturating_add(RATE_WINDOW_SECONDS)
+        );
+        return Err(e.into());
+    }
+    rater_state.serialize(&mut &mut rater_account.data.borrow_mut()[..])?;
+
+    let (expected_subject, bump) = Pubkey::find_program_address(
+        &[SUBJECT_SEED, board_account.key.as_ref(), subject.key.as_ref()],
+        program_id,
+    );
+    if expected_subject != *subject_account.key {
+        msg!("Subject account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    // First rating of this subject: create its score account
+    let mut subject_state = if subject_account.lamports() == 0 {
+        invoke_signed(
+            &system_instruction::create_account(
+                rater.key,
+                subject_account.key,
+                Rent::get()?.minimum_balance(SUBJECT_ACCOUNT_SIZE),
+                SUBJECT_ACCOUNT_SIZE as u64,
+                program_id,
+            ),
+            &[rater.clone(), subject_account.clone(), system_program.clone()],
+            &[&[SUBJECT_SEED, board_account.key.as_ref(), subject.key.as_ref(), &[bump]]],
+        )?;
+        SubjectAccount {
+            is_initialized: true,
+            board: *board_account.key,
+            subject: *subject.key,
+            score: 0,
+            last_updated: now,
+            bump,
+        }
+    } else {
+        if subject_account.owner != program_id {
+            msg!("Subject account does not have the correct program id");
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        SubjectAccount::try_from_slice(&subject_account.data.borrow())?
+    };
+
+    // Settle decay up to now before applying the new rating
+    let previous = subject_state.current_score(now);
+    subject_state.score = apply_delta(previous, delta);
+    subject_state.last_updated = now;
+    subject_state.serialize(&mut &mut subject_account.data.borrow_mut()[..])?;
+
+    msg!("Score of {} changed from {} to {}", subject.key, previous, subject_state.score);
+
+    Ok(())
+}
+
+fn load_board(program_id: &Pubkey, board_account: &AccountInfo) -> Result<BoardAccount, ProgramError> {
+    if board_account.owner != program_id {
+        msg!("Board account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let board = BoardAccount::try_from_slice(&board_account.data.borrow())?;
+    if !board.is_initialized {
+        return Err(ReputationError::NotInitialized.into());
+    }
+
+    Ok(board)
+}
+
+fn load_board_as_admin(
+    program_id: &Pubkey,
+    board_account: &AccountInfo,
+    admin: &AccountInfo,
+) -> Result<BoardAccount, ProgramError> {
+    if !admin.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let board = load_board(program_id, board_account)?;
+
+    if board.admin != *admin.key {
+        msg!("Only the admin can manage raters");
+        return Err(ReputationError::Unauthorized.into());
+    }
+
+    Ok(board)
+}
+
+fn load_rater(
+    program_id: &Pubkey,
+    board_account: &AccountInfo,
+    rater_account: &AccountInfo,
+) -> Result<RaterAccount, ProgramError> {
+    if rater_account.owner != program_id {
+        msg!("Rater account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let rater = RaterAccount::try_from_slice(&rater_account.data.borrow())?;
+    if !rater.is_initialized {
+        return Err(ReputationError::NotInitialized.into());
+    }
+
+    // A rater registered on another board has no say here
+    if rater.board != *board_account.key {
+        msg!("Rater is registered on a different board");
+        return Err(ReputationError::Unauthorized.into());
+    }
+
+    Ok(rater)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_program::{clock::Epoch, entrypoint::SUCCESS, program_stubs};
+
+    /// Serves a Clock sysvar at a fixed time so `rate` can run off-chain
+    struct TestSyscallStubs {
+        unix_timestamp: i64,
+    }
+
+    impl program_stubs::SyscallStubs for TestSyscallStubs {
+        fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+            unsafe {
+                *(var_addr as *mut Clock) = Clock {
+                    unix_timestamp: self.unix_timestamp,
+                    ..Clock::default()
+                };
+            }
+            SUCCESS
+        }
+    }
+
+    #[test]
+    fn test_decay_bounds_and_rate_limit() {
+        // Decay moves toward zero from either side, by whole days only
+        assert_eq!(decayed_score(500, SECONDS_PER_DAY - 1), 500);
+        assert_eq!(decayed_score(500, 3 * SECONDS_PER_DAY), 470);
+        assert_eq!(decayed_score(-500, 3 * SECONDS_PER_DAY), -470);
+        assert_eq!(decayed_score(15, 5 * SECONDS_PER_DAY), 0);
+        assert_eq!(decayed_score(-15, 5 * SECONDS_PER_DAY), 0);
+        assert_eq!(decayed_score(500, i64::MAX), 0);
+        assert_eq!(decayed_score(500, -SECONDS_PER_DAY), 500);
+
+        assert_eq!(apply_delta(950, MAX_DELTA), MAX_SCORE);
+        assert_eq!(apply_delta(-950, -MAX_DELTA), MIN_SCORE);
+        assert_eq!(apply_delta(20, -50), -30);
+
+        let mut rater = RaterAccount {
+            is_initialized: true,
+            board: Pubkey::new_unique(),
+            rater: Pubkey::new_unique(),
+            window_start: i64::MIN,
+            ratings_in_window: 0,
+            bump: 255,
+        };
+        let start = 1_700_000_000;
+        for i in 0..MAX_RATINGS_PER_WINDOW {
+            rater.try_rate(start + i as i64).unwrap();
+        }
+        assert_eq!(rater.try_rate(start + 60), Err(ReputationError::RateLimited));
+        rater.try_rate(start + RATE_WINDOW_SECONDS).unwrap();
+        assert_eq!(rater.ratings_in_window, 1);
+    }
+
+    #[test]
+    fn test_rate_applies_decay_then_delta() {
+        let now = 1_700_000_000;
+        program_stubs::set_syscall_stubs(Box::new(TestSyscallStubs { unix_timestamp: now }));
+
+        let program_id = Pubkey::new_unique();
+        let rater_key = Pubkey::new_unique();
+        let board_key = Pubkey::new_unique();
+        let subject_key = Pubkey::new_unique();
+        let (rater_pda, _) =
+            Pubkey::find_program_address(&[RATER_SEED, board_key.as_ref(), rater_key.as_ref()], &program_id);
+        let (subject_pda, subject_bump) =
+            Pubkey::find_program_address(&[SUBJECT_SEED, board_key.as_ref(), subject_key.as_ref()], &program_id);
+
+        let board = BoardAccount {
+            is_initialized: true,
+            admin: Pubkey::new_unique(),
+            bump: 255,
+        };
+        let rater = RaterAccount {
+            is_initialized: true,
+            board: board_key,
+            rater: rater_key,
+            window_start: i64::MIN,
+            ratings_in_window: 0,
+            bump: 255,
+        };
+        // 200 points, last rated ten days ago
+        let subject = SubjectAccount {
+            is_initialized: true,
+            board: board_key,
+            subject: subject_key,
+            score: 200,
+            last_updated: now - 10 * SECONDS_PER_DAY,
+            bump: subject_bump,
+        };
+
+        let keys = [rater_key, board_key, rater_pda, subject_key, subject_pda, Pubkey::default()];
+        let mut lamports: Vec<u64> = vec![10_000; keys.len()];
+        let mut data: Vec<Vec<u8>> = vec![vec![]; keys.len()];
+        data[1] = board.try_to_vec().unwrap();
+        data[2] = rater.try_to_vec().unwrap();
+        data[4] = subject.try_to_vec().unwrap();
+
+        let accounts: Vec<AccountInfo> = keys
+            .iter()
+            .zip(lamports.iter_mut())
+            .zip(data.iter_mut())
+            .enumerate()
+            .map(|(i, ((key, lamports), data))| {
+                AccountInfo::new(key, i == 0, true, lamports, data, &program_id, false, Epoch::default())
+            })
+            .collect();
+
+        let instruction_data = ReputationInstruction::Rate { delta: -50 }.try_to_vec().unwrap();
+        process_instruction(&program_id, &accounts, &instruction_data).unwrap();
+
+        // 200 decays to 100, then -50
+        let subject = SubjectAccount::try_from_slice(&accounts[4].data.borrow()).unwrap();
+        assert_eq!(subject.score, 50);
+        assert_eq!(subject.last_updated, now);
+
+        let rater = RaterAccount::try_from_slice(&accounts[2].data.borrow()).unwrap();
+        assert_eq!(rater.ratings_in_window, 1);
+        assert_eq!(rater.window_start, now);
+
+        let instruction_data = ReputationInstruction::Rate { delta: MAX_DELTA + 1 }.try_to_vec().unwrap();
+        let result = process_instruction(&program_id, &accounts, &instruction_data);
+        assert_eq!(result, Err(ReputationError::InvalidDelta.into()));
+    }
+}
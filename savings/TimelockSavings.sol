@@ -0,0 +1,88 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+/**
+ * @title TimelockSavings
+ * @dev Per-owner savings vault with two-phase withdrawals. A withdrawal is
+ * requested first and can only be executed once the owner's delay has passed;
+ * until then it can be cancelled. The delay is fixed when the vault is opened.
+ */
+contract TimelockSavings {
+    uint256 public constant MIN_DELAY = 1 hours;
+    uint256 public constant MAX_DELAY = 30 days;
+
+    struct PendingWithdrawal {
+        uint256 amount;
+        address payable recipient;
+        uint256 unlockAt;
+    }
+
+    struct Vault {
+        bool initialized;
+        uint256 delay;
+        uint256 balance;
+        PendingWithdrawal pending;
+    }
+
+    mapping(address => Vault) public vaults;
+
+    event VaultOpened(address indexed owner, uint256 delay);
+    event Deposited(address indexed owner, address indexed from, uint256 amount);
+    event WithdrawalRequested(address indexed owner, address indexed recipient, uint256 amount, uint256 unlockAt);
+    event WithdrawalCancelled(address indexed owner, uint256 amount);
+    event Withdrawn(address indexed owner, address indexed recipient, uint256 amount);
+
+    function open(uint256 delay) external {
+        Vault storage vault = vaults[msg.sender];
+        require(!vault.initialized, "Already opened");
+        require(delay >= MIN_DELAY && delay <= MAX_DELAY, "Invalid delay");
+
+        vault.initialized = true;
+        vault.delay = delay;
+        emit VaultOpened(msg.sender, delay);
+    }
+
+    function deposit(address owner) external payable {
+        Vault storage vault = vaults[owner];
+        require(vault.initialized, "No vault");
+        require(msg.value > 0, "Zero amount");
+
+        vault.balance += msg.value;
+        emit Deposited(owner, msg.sender, msg.value);
+    }
+
+    function requestWithdrawal(uint256 amount, address payable recipient) external {
+        Vault storage vault = vaults[msg.sender];
+        require(vault.initialized, "No vault");
+        require(amount > 0, "Zero amount");
+        require(vault.pending.amount == 0, "Withdrawal pending");
+
+        uint256 unlockAt = block.timestamp + vault.delay;
+        vault.pending = PendingWithdrawal(amount, recipient, unlockAt);
+        emit WithdrawalRequested(msg.sender, recipient, amount, unlockAt);
+    }
+
+    function cancelWithdrawal() external {
+        Vault storage vault = vaults[msg.sender];
+        uint256 amount = vault.pending.amount;
+        require(amount > 0, "No pending withdrawal");
+
+        delete vault.pending;
+        emit WithdrawalCancelled(msg.sender, amount);
+    }
+
+    function executeWithdrawal() external {
+        Vault storage vault = vaults[msg.sender];
+        PendingWithdrawal memory pending = vault.pending;
+        require(pending.amount > 0, "No pending withdrawal");
+        require(block.timestamp >= pending.unlockAt, "Delay not elapsed");
+        require(pending.amount <= vault.balance, "Insufficient funds");
+
+        delete vault.pending;
+        vault.balance -= pending.amount;
+        emit Withdrawn(msg.sender, pending.recipient, pending.amount);
+
+        (bool success, ) = pending.recipient.call{value: pending.amount}("");
+        require(success, "Transfer failed");
+    }
+}
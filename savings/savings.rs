@@ -0,0 +1,442 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint,
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// Seed prefix for savings vault PDAs: ["savings", owner]
+pub const SAVINGS_SEED: &[u8] = b"savings";
+
+/// Shortest allowed withdrawal delay (1 hour)
+pub const MIN_DELAY_SECONDS: i64 = 3_600;
+
+/// Longest allowed withdrawal delay (30 days)
+pub const MAX_DELAY_SECONDS: i64 = 30 * 86_400;
+
+/// Size of a `SavingsAccount` with a pending withdrawal
+pub const SAVINGS_ACCOUNT_SIZE: usize = 1 // is_initialized
+    + 32 // owner
+    + 8 // delay_seconds
+    + 1 + 8 + 32 + 8 // pending
+    + 1; // bump
+
+/// Errors returned by the program as `ProgramError::Custom` codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SavingsError {
+    /// Delay is outside `MIN_DELAY_SECONDS..=MAX_DELAY_SECONDS`
+    InvalidDelay = 0,
+    /// A withdrawal is already pending
+    WithdrawalPending = 1,
+    /// No withdrawal is pending
+    NoPendingWithdrawal = 2,
+    /// The delay has not elapsed yet
+    DelayNotElapsed = 3,
+    /// The vault holds less than the amount above rent
+    InsufficientFunds = 4,
+    /// Only the owner can do this
+    Unauthorized = 5,
+    /// The vault has not been initialized
+    NotInitialized = 6,
+    /// Amount is zero
+    InvalidAmount = 7,
+    /// The recipient account does not match the request
+    WrongRecipient = 8,
+}
+
+impl From<SavingsError> for ProgramError {
+    fn from(e: SavingsError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+/// A requested withdrawal, executable from `unlock_at`
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PendingWithdrawal {
+    pub amount: u64,
+    pub recipient: Pubkey,
+    pub unlock_at: i64,
+}
+
+/// Lamports held by this PDA above rent, withdrawable in two steps
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct SavingsAccount {
+    pub is_initialized: bool,
+    pub owner: Pubkey,
+    /// Fixed at creation, so a stolen key cannot shorten it
+    pub delay_seconds: i64,
+    pub pending: Option<PendingWithdrawal>,
+    pub bump: u8,
+}
+
+impl SavingsAccount {
+    /// Start a withdrawal of `amount` to `recipient`, unlocking `delay_seconds` from `now`
+    pub fn request(&mut self, amount: u64, recipient: Pubkey, now: i64) -> Result<PendingWithdrawal, SavingsError> {
+        if amount == 0 {
+            return Err(SavingsError::InvalidAmount);
+        }
+        if self.pending.is_some() {
+            return Err(SavingsError::WithdrawalPending);
+        }
+
+        let pending = PendingWithdrawal {
+            amount,
+            recipient,
+            unlock_at: now.saturating_add(self.delay_seconds),
+        };
+        self.pending = Some(pending);
+        Ok(pending)
+    }
+
+    /// Take the pending withdrawal if its delay has elapsed at `now`
+    pub fn take_ready(&mut self, now: i64) -> Result<PendingWithdrawal, SavingsError> {
+        let pending = self.pending.ok_or(SavingsError::NoPendingWithdrawal)?;
+        if now < pending.unlock_at {
+            return Err(SavingsError::DelayNotElapsed);
+        }
+        self.pending = None;
+        Ok(pending)
+    }
+}
+
+entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = SavingsInstruction::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        SavingsInstruction::Initialize { delay_seconds } => initialize(program_id, accounts, delay_seconds),
+        SavingsInstruction::Deposit { amount } => deposit(program_id, accounts, amount),
+        SavingsInstruction::RequestWithdrawal { amount, recipient } => {
+            request_withdrawal(program_id, accounts, amount, recipient)
+        }
+        SavingsInstruction::CancelWithdrawal => cancel_withdrawal(program_id, accounts),
+        SavingsInstruction::ExecuteWithdrawal => execute_withdrawal(program_id, accounts),
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub enum SavingsInstruction {
+    /// Create a vault whose withdrawals wait `delay_seconds`
+    /// Accounts: [writable, signer] owner, [writable] savings PDA, [] system program
+    Initialize { delay_seconds: i64 },
+    /// Add lamports; anyone can deposit
+    /// Accounts: [writable, signer] depositor, [writable] savings PDA, [] system program
+    Deposit { amount: u64 },
+    /// Phase 1: announce a withdrawal of `amount` to `recipient`
+    /// Accounts: [signer] owner, [writable] savings PDA
+    RequestWithdrawal { amount: u64, recipient: Pubkey },
+    /// Drop the pending withdrawal at any point before it executes
+    /// Accounts: [signer] owner, [writable] savings PDA
+    CancelWithdrawal,
+    /// Phase 2: pay the pending withdrawal once the delay has elapsed
+    /// Accounts: [signer] owner, [writable] savings PDA, [writable] recipient
+    ExecuteWithdrawal,
+}
+
+fn initialize(program_id: &Pubkey, accounts: &[AccountInfo], delay_seconds: i64) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let owner = next_account_info(accounts_iter)?;
+    let savings_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if !(MIN_DELAY_SECONDS..=MAX_DELAY_SECONDS).contains(&delay_seconds) {
+        msg!("Delay must be {}-{} seconds", MIN_DELAY_SECONDS, MAX_DELAY_SECONDS);
+        return Err(SavingsError::InvalidDelay.into());
+    }
+
+    let (expected_savings, bump) = Pubkey::find_program_address(&[SAVINGS_SEED, owner.key.as_ref()], program_id);
+    if expected_savings != *savings_account.key {
+        msg!("Savings account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    invoke_signed(
+        &system_instruction::create_account(
+            owner.key,
+            savings_account.key,
+            Rent::get()?.minimum_balance(SAVINGS_ACCOUNT_SIZE),
+            SAVINGS_ACCOUNT_SIZE as u64,
+            program_id,
+        ),
+        &[owner.clone(), savings_account.clone(), system_program.clone()],
+        &[&[SAVINGS_SEED, owner.key.as_ref(), &[bump]]],
+    )?;
+
+    let savings = SavingsAccount {
+        is_initialized: true,
+        owner: *owner.key,
+        delay_seconds,
+        pending: None,
+        bump,
+    };
+    savings.serialize(&mut &mut savings_account.data.borrow_mut()[..])?;
+
+    msg!("Savings vault created with a {} second withdrawal delay", delay_seconds);
+
+    Ok(())
+}
+
+fn deposit(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let depositor = next_account_info(accounts_iter)?;
+    let savings_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !depositor.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if amount == 0 {
+        return Err(SavingsError::InvalidAmount.into());
+    }
+
+    load_savings(program_id, savings_account)?;
+
+    invoke(
+        &system_instruction::transfer(depositor.key, savings_account.key, amount),
+        &[depositor.clone(), savings_account.clone(), system_program.clone()],
+    )?;
+
+    msg!("Deposited {} lamports", amount);
+
+    Ok(())
+}
+
+fn request_withdrawal(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64, recipient: Pubkey) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let owner = next_account_info(accounts_iter)?;
+    let savings_account = next_account_info(accounts_iter)?;
+
+    let mut savings = load_savings_as_owner(program_id, savings_account, owner)?;
+
+    let pending = savings.request(amount, recipient, Clock::get()?.unix_timestamp)?;
+    savings.serialize(&mut &mut savings_account.data.borrow_mut()[..])?;
+
+    // Watchers see this log and have until `unlock_at` to react if it is unexpected
+    msg!(
+        "Withdrawal of {} to {} requested; executable at {}",
+        pending.amount,
+        pending.recipient,
+        pending.unlock_at
+    );
+
+    Ok(())
+}
+
+fn cancel_withdrawal(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let owner = next_account_info(accounts_iter)?;
+    let savings_account = next_account_info(accounts_iter)?;
+
+    let mut savings = load_savings_as_owner(program_id, savings_account, owner)?;
+
+    let pending = savings.pending.take().ok_or(SavingsError::NoPendingWithdrawal)?;
+    savings.serialize(&mut &mut savings_account.data.borrow_mut()[..])?;
+
+    msg!("Withdrawal of {} to {} cancelled", pending.amount, pending.recipient);
+
+    Ok(())
+}
+
+fn execute_withdrawal(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let owner = next_account_info(accounts_iter)?;
+    let savings_account = next_account_info(accounts_iter)?;
+    let recipient = next_account_info(accounts_iter)?;
+
+    let mut savings = load_savings_as_owner(program_id, savings_account, owner)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let pending = match savings.take_ready(now) {
+        Ok(pending) => pending,
+        Err(e) => {
+            if let Some(pending) = savings.pending {
+                msg!("Withdrawal unlocks at {}, now {}", pending.unlock_at, now);
+            }
+            return Err(e.into());
+        }
+    };
+
+    if pending.recipient != *recipient.key {
+        msg!("Withdrawal was requested to {}", pending.recipient);
+        return Err(SavingsError::WrongRecipient.into());
+    }
+
+    let available = savings_account
+        .lamports()
+        .saturating_sub(Rent::get()?.minimum_balance(savings_account.data_len()));
+    if pending.amount > available {
+        msg!("Requested {} but only {} is available", pending.amount, available);
+        return Err(SavingsError::InsufficientFunds.into());
+    }
+
+    **savings_account.try_borrow_mut_lamports()? -= pending.amount;
+    **recipient.try_borrow_mut_lamports()? = recipient
+        .lamports()
+        .checked_add(pending.amount)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    savings.serialize(&mut &mut savings_account.data.borrow_mut()[..])?;
+
+    msg!("Withdrew {} to {}", pending.amount, recipient.key);
+
+    Ok(())
+}
+
+fn load_savings(program_id: &Pubkey, savings_account: &AccountInfo) -> Result<SavingsAccount, ProgramError> {
+    if savings_account.owner != program_id {
+        msg!("Savings account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    // No pending withdrawal makes the encoding shorter than the account
+    let savings = SavingsAccount::deserialize(&mut &savings_account.data.borrow()[..])?;
+
+    if !savings.is_initialized {
+        return Err(SavingsError::NotInitialized.into());
+    }
+
+    Ok(savings)
+}
+
+fn load_savings_as_owner(
+    program_id: &Pubkey,
+    savings_account: &AccountInfo,
+    owner: &AccountInfo,
+) -> Result<SavingsAccount, ProgramError> {
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let savings = load_savings(program_id, savings_account)?;
+
+    if savings.owner != *owner.key {
+        msg!("Only the owner can manage withdrawals");
+        return Err(SavingsError::Unauthorized.into());
+    }
+
+    Ok(savings)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_program::{clock::Epoch, entrypoint::SUCCESS, program_stubs};
+
+    /// Serves Clock at a fixed time and the default Rent so handlers can run off-chain
+    struct TestSyscallStubs {
+        unix_timestamp: i64,
+    }
+
+    impl program_stubs::SyscallStubs for TestSyscallStubs {
+        fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+            unsafe {
+                *(var_addr as *mut Clock) = Clock {
+                    unix_timestamp: self.unix_timestamp,
+                    ..Clock::default()
+                };
+            }
+            SUCCESS
+        }
+
+        fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+            unsafe {
+                *(var_addr as *mut Rent) = Rent::default();
+            }
+            SUCCESS
+        }
+    }
+
+    fn new_savings(owner: Pubkey) -> SavingsAccount {
+        SavingsAccount {
+            is_initialized: true,
+            owner,
+            delay_seconds: 86_400,
+            pending: None,
+            bump: 255,
+        }
+    }
+
+    #[test]
+    fn test_two_phase_withdrawal() {
+        let mut savings = new_savings(Pubkey::new_unique());
+        let recipient = Pubkey::new_unique();
+        let now = 1_700_000_000;
+
+        assert_eq!(savings.take_ready(now), Err(SavingsError::NoPendingWithdrawal));
+        assert_eq!(savings.request(0, recipient, now), Err(SavingsError::InvalidAmount));
+
+        let pending = savings.request(500, recipient, now).unwrap();
+        assert_eq!(pending.unlock_at, now + 86_400);
+        assert_eq!(savings.request(100, recipient, now), Err(SavingsError::WithdrawalPending));
+
+        assert_eq!(savings.take_ready(now + 86_399), Err(SavingsError::DelayNotElapsed));
+        assert_eq!(savings.pending, Some(pending));
+        assert_eq!(savings.take_ready(now + 86_400), Ok(pending));
+        assert_eq!(savings.pending, None);
+
+        // A pending withdrawal fits the allocated size
+        savings.request(1, recipient, now).unwrap();
+        assert_eq!(savings.try_to_vec().unwrap().len(), SAVINGS_ACCOUNT_SIZE);
+    }
+
+    #[test]
+    fn test_execute_waits_for_delay() {
+        let now = 1_700_000_000;
+        let program_id = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let recipient = Pubkey::new_unique();
+        let savings_key = Pubkey::new_unique();
+        let rent_minimum = Rent::default().minimum_balance(SAVINGS_ACCOUNT_SIZE);
+
+        let mut savings = new_savings(owner);
+        savings.request(5_000, recipient, now).unwrap();
+        let mut savings_data = savings.try_to_vec().unwrap();
+
+        let keys = [owner, savings_key, recipient];
+        let mut lamports: Vec<u64> = vec![0, rent_minimum + 5_000, 0];
+        let mut data: Vec<Vec<u8>> = vec![vec![], std::mem::take(&mut savings_data), vec![]];
+
+        let accounts: Vec<AccountInfo> = keys
+            .iter()
+            .zip(lamports.iter_mut())
+            .zip(data.iter_mut())
+            .enumerate()
+            .map(|(i, ((key, lamports), data))| {
+                AccountInfo::new(key, i == 0, true, lamports, data, &program_id, false, Epoch::default())
+            })
+            .collect();
+        let instruction_data = SavingsInstruction::ExecuteWithdrawal.try_to_vec().unwrap();
+
+        program_stubs::set_syscall_stubs(Box::new(TestSyscallStubs { unix_timestamp: now + 3_600 }));
+        let result = process_instruction(&program_id, &accounts, &instruction_data);
+        assert_eq!(result, Err(SavingsError::DelayNotElapsed.into()));
+
+        program_stubs::set_syscall_stubs(Box::new(TestSyscallStubs { unix_timestamp: now + 86_400 }));
+        process_instruction(&program_id, &accounts, &instruction_data).unwrap();
+        assert_eq!(accounts[2].lamports(), 5_000);
+        assert_eq!(accounts[1].lamports(), rent_minimum);
+
+        // The request is consumed
+        let result = process_instruction(&program_id, &accounts, &instruction_data);
+        assert_eq!(result, Err(SavingsError::NoPendingWithdrawal.into()));
+    }
+}
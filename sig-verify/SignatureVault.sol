@@ -0,0 +1,63 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+/**
+ * @title SignatureVault
+ * @dev ETH that moves only with an off-chain signature from `signer`; anyone
+ * can relay it. Ethereum verifies secp256k1 signatures with the ecrecover
+ * precompile. There is no ed25519 precompile, so only Ethereum keys are supported.
+ */
+contract SignatureVault {
+    address public immutable signer;
+    uint256 public nonce;
+
+    event Deposited(address indexed from, uint256 amount);
+    event Withdrawn(address indexed recipient, uint256 amount, uint256 nonce);
+
+    constructor(address _signer) {
+        require(_signer != address(0), "Invalid signer");
+        signer = _signer;
+    }
+
+    receive() external payable {
+        emit Deposited(msg.sender, msg.value);
+    }
+
+    /// Message the signer signs with personal_sign
+    function withdrawMessage(address recipient, uint256 amount, uint256 _nonce) public view returns (bytes memory) {
+        return abi.encodePacked("withdraw", address(this), recipient, amount, _nonce);
+    }
+
+    function withdraw(address payable recipient, uint256 amount, uint8 v, bytes32 r, bytes32 s) external {
+        bytes memory message = withdrawMessage(recipient, amount, nonce);
+        bytes32 digest = keccak256(
+            abi.encodePacked("\x19Ethereum Signed Message:\n", _toString(message.length), message)
+        );
+        address recovered = ecrecover(digest, v, r, s);
+        require(recovered != address(0) && recovered == signer, "Invalid signature");
+        require(amount <= address(this).balance, "Insufficient funds");
+
+        emit Withdrawn(recipient, amount, nonce);
+        nonce++;
+
+        (bool success, ) = recipient.call{value: amount}("");
+        require(success, "Transfer failed");
+    }
+
+    function _toString(uint256 value) private pure returns (string memory) {
+        if (value == 0) {
+            return "0";
+        }
+        uint256 digits;
+        for (uint256 temp = value; temp != 0; temp /= 10) {
+            digits++;
+        }
+        bytes memory buffer = new bytes(digits);
+        while (value != 0) {
+            digits--;
+            buffer[digits] = bytes1(uint8(48 + (value % 10)));
+            value /= 10;
+        }
+        return string(buffer);
+    }
+}
@@ -0,0 +1,439 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    ed25519_program,
+    entrypoint,
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    secp256k1_program, system_instruction,
+    sysvar::{
+        self,
+        instructions::{load_current_index_checked, load_instruction_at_checked},
+        Sysvar,
+    },
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// Seed prefix for vault PDAs: ["vault", signer bytes]
+pub const VAULT_SEED: &[u8] = b"vault";
+
+/// Size of a `VaultAccount`, sized for the larger signer variant
+pub const VAULT_ACCOUNT_SIZE: usize = 1 // is_initialized
+    + 1 + 32 // signer
+    + 8 // nonce
+    + 1; // bump
+
+/// Instruction index meaning "this instruction" in ed25519 offsets
+const ED25519_THIS_INSTRUCTION: u16 = u16::MAX;
+
+/// Errors returned by the program as `ProgramError::Custom` codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SigVerifyError {
+    /// The instruction before this one is not a single-signature precompile
+    /// verification of the vault signer over the expected message
+    InvalidSignatureInstruction = 0,
+    /// The withdrawal would leave the vault below rent exemption
+    InsufficientFunds = 1,
+    /// The vault has not been initialized
+    NotInitialized = 2,
+}
+
+impl From<SigVerifyError> for ProgramError {
+    fn from(e: SigVerifyError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+/// Key allowed to authorize withdrawals, from either chain
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthorizedSigner {
+    /// 20-byte Ethereum address, verified by the secp256k1 precompile
+    Ethereum([u8; 20]),
+    /// ed25519 public key, verified by the ed25519 precompile
+    Ed25519(Pubkey),
+}
+
+impl AuthorizedSigner {
+    pub fn seed(&self) -> &[u8] {
+        match self {
+            AuthorizedSigner::Ethereum(address) => address,
+            AuthorizedSigner::Ed25519(key) => key.as_ref(),
+        }
+    }
+}
+
+/// SOL that moves only with an off-chain signature from `signer`; no Solana
+/// account needs to sign
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct VaultAccount {
+    pub is_initialized: bool,
+    pub signer: AuthorizedSigner,
+    /// Included in each signed message; incremented on use to prevent replay
+    pub nonce: u64,
+    pub bump: u8,
+}
+
+/// Bytes the signer signs to authorize a withdrawal
+pub fn withdraw_message(vault: &Pubkey, recipient: &Pubkey, amount: u64, nonce: u64) -> Vec<u8> {
+    let mut message = b"withdraw".to_vec();
+    message.extend_from_slice(vault.as_ref());
+    message.extend_from_slice(recipient.as_ref());
+    message.extend_from_slice(&amount.to_le_bytes());
+    message.extend_from_slice(&nonce.to_le_bytes());
+    message
+}
+
+/// EIP-191 form of `message`, as produced by `personal_sign` in Ethereum
+/// wallets. The secp256k1 precompile keccak-hashes exactly these bytes.
+pub fn eth_personal_message(message: &[u8]) -> Vec<u8> {
+    let mut prefixed = format!("\x19Ethereum Signed Message:\n{}", message.len()).into_bytes();
+    prefixed.extend_from_slice(message);
+    prefixed
+}
+
+fn read_u16(data: &[u8], at: usize) -> Option<usize> {
+    Some(u16::from_le_bytes([*data.get(at)?, *data.get(at + 1)?]) as usize)
+}
+
+/// Public key and message of a single-signature ed25519 instruction whose
+/// offsets all point into its own data
+pub fn parse_ed25519(data: &[u8], own_index: u16) -> Option<(Pubkey, &[u8])> {
+    // num_signatures (u8), padding (u8), then 7 u16s: signature offset and
+    // instruction index, public key offset and index, message offset, size and index
+    if *data.first()? != 1 {
+        return None;
+    }
+    let field = |i: usize| read_u16(data, 2 + i * 2);
+    let own = |index: usize| index == ED25519_THIS_INSTRUCTION as usize || index == own_index as usize;
+    if !own(field(1)?) || !own(field(3)?) || !own(field(6)?) {
+        return None;
+    }
+    let key_offset = field(2)?;
+    let (message_offset, message_size) = (field(4)?, field(5)?);
+    let key = Pubkey::try_from(data.get(key_offset..key_offset + 32)?).ok()?;
+    Some((key, data.get(message_offset..message_offset + message_size)?))
+}
+
+/// Ethereum address and message of a single-signature secp256k1 instruction
+/// whose offsets all point into its own data
+pub fn parse_secp256k1(data: &[u8], own_index: u8) -> Option<([u8; 20], &[u8])> {
+    // count (u8), then signature offset (u16) and index (u8), address offset
+    // (u16) and index (u8), message offset (u16), size (u16) and index (u8)
+    if *data.first()? != 1 {
+        return None;
+    }
+    if *data.get(3)? != own_index || *data.get(6)? != own_index || *data.get(11)? != own_index {
+        return None;
+    }
+    let address_offset = read_u16(data, 4)?;
+    let (message_offset, message_size) = (read_u16(data, 7)?, read_u16(data, 9)?);
+    let address = data.get(address_offset..address_offset + 20)?.try_into().ok()?;
+    Some((address, data.get(message_offset..message_offset + message_size)?))
+}
+
+entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = SigVerifyInstruction::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        SigVerifyInstruction::Initialize { signer } => initialize(program_id, accounts, signer),
+        SigVerifyInstruction::Withdraw { amount } => withdraw(program_id, accounts, amount),
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub enum SigVerifyInstruction {
+    /// Create a vault for `signer`. Anyone can create and fund it.
+    /// Accounts: [writable, signer] payer, [writable] vault PDA, [] system program
+    Initialize { signer: AuthorizedSigner },
+    /// Send `amount` lamports to the recipient. The previous instruction must
+    /// be a secp256k1 (Ethereum signer) or ed25519 verification of
+    /// `withdraw_message` (EIP-191 prefixed for Ethereum) by the vault signer.
+    /// Accounts: [writable] vault PDA, [writable] recipient, [] instructions sysvar
+    Withdraw { amount: u64 },
+}
+
+fn initialize(program_id: &Pubkey, accounts: &[AccountInfo], signer: AuthorizedSigner) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let payer = next_account_info(accounts_iter)?;
+    let vault_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !payer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let (expected_vault, bump) = Pubkey::find_program_address(&[VAULT_SEED, signer.seed()], program_id);
+    if expected_vault != *vault_account.key {
+        msg!("Vault account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    invoke_signed(
+        &system_instruction::create_account(
+            payer.key,
+            vault_account.key,
+            Rent::get()?.minimum_balance(VAULT_ACCOUNT_SIZE),
+            VAULT_ACCOUNT_SIZE as u64,
+            program_id,
+        ),
+        &[payer.clone(), vault_account.clone(), system_program.clone()],
+        &[&[VAULT_SEED, signer.seed(), &[bump]]],
+    )?;
+
+    let vault = VaultAccount {
+        is_initialized: true,
+        signer,
+        nonce: 0,
+        bump,
+    };
+    vault.serialize(&mut &mut vault_account.data.borrow_mut()[..])?;
+
+    msg!("Vault created for {:?}", signer);
+
+    Ok(())
+}
+
+fn withdraw(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let vault_account = next_account_info(accounts_iter)?;
+    let recipient = next_account_info(accounts_iter)?;
+    let instructions_sysvar = next_account_info(accounts_iter)?;
+
+    let mut vault = load_vault(program_id, vault_account)?;
+
+    let message = withdraw_message(vault_account.key, recipient.key, amount, vault.nonce);
+    verify_signature_instruction(instructions_sysvar, &vault.signer, &message)?;
+
+    let available = vault_account
+        .lamports()
+        .saturating_sub(Rent::get()?.minimum_balance(vault_account.data_len()));
+    if amount > available {
+        msg!("Only {} lamports available", available);
+        return Err(SigVerifyError::InsufficientFunds.into());
+    }
+
+    **vault_account.try_borrow_mut_lamports()? -= amount;
+    **recipient.try_borrow_mut_lamports()? += amount;
+
+    vault.nonce = vault.nonce.checked_add(1).ok_or(ProgramError::ArithmeticOverflow)?;
+    vault.serialize(&mut &mut vault_account.data.borrow_mut()[..])?;
+
+    msg!("Withdrew {} lamports to {}", amount, recipient.key);
+
+    Ok(())
+}
+
+/// Check that the previous instruction verified `signer`'s signature over
+/// `message`. A failed precompile fails the whole transaction, so finding the
+/// instruction is enough; the program only checks it verified the right thing.
+fn verify_signature_instruction(
+    instructions_sysvar: &AccountInfo,
+    signer: &AuthorizedSigner,
+    message: &[u8],
+) -> ProgramResult {
+    if *instructions_sysvar.key != sysvar::instructions::id() {
+        msg!("Expected the instructions sysvar");
+        return Err(ProgramError::UnsupportedSysvar);
+    }
+
+    let current_index = load_current_index_checked(instructions_sysvar)?;
+    let index = current_index.checked_sub(1).ok_or_else(|| {
+        msg!("Expected a signature verification instruction before this one");
+        SigVerifyError::InvalidSignatureInstruction
+    })?;
+    let ix = load_instruction_at_checked(index as usize, instructions_sysvar)?;
+
+    let verified = match signer {
+        AuthorizedSigner::Ethereum(address) => {
+            ix.program_id == secp256k1_program::id()
+                && u8::try_from(index).ok().and_then(|own| parse_secp256k1(&ix.data, own))
+                    == Some((*address, eth_personal_message(message).as_slice()))
+        }
+        AuthorizedSigner::Ed25519(key) => {
+            ix.program_id == ed25519_program::id() && parse_ed25519(&ix.data, index) == Some((*key, message))
+        }
+    };
+
+    if !verified {
+        msg!("Signature instruction does not verify the vault signer over the withdrawal");
+        return Err(SigVerifyError::InvalidSignatureInstruction.into());
+    }
+
+    Ok(())
+}
+
+/// Validate the vault account and return it
+fn load_vault(program_id: &Pubkey, vault_account: &AccountInfo) -> Result<VaultAccount, ProgramError> {
+    if vault_account.owner != program_id {
+        msg!("Vault account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let vault = VaultAccount::deserialize(&mut &vault_account.data.borrow()[..])?;
+
+    if !vault.is_initialized {
+        return Err(SigVerifyError::NotInitialized.into());
+    }
+
+    Ok(vault)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_program::{
+        clock::Epoch,
+        entrypoint::SUCCESS,
+        program_stubs,
+        sysvar::instructions::{construct_instructions_data, BorrowedInstruction},
+    };
+
+    /// Serves a default Rent sysvar so handlers can run off-chain
+    struct TestSyscallStubs;
+
+    impl program_stubs::SyscallStubs for TestSyscallStubs {
+        fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+            unsafe {
+                *(var_addr as *mut Rent) = Rent::default();
+            }
+            SUCCESS
+        }
+    }
+
+    /// secp256k1 instruction data laid out as `new_secp256k1_instruction` does:
+    /// offsets, address, signature and recovery id (zeroed here), then message
+    fn secp256k1_data(address: &[u8; 20], message: &[u8], own_index: u8) -> Vec<u8> {
+        let address_offset: u16 = 12;
+        let signature_offset = address_offset + 20;
+        let message_offset = signature_offset + 65;
+        let mut data = vec![1];
+        data.extend_from_slice(&signature_offset.to_le_bytes());
+        data.push(own_index);
+        data.extend_from_slice(&address_offset.to_le_bytes());
+        data.push(own_index);
+        data.extend_from_slice(&message_offset.to_le_bytes());
+        data.extend_from_slice(&(message.len() as u16).to_le_bytes());
+        data.push(own_index);
+        data.extend_from_slice(address);
+        data.extend_from_slice(&[0; 65]);
+        data.extend_from_slice(message);
+        data
+    }
+
+    #[test]
+    fn test_parse_precompile_data() {
+        let address = [7u8; 20];
+        let data = secp256k1_data(&address, b"hello", 0);
+        assert_eq!(parse_secp256k1(&data, 0), Some((address, &b"hello"[..])));
+        // Offsets that point at another instruction's data are rejected
+        assert_eq!(parse_secp256k1(&data, 1), None);
+        assert_eq!(parse_secp256k1(&data[..40], 0), None);
+
+        let key = Pubkey::new_unique();
+        let mut data = vec![1, 0];
+        let (key_offset, signature_offset, message_offset) = (16u16, 48u16, 112u16);
+        for field in [signature_offset, u16::MAX, key_offset, u16::MAX, message_offset, 5, u16::MAX] {
+            data.extend_from_slice(&field.to_le_bytes());
+        }
+        data.extend_from_slice(key.as_ref());
+        data.extend_from_slice(&[0; 64]);
+        data.extend_from_slice(b"hello");
+        assert_eq!(parse_ed25519(&data, 3), Some((key, &b"hello"[..])));
+        data[0] = 2;
+        assert_eq!(parse_ed25519(&data, 3), None);
+
+        assert_eq!(
+            eth_personal_message(b"hello"),
+            b"\x19Ethereum Signed Message:\n5hello".to_vec()
+        );
+    }
+
+    #[test]
+    fn test_withdraw_with_ethereum_signature() {
+        program_stubs::set_syscall_stubs(Box::new(TestSyscallStubs));
+
+        let program_id = Pubkey::new_unique();
+        let address = [9u8; 20];
+        let vault_key = Pubkey::new_unique();
+        let recipient_key = Pubkey::new_unique();
+        let rent_exempt = Rent::default().minimum_balance(VAULT_ACCOUNT_SIZE);
+
+        let vault = VaultAccount {
+            is_initialized: true,
+            signer: AuthorizedSigner::Ethereum(address),
+            nonce: 3,
+            bump: 255,
+        };
+        let mut vault_data = vault.try_to_vec().unwrap();
+        vault_data.resize(VAULT_ACCOUNT_SIZE, 0);
+
+        let withdraw_data = SigVerifyInstruction::Withdraw { amount: 500 }.try_to_vec().unwrap();
+        let sysvar_for = |signed_nonce: u64| {
+            let signed = eth_personal_message(&withdraw_message(&vault_key, &recipient_key, 500, signed_nonce));
+            let secp_data = secp256k1_data(&address, &signed, 0);
+            let secp_program_id = secp256k1_program::id();
+            let mut data = construct_instructions_data(&[
+                BorrowedInstruction {
+                    program_id: &secp_program_id,
+                    accounts: vec![],
+                    data: &secp_data,
+                },
+                BorrowedInstruction {
+                    program_id: &program_id,
+                    accounts: vec![],
+                    data: &withdraw_data,
+                },
+            ]);
+            // The current instruction index is stored in the last two bytes
+            let index_at = data.len() - 2;
+            data[index_at..].copy_from_slice(&1u16.to_le_bytes());
+            data
+        };
+
+        let keys = [vault_key, recipient_key, sysvar::instructions::id()];
+        let mut lamports: Vec<u64> = vec![rent_exempt + 1_000, 0, 0];
+
+        // A signature over an old nonce is a replay
+        let mut data: Vec<Vec<u8>> = vec![vault_data.clone(), vec![], sysvar_for(2)];
+        let accounts: Vec<AccountInfo> = keys
+            .iter()
+            .zip(lamports.iter_mut())
+            .zip(data.iter_mut())
+            .enumerate()
+            .map(|(i, ((key, lamports), data))| {
+                AccountInfo::new(key, false, i < 2, lamports, data, &program_id, false, Epoch::default())
+            })
+            .collect();
+        let result = process_instruction(&program_id, &accounts, &withdraw_data);
+        assert_eq!(result, Err(SigVerifyError::InvalidSignatureInstruction.into()));
+        drop(accounts);
+
+        let mut data: Vec<Vec<u8>> = vec![vault_data, vec![], sysvar_for(3)];
+        let accounts: Vec<AccountInfo> = keys
+            .iter()
+            .zip(lamports.iter_mut())
+            .zip(data.iter_mut())
+            .enumerate()
+            .map(|(i, ((key, lamports), data))| {
+                AccountInfo::new(key, false, i < 2, lamports, data, &program_id, false, Epoch::default())
+            })
+            .collect();
+        let result = process_instruction(&program_id, &accounts, &withdraw_data);
+        assert!(result.is_ok());
+        assert_eq!(accounts[0].lamports(), rent_exempt + 500);
+        assert_eq!(accounts[1].lamports(), 500);
+
+        let stored = VaultAccount::deserialize(&mut &accounts[0].data.borrow()[..]).unwrap();
+        assert_eq!(stored.nonce, 4);
+    }
+}
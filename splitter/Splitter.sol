@@ -0,0 +1,72 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+interface IERC20 {
+    function transfer(address to, uint256 amount) external returns (bool);
+    function balanceOf(address account) external view returns (uint256);
+}
+
+/**
+ * @title Splitter
+ * @dev Splits accumulated ETH or ERC20 balances among fixed recipients by basis-point shares
+ */
+contract Splitter {
+    uint256 public constant MAX_RECIPIENTS = 10;
+    uint256 public constant TOTAL_BPS = 10_000;
+
+    address[] public recipients;
+    uint256[] public sharesBps;
+
+    event Distributed(address indexed token, uint256 total);
+
+    constructor(address[] memory _recipients, uint256[] memory _sharesBps) {
+        require(_recipients.length > 0 && _recipients.length <= MAX_RECIPIENTS, "Need 1-10 recipients");
+        require(_recipients.length == _sharesBps.length, "Length mismatch");
+
+        uint256 total;
+        for (uint256 i = 0; i < _recipients.length; i++) {
+            require(_sharesBps[i] > 0, "Zero share");
+            for (uint256 j = 0; j < i; j++) {
+                require(_recipients[j] != _recipients[i], "Duplicate recipient");
+            }
+            total += _sharesBps[i];
+        }
+        require(total == TOTAL_BPS, "Shares must sum to 10000");
+
+        recipients = _recipients;
+        sharesBps = _sharesBps;
+    }
+
+    receive() external payable {}
+
+    /**
+     * @dev Pay out the contract's ETH balance (callable by anyone); rounding dust stays for next time
+     */
+    function distribute() external {
+        uint256 total = address(this).balance;
+        require(total > 0, "Nothing to distribute");
+
+        for (uint256 i = 0; i < recipients.length; i++) {
+            uint256 amount = (total * sharesBps[i]) / TOTAL_BPS;
+            (bool success, ) = recipients[i].call{value: amount}("");
+            require(success, "Transfer failed");
+        }
+
+        emit Distributed(address(0), total);
+    }
+
+    /**
+     * @dev Pay out the contract's balance of `token` (callable by anyone)
+     */
+    function distributeToken(IERC20 token) external {
+        uint256 total = token.balanceOf(address(this));
+        require(total > 0, "Nothing to distribute");
+
+        for (uint256 i = 0; i < recipients.length; i++) {
+            uint256 amount = (total * sharesBps[i]) / TOTAL_BPS;
+            require(token.transfer(recipients[i], amount), "Transfer failed");
+        }
+
+        emit Distributed(address(token), total);
+    }
+}
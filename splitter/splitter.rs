@@ -0,0 +1,328 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint,
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use spl_token::state::Account as TokenAccount;
+
+/// Seed prefix for splitter PDAs: ["splitter", creator, seed]
+pub const SPLITTER_SEED: &[u8] = b"splitter";
+
+/// Maximum number of recipients per splitter
+pub const MAX_RECIPIENTS: usize = 10;
+
+/// Shares are expressed in basis points and must sum to this
+pub const TOTAL_BPS: u16 = 10_000;
+
+/// Size of a `SplitterAccount` with `MAX_RECIPIENTS` recipients
+pub const SPLITTER_ACCOUNT_SIZE: usize = 1 // is_initialized
+    + 32 // creator
+    + 8 // seed
+    + 4 + MAX_RECIPIENTS * (32 + 2) // recipients
+    + 1; // bump
+
+/// Errors returned by the program as `ProgramError::Custom` codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SplitterError {
+    /// Shares must be non-zero and sum to exactly 10,000 bps
+    InvalidShares = 0,
+    /// Between 1 and `MAX_RECIPIENTS` recipients are allowed
+    TooManyRecipients = 1,
+    /// A payout account does not match the recipient at that position
+    RecipientMismatch = 2,
+    /// The vault holds nothing to distribute
+    NothingToDistribute = 3,
+    /// The splitter has not been initialized
+    NotInitialized = 4,
+    /// Each recipient may appear only once
+    DuplicateRecipient = 5,
+}
+
+impl From<SplitterError> for ProgramError {
+    fn from(e: SplitterError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Recipient {
+    pub address: Pubkey,
+    /// Share of every distribution in basis points
+    pub share_bps: u16,
+}
+
+/// An immutable split. Lamports sent to this account (above rent) and tokens
+/// in any token account it owns are paid out by `Distribute`.
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct SplitterAccount {
+    pub is_initialized: bool,
+    pub creator: Pubkey,
+    pub seed: u64,
+    pub recipients: Vec<Recipient>,
+    pub bump: u8,
+}
+
+/// Each recipient's share of `total`, rounded down. The rounding dust stays
+/// in the vault and is included in the next distribution.
+pub fn split_amounts(total: u64, recipients: &[Recipient]) -> Vec<u64> {
+    recipients
+        .iter()
+        .map(|r| (total as u128 * r.share_bps as u128 / TOTAL_BPS as u128) as u64)
+        .collect()
+}
+
+/// Check recipient count, share sum, and uniqueness
+pub fn validate_recipients(recipients: &[Recipient]) -> Result<(), SplitterError> {
+    if recipients.is_empty() || recipients.len() > MAX_RECIPIENTS {
+        return Err(SplitterError::TooManyRecipients);
+    }
+
+    let mut total: u32 = 0;
+    for (i, recipient) in recipients.iter().enumerate() {
+        if recipient.share_bps == 0 {
+            return Err(SplitterError::InvalidShares);
+        }
+        if recipients[..i].iter().any(|r| r.address == recipient.address) {
+            return Err(SplitterError::DuplicateRecipient);
+        }
+        total += recipient.share_bps as u32;
+    }
+
+    if total != TOTAL_BPS as u32 {
+        return Err(SplitterError::InvalidShares);
+    }
+
+    Ok(())
+}
+
+entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = SplitterInstruction::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        SplitterInstruction::CreateSplitter { seed, recipients } => {
+            create_splitter(program_id, accounts, seed, recipients)
+        }
+        SplitterInstruction::Distribute => distribute(program_id, accounts),
+        SplitterInstruction::DistributeToken => distribute_token(program_id, accounts),
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub enum SplitterInstruction {
+    /// Create the splitter PDA with a fixed recipient list
+    /// Accounts: [writable, signer] creator, [writable] splitter PDA, [] system program
+    CreateSplitter { seed: u64, recipients: Vec<Recipient> },
+    /// Pay out the splitter's lamports above rent. Anyone may call.
+    /// Accounts: [writable] splitter PDA, then [writable] each recipient in stored order
+    Distribute,
+    /// Pay out a token account owned by the splitter PDA. Anyone may call.
+    /// Accounts: [writable] splitter PDA, [writable] vault token account, [] token program,
+    /// then [writable] each recipient's token account (same mint) in stored order
+    DistributeToken,
+}
+
+fn create_splitter(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    seed: u64,
+    recipients: Vec<Recipient>,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let creator = next_account_info(accounts_iter)?;
+    let splitter_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !creator.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    validate_recipients(&recipients)?;
+
+    let seed_bytes = seed.to_le_bytes();
+    let (expected_splitter, bump) =
+        Pubkey::find_program_address(&[SPLITTER_SEED, creator.key.as_ref(), &seed_bytes], program_id);
+    if expected_splitter != *splitter_account.key {
+        msg!("Splitter account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    invoke_signed(
+        &system_instruction::create_account(
+            creator.key,
+            splitter_account.key,
+            Rent::get()?.minimum_balance(SPLITTER_ACCOUNT_SIZE),
+            SPLITTER_ACCOUNT_SIZE as u64,
+            program_id,
+        ),
+        &[creator.clone(), splitter_account.clone(), system_program.clone()],
+        &[&[SPLITTER_SEED, creator.key.as_ref(), &seed_bytes, &[bump]]],
+    )?;
+
+    let splitter = SplitterAccount {
+        is_initialized: true,
+        creator: *creator.key,
+        seed,
+        recipients,
+        bump,
+    };
+    splitter.serialize(&mut &mut splitter_account.data.borrow_mut()[..])?;
+
+    msg!("Splitter created with {} recipients", splitter.recipients.len());
+
+    Ok(())
+}
+
+fn distribute(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let splitter_account = next_account_info(accounts_iter)?;
+
+    let splitter = load_splitter(program_id, splitter_account)?;
+
+    let rent_minimum = Rent::get()?.minimum_balance(splitter_account.data_len());
+    let total = splitter_account.lamports().saturating_sub(rent_minimum);
+    let amounts = split_amounts(total, &splitter.recipients);
+    if amounts.iter().all(|&a| a == 0) {
+        return Err(SplitterError::NothingToDistribute.into());
+    }
+
+    for (recipient, amount) in splitter.recipients.iter().zip(amounts) {
+        let payout = next_account_info(accounts_iter)?;
+        if *payout.key != recipient.address {
+            msg!("Expected recipient {}", recipient.address);
+            return Err(SplitterError::RecipientMismatch.into());
+        }
+
+        **splitter_account.try_borrow_mut_lamports()? -= amount;
+        **payout.try_borrow_mut_lamports()? += amount;
+    }
+
+    msg!("Distributed {} lamports", total);
+
+    Ok(())
+}
+
+fn distribute_token(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let splitter_account = next_account_info(accounts_iter)?;
+    let vault = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+
+    if *token_program.key != spl_token::id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let splitter = load_splitter(program_id, splitter_account)?;
+
+    // The token program rejects transfers unless the splitter PDA owns the vault
+    let vault_state = TokenAccount::unpack(&vault.data.borrow())?;
+    let amounts = split_amounts(vault_state.amount, &splitter.recipients);
+    if amounts.iter().all(|&a| a == 0) {
+        return Err(SplitterError::NothingToDistribute.into());
+    }
+
+    let seed_bytes = splitter.seed.to_le_bytes();
+    let signer_seeds: &[&[u8]] = &[SPLITTER_SEED, splitter.creator.as_ref(), &seed_bytes, &[splitter.bump]];
+
+    for (recipient, amount) in splitter.recipients.iter().zip(amounts) {
+        let payout = next_account_info(accounts_iter)?;
+        let payout_state = TokenAccount::unpack(&payout.data.borrow())?;
+        if payout_state.owner != recipient.address || payout_state.mint != vault_state.mint {
+            msg!("Expected a {} token account owned by {}", vault_state.mint, recipient.address);
+            return Err(SplitterError::RecipientMismatch.into());
+        }
+
+        if amount == 0 {
+            continue;
+        }
+
+        invoke_signed(
+            &spl_token::instruction::transfer(
+                &spl_token::id(),
+                vault.key,
+                payout.key,
+                splitter_account.key,
+                &[],
+                amount,
+            )?,
+            &[vault.clone(), payout.clone(), splitter_account.clone()],
+            &[signer_seeds],
+        )?;
+    }
+
+    msg!("Distributed {} of {}", vault_state.amount, vault_state.mint);
+
+    Ok(())
+}
+
+/// Validate the splitter account, returning its state
+fn load_splitter(program_id: &Pubkey, splitter_account: &AccountInfo) -> Result<SplitterAccount, ProgramError> {
+    if splitter_account.owner != program_id {
+        msg!("Splitter account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let splitter = SplitterAccount::deserialize(&mut &splitter_account.data.borrow()[..])?;
+    if !splitter.is_initialized {
+        return Err(SplitterError::NotInitialized.into());
+    }
+
+    Ok(splitter)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn recipient(share_bps: u16) -> Recipient {
+        Recipient {
+            address: Pubkey::new_unique(),
+            share_bps,
+        }
+    }
+
+    #[test]
+    fn test_validate_recipients() {
+        assert_eq!(validate_recipients(&[recipient(6_000), recipient(4_000)]), Ok(()));
+        assert_eq!(validate_recipients(&[]), Err(SplitterError::TooManyRecipients));
+        assert_eq!(
+            validate_recipients(&[recipient(6_000), recipient(3_000)]),
+            Err(SplitterError::InvalidShares)
+        );
+        assert_eq!(
+            validate_recipients(&[recipient(10_000), recipient(0)]),
+            Err(SplitterError::InvalidShares)
+        );
+
+        let twice = recipient(5_000);
+        assert_eq!(
+            validate_recipients(&[twice.clone(), twice]),
+            Err(SplitterError::DuplicateRecipient)
+        );
+    }
+
+    #[test]
+    fn test_split_amounts_round_down() {
+        let recipients = [recipient(5_000), recipient(3_333), recipient(1_667)];
+        let amounts = split_amounts(1_001, &recipients);
+        assert_eq!(amounts, vec![500, 333, 166]);
+
+        // Two lamports of dust stay behind for the next distribution
+        assert_eq!(1_001 - amounts.iter().sum::<u64>(), 2);
+    }
+}
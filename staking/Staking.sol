@@ -0,0 +1,112 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+interface IERC20 {
+    function transfer(address to, uint256 amount) external returns (bool);
+    function transferFrom(address from, address to, uint256 amount) external returns (bool);
+    function balanceOf(address account) external view returns (uint256);
+}
+
+/**
+ * @title Staking
+ * @dev Stake one token and earn another at a fixed rate per block, shared pro rata
+ */
+contract Staking {
+    uint256 public constant REWARD_PRECISION = 1e12;
+
+    struct UserStake {
+        uint256 amount;
+        uint256 rewardDebt;
+        uint256 pendingRewards;
+    }
+
+    IERC20 public immutable stakeToken;
+    IERC20 public immutable rewardToken;
+    uint256 public immutable rewardRate;
+
+    uint256 public accRewardPerShare;
+    uint256 public lastUpdateBlock;
+    uint256 public totalStaked;
+
+    mapping(address => UserStake) public stakes;
+
+    event Staked(address indexed user, uint256 amount);
+    event Unstaked(address indexed user, uint256 amount);
+    event RewardsClaimed(address indexed user, uint256 amount);
+
+    constructor(IERC20 _stakeToken, IERC20 _rewardToken, uint256 _rewardRate) {
+        require(_rewardRate > 0, "Reward rate must be greater than zero");
+        stakeToken = _stakeToken;
+        rewardToken = _rewardToken;
+        rewardRate = _rewardRate;
+        lastUpdateBlock = block.number;
+    }
+
+    /**
+     * @dev Stake tokens (requires prior `approve`)
+     */
+    function stake(uint256 amount) external {
+        require(amount > 0, "Amount must be greater than zero");
+        UserStake storage s = _settle(msg.sender);
+
+        s.amount += amount;
+        s.rewardDebt = (s.amount * accRewardPerShare) / REWARD_PRECISION;
+        totalStaked += amount;
+
+        require(stakeToken.transferFrom(msg.sender, address(this), amount), "Transfer failed");
+
+        emit Staked(msg.sender, amount);
+    }
+
+    /**
+     * @dev Withdraw staked tokens; earned rewards stay pending
+     */
+    function unstake(uint256 amount) external {
+        require(amount > 0, "Amount must be greater than zero");
+        UserStake storage s = _settle(msg.sender);
+        require(s.amount >= amount, "Insufficient stake");
+
+        s.amount -= amount;
+        s.rewardDebt = (s.amount * accRewardPerShare) / REWARD_PRECISION;
+        totalStaked -= amount;
+
+        require(stakeToken.transfer(msg.sender, amount), "Transfer failed");
+
+        emit Unstaked(msg.sender, amount);
+    }
+
+    /**
+     * @dev Pay out all pending rewards
+     */
+    function claimRewards() external {
+        UserStake storage s = _settle(msg.sender);
+        uint256 reward = s.pendingRewards;
+        require(reward > 0, "No rewards");
+        require(rewardToken.balanceOf(address(this)) >= reward, "Insufficient rewards");
+
+        s.pendingRewards = 0;
+
+        require(rewardToken.transfer(msg.sender, reward), "Transfer failed");
+
+        emit RewardsClaimed(msg.sender, reward);
+    }
+
+    function _updatePool() private {
+        if (block.number <= lastUpdateBlock) {
+            return;
+        }
+        if (totalStaked > 0) {
+            uint256 reward = (block.number - lastUpdateBlock) * rewardRate;
+            accRewardPerShare += (reward * REWARD_PRECISION) / totalStaked;
+        }
+        lastUpdateBlock = block.number;
+    }
+
+    function _settle(address user) private returns (UserStake storage s) {
+        _updatePool();
+        s = stakes[user];
+        uint256 accrued = (s.amount * accRewardPerShare) / REWARD_PRECISION;
+        s.pendingRewards += accrued - s.rewardDebt;
+        s.rewardDebt = accrued;
+    }
+}
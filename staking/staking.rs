@@ -0,0 +1,622 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint,
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use spl_token::state::Account as TokenAccount;
+
+/// Seed prefix for stake pool PDAs: ["pool", authority, stake_mint]
+pub const POOL_SEED: &[u8] = b"pool";
+
+/// Seed prefix for the staked-token vault PDA: ["stake_vault", pool]
+pub const STAKE_VAULT_SEED: &[u8] = b"stake_vault";
+
+/// Seed prefix for the reward-token vault PDA: ["reward_vault", pool]
+pub const REWARD_VAULT_SEED: &[u8] = b"reward_vault";
+
+/// Seed prefix for per-user stake PDAs: ["stake", pool, owner]
+pub const USER_STAKE_SEED: &[u8] = b"stake";
+
+/// Fixed-point scale for `acc_reward_per_share`
+pub const REWARD_PRECISION: u128 = 1_000_000_000_000;
+
+/// Size of a `StakePool`
+pub const POOL_ACCOUNT_SIZE: usize = 1 // is_initialized
+    + 32 // authority
+    + 32 // stake_mint
+    + 32 // reward_mint
+    + 32 // stake_vault
+    + 32 // reward_vault
+    + 8 // reward_rate
+    + 16 // acc_reward_per_share
+    + 8 // last_update_slot
+    + 8 // total_staked
+    + 1; // bump
+
+/// Size of a `UserStake`
+pub const USER_STAKE_ACCOUNT_SIZE: usize = 1 // is_initialized
+    + 32 // pool
+    + 32 // owner
+    + 8 // amount
+    + 16 // reward_debt
+    + 8; // pending_rewards
+
+/// Errors returned by the program as `ProgramError::Custom` codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StakingError {
+    /// Amounts must be greater than zero
+    ZeroAmount = 0,
+    /// Unstake amount exceeds the staked balance
+    InsufficientStake = 1,
+    /// The pool or stake account has not been initialized
+    NotInitialized = 2,
+    /// A vault or stake account does not belong to this pool
+    InvalidPoolAccount = 3,
+    /// The reward vault cannot cover the claim; the authority must top it up
+    InsufficientRewards = 4,
+    /// There are no rewards to claim
+    NoRewards = 5,
+}
+
+impl From<StakingError> for ProgramError {
+    fn from(e: StakingError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+/// Pool-wide accounting. Rewards are emitted at `reward_rate` per slot and
+/// shared pro rata through `acc_reward_per_share`, the running total of
+/// rewards earned by one staked token (scaled by `REWARD_PRECISION`).
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct StakePool {
+    pub is_initialized: bool,
+    /// Creator of the pool, who funds the reward vault
+    pub authority: Pubkey,
+    pub stake_mint: Pubkey,
+    pub reward_mint: Pubkey,
+    pub stake_vault: Pubkey,
+    pub reward_vault: Pubkey,
+    /// Reward tokens emitted per slot across all stakers
+    pub reward_rate: u64,
+    pub acc_reward_per_share: u128,
+    pub last_update_slot: u64,
+    pub total_staked: u64,
+    pub bump: u8,
+}
+
+impl StakePool {
+    /// Bring `acc_reward_per_share` up to `slot`. Slots with nothing staked
+    /// emit no rewards.
+    pub fn update(&mut self, slot: u64) -> Result<(), ProgramError> {
+        if slot <= self.last_update_slot {
+            return Ok(());
+        }
+        if self.total_staked > 0 {
+            let elapsed = (slot - self.last_update_slot) as u128;
+            let reward = elapsed
+                .checked_mul(self.reward_rate as u128)
+                .and_then(|r| r.checked_mul(REWARD_PRECISION))
+                .ok_or(ProgramError::ArithmeticOverflow)?;
+            self.acc_reward_per_share = self
+                .acc_reward_per_share
+                .checked_add(reward / self.total_staked as u128)
+                .ok_or(ProgramError::ArithmeticOverflow)?;
+        }
+        self.last_update_slot = slot;
+        Ok(())
+    }
+}
+
+/// One user's position. `reward_debt` is the share of `acc_reward_per_share`
+/// already accounted for, so only rewards earned since the last change accrue.
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct UserStake {
+    pub is_initialized: bool,
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub reward_debt: u128,
+    /// Rewards earned but not yet claimed
+    pub pending_rewards: u64,
+}
+
+impl UserStake {
+    /// Move rewards earned since the last settlement into `pending_rewards`.
+    /// Call after `StakePool::update` and before changing `amount`.
+    pub fn settle(&mut self, acc_reward_per_share: u128) -> Result<(), ProgramError> {
+        let accrued = (self.amount as u128)
+            .checked_mul(acc_reward_per_share)
+            .ok_or(ProgramError::ArithmeticOverflow)?
+            / REWARD_PRECISION;
+        let earned = accrued.saturating_sub(self.reward_debt);
+        self.pending_rewards = u64::try_from(earned)
+            .ok()
+            .and_then(|e| self.pending_rewards.checked_add(e))
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        self.reward_debt = accrued;
+        Ok(())
+    }
+
+    /// Reset `reward_debt` after `amount` changes
+    pub fn reset_debt(&mut self, acc_reward_per_share: u128) -> Result<(), ProgramError> {
+        self.reward_debt = (self.amount as u128)
+            .checked_mul(acc_reward_per_share)
+            .ok_or(ProgramError::ArithmeticOverflow)?
+            / REWARD_PRECISION;
+        Ok(())
+    }
+}
+
+entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = StakingInstruction::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        StakingInstruction::InitializePool { reward_rate } => initialize_pool(program_id, accounts, reward_rate),
+        StakingInstruction::Stake { amount } => stake(program_id, accounts, amount),
+        StakingInstruction::Unstake { amount } => unstake(program_id, accounts, amount),
+        StakingInstruction::ClaimRewards => claim_rewards(program_id, accounts),
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub enum StakingInstruction {
+    /// Create the pool and both vaults. Rewards are funded by transferring
+    /// reward tokens to the reward vault.
+    /// Accounts: [writable, signer] authority, [writable] pool PDA, [] stake mint, [] reward mint,
+    /// [writable] stake vault PDA, [writable] reward vault PDA, [] token program, [] system program
+    InitializePool { reward_rate: u64 },
+    /// Deposit staked tokens, creating the user's stake PDA on first use
+    /// Accounts: [writable, signer] owner, [writable] pool PDA, [writable] user stake PDA,
+    /// [writable] stake vault, [writable] owner stake-token account, [] token program, [] system program
+    Stake { amount: u64 },
+    /// Withdraw staked tokens; earned rewards stay pending
+    /// Accounts: [signer] owner, [writable] pool PDA, [writable] user stake PDA,
+    /// [writable] stake vault, [writable] owner stake-token account, [] token program
+    Unstake { amount: u64 },
+    /// Pay out all pending rewards
+    /// Accounts: [signer] owner, [writable] pool PDA, [writable] user stake PDA,
+    /// [writable] reward vault, [writable] owner reward-token account, [] token program
+    ClaimRewards,
+}
+
+fn initialize_pool(program_id: &Pubkey, accounts: &[AccountInfo], reward_rate: u64) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let authority = next_account_info(accounts_iter)?;
+    let pool_account = next_account_info(accounts_iter)?;
+    let stake_mint = next_account_info(accounts_iter)?;
+    let reward_mint = next_account_info(accounts_iter)?;
+    let stake_vault = next_account_info(accounts_iter)?;
+    let reward_vault = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if reward_rate == 0 {
+        msg!("Reward rate must be greater than zero");
+        return Err(StakingError::ZeroAmount.into());
+    }
+
+    if *token_program.key != spl_token::id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let (expected_pool, bump) = Pubkey::find_program_address(
+        &[POOL_SEED, authority.key.as_ref(), stake_mint.key.as_ref()],
+        program_id,
+    );
+    if expected_pool != *pool_account.key {
+        msg!("Pool account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let rent = Rent::get()?;
+
+    invoke_signed(
+        &system_instruction::create_account(
+            authority.key,
+            pool_account.key,
+            rent.minimum_balance(POOL_ACCOUNT_SIZE),
+            POOL_ACCOUNT_SIZE as u64,
+            program_id,
+        ),
+        &[authority.clone(), pool_account.clone(), system_program.clone()],
+        &[&[POOL_SEED, authority.key.as_ref(), stake_mint.key.as_ref(), &[bump]]],
+    )?;
+
+    // Create both vaults, owned by the pool PDA
+    for (vault, mint, seed) in [
+        (stake_vault, stake_mint, STAKE_VAULT_SEED),
+        (reward_vault, reward_mint, REWARD_VAULT_SEED),
+    ] {
+        let (expected_vault, vault_bump) =
+            Pubkey::find_program_address(&[seed, pool_account.key.as_ref()], program_id);
+        if expected_vault != *vault.key {
+            msg!("Vault account does not match the expected PDA");
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        invoke_signed(
+            &system_instruction::create_account(
+                authority.key,
+                vault.key,
+                rent.minimum_balance(TokenAccount::LEN),
+                TokenAccount::LEN as u64,
+                &spl_token::id(),
+            ),
+            &[authority.clone(), vault.clone(), system_program.clone()],
+            &[&[seed, pool_account.key.as_ref(), &[vault_bump]]],
+        )?;
+        invoke(
+            &spl_token::instruction::initialize_account3(
+                &spl_token::id(),
+                vault.key,
+                mint.key,
+                pool_account.key,
+            )?,
+            &[vault.clone(), mint.clone()],
+        )?;
+    }
+
+    let pool = StakePool {
+        is_initialized: true,
+        authority: *authority.key,
+        stake_mint: *stake_mint.key,
+        reward_mint: *reward_mint.key,
+        stake_vault: *stake_vault.key,
+        reward_vault: *reward_vault.key,
+        reward_rate,
+        acc_reward_per_share: 0,
+        last_update_slot: Clock::get()?.slot,
+        total_staked: 0,
+        bump,
+    };
+    pool.serialize(&mut &mut pool_account.data.borrow_mut()[..])?;
+
+    msg!("Stake pool created with reward rate {} per slot", reward_rate);
+
+    Ok(())
+}
+
+fn stake(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let owner = next_account_info(accounts_iter)?;
+    let pool_account = next_account_info(accounts_iter)?;
+    let user_stake_account = next_account_info(accounts_iter)?;
+    let stake_vault = next_account_info(accounts_iter)?;
+    let owner_token = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if amount == 0 {
+        return Err(StakingError::ZeroAmount.into());
+    }
+
+    let mut pool = load_pool(program_id, pool_account, token_program)?;
+    if pool.stake_vault != *stake_vault.key {
+        return Err(StakingError::InvalidPoolAccount.into());
+    }
+
+    // Create the user's stake account on first deposit
+    if user_stake_account.lamports() == 0 {
+        let (expected_stake, stake_bump) = Pubkey::find_program_address(
+            &[USER_STAKE_SEED, pool_account.key.as_ref(), owner.key.as_ref()],
+            program_id,
+        );
+        if expected_stake != *user_stake_account.key {
+            msg!("Stake account does not match the expected PDA");
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        invoke_signed(
+            &system_instruction::create_account(
+                owner.key,
+                user_stake_account.key,
+                Rent::get()?.minimum_balance(USER_STAKE_ACCOUNT_SIZE),
+                USER_STAKE_ACCOUNT_SIZE as u64,
+                program_id,
+            ),
+            &[owner.clone(), user_stake_account.clone(), system_program.clone()],
+            &[&[USER_STAKE_SEED, pool_account.key.as_ref(), owner.key.as_ref(), &[stake_bump]]],
+        )?;
+
+        let user_stake = UserStake {
+            is_initialized: true,
+            pool: *pool_account.key,
+            owner: *owner.key,
+            amount: 0,
+            reward_debt: 0,
+            pending_rewards: 0,
+        };
+        user_stake.serialize(&mut &mut user_stake_account.data.borrow_mut()[..])?;
+    }
+
+    let mut user_stake = load_user_stake(program_id, user_stake_account, pool_account, owner)?;
+
+    pool.update(Clock::get()?.slot)?;
+    user_stake.settle(pool.acc_reward_per_share)?;
+
+    invoke(
+        &spl_token::instruction::transfer(
+            &spl_token::id(),
+            owner_token.key,
+            stake_vault.key,
+            owner.key,
+            &[],
+            amount,
+        )?,
+        &[owner_token.clone(), stake_vault.clone(), owner.clone()],
+    )?;
+
+    user_stake.amount = user_stake.amount.checked_add(amount).ok_or(ProgramError::ArithmeticOverflow)?;
+    user_stake.reset_debt(pool.acc_reward_per_share)?;
+    pool.total_staked = pool.total_staked.checked_add(amount).ok_or(ProgramError::ArithmeticOverflow)?;
+
+    pool.serialize(&mut &mut pool_account.data.borrow_mut()[..])?;
+    user_stake.serialize(&mut &mut user_stake_account.data.borrow_mut()[..])?;
+
+    msg!("Staked {} (total {})", amount, user_stake.amount);
+
+    Ok(())
+}
+
+fn unstake(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let owner = next_account_info(accounts_iter)?;
+    let pool_account = next_account_info(accounts_iter)?;
+    let user_stake_account = next_account_info(accounts_iter)?;
+    let stake_vault = next_account_info(accounts_iter)?;
+    let owner_token = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if amount == 0 {
+        return Err(StakingError::ZeroAmount.into());
+    }
+
+    let mut pool = load_pool(program_id, pool_account, token_program)?;
+    if pool.stake_vault != *stake_vault.key {
+        return Err(StakingError::InvalidPoolAccount.into());
+    }
+
+    let mut user_stake = load_user_stake(program_id, user_stake_account, pool_account, owner)?;
+
+    if amount > user_stake.amount {
+        msg!("Only {} staked", user_stake.amount);
+        return Err(StakingError::InsufficientStake.into());
+    }
+
+    pool.update(Clock::get()?.slot)?;
+    user_stake.settle(pool.acc_reward_per_share)?;
+
+    user_stake.amount -= amount;
+    user_stake.reset_debt(pool.acc_reward_per_share)?;
+    pool.total_staked -= amount;
+
+    invoke_signed(
+        &spl_token::instruction::transfer(
+            &spl_token::id(),
+            stake_vault.key,
+            owner_token.key,
+            pool_account.key,
+            &[],
+            amount,
+        )?,
+        &[stake_vault.clone(), owner_token.clone(), pool_account.clone()],
+        &[&[POOL_SEED, pool.authority.as_ref(), pool.stake_mint.as_ref(), &[pool.bump]]],
+    )?;
+
+    pool.serialize(&mut &mut pool_account.data.borrow_mut()[..])?;
+    user_stake.serialize(&mut &mut user_stake_account.data.borrow_mut()[..])?;
+
+    msg!("Unstaked {} (remaining {})", amount, user_stake.amount);
+
+    Ok(())
+}
+
+fn claim_rewards(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let owner = next_account_info(accounts_iter)?;
+    let pool_account = next_account_info(accounts_iter)?;
+    let user_stake_account = next_account_info(accounts_iter)?;
+    let reward_vault = next_account_info(accounts_iter)?;
+    let owner_reward_token = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut pool = load_pool(program_id, pool_account, token_program)?;
+    if pool.reward_vault != *reward_vault.key {
+        return Err(StakingError::InvalidPoolAccount.into());
+    }
+
+    let mut user_stake = load_user_stake(program_id, user_stake_account, pool_account, owner)?;
+
+    pool.update(Clock::get()?.slot)?;
+    user_stake.settle(pool.acc_reward_per_share)?;
+
+    let reward = user_stake.pending_rewards;
+    if reward == 0 {
+        return Err(StakingError::NoRewards.into());
+    }
+
+    let available = TokenAccount::unpack(&reward_vault.data.borrow())?.amount;
+    if available < reward {
+        msg!("Reward vault holds {} but {} is owed", available, reward);
+        return Err(StakingError::InsufficientRewards.into());
+    }
+
+    user_stake.pending_rewards = 0;
+
+    invoke_signed(
+        &spl_token::instruction::transfer(
+            &spl_token::id(),
+            reward_vault.key,
+            owner_reward_token.key,
+            pool_account.key,
+            &[],
+            reward,
+        )?,
+        &[reward_vault.clone(), owner_reward_token.clone(), pool_account.clone()],
+        &[&[POOL_SEED, pool.authority.as_ref(), pool.stake_mint.as_ref(), &[pool.bump]]],
+    )?;
+
+    pool.serialize(&mut &mut pool_account.data.borrow_mut()[..])?;
+    user_stake.serialize(&mut &mut user_stake_account.data.borrow_mut()[..])?;
+
+    msg!("Claimed {} reward tokens", reward);
+
+    Ok(())
+}
+
+/// Validate the pool state account, returning the state
+fn load_pool(
+    program_id: &Pubkey,
+    pool_account: &AccountInfo,
+    token_program: &AccountInfo,
+) -> Result<StakePool, ProgramError> {
+    if pool_account.owner != program_id {
+        msg!("Pool account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    if *token_program.key != spl_token::id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let pool = StakePool::try_from_slice(&pool_account.data.borrow())?;
+    if !pool.is_initialized {
+        return Err(StakingError::NotInitialized.into());
+    }
+
+    Ok(pool)
+}
+
+/// Validate a user stake account against its pool and owner, returning the state
+fn load_user_stake(
+    program_id: &Pubkey,
+    user_stake_account: &AccountInfo,
+    pool_account: &AccountInfo,
+    owner: &AccountInfo,
+) -> Result<UserStake, ProgramError> {
+    if user_stake_account.owner != program_id {
+        msg!("Stake account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let user_stake = UserStake::try_from_slice(&user_stake_account.data.borrow())?;
+    if !user_stake.is_initialized {
+        return Err(StakingError::NotInitialized.into());
+    }
+
+    if user_stake.pool != *pool_account.key || user_stake.owner != *owner.key {
+        msg!("Stake account does not belong to this pool and owner");
+        return Err(StakingError::InvalidPoolAccount.into());
+    }
+
+    Ok(user_stake)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn pool(reward_rate: u64) -> StakePool {
+        StakePool {
+            is_initialized: true,
+            authority: Pubkey::new_unique(),
+            stake_mint: Pubkey::new_unique(),
+            reward_mint: Pubkey::new_unique(),
+            stake_vault: Pubkey::new_unique(),
+            reward_vault: Pubkey::new_unique(),
+            reward_rate,
+            acc_reward_per_share: 0,
+            last_update_slot: 0,
+            total_staked: 0,
+            bump: 255,
+        }
+    }
+
+    fn user() -> UserStake {
+        UserStake {
+            is_initialized: true,
+            pool: Pubkey::new_unique(),
+            owner: Pubkey::new_unique(),
+            amount: 0,
+            reward_debt: 0,
+            pending_rewards: 0,
+        }
+    }
+
+    impl UserStake {
+        /// Mirrors the accounting in `stake`
+        fn deposit(&mut self, pool: &mut StakePool, slot: u64, amount: u64) {
+            pool.update(slot).unwrap();
+            self.settle(pool.acc_reward_per_share).unwrap();
+            self.amount += amount;
+            self.reset_debt(pool.acc_reward_per_share).unwrap();
+            pool.total_staked += amount;
+        }
+    }
+
+    #[test]
+    fn test_rewards_split_pro_rata() {
+        let mut pool = pool(100);
+        let mut alice = user();
+        let mut bob = user();
+
+        // Alice alone for 10 slots earns all 1000
+        alice.deposit(&mut pool, 0, 100);
+        bob.deposit(&mut pool, 10, 300);
+
+        // Then 10 slots shared 1:3
+        pool.update(20).unwrap();
+        alice.settle(pool.acc_reward_per_share).unwrap();
+        bob.settle(pool.acc_reward_per_share).unwrap();
+
+        assert_eq!(alice.pending_rewards, 1_000 + 250);
+        assert_eq!(bob.pending_rewards, 750);
+    }
+
+    #[test]
+    fn test_no_rewards_accrue_while_pool_is_empty() {
+        let mut pool = pool(100);
+        pool.update(50).unwrap();
+        assert_eq!(pool.acc_reward_per_share, 0);
+
+        // A late staker does not collect rewards from the empty period
+        let mut carol = user();
+        carol.deposit(&mut pool, 50, 10);
+        pool.update(51).unwrap();
+        carol.settle(pool.acc_reward_per_share).unwrap();
+        assert_eq!(carol.pending_rewards, 100);
+    }
+}
@@ -0,0 +1,90 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+/**
+ * @title Subscriptions
+ * @dev Prefunded ETH subscriptions billed in arrears; merchants pull one period at a time
+ */
+contract Subscriptions {
+    struct Subscription {
+        address merchant;
+        uint256 amountPerPeriod;
+        uint256 periodBlocks;
+        uint256 periodStartBlock;
+        uint256 balance;
+        bool active;
+    }
+
+    // subscriber => merchant => subscription
+    mapping(address => mapping(address => Subscription)) public subscriptions;
+
+    event Subscribed(address indexed subscriber, address indexed merchant, uint256 amountPerPeriod, uint256 periodBlocks);
+    event Funded(address indexed subscriber, address indexed merchant, uint256 amount);
+    event Collected(address indexed subscriber, address indexed merchant, uint256 amount);
+    event Cancelled(address indexed subscriber, address indexed merchant, uint256 paid, uint256 refunded);
+
+    function subscribe(address merchant, uint256 amountPerPeriod, uint256 periodBlocks) external payable {
+        require(amountPerPeriod > 0 && periodBlocks > 0, "Invalid parameters");
+        Subscription storage s = subscriptions[msg.sender][merchant];
+        require(!s.active, "Already subscribed");
+
+        subscriptions[msg.sender][merchant] = Subscription(
+            merchant,
+            amountPerPeriod,
+            periodBlocks,
+            block.number,
+            msg.value,
+            true
+        );
+
+        emit Subscribed(msg.sender, merchant, amountPerPeriod, periodBlocks);
+    }
+
+    function fund(address merchant) external payable {
+        Subscription storage s = subscriptions[msg.sender][merchant];
+        require(s.active, "Not subscribed");
+        s.balance += msg.value;
+        emit Funded(msg.sender, merchant, msg.value);
+    }
+
+    /**
+     * @dev Pay one ended period to the merchant (callable by anyone)
+     */
+    function collect(address subscriber, address merchant) external {
+        Subscription storage s = subscriptions[subscriber][merchant];
+        require(s.active, "Not subscribed");
+        require(block.number >= s.periodStartBlock + s.periodBlocks, "Payment not due");
+        require(s.balance >= s.amountPerPeriod, "Insufficient balance");
+
+        s.periodStartBlock += s.periodBlocks;
+        s.balance -= s.amountPerPeriod;
+
+        (bool success, ) = merchant.call{value: s.amountPerPeriod}("");
+        require(success, "Transfer failed");
+
+        emit Collected(subscriber, merchant, s.amountPerPeriod);
+    }
+
+    /**
+     * @dev Pay unpaid periods plus a prorated share of the current one, refund the rest
+     */
+    function cancel(address merchant) external {
+        Subscription storage s = subscriptions[msg.sender][merchant];
+        require(s.active, "Not subscribed");
+
+        uint256 owed = ((block.number - s.periodStartBlock) * s.amountPerPeriod) / s.periodBlocks;
+        if (owed > s.balance) {
+            owed = s.balance;
+        }
+        uint256 refund = s.balance - owed;
+
+        delete subscriptions[msg.sender][merchant];
+
+        (bool paid, ) = merchant.call{value: owed}("");
+        require(paid, "Transfer failed");
+        (bool refunded, ) = msg.sender.call{value: refund}("");
+        require(refunded, "Refund failed");
+
+        emit Cancelled(msg.sender, merchant, owed, refund);
+    }
+}
@@ -0,0 +1,408 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint,
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// Seed prefix for subscription PDAs: ["subscription", subscriber, merchant]
+pub const SUBSCRIPTION_SEED: &[u8] = b"subscription";
+
+/// Size of a `SubscriptionAccount`
+pub const SUBSCRIPTION_ACCOUNT_SIZE: usize = 1 // is_initialized
+    + 32 // subscriber
+    + 32 // merchant
+    + 8 // amount_per_period
+    + 8 // period_slots
+    + 8 // period_start_slot
+    + 1; // bump
+
+/// Errors returned by the program as `ProgramError::Custom` codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubscriptionError {
+    /// Amount or period is zero
+    InvalidParameters = 0,
+    /// The current period has not ended yet
+    PaymentNotDue = 1,
+    /// The prefunded balance cannot cover the payment
+    InsufficientBalance = 2,
+    /// Only the subscriber may fund or cancel
+    NotSubscriber = 3,
+    /// The subscription has not been initialized
+    NotInitialized = 4,
+    /// The merchant account does not match the subscription
+    WrongMerchant = 5,
+}
+
+impl From<SubscriptionError> for ProgramError {
+    fn from(e: SubscriptionError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+/// A subscription billed in arrears: each period is paid once it has ended.
+/// The prefunded balance is the account's lamports above its rent-exempt minimum.
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct SubscriptionAccount {
+    pub is_initialized: bool,
+    pub subscriber: Pubkey,
+    /// Receives every payment
+    pub merchant: Pubkey,
+    /// Lamports owed for each full period
+    pub amount_per_period: u64,
+    pub period_slots: u64,
+    /// Start of the oldest unpaid period
+    pub period_start_slot: u64,
+    pub bump: u8,
+}
+
+impl SubscriptionAccount {
+    /// Whether the oldest unpaid period has ended at `slot`
+    pub fn payment_due(&self, slot: u64) -> bool {
+        slot >= self.period_start_slot.saturating_add(self.period_slots)
+    }
+
+    /// What the merchant is owed if the subscription stops at `slot`: every
+    /// unpaid full period plus a prorated share of the current one
+    pub fn amount_owed(&self, slot: u64) -> u64 {
+        let elapsed = slot.saturating_sub(self.period_start_slot) as u128;
+        let owed = elapsed * self.amount_per_period as u128 / self.period_slots as u128;
+        u64::try_from(owed).unwrap_or(u64::MAX)
+    }
+}
+
+entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = SubscriptionInstruction::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        SubscriptionInstruction::Subscribe {
+            amount_per_period,
+            period_slots,
+            initial_deposit,
+        } => subscribe(program_id, accounts, amount_per_period, period_slots, initial_deposit),
+        SubscriptionInstruction::Fund { amount } => fund(program_id, accounts, amount),
+        SubscriptionInstruction::Collect => collect(program_id, accounts),
+        SubscriptionInstruction::Cancel => cancel(program_id, accounts),
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub enum SubscriptionInstruction {
+    /// Create the subscription PDA and prefund it; the first period starts now
+    /// Accounts: [writable, signer] subscriber, [] merchant, [writable] subscription PDA, [] system program
+    Subscribe {
+        amount_per_period: u64,
+        period_slots: u64,
+        initial_deposit: u64,
+    },
+    /// Top up the prefunded balance
+    /// Accounts: [writable, signer] subscriber, [writable] subscription PDA, [] system program
+    Fund { amount: u64 },
+    /// Pay one ended period to the merchant. Anyone may call this (e.g. a crank);
+    /// funds can only go to the stored merchant.
+    /// Accounts: [writable] merchant, [writable] subscription PDA
+    Collect,
+    /// Pay the merchant for unpaid periods plus a prorated share of the current
+    /// one, refund the rest, and close the subscription
+    /// Accounts: [writable, signer] subscriber, [writable] merchant, [writable] subscription PDA
+    Cancel,
+}
+
+fn subscribe(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount_per_period: u64,
+    period_slots: u64,
+    initial_deposit: u64,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let subscriber = next_account_info(accounts_iter)?;
+    let merchant = next_account_info(accounts_iter)?;
+    let subscription_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !subscriber.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if amount_per_period == 0 || period_slots == 0 {
+        msg!("Amount and period must be greater than zero");
+        return Err(SubscriptionError::InvalidParameters.into());
+    }
+
+    let (expected_subscription, bump) = Pubkey::find_program_address(
+        &[SUBSCRIPTION_SEED, subscriber.key.as_ref(), merchant.key.as_ref()],
+        program_id,
+    );
+    if expected_subscription != *subscription_account.key {
+        msg!("Subscription account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let rent_minimum = Rent::get()?.minimum_balance(SUBSCRIPTION_ACCOUNT_SIZE);
+    let lamports = rent_minimum
+        .checked_add(initial_deposit)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    invoke_signed(
+        &system_instruction::create_account(
+            subscriber.key,
+            subscription_account.key,
+            lamports,
+            SUBSCRIPTION_ACCOUNT_SIZE as u64,
+            program_id,
+        ),
+        &[subscriber.clone(), subscription_account.clone(), system_program.clone()],
+        &[&[SUBSCRIPTION_SEED, subscriber.key.as_ref(), merchant.key.as_ref(), &[bump]]],
+    )?;
+
+    let subscription = SubscriptionAccount {
+        is_initialized: true,
+        subscriber: *subscriber.key,
+        merchant: *merchant.key,
+        amount_per_period,
+        period_slots,
+        period_start_slot: Clock::get()?.slot,
+        bump,
+    };
+    subscription.serialize(&mut &mut subscription_account.data.borrow_mut()[..])?;
+
+    msg!(
+        "Subscribed to {}: {} lamports every {} slots",
+        merchant.key,
+        amount_per_period,
+        period_slots
+    );
+
+    Ok(())
+}
+
+fn fund(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let subscriber = next_account_info(accounts_iter)?;
+    let subscription_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !subscriber.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let subscription = load_subscription(program_id, subscription_account)?;
+
+    if subscription.subscriber != *subscriber.key {
+        return Err(SubscriptionError::NotSubscriber.into());
+    }
+
+    invoke(
+        &system_instruction::transfer(subscriber.key, subscription_account.key, amount),
+        &[subscriber.clone(), subscription_account.clone(), system_program.clone()],
+    )?;
+
+    msg!("Added {} lamports", amount);
+
+    Ok(())
+}
+
+fn collect(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let merchant = next_account_info(accounts_iter)?;
+    let subscription_account = next_account_info(accounts_iter)?;
+
+    let mut subscription = load_subscription(program_id, subscription_account)?;
+
+    if subscription.merchant != *merchant.key {
+        return Err(SubscriptionError::WrongMerchant.into());
+    }
+
+    let clock = Clock::get()?;
+    if !subscription.payment_due(clock.slot) {
+        msg!(
+            "Next payment due at slot {}",
+            subscription.period_start_slot + subscription.period_slots
+        );
+        return Err(SubscriptionError::PaymentNotDue.into());
+    }
+
+    let balance = prefunded_balance(subscription_account)?;
+    if balance < subscription.amount_per_period {
+        msg!("Balance {} cannot cover {}", balance, subscription.amount_per_period);
+        return Err(SubscriptionError::InsufficientBalance.into());
+    }
+
+    // One period per call, so a missed crank can catch up one period at a time
+    subscription.period_start_slot += subscription.period_slots;
+    subscription.serialize(&mut &mut subscription_account.data.borrow_mut()[..])?;
+
+    **subscription_account.try_borrow_mut_lamports()? -= subscription.amount_per_period;
+    **merchant.try_borrow_mut_lamports()? += subscription.amount_per_period;
+
+    msg!("Collected {} lamports", subscription.amount_per_period);
+
+    Ok(())
+}
+
+fn cancel(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let subscriber = next_account_info(accounts_iter)?;
+    let merchant = next_account_info(accounts_iter)?;
+    let subscription_account = next_account_info(accounts_iter)?;
+
+    if !subscriber.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let subscription = load_subscription(program_id, subscription_account)?;
+
+    if subscription.subscriber != *subscriber.key {
+        return Err(SubscriptionError::NotSubscriber.into());
+    }
+
+    if subscription.merchant != *merchant.key {
+        return Err(SubscriptionError::WrongMerchant.into());
+    }
+
+    let balance = prefunded_balance(subscription_account)?;
+    let owed = subscription.amount_owed(Clock::get()?.slot).min(balance);
+
+    // Pay the merchant what is owed; everything else, rent included, goes back
+    // to the subscriber as the account closes
+    let refund = subscription_account.lamports() - owed;
+    **subscription_account.try_borrow_mut_lamports()? = 0;
+    **merchant.try_borrow_mut_lamports()? += owed;
+    **subscriber.try_borrow_mut_lamports()? += refund;
+    subscription_account.data.borrow_mut().fill(0);
+
+    msg!("Cancelled: {} to merchant, {} refunded", owed, refund);
+
+    Ok(())
+}
+
+/// Validate the subscription account, returning its state
+fn load_subscription(
+    program_id: &Pubkey,
+    subscription_account: &AccountInfo,
+) -> Result<SubscriptionAccount, ProgramError> {
+    if subscription_account.owner != program_id {
+        msg!("Subscription account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let subscription = SubscriptionAccount::try_from_slice(&subscription_account.data.borrow())?;
+    if !subscription.is_initialized {
+        return Err(SubscriptionError::NotInitialized.into());
+    }
+
+    Ok(subscription)
+}
+
+/// Lamports available for payments: everything above the rent-exempt minimum
+fn prefunded_balance(subscription_account: &AccountInfo) -> Result<u64, ProgramError> {
+    let rent_minimum = Rent::get()?.minimum_balance(subscription_account.data_len());
+    Ok(subscription_account.lamports().saturating_sub(rent_minimum))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_program::{clock::Epoch, entrypoint::SUCCESS, program_stubs};
+
+    /// Serves a Clock sysvar at a fixed slot so handlers can run off-chain
+    struct TestSyscallStubs {
+        slot: u64,
+    }
+
+    impl program_stubs::SyscallStubs for TestSyscallStubs {
+        fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+            unsafe {
+                *(var_addr as *mut Clock) = Clock {
+                    slot: self.slot,
+                    ..Clock::default()
+                };
+            }
+            SUCCESS
+        }
+    }
+
+    fn subscription(merchant: Pubkey) -> SubscriptionAccount {
+        SubscriptionAccount {
+            is_initialized: true,
+            subscriber: Pubkey::new_unique(),
+            merchant,
+            amount_per_period: 1_000,
+            period_slots: 100,
+            period_start_slot: 500,
+            bump: 255,
+        }
+    }
+
+    #[test]
+    fn test_payment_due_and_proration() {
+        let sub = subscription(Pubkey::new_unique());
+
+        assert!(!sub.payment_due(599));
+        assert!(sub.payment_due(600));
+
+        // A quarter of the way into the first period
+        assert_eq!(sub.amount_owed(525), 250);
+        // One full unpaid period plus half of the next
+        assert_eq!(sub.amount_owed(650), 1_500);
+        // Nothing is owed for slots before the period started
+        assert_eq!(sub.amount_owed(400), 0);
+    }
+
+    #[test]
+    fn test_collect_before_period_ends_fails() {
+        program_stubs::set_syscall_stubs(Box::new(TestSyscallStubs { slot: 550 }));
+
+        let program_id = Pubkey::new_unique();
+        let merchant = Pubkey::new_unique();
+        let subscription_key = Pubkey::new_unique();
+
+        let mut merchant_lamports = 0;
+        let mut merchant_data = vec![];
+        let mut subscription_lamports = 1_000_000;
+        let mut subscription_data = subscription(merchant).try_to_vec().unwrap();
+
+        let accounts = vec![
+            AccountInfo::new(
+                &merchant,
+                false,
+                true,
+                &mut merchant_lamports,
+                &mut merchant_data,
+                &program_id,
+                false,
+                Epoch::default(),
+            ),
+            AccountInfo::new(
+                &subscription_key,
+                false,
+                true,
+                &mut subscription_lamports,
+                &mut subscription_data,
+                &program_id,
+                false,
+                Epoch::default(),
+            ),
+        ];
+
+        let instruction_data = SubscriptionInstruction::Collect.try_to_vec().unwrap();
+        let result = process_instruction(&program_id, &accounts, &instruction_data);
+        assert_eq!(result, Err(SubscriptionError::PaymentNotDue.into()));
+    }
+}
@@ -0,0 +1,123 @@
+//! `TestAccount`: an owned account for unit tests that call a program's
+//! `process_instruction` directly, built fluently instead of declaring the
+//! lamports, data and flags of every `AccountInfo::new` by hand.
+//!
+//! ```ignore
+//! let mut counter = TestAccount::new(key).writable().data_len(CounterAccount::LEN).owner(program_id);
+//! let mut owner = TestAccount::new(owner_key).signer();
+//! let accounts = [counter.info(), owner.info()];
+//! process_instruction(&program_id, &accounts, &data)?;
+//! ```
+
+use solana_sdk::{account_info::AccountInfo, clock::Epoch, pubkey::Pubkey, rent::Rent, system_program};
+
+/// An account's key, lamports, data and flags, lent out as an `AccountInfo`.
+/// Starts as a read-only, non-signer, empty account owned by the system
+/// program, with no lamports.
+#[derive(Clone, Debug)]
+pub struct TestAccount {
+    key: Pubkey,
+    is_signer: bool,
+    is_writable: bool,
+    lamports: u64,
+    data: Vec<u8>,
+    owner: Pubkey,
+    executable: bool,
+}
+
+impl TestAccount {
+    pub fn new(key: Pubkey) -> Self {
+        Self {
+            key,
+            is_signer: false,
+            is_writable: false,
+            lamports: 0,
+            data: vec![],
+            owner: system_program::id(),
+            executable: false,
+        }
+    }
+
+    pub fn signer(mut self) -> Self {
+        self.is_signer = true;
+        self
+    }
+
+    pub fn writable(mut self) -> Self {
+        self.is_writable = true;
+        self
+    }
+
+    pub fn executable(mut self) -> Self {
+        self.executable = true;
+        self
+    }
+
+    pub fn lamports(mut self, lamports: u64) -> Self {
+        self.lamports = lamports;
+        self
+    }
+
+    /// Fund the account to the default Rent sysvar's minimum for its data,
+    /// so set the data first
+    pub fn rent_exempt(mut self) -> Self {
+        self.lamports = Rent::default().minimum_balance(self.data.len());
+        self
+    }
+
+    pub fn data(mut self, data: impl Into<Vec<u8>>) -> Self {
+        self.data = data.into();
+        self
+    }
+
+    /// Zero-fill the data to `len` bytes, keeping any data already set
+    pub fn data_len(mut self, len: usize) -> Self {
+        self.data.resize(len, 0);
+        self
+    }
+
+    pub fn owner(mut self, owner: Pubkey) -> Self {
+        self.owner = owner;
+        self
+    }
+
+    pub fn key(&self) -> Pubkey {
+        self.key
+    }
+
+    /// Lend the account to a program as an `AccountInfo`; writes through it
+    /// land in this `TestAccount`
+    pub fn info(&mut self) -> AccountInfo<'_> {
+        AccountInfo::new(
+            &self.key,
+            self.is_signer,
+            self.is_writable,
+            &mut self.lamports,
+            &mut self.data,
+            &self.owner,
+            self.executable,
+            Epoch::default(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_builder() {
+        let (key, program_id) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut account = TestAccount::new(key).signer().writable().data([1, 2]).data_len(200).owner(program_id);
+        let info = account.info();
+        assert_eq!((*info.key, *info.owner), (key, program_id));
+        assert!(info.is_signer && info.is_writable && !info.executable);
+        assert_eq!((info.data_len(), info.data.borrow()[1], info.lamports()), (200, 2, 0));
+
+        **info.lamports.borrow_mut() = 5;
+        assert_eq!(account.info().lamports(), 5);
+
+        let mut funded = TestAccount::new(key).data_len(10).rent_exempt();
+        assert_eq!(funded.info().lamports(), Rent::default().minimum_balance(10));
+    }
+}
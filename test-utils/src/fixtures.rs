@@ -0,0 +1,144 @@
+//! Account fixtures: accounts saved as JSON so regression tests can replay
+//! real cluster state against a new program version.
+//!
+//! A fixture is the JSON `solana account --output json` prints, which is also
+//! what `solana-test-validator --account` loads, so a devnet account is
+//! captured with the stock CLI:
+//!
+//! ```bash
+//! solana account <ADDRESS> --url devnet --output json --output-file fixture.json
+//! ```
+//!
+//! `snapshot` writes the same files from a running `ProgramTest`.
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use serde::{Deserialize, Serialize};
+use solana_account_decoder::{UiAccount, UiAccountEncoding};
+use solana_program_test::{BanksClient, ProgramTest};
+use solana_sdk::{account::Account, pubkey::Pubkey};
+
+/// One account and its address
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AccountFixture {
+    pub pubkey: String,
+    pub account: UiAccount,
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+impl AccountFixture {
+    /// Capture `account` at `pubkey`, with its data base64-encoded
+    pub fn new(pubkey: &Pubkey, account: &Account) -> Self {
+        Self {
+            pubkey: pubkey.to_string(),
+            account: UiAccount::encode(pubkey, account, UiAccountEncoding::Base64, None, None),
+        }
+    }
+
+    pub fn pubkey(&self) -> io::Result<Pubkey> {
+        Pubkey::from_str(&self.pubkey).map_err(|error| invalid_data(format!("pubkey {}: {}", self.pubkey, error)))
+    }
+
+    /// The account as the runtime stores it
+    pub fn to_account(&self) -> io::Result<Account> {
+        self.account
+            .decode()
+            .ok_or_else(|| invalid_data(format!("account {} has undecodable data or owner", self.pubkey)))
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref();
+        let json = fs::read_to_string(path)?;
+        serde_json::from_str(&json).map_err(|error| invalid_data(format!("{}: {}", path.display(), error)))
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(path, json + "\n")
+    }
+}
+
+/// Add the fixture at `path` to `program_test`, returning its address
+pub fn add_fixture(program_test: &mut ProgramTest, path: impl AsRef<Path>) -> io::Result<Pubkey> {
+    let fixture = AccountFixture::load(path)?;
+    let pubkey = fixture.pubkey()?;
+    program_test.add_account(pubkey, fixture.to_account()?);
+    Ok(pubkey)
+}
+
+/// Add every `*.json` fixture in `dir` to `program_test`, returning their
+/// addresses in file name order
+pub fn add_fixtures(program_test: &mut ProgramTest, dir: impl AsRef<Path>) -> io::Result<Vec<Pubkey>> {
+    let mut paths = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|extension| extension == "json") {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+    paths.into_iter().map(|path| add_fixture(program_test, path)).collect()
+}
+
+/// Save each of `pubkeys` from a running `ProgramTest` to `dir` as
+/// `<pubkey>.json`, returning the paths written. Fails on a missing account.
+pub async fn snapshot(
+    banks_client: &mut BanksClient,
+    pubkeys: &[Pubkey],
+    dir: impl AsRef<Path>,
+) -> io::Result<Vec<PathBuf>> {
+    let dir = dir.as_ref();
+    fs::create_dir_all(dir)?;
+    let mut paths = Vec::new();
+    for pubkey in pubkeys {
+        let account = banks_client
+            .get_account(*pubkey)
+            .await
+            .map_err(io::Error::other)?
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("account {} not found", pubkey)))?;
+        let path = dir.join(format!("{}.json", pubkey));
+        AccountFixture::new(pubkey, &account).save(&path)?;
+        paths.push(path);
+    }
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_fixture_round_trip() {
+        let pubkey = Pubkey::new_unique();
+        let account = Account {
+            lamports: 1_231_920,
+            data: vec![1, 2, 3, 255],
+            owner: Pubkey::new_unique(),
+            executable: false,
+            rent_epoch: u64::MAX,
+        };
+        let dir = std::env::temp_dir().join(format!("test-utils-fixtures-{}", pubkey));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("account.json");
+        AccountFixture::new(&pubkey, &account).save(&path).unwrap();
+
+        // The CLI's field names, so either side can read the other's files
+        let json: serde_json::Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(json["account"]["data"][1], "base64");
+        assert_eq!(json["account"]["rentEpoch"], u64::MAX);
+
+        let fixture = AccountFixture::load(&path).unwrap();
+        assert_eq!((fixture.pubkey().unwrap(), fixture.to_account().unwrap()), (pubkey, account));
+
+        let mut program_test = ProgramTest::default();
+        assert_eq!(add_fixtures(&mut program_test, &dir).unwrap(), vec![pubkey]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,119 @@
+//! Golden byte fixtures: the serialized bytes of canonical values, checked
+//! in so a change to any on-chain layout (a reordered field, a widened type,
+//! a variant inserted mid-enum) fails a test instead of silently breaking
+//! deployed accounts and clients.
+//!
+//! A fixture is a text file with one `<name> <hex>` line per value. After a
+//! deliberate layout change, rewrite it with
+//!
+//! ```bash
+//! UPDATE_GOLDEN=1 cargo test --workspace
+//! ```
+//!
+//! and review the diff.
+
+use std::{collections::BTreeMap, fmt::Write, fs, path::Path};
+
+/// Named byte strings compared as a whole against one fixture file
+#[derive(Debug, Default)]
+pub struct GoldenBytes {
+    entries: BTreeMap<String, Vec<u8>>,
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::with_capacity(bytes.len() * 2), |mut hex, byte| {
+        write!(hex, "{:02x}", byte).unwrap();
+        hex
+    })
+}
+
+fn from_hex(hex: &str) -> Vec<u8> {
+    (0..hex.len())
+        .step_by(2)
+        .map(|at| u8::from_str_radix(&hex[at..at + 2], 16).expect("fixture bytes are hex"))
+        .collect()
+}
+
+/// The entries of the fixture at `path`, to check that bytes written by an
+/// earlier version still decode
+pub fn read_fixture(path: impl AsRef<Path>) -> BTreeMap<String, Vec<u8>> {
+    let path = path.as_ref();
+    let fixture = fs::read_to_string(path).unwrap_or_else(|error| panic!("reading {}: {}", path.display(), error));
+    fixture
+        .lines()
+        .filter_map(|line| line.split_once(' '))
+        .map(|(name, hex)| (name.to_string(), from_hex(hex)))
+        .collect()
+}
+
+impl GoldenBytes {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record `bytes` under `name`, which must be unique in the fixture
+    pub fn add(&mut self, name: &str, bytes: impl AsRef<[u8]>) -> &mut Self {
+        let previous = self.entries.insert(name.to_string(), bytes.as_ref().to_vec());
+        assert!(previous.is_none(), "golden entry {} added twice", name);
+        self
+    }
+
+    fn render(&self) -> String {
+        self.entries.iter().map(|(name, bytes)| format!("{} {}\n", name, to_hex(bytes))).collect()
+    }
+
+    /// Names of the entries that differ from, are missing from or are extra
+    /// to the rendered fixture `expected`
+    fn changed(&self, expected: &str) -> Vec<String> {
+        let rendered = self.render();
+        let expected: BTreeMap<&str, &str> = expected.lines().filter_map(|line| line.split_once(' ')).collect();
+        let actual: BTreeMap<&str, &str> = rendered.lines().filter_map(|line| line.split_once(' ')).collect();
+
+        let mut changed: Vec<String> = actual
+            .iter()
+            .filter(|(name, hex)| expected.get(*name) != Some(hex))
+            .map(|(name, _)| name.to_string())
+            .collect();
+        changed.extend(expected.keys().filter(|name| !actual.contains_key(*name)).map(|name| name.to_string()));
+        changed
+    }
+
+    /// Panic, naming every entry that differs, unless the fixture at `path`
+    /// holds exactly these entries. Writes the fixture instead when
+    /// `UPDATE_GOLDEN` is set.
+    pub fn check(&self, path: impl AsRef<Path>) {
+        let path = path.as_ref();
+        if std::env::var_os("UPDATE_GOLDEN").is_some() {
+            fs::write(path, self.render()).unwrap_or_else(|error| panic!("writing {}: {}", path.display(), error));
+            return;
+        }
+
+        let expected = fs::read_to_string(path).unwrap_or_else(|error| {
+            panic!("reading {}: {}; run with UPDATE_GOLDEN=1 to create it", path.display(), error)
+        });
+        let changed = self.changed(&expected);
+        assert!(
+            changed.is_empty(),
+            "layout changed for {:?} (fixture {}); if deliberate, rerun with UPDATE_GOLDEN=1",
+            changed,
+            path.display()
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_golden_bytes() {
+        let mut golden = GoldenBytes::new();
+        golden.add("b", [1]).add("a", [0, 255]);
+        assert_eq!(golden.render(), "a 00ff\nb 01\n");
+        assert!(golden.changed("a 00ff\nb 01\n").is_empty());
+
+        // A changed value, a missing entry and an extra one
+        assert_eq!(golden.changed("a 00fe\nc 02\n"), ["a", "b", "c"]);
+        assert_eq!(from_hex("00ff"), [0, 255]);
+    }
+}
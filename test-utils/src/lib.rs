@@ -0,0 +1,10 @@
+//! Support code shared by the programs' tests.
+//!
+//! - `account`: `TestAccount`, a builder for the `AccountInfo`s unit tests pass
+//!   to `process_instruction`
+//! - `fixtures`: accounts saved as JSON and loaded back into a `ProgramTest`
+//! - `golden`: checked-in serialized bytes that lock the on-chain layouts
+
+pub mod account;
+pub mod fixtures;
+pub mod golden;
@@ -0,0 +1,119 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+/**
+ * @title TicTacToe
+ * @dev Two-player tic-tac-toe with an optional ETH wager. The creator plays X
+ * and moves first; the opponent matches the wager to join. The winner takes
+ * the pot and a draw refunds both players.
+ */
+contract TicTacToe {
+    enum Mark { Empty, X, O }
+    enum State { WaitingForOpponent, Active, XWon, OWon, Draw, Cancelled }
+
+    struct Game {
+        address payable playerX;
+        address payable playerO;
+        uint256 wager;
+        Mark[9] board;
+        Mark turn;
+        State state;
+    }
+
+    Game[] private games;
+
+    event GameCreated(uint256 indexed gameId, address indexed playerX, uint256 wager);
+    event GameJoined(uint256 indexed gameId, address indexed playerO);
+    event Moved(uint256 indexed gameId, address indexed player, uint8 cell);
+    event GameOver(uint256 indexed gameId, State result);
+
+    function createGame() external payable returns (uint256 gameId) {
+        gameId = games.length;
+        Game storage game = games.push();
+        game.playerX = payable(msg.sender);
+        game.wager = msg.value;
+        game.turn = Mark.X;
+        emit GameCreated(gameId, msg.sender, msg.value);
+    }
+
+    function joinGame(uint256 gameId) external payable {
+        Game storage game = games[gameId];
+        require(game.state == State.WaitingForOpponent, "Already joined");
+        require(msg.sender != game.playerX, "Cannot play yourself");
+        require(msg.value == game.wager, "Wager mismatch");
+
+        game.playerO = payable(msg.sender);
+        game.state = State.Active;
+        emit GameJoined(gameId, msg.sender);
+    }
+
+    function play(uint256 gameId, uint8 cell) external {
+        Game storage game = games[gameId];
+        require(game.state == State.Active, "Not active");
+        Mark mark = msg.sender == game.playerX ? Mark.X : msg.sender == game.playerO ? Mark.O : Mark.Empty;
+        require(mark != Mark.Empty, "Not a player");
+        require(mark == game.turn, "Not your turn");
+        require(cell < 9, "Invalid cell");
+        require(game.board[cell] == Mark.Empty, "Cell occupied");
+
+        game.board[cell] = mark;
+        game.turn = mark == Mark.X ? Mark.O : Mark.X;
+        emit Moved(gameId, msg.sender, cell);
+
+        if (_hasLine(game.board, mark)) {
+            game.state = mark == Mark.X ? State.XWon : State.OWon;
+            emit GameOver(gameId, game.state);
+            _pay(mark == Mark.X ? game.playerX : game.playerO, game.wager * 2);
+        } else if (_isFull(game.board)) {
+            game.state = State.Draw;
+            emit GameOver(gameId, State.Draw);
+            _pay(game.playerX, game.wager);
+            _pay(game.playerO, game.wager);
+        }
+    }
+
+    /// @dev Creator withdraws from a game nobody joined
+    function cancel(uint256 gameId) external {
+        Game storage game = games[gameId];
+        require(msg.sender == game.playerX, "Only creator");
+        require(game.state == State.WaitingForOpponent, "Already joined");
+
+        game.state = State.Cancelled;
+        emit GameOver(gameId, State.Cancelled);
+        _pay(game.playerX, game.wager);
+    }
+
+    function getBoard(uint256 gameId) external view returns (Mark[9] memory) {
+        return games[gameId].board;
+    }
+
+    function _hasLine(Mark[9] storage b, Mark m) private view returns (bool) {
+        uint8[3][8] memory lines = [
+            [0, 1, 2], [3, 4, 5], [6, 7, 8],
+            [0, 3, 6], [1, 4, 7], [2, 5, 8],
+            [0, 4, 8], [2, 4, 6]
+        ];
+        for (uint256 i = 0; i < 8; i++) {
+            if (b[lines[i][0]] == m && b[lines[i][1]] == m && b[lines[i][2]] == m) {
+                return true;
+            }
+        }
+        return false;
+    }
+
+    function _isFull(Mark[9] storage b) private view returns (bool) {
+        for (uint256 i = 0; i < 9; i++) {
+            if (b[i] == Mark.Empty) {
+                return false;
+            }
+        }
+        return true;
+    }
+
+    function _pay(address payable to, uint256 amount) private {
+        if (amount > 0) {
+            (bool success, ) = to.call{value: amount}("");
+            require(success, "Transfer failed");
+        }
+    }
+}
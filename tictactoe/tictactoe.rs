@@ -0,0 +1,456 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint,
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// Seed prefix for game PDAs: ["game", player_x, game_id]
+pub const GAME_SEED: &[u8] = b"game";
+
+/// Every line that wins: rows, columns, diagonals
+pub const LINES: [[usize; 3]; 8] = [
+    [0, 1, 2],
+    [3, 4, 5],
+    [6, 7, 8],
+    [0, 3, 6],
+    [1, 4, 7],
+    [2, 5, 8],
+    [0, 4, 8],
+    [2, 4, 6],
+];
+
+/// Size of a `GameAccount`
+pub const GAME_ACCOUNT_SIZE: usize = 1 // is_initialized
+    + 8 // game_id
+    + 32 // player_x
+    + 32 // player_o
+    + 8 // wager
+    + 9 // board
+    + 1 // turn
+    + 1 // state
+    + 1; // bump
+
+/// Errors returned by the program as `ProgramError::Custom` codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TicTacToeError {
+    /// Cell index is not 0-8
+    InvalidCell = 0,
+    /// Cell already has a mark
+    CellOccupied = 1,
+    /// It is the other player's turn
+    NotYourTurn = 2,
+    /// The game is not being played
+    NotActive = 3,
+    /// Signer is not a player in this game
+    NotAPlayer = 4,
+    /// The game already has an opponent
+    AlreadyJoined = 5,
+    /// Only the creator can do this
+    Unauthorized = 6,
+    /// The game has not been initialized
+    NotInitialized = 7,
+    /// A player account does not match the game
+    WrongPlayerAccount = 8,
+    /// The game has started and is not finished
+    GameInProgress = 9,
+}
+
+impl From<TicTacToeError> for ProgramError {
+    fn from(e: TicTacToeError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+/// A board cell
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mark {
+    Empty,
+    X,
+    O,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameState {
+    WaitingForOpponent,
+    Active,
+    XWon,
+    OWon,
+    Draw,
+}
+
+/// One game; holds both wagers until it ends
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct GameAccount {
+    pub is_initialized: bool,
+    pub game_id: u64,
+    pub player_x: Pubkey,
+    pub player_o: Pubkey,
+    /// Lamports each player stakes; zero for a friendly game
+    pub wager: u64,
+    /// Row-major, cell 0 is top left
+    pub board: [Mark; 9],
+    /// Mark of the player to move
+    pub turn: Mark,
+    pub state: GameState,
+    pub bump: u8,
+}
+
+/// The mark that holds a full line, if any
+pub fn winner(board: &[Mark; 9]) -> Option<Mark> {
+    LINES.iter().find_map(|[a, b, c]| {
+        let mark = board[*a];
+        (mark != Mark::Empty && mark == board[*b] && mark == board[*c]).then_some(mark)
+    })
+}
+
+impl GameAccount {
+    /// Place the mark of `player` on `cell` and return the resulting state
+    pub fn play(&mut self, player: &Pubkey, cell: u8) -> Result<GameState, TicTacToeError> {
+        if self.state != GameState::Active {
+            return Err(TicTacToeError::NotActive);
+        }
+
+        let mark = if *player == self.player_x {
+            Mark::X
+        } else if *player == self.player_o {
+            Mark::O
+        } else {
+            return Err(TicTacToeError::NotAPlayer);
+        };
+        if mark != self.turn {
+            return Err(TicTacToeError::NotYourTurn);
+        }
+
+        let cell = cell as usize;
+        if cell >= self.board.len() {
+            return Err(TicTacToeError::InvalidCell);
+        }
+        if self.board[cell] != Mark::Empty {
+            return Err(TicTacToeError::CellOccupied);
+        }
+        self.board[cell] = mark;
+
+        self.state = match winner(&self.board) {
+            Some(Mark::X) => GameState::XWon,
+            Some(_) => GameState::OWon,
+            None if self.board.iter().all(|m| *m != Mark::Empty) => GameState::Draw,
+            None => GameState::Active,
+        };
+        self.turn = if mark == Mark::X { Mark::O } else { Mark::X };
+
+        Ok(self.state)
+    }
+}
+
+entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = TicTacToeInstruction::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        TicTacToeInstruction::CreateGame { game_id, wager } => create_game(program_id, accounts, game_id, wager),
+        TicTacToeInstruction::JoinGame => join_game(program_id, accounts),
+        TicTacToeInstruction::Play { cell } => play(program_id, accounts, cell),
+        TicTacToeInstruction::Close => close(program_id, accounts),
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub enum TicTacToeInstruction {
+    /// Open a game as X, staking `wager` lamports
+    /// Accounts: [writable, signer] player X, [writable] game PDA, [] system program
+    CreateGame { game_id: u64, wager: u64 },
+    /// Join as O, matching the wager; X moves first
+    /// Accounts: [writable, signer] player O, [writable] game PDA, [] system program
+    JoinGame,
+    /// Mark `cell` (0-8); a finishing move pays out the pot
+    /// Accounts: [signer] player, [writable] game PDA, [writable] player X, [writable] player O
+    Play { cell: u8 },
+    /// Creator closes a game that nobody joined or that has ended, taking the rent
+    /// and any unclaimed wager back
+    /// Accounts: [writable, signer] player X, [writable] game PDA
+    Close,
+}
+
+fn create_game(program_id: &Pubkey, accounts: &[AccountInfo], game_id: u64, wager: u64) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let player_x = next_account_info(accounts_iter)?;
+    let game_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !player_x.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let (expected_game, bump) = Pubkey::find_program_address(
+        &[GAME_SEED, player_x.key.as_ref(), &game_id.to_le_bytes()],
+        program_id,
+    );
+    if expected_game != *game_account.key {
+        msg!("Game account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    // The wager rides on top of rent, so the game PDA is the escrow
+    let lamports = Rent::get()?
+        .minimum_balance(GAME_ACCOUNT_SIZE)
+        .checked_add(wager)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    invoke_signed(
+        &system_instruction::create_account(
+            player_x.key,
+            game_account.key,
+            lamports,
+            GAME_ACCOUNT_SIZE as u64,
+            program_id,
+        ),
+        &[player_x.clone(), game_account.clone(), system_program.clone()],
+        &[&[GAME_SEED, player_x.key.as_ref(), &game_id.to_le_bytes(), &[bump]]],
+    )?;
+
+    let game = GameAccount {
+        is_initialized: true,
+        game_id,
+        player_x: *player_x.key,
+        player_o: Pubkey::default(),
+        wager,
+        board: [Mark::Empty; 9],
+        turn: Mark::X,
+        state: GameState::WaitingForOpponent,
+        bump,
+    };
+    game.serialize(&mut &mut game_account.data.borrow_mut()[..])?;
+
+    msg!("Game {} created with a wager of {}", game_id, wager);
+
+    Ok(())
+}
+
+fn join_game(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let player_o = next_account_info(accounts_iter)?;
+    let game_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !player_o.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut game = load_game(program_id, game_account)?;
+
+    if game.state != GameState::WaitingForOpponent {
+        return Err(TicTacToeError::AlreadyJoined.into());
+    }
+    if game.player_x == *player_o.key {
+        msg!("Cannot play against yourself");
+        return Err(TicTacToeError::NotAPlayer.into());
+    }
+
+    if game.wager > 0 {
+        invoke(
+            &system_instruction::transfer(player_o.key, game_account.key, game.wager),
+            &[player_o.clone(), game_account.clone(), system_program.clone()],
+        )?;
+    }
+
+    game.player_o = *player_o.key;
+    game.state = GameState::Active;
+    game.serialize(&mut &mut game_account.data.borrow_mut()[..])?;
+
+    msg!("{} joined game {}", player_o.key, game.game_id);
+
+    Ok(())
+}
+
+fn play(program_id: &Pubkey, accounts: &[AccountInfo], cell: u8) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let player = next_account_info(accounts_iter)?;
+    let game_account = next_account_info(accounts_iter)?;
+    let player_x = next_account_info(accounts_iter)?;
+    let player_o = next_account_info(accounts_iter)?;
+
+    if !player.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut game = load_game(program_id, game_account)?;
+
+    if game.player_x != *player_x.key || game.player_o != *player_o.key {
+        return Err(TicTacToeError::WrongPlayerAccount.into());
+    }
+
+    let state = game.play(player.key, cell)?;
+    game.serialize(&mut &mut game_account.data.borrow_mut()[..])?;
+
+    msg!("{} marked cell {}", player.key, cell);
+
+    match state {
+        GameState::XWon => pay(game_account, player_x, game.wager * 2)?,
+        GameState::OWon => pay(game_account, player_o, game.wager * 2)?,
+        GameState::Draw => {
+            pay(game_account, player_x, game.wager)?;
+            pay(game_account, player_o, game.wager)?;
+        }
+        _ => return Ok(()),
+    }
+
+    msg!("Game {} over: {:?}", game.game_id, state);
+
+    Ok(())
+}
+
+fn close(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let player_x = next_account_info(accounts_iter)?;
+    let game_account = next_account_info(accounts_iter)?;
+
+    if !player_x.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let game = load_game(program_id, game_account)?;
+
+    if game.player_x != *player_x.key {
+        msg!("Only the creator can close the game");
+        return Err(TicTacToeError::Unauthorized.into());
+    }
+    if game.state == GameState::Active {
+        return Err(TicTacToeError::GameInProgress.into());
+    }
+
+    let lamports = game_account.lamports();
+    pay(game_account, player_x, lamports)?;
+    game_account.data.borrow_mut().fill(0);
+
+    msg!("Game {} closed", game.game_id);
+
+    Ok(())
+}
+
+/// Move `amount` lamports out of the program-owned game account
+fn pay(game_account: &AccountInfo, recipient: &AccountInfo, amount: u64) -> ProgramResult {
+    **game_account.try_borrow_mut_lamports()? = game_account
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(ProgramError::InsufficientFunds)?;
+    **recipient.try_borrow_mut_lamports()? = recipient
+        .lamports()
+        .checked_add(amount)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    Ok(())
+}
+
+fn load_game(program_id: &Pubkey, game_account: &AccountInfo) -> Result<GameAccount, ProgramError> {
+    if game_account.owner != program_id {
+        msg!("Game account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let game = GameAccount::try_from_slice(&game_account.data.borrow())?;
+
+    if !game.is_initialized {
+        return Err(TicTacToeError::NotInitialized.into());
+    }
+
+    Ok(game)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_program::clock::Epoch;
+
+    fn new_game(player_x: Pubkey, player_o: Pubkey, wager: u64) -> GameAccount {
+        GameAccount {
+            is_initialized: true,
+            game_id: 0,
+            player_x,
+            player_o,
+            wager,
+            board: [Mark::Empty; 9],
+            turn: Mark::X,
+            state: GameState::Active,
+            bump: 255,
+        }
+    }
+
+    #[test]
+    fn test_turns_and_draw() {
+        let x = Pubkey::new_unique();
+        let o = Pubkey::new_unique();
+        let mut game = new_game(x, o, 0);
+
+        assert_eq!(game.play(&o, 0), Err(TicTacToeError::NotYourTurn));
+        assert_eq!(game.play(&Pubkey::new_unique(), 0), Err(TicTacToeError::NotAPlayer));
+        assert_eq!(game.play(&x, 9), Err(TicTacToeError::InvalidCell));
+
+        // X O X
+        // X O O
+        // O X X
+        let moves = [(x, 0), (o, 1), (x, 2), (o, 4), (x, 3), (o, 5), (x, 7), (o, 6)];
+        for (player, cell) in moves {
+            assert_eq!(game.play(&player, cell), Ok(GameState::Active));
+        }
+        assert_eq!(game.play(&o, 8), Err(TicTacToeError::NotYourTurn));
+        assert_eq!(game.play(&x, 0), Err(TicTacToeError::CellOccupied));
+        assert_eq!(game.play(&x, 8), Ok(GameState::Draw));
+        assert_eq!(winner(&game.board), None);
+        assert_eq!(game.play(&o, 8), Err(TicTacToeError::NotActive));
+
+        assert_eq!(game.try_to_vec().unwrap().len(), GAME_ACCOUNT_SIZE);
+    }
+
+    #[test]
+    fn test_winning_move_pays_pot() {
+        let program_id = Pubkey::new_unique();
+        let x = Pubkey::new_unique();
+        let o = Pubkey::new_unique();
+        let game_key = Pubkey::new_unique();
+        let wager = 1_000;
+
+        let mut game = new_game(x, o, wager);
+        // X has the top row but for cell 2
+        game.board[0] = Mark::X;
+        game.board[1] = Mark::X;
+        game.board[3] = Mark::O;
+        game.board[4] = Mark::O;
+
+        // The signer is passed first and again as player X
+        let keys = [x, game_key, x, o];
+        let mut lamports: Vec<u64> = vec![0, 2 * wager + 500, 0, 0];
+        let mut data: Vec<Vec<u8>> = vec![vec![], game.try_to_vec().unwrap(), vec![], vec![]];
+
+        let accounts: Vec<AccountInfo> = keys
+            .iter()
+            .zip(lamports.iter_mut())
+            .zip(data.iter_mut())
+            .enumerate()
+            .map(|(i, ((key, lamports), data))| {
+                AccountInfo::new(key, i == 0, true, lamports, data, &program_id, false, Epoch::default())
+            })
+            .collect();
+
+        let instruction_data = TicTacToeInstruction::Play { cell: 2 }.try_to_vec().unwrap();
+        process_instruction(&program_id, &accounts, &instruction_data).unwrap();
+
+        let game = GameAccount::try_from_slice(&accounts[1].data.borrow()).unwrap();
+        assert_eq!(game.state, GameState::XWon);
+        assert_eq!(accounts[1].lamports(), 500);
+        assert_eq!(accounts[2].lamports(), 2 * wager);
+        assert_eq!(accounts[3].lamports(), 0);
+    }
+}
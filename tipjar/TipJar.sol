@@ -0,0 +1,95 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+interface IERC20 {
+    function transfer(address to, uint256 amount) external returns (bool);
+    function transferFrom(address from, address to, uint256 amount) external returns (bool);
+}
+
+/**
+ * @title TipJar
+ * @dev Per-creator tip jars accepting ETH or any ERC20, with tip count and top-tipper tracking
+ */
+contract TipJar {
+    struct Jar {
+        bool exists;
+        uint256 tipCount;
+        uint256 totalEth;
+        address topTipper;
+        uint256 topTip;
+        uint256 ethBalance;
+    }
+
+    mapping(address => Jar) public jars;
+    // creator => token => withdrawable balance
+    mapping(address => mapping(IERC20 => uint256)) public tokenBalances;
+
+    event JarCreated(address indexed creator);
+    event Tipped(address indexed creator, address indexed tipper, address token, uint256 amount);
+    event Withdrawn(address indexed creator, address token, uint256 amount);
+
+    function createJar() external {
+        require(!jars[msg.sender].exists, "Jar exists");
+        jars[msg.sender].exists = true;
+        emit JarCreated(msg.sender);
+    }
+
+    /**
+     * @dev Tip ETH to a creator
+     */
+    function tip(address creator) external payable {
+        require(msg.value > 0, "Amount must be greater than zero");
+        Jar storage jar = jars[creator];
+        require(jar.exists, "No jar");
+
+        jar.tipCount += 1;
+        jar.totalEth += msg.value;
+        jar.ethBalance += msg.value;
+        if (msg.value > jar.topTip) {
+            jar.topTip = msg.value;
+            jar.topTipper = msg.sender;
+        }
+
+        emit Tipped(creator, msg.sender, address(0), msg.value);
+    }
+
+    /**
+     * @dev Tip an ERC20 token to a creator (requires prior `approve`)
+     */
+    function tipToken(address creator, IERC20 token, uint256 amount) external {
+        require(amount > 0, "Amount must be greater than zero");
+        Jar storage jar = jars[creator];
+        require(jar.exists, "No jar");
+
+        jar.tipCount += 1;
+        tokenBalances[creator][token] += amount;
+
+        require(token.transferFrom(msg.sender, address(this), amount), "Transfer failed");
+
+        emit Tipped(creator, msg.sender, address(token), amount);
+    }
+
+    function withdraw(uint256 amount) external {
+        Jar storage jar = jars[msg.sender];
+        require(amount > 0, "Amount must be greater than zero");
+        require(jar.ethBalance >= amount, "Insufficient funds");
+
+        jar.ethBalance -= amount;
+
+        (bool success, ) = msg.sender.call{value: amount}("");
+        require(success, "Transfer failed");
+
+        emit Withdrawn(msg.sender, address(0), amount);
+    }
+
+    function withdrawToken(IERC20 token, uint256 amount) external {
+        require(amount > 0, "Amount must be greater than zero");
+        require(tokenBalances[msg.sender][token] >= amount, "Insufficient funds");
+
+        tokenBalances[msg.sender][token] -= amount;
+
+        require(token.transfer(msg.sender, amount), "Transfer failed");
+
+        emit Withdrawn(msg.sender, address(token), amount);
+    }
+}
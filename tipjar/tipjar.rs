@@ -0,0 +1,450 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint,
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use spl_token::state::Account as TokenAccount;
+
+/// Seed prefix for jar PDAs: ["jar", creator]
+pub const JAR_SEED: &[u8] = b"jar";
+
+/// Seed prefix for per-mint token vault PDAs: ["vault", jar, mint]
+pub const VAULT_SEED: &[u8] = b"vault";
+
+/// Size of a `JarAccount`
+pub const JAR_ACCOUNT_SIZE: usize = 1 // is_initialized
+    + 32 // creator
+    + 8 // tip_count
+    + 8 // total_lamports
+    + 1 + 32 // top_tipper
+    + 8 // top_tip
+    + 1; // bump
+
+/// Errors returned by the program as `ProgramError::Custom` codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TipJarError {
+    /// Tip or withdrawal amount must be greater than zero
+    ZeroAmount = 0,
+    /// Only the jar's creator may withdraw
+    NotCreator = 1,
+    /// The jar does not hold enough to cover the withdrawal
+    InsufficientFunds = 2,
+    /// The jar has not been initialized
+    NotInitialized = 3,
+}
+
+impl From<TipJarError> for ProgramError {
+    fn from(e: TipJarError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+/// A creator's jar. Lamport tips are held in this account; token tips go to
+/// one vault per mint.
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct JarAccount {
+    pub is_initialized: bool,
+    pub creator: Pubkey,
+    /// Tips of any kind received
+    pub tip_count: u64,
+    /// Lamports received in tips over the jar's lifetime
+    pub total_lamports: u64,
+    /// Sender of the largest single lamport tip
+    pub top_tipper: Option<Pubkey>,
+    pub top_tip: u64,
+    pub bump: u8,
+}
+
+impl JarAccount {
+    /// Count a tip and, for lamport tips, update the lifetime total and top tipper
+    pub fn record_tip(&mut self, tipper: &Pubkey, lamports: Option<u64>) -> Result<(), ProgramError> {
+        self.tip_count = self.tip_count.checked_add(1).ok_or(ProgramError::ArithmeticOverflow)?;
+        if let Some(amount) = lamports {
+            self.total_lamports = self
+                .total_lamports
+                .checked_add(amount)
+                .ok_or(ProgramError::ArithmeticOverflow)?;
+            if amount > self.top_tip {
+                self.top_tip = amount;
+                self.top_tipper = Some(*tipper);
+            }
+        }
+        Ok(())
+    }
+}
+
+entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = TipJarInstruction::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        TipJarInstruction::CreateJar => create_jar(program_id, accounts),
+        TipJarInstruction::Tip { amount } => tip(program_id, accounts, amount),
+        TipJarInstruction::TipToken { amount } => tip_token(program_id, accounts, amount),
+        TipJarInstruction::Withdraw { amount } => withdraw(program_id, accounts, amount),
+        TipJarInstruction::WithdrawToken { amount } => withdraw_token(program_id, accounts, amount),
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub enum TipJarInstruction {
+    /// Create the creator's jar PDA
+    /// Accounts: [writable, signer] creator, [writable] jar PDA, [] system program
+    CreateJar,
+    /// Tip `amount` lamports
+    /// Accounts: [writable, signer] tipper, [writable] jar PDA, [] system program
+    Tip { amount: u64 },
+    /// Tip `amount` of an SPL token, creating the jar's vault for that mint on first use
+    /// Accounts: [writable, signer] tipper, [writable] jar PDA, [writable] vault PDA, [] mint,
+    /// [writable] tipper token account, [] token program, [] system program
+    TipToken { amount: u64 },
+    /// Withdraw lamports above the jar's rent-exempt minimum
+    /// Accounts: [writable, signer] creator, [writable] jar PDA
+    Withdraw { amount: u64 },
+    /// Withdraw tokens from one of the jar's vaults
+    /// Accounts: [signer] creator, [] jar PDA, [writable] vault PDA,
+    /// [writable] creator token account, [] token program
+    WithdrawToken { amount: u64 },
+}
+
+fn create_jar(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let creator = next_account_info(accounts_iter)?;
+    let jar_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !creator.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let (expected_jar, bump) = Pubkey::find_program_address(&[JAR_SEED, creator.key.as_ref()], program_id);
+    if expected_jar != *jar_account.key {
+        msg!("Jar account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    invoke_signed(
+        &system_instruction::create_account(
+            creator.key,
+            jar_account.key,
+            Rent::get()?.minimum_balance(JAR_ACCOUNT_SIZE),
+            JAR_ACCOUNT_SIZE as u64,
+            program_id,
+        ),
+        &[creator.clone(), jar_account.clone(), system_program.clone()],
+        &[&[JAR_SEED, creator.key.as_ref(), &[bump]]],
+    )?;
+
+    let jar = JarAccount {
+        is_initialized: true,
+        creator: *creator.key,
+        tip_count: 0,
+        total_lamports: 0,
+        top_tipper: None,
+        top_tip: 0,
+        bump,
+    };
+    jar.serialize(&mut &mut jar_account.data.borrow_mut()[..])?;
+
+    msg!("Tip jar created for {}", creator.key);
+
+    Ok(())
+}
+
+fn tip(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let tipper = next_account_info(accounts_iter)?;
+    let jar_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !tipper.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if amount == 0 {
+        return Err(TipJarError::ZeroAmount.into());
+    }
+
+    let mut jar = load_jar(program_id, jar_account)?;
+
+    invoke(
+        &system_instruction::transfer(tipper.key, jar_account.key, amount),
+        &[tipper.clone(), jar_account.clone(), system_program.clone()],
+    )?;
+
+    jar.record_tip(tipper.key, Some(amount))?;
+    jar.serialize(&mut &mut jar_account.data.borrow_mut()[..])?;
+
+    msg!("{} tipped {} lamports (tip #{})", tipper.key, amount, jar.tip_count);
+
+    Ok(())
+}
+
+fn tip_token(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let tipper = next_account_info(accounts_iter)?;
+    let jar_account = next_account_info(accounts_iter)?;
+    let vault = next_account_info(accounts_iter)?;
+    let mint = next_account_info(accounts_iter)?;
+    let tipper_token = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !tipper.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if amount == 0 {
+        return Err(TipJarError::ZeroAmount.into());
+    }
+
+    if *token_program.key != spl_token::id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let mut jar = load_jar(program_id, jar_account)?;
+
+    let (expected_vault, vault_bump) = Pubkey::find_program_address(
+        &[VAULT_SEED, jar_account.key.as_ref(), mint.key.as_ref()],
+        program_id,
+    );
+    if expected_vault != *vault.key {
+        msg!("Vault account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    // First tip in this mint: create the jar's vault for it
+    if vault.lamports() == 0 {
+        invoke_signed(
+            &system_instruction::create_account(
+                tipper.key,
+                vault.key,
+                Rent::get()?.minimum_balance(TokenAccount::LEN),
+                TokenAccount::LEN as u64,
+                &spl_token::id(),
+            ),
+            &[tipper.clone(), vault.clone(), system_program.clone()],
+            &[&[VAULT_SEED, jar_account.key.as_ref(), mint.key.as_ref(), &[vault_bump]]],
+        )?;
+        invoke(
+            &spl_token::instruction::initialize_account3(
+                &spl_token::id(),
+                vault.key,
+                mint.key,
+                jar_account.key,
+            )?,
+            &[vault.clone(), mint.clone()],
+        )?;
+    }
+
+    invoke(
+        &spl_token::instruction::transfer(
+            &spl_token::id(),
+            tipper_token.key,
+            vault.key,
+            tipper.key,
+            &[],
+            amount,
+        )?,
+        &[tipper_token.clone(), vault.clone(), tipper.clone()],
+    )?;
+
+    jar.record_tip(tipper.key, None)?;
+    jar.serialize(&mut &mut jar_account.data.borrow_mut()[..])?;
+
+    msg!("{} tipped {} of {} (tip #{})", tipper.key, amount, mint.key, jar.tip_count);
+
+    Ok(())
+}
+
+fn withdraw(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let creator = next_account_info(accounts_iter)?;
+    let jar_account = next_account_info(accounts_iter)?;
+
+    if !creator.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if amount == 0 {
+        return Err(TipJarError::ZeroAmount.into());
+    }
+
+    let jar = load_jar(program_id, jar_account)?;
+
+    if jar.creator != *creator.key {
+        return Err(TipJarError::NotCreator.into());
+    }
+
+    let available = jar_account
+        .lamports()
+        .saturating_sub(Rent::get()?.minimum_balance(jar_account.data_len()));
+    if amount > available {
+        msg!("Only {} lamports available", available);
+        return Err(TipJarError::InsufficientFunds.into());
+    }
+
+    **jar_account.try_borrow_mut_lamports()? -= amount;
+    **creator.try_borrow_mut_lamports()? += amount;
+
+    msg!("Withdrew {} lamports", amount);
+
+    Ok(())
+}
+
+fn withdraw_token(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let creator = next_account_info(accounts_iter)?;
+    let jar_account = next_account_info(accounts_iter)?;
+    let vault = next_account_info(accounts_iter)?;
+    let creator_token = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+
+    if !creator.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if amount == 0 {
+        return Err(TipJarError::ZeroAmount.into());
+    }
+
+    if *token_program.key != spl_token::id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let jar = load_jar(program_id, jar_account)?;
+
+    if jar.creator != *creator.key {
+        return Err(TipJarError::NotCreator.into());
+    }
+
+    // The token program rejects the transfer unless the jar PDA owns the vault
+    let vault_state = TokenAccount::unpack(&vault.data.borrow())?;
+    if vault_state.amount < amount {
+        msg!("Only {} tokens available", vault_state.amount);
+        return Err(TipJarError::InsufficientFunds.into());
+    }
+
+    invoke_signed(
+        &spl_token::instruction::transfer(
+            &spl_token::id(),
+            vault.key,
+            creator_token.key,
+            jar_account.key,
+            &[],
+            amount,
+        )?,
+        &[vault.clone(), creator_token.clone(), jar_account.clone()],
+        &[&[JAR_SEED, jar.creator.as_ref(), &[jar.bump]]],
+    )?;
+
+    msg!("Withdrew {} of {}", amount, vault_state.mint);
+
+    Ok(())
+}
+
+/// Validate the jar account, returning its state
+fn load_jar(program_id: &Pubkey, jar_account: &AccountInfo) -> Result<JarAccount, ProgramError> {
+    if jar_account.owner != program_id {
+        msg!("Jar account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let jar = JarAccount::try_from_slice(&jar_account.data.borrow())?;
+    if !jar.is_initialized {
+        return Err(TipJarError::NotInitialized.into());
+    }
+
+    Ok(jar)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_program::clock::Epoch;
+
+    fn jar(creator: Pubkey) -> JarAccount {
+        JarAccount {
+            is_initialized: true,
+            creator,
+            tip_count: 0,
+            total_lamports: 0,
+            top_tipper: None,
+            top_tip: 0,
+            bump: 255,
+        }
+    }
+
+    #[test]
+    fn test_record_tip_tracks_count_total_and_top_tipper() {
+        let mut jar = jar(Pubkey::new_unique());
+        let alice = Pubkey::new_unique();
+        let bob = Pubkey::new_unique();
+
+        jar.record_tip(&alice, Some(500)).unwrap();
+        jar.record_tip(&bob, Some(900)).unwrap();
+        jar.record_tip(&alice, Some(900)).unwrap();
+        jar.record_tip(&alice, None).unwrap();
+
+        assert_eq!(jar.tip_count, 4);
+        assert_eq!(jar.total_lamports, 2_300);
+        // Ties keep the earlier tipper
+        assert_eq!(jar.top_tipper, Some(bob));
+        assert_eq!(jar.top_tip, 900);
+    }
+
+    #[test]
+    fn test_withdraw_by_non_creator_fails() {
+        let program_id = Pubkey::new_unique();
+        let creator = Pubkey::new_unique();
+        let intruder = Pubkey::new_unique();
+        let jar_key = Pubkey::new_unique();
+
+        let mut intruder_lamports = 0;
+        let mut intruder_data = vec![];
+        let mut jar_lamports = 1_000_000;
+        let mut jar_data = jar(creator).try_to_vec().unwrap();
+
+        let accounts = vec![
+            AccountInfo::new(
+                &intruder,
+                true,
+                true,
+                &mut intruder_lamports,
+                &mut intruder_data,
+                &program_id,
+                false,
+                Epoch::default(),
+            ),
+            AccountInfo::new(
+                &jar_key,
+                false,
+                true,
+                &mut jar_lamports,
+                &mut jar_data,
+                &program_id,
+                false,
+                Epoch::default(),
+            ),
+        ];
+
+        let instruction_data = TipJarInstruction::Withdraw { amount: 1 }.try_to_vec().unwrap();
+        let result = process_instruction(&program_id, &accounts, &instruction_data);
+        assert_eq!(result, Err(TipJarError::NotCreator.into()));
+    }
+}
@@ -0,0 +1,65 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+/**
+ * @title TodoList
+ * @dev One todo list per user. Item IDs come from a per-user counter, so a
+ * deleted item's ID is never reused.
+ */
+contract TodoList {
+    uint256 public constant MAX_ITEMS = 32;
+    uint256 public constant MAX_TEXT_LENGTH = 100;
+
+    struct Item {
+        uint64 id;
+        bool completed;
+        string text;
+    }
+
+    mapping(address => Item[]) private lists;
+    mapping(address => uint64) public nextId;
+
+    event ItemAdded(address indexed owner, uint64 indexed id, string text);
+    event ItemCompleted(address indexed owner, uint64 indexed id);
+    event ItemDeleted(address indexed owner, uint64 indexed id);
+
+    function addItem(string calldata text) external returns (uint64 id) {
+        require(bytes(text).length > 0 && bytes(text).length <= MAX_TEXT_LENGTH, "Invalid text");
+        require(lists[msg.sender].length < MAX_ITEMS, "List full");
+
+        id = nextId[msg.sender]++;
+        lists[msg.sender].push(Item(id, false, text));
+        emit ItemAdded(msg.sender, id, text);
+    }
+
+    function completeItem(uint64 id) external {
+        Item storage item = lists[msg.sender][_indexOf(msg.sender, id)];
+        require(!item.completed, "Already completed");
+        item.completed = true;
+        emit ItemCompleted(msg.sender, id);
+    }
+
+    /// Removes an item, keeping the others in order
+    function deleteItem(uint64 id) external {
+        Item[] storage items = lists[msg.sender];
+        for (uint256 i = _indexOf(msg.sender, id); i + 1 < items.length; i++) {
+            items[i] = items[i + 1];
+        }
+        items.pop();
+        emit ItemDeleted(msg.sender, id);
+    }
+
+    function getItems(address owner) external view returns (Item[] memory) {
+        return lists[owner];
+    }
+
+    function _indexOf(address owner, uint64 id) private view returns (uint256) {
+        Item[] storage items = lists[owner];
+        for (uint256 i = 0; i < items.length; i++) {
+            if (items[i].id == id) {
+                return i;
+            }
+        }
+        revert("Item not found");
+    }
+}
@@ -0,0 +1,383 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint,
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// Seed prefix for todo list PDAs: ["todo", owner]
+pub const TODO_SEED: &[u8] = b"todo";
+
+/// Most items a list can hold at once
+pub const MAX_ITEMS: usize = 32;
+
+/// Maximum item text length in bytes
+pub const MAX_TEXT_LENGTH: usize = 100;
+
+/// Size of an empty `TodoList`
+pub const LIST_HEADER_SIZE: usize = 1 // is_initialized
+    + 32 // owner
+    + 8 // next_id
+    + 1 // bump
+    + 4; // items length prefix
+
+/// Size of a `TodoItem` without its text
+pub const ITEM_HEADER_SIZE: usize = 8 // id
+    + 1 // completed
+    + 4; // text length prefix
+
+/// Errors returned by the program as `ProgramError::Custom` codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TodoError {
+    /// Only the list owner can change it
+    Unauthorized = 0,
+    /// The list has not been initialized
+    NotInitialized = 1,
+    /// The list already holds `MAX_ITEMS` items
+    ListFull = 2,
+    /// Text is empty or longer than `MAX_TEXT_LENGTH` bytes
+    InvalidText = 3,
+    /// No item with this ID
+    ItemNotFound = 4,
+    /// The item is already completed
+    AlreadyCompleted = 5,
+}
+
+impl From<TodoError> for ProgramError {
+    fn from(e: TodoError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TodoItem {
+    pub id: u64,
+    pub completed: bool,
+    pub text: String,
+}
+
+/// A user's todo list, sized exactly to its items
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct TodoList {
+    pub is_initialized: bool,
+    pub owner: Pubkey,
+    /// ID for the next item. Only ever increases, so a deleted item's ID is never reused
+    pub next_id: u64,
+    pub bump: u8,
+    pub items: Vec<TodoItem>,
+}
+
+impl TodoList {
+    /// Serialized size, and so the account size
+    pub fn size(&self) -> usize {
+        LIST_HEADER_SIZE
+            + self
+                .items
+                .iter()
+                .map(|item| ITEM_HEADER_SIZE + item.text.len())
+                .sum::<usize>()
+    }
+
+    /// Append an item and return its ID
+    pub fn add(&mut self, text: String) -> Result<u64, TodoError> {
+        if text.is_empty() || text.len() > MAX_TEXT_LENGTH {
+            return Err(TodoError::InvalidText);
+        }
+        if self.items.len() >= MAX_ITEMS {
+            return Err(TodoError::ListFull);
+        }
+
+        let id = self.next_id;
+        self.next_id += 1;
+        self.items.push(TodoItem {
+            id,
+            completed: false,
+            text,
+        });
+
+        Ok(id)
+    }
+
+    pub fn complete(&mut self, id: u64) -> Result<(), TodoError> {
+        let item = self
+            .items
+            .iter_mut()
+            .find(|item| item.id == id)
+            .ok_or(TodoError::ItemNotFound)?;
+        if item.completed {
+            return Err(TodoError::AlreadyCompleted);
+        }
+        item.completed = true;
+        Ok(())
+    }
+
+    /// Remove an item, keeping the others in order
+    pub fn remove(&mut self, id: u64) -> Result<TodoItem, TodoError> {
+        let index = self
+            .items
+            .iter()
+            .position(|item| item.id == id)
+            .ok_or(TodoError::ItemNotFound)?;
+        Ok(self.items.remove(index))
+    }
+}
+
+entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = TodoInstruction::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        TodoInstruction::CreateList => create_list(program_id, accounts),
+        TodoInstruction::AddItem { text } => add_item(program_id, accounts, text),
+        TodoInstruction::CompleteItem { id } => complete_item(program_id, accounts, id),
+        TodoInstruction::DeleteItem { id } => delete_item(program_id, accounts, id),
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub enum TodoInstruction {
+    /// Create an empty list for the signer
+    /// Accounts: [writable, signer] owner, [writable] list PDA, [] system program
+    CreateList,
+    /// Append an item, growing the account; the owner pays the extra rent
+    /// Accounts: [writable, signer] owner, [writable] list PDA, [] system program
+    AddItem { text: String },
+    /// Mark an item completed
+    /// Accounts: [signer] owner, [writable] list PDA
+    CompleteItem { id: u64 },
+    /// Remove an item, shrinking the account and refunding rent to the owner
+    /// Accounts: [writable, signer] owner, [writable] list PDA
+    DeleteItem { id: u64 },
+}
+
+fn create_list(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let owner = next_account_info(accounts_iter)?;
+    let list_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let (expected_list, bump) = Pubkey::find_program_address(&[TODO_SEED, owner.key.as_ref()], program_id);
+    if expected_list != *list_account.key {
+        msg!("List account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    invoke_signed(
+        &system_instruction::create_account(
+            owner.key,
+            list_account.key,
+            Rent::get()?.minimum_balance(LIST_HEADER_SIZE),
+            LIST_HEADER_SIZE as u64,
+            program_id,
+        ),
+        &[owner.clone(), list_account.clone(), system_program.clone()],
+        &[&[TODO_SEED, owner.key.as_ref(), &[bump]]],
+    )?;
+
+    let list = TodoList {
+        is_initialized: true,
+        owner: *owner.key,
+        next_id: 0,
+        bump,
+        items: Vec::new(),
+    };
+    list.serialize(&mut &mut list_account.data.borrow_mut()[..])?;
+
+    msg!("Todo list created for {}", owner.key);
+
+    Ok(())
+}
+
+fn add_item(program_id: &Pubkey, accounts: &[AccountInfo], text: String) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let owner = next_account_info(accounts_iter)?;
+    let list_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    let mut list = load_list(program_id, list_account, owner)?;
+
+    let id = list.add(text)?;
+    let new_len = list.size();
+
+    // Top up rent before growing, so the account is never below the minimum for its size
+    let required = Rent::get()?.minimum_balance(new_len);
+    let shortfall = required.saturating_sub(list_account.lamports());
+    if shortfall > 0 {
+        invoke(
+            &system_instruction::transfer(owner.key, list_account.key, shortfall),
+            &[owner.clone(), list_account.clone(), system_program.clone()],
+        )?;
+    }
+    list_account.realloc(new_len, false)?;
+
+    list.serialize(&mut &mut list_account.data.borrow_mut()[..])?;
+
+    msg!("Added item {} ({} of {})", id, list.items.len(), MAX_ITEMS);
+
+    Ok(())
+}
+
+fn complete_item(program_id: &Pubkey, accounts: &[AccountInfo], id: u64) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let owner = next_account_info(accounts_iter)?;
+    let list_account = next_account_info(accounts_iter)?;
+
+    let mut list = load_list(program_id, list_account, owner)?;
+
+    // Same size before and after, so no realloc
+    list.complete(id)?;
+    list.serialize(&mut &mut list_account.data.borrow_mut()[..])?;
+
+    msg!("Completed item {}", id);
+
+    Ok(())
+}
+
+fn delete_item(program_id: &Pubkey, accounts: &[AccountInfo], id: u64) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let owner = next_account_info(accounts_iter)?;
+    let list_account = next_account_info(accounts_iter)?;
+
+    let mut list = load_list(program_id, list_account, owner)?;
+
+    list.remove(id)?;
+    let new_len = list.size();
+
+    // Write first: the serialized list fits the old size, and realloc then
+    // cuts off the stale tail
+    list.serialize(&mut &mut list_account.data.borrow_mut()[..])?;
+    list_account.realloc(new_len, false)?;
+
+    // This program owns the list, so it can debit the excess rent directly
+    let excess = list_account
+        .lamports()
+        .saturating_sub(Rent::get()?.minimum_balance(new_len));
+    **list_account.try_borrow_mut_lamports()? -= excess;
+    **owner.try_borrow_mut_lamports()? += excess;
+
+    msg!("Deleted item {}, refunded {} lamports", id, excess);
+
+    Ok(())
+}
+
+/// Validate the list account and that `owner` signed and owns it
+fn load_list(program_id: &Pubkey, list_account: &AccountInfo, owner: &AccountInfo) -> Result<TodoList, ProgramError> {
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if list_account.owner != program_id {
+        msg!("List account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let list = TodoList::try_from_slice(&list_account.data.borrow())?;
+
+    if !list.is_initialized {
+        return Err(TodoError::NotInitialized.into());
+    }
+
+    if list.owner != *owner.key {
+        msg!("Only the list owner can change it");
+        return Err(TodoError::Unauthorized.into());
+    }
+
+    Ok(list)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_program::clock::Epoch;
+
+    fn new_list(owner: Pubkey) -> TodoList {
+        TodoList {
+            is_initialized: true,
+            owner,
+            next_id: 0,
+            bump: 255,
+            items: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_ids_are_never_reused() {
+        let mut list = new_list(Pubkey::new_unique());
+        assert_eq!(list.size(), list.try_to_vec().unwrap().len());
+
+        assert_eq!(list.add("write tests".to_string()), Ok(0));
+        assert_eq!(list.add("ship".to_string()), Ok(1));
+        assert_eq!(list.size(), list.try_to_vec().unwrap().len());
+
+        list.complete(0).unwrap();
+        assert_eq!(list.complete(0), Err(TodoError::AlreadyCompleted));
+
+        // Deleting the newest item does not free its ID
+        list.remove(1).unwrap();
+        assert_eq!(list.add("ship again".to_string()), Ok(2));
+        assert_eq!(list.remove(1), Err(TodoError::ItemNotFound));
+
+        assert_eq!(list.add(String::new()), Err(TodoError::InvalidText));
+        assert_eq!(list.add("x".repeat(MAX_TEXT_LENGTH + 1)), Err(TodoError::InvalidText));
+
+        while list.items.len() < MAX_ITEMS {
+            list.add("x".repeat(MAX_TEXT_LENGTH)).unwrap();
+        }
+        assert_eq!(list.add("one more".to_string()), Err(TodoError::ListFull));
+        assert_eq!(list.size(), list.try_to_vec().unwrap().len());
+    }
+
+    #[test]
+    fn test_complete_requires_owner() {
+        let program_id = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let list_key = Pubkey::new_unique();
+
+        let mut list = new_list(owner);
+        list.add("water plants".to_string()).unwrap();
+        let list_data = list.try_to_vec().unwrap();
+
+        for (signer, expected) in [
+            (Pubkey::new_unique(), Err(TodoError::Unauthorized.into())),
+            (owner, Ok(())),
+        ] {
+            let keys = [signer, list_key];
+            let mut lamports: Vec<u64> = vec![10_000; keys.len()];
+            let mut data: Vec<Vec<u8>> = vec![vec![], list_data.clone()];
+
+            let accounts: Vec<AccountInfo> = keys
+                .iter()
+                .zip(lamports.iter_mut())
+                .zip(data.iter_mut())
+                .enumerate()
+                .map(|(i, ((key, lamports), data))| {
+                    AccountInfo::new(key, i == 0, true, lamports, data, &program_id, false, Epoch::default())
+                })
+                .collect();
+
+            let instruction_data = TodoInstruction::CompleteItem { id: 0 }.try_to_vec().unwrap();
+            let result = process_instruction(&program_id, &accounts, &instruction_data);
+            assert_eq!(result, expected);
+
+            let stored = TodoList::try_from_slice(&accounts[1].data.borrow()).unwrap();
+            assert_eq!(stored.items[0].completed, result.is_ok());
+        }
+    }
+}
@@ -0,0 +1,143 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+/**
+ * @title FeeToken
+ * @dev ERC20 that withholds a capped percentage of every transfer as a fee and
+ * carries its own metadata URI, mirroring Token-2022's transfer fee and
+ * metadata extensions
+ */
+contract FeeToken {
+    uint16 public constant MAX_FEE_BASIS_POINTS = 10_000;
+    // Fee changes wait this long, like Token-2022's two-epoch delay
+    uint256 public constant FEE_CHANGE_DELAY = 2 days;
+
+    struct TransferFee {
+        uint256 effectiveAt;
+        uint256 maximumFee;
+        uint16 basisPoints;
+    }
+
+    string public name;
+    string public symbol;
+    string public uri;
+    uint8 public immutable decimals;
+    address public immutable owner;
+
+    TransferFee public olderFee;
+    TransferFee public newerFee;
+    uint256 public withheldFees;
+
+    uint256 public totalSupply;
+    mapping(address => uint256) public balanceOf;
+    mapping(address => mapping(address => uint256)) public allowance;
+
+    event Transfer(address indexed from, address indexed to, uint256 value);
+    event Approval(address indexed owner, address indexed spender, uint256 value);
+    event FeeWithheld(address indexed from, address indexed to, uint256 fee);
+    event TransferFeeSet(uint16 basisPoints, uint256 maximumFee, uint256 effectiveAt);
+    event FeesWithdrawn(address indexed to, uint256 amount);
+
+    modifier onlyOwner() {
+        require(msg.sender == owner, "Only owner");
+        _;
+    }
+
+    constructor(
+        string memory _name,
+        string memory _symbol,
+        string memory _uri,
+        uint8 _decimals,
+        uint16 _basisPoints,
+        uint256 _maximumFee
+    ) {
+        require(_basisPoints <= MAX_FEE_BASIS_POINTS, "Fee exceeds 100%");
+        name = _name;
+        symbol = _symbol;
+        uri = _uri;
+        decimals = _decimals;
+        owner = msg.sender;
+
+        olderFee = TransferFee(0, _maximumFee, _basisPoints);
+        newerFee = olderFee;
+    }
+
+    /**
+     * @dev Fee withheld on a transfer of `amount`, rounded up and capped
+     */
+    function transferFee(uint256 amount) public view returns (uint256) {
+        TransferFee memory current = block.timestamp >= newerFee.effectiveAt ? newerFee : olderFee;
+        if (current.basisPoints == 0 || amount == 0) {
+            return 0;
+        }
+        uint256 fee = (amount * current.basisPoints + MAX_FEE_BASIS_POINTS - 1) / MAX_FEE_BASIS_POINTS;
+        return fee < current.maximumFee ? fee : current.maximumFee;
+    }
+
+    function mint(address to, uint256 amount) external onlyOwner {
+        totalSupply += amount;
+        balanceOf[to] += amount;
+        emit Transfer(address(0), to, amount);
+    }
+
+    function transfer(address to, uint256 amount) external returns (bool) {
+        _transfer(msg.sender, to, amount);
+        return true;
+    }
+
+    function approve(address spender, uint256 amount) external returns (bool) {
+        allowance[msg.sender][spender] = amount;
+        emit Approval(msg.sender, spender, amount);
+        return true;
+    }
+
+    function transferFrom(address from, address to, uint256 amount) external returns (bool) {
+        require(allowance[from][msg.sender] >= amount, "Insufficient allowance");
+        allowance[from][msg.sender] -= amount;
+        _transfer(from, to, amount);
+        return true;
+    }
+
+    /**
+     * @dev Schedule a new fee. Holders see it coming before it applies.
+     */
+    function setTransferFee(uint16 basisPoints, uint256 maximumFee) external onlyOwner {
+        require(basisPoints <= MAX_FEE_BASIS_POINTS, "Fee exceeds 100%");
+
+        if (block.timestamp >= newerFee.effectiveAt) {
+            olderFee = newerFee;
+        }
+        newerFee = TransferFee(block.timestamp + FEE_CHANGE_DELAY, maximumFee, basisPoints);
+
+        emit TransferFeeSet(basisPoints, maximumFee, newerFee.effectiveAt);
+    }
+
+    function withdrawFees(address to) external onlyOwner {
+        uint256 amount = withheldFees;
+        require(amount > 0, "No fees to withdraw");
+
+        withheldFees = 0;
+        balanceOf[to] += amount;
+
+        emit Transfer(address(this), to, amount);
+        emit FeesWithdrawn(to, amount);
+    }
+
+    function setUri(string calldata _uri) external onlyOwner {
+        uri = _uri;
+    }
+
+    function _transfer(address from, address to, uint256 amount) internal {
+        require(balanceOf[from] >= amount, "Insufficient balance");
+
+        uint256 fee = transferFee(amount);
+        balanceOf[from] -= amount;
+        balanceOf[to] += amount - fee;
+        withheldFees += fee;
+
+        emit Transfer(from, to, amount - fee);
+        if (fee > 0) {
+            emit FeeWithheld(from, to, fee);
+        }
+    }
+}
@@ -0,0 +1,474 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::{Clock, Epoch},
+    entrypoint,
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use spl_token_2022::{
+    extension::{
+        metadata_pointer,
+        transfer_fee::{instruction as transfer_fee_instruction, TransferFeeConfig, MAX_FEE_BASIS_POINTS},
+        BaseStateWithExtensions, ExtensionType, StateWithExtensions,
+    },
+    state::Mint,
+};
+use spl_token_metadata_interface::state::TokenMetadata;
+
+/// Seed prefix for mint PDAs: ["mint", creator]. The mint is its own mint
+/// authority, so only this program can mint, on the creator's instruction.
+pub const MINT_SEED: &[u8] = b"mint";
+
+/// Metadata field limits
+pub const MAX_NAME_LENGTH: usize = 32;
+pub const MAX_SYMBOL_LENGTH: usize = 10;
+pub const MAX_URI_LENGTH: usize = 200;
+
+/// Errors returned by the program as `ProgramError::Custom` codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Token2022Error {
+    /// Fee is above 100%
+    InvalidFee = 0,
+    /// Name, symbol or URI exceeds its limit
+    MetadataTooLong = 1,
+    /// Amount is zero
+    ZeroAmount = 2,
+}
+
+impl From<Token2022Error> for ProgramError {
+    fn from(e: Token2022Error) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+/// Fee the token program will withhold when `amount` of the mint is
+/// transferred in `epoch`. Mints without the transfer fee extension charge nothing.
+pub fn transfer_fee_for(mint_data: &[u8], epoch: Epoch, amount: u64) -> Result<u64, ProgramError> {
+    let mint = StateWithExtensions::<Mint>::unpack(mint_data)?;
+    match mint.get_extension::<TransferFeeConfig>() {
+        Ok(config) => config
+            .calculate_epoch_fee(epoch, amount)
+            .ok_or(ProgramError::ArithmeticOverflow),
+        Err(_) => Ok(0),
+    }
+}
+
+entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = Token2022Instruction::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        Token2022Instruction::CreateMint {
+            decimals,
+            transfer_fee_basis_points,
+            maximum_fee,
+            name,
+            symbol,
+            uri,
+        } => create_mint(
+            program_id,
+            accounts,
+            decimals,
+            transfer_fee_basis_points,
+            maximum_fee,
+            name,
+            symbol,
+            uri,
+        ),
+        Token2022Instruction::MintTo { amount } => mint_to(program_id, accounts, amount),
+        Token2022Instruction::TransferWithFee { amount } => transfer_with_fee(accounts, amount),
+        Token2022Instruction::WithdrawFees => withdraw_fees(accounts),
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub enum Token2022Instruction {
+    /// Create a Token-2022 mint with the transfer fee and metadata pointer
+    /// extensions, storing the metadata in the mint itself
+    /// Accounts: [writable, signer] creator, [writable] mint PDA, [] token-2022 program,
+    /// [] system program
+    CreateMint {
+        decimals: u8,
+        transfer_fee_basis_points: u16,
+        maximum_fee: u64,
+        name: String,
+        symbol: String,
+        uri: String,
+    },
+    /// Mint tokens, signed by the mint PDA
+    /// Accounts: [signer] creator, [writable] mint PDA, [writable] destination token account,
+    /// [] token-2022 program
+    MintTo { amount: u64 },
+    /// Transfer `amount` with the fee the token program will withhold stated up front
+    /// Accounts: [signer] owner, [writable] source token account, [] mint,
+    /// [writable] destination token account, [] token-2022 program
+    TransferWithFee { amount: u64 },
+    /// Harvest withheld fees from token accounts into the mint, then withdraw them
+    /// Accounts: [signer] creator (withdraw authority), [writable] mint,
+    /// [writable] creator token account, [] token-2022 program, then [writable] token
+    /// accounts holding withheld fees
+    WithdrawFees,
+}
+
+#[allow(clippy::too_many_arguments)]
+fn create_mint(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    decimals: u8,
+    transfer_fee_basis_points: u16,
+    maximum_fee: u64,
+    name: String,
+    symbol: String,
+    uri: String,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let creator = next_account_info(accounts_iter)?;
+    let mint_account = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !creator.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    check_token_program(token_program)?;
+
+    if transfer_fee_basis_points > MAX_FEE_BASIS_POINTS {
+        msg!("Transfer fee of {} bps exceeds 100%", transfer_fee_basis_points);
+        return Err(Token2022Error::InvalidFee.into());
+    }
+
+    if name.len() > MAX_NAME_LENGTH || symbol.len() > MAX_SYMBOL_LENGTH || uri.len() > MAX_URI_LENGTH {
+        return Err(Token2022Error::MetadataTooLong.into());
+    }
+
+    let (expected_mint, bump) = Pubkey::find_program_address(&[MINT_SEED, creator.key.as_ref()], program_id);
+    if expected_mint != *mint_account.key {
+        msg!("Mint account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+    let mint_seeds: &[&[u8]] = &[MINT_SEED, creator.key.as_ref(), &[bump]];
+
+    // Extensions must be initialized before the mint itself, so the account is
+    // allocated for them up front. The metadata is variable length: the token
+    // program grows the account when it is written, so only its rent is paid now.
+    let space = ExtensionType::try_calculate_account_len::<Mint>(&[
+        ExtensionType::TransferFeeConfig,
+        ExtensionType::MetadataPointer,
+    ])?;
+    let metadata_space = TokenMetadata {
+        name: name.clone(),
+        symbol: symbol.clone(),
+        uri: uri.clone(),
+        ..Default::default()
+    }
+    .tlv_size_of()?;
+
+    invoke_signed(
+        &system_instruction::create_account(
+            creator.key,
+            mint_account.key,
+            Rent::get()?.minimum_balance(space + metadata_space),
+            space as u64,
+            &spl_token_2022::id(),
+        ),
+        &[creator.clone(), mint_account.clone(), system_program.clone()],
+        &[mint_seeds],
+    )?;
+
+    // The creator can change the fee later and withdraws the withheld fees
+    invoke(
+        &transfer_fee_instruction::initialize_transfer_fee_config(
+            &spl_token_2022::id(),
+            mint_account.key,
+            Some(creator.key),
+            Some(creator.key),
+            transfer_fee_basis_points,
+            maximum_fee,
+        )?,
+        std::slice::from_ref(mint_account),
+    )?;
+
+    // Point the metadata at the mint itself instead of a separate account
+    invoke(
+        &metadata_pointer::instruction::initialize(
+            &spl_token_2022::id(),
+            mint_account.key,
+            Some(*creator.key),
+            Some(*mint_account.key),
+        )?,
+        std::slice::from_ref(mint_account),
+    )?;
+
+    invoke(
+        &spl_token_2022::instruction::initialize_mint2(
+            &spl_token_2022::id(),
+            mint_account.key,
+            mint_account.key,
+            None,
+            decimals,
+        )?,
+        std::slice::from_ref(mint_account),
+    )?;
+
+    invoke_signed(
+        &spl_token_metadata_interface::instruction::initialize(
+            &spl_token_2022::id(),
+            mint_account.key,
+            creator.key,
+            mint_account.key,
+            mint_account.key,
+            name,
+            symbol,
+            uri,
+        ),
+        &[mint_account.clone(), creator.clone(), mint_account.clone()],
+        &[mint_seeds],
+    )?;
+
+    msg!("Mint {} created with a {} bps transfer fee", mint_account.key, transfer_fee_basis_points);
+
+    Ok(())
+}
+
+fn mint_to(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let creator = next_account_info(accounts_iter)?;
+    let mint_account = next_account_info(accounts_iter)?;
+    let destination = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+
+    if !creator.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    check_token_program(token_program)?;
+
+    if amount == 0 {
+        return Err(Token2022Error::ZeroAmount.into());
+    }
+
+    // Deriving the mint from the signer is the authorization check: another
+    // wallet's seeds lead to another mint
+    let (expected_mint, bump) = Pubkey::find_program_address(&[MINT_SEED, creator.key.as_ref()], program_id);
+    if expected_mint != *mint_account.key {
+        msg!("Mint was not created by this signer");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    invoke_signed(
+        &spl_token_2022::instruction::mint_to(
+            &spl_token_2022::id(),
+            mint_account.key,
+            destination.key,
+            mint_account.key,
+            &[],
+            amount,
+        )?,
+        &[mint_account.clone(), destination.clone(), mint_account.clone()],
+        &[&[MINT_SEED, creator.key.as_ref(), &[bump]]],
+    )?;
+
+    msg!("Minted {} to {}", amount, destination.key);
+
+    Ok(())
+}
+
+fn transfer_with_fee(accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let owner = next_account_info(accounts_iter)?;
+    let source = next_account_info(accounts_iter)?;
+    let mint_account = next_account_info(accounts_iter)?;
+    let destination = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    check_token_program(token_program)?;
+
+    if amount == 0 {
+        return Err(Token2022Error::ZeroAmount.into());
+    }
+
+    let mint_data = mint_account.data.borrow();
+    let decimals = StateWithExtensions::<Mint>::unpack(&mint_data)?.base.decimals;
+    let fee = transfer_fee_for(&mint_data, Clock::get()?.epoch, amount)?;
+    drop(mint_data);
+
+    // `transfer_checked_with_fee` fails if `fee` differs from what the token
+    // program computes, so a fee change cannot silently reduce what arrives.
+    // Plain `transfer` is rejected for fee-bearing mints.
+    invoke(
+        &transfer_fee_instruction::transfer_checked_with_fee(
+            &spl_token_2022::id(),
+            source.key,
+            mint_account.key,
+            destination.key,
+            owner.key,
+            &[],
+            amount,
+            decimals,
+            fee,
+        )?,
+        &[source.clone(), mint_account.clone(), destination.clone(), owner.clone()],
+    )?;
+
+    msg!("Transferred {}; {} withheld as fee, {} received", amount, fee, amount - fee);
+
+    Ok(())
+}
+
+fn withdraw_fees(accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let creator = next_account_info(accounts_iter)?;
+    let mint_account = next_account_info(accounts_iter)?;
+    let destination = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+    let sources: Vec<AccountInfo> = accounts_iter.cloned().collect();
+
+    if !creator.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    check_token_program(token_program)?;
+
+    // Fees are withheld in each recipient's token account. Harvesting moves
+    // them to the mint and needs no signature, so anyone may do it.
+    if !sources.is_empty() {
+        let source_keys: Vec<&Pubkey> = sources.iter().map(|source| source.key).collect();
+        let mut harvest_accounts = vec![mint_account.clone()];
+        harvest_accounts.extend(sources.iter().cloned());
+        invoke(
+            &transfer_fee_instruction::harvest_withheld_tokens_to_mint(
+                &spl_token_2022::id(),
+                mint_account.key,
+                &source_keys,
+            )?,
+            &harvest_accounts,
+        )?;
+    }
+
+    // The token program checks the creator is the mint's withdraw authority
+    invoke(
+        &transfer_fee_instruction::withdraw_withheld_tokens_from_mint(
+            &spl_token_2022::id(),
+            mint_account.key,
+            destination.key,
+            creator.key,
+            &[],
+        )?,
+        &[mint_account.clone(), destination.clone(), creator.clone()],
+    )?;
+
+    msg!("Withheld fees withdrawn to {}", destination.key);
+
+    Ok(())
+}
+
+/// Token-2022 is a separate program from SPL Token; accounts of one cannot be used with the other
+fn check_token_program(token_program: &AccountInfo) -> ProgramResult {
+    if *token_program.key != spl_token_2022::id() {
+        msg!("Expected the Token-2022 program");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_program::program_option::COption;
+    use spl_token_2022::extension::{transfer_fee::TransferFee, StateWithExtensionsMut};
+
+    fn fee(epoch: u64, basis_points: u16, maximum_fee: u64) -> TransferFee {
+        TransferFee {
+            epoch: epoch.into(),
+            maximum_fee: maximum_fee.into(),
+            transfer_fee_basis_points: basis_points.into(),
+        }
+    }
+
+    #[test]
+    fn test_transfer_fee_rounds_up_caps_and_follows_epoch() {
+        let space = ExtensionType::try_calculate_account_len::<Mint>(&[ExtensionType::TransferFeeConfig]).unwrap();
+        let mut data = vec![0u8; space];
+        let mut mint = StateWithExtensionsMut::<Mint>::unpack_uninitialized(&mut data).unwrap();
+        let config = mint.init_extension::<TransferFeeConfig>(true).unwrap();
+        // 1% until epoch 10, then 0.5% capped at 20
+        config.older_transfer_fee = fee(0, 100, u64::MAX);
+        config.newer_transfer_fee = fee(10, 50, 20);
+        mint.base = Mint {
+            mint_authority: COption::None,
+            supply: 0,
+            decimals: 6,
+            is_initialized: true,
+            freeze_authority: COption::None,
+        };
+        mint.pack_base();
+        mint.init_account_type().unwrap();
+
+        assert_eq!(transfer_fee_for(&data, 9, 1_000).unwrap(), 10);
+        // Fees round up: 0.5% of 101 is 0.505
+        assert_eq!(transfer_fee_for(&data, 10, 101).unwrap(), 1);
+        assert_eq!(transfer_fee_for(&data, 10, 1_000_000).unwrap(), 20);
+
+        // A mint without the extension charges nothing
+        let plain_space = ExtensionType::try_calculate_account_len::<Mint>(&[]).unwrap();
+        let mut plain = vec![0u8; plain_space];
+        let mut mint = StateWithExtensionsMut::<Mint>::unpack_uninitialized(&mut plain).unwrap();
+        mint.base.is_initialized = true;
+        mint.pack_base();
+        assert_eq!(transfer_fee_for(&plain, 10, 1_000).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_create_mint_rejects_fee_above_100_percent() {
+        let program_id = Pubkey::new_unique();
+        let keys = [
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            spl_token_2022::id(),
+            solana_program::system_program::id(),
+        ];
+        let mut lamports: Vec<u64> = vec![10_000; keys.len()];
+        let mut data: Vec<Vec<u8>> = vec![vec![]; keys.len()];
+
+        let accounts: Vec<AccountInfo> = keys
+            .iter()
+            .zip(lamports.iter_mut())
+            .zip(data.iter_mut())
+            .enumerate()
+            .map(|(i, ((key, lamports), data))| {
+                AccountInfo::new(key, i == 0, true, lamports, data, &program_id, false, Epoch::default())
+            })
+            .collect();
+
+        let instruction_data = Token2022Instruction::CreateMint {
+            decimals: 6,
+            transfer_fee_basis_points: MAX_FEE_BASIS_POINTS + 1,
+            maximum_fee: 1_000,
+            name: "Fee Token".to_string(),
+            symbol: "FEE".to_string(),
+            uri: "https://example.com/fee.json".to_string(),
+        }
+        .try_to_vec()
+        .unwrap();
+        let result = process_instruction(&program_id, &accounts, &instruction_data);
+        assert_eq!(result, Err(Token2022Error::InvalidFee.into()));
+    }
+}
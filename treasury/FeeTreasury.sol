@@ -0,0 +1,108 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+/**
+ * @title FeeTreasury
+ * @dev Collects fees from other contracts, tracks totals per source, and pays
+ * withdrawals out across a configurable split
+ */
+contract FeeTreasury {
+    uint256 public constant MAX_PAYEES = 5;
+    uint16 public constant TOTAL_BPS = 10_000;
+
+    struct Payee {
+        address account;
+        uint16 shareBps;
+    }
+
+    address public immutable authority;
+    uint256 public totalCollected;
+    uint256 public totalWithdrawn;
+    Payee[] private payees;
+
+    // source contract => lifetime fees paid
+    mapping(address => uint256) public paidBySource;
+
+    event FeeCollected(address indexed source, address indexed payer, uint256 amount);
+    event SplitUpdated(uint256 payeeCount);
+    event Withdrawn(uint256 amount);
+
+    modifier onlyAuthority() {
+        require(msg.sender == authority, "Only authority");
+        _;
+    }
+
+    constructor(Payee[] memory _payees) {
+        authority = msg.sender;
+        _setSplit(_payees);
+    }
+
+    /**
+     * @dev Pay a fee on behalf of the calling contract; `payer` is informational
+     */
+    function collectFee(address payer) external payable {
+        require(msg.value > 0, "Zero amount");
+
+        paidBySource[msg.sender] += msg.value;
+        totalCollected += msg.value;
+
+        emit FeeCollected(msg.sender, payer, msg.value);
+    }
+
+    /**
+     * @dev Replace the fee split (authority only)
+     */
+    function setSplit(Payee[] calldata _payees) external onlyAuthority {
+        _setSplit(_payees);
+    }
+
+    /**
+     * @dev Pay `amount` out across the split; rounding dust goes to the first payee
+     */
+    function withdraw(uint256 amount) external onlyAuthority {
+        require(amount > 0, "Zero amount");
+        require(amount <= address(this).balance, "Insufficient funds");
+
+        totalWithdrawn += amount;
+
+        uint256 paid;
+        uint256[] memory shares = new uint256[](payees.length);
+        for (uint256 i = 0; i < payees.length; i++) {
+            shares[i] = (amount * payees[i].shareBps) / TOTAL_BPS;
+            paid += shares[i];
+        }
+        shares[0] += amount - paid;
+
+        for (uint256 i = 0; i < payees.length; i++) {
+            (bool success, ) = payees[i].account.call{value: shares[i]}("");
+            require(success, "Transfer failed");
+        }
+
+        emit Withdrawn(amount);
+    }
+
+    function getPayees() external view returns (Payee[] memory) {
+        return payees;
+    }
+
+    function _setSplit(Payee[] memory _payees) private {
+        require(_payees.length > 0 && _payees.length <= MAX_PAYEES, "Invalid payee count");
+
+        uint256 total;
+        for (uint256 i = 0; i < _payees.length; i++) {
+            require(_payees[i].shareBps > 0, "Zero share");
+            for (uint256 j = 0; j < i; j++) {
+                require(_payees[j].account != _payees[i].account, "Duplicate payee");
+            }
+            total += _payees[i].shareBps;
+        }
+        require(total == TOTAL_BPS, "Shares must sum to 10000");
+
+        delete payees;
+        for (uint256 i = 0; i < _payees.length; i++) {
+            payees.push(_payees[i]);
+        }
+
+        emit SplitUpdated(_payees.length);
+    }
+}
@@ -0,0 +1,558 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint,
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction, system_program,
+    sysvar::Sysvar,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// Seed prefix for treasury PDAs: ["treasury", authority]
+pub const TREASURY_SEED: &[u8] = b"treasury";
+
+/// Seed prefix for per-source totals: ["source", treasury, source_program]
+pub const SOURCE_SEED: &[u8] = b"source";
+
+/// Seed a source program signs with when paying fees: ["fee-source"],
+/// derived under the *source's* program id
+pub const SOURCE_AUTHORITY_SEED: &[u8] = b"fee-source";
+
+/// Maximum number of payees in a fee split
+pub const MAX_PAYEES: usize = 5;
+
+/// Shares are expressed in basis points and must sum to this
+pub const TOTAL_BPS: u16 = 10_000;
+
+/// Size of a `TreasuryAccount` with `MAX_PAYEES` payees
+pub const TREASURY_ACCOUNT_SIZE: usize = 1 // is_initialized
+    + 32 // authority
+    + 8 // total_collected
+    + 8 // total_withdrawn
+    + 4 + MAX_PAYEES * (32 + 2) // payees
+    + 1; // bump
+
+/// Errors returned by the program as `ProgramError::Custom` codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TreasuryError {
+    /// Shares must be non-zero, unique per payee and sum to exactly 10,000 bps
+    InvalidSplit = 0,
+    /// Between 1 and `MAX_PAYEES` payees are allowed
+    TooManyPayees = 1,
+    /// Only the treasury authority can do this
+    Unauthorized = 2,
+    /// The signer is not the source program's fee authority
+    UnauthorizedSource = 3,
+    /// Amount is zero
+    ZeroAmount = 4,
+    /// Withdrawal exceeds the balance above rent
+    InsufficientFunds = 5,
+    /// A payout account does not match the payee at that position
+    PayeeMismatch = 6,
+    /// Account does not match the expected PDA
+    WrongAccount = 7,
+    /// The treasury has not been initialized
+    NotInitialized = 8,
+}
+
+impl From<TreasuryError> for ProgramError {
+    fn from(e: TreasuryError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Payee {
+    pub address: Pubkey,
+    /// Share of every withdrawal in basis points
+    pub share_bps: u16,
+}
+
+/// Revenue sink for one authority; fees are held as lamports on this PDA
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct TreasuryAccount {
+    pub is_initialized: bool,
+    pub authority: Pubkey,
+    pub total_collected: u64,
+    pub total_withdrawn: u64,
+    /// How withdrawals are split; changeable by the authority
+    pub payees: Vec<Payee>,
+    pub bump: u8,
+}
+
+/// Lifetime fees paid in by one source program
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct SourceAccount {
+    pub treasury: Pubkey,
+    pub source_program: Pubkey,
+    pub total_paid: u64,
+    pub payments: u64,
+    pub bump: u8,
+}
+
+impl SourceAccount {
+    pub const SIZE: usize = 32 // treasury
+        + 32 // source_program
+        + 8 // total_paid
+        + 8 // payments
+        + 1; // bump
+}
+
+/// Each payee's share of `amount`, rounded down, with the rounding dust added
+/// to the first payee so the whole amount leaves the treasury
+pub fn split_amounts(amount: u64, payees: &[Payee]) -> Vec<u64> {
+    let mut shares: Vec<u64> = payees
+        .iter()
+        .map(|p| (amount as u128 * p.share_bps as u128 / TOTAL_BPS as u128) as u64)
+        .collect();
+    let dust = amount - shares.iter().sum::<u64>();
+    if let Some(first) = shares.first_mut() {
+        *first += dust;
+    }
+    shares
+}
+
+/// Check payee count, share sum, and uniqueness
+pub fn validate_payees(payees: &[Payee]) -> Result<(), TreasuryError> {
+    if payees.is_empty() || payees.len() > MAX_PAYEES {
+        return Err(TreasuryError::TooManyPayees);
+    }
+
+    let mut total: u32 = 0;
+    for (i, payee) in payees.iter().enumerate() {
+        if payee.share_bps == 0 || payees[..i].iter().any(|p| p.address == payee.address) {
+            return Err(TreasuryError::InvalidSplit);
+        }
+        total += payee.share_bps as u32;
+    }
+
+    if total != TOTAL_BPS as u32 {
+        return Err(TreasuryError::InvalidSplit);
+    }
+
+    Ok(())
+}
+
+/// The PDA a source program signs with to have its fees attributed to it
+pub fn source_authority(source_program: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[SOURCE_AUTHORITY_SEED], source_program)
+}
+
+/// Address of the treasury controlled by `authority`
+pub fn treasury_address(program_id: &Pubkey, authority: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[TREASURY_SEED, authority.as_ref()], program_id)
+}
+
+/// Address of `source_program`'s totals in `treasury`
+pub fn source_address(program_id: &Pubkey, treasury: &Pubkey, source_program: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[SOURCE_SEED, treasury.as_ref(), source_program.as_ref()], program_id)
+}
+
+/// Build a `CollectFee` instruction; the source program invokes it signed
+/// with `[SOURCE_AUTHORITY_SEED, &[bump]]`
+pub fn collect_fee_instruction(
+    program_id: &Pubkey,
+    treasury: &Pubkey,
+    source_program: &Pubkey,
+    payer: &Pubkey,
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    let (authority, _) = source_authority(source_program);
+    let (source, _) = source_address(program_id, treasury, source_program);
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(authority, true),
+            AccountMeta::new_readonly(*source_program, false),
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(*treasury, false),
+            AccountMeta::new(source, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: TreasuryInstruction::CollectFee { amount }.try_to_vec()?,
+    })
+}
+
+entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = TreasuryInstruction::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        TreasuryInstruction::Initialize { payees } => initialize(program_id, accounts, payees),
+        TreasuryInstruction::CollectFee { amount } => collect_fee(program_id, accounts, amount),
+        TreasuryInstruction::SetSplit { payees } => set_split(program_id, accounts, payees),
+        TreasuryInstruction::Withdraw { amount } => withdraw(program_id, accounts, amount),
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub enum TreasuryInstruction {
+    /// Create the signer's treasury with an initial fee split
+    /// Accounts: [writable, signer] authority, [writable] treasury PDA, [] system program
+    Initialize { payees: Vec<Payee> },
+    /// Pay `amount` lamports of fees into the treasury on behalf of a source
+    /// program (called via CPI), creating the source's totals on first use
+    /// Accounts: [signer] source authority PDA, [] source program, [writable, signer] payer,
+    ///           [writable] treasury PDA, [writable] source PDA, [] system program
+    CollectFee { amount: u64 },
+    /// Replace the fee split (authority only)
+    /// Accounts: [signer] authority, [writable] treasury PDA
+    SetSplit { payees: Vec<Payee> },
+    /// Pay `amount` out across the fee split (authority only)
+    /// Accounts: [signer] authority, [writable] treasury PDA, then [writable] each payee in order
+    Withdraw { amount: u64 },
+}
+
+fn initialize(program_id: &Pubkey, accounts: &[AccountInfo], payees: Vec<Payee>) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let authority = next_account_info(accounts_iter)?;
+    let treasury_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    validate_payees(&payees)?;
+
+    let (expected_treasury, bump) = treasury_address(program_id, authority.key);
+    if expected_treasury != *treasury_account.key {
+        msg!("Treasury account does not match the expected PDA");
+        return Err(TreasuryError::WrongAccount.into());
+    }
+
+    invoke_signed(
+        &system_instruction::create_account(
+            authority.key,
+            treasury_account.key,
+            Rent::get()?.minimum_balance(TREASURY_ACCOUNT_SIZE),
+            TREASURY_ACCOUNT_SIZE as u64,
+            program_id,
+        ),
+        &[authority.clone(), treasury_account.clone(), system_program.clone()],
+        &[&[TREASURY_SEED, authority.key.as_ref(), &[bump]]],
+    )?;
+
+    let treasury = TreasuryAccount {
+        is_initialized: true,
+        authority: *authority.key,
+        total_collected: 0,
+        total_withdrawn: 0,
+        payees,
+        bump,
+    };
+    treasury.serialize(&mut &mut treasury_account.data.borrow_mut()[..])?;
+
+    msg!("Treasury created for {} with {} payees", authority.key, treasury.payees.len());
+
+    Ok(())
+}
+
+fn collect_fee(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let authority = next_account_info(accounts_iter)?;
+    let source_program = next_account_info(accounts_iter)?;
+    let payer = next_account_info(accounts_iter)?;
+    let treasury_account = next_account_info(accounts_iter)?;
+    let source_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if amount == 0 {
+        return Err(TreasuryError::ZeroAmount.into());
+    }
+
+    // Only the source program can produce this signature, so per-source
+    // totals cannot be padded by someone else
+    let (expected_authority, _) = source_authority(source_program.key);
+    if !authority.is_signer || expected_authority != *authority.key {
+        msg!("Signer is not the fee authority of {}", source_program.key);
+        return Err(TreasuryError::UnauthorizedSource.into());
+    }
+
+    let mut treasury = load_treasury(program_id, treasury_account)?;
+
+    let (expected_source, bump) = source_address(program_id, treasury_account.key, source_program.key);
+    if expected_source != *source_account.key {
+        msg!("Source account does not match the expected PDA");
+        return Err(TreasuryError::WrongAccount.into());
+    }
+
+    let mut source = if source_account.lamports() == 0 {
+        invoke_signed(
+            &system_instruction::create_account(
+                payer.key,
+                source_account.key,
+                Rent::get()?.minimum_balance(SourceAccount::SIZE),
+                SourceAccount::SIZE as u64,
+                program_id,
+            ),
+            &[payer.clone(), source_account.clone(), system_program.clone()],
+            &[&[SOURCE_SEED, treasury_account.key.as_ref(), source_program.key.as_ref(), &[bump]]],
+        )?;
+
+        SourceAccount {
+            treasury: *treasury_account.key,
+            source_program: *source_program.key,
+            total_paid: 0,
+            payments: 0,
+            bump,
+        }
+    } else {
+        load_source(program_id, source_account)?
+    };
+
+    invoke(
+        &system_instruction::transfer(payer.key, treasury_account.key, amount),
+        &[payer.clone(), treasury_account.clone(), system_program.clone()],
+    )?;
+
+    source.total_paid = source.total_paid.checked_add(amount).ok_or(ProgramError::ArithmeticOverflow)?;
+    source.payments += 1;
+    source.serialize(&mut &mut source_account.data.borrow_mut()[..])?;
+
+    treasury.total_collected = treasury
+        .total_collected
+        .checked_add(amount)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    treasury.serialize(&mut &mut treasury_account.data.borrow_mut()[..])?;
+
+    msg!("Collected {} lamports from {} ({} total)", amount, source_program.key, source.total_paid);
+
+    Ok(())
+}
+
+fn set_split(program_id: &Pubkey, accounts: &[AccountInfo], payees: Vec<Payee>) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let authority = next_account_info(accounts_iter)?;
+    let treasury_account = next_account_info(accounts_iter)?;
+
+    let mut treasury = load_treasury_as_authority(program_id, treasury_account, authority)?;
+
+    validate_payees(&payees)?;
+
+    treasury.payees = payees;
+    treasury.serialize(&mut &mut treasury_account.data.borrow_mut()[..])?;
+
+    msg!("Fee split updated to {} payees", treasury.payees.len());
+
+    Ok(())
+}
+
+fn withdraw(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let authority = next_account_info(accounts_iter)?;
+    let treasury_account = next_account_info(accounts_iter)?;
+
+    let mut treasury = load_treasury_as_authority(program_id, treasury_account, authority)?;
+
+    if amount == 0 {
+        return Err(TreasuryError::ZeroAmount.into());
+    }
+
+    let available = treasury_account
+        .lamports()
+        .saturating_sub(Rent::get()?.minimum_balance(treasury_account.data_len()));
+    if amount > available {
+        msg!("Requested {} but only {} is available", amount, available);
+        return Err(TreasuryError::InsufficientFunds.into());
+    }
+
+    let shares = split_amounts(amount, &treasury.payees);
+    for (payee, share) in treasury.payees.iter().zip(shares) {
+        let payee_account = next_account_info(accounts_iter)?;
+        if *payee_account.key != payee.address {
+            msg!("Expected payee {}, got {}", payee.address, payee_account.key);
+            return Err(TreasuryError::PayeeMismatch.into());
+        }
+
+        **treasury_account.try_borrow_mut_lamports()? -= share;
+        **payee_account.try_borrow_mut_lamports()? = payee_account
+            .lamports()
+            .checked_add(share)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+    }
+
+    treasury.total_withdrawn = treasury
+        .total_withdrawn
+        .checked_add(amount)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    treasury.serialize(&mut &mut treasury_account.data.borrow_mut()[..])?;
+
+    msg!("Withdrew {} lamports to {} payees", amount, treasury.payees.len());
+
+    Ok(())
+}
+
+fn load_treasury(program_id: &Pubkey, treasury_account: &AccountInfo) -> Result<TreasuryAccount, ProgramError> {
+    if treasury_account.owner != program_id {
+        msg!("Treasury account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    // Allocated for MAX_PAYEES, so the payee list may not fill the account
+    let treasury = TreasuryAccount::deserialize(&mut &treasury_account.data.borrow()[..])?;
+
+    if !treasury.is_initialized {
+        return Err(TreasuryError::NotInitialized.into());
+    }
+
+    Ok(treasury)
+}
+
+fn load_treasury_as_authority(
+    program_id: &Pubkey,
+    treasury_account: &AccountInfo,
+    authority: &AccountInfo,
+) -> Result<TreasuryAccount, ProgramError> {
+    if !authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let treasury = load_treasury(program_id, treasury_account)?;
+
+    if treasury.authority != *authority.key {
+        msg!("Only the treasury authority can do this");
+        return Err(TreasuryError::Unauthorized.into());
+    }
+
+    Ok(treasury)
+}
+
+fn load_source(program_id: &Pubkey, source_account: &AccountInfo) -> Result<SourceAccount, ProgramError> {
+    if source_account.owner != program_id {
+        msg!("Source account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    Ok(SourceAccount::try_from_slice(&source_account.data.borrow())?)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_program::{clock::Epoch, entrypoint::SUCCESS, program_stubs};
+
+    /// Serves the Rent sysvar so handlers can run off-chain
+    struct TestSyscallStubs;
+
+    impl program_stubs::SyscallStubs for TestSyscallStubs {
+        fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+            unsafe {
+                *(var_addr as *mut Rent) = Rent::default();
+            }
+            SUCCESS
+        }
+    }
+
+    fn payee(share_bps: u16) -> Payee {
+        Payee {
+            address: Pubkey::new_unique(),
+            share_bps,
+        }
+    }
+
+    #[test]
+    fn test_split_validation_and_rounding() {
+        let payees = vec![payee(3_333), payee(3_333), payee(3_334)];
+        assert_eq!(validate_payees(&payees), Ok(()));
+
+        // Dust goes to the first payee so nothing is left behind
+        let shares = split_amounts(100, &payees);
+        assert_eq!(shares, vec![34, 33, 33]);
+        assert_eq!(shares.iter().sum::<u64>(), 100);
+
+        assert_eq!(validate_payees(&[payee(5_000)]), Err(TreasuryError::InvalidSplit));
+        assert_eq!(validate_payees(&[]), Err(TreasuryError::TooManyPayees));
+        let duplicate = payee(5_000);
+        assert_eq!(
+            validate_payees(&[duplicate.clone(), duplicate]),
+            Err(TreasuryError::InvalidSplit)
+        );
+
+        let source = SourceAccount {
+            treasury: Pubkey::new_unique(),
+            source_program: Pubkey::new_unique(),
+            total_paid: 0,
+            payments: 0,
+            bump: 255,
+        };
+        assert_eq!(source.try_to_vec().unwrap().len(), SourceAccount::SIZE);
+
+        let full = TreasuryAccount {
+            is_initialized: true,
+            authority: Pubkey::new_unique(),
+            total_collected: 0,
+            total_withdrawn: 0,
+            payees: (0..MAX_PAYEES).map(|_| payee(2_000)).collect(),
+            bump: 255,
+        };
+        assert_eq!(full.try_to_vec().unwrap().len(), TREASURY_ACCOUNT_SIZE);
+    }
+
+    #[test]
+    fn test_withdraw_follows_split() {
+        program_stubs::set_syscall_stubs(Box::new(TestSyscallStubs));
+
+        let program_id = Pubkey::new_unique();
+        let authority = Pubkey::new_unique();
+        let (treasury_key, bump) = treasury_address(&program_id, &authority);
+        let payees = vec![payee(7_500), payee(2_500)];
+
+        let treasury = TreasuryAccount {
+            is_initialized: true,
+            authority,
+            total_collected: 1_000,
+            total_withdrawn: 0,
+            payees: payees.clone(),
+            bump,
+        };
+        let mut treasury_data = treasury.try_to_vec().unwrap();
+        treasury_data.resize(TREASURY_ACCOUNT_SIZE, 0);
+        let rent = Rent::default().minimum_balance(TREASURY_ACCOUNT_SIZE);
+
+        let keys = [authority, treasury_key, payees[0].address, payees[1].address];
+        let mut lamports: Vec<u64> = vec![0, rent + 1_000, 0, 0];
+        let mut data: Vec<Vec<u8>> = vec![vec![], treasury_data, vec![], vec![]];
+
+        let accounts: Vec<AccountInfo> = keys
+            .iter()
+            .zip(lamports.iter_mut())
+            .zip(data.iter_mut())
+            .enumerate()
+            .map(|(i, ((key, lamports), data))| {
+                AccountInfo::new(key, i == 0, true, lamports, data, &program_id, false, Epoch::default())
+            })
+            .collect();
+
+        // Rent stays behind
+        let too_much = TreasuryInstruction::Withdraw { amount: 1_001 }.try_to_vec().unwrap();
+        let result = process_instruction(&program_id, &accounts, &too_much);
+        assert_eq!(result, Err(TreasuryError::InsufficientFunds.into()));
+
+        // Payees must come in split order
+        let withdraw = TreasuryInstruction::Withdraw { amount: 800 }.try_to_vec().unwrap();
+        let swapped = vec![accounts[0].clone(), accounts[1].clone(), accounts[3].clone(), accounts[2].clone()];
+        let result = process_instruction(&program_id, &swapped, &withdraw);
+        assert_eq!(result, Err(TreasuryError::PayeeMismatch.into()));
+
+        process_instruction(&program_id, &accounts, &withdraw).unwrap();
+        assert_eq!(accounts[1].lamports(), rent + 200);
+        assert_eq!(accounts[2].lamports(), 600);
+        assert_eq!(accounts[3].lamports(), 200);
+
+        let stored = TreasuryAccount::deserialize(&mut &accounts[1].data.borrow()[..]).unwrap();
+        assert_eq!(stored.total_withdrawn, 800);
+    }
+}
@@ -0,0 +1,73 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+interface IERC20 {
+    function transfer(address to, uint256 amount) external returns (bool);
+    function transferFrom(address from, address to, uint256 amount) external returns (bool);
+}
+
+/**
+ * @title Vault
+ * @dev Holds ETH and ERC20 tokens on behalf of each depositor. Funds only leave
+ * through withdraw functions, which check the caller's recorded balance.
+ */
+contract Vault {
+    mapping(address => uint256) public ethBalances;
+    mapping(address => mapping(address => uint256)) public tokenBalances;
+
+    event Deposited(address indexed owner, uint256 amount);
+    event Withdrawn(address indexed owner, uint256 amount);
+    event TokenDeposited(address indexed owner, address indexed token, uint256 amount);
+    event TokenWithdrawn(address indexed owner, address indexed token, uint256 amount);
+
+    /**
+     * @dev Deposit the ETH sent with the call
+     */
+    function deposit() external payable {
+        require(msg.value > 0, "Amount must be greater than zero");
+
+        ethBalances[msg.sender] += msg.value;
+
+        emit Deposited(msg.sender, msg.value);
+    }
+
+    /**
+     * @dev Withdraw `amount` of the caller's ETH
+     */
+    function withdraw(uint256 amount) external {
+        require(amount > 0, "Amount must be greater than zero");
+        require(ethBalances[msg.sender] >= amount, "Insufficient balance");
+
+        // Update state before the external call
+        ethBalances[msg.sender] -= amount;
+        (bool success, ) = msg.sender.call{value: amount}("");
+        require(success, "ETH transfer failed");
+
+        emit Withdrawn(msg.sender, amount);
+    }
+
+    /**
+     * @dev Pull `amount` of `token` from the caller (requires approval)
+     */
+    function depositToken(IERC20 token, uint256 amount) external {
+        require(amount > 0, "Amount must be greater than zero");
+
+        require(token.transferFrom(msg.sender, address(this), amount), "Transfer failed");
+        tokenBalances[msg.sender][address(token)] += amount;
+
+        emit TokenDeposited(msg.sender, address(token), amount);
+    }
+
+    /**
+     * @dev Withdraw `amount` of the caller's `token`
+     */
+    function withdrawToken(IERC20 token, uint256 amount) external {
+        require(amount > 0, "Amount must be greater than zero");
+        require(tokenBalances[msg.sender][address(token)] >= amount, "Insufficient balance");
+
+        tokenBalances[msg.sender][address(token)] -= amount;
+        require(token.transfer(msg.sender, amount), "Transfer failed");
+
+        emit TokenWithdrawn(msg.sender, address(token), amount);
+    }
+}
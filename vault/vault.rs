@@ -0,0 +1,534 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint,
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use spl_token::state::Account as TokenAccount;
+
+/// Seed prefix for vault state PDAs: ["vault_state", owner]
+pub const STATE_SEED: &[u8] = b"vault_state";
+
+/// Seed prefix for the lamport vault PDA: ["sol_vault", state]
+pub const SOL_VAULT_SEED: &[u8] = b"sol_vault";
+
+/// Seed prefix for token vault PDAs: ["token_vault", state, mint]
+pub const TOKEN_VAULT_SEED: &[u8] = b"token_vault";
+
+/// Size of a `VaultState`
+pub const VAULT_STATE_SIZE: usize = 1 // is_initialized
+    + 32 // owner
+    + 8 // lamports
+    + 1 // open_token_vaults
+    + 1 // bump
+    + 1; // sol_vault_bump
+
+/// Errors returned by the program as `ProgramError::Custom` codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VaultError {
+    /// Amount is zero
+    InvalidParameters = 0,
+    /// The vault holds less than the requested amount
+    InsufficientFunds = 1,
+    /// Only the vault owner can withdraw or close
+    Unauthorized = 2,
+    /// The vault has not been initialized
+    NotInitialized = 3,
+    /// Token vaults must be emptied before the vault can be closed
+    TokenVaultsOpen = 4,
+    /// A vault account does not match the expected PDA
+    InvalidVaultAccount = 5,
+}
+
+impl From<VaultError> for ProgramError {
+    fn from(e: VaultError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+/// Bookkeeping for one owner's vault. Lamports sit in the system-owned SOL
+/// vault PDA, and tokens in token accounts whose authority is this PDA.
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct VaultState {
+    pub is_initialized: bool,
+    pub owner: Pubkey,
+    /// Deposited lamports, excluding the SOL vault's rent-exempt reserve
+    pub lamports: u64,
+    /// Token vaults created and not yet closed
+    pub open_token_vaults: u8,
+    pub bump: u8,
+    pub sol_vault_bump: u8,
+}
+
+impl VaultState {
+    pub fn deposit(&mut self, amount: u64) -> Result<(), ProgramError> {
+        self.lamports = self.lamports.checked_add(amount).ok_or(ProgramError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    pub fn withdraw(&mut self, amount: u64) -> Result<(), VaultError> {
+        self.lamports = self.lamports.checked_sub(amount).ok_or(VaultError::InsufficientFunds)?;
+        Ok(())
+    }
+}
+
+entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = VaultInstruction::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        VaultInstruction::Initialize => initialize(program_id, accounts),
+        VaultInstruction::Deposit { amount } => deposit(program_id, accounts, amount),
+        VaultInstruction::Withdraw { amount } => withdraw(program_id, accounts, amount),
+        VaultInstruction::DepositToken { amount } => deposit_token(program_id, accounts, amount),
+        VaultInstruction::WithdrawToken { amount } => withdraw_token(program_id, accounts, amount),
+        VaultInstruction::Close => close(program_id, accounts),
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub enum VaultInstruction {
+    /// Create the vault state and fund the SOL vault with its rent-exempt reserve
+    /// Accounts: [writable, signer] owner, [writable] state PDA, [writable] SOL vault PDA,
+    /// [] system program
+    Initialize,
+    /// Move lamports from the owner into the SOL vault
+    /// Accounts: [writable, signer] owner, [writable] state PDA, [writable] SOL vault PDA,
+    /// [] system program
+    Deposit { amount: u64 },
+    /// Release lamports from the SOL vault, signed by the program with the vault's seeds
+    /// Accounts: same as `Deposit`
+    Withdraw { amount: u64 },
+    /// Move tokens into the vault's token account for the mint, creating it if needed
+    /// Accounts: [writable, signer] owner, [writable] state PDA, [] mint, [writable] token vault PDA,
+    /// [writable] owner token account, [] token program, [] system program
+    DepositToken { amount: u64 },
+    /// Release tokens, closing the token vault once it is empty
+    /// Accounts: [writable, signer] owner, [writable] state PDA, [writable] token vault PDA,
+    /// [writable] owner token account, [] token program
+    WithdrawToken { amount: u64 },
+    /// Return every lamport, including rent, to the owner and close the vault
+    /// Accounts: same as `Deposit`
+    Close,
+}
+
+fn initialize(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let owner = next_account_info(accounts_iter)?;
+    let state_account = next_account_info(accounts_iter)?;
+    let sol_vault = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let (expected_state, bump) = Pubkey::find_program_address(&[STATE_SEED, owner.key.as_ref()], program_id);
+    let (expected_sol_vault, sol_vault_bump) =
+        Pubkey::find_program_address(&[SOL_VAULT_SEED, state_account.key.as_ref()], program_id);
+    if expected_state != *state_account.key || expected_sol_vault != *sol_vault.key {
+        msg!("State or SOL vault account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let rent = Rent::get()?;
+
+    invoke_signed(
+        &system_instruction::create_account(
+            owner.key,
+            state_account.key,
+            rent.minimum_balance(VAULT_STATE_SIZE),
+            VAULT_STATE_SIZE as u64,
+            program_id,
+        ),
+        &[owner.clone(), state_account.clone(), system_program.clone()],
+        &[&[STATE_SEED, owner.key.as_ref(), &[bump]]],
+    )?;
+
+    // The SOL vault stays a plain system account; it only needs to stay
+    // rent-exempt, so it is funded with the zero-data minimum up front
+    invoke(
+        &system_instruction::transfer(owner.key, sol_vault.key, rent.minimum_balance(0)),
+        &[owner.clone(), sol_vault.clone(), system_program.clone()],
+    )?;
+
+    let state = VaultState {
+        is_initialized: true,
+        owner: *owner.key,
+        lamports: 0,
+        open_token_vaults: 0,
+        bump,
+        sol_vault_bump,
+    };
+    state.serialize(&mut &mut state_account.data.borrow_mut()[..])?;
+
+    msg!("Vault created for {}", owner.key);
+
+    Ok(())
+}
+
+fn deposit(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let owner = next_account_info(accounts_iter)?;
+    let state_account = next_account_info(accounts_iter)?;
+    let sol_vault = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if amount == 0 {
+        return Err(VaultError::InvalidParameters.into());
+    }
+
+    let mut state = load_state(program_id, state_account, owner)?;
+    check_sol_vault(program_id, state_account, sol_vault, &state)?;
+
+    invoke(
+        &system_instruction::transfer(owner.key, sol_vault.key, amount),
+        &[owner.clone(), sol_vault.clone(), system_program.clone()],
+    )?;
+
+    state.deposit(amount)?;
+    state.serialize(&mut &mut state_account.data.borrow_mut()[..])?;
+
+    msg!("Deposited {} lamports; vault holds {}", amount, state.lamports);
+
+    Ok(())
+}
+
+fn withdraw(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let owner = next_account_info(accounts_iter)?;
+    let state_account = next_account_info(accounts_iter)?;
+    let sol_vault = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if amount == 0 {
+        return Err(VaultError::InvalidParameters.into());
+    }
+
+    let mut state = load_state(program_id, state_account, owner)?;
+    check_sol_vault(program_id, state_account, sol_vault, &state)?;
+
+    if let Err(e) = state.withdraw(amount) {
+        msg!("Vault holds {} lamports, cannot withdraw {}", state.lamports, amount);
+        return Err(e.into());
+    }
+
+    // No private key exists for the SOL vault; only these seeds can sign for it
+    invoke_signed(
+        &system_instruction::transfer(sol_vault.key, owner.key, amount),
+        &[sol_vault.clone(), owner.clone(), system_program.clone()],
+        &[&[SOL_VAULT_SEED, state_account.key.as_ref(), &[state.sol_vault_bump]]],
+    )?;
+
+    state.serialize(&mut &mut state_account.data.borrow_mut()[..])?;
+
+    msg!("Withdrew {} lamports; vault holds {}", amount, state.lamports);
+
+    Ok(())
+}
+
+fn deposit_token(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let owner = next_account_info(accounts_iter)?;
+    let state_account = next_account_info(accounts_iter)?;
+    let mint = next_account_info(accounts_iter)?;
+    let token_vault = next_account_info(accounts_iter)?;
+    let owner_token = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if amount == 0 {
+        return Err(VaultError::InvalidParameters.into());
+    }
+
+    if *token_program.key != spl_token::id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let mut state = load_state(program_id, state_account, owner)?;
+
+    let (expected_token_vault, token_vault_bump) = Pubkey::find_program_address(
+        &[TOKEN_VAULT_SEED, state_account.key.as_ref(), mint.key.as_ref()],
+        program_id,
+    );
+    if expected_token_vault != *token_vault.key {
+        msg!("Token vault does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    // First deposit of this mint: create the token vault, with the state PDA as its authority
+    if token_vault.lamports() == 0 {
+        invoke_signed(
+            &system_instruction::create_account(
+                owner.key,
+                token_vault.key,
+                Rent::get()?.minimum_balance(TokenAccount::LEN),
+                TokenAccount::LEN as u64,
+                &spl_token::id(),
+            ),
+            &[owner.clone(), token_vault.clone(), system_program.clone()],
+            &[&[TOKEN_VAULT_SEED, state_account.key.as_ref(), mint.key.as_ref(), &[token_vault_bump]]],
+        )?;
+        invoke(
+            &spl_token::instruction::initialize_account3(
+                &spl_token::id(),
+                token_vault.key,
+                mint.key,
+                state_account.key,
+            )?,
+            &[token_vault.clone(), mint.clone()],
+        )?;
+        state.open_token_vaults = state
+            .open_token_vaults
+            .checked_add(1)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+    }
+
+    invoke(
+        &spl_token::instruction::transfer(&spl_token::id(), owner_token.key, token_vault.key, owner.key, &[], amount)?,
+        &[owner_token.clone(), token_vault.clone(), owner.clone()],
+    )?;
+
+    state.serialize(&mut &mut state_account.data.borrow_mut()[..])?;
+
+    msg!("Deposited {} of {}", amount, mint.key);
+
+    Ok(())
+}
+
+fn withdraw_token(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let owner = next_account_info(accounts_iter)?;
+    let state_account = next_account_info(accounts_iter)?;
+    let token_vault = next_account_info(accounts_iter)?;
+    let owner_token = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if amount == 0 {
+        return Err(VaultError::InvalidParameters.into());
+    }
+
+    if *token_program.key != spl_token::id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let mut state = load_state(program_id, state_account, owner)?;
+
+    let vault_state = TokenAccount::unpack(&token_vault.data.borrow())?;
+    if vault_state.owner != *state_account.key {
+        msg!("Token account is not held by this vault");
+        return Err(VaultError::InvalidVaultAccount.into());
+    }
+
+    if vault_state.amount < amount {
+        msg!("Vault holds {} tokens, cannot withdraw {}", vault_state.amount, amount);
+        return Err(VaultError::InsufficientFunds.into());
+    }
+
+    let state_seeds: &[&[u8]] = &[STATE_SEED, state.owner.as_ref(), &[state.bump]];
+    invoke_signed(
+        &spl_token::instruction::transfer(
+            &spl_token::id(),
+            token_vault.key,
+            owner_token.key,
+            state_account.key,
+            &[],
+            amount,
+        )?,
+        &[token_vault.clone(), owner_token.clone(), state_account.clone()],
+        &[state_seeds],
+    )?;
+
+    // An emptied token vault is closed so its rent goes back to the owner
+    if vault_state.amount == amount {
+        invoke_signed(
+            &spl_token::instruction::close_account(
+                &spl_token::id(),
+                token_vault.key,
+                owner.key,
+                state_account.key,
+                &[],
+            )?,
+            &[token_vault.clone(), owner.clone(), state_account.clone()],
+            &[state_seeds],
+        )?;
+        state.open_token_vaults -= 1;
+        state.serialize(&mut &mut state_account.data.borrow_mut()[..])?;
+    }
+
+    msg!("Withdrew {} of {}", amount, vault_state.mint);
+
+    Ok(())
+}
+
+fn close(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let owner = next_account_info(accounts_iter)?;
+    let state_account = next_account_info(accounts_iter)?;
+    let sol_vault = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let state = load_state(program_id, state_account, owner)?;
+    check_sol_vault(program_id, state_account, sol_vault, &state)?;
+
+    if state.open_token_vaults > 0 {
+        msg!("Withdraw all tokens first; {} token vaults are open", state.open_token_vaults);
+        return Err(VaultError::TokenVaultsOpen.into());
+    }
+
+    // Sweep the SOL vault, deposits and reserve alike
+    let swept = sol_vault.lamports();
+    invoke_signed(
+        &system_instruction::transfer(sol_vault.key, owner.key, swept),
+        &[sol_vault.clone(), owner.clone(), system_program.clone()],
+        &[&[SOL_VAULT_SEED, state_account.key.as_ref(), &[state.sol_vault_bump]]],
+    )?;
+
+    // The state account is owned by this program, so its lamports can be moved directly
+    let lamports = state_account.lamports();
+    **state_account.try_borrow_mut_lamports()? = 0;
+    **owner.try_borrow_mut_lamports()? += lamports;
+    state_account.data.borrow_mut().fill(0);
+
+    msg!("Vault closed; returned {} lamports", swept + lamports);
+
+    Ok(())
+}
+
+/// Validate the state account and that `owner` owns it, returning the state
+fn load_state(program_id: &Pubkey, state_account: &AccountInfo, owner: &AccountInfo) -> Result<VaultState, ProgramError> {
+    if state_account.owner != program_id {
+        msg!("State account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let state = VaultState::try_from_slice(&state_account.data.borrow())?;
+
+    if !state.is_initialized {
+        msg!("Vault has not been initialized");
+        return Err(VaultError::NotInitialized.into());
+    }
+
+    if state.owner != *owner.key {
+        msg!("Only the vault owner can use this vault");
+        return Err(VaultError::Unauthorized.into());
+    }
+
+    Ok(state)
+}
+
+/// Check the SOL vault is this state's PDA
+fn check_sol_vault(
+    program_id: &Pubkey,
+    state_account: &AccountInfo,
+    sol_vault: &AccountInfo,
+    state: &VaultState,
+) -> ProgramResult {
+    let expected = Pubkey::create_program_address(
+        &[SOL_VAULT_SEED, state_account.key.as_ref(), &[state.sol_vault_bump]],
+        program_id,
+    )?;
+    if expected != *sol_vault.key {
+        msg!("SOL vault does not belong to this vault");
+        return Err(VaultError::InvalidVaultAccount.into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_program::clock::Epoch;
+
+    fn new_state(owner: Pubkey) -> VaultState {
+        VaultState {
+            is_initialized: true,
+            owner,
+            lamports: 0,
+            open_token_vaults: 0,
+            bump: 255,
+            sol_vault_bump: 255,
+        }
+    }
+
+    #[test]
+    fn test_deposit_and_withdraw_accounting() {
+        let mut state = new_state(Pubkey::new_unique());
+
+        state.deposit(1_000).unwrap();
+        state.deposit(500).unwrap();
+        state.withdraw(1_200).unwrap();
+        assert_eq!(state.lamports, 300);
+
+        assert_eq!(state.withdraw(301), Err(VaultError::InsufficientFunds));
+        assert_eq!(state.lamports, 300);
+
+        state.lamports = u64::MAX;
+        assert_eq!(state.deposit(1), Err(ProgramError::ArithmeticOverflow));
+    }
+
+    #[test]
+    fn test_withdraw_by_non_owner_fails() {
+        let program_id = Pubkey::new_unique();
+        let intruder_key = Pubkey::new_unique();
+        let state_key = Pubkey::new_unique();
+        let system_program_id = solana_program::system_program::id();
+
+        let mut state = new_state(Pubkey::new_unique());
+        state.lamports = 1_000;
+        let mut state_data = state.try_to_vec().unwrap();
+
+        let keys = [intruder_key, state_key, Pubkey::new_unique(), system_program_id];
+        let mut lamports: Vec<u64> = vec![10_000; keys.len()];
+        let mut data: Vec<Vec<u8>> = vec![vec![]; keys.len()];
+        data[1] = std::mem::take(&mut state_data);
+
+        let accounts: Vec<AccountInfo> = keys
+            .iter()
+            .zip(lamports.iter_mut())
+            .zip(data.iter_mut())
+            .enumerate()
+            .map(|(i, ((key, lamports), data))| {
+                AccountInfo::new(key, i == 0, true, lamports, data, &program_id, false, Epoch::default())
+            })
+            .collect();
+
+        let instruction_data = VaultInstruction::Withdraw { amount: 1_000 }.try_to_vec().unwrap();
+        let result = process_instruction(&program_id, &accounts, &instruction_data);
+        assert_eq!(result, Err(VaultError::Unauthorized.into()));
+    }
+}
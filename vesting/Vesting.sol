@@ -0,0 +1,120 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+interface IERC20 {
+    function transfer(address to, uint256 amount) external returns (bool);
+    function transferFrom(address from, address to, uint256 amount) external returns (bool);
+}
+
+/**
+ * @title Vesting
+ * @dev Cliff + linear token vesting schedules
+ */
+contract Vesting {
+    struct Schedule {
+        address grantor;
+        address beneficiary;
+        IERC20 token;
+        uint256 totalAmount;
+        uint256 claimedAmount;
+        uint256 startBlock;
+        uint256 cliffBlock;
+        uint256 endBlock;
+        bool revocable;
+        bool revoked;
+    }
+
+    uint256 public nextScheduleId;
+    mapping(uint256 => Schedule) public schedules;
+
+    event ScheduleCreated(uint256 indexed scheduleId, address indexed beneficiary, uint256 amount);
+    event Claimed(uint256 indexed scheduleId, uint256 amount);
+    event Revoked(uint256 indexed scheduleId, uint256 vested, uint256 returned);
+
+    /**
+     * @dev Fund a schedule; nothing vests before `startBlock + cliffBlocks`,
+     * and everything has vested at `startBlock + durationBlocks`
+     */
+    function createSchedule(
+        address beneficiary,
+        IERC20 token,
+        uint256 amount,
+        uint256 startBlock,
+        uint256 cliffBlocks,
+        uint256 durationBlocks,
+        bool revocable
+    ) external returns (uint256 scheduleId) {
+        require(amount > 0 && durationBlocks > 0, "Amount and duration must be positive");
+        require(cliffBlocks <= durationBlocks, "Cliff must be within the duration");
+
+        scheduleId = nextScheduleId++;
+        schedules[scheduleId] = Schedule(
+            msg.sender,
+            beneficiary,
+            token,
+            amount,
+            0,
+            startBlock,
+            startBlock + cliffBlocks,
+            startBlock + durationBlocks,
+            revocable,
+            false
+        );
+
+        require(token.transferFrom(msg.sender, address(this), amount), "Funding failed");
+
+        emit ScheduleCreated(scheduleId, beneficiary, amount);
+    }
+
+    /**
+     * @dev Tokens vested by `blockNumber`
+     */
+    function vestedAmount(uint256 scheduleId, uint256 blockNumber) public view returns (uint256) {
+        Schedule storage s = schedules[scheduleId];
+        if (s.revoked || blockNumber >= s.endBlock) {
+            return s.totalAmount;
+        }
+        if (blockNumber < s.cliffBlock) {
+            return 0;
+        }
+        return (s.totalAmount * (blockNumber - s.startBlock)) / (s.endBlock - s.startBlock);
+    }
+
+    /**
+     * @dev Send all vested but unclaimed tokens to the beneficiary
+     */
+    function claim(uint256 scheduleId) external {
+        Schedule storage s = schedules[scheduleId];
+        require(msg.sender == s.beneficiary, "Only the beneficiary can claim");
+
+        uint256 claimable = vestedAmount(scheduleId, block.number) - s.claimedAmount;
+        require(claimable > 0, "Nothing to claim");
+
+        s.claimedAmount += claimable;
+        require(s.token.transfer(s.beneficiary, claimable), "Transfer failed");
+
+        emit Claimed(scheduleId, claimable);
+    }
+
+    /**
+     * @dev Stop vesting and return unvested tokens to the grantor
+     */
+    function revoke(uint256 scheduleId) external {
+        Schedule storage s = schedules[scheduleId];
+        require(msg.sender == s.grantor, "Only the grantor can revoke");
+        require(s.revocable, "Schedule is not revocable");
+        require(!s.revoked, "Schedule already revoked");
+
+        uint256 vested = vestedAmount(scheduleId, block.number);
+        uint256 unvested = s.totalAmount - vested;
+
+        s.totalAmount = vested;
+        s.revoked = true;
+
+        if (unvested > 0) {
+            require(s.token.transfer(s.grantor, unvested), "Transfer failed");
+        }
+
+        emit Revoked(scheduleId, vested, unvested);
+    }
+}
@@ -0,0 +1,489 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint,
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use spl_token::state::Account as TokenAccount;
+
+/// Seed prefix for schedule PDAs: ["vesting", grantor, beneficiary, mint]
+pub const VESTING_SEED: &[u8] = b"vesting";
+
+/// Seed prefix for vault token account PDAs: ["vault", schedule]
+pub const VAULT_SEED: &[u8] = b"vault";
+
+/// Size of a `VestingSchedule` account
+pub const SCHEDULE_ACCOUNT_SIZE: usize = 1 // is_initialized
+    + 32 // grantor
+    + 32 // beneficiary
+    + 32 // mint
+    + 8 // total_amount
+    + 8 // claimed_amount
+    + 8 // start_slot
+    + 8 // cliff_slot
+    + 8 // end_slot
+    + 1 // revocable
+    + 1 // revoked
+    + 1 // bump
+    + 1; // vault_bump
+
+/// Errors returned by the program as `ProgramError::Custom` codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VestingError {
+    /// Amount is zero, or the cliff falls outside the vesting period
+    InvalidSchedule = 0,
+    /// No vested tokens are waiting to be claimed
+    NothingToClaim = 1,
+    /// The schedule was created without the right to revoke it
+    NotRevocable = 2,
+    /// The schedule has already been revoked
+    AlreadyRevoked = 3,
+    /// The signer is not the grantor or beneficiary this action requires
+    Unauthorized = 4,
+    /// The schedule has not been initialized
+    NotInitialized = 5,
+}
+
+impl From<VestingError> for ProgramError {
+    fn from(e: VestingError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+/// A cliff + linear release of `total_amount` tokens to `beneficiary`
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct VestingSchedule {
+    pub is_initialized: bool,
+    /// Funded the schedule; receives unvested tokens on revoke
+    pub grantor: Pubkey,
+    /// Receives tokens as they vest
+    pub beneficiary: Pubkey,
+    pub mint: Pubkey,
+    /// Tokens granted (reduced to the vested amount on revoke)
+    pub total_amount: u64,
+    /// Tokens already sent to the beneficiary
+    pub claimed_amount: u64,
+    /// Slot at which linear vesting starts accruing
+    pub start_slot: u64,
+    /// Nothing can be claimed before this slot
+    pub cliff_slot: u64,
+    /// Slot at which everything has vested
+    pub end_slot: u64,
+    pub revocable: bool,
+    pub revoked: bool,
+    pub bump: u8,
+    pub vault_bump: u8,
+}
+
+impl VestingSchedule {
+    /// Tokens vested by `slot`: nothing before the cliff, then linear from
+    /// `start_slot` to `end_slot`. Once revoked, `total_amount` is final.
+    pub fn vested_amount(&self, slot: u64) -> u64 {
+        if self.revoked || slot >= self.end_slot {
+            self.total_amount
+        } else if slot < self.cliff_slot {
+            0
+        } else {
+            // u128 so total_amount * elapsed cannot overflow
+            let elapsed = (slot - self.start_slot) as u128;
+            let duration = (self.end_slot - self.start_slot) as u128;
+            (self.total_amount as u128 * elapsed / duration) as u64
+        }
+    }
+}
+
+entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = VestingInstruction::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        VestingInstruction::CreateSchedule {
+            amount,
+            start_slot,
+            cliff_slots,
+            duration_slots,
+            revocable,
+        } => create_schedule(
+            program_id,
+            accounts,
+            amount,
+            start_slot,
+            cliff_slots,
+            duration_slots,
+            revocable,
+        ),
+        VestingInstruction::Claim => claim(program_id, accounts),
+        VestingInstruction::Revoke => revoke(program_id, accounts),
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub enum VestingInstruction {
+    /// Create a schedule and fund its vault with `amount` tokens. Vesting is
+    /// linear over `duration_slots` from `start_slot`, with nothing claimable
+    /// for the first `cliff_slots`.
+    /// Accounts: [writable, signer] grantor, [] beneficiary, [] mint, [writable] schedule PDA,
+    /// [writable] vault PDA, [writable] grantor token account, [] token program, [] system program
+    CreateSchedule {
+        amount: u64,
+        start_slot: u64,
+        cliff_slots: u64,
+        duration_slots: u64,
+        revocable: bool,
+    },
+    /// Send all vested but unclaimed tokens to the beneficiary
+    /// Accounts: [signer] beneficiary, [writable] schedule PDA, [writable] vault PDA,
+    /// [writable] beneficiary token account, [] token program
+    Claim,
+    /// Stop vesting and return unvested tokens to the grantor; tokens vested
+    /// so far stay claimable (grantor only, revocable schedules only)
+    /// Accounts: [signer] grantor, [writable] schedule PDA, [writable] vault PDA,
+    /// [writable] grantor token account, [] token program
+    Revoke,
+}
+
+fn create_schedule(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+    start_slot: u64,
+    cliff_slots: u64,
+    duration_slots: u64,
+    revocable: bool,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let grantor = next_account_info(accounts_iter)?;
+    let beneficiary = next_account_info(accounts_iter)?;
+    let mint = next_account_info(accounts_iter)?;
+    let schedule_account = next_account_info(accounts_iter)?;
+    let vault = next_account_info(accounts_iter)?;
+    let grantor_token = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !grantor.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if amount == 0 || duration_slots == 0 || cliff_slots > duration_slots {
+        msg!("Amount and duration must be positive and the cliff within the duration");
+        return Err(VestingError::InvalidSchedule.into());
+    }
+
+    if *token_program.key != spl_token::id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let (expected_schedule, bump) = Pubkey::find_program_address(
+        &[VESTING_SEED, grantor.key.as_ref(), beneficiary.key.as_ref(), mint.key.as_ref()],
+        program_id,
+    );
+    if expected_schedule != *schedule_account.key {
+        msg!("Schedule account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let (expected_vault, vault_bump) =
+        Pubkey::find_program_address(&[VAULT_SEED, schedule_account.key.as_ref()], program_id);
+    if expected_vault != *vault.key {
+        msg!("Vault account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let end_slot = start_slot
+        .checked_add(duration_slots)
+        .ok_or(VestingError::InvalidSchedule)?;
+
+    let rent = Rent::get()?;
+
+    invoke_signed(
+        &system_instruction::create_account(
+            grantor.key,
+            schedule_account.key,
+            rent.minimum_balance(SCHEDULE_ACCOUNT_SIZE),
+            SCHEDULE_ACCOUNT_SIZE as u64,
+            program_id,
+        ),
+        &[grantor.clone(), schedule_account.clone(), system_program.clone()],
+        &[&[
+            VESTING_SEED,
+            grantor.key.as_ref(),
+            beneficiary.key.as_ref(),
+            mint.key.as_ref(),
+            &[bump],
+        ]],
+    )?;
+
+    invoke_signed(
+        &system_instruction::create_account(
+            grantor.key,
+            vault.key,
+            rent.minimum_balance(TokenAccount::LEN),
+            TokenAccount::LEN as u64,
+            &spl_token::id(),
+        ),
+        &[grantor.clone(), vault.clone(), system_program.clone()],
+        &[&[VAULT_SEED, schedule_account.key.as_ref(), &[vault_bump]]],
+    )?;
+    invoke(
+        &spl_token::instruction::initialize_account3(
+            &spl_token::id(),
+            vault.key,
+            mint.key,
+            schedule_account.key,
+        )?,
+        &[vault.clone(), mint.clone()],
+    )?;
+
+    invoke(
+        &spl_token::instruction::transfer(
+            &spl_token::id(),
+            grantor_token.key,
+            vault.key,
+            grantor.key,
+            &[],
+            amount,
+        )?,
+        &[grantor_token.clone(), vault.clone(), grantor.clone()],
+    )?;
+
+    let schedule = VestingSchedule {
+        is_initialized: true,
+        grantor: *grantor.key,
+        beneficiary: *beneficiary.key,
+        mint: *mint.key,
+        total_amount: amount,
+        claimed_amount: 0,
+        start_slot,
+        cliff_slot: start_slot + cliff_slots,
+        end_slot,
+        revocable,
+        revoked: false,
+        bump,
+        vault_bump,
+    };
+    schedule.serialize(&mut &mut schedule_account.data.borrow_mut()[..])?;
+
+    msg!(
+        "Vesting {} tokens from slot {} (cliff {}) to {}",
+        amount,
+        start_slot,
+        schedule.cliff_slot,
+        end_slot
+    );
+
+    Ok(())
+}
+
+fn claim(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let beneficiary = next_account_info(accounts_iter)?;
+    let schedule_account = next_account_info(accounts_iter)?;
+    let vault = next_account_info(accounts_iter)?;
+    let beneficiary_token = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+
+    if !beneficiary.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut schedule = load_schedule(program_id, schedule_account, vault, token_program)?;
+
+    if schedule.beneficiary != *beneficiary.key {
+        msg!("Only the beneficiary can claim");
+        return Err(VestingError::Unauthorized.into());
+    }
+
+    let clock = Clock::get()?;
+    let claimable = schedule
+        .vested_amount(clock.slot)
+        .saturating_sub(schedule.claimed_amount);
+    if claimable == 0 {
+        msg!("Nothing to claim at slot {}", clock.slot);
+        return Err(VestingError::NothingToClaim.into());
+    }
+
+    schedule.claimed_amount += claimable;
+    schedule.serialize(&mut &mut schedule_account.data.borrow_mut()[..])?;
+
+    transfer_from_vault(schedule_account, vault, beneficiary_token, &schedule, claimable)?;
+
+    msg!("Claimed {} ({} of {} total)", claimable, schedule.claimed_amount, schedule.total_amount);
+
+    Ok(())
+}
+
+fn revoke(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let grantor = next_account_info(accounts_iter)?;
+    let schedule_account = next_account_info(accounts_iter)?;
+    let vault = next_account_info(accounts_iter)?;
+    let grantor_token = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+
+    if !grantor.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut schedule = load_schedule(program_id, schedule_account, vault, token_program)?;
+
+    if schedule.grantor != *grantor.key {
+        msg!("Only the grantor can revoke");
+        return Err(VestingError::Unauthorized.into());
+    }
+
+    if !schedule.revocable {
+        msg!("Schedule is not revocable");
+        return Err(VestingError::NotRevocable.into());
+    }
+
+    if schedule.revoked {
+        msg!("Schedule already revoked");
+        return Err(VestingError::AlreadyRevoked.into());
+    }
+
+    let clock = Clock::get()?;
+    let vested = schedule.vested_amount(clock.slot);
+    let unvested = schedule.total_amount - vested;
+
+    schedule.total_amount = vested;
+    schedule.revoked = true;
+    schedule.serialize(&mut &mut schedule_account.data.borrow_mut()[..])?;
+
+    if unvested > 0 {
+        transfer_from_vault(schedule_account, vault, grantor_token, &schedule, unvested)?;
+    }
+
+    msg!("Revoked at slot {}: {} vested, {} returned", clock.slot, vested, unvested);
+
+    Ok(())
+}
+
+/// Validate the schedule account and its vault, returning the schedule
+fn load_schedule(
+    program_id: &Pubkey,
+    schedule_account: &AccountInfo,
+    vault: &AccountInfo,
+    token_program: &AccountInfo,
+) -> Result<VestingSchedule, ProgramError> {
+    if schedule_account.owner != program_id {
+        msg!("Schedule account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    if !schedule_account.is_writable {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    if *token_program.key != spl_token::id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let schedule = VestingSchedule::try_from_slice(&schedule_account.data.borrow())?;
+
+    if !schedule.is_initialized {
+        msg!("Schedule has not been initialized");
+        return Err(VestingError::NotInitialized.into());
+    }
+
+    let expected_vault = Pubkey::create_program_address(
+        &[VAULT_SEED, schedule_account.key.as_ref(), &[schedule.vault_bump]],
+        program_id,
+    )?;
+    if expected_vault != *vault.key {
+        msg!("Vault account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    Ok(schedule)
+}
+
+/// Transfer `amount` from the vault, signed by the schedule PDA
+fn transfer_from_vault<'a>(
+    schedule_account: &AccountInfo<'a>,
+    vault: &AccountInfo<'a>,
+    destination: &AccountInfo<'a>,
+    schedule: &VestingSchedule,
+    amount: u64,
+) -> ProgramResult {
+    invoke_signed(
+        &spl_token::instruction::transfer(
+            &spl_token::id(),
+            vault.key,
+            destination.key,
+            schedule_account.key,
+            &[],
+            amount,
+        )?,
+        &[vault.clone(), destination.clone(), schedule_account.clone()],
+        &[&[
+            VESTING_SEED,
+            schedule.grantor.as_ref(),
+            schedule.beneficiary.as_ref(),
+            schedule.mint.as_ref(),
+            &[schedule.bump],
+        ]],
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn schedule() -> VestingSchedule {
+        VestingSchedule {
+            is_initialized: true,
+            grantor: Pubkey::new_unique(),
+            beneficiary: Pubkey::new_unique(),
+            mint: Pubkey::new_unique(),
+            total_amount: 1_000,
+            claimed_amount: 0,
+            start_slot: 100,
+            cliff_slot: 200,
+            end_slot: 1_100,
+            revocable: true,
+            revoked: false,
+            bump: 255,
+            vault_bump: 255,
+        }
+    }
+
+    #[test]
+    fn test_vested_amount_at_boundary_slots() {
+        let schedule = schedule();
+
+        assert_eq!(schedule.vested_amount(0), 0);
+        assert_eq!(schedule.vested_amount(199), 0);
+        // At the cliff, everything accrued since start unlocks at once
+        assert_eq!(schedule.vested_amount(200), 100);
+        assert_eq!(schedule.vested_amount(600), 500);
+        assert_eq!(schedule.vested_amount(1_099), 999);
+        assert_eq!(schedule.vested_amount(1_100), 1_000);
+        assert_eq!(schedule.vested_amount(u64::MAX), 1_000);
+    }
+
+    #[test]
+    fn test_revoked_schedule_stops_vesting() {
+        let mut schedule = schedule();
+        schedule.total_amount = schedule.vested_amount(600);
+        schedule.revoked = true;
+
+        assert_eq!(schedule.vested_amount(600), 500);
+        assert_eq!(schedule.vested_amount(1_100), 500);
+    }
+}
@@ -0,0 +1,91 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+/**
+ * @title Voting
+ * @dev One-address-one-vote proposals with a quorum and a voting window
+ */
+contract Voting {
+    enum Outcome {
+        Pending,
+        Passed,
+        Rejected,
+        QuorumNotMet
+    }
+
+    struct Proposal {
+        address creator;
+        string description;
+        uint256 yesVotes;
+        uint256 noVotes;
+        uint256 quorum;
+        uint256 startBlock;
+        uint256 endBlock;
+        Outcome outcome;
+    }
+
+    Proposal[] public proposals;
+    mapping(uint256 => mapping(address => bool)) public hasVoted;
+
+    event ProposalCreated(uint256 indexed proposalId, address indexed creator, uint256 startBlock, uint256 endBlock);
+    event VoteCast(uint256 indexed proposalId, address indexed voter, bool approve);
+    event ProposalFinalized(uint256 indexed proposalId, Outcome outcome);
+
+    /**
+     * @dev Create a proposal whose voting window opens after `startDelayBlocks`
+     */
+    function createProposal(
+        string memory description,
+        uint256 quorum,
+        uint256 startDelayBlocks,
+        uint256 durationBlocks
+    ) external returns (uint256 proposalId) {
+        require(bytes(description).length > 0 && bytes(description).length <= 200, "Description must be 1-200 bytes");
+        require(quorum > 0 && durationBlocks > 0, "Quorum and duration must be greater than zero");
+
+        uint256 startBlock = block.number + startDelayBlocks;
+        proposals.push(
+            Proposal(msg.sender, description, 0, 0, quorum, startBlock, startBlock + durationBlocks, Outcome.Pending)
+        );
+        proposalId = proposals.length - 1;
+
+        emit ProposalCreated(proposalId, msg.sender, startBlock, startBlock + durationBlocks);
+    }
+
+    /**
+     * @dev Vote yes or no; each address votes once per proposal
+     */
+    function castVote(uint256 proposalId, bool approve) external {
+        Proposal storage p = proposals[proposalId];
+        require(block.number >= p.startBlock && block.number < p.endBlock, "Voting is closed");
+        require(!hasVoted[proposalId][msg.sender], "Already voted");
+
+        hasVoted[proposalId][msg.sender] = true;
+        if (approve) {
+            p.yesVotes += 1;
+        } else {
+            p.noVotes += 1;
+        }
+
+        emit VoteCast(proposalId, msg.sender, approve);
+    }
+
+    /**
+     * @dev Record the outcome once voting has ended (callable by anyone)
+     */
+    function finalize(uint256 proposalId) external {
+        Proposal storage p = proposals[proposalId];
+        require(block.number >= p.endBlock, "Voting has not ended");
+        require(p.outcome == Outcome.Pending, "Already finalized");
+
+        if (p.yesVotes + p.noVotes < p.quorum) {
+            p.outcome = Outcome.QuorumNotMet;
+        } else if (p.yesVotes > p.noVotes) {
+            p.outcome = Outcome.Passed;
+        } else {
+            p.outcome = Outcome.Rejected;
+        }
+
+        emit ProposalFinalized(proposalId, p.outcome);
+    }
+}
@@ -0,0 +1,468 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint,
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// Maximum proposal description length in bytes
+pub const MAX_DESCRIPTION_LENGTH: usize = 200;
+
+/// Seed prefix for proposal PDAs: ["proposal", creator, proposal_id]
+pub const PROPOSAL_SEED: &[u8] = b"proposal";
+
+/// Seed prefix for vote record PDAs: ["vote", proposal, voter]
+pub const VOTE_SEED: &[u8] = b"vote";
+
+/// Size of a `ProposalAccount` with a maximum-length description
+pub const PROPOSAL_ACCOUNT_SIZE: usize = 1 // is_initialized
+    + 32 // creator
+    + 8 // proposal_id
+    + 4 + MAX_DESCRIPTION_LENGTH // description
+    + 8 // yes_votes
+    + 8 // no_votes
+    + 8 // quorum
+    + 8 // start_slot
+    + 8 // end_slot
+    + 1 // outcome
+    + 1; // bump
+
+/// Size of a `VoteRecord` account
+pub const VOTE_RECORD_SIZE: usize = 32 + 32 + 1;
+
+/// Errors returned by the program as `ProgramError::Custom` codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VotingError {
+    /// Description is empty or longer than `MAX_DESCRIPTION_LENGTH`
+    InvalidDescription = 0,
+    /// Quorum or duration is zero
+    InvalidParameters = 1,
+    /// The voting window is not open
+    VotingClosed = 2,
+    /// The voter already has a vote record for this proposal
+    AlreadyVoted = 3,
+    /// The voting window has not ended yet
+    VotingNotEnded = 4,
+    /// The proposal has already been finalized
+    AlreadyFinalized = 5,
+    /// The proposal has not been initialized
+    NotInitialized = 6,
+}
+
+impl From<VotingError> for ProgramError {
+    fn from(e: VotingError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+/// Result of a proposal once its voting window has ended
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProposalOutcome {
+    /// Not finalized yet
+    Pending,
+    /// Quorum met and more yes than no votes
+    Passed,
+    /// Quorum met but not more yes than no votes
+    Rejected,
+    /// Fewer than `quorum` votes were cast
+    QuorumNotMet,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct ProposalAccount {
+    pub is_initialized: bool,
+    pub creator: Pubkey,
+    /// Creator-chosen id, part of the PDA seeds
+    pub proposal_id: u64,
+    pub description: String,
+    pub yes_votes: u64,
+    pub no_votes: u64,
+    /// Minimum number of votes (yes + no) for the result to count
+    pub quorum: u64,
+    /// First slot at which votes are accepted
+    pub start_slot: u64,
+    /// Votes are accepted before this slot
+    pub end_slot: u64,
+    pub outcome: ProposalOutcome,
+    pub bump: u8,
+}
+
+impl ProposalAccount {
+    /// The outcome the current tally would finalize to
+    pub fn tally(&self) -> ProposalOutcome {
+        if self.yes_votes.saturating_add(self.no_votes) < self.quorum {
+            ProposalOutcome::QuorumNotMet
+        } else if self.yes_votes > self.no_votes {
+            ProposalOutcome::Passed
+        } else {
+            ProposalOutcome::Rejected
+        }
+    }
+}
+
+/// One voter's ballot; its existence at ["vote", proposal, voter] prevents
+/// double voting
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct VoteRecord {
+    pub proposal: Pubkey,
+    pub voter: Pubkey,
+    pub approve: bool,
+}
+
+entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = VotingInstruction::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        VotingInstruction::CreateProposal {
+            proposal_id,
+            description,
+            quorum,
+            start_delay_slots,
+            duration_slots,
+        } => create_proposal(
+            program_id,
+            accounts,
+            proposal_id,
+            description,
+            quorum,
+            start_delay_slots,
+            duration_slots,
+        ),
+        VotingInstruction::CastVote { approve } => cast_vote(program_id, accounts, approve),
+        VotingInstruction::Finalize => finalize(program_id, accounts),
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub enum VotingInstruction {
+    /// Create a proposal whose voting window opens `start_delay_slots` from
+    /// now and lasts `duration_slots`
+    /// Accounts: [writable, signer] creator, [writable] proposal PDA, [] system program
+    CreateProposal {
+        proposal_id: u64,
+        description: String,
+        quorum: u64,
+        start_delay_slots: u64,
+        duration_slots: u64,
+    },
+    /// Vote yes (`approve = true`) or no; creates the voter's vote record PDA
+    /// Accounts: [writable, signer] voter, [writable] proposal PDA, [writable] vote record PDA,
+    /// [] system program
+    CastVote { approve: bool },
+    /// Record the outcome once the voting window has ended (permissionless)
+    /// Accounts: [writable] proposal PDA
+    Finalize,
+}
+
+fn create_proposal(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    proposal_id: u64,
+    description: String,
+    quorum: u64,
+    start_delay_slots: u64,
+    duration_slots: u64,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let creator = next_account_info(accounts_iter)?;
+    let proposal_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !creator.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if description.is_empty() || description.len() > MAX_DESCRIPTION_LENGTH {
+        msg!("Description must be 1-{} bytes", MAX_DESCRIPTION_LENGTH);
+        return Err(VotingError::InvalidDescription.into());
+    }
+
+    if quorum == 0 || duration_slots == 0 {
+        msg!("Quorum and duration must be greater than zero");
+        return Err(VotingError::InvalidParameters.into());
+    }
+
+    let id_bytes = proposal_id.to_le_bytes();
+    let (expected_proposal, bump) =
+        Pubkey::find_program_address(&[PROPOSAL_SEED, creator.key.as_ref(), &id_bytes], program_id);
+    if expected_proposal != *proposal_account.key {
+        msg!("Proposal account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let clock = Clock::get()?;
+    let start_slot = clock
+        .slot
+        .checked_add(start_delay_slots)
+        .ok_or(VotingError::InvalidParameters)?;
+    let end_slot = start_slot
+        .checked_add(duration_slots)
+        .ok_or(VotingError::InvalidParameters)?;
+
+    invoke_signed(
+        &system_instruction::create_account(
+            creator.key,
+            proposal_account.key,
+            Rent::get()?.minimum_balance(PROPOSAL_ACCOUNT_SIZE),
+            PROPOSAL_ACCOUNT_SIZE as u64,
+            program_id,
+        ),
+        &[creator.clone(), proposal_account.clone(), system_program.clone()],
+        &[&[PROPOSAL_SEED, creator.key.as_ref(), &id_bytes, &[bump]]],
+    )?;
+
+    let proposal = ProposalAccount {
+        is_initialized: true,
+        creator: *creator.key,
+        proposal_id,
+        description,
+        yes_votes: 0,
+        no_votes: 0,
+        quorum,
+        start_slot,
+        end_slot,
+        outcome: ProposalOutcome::Pending,
+        bump,
+    };
+    proposal.serialize(&mut &mut proposal_account.data.borrow_mut()[..])?;
+
+    msg!("Proposal {} open for slots {}..{}", proposal_id, start_slot, end_slot);
+
+    Ok(())
+}
+
+fn cast_vote(program_id: &Pubkey, accounts: &[AccountInfo], approve: bool) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let voter = next_account_info(accounts_iter)?;
+    let proposal_account = next_account_info(accounts_iter)?;
+    let vote_record = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !voter.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut proposal = load_proposal(program_id, proposal_account)?;
+
+    let (expected_record, bump) = Pubkey::find_program_address(
+        &[VOTE_SEED, proposal_account.key.as_ref(), voter.key.as_ref()],
+        program_id,
+    );
+    if expected_record != *vote_record.key {
+        msg!("Vote record does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    // The record PDA can only be created once per voter and proposal
+    if vote_record.lamports() > 0 {
+        msg!("{} has already voted", voter.key);
+        return Err(VotingError::AlreadyVoted.into());
+    }
+
+    let clock = Clock::get()?;
+    if clock.slot < proposal.start_slot || clock.slot >= proposal.end_slot {
+        msg!("Voting is open for slots {}..{}", proposal.start_slot, proposal.end_slot);
+        return Err(VotingError::VotingClosed.into());
+    }
+
+    invoke_signed(
+        &system_instruction::create_account(
+            voter.key,
+            vote_record.key,
+            Rent::get()?.minimum_balance(VOTE_RECORD_SIZE),
+            VOTE_RECORD_SIZE as u64,
+            program_id,
+        ),
+        &[voter.clone(), vote_record.clone(), system_program.clone()],
+        &[&[VOTE_SEED, proposal_account.key.as_ref(), voter.key.as_ref(), &[bump]]],
+    )?;
+
+    let record = VoteRecord {
+        proposal: *proposal_account.key,
+        voter: *voter.key,
+        approve,
+    };
+    record.serialize(&mut &mut vote_record.data.borrow_mut()[..])?;
+
+    if approve {
+        proposal.yes_votes = proposal.yes_votes.checked_add(1).ok_or(ProgramError::InvalidAccountData)?;
+    } else {
+        proposal.no_votes = proposal.no_votes.checked_add(1).ok_or(ProgramError::InvalidAccountData)?;
+    }
+    proposal.serialize(&mut &mut proposal_account.data.borrow_mut()[..])?;
+
+    msg!("Vote cast: yes={}, no={}", proposal.yes_votes, proposal.no_votes);
+
+    Ok(())
+}
+
+fn finalize(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let proposal_account = next_account_info(accounts_iter)?;
+
+    let mut proposal = load_proposal(program_id, proposal_account)?;
+
+    let clock = Clock::get()?;
+    if clock.slot < proposal.end_slot {
+        msg!("Voting ends at slot {}", proposal.end_slot);
+        return Err(VotingError::VotingNotEnded.into());
+    }
+
+    proposal.outcome = proposal.tally();
+    proposal.serialize(&mut &mut proposal_account.data.borrow_mut()[..])?;
+
+    msg!("Proposal {} finalized: {:?}", proposal.proposal_id, proposal.outcome);
+
+    Ok(())
+}
+
+/// Validate a writable, initialized, not yet finalized proposal account
+fn load_proposal(
+    program_id: &Pubkey,
+    proposal_account: &AccountInfo,
+) -> Result<ProposalAccount, ProgramError> {
+    if proposal_account.owner != program_id {
+        msg!("Proposal account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    if !proposal_account.is_writable {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let proposal = ProposalAccount::deserialize(&mut &proposal_account.data.borrow()[..])?;
+
+    if !proposal.is_initialized {
+        msg!("Proposal has not been initialized");
+        return Err(VotingError::NotInitialized.into());
+    }
+
+    if proposal.outcome != ProposalOutcome::Pending {
+        msg!("Proposal already finalized");
+        return Err(VotingError::AlreadyFinalized.into());
+    }
+
+    Ok(proposal)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_program::clock::Epoch;
+
+    fn proposal(yes_votes: u64, no_votes: u64) -> ProposalAccount {
+        ProposalAccount {
+            is_initialized: true,
+            creator: Pubkey::new_unique(),
+            proposal_id: 0,
+            description: "Fund the grants program".to_string(),
+            yes_votes,
+            no_votes,
+            quorum: 10,
+            start_slot: 0,
+            end_slot: 100,
+            outcome: ProposalOutcome::Pending,
+            bump: 255,
+        }
+    }
+
+    #[test]
+    fn test_tally() {
+        assert_eq!(proposal(5, 4).tally(), ProposalOutcome::QuorumNotMet);
+        assert_eq!(proposal(6, 4).tally(), ProposalOutcome::Passed);
+        assert_eq!(proposal(5, 5).tally(), ProposalOutcome::Rejected);
+    }
+
+    #[test]
+    fn test_double_vote_rejected() {
+        let program_id = Pubkey::new_unique();
+        let voter_key = Pubkey::new_unique();
+        let proposal_key = Pubkey::new_unique();
+        let (record_key, _) = Pubkey::find_program_address(
+            &[VOTE_SEED, proposal_key.as_ref(), voter_key.as_ref()],
+            &program_id,
+        );
+        let system_program_id = solana_program::system_program::id();
+
+        let mut voter_lamports = 1_000_000;
+        let mut voter_data = vec![];
+        let voter = AccountInfo::new(
+            &voter_key,
+            true,
+            true,
+            &mut voter_lamports,
+            &mut voter_data,
+            &system_program_id,
+            false,
+            Epoch::default(),
+        );
+
+        let mut proposal_lamports = 1_000_000;
+        let mut proposal_data = proposal(1, 0).try_to_vec().unwrap();
+        proposal_data.resize(PROPOSAL_ACCOUNT_SIZE, 0);
+        let proposal_account = AccountInfo::new(
+            &proposal_key,
+            false,
+            true,
+            &mut proposal_lamports,
+            &mut proposal_data,
+            &program_id,
+            false,
+            Epoch::default(),
+        );
+
+        // The voter's record already exists from an earlier vote
+        let mut record_lamports = 1_000_000;
+        let mut record_data = VoteRecord {
+            proposal: proposal_key,
+            voter: voter_key,
+            approve: true,
+        }
+        .try_to_vec()
+        .unwrap();
+        let record = AccountInfo::new(
+            &record_key,
+            false,
+            true,
+            &mut record_lamports,
+            &mut record_data,
+            &program_id,
+            false,
+            Epoch::default(),
+        );
+
+        let mut system_lamports = 0;
+        let mut system_data = vec![];
+        let system_program = AccountInfo::new(
+            &system_program_id,
+            false,
+            false,
+            &mut system_lamports,
+            &mut system_data,
+            &system_program_id,
+            true,
+            Epoch::default(),
+        );
+
+        let accounts = vec![voter, proposal_account, record, system_program];
+        let instruction_data = VotingInstruction::CastVote { approve: false }.try_to_vec().unwrap();
+
+        let result = process_instruction(&program_id, &accounts, &instruction_data);
+        assert_eq!(result, Err(VotingError::AlreadyVoted.into()));
+    }
+}
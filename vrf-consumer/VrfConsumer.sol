@@ -0,0 +1,83 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+interface IVRFCoordinatorV2 {
+    function requestRandomWords(
+        bytes32 keyHash,
+        uint64 subId,
+        uint16 minimumRequestConfirmations,
+        uint32 callbackGasLimit,
+        uint32 numWords
+    ) external returns (uint256 requestId);
+}
+
+/**
+ * @title VrfConsumer
+ * @dev Requests randomness from a Chainlink VRF v2 coordinator and stores it when the coordinator calls back
+ */
+contract VrfConsumer {
+    uint16 public constant REQUEST_CONFIRMATIONS = 3;
+    uint32 public constant CALLBACK_GAS_LIMIT = 100_000;
+
+    IVRFCoordinatorV2 public immutable coordinator;
+    bytes32 public immutable keyHash;
+    uint64 public immutable subscriptionId;
+
+    struct Request {
+        address requester;
+        bool fulfilled;
+        uint256 randomness;
+    }
+
+    mapping(uint256 => Request) public requests;
+
+    event RandomnessRequested(uint256 indexed requestId, address indexed requester);
+    event RandomnessFulfilled(uint256 indexed requestId, uint256 randomness);
+
+    constructor(IVRFCoordinatorV2 _coordinator, bytes32 _keyHash, uint64 _subscriptionId) {
+        coordinator = _coordinator;
+        keyHash = _keyHash;
+        subscriptionId = _subscriptionId;
+    }
+
+    /**
+     * @dev Ask the coordinator for one random word, paid from the subscription
+     */
+    function requestRandomness() external returns (uint256 requestId) {
+        requestId = coordinator.requestRandomWords(
+            keyHash,
+            subscriptionId,
+            REQUEST_CONFIRMATIONS,
+            CALLBACK_GAS_LIMIT,
+            1
+        );
+        requests[requestId].requester = msg.sender;
+
+        emit RandomnessRequested(requestId, msg.sender);
+    }
+
+    /**
+     * @dev Callback from the coordinator, which has already verified the VRF proof
+     */
+    function rawFulfillRandomWords(uint256 requestId, uint256[] memory randomWords) external {
+        require(msg.sender == address(coordinator), "Only coordinator can fulfill");
+        Request storage request = requests[requestId];
+        require(request.requester != address(0), "Unknown request");
+        require(!request.fulfilled, "Already fulfilled");
+
+        request.fulfilled = true;
+        request.randomness = randomWords[0];
+
+        emit RandomnessFulfilled(requestId, randomWords[0]);
+    }
+
+    /**
+     * @dev Random number in [0, n) for a fulfilled request
+     */
+    function randomInRange(uint256 requestId, uint256 n) external view returns (uint256) {
+        require(n > 0, "Empty range");
+        Request storage request = requests[requestId];
+        require(request.fulfilled, "Not fulfilled");
+        return request.randomness % n;
+    }
+}
@@ -0,0 +1,389 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    ed25519_program,
+    entrypoint,
+    entrypoint::ProgramResult,
+    hash::hashv,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::{self, instructions::get_instruction_relative, Sysvar},
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// Seed prefix for randomness request PDAs: ["request", requester, seed]
+pub const REQUEST_SEED: &[u8] = b"request";
+
+/// Size of a `RandomnessRequest`
+pub const REQUEST_ACCOUNT_SIZE: usize = 1 // is_initialized
+    + 32 // requester
+    + 32 // oracle
+    + 32 // seed
+    + 8 // request_slot
+    + 1 + 32 // randomness
+    + 1; // bump
+
+/// Header of an Ed25519 program instruction: signature count and padding
+const ED25519_HEADER_SIZE: usize = 2;
+
+/// One `Ed25519SignatureOffsets` entry: seven u16 fields
+const ED25519_OFFSETS_SIZE: usize = 14;
+
+/// Errors returned by the program as `ProgramError::Custom` codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VrfError {
+    /// The request already has its randomness
+    AlreadyFulfilled = 0,
+    /// The request is still waiting for the oracle
+    NotFulfilled = 1,
+    /// The previous instruction is not an Ed25519 signature verification
+    MissingSignatureVerification = 2,
+    /// The verified signature is not the oracle's signature over this request
+    InvalidProof = 3,
+    /// The request has not been initialized
+    NotInitialized = 4,
+    /// Only the requester may close the request
+    Unauthorized = 5,
+}
+
+impl From<VrfError> for ProgramError {
+    fn from(e: VrfError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+/// A request for one random value from a chosen oracle. Consumers such as a
+/// lottery store the request address and read `randomness` once it is set.
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct RandomnessRequest {
+    pub is_initialized: bool,
+    pub requester: Pubkey,
+    /// Ed25519 key whose signature over `request_message` produces the randomness
+    pub oracle: Pubkey,
+    /// Requester-chosen value that makes each request unique
+    pub seed: [u8; 32],
+    pub request_slot: u64,
+    /// `sha256(signature)`, set by `Fulfill`
+    pub randomness: Option<[u8; 32]>,
+    pub bump: u8,
+}
+
+/// The message the oracle signs for a request
+pub fn request_message(request: &Pubkey, seed: &[u8; 32]) -> Vec<u8> {
+    [request.as_ref(), seed.as_ref()].concat()
+}
+
+/// Check that an Ed25519 program instruction verified exactly one signature,
+/// by `oracle`, over `message`, with everything inline in its own data.
+/// Returns the signature.
+pub fn verified_signature(ix_data: &[u8], oracle: &Pubkey, message: &[u8]) -> Result<[u8; 64], VrfError> {
+    if ix_data.len() < ED25519_HEADER_SIZE + ED25519_OFFSETS_SIZE || ix_data[0] != 1 {
+        return Err(VrfError::InvalidProof);
+    }
+
+    let offsets: Vec<usize> = ix_data[ED25519_HEADER_SIZE..ED25519_HEADER_SIZE + ED25519_OFFSETS_SIZE]
+        .chunks_exact(2)
+        .map(|b| u16::from_le_bytes([b[0], b[1]]) as usize)
+        .collect();
+    let (signature_offset, signature_ix, public_key_offset, public_key_ix) =
+        (offsets[0], offsets[1], offsets[2], offsets[3]);
+    let (message_offset, message_size, message_ix) = (offsets[4], offsets[5], offsets[6]);
+
+    // u16::MAX means "this instruction"; anything else could point at data we never see
+    let this_ix = u16::MAX as usize;
+    if signature_ix != this_ix || public_key_ix != this_ix || message_ix != this_ix {
+        return Err(VrfError::InvalidProof);
+    }
+
+    let slice = |offset: usize, len: usize| ix_data.get(offset..offset + len).ok_or(VrfError::InvalidProof);
+    if slice(public_key_offset, 32)? != oracle.as_ref() || slice(message_offset, message_size)? != message {
+        return Err(VrfError::InvalidProof);
+    }
+
+    let mut signature = [0u8; 64];
+    signature.copy_from_slice(slice(signature_offset, 64)?);
+    Ok(signature)
+}
+
+/// Map randomness uniformly enough onto 0..n (n > 0); the bias is at most n / 2^64
+pub fn random_in_range(randomness: &[u8; 32], n: u64) -> u64 {
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&randomness[..8]);
+    u64::from_le_bytes(bytes) % n
+}
+
+entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = VrfInstruction::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        VrfInstruction::RequestRandomness { seed, oracle } => request_randomness(program_id, accounts, seed, oracle),
+        VrfInstruction::Fulfill => fulfill(program_id, accounts),
+        VrfInstruction::Close => close(program_id, accounts),
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub enum VrfInstruction {
+    /// Open a request that `oracle` will answer
+    /// Accounts: [writable, signer] requester, [writable] request PDA, [] system program
+    RequestRandomness { seed: [u8; 32], oracle: Pubkey },
+    /// Store the randomness; must directly follow an Ed25519 program instruction
+    /// verifying the oracle's signature over `request_message`. Callable by anyone.
+    /// Accounts: [writable] request PDA, [] instructions sysvar
+    Fulfill,
+    /// Close a fulfilled request and reclaim its rent
+    /// Accounts: [writable, signer] requester, [writable] request PDA
+    Close,
+}
+
+fn request_randomness(program_id: &Pubkey, accounts: &[AccountInfo], seed: [u8; 32], oracle: Pubkey) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let requester = next_account_info(accounts_iter)?;
+    let request_account = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !requester.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let (expected_request, bump) =
+        Pubkey::find_program_address(&[REQUEST_SEED, requester.key.as_ref(), &seed], program_id);
+    if expected_request != *request_account.key {
+        msg!("Request account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    invoke_signed(
+        &system_instruction::create_account(
+            requester.key,
+            request_account.key,
+            Rent::get()?.minimum_balance(REQUEST_ACCOUNT_SIZE),
+            REQUEST_ACCOUNT_SIZE as u64,
+            program_id,
+        ),
+        &[requester.clone(), request_account.clone(), system_program.clone()],
+        &[&[REQUEST_SEED, requester.key.as_ref(), &seed, &[bump]]],
+    )?;
+
+    let request = RandomnessRequest {
+        is_initialized: true,
+        requester: *requester.key,
+        oracle,
+        seed,
+        request_slot: Clock::get()?.slot,
+        randomness: None,
+        bump,
+    };
+    request.serialize(&mut &mut request_account.data.borrow_mut()[..])?;
+
+    msg!("Randomness requested from oracle {}", oracle);
+
+    Ok(())
+}
+
+fn fulfill(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let request_account = next_account_info(accounts_iter)?;
+    let instructions_sysvar = next_account_info(accounts_iter)?;
+
+    let mut request = load_request(program_id, request_account)?;
+
+    if request.randomness.is_some() {
+        msg!("Request has already been fulfilled");
+        return Err(VrfError::AlreadyFulfilled.into());
+    }
+
+    if *instructions_sysvar.key != sysvar::instructions::id() {
+        msg!("Expected the instructions sysvar");
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    // The Ed25519 program has already checked the signature itself; we check
+    // that it was the oracle's signature over this request
+    let verify_ix = get_instruction_relative(-1, instructions_sysvar)
+        .map_err(|_| VrfError::MissingSignatureVerification)?;
+    if verify_ix.program_id != ed25519_program::id() {
+        msg!("Fulfill must follow an Ed25519 signature verification");
+        return Err(VrfError::MissingSignatureVerification.into());
+    }
+
+    let message = request_message(request_account.key, &request.seed);
+    let signature = verified_signature(&verify_ix.data, &request.oracle, &message)?;
+
+    let randomness = hashv(&[&signature]).to_bytes();
+    request.randomness = Some(randomness);
+    request.serialize(&mut &mut request_account.data.borrow_mut()[..])?;
+
+    msg!("Request {} fulfilled", request_account.key);
+
+    Ok(())
+}
+
+fn close(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let requester = next_account_info(accounts_iter)?;
+    let request_account = next_account_info(accounts_iter)?;
+
+    if !requester.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let request = load_request(program_id, request_account)?;
+
+    if request.requester != *requester.key {
+        msg!("Only the requester can close the request");
+        return Err(VrfError::Unauthorized.into());
+    }
+
+    if request.randomness.is_none() {
+        msg!("Request is still waiting for the oracle");
+        return Err(VrfError::NotFulfilled.into());
+    }
+
+    let lamports = request_account.lamports();
+    **request_account.try_borrow_mut_lamports()? = 0;
+    **requester.try_borrow_mut_lamports()? += lamports;
+    request_account.data.borrow_mut().fill(0);
+
+    msg!("Request {} closed", request_account.key);
+
+    Ok(())
+}
+
+/// Validate the request account, returning its state
+fn load_request(program_id: &Pubkey, request_account: &AccountInfo) -> Result<RandomnessRequest, ProgramError> {
+    if request_account.owner != program_id {
+        msg!("Request account does not have the correct program id");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let request = RandomnessRequest::try_from_slice(&request_account.data.borrow())?;
+
+    if !request.is_initialized {
+        msg!("Request has not been initialized");
+        return Err(VrfError::NotInitialized.into());
+    }
+
+    Ok(request)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_program::clock::Epoch;
+
+    /// Ed25519 program instruction data for one signature, laid out like the
+    /// SDK's `new_ed25519_instruction`: offsets, public key, signature, message
+    fn ed25519_ix_data(public_key: &Pubkey, signature: &[u8; 64], message: &[u8]) -> Vec<u8> {
+        let public_key_offset = ED25519_HEADER_SIZE + ED25519_OFFSETS_SIZE;
+        let signature_offset = public_key_offset + 32;
+        let message_offset = signature_offset + 64;
+
+        let mut data = vec![1u8, 0];
+        for field in [
+            signature_offset,
+            u16::MAX as usize,
+            public_key_offset,
+            u16::MAX as usize,
+            message_offset,
+            message.len(),
+            u16::MAX as usize,
+        ] {
+            data.extend_from_slice(&(field as u16).to_le_bytes());
+        }
+        data.extend_from_slice(public_key.as_ref());
+        data.extend_from_slice(signature);
+        data.extend_from_slice(message);
+        data
+    }
+
+    #[test]
+    fn test_verified_signature_checks_key_and_message() {
+        let oracle = Pubkey::new_unique();
+        let request = Pubkey::new_unique();
+        let message = request_message(&request, &[9u8; 32]);
+        let signature = [7u8; 64];
+
+        let data = ed25519_ix_data(&oracle, &signature, &message);
+        assert_eq!(verified_signature(&data, &oracle, &message), Ok(signature));
+
+        // Someone else's key, or a signature over a different request, is rejected
+        assert_eq!(
+            verified_signature(&data, &Pubkey::new_unique(), &message),
+            Err(VrfError::InvalidProof)
+        );
+        let other_message = request_message(&Pubkey::new_unique(), &[9u8; 32]);
+        assert_eq!(
+            verified_signature(&data, &oracle, &other_message),
+            Err(VrfError::InvalidProof)
+        );
+
+        // Offsets pointing into another instruction are rejected
+        let mut external = data.clone();
+        external[4..6].copy_from_slice(&0u16.to_le_bytes());
+        assert_eq!(verified_signature(&external, &oracle, &message), Err(VrfError::InvalidProof));
+
+        let randomness = hashv(&[&signature]).to_bytes();
+        assert!(random_in_range(&randomness, 6) < 6);
+    }
+
+    #[test]
+    fn test_fulfill_twice_fails() {
+        let program_id = Pubkey::new_unique();
+        let request_key = Pubkey::new_unique();
+        let sysvar_key = sysvar::instructions::id();
+        let sysvar_owner = sysvar::id();
+
+        let request = RandomnessRequest {
+            is_initialized: true,
+            requester: Pubkey::new_unique(),
+            oracle: Pubkey::new_unique(),
+            seed: [1u8; 32],
+            request_slot: 10,
+            randomness: Some([2u8; 32]),
+            bump: 255,
+        };
+        let mut request_data = request.try_to_vec().unwrap();
+        let mut request_lamports = 0;
+        let mut sysvar_data = vec![0u8; 2];
+        let mut sysvar_lamports = 0;
+
+        let accounts = vec![
+            AccountInfo::new(
+                &request_key,
+                false,
+                true,
+                &mut request_lamports,
+                &mut request_data,
+                &program_id,
+                false,
+                Epoch::default(),
+            ),
+            AccountInfo::new(
+                &sysvar_key,
+                false,
+                false,
+                &mut sysvar_lamports,
+                &mut sysvar_data,
+                &sysvar_owner,
+                false,
+                Epoch::default(),
+            ),
+        ];
+
+        let instruction_data = VrfInstruction::Fulfill.try_to_vec().unwrap();
+        let result = process_instruction(&program_id, &accounts, &instruction_data);
+        assert_eq!(result, Err(VrfError::AlreadyFulfilled.into()));
+    }
+}
@@ -0,0 +1,55 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+interface IWETH {
+    function deposit() external payable;
+    function withdraw(uint256 amount) external;
+    function transfer(address to, uint256 amount) external returns (bool);
+    function transferFrom(address from, address to, uint256 amount) external returns (bool);
+}
+
+/**
+ * @title WethHelper
+ * @dev Wraps ETH into WETH to pay token-only recipients, and unwraps WETH back to ETH
+ */
+contract WethHelper {
+    IWETH public immutable weth;
+
+    event TransferredAsWeth(address indexed from, address indexed to, uint256 amount);
+    event Unwrapped(address indexed owner, uint256 amount);
+
+    constructor(IWETH _weth) {
+        weth = _weth;
+    }
+
+    /**
+     * @dev Wrap the ETH sent with the call and transfer it to `to` as WETH
+     */
+    function transferAsWeth(address to) external payable {
+        require(msg.value > 0, "Amount must be greater than zero");
+
+        weth.deposit{value: msg.value}();
+        require(weth.transfer(to, msg.value), "Transfer failed");
+
+        emit TransferredAsWeth(msg.sender, to, msg.value);
+    }
+
+    /**
+     * @dev Pull `amount` WETH from the caller (requires approval) and send it back as ETH
+     */
+    function unwrap(uint256 amount) external {
+        require(amount > 0, "Amount must be greater than zero");
+
+        require(weth.transferFrom(msg.sender, address(this), amount), "Transfer failed");
+        weth.withdraw(amount);
+        (bool success, ) = msg.sender.call{value: amount}("");
+        require(success, "ETH transfer failed");
+
+        emit Unwrapped(msg.sender, amount);
+    }
+
+    // WETH sends ETH here on withdraw
+    receive() external payable {
+        require(msg.sender == address(weth), "Only WETH");
+    }
+}
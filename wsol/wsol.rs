@@ -0,0 +1,228 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint,
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use spl_token::{native_mint, state::Account as TokenAccount};
+
+/// Seed prefix for the temporary wSOL account: ["temp_wsol", payer]
+pub const TEMP_WSOL_SEED: &[u8] = b"temp_wsol";
+
+/// Errors returned by the program as `ProgramError::Custom` codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WsolError {
+    /// Amount is zero
+    InvalidParameters = 0,
+    /// The mint or token account is not wrapped SOL
+    NotNativeMint = 1,
+}
+
+impl From<WsolError> for ProgramError {
+    fn from(e: WsolError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+/// Lamports a temporary wSOL account needs to hold `amount` wrapped SOL:
+/// the amount itself plus the token account's rent-exempt reserve
+pub fn wrap_funding(amount: u64, rent: &Rent) -> Option<u64> {
+    amount.checked_add(rent.minimum_balance(TokenAccount::LEN))
+}
+
+entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = WsolInstruction::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        WsolInstruction::TransferAsWsol { amount } => transfer_as_wsol(program_id, accounts, amount),
+        WsolInstruction::Unwrap => unwrap(accounts),
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub enum WsolInstruction {
+    /// Wrap `amount` lamports in a temporary wSOL account, send them to a wSOL
+    /// token account with a token transfer, then close the temporary account
+    /// Accounts: [writable, signer] payer, [writable] temporary wSOL PDA, [] native mint,
+    /// [writable] recipient wSOL token account, [] token program, [] system program
+    TransferAsWsol { amount: u64 },
+    /// Unwrap: close a wSOL token account, sending all of its lamports to its owner
+    /// Accounts: [writable, signer] owner, [writable] wSOL token account, [] token program
+    Unwrap,
+}
+
+fn transfer_as_wsol(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let payer = next_account_info(accounts_iter)?;
+    let temp_wsol = next_account_info(accounts_iter)?;
+    let mint = next_account_info(accounts_iter)?;
+    let recipient = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    if !payer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if amount == 0 {
+        return Err(WsolError::InvalidParameters.into());
+    }
+
+    if *token_program.key != spl_token::id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    if *mint.key != native_mint::id() {
+        msg!("Mint must be the native mint {}", native_mint::id());
+        return Err(WsolError::NotNativeMint.into());
+    }
+
+    // A PDA lets the program create the temporary account without a fresh keypair
+    let (expected_temp, bump) = Pubkey::find_program_address(&[TEMP_WSOL_SEED, payer.key.as_ref()], program_id);
+    if expected_temp != *temp_wsol.key {
+        msg!("Temporary wSOL account does not match the expected PDA");
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let lamports = wrap_funding(amount, &Rent::get()?).ok_or(ProgramError::ArithmeticOverflow)?;
+
+    // 1. Create a token account for the native mint, funded with the rent plus the amount
+    invoke_signed(
+        &system_instruction::create_account(
+            payer.key,
+            temp_wsol.key,
+            lamports,
+            TokenAccount::LEN as u64,
+            &spl_token::id(),
+        ),
+        &[payer.clone(), temp_wsol.clone(), system_program.clone()],
+        &[&[TEMP_WSOL_SEED, payer.key.as_ref(), &[bump]]],
+    )?;
+    invoke(
+        &spl_token::instruction::initialize_account3(&spl_token::id(), temp_wsol.key, mint.key, payer.key)?,
+        &[temp_wsol.clone(), mint.clone()],
+    )?;
+
+    // 2. Sync: the token balance becomes lamports minus the rent-exempt reserve.
+    // initialize_account already counts lamports present at creation; sync_native
+    // is what picks up lamports sent to an existing wSOL account.
+    invoke(
+        &spl_token::instruction::sync_native(&spl_token::id(), temp_wsol.key)?,
+        std::slice::from_ref(temp_wsol),
+    )?;
+
+    // 3. Use it like any other token
+    invoke(
+        &spl_token::instruction::transfer(&spl_token::id(), temp_wsol.key, recipient.key, payer.key, &[], amount)?,
+        &[temp_wsol.clone(), recipient.clone(), payer.clone()],
+    )?;
+
+    // 4. Close the empty account; its rent-exempt reserve goes back to the payer
+    invoke(
+        &spl_token::instruction::close_account(&spl_token::id(), temp_wsol.key, payer.key, payer.key, &[])?,
+        &[temp_wsol.clone(), payer.clone()],
+    )?;
+
+    msg!("Sent {} lamports as wSOL to {}", amount, recipient.key);
+
+    Ok(())
+}
+
+fn unwrap(accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let owner = next_account_info(accounts_iter)?;
+    let wsol_account = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if *token_program.key != spl_token::id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let token_account = TokenAccount::unpack(&wsol_account.data.borrow())?;
+    if !token_account.is_native() {
+        msg!("Only wSOL accounts can be unwrapped");
+        return Err(WsolError::NotNativeMint.into());
+    }
+
+    // Closing a native account releases its whole balance, wrapped amount and rent alike
+    invoke(
+        &spl_token::instruction::close_account(&spl_token::id(), wsol_account.key, owner.key, owner.key, &[])?,
+        &[wsol_account.clone(), owner.clone()],
+    )?;
+
+    msg!("Unwrapped {} lamports", token_account.amount);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_program::{clock::Epoch, program_option::COption};
+
+    #[test]
+    fn test_wrap_funding_adds_rent_reserve() {
+        let rent = Rent::default();
+        let reserve = rent.minimum_balance(TokenAccount::LEN);
+
+        assert_eq!(wrap_funding(1_000_000_000, &rent), Some(1_000_000_000 + reserve));
+        assert_eq!(wrap_funding(u64::MAX, &rent), None);
+    }
+
+    #[test]
+    fn test_unwrap_rejects_non_native_account() {
+        let program_id = Pubkey::new_unique();
+        let owner_key = Pubkey::new_unique();
+        let token_account_key = Pubkey::new_unique();
+        let token_program_key = spl_token::id();
+
+        let token_account = TokenAccount {
+            mint: Pubkey::new_unique(),
+            owner: owner_key,
+            amount: 5,
+            state: spl_token::state::AccountState::Initialized,
+            is_native: COption::None,
+            ..TokenAccount::default()
+        };
+        let mut token_data = vec![0u8; TokenAccount::LEN];
+        TokenAccount::pack(token_account, &mut token_data).unwrap();
+
+        let keys = [owner_key, token_account_key, token_program_key];
+        let mut lamports: Vec<u64> = vec![10_000; keys.len()];
+        let mut data: Vec<Vec<u8>> = vec![vec![]; keys.len()];
+        data[1] = token_data;
+
+        let accounts: Vec<AccountInfo> = keys
+            .iter()
+            .zip(lamports.iter_mut())
+            .zip(data.iter_mut())
+            .enumerate()
+            .map(|(i, ((key, lamports), data))| {
+                AccountInfo::new(key, i == 0, true, lamports, data, &program_id, false, Epoch::default())
+            })
+            .collect();
+
+        let instruction_data = WsolInstruction::Unwrap.try_to_vec().unwrap();
+        let result = process_instruction(&program_id, &accounts, &instruction_data);
+        assert_eq!(result, Err(WsolError::NotNativeMint.into()));
+    }
+}